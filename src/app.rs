@@ -1,40 +1,64 @@
 use std::collections::{HashMap, HashSet};
-use std::path::{Path, PathBuf};
+use std::ops::RangeInclusive;
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, Instant};
 
 use chrono::Utc;
 use ratatui::style::Color;
 
 use crate::config::CommentTypeConfig;
 use crate::error::{Result, TuicrError};
+use crate::model::comment::insert_threaded;
+use crate::model::pairing::pair_change_block;
 use crate::model::{
-    ClearScope, Comment, CommentType, DiffFile, DiffHunk, DiffLine, FileStatus, LineOrigin,
-    LineRange, LineSide, ReviewSession, SessionDiffSource,
+    ClearScope, Comment, CommentType, DiffFile, DiffHunk, DiffLine, FileStatus, HunkKey,
+    LineFingerprint, LineOrigin, LineRange, LineSide, ReviewSession, SessionDiffSource,
+    detect_moved_blocks,
 };
-use crate::persistence::load_latest_session_for_context;
-use crate::syntax::SyntaxHighlighter;
-use crate::theme::Theme;
+use crate::persistence::{
+    delete_session, list_sessions_for_repo, load_latest_session_for_context, save_session,
+};
+use crate::suggestion;
+use crate::syntax::{HighlightJob, HighlightPool};
+use crate::theme::{Theme, ThemeArg, resolve_theme};
+use crate::ui::inline_image::{ImageProtocol, PREVIEW_ROWS};
+use crate::ui::scrollbar::{ScrollbarTick, VisualRows};
+use crate::undo::{CommentAnchor, UndoAction, UndoHistory};
 use crate::update::UpdateInfo;
 use crate::vcs::git::calculate_gap;
-use crate::vcs::{CommitInfo, FileBackend, VcsBackend, VcsInfo, detect_vcs};
+use crate::vcs::{
+    BlameLine, BlameTarget, BranchInfo, CommitInfo, DiffStat, FileBackend, VcsBackend, VcsInfo,
+    detect_vcs,
+};
 
 const VISIBLE_COMMIT_COUNT: usize = 10;
 const COMMIT_PAGE_SIZE: usize = 10;
+/// Commits whose diffstat is computed per call of `App::load_commit_stats`
+const COMMIT_STATS_BATCH: usize = 10;
+/// Diffstats are turned off when a batch takes longer than this
+const COMMIT_STATS_BUDGET: Duration = Duration::from_millis(200);
 pub const STAGED_SELECTION_ID: &str = "__tuicr_staged__";
 pub const UNSTAGED_SELECTION_ID: &str = "__tuicr_unstaged__";
+/// Default lines loaded per press of a gap expander.
 pub const GAP_EXPAND_BATCH: usize = 20;
-
-/// Count how many annotation lines a gap produces (expanders + hidden count).
-fn gap_annotation_line_count(is_top_of_file: bool, remaining: usize) -> usize {
-    if remaining == 0 {
-        0
-    } else if is_top_of_file {
-        // ↑ expander, plus a HiddenLines line when remaining > batch
-        if remaining > GAP_EXPAND_BATCH { 2 } else { 1 }
-    } else {
-        // Between hunks: ↓ + HiddenLines + ↑ when >= batch, else single ↕
-        if remaining >= GAP_EXPAND_BATCH { 3 } else { 1 }
-    }
-}
+pub const DEFAULT_AUTOSAVE_INTERVAL: Duration = Duration::from_secs(30);
+/// How long the status bar shows "saved" after an autosave
+pub const SAVED_INDICATOR_DURATION: Duration = Duration::from_secs(2);
+/// Default rows of the `:comments` panel, including borders.
+pub const DEFAULT_COMMENT_LIST_HEIGHT: u16 = 10;
+/// Default width of the file list, in percent of the screen.
+pub const DEFAULT_FILE_LIST_WIDTH_PERCENT: u16 = 20;
+/// Narrowest and widest the file list can be resized to, in percent.
+pub const FILE_LIST_WIDTH_PERCENT_RANGE: RangeInclusive<u16> = 15..=60;
+/// Default rows the inline commit selector may take, including borders.
+pub const DEFAULT_COMMIT_SELECTOR_HEIGHT: u16 = 8;
+/// Fewest and most rows the inline commit selector can be resized to.
+pub const COMMIT_SELECTOR_HEIGHT_RANGE: RangeInclusive<u16> = 3..=20;
+/// Percent of the screen `;<` / `;>` resize the file list by.
+const FILE_LIST_WIDTH_STEP: i16 = 5;
+
+/// Longest gap between two clicks on the same cell that counts as a double-click.
+const DOUBLE_CLICK_WINDOW: Duration = Duration::from_millis(400);
 
 #[derive(Debug, Clone)]
 pub enum FileTreeItem {
@@ -57,6 +81,17 @@ pub struct GapId {
     pub hunk_idx: usize,
 }
 
+/// Rows a `]`/`[` motion moves the cursor between
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BracketMotion {
+    /// First row of a file or line comment (`]c` / `[c`)
+    Comment,
+    /// File header (`]f` / `[f`)
+    File,
+    /// Header of a file that is neither reviewed nor collapsed (`]u` / `[u`)
+    UnreviewedFile,
+}
+
 /// Direction of gap expansion
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ExpandDirection {
@@ -79,7 +114,7 @@ pub enum GapCursorHit {
 }
 
 /// Describes what a rendered line represents - built once and used for O(1) cursor queries
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum AnnotatedLine {
     /// Review comments section header line
     ReviewCommentsHeader,
@@ -126,6 +161,14 @@ pub enum AnnotatedLine {
     },
     /// Binary or empty file indicator
     BinaryOrEmpty { file_idx: usize },
+    /// Blank row reserved for an inline image preview
+    ImagePreview { file_idx: usize },
+    /// Count of diff lines past `max_file_lines`, loaded on Enter
+    TruncatedFile { file_idx: usize },
+    /// Run of unchanged lines inside a hunk, hidden in changes-only view
+    HiddenContext { file_idx: usize, count: usize },
+    /// Mode change or symlink retarget descriptor
+    FileMetadata { file_idx: usize },
     /// Spacing between files
     Spacing,
 }
@@ -149,7 +192,11 @@ pub fn annotation_file_idx(annotation: &AnnotatedLine) -> Option<usize> {
         | AnnotatedLine::DiffLine { file_idx, .. }
         | AnnotatedLine::SideBySideLine { file_idx, .. }
         | AnnotatedLine::LineComment { file_idx, .. }
-        | AnnotatedLine::BinaryOrEmpty { file_idx } => Some(*file_idx),
+        | AnnotatedLine::BinaryOrEmpty { file_idx }
+        | AnnotatedLine::ImagePreview { file_idx }
+        | AnnotatedLine::TruncatedFile { file_idx }
+        | AnnotatedLine::HiddenContext { file_idx, .. }
+        | AnnotatedLine::FileMetadata { file_idx } => Some(*file_idx),
         AnnotatedLine::ReviewCommentsHeader
         | AnnotatedLine::ReviewComment { .. }
         | AnnotatedLine::Expander { .. }
@@ -159,6 +206,27 @@ pub fn annotation_file_idx(annotation: &AnnotatedLine) -> Option<usize> {
     }
 }
 
+/// Context lines from `start` that changes-only view folds into one row.
+/// Commented lines stay visible so their comments have a line to sit under.
+pub fn hidden_context_run(
+    lines: &[DiffLine],
+    start: usize,
+    line_comments: &HashMap<u32, Vec<Comment>>,
+) -> usize {
+    lines
+        .get(start..)
+        .unwrap_or_default()
+        .iter()
+        .take_while(|line| {
+            line.origin == LineOrigin::Context
+                && ![line.old_lineno, line.new_lineno]
+                    .into_iter()
+                    .flatten()
+                    .any(|lineno| line_comments.contains_key(&lineno))
+        })
+        .count()
+}
+
 /// Search `line_annotations` for the annotation whose `new_lineno` best matches
 /// `target_lineno` within the file identified by `current_file`.
 pub fn find_source_line(
@@ -212,6 +280,8 @@ pub enum InputMode {
     Confirm,
     CommitSelect,
     VisualSelect,
+    SessionBrowser,
+    BranchPicker,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -235,6 +305,15 @@ pub enum DiffSource {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ConfirmAction {
     CopyAndQuit,
+    /// Delete the session selected in the session browser
+    DeleteSession,
+    /// Revert a working tree hunk
+    DiscardHunk {
+        file_idx: usize,
+        hunk_idx: usize,
+    },
+    /// Delete the comment selected in the `:comments` list
+    DeleteListedComment,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -242,6 +321,8 @@ pub enum FocusedPanel {
     FileList,
     Diff,
     CommitSelector,
+    /// The `:comments` list below the diff
+    CommentList,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -274,6 +355,8 @@ pub struct App {
     pub input_mode: InputMode,
     pub focused_panel: FocusedPanel,
     pub diff_view_mode: DiffViewMode,
+    /// Per-file overrides of `diff_view_mode`, cleared when the diff reloads
+    pub file_view_modes: HashMap<PathBuf, DiffViewMode>,
 
     pub file_list_state: FileListState,
     pub diff_state: DiffState,
@@ -285,10 +368,15 @@ pub struct App {
     pub comment_cursor: usize,
     pub comment_type: CommentType,
     pub comment_types: Vec<CommentTypeDefinition>,
+    /// `default_comment_type` from the config; new comments start as the
+    /// first of `comment_types` when unset
+    pub preferred_comment_type: Option<CommentType>,
     pub comment_is_review_level: bool,
     pub comment_is_file_level: bool,
     pub comment_line: Option<(u32, LineSide)>,
     pub editing_comment_id: Option<String>,
+    /// Root comment of the thread the comment being written replies to
+    pub reply_to: Option<String>,
 
     /// Visual selection anchor point (starting line, side)
     pub visual_anchor: Option<(u32, LineSide)>,
@@ -306,16 +394,54 @@ pub struct App {
     /// State describing how many commits are currently shown and how pagination behaves.
     pub visible_commit_count: usize,
     pub commit_page_size: usize,
+    /// Commits loaded when the selector opens (`--depth`); later pages
+    /// come in `commit_page_size` at a time.
+    pub commit_depth: usize,
     pub has_more_commit: bool,
 
     pub should_quit: bool,
     pub dirty: bool,
     pub quit_warned: bool,
+    /// Set by `:q!` to exit without autosaving
+    pub discard_on_quit: bool,
     pub message: Option<Message>,
     pub pending_confirm: Option<ConfirmAction>,
     pub supports_keyboard_enhancement: bool,
     pub show_file_list: bool,
     pub cursor_line_highlight: bool,
+    /// Skip reviewed files in next/prev file navigation
+    pub skip_reviewed_in_navigation: bool,
+    /// Scroll the diff to the file under the file-list cursor as it moves
+    pub file_list_preview: bool,
+    /// Fold unchanged lines inside hunks (`:set changesonly`)
+    pub changes_only: bool,
+    /// Generated files the user expanded; the rest stay collapsed
+    pub expanded_generated_files: HashSet<PathBuf>,
+    /// Files folded to their header by double-clicking it
+    pub folded_files: HashSet<PathBuf>,
+    /// Show the scroll indicator on the right edge of the diff panel
+    pub show_scrollbar: bool,
+    /// Lines marked on the diff scrollbar; cleared when annotations change
+    pub scrollbar_ticks: Option<Vec<(usize, ScrollbarTick)>>,
+    /// Visual rows of the diff lines for the scrollbar with wrapping on;
+    /// cleared with the ticks
+    pub scrollbar_rows: Option<VisualRows>,
+    /// List files dropped by ignore rules at the end of the file list
+    pub show_ignored: bool,
+    /// Paths of the current diff's ignored files, loaded while `show_ignored` is on
+    pub ignored_files: Vec<PathBuf>,
+    /// `:filter` glob limiting the file list and file navigation
+    pub file_filter: Option<FileFilter>,
+    /// Hide whitespace-only changes (`git diff -w`)
+    pub ignore_whitespace: bool,
+    /// Color moved blocks and allow jumping between their two sides
+    pub detect_moves: bool,
+    /// Drawn in the first column of wrapped continuation rows; empty for none
+    pub wrap_marker: String,
+    /// `status_line` template for the status bar; the built-in layout when unset
+    pub status_line: Option<String>,
+    /// Post `:export github` reviews with `issue` comments as REQUEST_CHANGES
+    pub github_request_changes: bool,
     pub file_list_area: Option<ratatui::layout::Rect>,
     pub diff_area: Option<ratatui::layout::Rect>,
     /// Inner content rect of the file list panel; populated during render.
@@ -325,17 +451,40 @@ pub struct App {
     /// Visual-row -> annotation-index map for the diff viewport. Wrapped
     /// logical lines repeat their annotation index across multiple rows.
     pub diff_row_to_annotation: Vec<usize>,
+    /// Time and cell of the last left click, for double-click detection.
+    pub last_left_click: Option<(Instant, u16, u16)>,
+    /// The left button was pressed on a diff line and is still held, so
+    /// dragging selects lines.
+    pub mouse_drag_in_diff: bool,
     pub expanded_dirs: HashSet<String>,
     /// Stores lines expanded downward from the upper boundary of each gap
     pub expanded_top: HashMap<GapId, Vec<DiffLine>>,
     /// Stores lines expanded upward from the lower boundary of each gap (in ascending line order)
     pub expanded_bottom: HashMap<GapId, Vec<DiffLine>>,
+    /// Lines loaded per press of a gap expander (`context_expand_lines`)
+    pub context_expand_lines: usize,
     /// Cached annotations describing what each rendered line represents
     pub line_annotations: Vec<AnnotatedLine>,
+    /// Index in `line_annotations` where each file's annotations start, plus
+    /// the total length; everything before the first file is review comments
+    annotation_file_starts: Vec<usize>,
+    /// Background syntax highlighting, started when the first file is shown
+    highlight_pool: Option<HighlightPool>,
+    /// Files (display path, content hash) queued for highlighting whose
+    /// results haven't arrived yet
+    highlight_in_flight: HashSet<(PathBuf, u64)>,
     /// Output to stdout instead of clipboard when exporting
     pub output_to_stdout: bool,
     /// Pending output to print to stdout after TUI exits
     pub pending_stdout_output: Option<String>,
+    /// File (absolute path) and line to open in `$EDITOR`; the main loop
+    /// suspends the TUI to run it
+    pub pending_editor: Option<(PathBuf, Option<u32>)>,
+    /// Temp file holding the commit message being edited in `$EDITOR`, and
+    /// the message it started with
+    commit_message_draft: Option<(PathBuf, String)>,
+    /// Temp file holding the comment being edited in `$EDITOR`
+    comment_draft: Option<PathBuf>,
     /// Calculated screen position for comment input cursor (col, row) for IME positioning.
     /// Set during render when in Comment mode, None otherwise.
     pub comment_cursor_screen_pos: Option<(u16, u16)>,
@@ -350,6 +499,8 @@ pub struct App {
     pub update_info: Option<UpdateInfo>,
     /// Accumulated digit count for {N}G jump-to-line
     pub pending_count: Option<usize>,
+    /// Cursor lines saved with `m{letter}`, jumped back to with `` `{letter} ``
+    pub marks: HashMap<char, usize>,
 
     // Inline commit selector state (shown at top of diff view for multi-commit reviews)
     /// CommitInfo for commits in the current review (display order: newest first)
@@ -360,12 +511,60 @@ pub struct App {
     pub commit_diff_cache: HashMap<(usize, usize), Vec<DiffFile>>,
     /// The combined "all selected" diff, cached for quick restoration
     pub range_diff_files: Option<Vec<DiffFile>>,
+    /// Diffstats of `review_commits`, by commit id, filled in by `load_commit_stats`
+    pub commit_stats: HashMap<String, DiffStat>,
+    /// Diffstats took too long to compute in this repository; leave them blank
+    commit_stats_disabled: bool,
     /// Saved inline selection range when entering full commit select mode via :commits
     pub saved_inline_selection: Option<(usize, usize)>,
-    /// Path filter for scoping diff to a specific file or directory
-    pub path_filter: Option<String>,
+    /// Repository-relative files or directories the diff is limited to;
+    /// empty to show every change
+    pub path_filter: Vec<String>,
+    /// `path_filter` from before `:only` narrowed the diff to one file,
+    /// restored by `:all`
+    pub only_saved_path_filter: Option<Vec<String>>,
     /// Whether to include the "Comment types:" legend line in export
     pub export_legend: bool,
+    /// Undo/redo stacks for comment and review-state changes
+    pub undo_history: UndoHistory,
+    /// Saved sessions shown by `:sessions`
+    pub session_browser: SessionBrowserState,
+    /// Local branches shown by `:branches`
+    pub branch_picker: BranchPickerState,
+    /// Blame of the line under the cursor, shown by `gb`
+    pub blame_popup: Option<BlamePopup>,
+    /// Whether the `:blame` column is shown next to the current file's lines
+    pub show_blame_column: bool,
+    /// Blame of each file the `:blame` column was shown for, by display
+    /// path; None for added files and failed blames
+    pub blame_cache: HashMap<PathBuf, Option<Vec<BlameLine>>>,
+    /// Summary of the diff shown by `:stats` until the next key press
+    pub stats_popup: Option<DiffSummary>,
+    /// Whether the `:comments` list is shown below the diff
+    pub show_comment_list: bool,
+    /// Selected row of the `:comments` list
+    pub comment_list_state: ratatui::widgets::ListState,
+    /// Rows of the `:comments` panel, including borders
+    pub comment_list_height: u16,
+    /// Width of the file list in percent of the screen, resized with `;<` / `;>`
+    pub file_list_width_percent: u16,
+    /// Most rows the inline commit selector takes, resized with `;+` / `;-`
+    pub commit_selector_height: u16,
+    /// Config file panel sizes are saved to when resized; None keeps them
+    /// for this run only
+    pub config_path: Option<PathBuf>,
+    /// `[syntax]` overrides from the config file, reapplied by `:theme`
+    pub syntax_overrides: Vec<(String, String)>,
+    /// How often unsaved changes are written to disk; None disables autosave
+    pub autosave_interval: Option<Duration>,
+    pub last_autosave_attempt: Instant,
+    /// When the session was last autosaved (drives the "saved" indicator)
+    pub last_autosaved_at: Option<Instant>,
+    /// Graphics protocol used to preview images inline; None shows the
+    /// textual descriptor only
+    pub image_protocol: Option<ImageProtocol>,
+    /// Recorded as the author of new comments; changed by `:set-author`
+    pub comment_author: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -376,6 +575,35 @@ pub struct CommentTypeDefinition {
     pub color: Option<Color>,
 }
 
+/// Glob set with `:filter`. Patterns without a `/` match the file name in
+/// any directory, like `.gitignore` entries.
+#[derive(Debug, Clone)]
+pub struct FileFilter {
+    pub pattern: String,
+    matcher: globset::GlobMatcher,
+}
+
+impl FileFilter {
+    pub fn new(pattern: &str) -> std::result::Result<Self, globset::Error> {
+        let matcher = globset::GlobBuilder::new(pattern)
+            .literal_separator(true)
+            .build()?
+            .compile_matcher();
+        Ok(Self {
+            pattern: pattern.to_string(),
+            matcher,
+        })
+    }
+
+    pub fn matches(&self, path: &Path) -> bool {
+        self.matcher.is_match(path)
+            || (!self.pattern.contains('/')
+                && path
+                    .file_name()
+                    .is_some_and(|name| self.matcher.is_match(name)))
+    }
+}
+
 #[derive(Default)]
 pub struct FileListState {
     pub list_state: ratatui::widgets::ListState,
@@ -383,6 +611,9 @@ pub struct FileListState {
     pub viewport_width: usize,    // Set during render
     pub viewport_height: usize,   // Set during render
     pub max_content_width: usize, // Set during render
+    /// File the selection was last synced to while the diff was focused.
+    /// The viewport only follows the selection when this changes.
+    pub synced_file_idx: Option<usize>,
 }
 
 impl FileListState {
@@ -435,6 +666,28 @@ impl Default for DiffState {
     }
 }
 
+/// A saved session listed in the `:sessions` browser.
+#[derive(Debug, Clone)]
+pub struct SessionBrowserEntry {
+    pub path: PathBuf,
+    pub session: ReviewSession,
+    /// The session's commits no longer resolve in the repository
+    pub stale: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct SessionBrowserState {
+    pub entries: Vec<SessionBrowserEntry>,
+    pub selected: usize,
+}
+
+/// Branches listed by the `:branches` picker.
+#[derive(Debug, Default)]
+pub struct BranchPickerState {
+    pub branches: Vec<BranchInfo>,
+    pub selected: usize,
+}
+
 #[derive(Debug, Default)]
 pub struct HelpState {
     pub scroll_offset: usize,
@@ -442,6 +695,54 @@ pub struct HelpState {
     pub total_lines: usize, // Set during render
 }
 
+/// A file or line comment listed by `:comments`.
+#[derive(Debug, Clone)]
+pub struct CommentListEntry {
+    pub path: PathBuf,
+    /// Line and side of a line comment; None for file comments
+    pub line: Option<(u32, LineSide)>,
+    pub comment: Comment,
+}
+
+/// Totals over the loaded diff, shown by `:stats`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffSummary {
+    pub files: usize,
+    pub additions: usize,
+    pub deletions: usize,
+    /// Files per status. Added, modified, deleted and renamed are always
+    /// listed; copies only when there are any.
+    pub by_status: Vec<(FileStatus, usize)>,
+    pub hunks: usize,
+    pub reviewed_hunks: usize,
+}
+
+/// Blame of one line, shown by `gb`. A second `gb` on the same line expands
+/// it to the full commit message.
+#[derive(Debug)]
+pub struct BlamePopup {
+    pub path: PathBuf,
+    pub line: u32,
+    pub side: LineSide,
+    /// Commit that last changed the line; None if it isn't committed yet
+    pub commit: Option<CommitInfo>,
+    pub expanded: bool,
+    pub scroll_offset: usize,
+    pub viewport_height: usize,
+    pub total_lines: usize, // Set during render
+}
+
+impl BlamePopup {
+    pub fn scroll_down(&mut self, lines: usize) {
+        let max_offset = self.total_lines.saturating_sub(self.viewport_height);
+        self.scroll_offset = (self.scroll_offset + lines).min(max_offset);
+    }
+
+    pub fn scroll_up(&mut self, lines: usize) {
+        self.scroll_offset = self.scroll_offset.saturating_sub(lines);
+    }
+}
+
 /// Represents a comment location for deletion
 enum CommentLocation {
     Review {
@@ -459,6 +760,49 @@ enum CommentLocation {
     },
 }
 
+impl CommentLocation {
+    fn anchor(&self) -> CommentAnchor {
+        match self {
+            CommentLocation::Review { .. } => CommentAnchor::Review,
+            CommentLocation::File { path, .. } => CommentAnchor::File(path.clone()),
+            CommentLocation::Line { path, line, .. } => CommentAnchor::Line(path.clone(), *line),
+        }
+    }
+}
+
+/// Canonical form of `path`, resolving `.` and `..` lexically and symlinks
+/// through the deepest ancestor that exists (deleted files don't).
+fn resolve_path(path: &Path) -> PathBuf {
+    let mut lexical = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                lexical.pop();
+            }
+            other => lexical.push(other),
+        }
+    }
+
+    let mut missing = Vec::new();
+    let mut existing = lexical.as_path();
+    loop {
+        if let Ok(canonical) = existing.canonicalize() {
+            return missing
+                .iter()
+                .rev()
+                .fold(canonical, |path, name| path.join(name));
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name);
+                existing = parent;
+            }
+            _ => return lexical,
+        }
+    }
+}
+
 impl App {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
@@ -469,15 +813,23 @@ impl App {
         pr_mode: bool,
         pr_base_ref: Option<&str>,
         working_tree: bool,
-        path_filter: Option<&str>,
+        path_filter: &[String],
+        files: Option<&str>,
         file_path: Option<&str>,
+        rename_threshold: Option<u16>,
+        max_file_lines: Option<usize>,
+        commit_depth: Option<usize>,
     ) -> Result<Self> {
+        let commit_depth = commit_depth.unwrap_or(VISIBLE_COMMIT_COUNT);
+        // The glob was validated while parsing the command line
+        let files_filter = files.and_then(|pattern| FileFilter::new(pattern).ok());
+        let files_filter = files_filter.as_ref();
+
         // --file mode: open a single file for annotation without VCS
         if let Some(file_path) = file_path {
             let vcs = Box::new(FileBackend::new(file_path)?);
             let vcs_info = vcs.info().clone();
-            let highlighter = theme.syntax_highlighter();
-            let diff_files = vcs.get_working_tree_diff(highlighter)?;
+            let diff_files = vcs.get_working_tree_diff()?;
             let session = Self::load_or_create_session(&vcs_info, SessionDiffSource::WorkingTree);
 
             let mut app = Self::build(
@@ -491,7 +843,8 @@ impl App {
                 DiffSource::WorkingTree,
                 InputMode::Normal,
                 Vec::new(),
-                None, // no path_filter
+                &[], // no path_filter
+                None,
             )?;
 
             // Hide file list since there's only one file
@@ -501,11 +854,19 @@ impl App {
             return Ok(app);
         }
 
-        let vcs = detect_vcs()?;
+        let mut vcs = detect_vcs()?;
+        if let Some(percent) = rename_threshold {
+            vcs.set_rename_threshold(percent);
+        }
+        vcs.set_max_file_lines(max_file_lines);
         let vcs_info = vcs.info().clone();
-        let highlighter = theme.syntax_highlighter();
+        let path_filter = &Self::repo_relative_paths(&vcs_info.root_path, path_filter)?;
         if pr_mode {
-            let pr_diff = vcs.get_pull_request_diff(pr_base_ref, highlighter)?;
+            let mut pr_diff = vcs.get_pull_request_diff(pr_base_ref)?;
+            pr_diff.files = Self::require_non_empty_diff_files(Self::filter_by_paths(
+                Self::filter_ignored_diff_files(&vcs_info.root_path, pr_diff.files),
+                path_filter,
+            ))?;
             let mut session = ReviewSession::new(
                 vcs_info.root_path.clone(),
                 pr_diff.info.head_commit.clone(),
@@ -534,6 +895,7 @@ impl App {
                 InputMode::Normal,
                 Vec::new(),
                 path_filter,
+                files_filter,
             );
         }
         // Determine the diff source, files, and session based on input.
@@ -551,7 +913,6 @@ impl App {
                     vcs.as_ref(),
                     &vcs_info.root_path,
                     &commit_ids,
-                    highlighter,
                     path_filter,
                 )?;
                 let session = Self::load_or_create_staged_unstaged_and_commits_session(
@@ -567,14 +928,12 @@ impl App {
                 let has_staged = Self::get_staged_diff_with_ignore(
                     vcs.as_ref(),
                     &vcs_info.root_path,
-                    highlighter,
                     path_filter,
                 )
                 .is_ok();
                 let has_unstaged = Self::get_unstaged_diff_with_ignore(
                     vcs.as_ref(),
                     &vcs_info.root_path,
-                    highlighter,
                     path_filter,
                 )
                 .is_ok();
@@ -599,6 +958,7 @@ impl App {
                     InputMode::Normal,
                     Vec::new(),
                     path_filter,
+                    files_filter,
                 )?;
 
                 app.range_diff_files = Some(app.diff_files.clone());
@@ -628,7 +988,6 @@ impl App {
                 vcs.as_ref(),
                 &vcs_info.root_path,
                 &commit_ids,
-                highlighter,
                 path_filter,
             )?;
             let session = Self::load_or_create_commit_range_session(&vcs_info, &commit_ids);
@@ -649,6 +1008,7 @@ impl App {
                 InputMode::Normal,
                 Vec::new(),
                 path_filter,
+                files_filter,
             )?;
 
             // Set up inline commit selector for multi-commit reviews
@@ -675,7 +1035,6 @@ impl App {
             let diff_files = Self::get_working_tree_diff_with_ignore(
                 vcs.as_ref(),
                 &vcs_info.root_path,
-                highlighter,
                 path_filter,
             )?;
             let session =
@@ -693,6 +1052,7 @@ impl App {
                 InputMode::Normal,
                 Vec::new(),
                 path_filter,
+                files_filter,
             )?;
 
             Ok(app)
@@ -700,7 +1060,6 @@ impl App {
             let has_staged_changes = match Self::get_staged_diff_with_ignore(
                 vcs.as_ref(),
                 &vcs_info.root_path,
-                highlighter,
                 path_filter,
             ) {
                 Ok(_) => true,
@@ -712,7 +1071,6 @@ impl App {
             let has_unstaged_changes = match Self::get_unstaged_diff_with_ignore(
                 vcs.as_ref(),
                 &vcs_info.root_path,
-                highlighter,
                 path_filter,
             ) {
                 Ok(_) => true,
@@ -725,7 +1083,6 @@ impl App {
                 match Self::get_working_tree_diff_with_ignore(
                     vcs.as_ref(),
                     &vcs_info.root_path,
-                    highlighter,
                     path_filter,
                 ) {
                     Ok(diff_files) => Some(diff_files),
@@ -736,7 +1093,7 @@ impl App {
                 None
             };
 
-            let commits = vcs.get_recent_commits(0, VISIBLE_COMMIT_COUNT)?;
+            let commits = vcs.get_recent_commits(0, commit_depth)?;
             if !has_staged_changes && !has_unstaged_changes && commits.is_empty() {
                 return Err(TuicrError::NoChanges);
             }
@@ -783,9 +1140,11 @@ impl App {
                 InputMode::CommitSelect,
                 commit_list,
                 path_filter,
+                files_filter,
             )?;
 
-            app.has_more_commit = commits.len() >= VISIBLE_COMMIT_COUNT;
+            app.commit_depth = commit_depth;
+            app.has_more_commit = commits.len() >= commit_depth;
             app.visible_commit_count = app.commit_list.len();
             Ok(app)
         }
@@ -804,8 +1163,28 @@ impl App {
         diff_source: DiffSource,
         input_mode: InputMode,
         commit_list: Vec<CommitInfo>,
-        path_filter: Option<&str>,
+        path_filter: &[String],
+        files_filter: Option<&FileFilter>,
     ) -> Result<Self> {
+        let diff_files = match files_filter {
+            Some(filter) => {
+                let diff_files: Vec<DiffFile> = diff_files
+                    .into_iter()
+                    .filter(|file| filter.matches(file.display_path()))
+                    .collect();
+                // The commit selector can still pick commits that touch matching files
+                if diff_files.is_empty() && input_mode != InputMode::CommitSelect {
+                    return Err(TuicrError::NoMatchingFiles(filter.pattern.clone()));
+                }
+                // Keep entries of filtered-out files that hold comments from earlier reviews
+                session
+                    .files
+                    .retain(|path, review| filter.matches(path) || review.comment_count() > 0);
+                diff_files
+            }
+            None => diff_files,
+        };
+
         // Ensure all diff files are registered in the session
         for file in &diff_files {
             session.add_file(file.display_path().clone(), file.status, file.content_hash);
@@ -820,6 +1199,9 @@ impl App {
 
         let comment_types = Self::resolve_comment_types(&theme, comment_type_configs);
         let default_comment_type = Self::first_comment_type(&comment_types);
+        let comment_author = vcs
+            .user_name()
+            .or_else(|| std::env::var("USER").ok().filter(|user| !user.is_empty()));
 
         let mut app = Self {
             theme,
@@ -831,6 +1213,7 @@ impl App {
             input_mode,
             focused_panel: FocusedPanel::Diff,
             diff_view_mode: DiffViewMode::Unified,
+            file_view_modes: HashMap::new(),
             file_list_state: FileListState::default(),
             diff_state: DiffState::default(),
             help_state: HelpState::default(),
@@ -841,10 +1224,12 @@ impl App {
             comment_cursor: 0,
             comment_type: default_comment_type,
             comment_types,
+            preferred_comment_type: None,
             comment_is_review_level: false,
             comment_is_file_level: true,
             comment_line: None,
             editing_comment_id: None,
+            reply_to: None,
             visual_anchor: None,
             comment_line_range: None,
             commit_list,
@@ -854,40 +1239,90 @@ impl App {
             commit_selection_range: None,
             visible_commit_count,
             commit_page_size: COMMIT_PAGE_SIZE,
+            commit_depth: VISIBLE_COMMIT_COUNT,
             has_more_commit,
             should_quit: false,
             dirty: false,
             quit_warned: false,
+            discard_on_quit: false,
             message: None,
             pending_confirm: None,
             supports_keyboard_enhancement: false,
             show_file_list: true,
             cursor_line_highlight: true,
+            skip_reviewed_in_navigation: false,
+            file_list_preview: false,
+            changes_only: false,
+            expanded_generated_files: HashSet::new(),
+            folded_files: HashSet::new(),
+            file_filter: files_filter.cloned(),
+            show_scrollbar: true,
+            scrollbar_ticks: None,
+            scrollbar_rows: None,
+            show_ignored: false,
+            ignored_files: Vec::new(),
+            ignore_whitespace: false,
+            detect_moves: true,
+            wrap_marker: "↪".to_string(),
+            status_line: None,
+            github_request_changes: true,
             file_list_area: None,
             diff_area: None,
             file_list_inner_area: None,
             diff_inner_area: None,
             diff_row_to_annotation: Vec::new(),
+            last_left_click: None,
+            mouse_drag_in_diff: false,
             expanded_dirs: HashSet::new(),
             expanded_top: HashMap::new(),
             expanded_bottom: HashMap::new(),
+            context_expand_lines: GAP_EXPAND_BATCH,
             line_annotations: Vec::new(),
+            annotation_file_starts: Vec::new(),
+            highlight_pool: None,
+            highlight_in_flight: HashSet::new(),
             output_to_stdout,
             pending_stdout_output: None,
+            pending_editor: None,
+            commit_message_draft: None,
+            comment_draft: None,
             comment_cursor_screen_pos: None,
             comment_input_annotation_offset: None,
             update_info: None,
             pending_count: None,
+            marks: HashMap::new(),
             review_commits: Vec::new(),
             show_commit_selector: false,
             commit_diff_cache: HashMap::new(),
             range_diff_files: None,
+            commit_stats: HashMap::new(),
+            commit_stats_disabled: false,
             saved_inline_selection: None,
-            path_filter: path_filter.map(|s| s.to_string()),
+            path_filter: path_filter.to_vec(),
+            only_saved_path_filter: None,
             export_legend: true,
+            undo_history: UndoHistory::default(),
+            session_browser: SessionBrowserState::default(),
+            branch_picker: BranchPickerState::default(),
+            blame_popup: None,
+            show_blame_column: false,
+            blame_cache: HashMap::new(),
+            stats_popup: None,
+            show_comment_list: false,
+            comment_list_state: ratatui::widgets::ListState::default(),
+            comment_list_height: DEFAULT_COMMENT_LIST_HEIGHT,
+            file_list_width_percent: DEFAULT_FILE_LIST_WIDTH_PERCENT,
+            commit_selector_height: DEFAULT_COMMIT_SELECTOR_HEIGHT,
+            config_path: None,
+            syntax_overrides: Vec::new(),
+            autosave_interval: Some(DEFAULT_AUTOSAVE_INTERVAL),
+            last_autosave_attempt: Instant::now(),
+            last_autosaved_at: None,
+            image_protocol: None,
+            comment_author,
         };
         // Auto-hide file list when path filter matches exactly one file
-        if app.path_filter.is_some() && app.diff_files.len() == 1 {
+        if !app.path_filter.is_empty() && app.diff_files.len() == 1 {
             app.show_file_list = false;
             app.focused_panel = FocusedPanel::Diff;
         }
@@ -897,7 +1332,7 @@ impl App {
         Ok(app)
     }
 
-    fn resolve_comment_types(
+    pub(crate) fn resolve_comment_types(
         theme: &Theme,
         comment_type_configs: Option<Vec<CommentTypeConfig>>,
     ) -> Vec<CommentTypeDefinition> {
@@ -937,7 +1372,11 @@ impl App {
             let id = config.id;
             let label = config.label.unwrap_or_else(|| id.clone());
             let definition = config.definition;
-            let color = config.color.as_deref().and_then(Self::parse_config_color);
+            let color = config
+                .color
+                .as_deref()
+                .and_then(Self::parse_config_color)
+                .map(|color| theme.color_mode.convert(color));
             resolved.push(CommentTypeDefinition {
                 id,
                 label,
@@ -960,8 +1399,11 @@ impl App {
             .unwrap_or_default()
     }
 
+    /// Type new comments start as. Editing a comment keeps its own type.
     fn default_comment_type(&self) -> CommentType {
-        Self::first_comment_type(&self.comment_types)
+        self.preferred_comment_type
+            .clone()
+            .unwrap_or_else(|| Self::first_comment_type(&self.comment_types))
     }
 
     fn parse_config_color(value: &str) -> Option<Color> {
@@ -1014,6 +1456,9 @@ impl App {
     }
 
     pub fn comment_type_color(&self, comment_type: &CommentType) -> Color {
+        if self.theme.monochrome {
+            return self.theme.fg_primary;
+        }
         if let Some(definition) = self
             .comment_types
             .iter()
@@ -1141,6 +1586,13 @@ impl App {
         session
     }
 
+    /// Swap in a different review session. Undo history refers to the old
+    /// session, so it is discarded.
+    fn replace_session(&mut self, session: ReviewSession) {
+        self.session = session;
+        self.undo_history.clear();
+    }
+
     fn staged_commit_entry() -> CommitInfo {
         CommitInfo {
             id: STAGED_SELECTION_ID.to_string(),
@@ -1202,6 +1654,8 @@ impl App {
                 old_lineno: None,
                 new_lineno: Some(i as u32 + 1),
                 highlighted_spans: None,
+                whitespace_only: false,
+                move_id: None,
             })
             .collect();
         let line_count = diff_lines.len() as u32;
@@ -1220,9 +1674,19 @@ impl App {
             status: FileStatus::Added,
             hunks,
             is_binary: false,
+            binary_meta: None,
             is_too_large: false,
+            truncated_lines: 0,
             is_commit_message: true,
+            is_submodule: false,
+            old_mode: None,
+            new_mode: None,
+            is_symlink: false,
+            is_conflicted: false,
+            has_conflicts: false,
             content_hash,
+            highlighted: false,
+            is_generated: false,
         };
         self.diff_files.insert(0, commit_msg_file);
         self.session.add_file(
@@ -1257,17 +1721,43 @@ impl App {
             .saturating_sub(self.special_commit_count())
     }
 
+    /// Drop `.tuicrignore`d files and tag moved blocks among the rest.
     fn filter_ignored_diff_files(repo_root: &Path, diff_files: Vec<DiffFile>) -> Vec<DiffFile> {
-        crate::tuicrignore::filter_diff_files(repo_root, diff_files)
+        let mut diff_files = crate::tuicrignore::filter_diff_files(repo_root, diff_files);
+        detect_moved_blocks(&mut diff_files);
+        diff_files
+    }
+
+    /// Make absolute filter paths relative to the repository root; relative
+    /// ones are taken to be repository-relative already.
+    fn repo_relative_paths(repo_root: &Path, paths: &[String]) -> Result<Vec<String>> {
+        let root = resolve_path(repo_root);
+        paths
+            .iter()
+            .map(|path| {
+                if !Path::new(path).is_absolute() {
+                    return Ok(path.clone());
+                }
+                resolve_path(Path::new(path))
+                    .strip_prefix(&root)
+                    .map(|relative| relative.to_string_lossy().replace('\\', "/"))
+                    .map_err(|_| TuicrError::PathOutsideRepository(path.clone()))
+            })
+            .collect()
     }
 
-    fn filter_by_path(diff_files: Vec<DiffFile>, path: &str) -> Vec<DiffFile> {
-        let path = path.trim_end_matches('/');
+    fn filter_by_paths(diff_files: Vec<DiffFile>, paths: &[String]) -> Vec<DiffFile> {
+        if paths.is_empty() {
+            return diff_files;
+        }
         diff_files
             .into_iter()
             .filter(|f| {
                 let display = f.display_path().to_string_lossy();
-                display == path || display.starts_with(&format!("{path}/"))
+                paths.iter().any(|path| {
+                    let path = path.trim_end_matches('/');
+                    path.is_empty() || display == path || display.starts_with(&format!("{path}/"))
+                })
             })
             .collect()
     }
@@ -1282,52 +1772,37 @@ impl App {
     fn get_working_tree_diff_with_ignore(
         vcs: &dyn VcsBackend,
         repo_root: &Path,
-        highlighter: &SyntaxHighlighter,
-        path_filter: Option<&str>,
+        path_filter: &[String],
     ) -> Result<Vec<DiffFile>> {
-        let diff_files = vcs.get_working_tree_diff(highlighter)?;
+        let diff_files = vcs.get_working_tree_diff()?;
         let diff_files = Self::filter_ignored_diff_files(repo_root, diff_files);
-        let diff_files = if let Some(path) = path_filter {
-            Self::filter_by_path(diff_files, path)
-        } else {
-            diff_files
-        };
+        let diff_files = Self::filter_by_paths(diff_files, path_filter);
         Self::require_non_empty_diff_files(diff_files)
     }
 
     fn get_staged_diff_with_ignore(
         vcs: &dyn VcsBackend,
         repo_root: &Path,
-        highlighter: &SyntaxHighlighter,
-        path_filter: Option<&str>,
+        path_filter: &[String],
     ) -> Result<Vec<DiffFile>> {
-        let diff_files = vcs.get_staged_diff(highlighter)?;
+        let diff_files = vcs.get_staged_diff()?;
         let diff_files = Self::filter_ignored_diff_files(repo_root, diff_files);
-        let diff_files = if let Some(path) = path_filter {
-            Self::filter_by_path(diff_files, path)
-        } else {
-            diff_files
-        };
+        let diff_files = Self::filter_by_paths(diff_files, path_filter);
         Self::require_non_empty_diff_files(diff_files)
     }
 
     fn get_unstaged_diff_with_ignore(
         vcs: &dyn VcsBackend,
         repo_root: &Path,
-        highlighter: &SyntaxHighlighter,
-        path_filter: Option<&str>,
+        path_filter: &[String],
     ) -> Result<Vec<DiffFile>> {
-        let diff_files = match vcs.get_unstaged_diff(highlighter) {
+        let diff_files = match vcs.get_unstaged_diff() {
             Ok(diff_files) => diff_files,
-            Err(TuicrError::UnsupportedOperation(_)) => vcs.get_working_tree_diff(highlighter)?,
+            Err(TuicrError::UnsupportedOperation(_)) => vcs.get_working_tree_diff()?,
             Err(e) => return Err(e),
         };
         let diff_files = Self::filter_ignored_diff_files(repo_root, diff_files);
-        let diff_files = if let Some(path) = path_filter {
-            Self::filter_by_path(diff_files, path)
-        } else {
-            diff_files
-        };
+        let diff_files = Self::filter_by_paths(diff_files, path_filter);
         Self::require_non_empty_diff_files(diff_files)
     }
 
@@ -1335,16 +1810,11 @@ impl App {
         vcs: &dyn VcsBackend,
         repo_root: &Path,
         commit_ids: &[String],
-        highlighter: &SyntaxHighlighter,
-        path_filter: Option<&str>,
+        path_filter: &[String],
     ) -> Result<Vec<DiffFile>> {
-        let diff_files = vcs.get_commit_range_diff(commit_ids, highlighter)?;
+        let diff_files = vcs.get_commit_range_diff(commit_ids)?;
         let diff_files = Self::filter_ignored_diff_files(repo_root, diff_files);
-        let diff_files = if let Some(path) = path_filter {
-            Self::filter_by_path(diff_files, path)
-        } else {
-            diff_files
-        };
+        let diff_files = Self::filter_by_paths(diff_files, path_filter);
         Self::require_non_empty_diff_files(diff_files)
     }
 
@@ -1352,26 +1822,19 @@ impl App {
         vcs: &dyn VcsBackend,
         repo_root: &Path,
         commit_ids: &[String],
-        highlighter: &SyntaxHighlighter,
-        path_filter: Option<&str>,
+        path_filter: &[String],
     ) -> Result<Vec<DiffFile>> {
-        let diff_files = vcs.get_working_tree_with_commits_diff(commit_ids, highlighter)?;
+        let diff_files = vcs.get_working_tree_with_commits_diff(commit_ids)?;
         let diff_files = Self::filter_ignored_diff_files(repo_root, diff_files);
-        let diff_files = if let Some(path) = path_filter {
-            Self::filter_by_path(diff_files, path)
-        } else {
-            diff_files
-        };
+        let diff_files = Self::filter_by_paths(diff_files, path_filter);
         Self::require_non_empty_diff_files(diff_files)
     }
 
     fn load_staged_and_unstaged_selection(&mut self) -> Result<()> {
-        let highlighter = self.theme.syntax_highlighter();
         let diff_files = match Self::get_working_tree_diff_with_ignore(
             self.vcs.as_ref(),
             &self.vcs_info.root_path,
-            highlighter,
-            self.path_filter.as_deref(),
+            &self.path_filter,
         ) {
             Ok(diff_files) => diff_files,
             Err(TuicrError::NoChanges) => {
@@ -1381,8 +1844,10 @@ impl App {
             Err(e) => return Err(e),
         };
 
-        self.session =
-            Self::load_or_create_session(&self.vcs_info, SessionDiffSource::StagedAndUnstaged);
+        self.replace_session(Self::load_or_create_session(
+            &self.vcs_info,
+            SessionDiffSource::StagedAndUnstaged,
+        ));
         for file in &diff_files {
             let path = file.display_path().clone();
             self.session.add_file(path, file.status, file.content_hash);
@@ -1402,12 +1867,10 @@ impl App {
     }
 
     fn load_staged_selection(&mut self) -> Result<()> {
-        let highlighter = self.theme.syntax_highlighter();
         let diff_files = match Self::get_staged_diff_with_ignore(
             self.vcs.as_ref(),
             &self.vcs_info.root_path,
-            highlighter,
-            self.path_filter.as_deref(),
+            &self.path_filter,
         ) {
             Ok(diff_files) => diff_files,
             Err(TuicrError::NoChanges) => {
@@ -1417,7 +1880,10 @@ impl App {
             Err(e) => return Err(e),
         };
 
-        self.session = Self::load_or_create_session(&self.vcs_info, SessionDiffSource::Staged);
+        self.replace_session(Self::load_or_create_session(
+            &self.vcs_info,
+            SessionDiffSource::Staged,
+        ));
         for file in &diff_files {
             let path = file.display_path().clone();
             self.session.add_file(path, file.status, file.content_hash);
@@ -1437,12 +1903,10 @@ impl App {
     }
 
     fn load_unstaged_selection(&mut self) -> Result<()> {
-        let highlighter = self.theme.syntax_highlighter();
         let diff_files = match Self::get_unstaged_diff_with_ignore(
             self.vcs.as_ref(),
             &self.vcs_info.root_path,
-            highlighter,
-            self.path_filter.as_deref(),
+            &self.path_filter,
         ) {
             Ok(diff_files) => diff_files,
             Err(TuicrError::NoChanges) => {
@@ -1452,7 +1916,10 @@ impl App {
             Err(e) => return Err(e),
         };
 
-        self.session = Self::load_or_create_session(&self.vcs_info, SessionDiffSource::Unstaged);
+        self.replace_session(Self::load_or_create_session(
+            &self.vcs_info,
+            SessionDiffSource::Unstaged,
+        ));
         for file in &diff_files {
             let path = file.display_path().clone();
             self.session.add_file(path, file.status, file.content_hash);
@@ -1471,6 +1938,29 @@ impl App {
         Ok(())
     }
 
+    /// Reloads diff files after a change on disk. Returns how many files were
+    /// added, removed, or had their diff change.
+    pub fn reload_changed_files(&mut self) -> Result<usize> {
+        let before: HashMap<PathBuf, u64> = self
+            .diff_files
+            .iter()
+            .map(|file| (file.display_path().clone(), file.content_hash))
+            .collect();
+
+        self.reload_diff_files()?;
+
+        let changed = self
+            .diff_files
+            .iter()
+            .filter(|file| before.get(file.display_path()) != Some(&file.content_hash))
+            .count();
+        let removed = before
+            .keys()
+            .filter(|path| !self.diff_files.iter().any(|f| f.display_path() == *path))
+            .count();
+        Ok(changed + removed)
+    }
+
     /// Reloads diff files from disk. Returns `(file_count, invalidated_count)` where
     /// `invalidated_count` is the number of previously reviewed files whose content changed.
     pub fn reload_diff_files(&mut self) -> Result<(usize, usize)> {
@@ -1488,14 +1978,12 @@ impl App {
             prev_cursor_line.saturating_sub(start)
         };
 
-        let highlighter = self.theme.syntax_highlighter();
         let diff_files = match &self.diff_source {
             DiffSource::CommitRange(commit_ids) => Self::get_commit_range_diff_with_ignore(
                 self.vcs.as_ref(),
                 &self.vcs_info.root_path,
                 commit_ids,
-                highlighter,
-                self.path_filter.as_deref(),
+                &self.path_filter,
             )?,
             DiffSource::WorkingTreeAndCommits(commit_ids)
             | DiffSource::StagedUnstagedAndCommits(commit_ids) => {
@@ -1504,15 +1992,12 @@ impl App {
                     self.vcs.as_ref(),
                     &self.vcs_info.root_path,
                     &ids,
-                    highlighter,
-                    self.path_filter.as_deref(),
+                    &self.path_filter,
                 )?
             }
             DiffSource::PullRequest { base_ref, .. } => {
                 let base = base_ref.clone();
-                let pr_diff = self
-                    .vcs
-                    .get_pull_request_diff(Some(base.as_str()), highlighter)?;
+                let pr_diff = self.vcs.get_pull_request_diff(Some(base.as_str()))?;
                 self.diff_source = DiffSource::PullRequest {
                     base_ref: pr_diff.info.base_ref,
                     merge_base_commit: pr_diff.info.merge_base_commit,
@@ -1520,26 +2005,26 @@ impl App {
                     commit_count: pr_diff.info.commit_count,
                 };
                 self.session.base_commit = pr_diff.info.head_commit;
-                Self::filter_ignored_diff_files(&self.vcs_info.root_path, pr_diff.files)
+                Self::filter_by_paths(
+                    Self::filter_ignored_diff_files(&self.vcs_info.root_path, pr_diff.files),
+                    &self.path_filter,
+                )
             }
             DiffSource::Staged => Self::get_staged_diff_with_ignore(
                 self.vcs.as_ref(),
                 &self.vcs_info.root_path,
-                highlighter,
-                self.path_filter.as_deref(),
+                &self.path_filter,
             )?,
             DiffSource::Unstaged => Self::get_unstaged_diff_with_ignore(
                 self.vcs.as_ref(),
                 &self.vcs_info.root_path,
-                highlighter,
-                self.path_filter.as_deref(),
+                &self.path_filter,
             )?,
             DiffSource::StagedAndUnstaged | DiffSource::WorkingTree => {
                 Self::get_working_tree_diff_with_ignore(
                     self.vcs.as_ref(),
                     &self.vcs_info.root_path,
-                    highlighter,
-                    self.path_filter.as_deref(),
+                    &self.path_filter,
                 )?
             }
         };
@@ -1547,6 +2032,11 @@ impl App {
         let mut invalidated = 0;
         for file in &diff_files {
             let path = file.display_path().clone();
+            // Hashes of whitespace-filtered diffs differ from the full diff the
+            // review was done on, so only register files new to the session
+            if self.ignore_whitespace && self.session.files.contains_key(&path) {
+                continue;
+            }
             if self.session.add_file(path, file.status, file.content_hash) {
                 invalidated += 1;
             }
@@ -1554,9 +2044,15 @@ impl App {
 
         self.diff_files = diff_files;
         self.clear_expanded_gaps();
+        self.file_view_modes.clear();
+        self.blame_cache.clear();
+        self.reanchor_comments();
 
         self.sort_files_by_directory(false);
         self.expand_all_dirs();
+        // File offsets come from the annotations, so build them before
+        // restoring the cursor
+        self.rebuild_annotations();
 
         if self.diff_files.is_empty() {
             self.diff_state.current_file_idx = 0;
@@ -1576,7 +2072,7 @@ impl App {
             self.jump_to_file(target_idx);
 
             let file_start = self.calculate_file_scroll_offset(target_idx);
-            let file_height = self.file_render_height(target_idx, &self.diff_files[target_idx]);
+            let file_height = self.file_render_height(target_idx);
             let relative_line = prev_relative_line.min(file_height.saturating_sub(1));
             self.diff_state.cursor_line = file_start.saturating_add(relative_line);
 
@@ -1599,10 +2095,41 @@ impl App {
             self.update_current_file_from_cursor();
         }
 
-        self.rebuild_annotations();
+        let total_lines = self.total_lines();
+        self.marks.retain(|_, line| *line < total_lines);
         Ok((self.diff_files.len(), invalidated))
     }
 
+    /// Move comments on files that changed on disk to where their lines
+    /// went, detaching the ones whose line is gone.
+    fn reanchor_comments(&mut self) {
+        for file in &self.diff_files {
+            let path = file.display_path();
+            let Some(review) = self.session.files.get(path) else {
+                continue;
+            };
+            let needs_reanchor = review
+                .line_comments
+                .values()
+                .flatten()
+                .any(|comment| comment.fingerprint.is_some() && !comment.orphaned);
+            if !needs_reanchor {
+                continue;
+            }
+            let Some(contents) = self.working_tree_contents(path) else {
+                continue;
+            };
+            let lines: Vec<&str> = contents.lines().collect();
+            let Some(review) = self.session.files.get_mut(path) else {
+                continue;
+            };
+            let (moved, detached) = review.reanchor_comments(&lines);
+            if moved + detached > 0 {
+                self.dirty = true;
+            }
+        }
+    }
+
     pub fn can_stage(&self) -> bool {
         matches!(
             self.diff_source,
@@ -1635,6 +2162,155 @@ impl App {
             staged += 1;
         }
         self.set_message(format!("Staged {} reviewed file(s)", staged));
+        self.reload_after_vcs_change();
+    }
+
+    /// Stage (or unstage) the hunk under the cursor and reload the diff.
+    pub fn stage_hunk_at_cursor(&mut self, unstage: bool) {
+        // Staging compares the working tree to the index and unstaging the
+        // index to HEAD, so the viewed diff must have the matching side.
+        let supported = if unstage {
+            matches!(
+                self.diff_source,
+                DiffSource::WorkingTree | DiffSource::Staged | DiffSource::StagedAndUnstaged
+            )
+        } else {
+            matches!(
+                self.diff_source,
+                DiffSource::WorkingTree | DiffSource::Unstaged | DiffSource::StagedAndUnstaged
+            )
+        };
+        if !supported {
+            self.set_warning(if unstage {
+                "Unstaging hunks not available for this diff"
+            } else {
+                "Staging hunks not available for this diff"
+            });
+            return;
+        }
+        if self.ignore_whitespace {
+            self.set_warning("Hunks can't be staged while ignoring whitespace");
+            return;
+        }
+        let Some((file_idx, hunk_idx)) = self.hunk_at_cursor() else {
+            self.set_warning("Move the cursor onto a hunk first");
+            return;
+        };
+        let Some(file) = self.diff_files.get(file_idx) else {
+            return;
+        };
+        if file.is_submodule {
+            self.set_warning("Stage submodule changes with :stage on the reviewed file");
+            return;
+        }
+        if file.truncated_lines > 0 && hunk_idx + 1 == file.hunks.len() {
+            self.set_warning("Load the rest of the file before staging this hunk");
+            return;
+        }
+        let Some(hunk) = file.hunks.get(hunk_idx) else {
+            return;
+        };
+        let path = file.display_path().clone();
+
+        let result = if unstage {
+            self.vcs.unstage_hunk(&path, hunk)
+        } else {
+            self.vcs.stage_hunk(&path, hunk)
+        };
+        match result {
+            Ok(()) => {
+                let verb = if unstage { "Unstaged" } else { "Staged" };
+                self.set_message(format!("{verb} hunk in {}", path.display()));
+            }
+            Err(TuicrError::NoChanges) => {
+                self.set_warning(if unstage {
+                    "Hunk has no staged changes"
+                } else {
+                    "Hunk is already staged"
+                });
+                return;
+            }
+            Err(e) => {
+                self.set_error(format!("{e}"));
+                return;
+            }
+        }
+
+        self.reload_after_vcs_change();
+    }
+
+    /// Ask for confirmation before discarding the working tree hunk under the cursor.
+    pub fn request_discard_hunk(&mut self) {
+        if self.diff_source != DiffSource::WorkingTree {
+            self.set_warning("Discarding hunks only available for working tree diffs");
+            return;
+        }
+        if self.ignore_whitespace {
+            self.set_warning("Hunks can't be discarded while ignoring whitespace");
+            return;
+        }
+        let Some((file_idx, hunk_idx)) = self.hunk_at_cursor() else {
+            self.set_warning("Move the cursor onto a hunk first");
+            return;
+        };
+        let Some(file) = self.diff_files.get(file_idx) else {
+            return;
+        };
+        if file.is_binary {
+            self.set_error("Cannot discard changes in binary files");
+            return;
+        }
+        if file.is_submodule {
+            self.set_error("Cannot discard submodule pointer changes");
+            return;
+        }
+        if file.truncated_lines > 0 && hunk_idx + 1 == file.hunks.len() {
+            self.set_warning("Load the rest of the file before discarding this hunk");
+            return;
+        }
+        if matches!(file.status, FileStatus::Renamed | FileStatus::Copied) {
+            self.set_error("Cannot discard changes in renamed or copied files");
+            return;
+        }
+        self.enter_confirm_mode(ConfirmAction::DiscardHunk { file_idx, hunk_idx });
+    }
+
+    /// Revert a working tree hunk, refusing if the file changed since the diff was loaded.
+    pub fn discard_hunk(&mut self, file_idx: usize, hunk_idx: usize) {
+        let Some(file) = self.diff_files.get(file_idx) else {
+            return;
+        };
+        let Some(hunk) = file.hunks.get(hunk_idx) else {
+            return;
+        };
+        let path = file.display_path().clone();
+
+        let unchanged = match self.vcs.get_working_tree_diff() {
+            Ok(current) => current
+                .iter()
+                .any(|f| f.display_path() == &path && f.content_hash == file.content_hash),
+            Err(_) => false,
+        };
+        if !unchanged {
+            self.set_error(format!(
+                "{} changed since the diff was loaded; reload with :e",
+                path.display()
+            ));
+            return;
+        }
+
+        match self.vcs.discard_hunk(&path, hunk) {
+            Ok(()) => self.set_message(format!("Discarded hunk in {}", path.display())),
+            Err(e) => {
+                self.set_error(format!("{e}"));
+                return;
+            }
+        }
+        self.reload_after_vcs_change();
+    }
+
+    /// Reload the diff after changing the index or working tree, which may leave it empty.
+    fn reload_after_vcs_change(&mut self) {
         if let Err(TuicrError::NoChanges) = self.reload_diff_files() {
             self.diff_files.clear();
             self.diff_state = DiffState::default();
@@ -1668,8 +2344,22 @@ impl App {
 
         if let Some(review) = self.session.get_file_mut(&path) {
             review.reviewed = !review.reviewed;
+            let description = if review.reviewed {
+                format!("mark {} reviewed", path.display())
+            } else {
+                format!("mark {} unreviewed", path.display())
+            };
+            let reviewed = !review.reviewed;
+            if review.reviewed && self.diff_files[file_idx].has_unresolved_conflicts() {
+                self.set_warning(format!(
+                    "{} still has unresolved merge conflicts",
+                    path.display()
+                ));
+            }
+            self.undo_history
+                .record(description, UndoAction::SetReviewed { path, reviewed });
             self.dirty = true;
-            self.rebuild_annotations();
+            self.rebuild_file_annotations(file_idx);
 
             if adjust_cursor {
                 self.diff_state.current_file_idx = file_idx;
@@ -1681,36 +2371,221 @@ impl App {
         }
     }
 
-    pub fn file_count(&self) -> usize {
-        self.diff_files.len()
-    }
-
-    pub fn reviewed_count(&self) -> usize {
-        self.session.reviewed_count()
+    /// Whether the hunk is marked reviewed on its own, which collapses it to
+    /// its header.
+    pub fn is_hunk_reviewed(&self, file_idx: usize, hunk_idx: usize) -> bool {
+        let Some(file) = self.diff_files.get(file_idx) else {
+            return false;
+        };
+        file.hunks.get(hunk_idx).is_some_and(|hunk| {
+            self.session
+                .files
+                .get(file.display_path())
+                .is_some_and(|review| review.is_hunk_reviewed(hunk))
+        })
+    }
+
+    /// Toggle the reviewed mark of the hunk whose header is under the cursor.
+    /// The file is marked reviewed once all of its hunks are, and unmarked
+    /// again when one of them is reopened.
+    pub fn toggle_hunk_reviewed_at_cursor(&mut self) {
+        let Some(&AnnotatedLine::HunkHeader { file_idx, hunk_idx }) =
+            self.line_annotations.get(self.diff_state.cursor_line)
+        else {
+            self.set_message("Move cursor to a hunk header to mark it reviewed");
+            return;
+        };
+        let file = &self.diff_files[file_idx];
+        let path = file.display_path().clone();
+        let key = HunkKey::of(&file.hunks[hunk_idx]);
+        let Some(review) = self.session.files.get(&path) else {
+            return;
+        };
+        let reviewed = !review.reviewed_hunks.contains(&key);
+        let file_reviewed = file.hunks.iter().all(|hunk| {
+            let other = HunkKey::of(hunk);
+            if other == key {
+                reviewed
+            } else {
+                review.reviewed_hunks.contains(&other)
+            }
+        });
+        let action = UndoAction::SetHunkReviewed {
+            path: path.clone(),
+            hunk: key,
+            reviewed,
+            file_reviewed,
+        };
+        let Some(inverse) = action.apply(&mut self.session) else {
+            return;
+        };
+        let description = if reviewed {
+            format!("mark hunk in {} reviewed", path.display())
+        } else {
+            format!("mark hunk in {} unreviewed", path.display())
+        };
+        self.undo_history.record(description, inverse);
+        self.dirty = true;
+        self.rebuild_file_annotations(file_idx);
+
+        if file_reviewed {
+            self.diff_state.cursor_line = self.calculate_file_scroll_offset(file_idx);
+            self.set_message(format!(
+                "All hunks reviewed, {} marked reviewed",
+                path.display()
+            ));
+        } else if reviewed {
+            self.set_message("Hunk reviewed");
+        } else {
+            self.set_message("Hunk unreviewed");
+        }
+        self.ensure_cursor_visible();
     }
 
-    /// Returns `(total_files, total_additions, total_deletions)` across all diff files.
-    pub fn diff_stat(&self) -> (usize, usize, usize) {
-        let mut additions = 0;
-        let mut deletions = 0;
+    /// Reviewed and total hunks across the diff. Hunks of reviewed files
+    /// count as reviewed.
+    pub fn hunk_progress(&self) -> (usize, usize) {
+        let mut reviewed = 0;
+        let mut total = 0;
         for file in &self.diff_files {
-            let (a, d) = file.stat();
-            additions += a;
-            deletions += d;
+            let review = self.session.files.get(file.display_path());
+            total += file.hunks.len();
+            reviewed += match review {
+                Some(review) if review.reviewed => file.hunks.len(),
+                Some(review) => file
+                    .hunks
+                    .iter()
+                    .filter(|hunk| review.is_hunk_reviewed(hunk))
+                    .count(),
+                None => 0,
+            };
         }
-        (self.diff_files.len(), additions, deletions)
-    }
-
-    /// Returns true when the cursor is in the review comments area above all files.
-    pub fn is_cursor_in_overview(&self) -> bool {
-        self.diff_state.cursor_line < self.review_comments_render_height()
+        (reviewed, total)
     }
 
-    pub fn set_message(&mut self, msg: impl Into<String>) {
-        self.message = Some(Message {
-            content: msg.into(),
-            message_type: MessageType::Info,
-        });
+    /// Whether only the header of `file` is shown: it is reviewed or folded,
+    /// or it is generated and hasn't been expanded.
+    pub fn is_file_collapsed(&self, file: &DiffFile) -> bool {
+        let path = file.display_path();
+        self.session.is_file_reviewed(path)
+            || self.folded_files.contains(path)
+            || (file.is_generated && !self.expanded_generated_files.contains(path))
+    }
+
+    /// Show or hide the body of a generated file.
+    pub fn toggle_generated_file(&mut self, file_idx: usize) {
+        let Some(path) = self
+            .diff_files
+            .get(file_idx)
+            .map(|file| file.display_path().clone())
+        else {
+            return;
+        };
+        if !self.expanded_generated_files.remove(&path) {
+            self.expanded_generated_files.insert(path);
+        }
+        self.rebuild_file_annotations(file_idx);
+    }
+
+    /// Fold a file to its header or unfold it. Generated files toggle their
+    /// expansion instead, since they start out collapsed.
+    pub fn toggle_file_fold(&mut self, file_idx: usize) {
+        let Some(file) = self.diff_files.get(file_idx) else {
+            return;
+        };
+        if file.is_generated {
+            self.toggle_generated_file(file_idx);
+            return;
+        }
+        let path = file.display_path().clone();
+        if !self.folded_files.remove(&path) {
+            self.folded_files.insert(path);
+        }
+        self.rebuild_file_annotations(file_idx);
+    }
+
+    /// Record a left click at `column`, `row` and report whether it completes
+    /// a double-click on the same cell.
+    pub fn register_left_click(&mut self, now: Instant, column: u16, row: u16) -> bool {
+        let double = self.last_left_click.is_some_and(|(at, c, r)| {
+            (c, r) == (column, row) && now.saturating_duration_since(at) <= DOUBLE_CLICK_WINDOW
+        });
+        // A third click starts a new pair rather than toggling again
+        self.last_left_click = if double {
+            None
+        } else {
+            Some((now, column, row))
+        };
+        double
+    }
+
+    pub fn file_count(&self) -> usize {
+        self.diff_files.len()
+    }
+
+    pub fn reviewed_count(&self) -> usize {
+        self.session.reviewed_count()
+    }
+
+    /// Returns `(total_files, total_additions, total_deletions)` across all diff files.
+    pub fn diff_stat(&self) -> (usize, usize, usize) {
+        let mut additions = 0;
+        let mut deletions = 0;
+        for file in &self.diff_files {
+            let (a, d) = file.stat();
+            additions += a;
+            deletions += d;
+        }
+        (self.diff_files.len(), additions, deletions)
+    }
+
+    /// File, line and per-status totals for `:stats`.
+    pub fn diff_summary(&self) -> DiffSummary {
+        let (files, additions, deletions) = self.diff_stat();
+        let count = |status: FileStatus| {
+            self.diff_files
+                .iter()
+                .filter(|file| file.status == status)
+                .count()
+        };
+        let mut by_status: Vec<(FileStatus, usize)> = [
+            FileStatus::Added,
+            FileStatus::Modified,
+            FileStatus::Deleted,
+            FileStatus::Renamed,
+        ]
+        .into_iter()
+        .map(|status| (status, count(status)))
+        .collect();
+        let copied = count(FileStatus::Copied);
+        if copied > 0 {
+            by_status.push((FileStatus::Copied, copied));
+        }
+        let (reviewed_hunks, hunks) = self.hunk_progress();
+        DiffSummary {
+            files,
+            additions,
+            deletions,
+            by_status,
+            hunks,
+            reviewed_hunks,
+        }
+    }
+
+    pub fn show_stats(&mut self) {
+        self.stats_popup = Some(self.diff_summary());
+    }
+
+    /// Returns true when the cursor is in the review comments area above all files.
+    pub fn is_cursor_in_overview(&self) -> bool {
+        self.diff_state.cursor_line < self.review_comments_render_height()
+    }
+
+    pub fn set_message(&mut self, msg: impl Into<String>) {
+        self.message = Some(Message {
+            content: msg.into(),
+            message_type: MessageType::Info,
+        });
     }
 
     pub fn set_warning(&mut self, msg: impl Into<String>) {
@@ -1727,6 +2602,58 @@ impl App {
         });
     }
 
+    /// Save the session if it has unsaved changes and autosave is enabled.
+    pub fn autosave(&mut self) -> Result<()> {
+        self.last_autosave_attempt = Instant::now();
+        if !self.dirty || self.autosave_interval.is_none() {
+            return Ok(());
+        }
+
+        save_session(&self.session)?;
+        self.dirty = false;
+        self.last_autosaved_at = Some(Instant::now());
+        Ok(())
+    }
+
+    /// Autosave once the configured interval has elapsed since the last attempt.
+    /// Failures are reported in the status bar and retried on the next interval.
+    pub fn autosave_if_due(&mut self) {
+        if let Some(interval) = self.autosave_interval
+            && self.last_autosave_attempt.elapsed() >= interval
+            && let Err(e) = self.autosave()
+        {
+            self.set_error(format!("Autosave failed: {e}"));
+        }
+    }
+
+    /// Autosave before exiting, returning whether to go ahead. A failed save
+    /// cancels the first quit so unsaved changes aren't lost silently;
+    /// quitting again discards them. `:q!` skips the save.
+    pub fn finish_quit(&mut self) -> bool {
+        if self.discard_on_quit {
+            return true;
+        }
+        match self.autosave() {
+            Ok(()) => true,
+            Err(_) if self.quit_warned => true,
+            Err(e) => {
+                self.should_quit = false;
+                self.quit_warned = true;
+                self.set_error(format!(
+                    "Autosave failed: {e}. Unsaved changes, quit again to discard them"
+                ));
+                false
+            }
+        }
+    }
+
+    pub fn show_saved_indicator(&self) -> bool {
+        !self.dirty
+            && self
+                .last_autosaved_at
+                .is_some_and(|at| at.elapsed() < SAVED_INDICATOR_DURATION)
+    }
+
     pub fn cursor_down(&mut self, lines: usize) {
         let max_line = self.total_lines().saturating_sub(1);
         self.diff_state.cursor_line = (self.diff_state.cursor_line + lines).min(max_line);
@@ -1801,6 +2728,46 @@ impl App {
             (self.diff_state.scroll_x.saturating_add(cols)).min(max_scroll_x);
     }
 
+    pub fn toggle_show_ignored(&mut self) {
+        self.show_ignored = !self.show_ignored;
+        if !self.show_ignored {
+            self.ignored_files.clear();
+            self.set_message("Ignored files: hidden");
+            return;
+        }
+        self.refresh_ignored_files();
+        self.set_message(format!("Ignored files: {}", self.ignored_files.len()));
+    }
+
+    /// Reload the diff of the current source without ignore rules and keep
+    /// the paths those rules drop.
+    fn refresh_ignored_files(&mut self) {
+        let vcs = self.vcs.as_ref();
+        let diff_files = match &self.diff_source {
+            DiffSource::WorkingTree | DiffSource::StagedAndUnstaged => vcs.get_working_tree_diff(),
+            DiffSource::Staged => vcs.get_staged_diff(),
+            DiffSource::Unstaged => vcs
+                .get_unstaged_diff()
+                .or_else(|_| vcs.get_working_tree_diff()),
+            DiffSource::CommitRange(ids) => vcs.get_commit_range_diff(ids),
+            DiffSource::WorkingTreeAndCommits(ids) | DiffSource::StagedUnstagedAndCommits(ids) => {
+                vcs.get_working_tree_with_commits_diff(ids)
+            }
+            DiffSource::PullRequest { base_ref, .. } => vcs
+                .get_pull_request_diff(Some(base_ref))
+                .map(|pr_diff| pr_diff.files),
+        };
+
+        self.ignored_files = match diff_files {
+            Ok(diff_files) => {
+                let diff_files = Self::filter_by_paths(diff_files, &self.path_filter);
+                crate::tuicrignore::ignored_paths(&self.vcs_info.root_path, &diff_files)
+            }
+            Err(_) => Vec::new(),
+        };
+        self.ignored_files.sort();
+    }
+
     pub fn toggle_diff_wrap(&mut self) {
         let enabled = !self.diff_state.wrap_lines;
         self.set_diff_wrap(enabled);
@@ -1819,14 +2786,172 @@ impl App {
         self.set_message(format!("Diff wrapping: {status}"));
     }
 
-    fn ensure_cursor_visible(&mut self) {
-        // Use visible_line_count which is computed during render based on actual line widths.
-        // Fall back to viewport_height if not yet set (before first render).
-        let visible_lines = if self.diff_state.visible_line_count > 0 {
+    pub fn toggle_ignore_whitespace(&mut self) {
+        self.set_ignore_whitespace(!self.ignore_whitespace);
+    }
+
+    /// Attribute comments created from now on to `name`; existing comments
+    /// keep their author. `None` shows the current author instead.
+    pub fn set_comment_author(&mut self, name: Option<&str>) {
+        match name.map(str::trim).filter(|name| !name.is_empty()) {
+            Some(name) => {
+                self.comment_author = Some(name.to_string());
+                self.set_message(format!("New comments are attributed to {name}"));
+            }
+            None => match &self.comment_author {
+                Some(author) => {
+                    let message = format!("New comments are attributed to {author}");
+                    self.set_message(message);
+                }
+                None => self.set_message("No comment author set; use :set-author <name>"),
+            },
+        }
+    }
+
+    /// Restrict the file list and file navigation to paths matching `pattern`,
+    /// jumping to the first match. `None` clears the filter.
+    pub fn set_file_filter(&mut self, pattern: Option<&str>) {
+        let Some(pattern) = pattern else {
+            if self.file_filter.take().is_some() {
+                self.set_message("Filter cleared");
+            }
+            self.restore_file_list_selection();
+            return;
+        };
+        let filter = match FileFilter::new(pattern) {
+            Ok(filter) => filter,
+            Err(e) => {
+                self.set_error(format!("Invalid glob: {e}"));
+                return;
+            }
+        };
+        let matches: Vec<usize> = self
+            .diff_files
+            .iter()
+            .enumerate()
+            .filter(|(_, file)| filter.matches(file.display_path()))
+            .map(|(idx, _)| idx)
+            .collect();
+        let Some(&first) = matches.first() else {
+            self.set_warning(format!("No files match {pattern}"));
+            return;
+        };
+
+        self.file_filter = Some(filter);
+        self.jump_to_file(first);
+        let noun = if matches.len() == 1 { "file" } else { "files" };
+        self.set_message(format!("Filter {pattern}: {} {noun}", matches.len()));
+    }
+
+    /// Narrow the diff to the current file, or restore the full diff when
+    /// `:only` is already in effect. Comments on hidden files stay in the session.
+    pub fn toggle_only_current_file(&mut self) {
+        if self.only_saved_path_filter.is_some() {
+            self.show_all_files();
+            return;
+        }
+        let Some(path) = self.current_file_path().cloned() else {
+            self.set_warning("No file to show");
+            return;
+        };
+        let full_filter = std::mem::replace(
+            &mut self.path_filter,
+            vec![path.to_string_lossy().into_owned()],
+        );
+        match self.reload_diff_files() {
+            Ok(_) => {
+                self.only_saved_path_filter = Some(full_filter);
+                self.set_message(format!("Showing only {}", path.display()));
+            }
+            Err(e) => {
+                self.path_filter = full_filter;
+                self.set_error(format!("Failed to narrow diff: {e}"));
+            }
+        }
+    }
+
+    /// Undo `:only`, keeping the focused file selected.
+    pub fn show_all_files(&mut self) {
+        let Some(full_filter) = self.only_saved_path_filter.take() else {
+            self.set_message("Already showing all files");
+            return;
+        };
+        let only_filter = std::mem::replace(&mut self.path_filter, full_filter);
+        match self.reload_diff_files() {
+            Ok((count, _)) => {
+                let noun = if count == 1 { "file" } else { "files" };
+                self.set_message(format!("Showing all {count} {noun}"));
+            }
+            Err(e) => {
+                self.only_saved_path_filter =
+                    Some(std::mem::replace(&mut self.path_filter, only_filter));
+                self.set_error(format!("Failed to reload diff: {e}"));
+            }
+        }
+    }
+
+    /// Reselect the current file in the file list after the visible items changed.
+    fn restore_file_list_selection(&mut self) {
+        if let Some(tree_idx) = self.file_idx_to_tree_idx(self.diff_state.current_file_idx) {
+            self.file_list_state.select(tree_idx);
+        }
+    }
+
+    pub fn set_ignore_whitespace(&mut self, ignore: bool) {
+        if ignore == self.ignore_whitespace {
+            return;
+        }
+        if !self.vcs.set_ignore_whitespace(ignore) {
+            self.set_warning("Ignoring whitespace not supported for this VCS");
+            return;
+        }
+
+        let cursor = self
+            .get_line_at_cursor()
+            .filter(|(_, side)| *side == LineSide::New)
+            .map(|(line, _)| line);
+        self.ignore_whitespace = ignore;
+        if let Err(e) = self.reload_diff_files() {
+            self.ignore_whitespace = !ignore;
+            self.vcs.set_ignore_whitespace(!ignore);
+            match e {
+                TuicrError::NoChanges => self.set_warning("All changes are whitespace-only"),
+                e => self.set_error(format!("Failed to reload diff: {e}")),
+            }
+            return;
+        }
+
+        if let Some(line) = cursor
+            && let FindSourceLineResult::Exact(idx) | FindSourceLineResult::Nearest(idx) =
+                find_source_line(
+                    &self.line_annotations,
+                    self.diff_state.current_file_idx,
+                    line,
+                )
+        {
+            self.diff_state.cursor_line = idx;
+            self.ensure_cursor_visible();
+        }
+        self.set_message(if ignore {
+            "Ignoring whitespace changes"
+        } else {
+            "Showing whitespace changes"
+        });
+    }
+
+    /// Logical lines that fit in the diff viewport. Uses visible_line_count, computed
+    /// during render from actual line widths, falling back to viewport_height before
+    /// the first render.
+    fn visible_lines(&self) -> usize {
+        if self.diff_state.visible_line_count > 0 {
             self.diff_state.visible_line_count
         } else {
             self.diff_state.viewport_height.max(1)
-        };
+        }
+    }
+
+    fn ensure_cursor_visible(&mut self) {
+        let visible_lines = self.visible_lines();
         let max_scroll = self.max_scroll_offset();
         if self.diff_state.cursor_line < self.diff_state.scroll_offset {
             self.diff_state.scroll_offset = self.diff_state.cursor_line;
@@ -1966,12 +3091,15 @@ impl App {
                 let top_len = self.expanded_top.get(gap_id).map_or(0, |v| v.len());
                 let bot_len = self.expanded_bottom.get(gap_id).map_or(0, |v| v.len());
                 let remaining = (gap as usize).saturating_sub(top_len + bot_len);
-                let count = remaining.min(GAP_EXPAND_BATCH);
+                let count = remaining.min(self.context_expand_lines);
                 Some(format!("... {arrow} expand ({count} lines) ..."))
             }
             AnnotatedLine::HiddenLines { count, .. } => {
                 Some(format!("... {count} lines hidden ..."))
             }
+            AnnotatedLine::HiddenContext { count, .. } => {
+                Some(format!("┄┄┄ {count} unchanged lines ┄┄┄"))
+            }
             AnnotatedLine::ExpandedContext {
                 gap_id,
                 line_idx: context_idx,
@@ -2000,11 +3128,17 @@ impl App {
                 if file.is_too_large {
                     Some("(file too large to display)".to_string())
                 } else if file.is_binary {
-                    Some("(binary file)".to_string())
+                    Some(file.binary_descriptor())
                 } else {
                     Some("(no changes)".to_string())
                 }
             }
+            AnnotatedLine::FileMetadata { file_idx } => {
+                self.diff_files.get(*file_idx)?.metadata_descriptor()
+            }
+            AnnotatedLine::TruncatedFile { file_idx } => {
+                Some(self.diff_files.get(*file_idx)?.truncated_descriptor())
+            }
             AnnotatedLine::SideBySideLine {
                 file_idx,
                 hunk_idx,
@@ -2025,10 +3159,49 @@ impl App {
                     .unwrap_or("");
                 Some(format!("{} {}", del_content, add_content))
             }
-            AnnotatedLine::Spacing => None,
+            AnnotatedLine::ImagePreview { .. } | AnnotatedLine::Spacing => None,
         }
     }
 
+    /// Characters in the longest content of one side-by-side pane among the
+    /// annotations in `range`. Both sides of a changed row are measured apart.
+    pub fn widest_pane_content(&self, range: std::ops::Range<usize>) -> usize {
+        let hunk_line = |file_idx: usize, hunk_idx: usize, line_idx: Option<usize>| {
+            let hunk = self.diff_files.get(file_idx)?.hunks.get(hunk_idx)?;
+            hunk.lines.get(line_idx?)
+        };
+        let width = |line: Option<&DiffLine>| line.map_or(0, |line| line.content.chars().count());
+        self.line_annotations
+            .iter()
+            .skip(range.start)
+            .take(range.len())
+            .map(|annotation| match annotation {
+                AnnotatedLine::DiffLine {
+                    file_idx,
+                    hunk_idx,
+                    line_idx,
+                    ..
+                } => width(hunk_line(*file_idx, *hunk_idx, Some(*line_idx))),
+                AnnotatedLine::SideBySideLine {
+                    file_idx,
+                    hunk_idx,
+                    del_line_idx,
+                    add_line_idx,
+                    ..
+                } => width(hunk_line(*file_idx, *hunk_idx, *del_line_idx)).max(width(hunk_line(
+                    *file_idx,
+                    *hunk_idx,
+                    *add_line_idx,
+                ))),
+                AnnotatedLine::ExpandedContext { gap_id, line_idx } => {
+                    width(self.get_expanded_line(gap_id, *line_idx))
+                }
+                _ => 0,
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
     fn gap_size(&self, gap_id: &GapId) -> Option<u32> {
         let file = self.diff_files.get(gap_id.file_idx)?;
         let hunk = file.hunks.get(gap_id.hunk_idx)?;
@@ -2054,6 +3227,19 @@ impl App {
             .min(max_scroll);
     }
 
+    /// Scroll so the cursor line is the first one on screen (`zt`).
+    pub fn scroll_cursor_to_top(&mut self) {
+        self.diff_state.scroll_offset = self.diff_state.cursor_line.min(self.max_scroll_offset());
+    }
+
+    /// Scroll so the cursor line is the last one on screen (`zb`).
+    pub fn scroll_cursor_to_bottom(&mut self) {
+        let visible_lines = self.visible_lines();
+        self.diff_state.scroll_offset = (self.diff_state.cursor_line + 1)
+            .saturating_sub(visible_lines)
+            .min(self.max_scroll_offset());
+    }
+
     pub fn go_to_source_line(&mut self, target_lineno: u32) {
         let current_file = self.diff_state.current_file_idx;
         let result = find_source_line(&self.line_annotations, current_file, target_lineno);
@@ -2117,6 +3303,23 @@ impl App {
         }
     }
 
+    /// Scroll the file list viewport down by `lines` while the diff is
+    /// focused. Neither the selection nor `current_file_idx` moves, so the
+    /// selected file may scroll out of view until the current file changes.
+    pub fn scroll_file_list_down(&mut self, lines: usize) {
+        let total = self.build_visible_items().len();
+        let viewport = self.file_list_state.viewport_height.max(1);
+        let max_offset = total.saturating_sub(viewport);
+        let offset = self.file_list_state.list_state.offset_mut();
+        *offset = (*offset + lines).min(max_offset);
+    }
+
+    /// Scroll the file list viewport up by `lines`; see [`App::scroll_file_list_down`].
+    pub fn scroll_file_list_up(&mut self, lines: usize) {
+        let offset = self.file_list_state.list_state.offset_mut();
+        *offset = offset.saturating_sub(lines);
+    }
+
     pub fn diff_annotation_at_screen_row(&self, screen_row: u16) -> Option<usize> {
         let inner = self.diff_inner_area?;
         if screen_row < inner.y || screen_row >= inner.y + inner.height {
@@ -2155,6 +3358,21 @@ impl App {
         }
     }
 
+    /// Move the cursor to a clicked diff row. Clicking a file header also
+    /// selects the file in the file list, and double-clicking folds it.
+    pub fn click_diff_annotation(&mut self, idx: usize, double_click: bool) {
+        self.move_cursor_to_annotation(idx);
+        let Some(&AnnotatedLine::FileHeader { file_idx }) = self.line_annotations.get(idx) else {
+            return;
+        };
+        if let Some(tree_idx) = self.file_idx_to_tree_idx(file_idx) {
+            self.file_list_state.select(tree_idx);
+        }
+        if double_click {
+            self.toggle_file_fold(file_idx);
+        }
+    }
+
     pub fn jump_to_file(&mut self, idx: usize) {
         use std::path::Path;
 
@@ -2180,36 +3398,400 @@ impl App {
         }
     }
 
-    pub fn jump_to_bottom(&mut self) {
-        let max_line = self.total_lines().saturating_sub(1);
-        self.diff_state.cursor_line = max_line;
-        // Always position so the last line is at the bottom of the viewport
-        let viewport = self.diff_state.viewport_height.max(1);
-        self.diff_state.scroll_offset = self.total_lines().saturating_sub(viewport);
+    /// Jump to the next (or previous) file with merge conflicts, wrapping
+    /// around. Returns false when no file is conflicted.
+    pub fn jump_to_conflicted_file(&mut self, forward: bool) -> bool {
+        let count = self.diff_files.len();
+        let current = self.diff_state.current_file_idx;
+        let target = (1..=count)
+            .map(|step| {
+                if forward {
+                    (current + step) % count
+                } else {
+                    (current + count - step % count) % count
+                }
+            })
+            .find(|&idx| self.diff_files[idx].has_unresolved_conflicts());
+
+        match target {
+            Some(idx) => {
+                self.jump_to_file(idx);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Jump from a line of a moved block to the first line of the other side
+    /// of the move.
+    pub fn jump_to_moved_counterpart(&mut self) {
+        if !self.detect_moves {
+            self.set_warning("Moved-block detection is disabled");
+            return;
+        }
+        let candidates = match self.line_annotations.get(self.diff_state.cursor_line) {
+            Some(AnnotatedLine::DiffLine {
+                file_idx,
+                hunk_idx,
+                line_idx,
+                ..
+            }) => vec![(*file_idx, *hunk_idx, *line_idx)],
+            Some(AnnotatedLine::SideBySideLine {
+                file_idx,
+                hunk_idx,
+                del_line_idx,
+                add_line_idx,
+                ..
+            }) => [*del_line_idx, *add_line_idx]
+                .into_iter()
+                .flatten()
+                .map(|line_idx| (*file_idx, *hunk_idx, line_idx))
+                .collect(),
+            _ => Vec::new(),
+        };
+        let Some((move_id, origin)) = candidates.into_iter().find_map(|(f, h, l)| {
+            let line = &self.diff_files[f].hunks[h].lines[l];
+            line.move_id.map(|id| (id, line.origin))
+        }) else {
+            self.set_warning("Not on a moved line");
+            return;
+        };
+
+        let target = self.diff_files.iter().enumerate().find_map(|(f, file)| {
+            file.hunks.iter().enumerate().find_map(|(h, hunk)| {
+                hunk.lines
+                    .iter()
+                    .position(|line| line.move_id == Some(move_id) && line.origin != origin)
+                    .map(|l| (f, h, l))
+            })
+        });
+        let Some((file_idx, hunk_idx, line_idx)) = target else {
+            return;
+        };
+        let position = self
+            .line_annotations
+            .iter()
+            .position(|annotation| match annotation {
+                AnnotatedLine::DiffLine {
+                    file_idx: f,
+                    hunk_idx: h,
+                    line_idx: l,
+                    ..
+                } => (*f, *h, *l) == (file_idx, hunk_idx, line_idx),
+                AnnotatedLine::SideBySideLine {
+                    file_idx: f,
+                    hunk_idx: h,
+                    del_line_idx,
+                    add_line_idx,
+                    ..
+                } => {
+                    (*f, *h) == (file_idx, hunk_idx)
+                        && (*del_line_idx == Some(line_idx) || *add_line_idx == Some(line_idx))
+                }
+                _ => false,
+            });
+        let Some(position) = position else {
+            self.set_warning("The other side of the move is collapsed");
+            return;
+        };
+
+        self.diff_state.cursor_line = position;
+        self.ensure_cursor_visible();
+        self.center_cursor();
         self.update_current_file_from_cursor();
+        let line = &self.diff_files[file_idx].hunks[hunk_idx].lines[line_idx];
+        let (direction, lineno) = match line.origin {
+            LineOrigin::Deletion => ("Moved from", line.old_lineno),
+            _ => ("Moved to", line.new_lineno),
+        };
+        let path = self.diff_files[file_idx].display_path().display();
+        self.set_message(match lineno {
+            Some(lineno) => format!("{direction} {path}:{lineno}"),
+            None => format!("{direction} {path}"),
+        });
     }
 
-    pub fn next_file(&mut self) {
-        let visible_items = self.build_visible_items();
-        let current_file_idx = self.diff_state.current_file_idx;
+    /// Remember the cursor line under `mark` (`m{letter}`).
+    pub fn set_mark(&mut self, mark: char) {
+        self.marks.insert(mark, self.diff_state.cursor_line);
+        self.set_message(format!("Mark '{mark}' set"));
+    }
 
-        for item in &visible_items {
-            if let FileTreeItem::File { file_idx, .. } = item
-                && *file_idx > current_file_idx
-            {
-                self.jump_to_file(*file_idx);
-                return;
+    /// Move the cursor to the line saved under `mark` and center it.
+    pub fn jump_to_mark(&mut self, mark: char) {
+        let Some(&line) = self.marks.get(&mark) else {
+            self.set_warning(format!("Mark '{mark}' not set"));
+            return;
+        };
+        self.diff_state.cursor_line = line.min(self.total_lines().saturating_sub(1));
+        self.center_cursor();
+        self.update_current_file_from_cursor();
+    }
+
+    /// Every file and line comment in the session, sorted by path and line
+    /// with file comments first.
+    pub fn comment_list_entries(&self) -> Vec<CommentListEntry> {
+        let mut entries = Vec::new();
+        for review in self.session.files.values() {
+            for comment in &review.file_comments {
+                entries.push(CommentListEntry {
+                    path: review.path.clone(),
+                    line: None,
+                    comment: comment.clone(),
+                });
+            }
+            for (line, comments) in &review.line_comments {
+                for comment in comments {
+                    entries.push(CommentListEntry {
+                        path: review.path.clone(),
+                        line: Some((*line, comment.side.unwrap_or(LineSide::New))),
+                        comment: comment.clone(),
+                    });
+                }
             }
         }
+        // Stable, so comments on one line keep their thread order
+        entries.sort_by(|a, b| {
+            (&a.path, a.line.map(|(line, _)| line)).cmp(&(&b.path, b.line.map(|(line, _)| line)))
+        });
+        entries
     }
 
-    pub fn prev_file(&mut self) {
-        let visible_items = self.build_visible_items();
-        let current_file_idx = self.diff_state.current_file_idx;
+    pub fn open_comment_list(&mut self) {
+        self.show_comment_list = true;
+        self.focused_panel = FocusedPanel::CommentList;
+        if self.comment_list_state.selected().is_none() {
+            self.comment_list_state.select(Some(0));
+        }
+    }
 
-        for item in visible_items.iter().rev() {
+    pub fn close_comment_list(&mut self) {
+        self.show_comment_list = false;
+        if self.focused_panel == FocusedPanel::CommentList {
+            self.focused_panel = FocusedPanel::Diff;
+        }
+    }
+
+    /// The comment under the `:comments` list selection.
+    pub fn selected_comment_list_entry(&self) -> Option<CommentListEntry> {
+        let selected = self.comment_list_state.selected()?;
+        self.comment_list_entries().into_iter().nth(selected)
+    }
+
+    pub fn comment_list_down(&mut self, n: usize) {
+        let last = self.comment_list_entries().len().saturating_sub(1);
+        let selected = self.comment_list_state.selected().unwrap_or(0);
+        self.comment_list_state
+            .select(Some((selected + n).min(last)));
+    }
+
+    pub fn comment_list_up(&mut self, n: usize) {
+        let selected = self.comment_list_state.selected().unwrap_or(0);
+        self.comment_list_state
+            .select(Some(selected.saturating_sub(n)));
+    }
+
+    /// Move the diff cursor to the line the selected comment is on and focus
+    /// the diff, keeping the list open.
+    pub fn jump_to_listed_comment(&mut self) {
+        let Some(entry) = self.selected_comment_list_entry() else {
+            self.set_warning("No comments");
+            return;
+        };
+        if let Some(row) = self
+            .comment_target_rows()
+            .into_iter()
+            .find_map(|(row, target)| {
+                self.comment_at_annotation(row)
+                    .is_some_and(|comment| comment.id == entry.comment.id)
+                    .then_some(target)
+            })
+        {
+            self.diff_state.cursor_line = row;
+            self.center_cursor();
+            self.update_current_file_from_cursor();
+        } else if let Some(file_idx) = self
+            .diff_files
+            .iter()
+            .position(|file| file.display_path() == &entry.path)
+        {
+            // Folded and collapsed files don't draw their comments
+            self.jump_to_file(file_idx);
+            self.set_message(format!("Comment is hidden in {}", entry.path.display()));
+        } else {
+            self.set_warning(format!("{} is not in this diff", entry.path.display()));
+            return;
+        }
+        self.focused_panel = FocusedPanel::Diff;
+    }
+
+    /// Ask before deleting the comment selected in the `:comments` list.
+    pub fn request_delete_listed_comment(&mut self) {
+        if self.selected_comment_list_entry().is_some() {
+            self.enter_confirm_mode(ConfirmAction::DeleteListedComment);
+        } else {
+            self.set_warning("No comments");
+        }
+    }
+
+    pub fn delete_listed_comment(&mut self) {
+        let Some(entry) = self.selected_comment_list_entry() else {
+            return;
+        };
+        let Some(review) = self.session.files.get(&entry.path) else {
+            return;
+        };
+        let location = match entry.line {
+            None => review
+                .file_comments
+                .iter()
+                .position(|comment| comment.id == entry.comment.id)
+                .map(|index| CommentLocation::File {
+                    path: entry.path.clone(),
+                    index,
+                }),
+            Some((line, side)) => review.line_comments.get(&line).and_then(|comments| {
+                comments
+                    .iter()
+                    .filter(|comment| comment.side.unwrap_or(LineSide::New) == side)
+                    .position(|comment| comment.id == entry.comment.id)
+                    .map(|index| CommentLocation::Line {
+                        path: entry.path.clone(),
+                        line,
+                        side,
+                        index,
+                    })
+            }),
+        };
+        self.delete_comment_at(location);
+    }
+
+    /// Move the cursor to the line of the next or previous file or line
+    /// comment (`]q` / `[q`), wrapping around. Returns false if no comment
+    /// is drawn in the diff.
+    pub fn jump_to_comment(&mut self, forward: bool) -> bool {
+        let mut targets: Vec<usize> = self
+            .comment_target_rows()
+            .into_iter()
+            .map(|(_, target)| target)
+            .collect();
+        targets.dedup();
+        let cursor = self.diff_state.cursor_line;
+        let target = if forward {
+            targets
+                .iter()
+                .find(|&&row| row > cursor)
+                .or(targets.first())
+        } else {
+            targets
+                .iter()
+                .rev()
+                .find(|&&row| row < cursor)
+                .or(targets.last())
+        };
+        let Some(&target) = target else {
+            return false;
+        };
+        self.diff_state.cursor_line = target;
+        self.center_cursor();
+        self.update_current_file_from_cursor();
+        true
+    }
+
+    /// Move the cursor to the next or previous row `motion` targets and
+    /// center it. Unlike `]q`, these stop at the last target.
+    pub fn jump_bracket_motion(&mut self, motion: BracketMotion, forward: bool) {
+        let targets: Vec<usize> = match motion {
+            BracketMotion::Comment => self
+                .comment_target_rows()
+                .into_iter()
+                .map(|(row, _)| row)
+                .collect(),
+            BracketMotion::File | BracketMotion::UnreviewedFile => self
+                .line_annotations
+                .iter()
+                .enumerate()
+                .filter_map(|(idx, annotation)| match annotation {
+                    AnnotatedLine::FileHeader { file_idx }
+                        if motion == BracketMotion::File
+                            || self
+                                .diff_files
+                                .get(*file_idx)
+                                .is_some_and(|file| !self.is_file_collapsed(file)) =>
+                    {
+                        Some(idx)
+                    }
+                    _ => None,
+                })
+                .collect(),
+        };
+        let cursor = self.diff_state.cursor_line;
+        let target = if forward {
+            targets.into_iter().find(|&row| row > cursor)
+        } else {
+            targets.into_iter().rev().find(|&row| row < cursor)
+        };
+        let Some(target) = target else {
+            let noun = match motion {
+                BracketMotion::Comment => "comments",
+                BracketMotion::File => "files",
+                BracketMotion::UnreviewedFile => "unreviewed files",
+            };
+            let direction = if forward { "below" } else { "above" };
+            self.set_message(format!("No more {noun} {direction}"));
+            return;
+        };
+        self.diff_state.cursor_line = target;
+        self.center_cursor();
+        self.update_current_file_from_cursor();
+    }
+
+    /// `(first row, annotated row)` of every file and line comment drawn in
+    /// the diff. A line comment annotates the diff line above it, a file
+    /// comment its file header.
+    fn comment_target_rows(&self) -> Vec<(usize, usize)> {
+        let mut rows = Vec::new();
+        let mut annotated = 0;
+        for (idx, annotation) in self.line_annotations.iter().enumerate() {
+            match annotation {
+                AnnotatedLine::FileHeader { .. } => annotated = idx,
+                AnnotatedLine::DiffLine { .. }
+                | AnnotatedLine::SideBySideLine { .. }
+                | AnnotatedLine::ExpandedContext { .. } => annotated = idx,
+                AnnotatedLine::FileComment { .. } | AnnotatedLine::LineComment { .. }
+                    if idx == 0 || self.line_annotations[idx - 1] != *annotation =>
+                {
+                    rows.push((idx, annotated));
+                }
+                _ => {}
+            }
+        }
+        rows
+    }
+
+    pub fn jump_to_top(&mut self) {
+        self.diff_state.cursor_line = 0;
+        self.diff_state.scroll_offset = 0;
+        self.update_current_file_from_cursor();
+    }
+
+    pub fn jump_to_bottom(&mut self) {
+        self.diff_state.cursor_line = self.total_lines().saturating_sub(1);
+        self.ensure_cursor_visible();
+        self.update_current_file_from_cursor();
+    }
+
+    pub fn next_file(&mut self) {
+        if self.all_files_reviewed_while_skipping() {
+            return;
+        }
+        let visible_items = self.build_visible_items();
+        let current_file_idx = self.diff_state.current_file_idx;
+
+        for item in &visible_items {
             if let FileTreeItem::File { file_idx, .. } = item
-                && *file_idx < current_file_idx
+                && *file_idx > current_file_idx
+                && !self.skip_file_in_navigation(*file_idx)
             {
                 self.jump_to_file(*file_idx);
                 return;
@@ -2217,119 +3799,156 @@ impl App {
         }
     }
 
-    fn file_idx_to_tree_idx(&self, target_file_idx: usize) -> Option<usize> {
+    pub fn prev_file(&mut self) {
+        if self.all_files_reviewed_while_skipping() {
+            return;
+        }
         let visible_items = self.build_visible_items();
-        for (tree_idx, item) in visible_items.iter().enumerate() {
+        let current_file_idx = self.diff_state.current_file_idx;
+
+        for item in visible_items.iter().rev() {
             if let FileTreeItem::File { file_idx, .. } = item
-                && *file_idx == target_file_idx
+                && *file_idx < current_file_idx
+                && !self.skip_file_in_navigation(*file_idx)
             {
-                return Some(tree_idx);
+                self.jump_to_file(*file_idx);
+                return;
             }
         }
-        None
     }
 
-    pub fn next_hunk(&mut self) {
-        // Find the next hunk header position after current cursor
-        let mut cumulative = self.review_comments_render_height();
-        for file in &self.diff_files {
-            let path = file.display_path();
-
-            // File header
-            cumulative += 1;
-
-            // If file is reviewed, skip all content
-            if self.session.is_file_reviewed(path) {
-                continue;
-            }
-
-            // File comments
-            if let Some(review) = self.session.files.get(path) {
-                cumulative += review.file_comments.len();
-            }
+    fn skip_file_in_navigation(&self, file_idx: usize) -> bool {
+        self.skip_reviewed_in_navigation
+            && self
+                .diff_files
+                .get(file_idx)
+                .is_some_and(|file| self.session.is_file_reviewed(file.display_path()))
+    }
 
-            if file.is_binary || file.hunks.is_empty() {
-                cumulative += 1; // "(binary file)" or "(no changes)"
-            } else {
-                for hunk in &file.hunks {
-                    // This is a hunk header position
-                    if cumulative > self.diff_state.cursor_line {
-                        self.diff_state.cursor_line = cumulative;
-                        self.ensure_cursor_visible();
-                        self.update_current_file_from_cursor();
-                        return;
-                    }
-                    cumulative += 1; // hunk header
-                    cumulative += hunk.lines.len(); // diff lines
-                }
-            }
-            cumulative += 1; // spacing
+    /// When skipping reviewed files leaves nothing to visit, say so.
+    fn all_files_reviewed_while_skipping(&mut self) -> bool {
+        let all_skipped = self.skip_reviewed_in_navigation
+            && !self.diff_files.is_empty()
+            && (0..self.diff_files.len()).all(|idx| self.skip_file_in_navigation(idx));
+        if all_skipped {
+            self.set_message("All files reviewed");
         }
+        all_skipped
     }
 
-    pub fn prev_hunk(&mut self) {
-        // Find the previous hunk header position before current cursor
-        let mut hunk_positions: Vec<usize> = Vec::new();
-        let mut cumulative = self.review_comments_render_height();
-
-        for file in &self.diff_files {
-            let path = file.display_path();
+    /// Fold the unchanged lines inside hunks, keeping the cursor on the
+    /// same source line where it is still shown.
+    pub fn set_changes_only(&mut self, enabled: bool) {
+        let cursor = self
+            .get_line_at_cursor()
+            .filter(|(_, side)| *side == LineSide::New)
+            .map(|(line, _)| line);
+        self.changes_only = enabled;
+        self.rebuild_annotations();
+        if let Some(line) = cursor
+            && let FindSourceLineResult::Exact(idx) | FindSourceLineResult::Nearest(idx) =
+                find_source_line(
+                    &self.line_annotations,
+                    self.diff_state.current_file_idx,
+                    line,
+                )
+        {
+            self.diff_state.cursor_line = idx;
+        } else {
+            self.diff_state.cursor_line = self
+                .diff_state
+                .cursor_line
+                .min(self.total_lines().saturating_sub(1));
+        }
+        self.ensure_cursor_visible();
+        let status = if enabled { "on" } else { "off" };
+        self.set_message(format!("Changes only: {status}"));
+    }
 
-            cumulative += 1; // File header
+    pub fn set_skip_reviewed_in_navigation(&mut self, enabled: bool) {
+        self.skip_reviewed_in_navigation = enabled;
+        let status = if enabled { "on" } else { "off" };
+        self.set_message(format!("Skip reviewed files: {status}"));
+    }
 
-            // If file is reviewed, skip all content
-            if self.session.is_file_reviewed(path) {
-                continue;
-            }
+    pub fn set_file_list_preview(&mut self, enabled: bool) {
+        self.file_list_preview = enabled;
+        let status = if enabled { "on" } else { "off" };
+        self.set_message(format!("File list preview: {status}"));
+    }
 
-            if let Some(review) = self.session.files.get(path) {
-                cumulative += review.file_comments.len();
+    fn file_idx_to_tree_idx(&self, target_file_idx: usize) -> Option<usize> {
+        let visible_items = self.build_visible_items();
+        for (tree_idx, item) in visible_items.iter().enumerate() {
+            if let FileTreeItem::File { file_idx, .. } = item
+                && *file_idx == target_file_idx
+            {
+                return Some(tree_idx);
             }
+        }
+        None
+    }
 
-            if file.is_binary || file.hunks.is_empty() {
-                cumulative += 1;
-            } else {
-                for hunk in &file.hunks {
-                    hunk_positions.push(cumulative);
-                    cumulative += 1;
-                    cumulative += hunk.lines.len();
+    /// Rows of the hunk headers `]` and `[` stop at. Reviewed hunks are
+    /// passed over when skipping reviewed files in navigation.
+    fn hunk_header_rows(&self) -> Vec<usize> {
+        self.line_annotations
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, annotation)| match annotation {
+                AnnotatedLine::HunkHeader { file_idx, hunk_idx }
+                    if !(self.skip_reviewed_in_navigation
+                        && self.is_hunk_reviewed(*file_idx, *hunk_idx)) =>
+                {
+                    Some(idx)
                 }
-            }
-            cumulative += 1;
-        }
+                _ => None,
+            })
+            .collect()
+    }
 
-        // Find the last hunk position before current cursor
-        for &pos in hunk_positions.iter().rev() {
-            if pos < self.diff_state.cursor_line {
-                self.diff_state.cursor_line = pos;
-                self.ensure_cursor_visible();
-                self.update_current_file_from_cursor();
-                return;
-            }
+    pub fn next_hunk(&mut self) {
+        let cursor = self.diff_state.cursor_line;
+        if let Some(row) = self
+            .hunk_header_rows()
+            .into_iter()
+            .find(|&row| row > cursor)
+        {
+            self.diff_state.cursor_line = row;
+            self.ensure_cursor_visible();
+            self.update_current_file_from_cursor();
         }
+    }
 
+    pub fn prev_hunk(&mut self) {
+        let cursor = self.diff_state.cursor_line;
         // If no previous hunk, go to start
-        self.diff_state.cursor_line = 0;
+        self.diff_state.cursor_line = self
+            .hunk_header_rows()
+            .into_iter()
+            .rfind(|&row| row < cursor)
+            .unwrap_or(0);
         self.ensure_cursor_visible();
         self.update_current_file_from_cursor();
     }
 
-    fn calculate_file_scroll_offset(&self, file_idx: usize) -> usize {
-        let mut offset = self.review_comments_render_height();
-        for (i, file) in self.diff_files.iter().enumerate() {
-            if i == file_idx {
-                break;
-            }
-            offset += self.file_render_height(i, file);
-        }
-        offset
+    pub fn calculate_file_scroll_offset(&self, file_idx: usize) -> usize {
+        let first = self.annotation_file_starts.first().copied().unwrap_or(0);
+        let start = self
+            .annotation_file_starts
+            .get(file_idx)
+            .or(self.annotation_file_starts.last())
+            .copied()
+            .unwrap_or(0);
+        self.review_comments_render_height() + start - first
     }
 
     fn review_comments_render_height(&self) -> usize {
-        let mut height = 1; // Header line
-        for comment in &self.session.review_comments {
-            height += Self::comment_display_lines(comment);
-        }
+        let mut height = self
+            .annotation_file_starts
+            .first()
+            .copied()
+            .unwrap_or(self.line_annotations.len());
         if self.input_mode == InputMode::Comment
             && self.comment_is_review_level
             && self.editing_comment_id.is_none()
@@ -2340,194 +3959,28 @@ impl App {
         height
     }
 
-    fn file_render_height(&self, file_idx: usize, file: &DiffFile) -> usize {
+    /// Number of annotated lines of the file, read from the annotation cache.
+    /// Whether an added or modified image gets an inline preview below its
+    /// descriptor. The preview is read from the working tree.
+    pub fn shows_image_preview(&self, file: &DiffFile) -> bool {
+        let Some(protocol) = self.image_protocol else {
+            return false;
+        };
         let path = file.display_path();
+        file.is_binary
+            && matches!(file.status, FileStatus::Added | FileStatus::Modified)
+            && protocol.supports(path)
+            && self.vcs_info.root_path.join(path).is_file()
+    }
 
-        // If reviewed, only show header (1 line total)
-        if self.session.is_file_reviewed(path) {
-            return 1;
-        }
-
-        let header_lines = 1; // File header
-        let spacing_lines = 1; // Blank line between files
-        let mut content_lines = 0;
-        let mut comment_lines = 0;
-
-        if let Some(review) = self.session.files.get(path) {
-            for comment in &review.file_comments {
-                comment_lines += Self::comment_display_lines(comment);
-            }
-        }
-
-        if file.is_binary || file.hunks.is_empty() {
-            content_lines = 1;
-        } else {
-            let line_comments = self.session.files.get(path).map(|r| &r.line_comments);
-
-            for (hunk_idx, hunk) in file.hunks.iter().enumerate() {
-                // Calculate gap before this hunk
-                let prev_hunk = if hunk_idx > 0 {
-                    file.hunks.get(hunk_idx - 1)
-                } else {
-                    None
-                };
-                let gap = calculate_gap(
-                    prev_hunk.map(|h| (&h.new_start, &h.new_count)),
-                    hunk.new_start,
-                );
-
-                let gap_id = GapId { file_idx, hunk_idx };
-
-                if gap > 0 {
-                    let top_len = self.expanded_top.get(&gap_id).map_or(0, |v| v.len());
-                    let bot_len = self.expanded_bottom.get(&gap_id).map_or(0, |v| v.len());
-                    let remaining = (gap as usize).saturating_sub(top_len + bot_len);
-                    content_lines += top_len + bot_len;
-                    content_lines += gap_annotation_line_count(hunk_idx == 0, remaining);
-                }
-
-                // Hunk header + diff lines
-                content_lines += 1; // Hunk header
-
-                // Count diff lines based on view mode
-                match self.diff_view_mode {
-                    DiffViewMode::Unified => {
-                        for diff_line in &hunk.lines {
-                            content_lines += 1;
-
-                            if let Some(line_comments) = line_comments {
-                                if let Some(old_ln) = diff_line.old_lineno
-                                    && let Some(comments) = line_comments.get(&old_ln)
-                                {
-                                    for comment in comments {
-                                        if comment.side == Some(LineSide::Old) {
-                                            comment_lines += Self::comment_display_lines(comment);
-                                        }
-                                    }
-                                }
-
-                                if let Some(new_ln) = diff_line.new_lineno
-                                    && let Some(comments) = line_comments.get(&new_ln)
-                                {
-                                    for comment in comments {
-                                        if comment.side != Some(LineSide::Old) {
-                                            comment_lines += Self::comment_display_lines(comment);
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    DiffViewMode::SideBySide => {
-                        use crate::model::LineOrigin;
-                        // Side-by-side mode: pair deletions with following additions
-                        let lines = &hunk.lines;
-                        let mut i = 0;
-                        while i < lines.len() {
-                            let diff_line = &lines[i];
-
-                            match diff_line.origin {
-                                LineOrigin::Context => {
-                                    content_lines += 1;
-
-                                    // Comments for context line
-                                    if let Some(line_comments) = line_comments
-                                        && let Some(new_ln) = diff_line.new_lineno
-                                        && let Some(comments) = line_comments.get(&new_ln)
-                                    {
-                                        for comment in comments {
-                                            if comment.side != Some(LineSide::Old) {
-                                                comment_lines +=
-                                                    Self::comment_display_lines(comment);
-                                            }
-                                        }
-                                    }
-                                    i += 1;
-                                }
-                                LineOrigin::Deletion => {
-                                    // Find consecutive deletions
-                                    let del_start = i;
-                                    let mut del_end = i + 1;
-                                    while del_end < lines.len()
-                                        && lines[del_end].origin == LineOrigin::Deletion
-                                    {
-                                        del_end += 1;
-                                    }
-
-                                    // Find consecutive additions following deletions
-                                    let add_start = del_end;
-                                    let mut add_end = add_start;
-                                    while add_end < lines.len()
-                                        && lines[add_end].origin == LineOrigin::Addition
-                                    {
-                                        add_end += 1;
-                                    }
-
-                                    let del_count = del_end - del_start;
-                                    let add_count = add_end - add_start;
-                                    // Paired lines use max of the two counts
-                                    content_lines += del_count.max(add_count);
-
-                                    // Count comments for all deletions and additions in this pair
-                                    if let Some(line_comments) = line_comments {
-                                        for line in &lines[del_start..del_end] {
-                                            if let Some(old_ln) = line.old_lineno
-                                                && let Some(comments) = line_comments.get(&old_ln)
-                                            {
-                                                for comment in comments {
-                                                    if comment.side == Some(LineSide::Old) {
-                                                        comment_lines +=
-                                                            Self::comment_display_lines(comment);
-                                                    }
-                                                }
-                                            }
-                                        }
-
-                                        for line in &lines[add_start..add_end] {
-                                            if let Some(new_ln) = line.new_lineno
-                                                && let Some(comments) = line_comments.get(&new_ln)
-                                            {
-                                                for comment in comments {
-                                                    if comment.side != Some(LineSide::Old) {
-                                                        comment_lines +=
-                                                            Self::comment_display_lines(comment);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-
-                                    i = add_end;
-                                }
-                                LineOrigin::Addition => {
-                                    // Standalone addition (not following deletions)
-                                    content_lines += 1;
-
-                                    if let Some(line_comments) = line_comments
-                                        && let Some(new_ln) = diff_line.new_lineno
-                                        && let Some(comments) = line_comments.get(&new_ln)
-                                    {
-                                        for comment in comments {
-                                            if comment.side != Some(LineSide::Old) {
-                                                comment_lines +=
-                                                    Self::comment_display_lines(comment);
-                                            }
-                                        }
-                                    }
-
-                                    i += 1;
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+    pub fn file_render_height(&self, file_idx: usize) -> usize {
+        match self.annotation_file_starts.get(file_idx..=file_idx + 1) {
+            Some([start, end]) => end - start,
+            _ => 0,
         }
-
-        header_lines + comment_lines + content_lines + spacing_lines
     }
 
-    fn update_current_file_from_cursor(&mut self) {
+    pub fn update_current_file_from_cursor(&mut self) {
         let mut cumulative = self.review_comments_render_height();
         if self.diff_state.cursor_line < cumulative {
             if !self.diff_files.is_empty() {
@@ -2536,8 +3989,8 @@ impl App {
             }
             return;
         }
-        for (i, file) in self.diff_files.iter().enumerate() {
-            let height = self.file_render_height(i, file);
+        for i in 0..self.diff_files.len() {
+            let height = self.file_render_height(i);
             if cumulative + height > self.diff_state.cursor_line {
                 self.diff_state.current_file_idx = i;
                 self.file_list_state.select(i);
@@ -2552,13 +4005,9 @@ impl App {
     }
 
     pub fn total_lines(&self) -> usize {
-        self.review_comments_render_height()
-            + self
-                .diff_files
-                .iter()
-                .enumerate()
-                .map(|(i, f)| self.file_render_height(i, f))
-                .sum::<usize>()
+        let first = self.annotation_file_starts.first().copied().unwrap_or(0);
+        let last = self.annotation_file_starts.last().copied().unwrap_or(0);
+        self.review_comments_render_height() + last - first
     }
 
     /// Calculate the maximum scroll offset.
@@ -2589,8 +4038,13 @@ impl App {
 
     /// Returns the source line number and side at the current cursor position, if on a diff line
     pub fn get_line_at_cursor(&self) -> Option<(u32, LineSide)> {
-        let target = self.diff_state.cursor_line;
-        match self.line_annotations.get(target) {
+        self.line_at_annotation(self.diff_state.cursor_line)
+    }
+
+    /// Line number and side of the diff line at annotation `idx`, preferring
+    /// the new side like [`App::get_line_at_cursor`].
+    fn line_at_annotation(&self, idx: usize) -> Option<(u32, LineSide)> {
+        match self.line_annotations.get(idx) {
             Some(AnnotatedLine::DiffLine {
                 old_lineno,
                 new_lineno,
@@ -2610,33 +4064,175 @@ impl App {
         }
     }
 
-    /// Find the comment at the current cursor position
-    fn find_comment_at_cursor(&self) -> Option<CommentLocation> {
-        let target = self.diff_state.cursor_line;
-        match self.line_annotations.get(target) {
-            Some(AnnotatedLine::ReviewComment { comment_idx }) => Some(CommentLocation::Review {
-                index: *comment_idx,
-            }),
-            Some(AnnotatedLine::FileComment {
+    /// Returns the raw content of the diff line under the cursor, without the origin marker.
+    /// In side-by-side mode the new side is preferred over the old side.
+    pub fn line_content_at_cursor(&self) -> Option<String> {
+        self.diff_line_at_cursor()
+            .map(|(_, line)| line.content.clone())
+    }
+
+    /// File index and diff line under the cursor, including expanded context.
+    /// In side-by-side mode the new side is preferred over the old side.
+    fn diff_line_at_cursor(&self) -> Option<(usize, &DiffLine)> {
+        match self.line_annotations.get(self.diff_state.cursor_line)? {
+            AnnotatedLine::DiffLine {
                 file_idx,
-                comment_idx,
-            }) => {
-                let path = self.diff_files.get(*file_idx)?.display_path().clone();
-                Some(CommentLocation::File {
-                    path,
-                    index: *comment_idx,
-                })
+                hunk_idx,
+                line_idx,
+                ..
+            } => {
+                let hunk = self.diff_files.get(*file_idx)?.hunks.get(*hunk_idx)?;
+                Some((*file_idx, hunk.lines.get(*line_idx)?))
             }
-            Some(AnnotatedLine::LineComment {
+            AnnotatedLine::SideBySideLine {
                 file_idx,
-                line,
-                side,
-                comment_idx,
-            }) => {
-                let path = self.diff_files.get(*file_idx)?.display_path().clone();
-                Some(CommentLocation::Line {
-                    path,
-                    line: *line,
+                hunk_idx,
+                del_line_idx,
+                add_line_idx,
+                ..
+            } => {
+                let hunk = self.diff_files.get(*file_idx)?.hunks.get(*hunk_idx)?;
+                let idx = add_line_idx.or(*del_line_idx)?;
+                Some((*file_idx, hunk.lines.get(idx)?))
+            }
+            AnnotatedLine::ExpandedContext { gap_id, line_idx } => self
+                .get_expanded_line(gap_id, *line_idx)
+                .map(|line| (gap_id.file_idx, line)),
+            _ => None,
+        }
+    }
+
+    /// File and hunk index of the hunk header or diff line under the cursor.
+    fn hunk_at_cursor(&self) -> Option<(usize, usize)> {
+        match self.line_annotations.get(self.diff_state.cursor_line)? {
+            AnnotatedLine::HunkHeader { file_idx, hunk_idx }
+            | AnnotatedLine::DiffLine {
+                file_idx, hunk_idx, ..
+            }
+            | AnnotatedLine::SideBySideLine {
+                file_idx, hunk_idx, ..
+            } => Some((*file_idx, *hunk_idx)),
+            _ => None,
+        }
+    }
+
+    /// The hunk under the cursor as raw diff text: its `@@` header followed by
+    /// every line with its origin marker.
+    pub fn hunk_lines_at_cursor(&self) -> Option<Vec<String>> {
+        let (file_idx, hunk_idx) = self.hunk_at_cursor()?;
+        let hunk = self.diff_files.get(file_idx)?.hunks.get(hunk_idx)?;
+        let mut lines = vec![hunk.header.trim_end().to_string()];
+        lines.extend(hunk.lines.iter().map(DiffLine::to_patch_line));
+        Some(lines)
+    }
+
+    /// Lines of the current visual selection, in display order. In side-by-side
+    /// mode the half of each row matching the selection's side is used.
+    /// With `with_markers`, each line keeps its `+`/`-`/` ` diff marker.
+    pub fn selected_lines(&self, with_markers: bool) -> Option<Vec<String>> {
+        let (range, side) = self.get_visual_selection()?;
+        let current_file = self.diff_state.current_file_idx;
+        let on_side = |line: &DiffLine| {
+            let (lineno, line_side) = match line.new_lineno {
+                Some(n) => (n, LineSide::New),
+                None => (line.old_lineno?, LineSide::Old),
+            };
+            (line_side == side && range.contains(lineno)).then_some(())
+        };
+
+        let mut lines = Vec::new();
+        for annotation in &self.line_annotations {
+            let line = match annotation {
+                AnnotatedLine::DiffLine {
+                    file_idx,
+                    hunk_idx,
+                    line_idx,
+                    ..
+                } if *file_idx == current_file => {
+                    let hunk = self.diff_files.get(*file_idx)?.hunks.get(*hunk_idx)?;
+                    hunk.lines.get(*line_idx).filter(|l| on_side(l).is_some())
+                }
+                AnnotatedLine::SideBySideLine {
+                    file_idx,
+                    hunk_idx,
+                    del_line_idx,
+                    add_line_idx,
+                    old_lineno,
+                    new_lineno,
+                } if *file_idx == current_file => {
+                    let hunk = self.diff_files.get(*file_idx)?.hunks.get(*hunk_idx)?;
+                    let (idx, lineno) = match side {
+                        LineSide::Old => (*del_line_idx, *old_lineno),
+                        LineSide::New => (*add_line_idx, *new_lineno),
+                    };
+                    idx.zip(lineno)
+                        .filter(|(_, n)| range.contains(*n))
+                        .and_then(|(idx, _)| hunk.lines.get(idx))
+                }
+                AnnotatedLine::ExpandedContext { gap_id, line_idx }
+                    if gap_id.file_idx == current_file =>
+                {
+                    self.get_expanded_line(gap_id, *line_idx)
+                        .filter(|l| on_side(l).is_some())
+                }
+                _ => None,
+            };
+            if let Some(line) = line {
+                lines.push(if with_markers {
+                    line.to_patch_line()
+                } else {
+                    line.content.clone()
+                });
+            }
+        }
+        Some(lines)
+    }
+
+    /// File and line range referenced by the cursor: the visual selection in
+    /// visual mode, otherwise the diff line under the cursor.
+    pub fn line_ref_at_cursor(&self) -> Option<(PathBuf, LineRange)> {
+        let range = match self.get_visual_selection() {
+            Some((range, _)) => range,
+            None => LineRange::single(self.get_line_at_cursor()?.0),
+        };
+        Some((self.current_file_path()?.clone(), range))
+    }
+
+    /// Find the comment at the current cursor position
+    fn find_comment_at_cursor(&self) -> Option<CommentLocation> {
+        self.comment_location_at(self.diff_state.cursor_line)
+    }
+
+    /// The comment drawn at annotation `idx`, if that row is part of one.
+    pub fn comment_at_annotation(&self, idx: usize) -> Option<&Comment> {
+        self.comment_at(&self.comment_location_at(idx)?)
+    }
+
+    fn comment_location_at(&self, idx: usize) -> Option<CommentLocation> {
+        match self.line_annotations.get(idx) {
+            Some(AnnotatedLine::ReviewComment { comment_idx }) => Some(CommentLocation::Review {
+                index: *comment_idx,
+            }),
+            Some(AnnotatedLine::FileComment {
+                file_idx,
+                comment_idx,
+            }) => {
+                let path = self.diff_files.get(*file_idx)?.display_path().clone();
+                Some(CommentLocation::File {
+                    path,
+                    index: *comment_idx,
+                })
+            }
+            Some(AnnotatedLine::LineComment {
+                file_idx,
+                line,
+                side,
+                comment_idx,
+            }) => {
+                let path = self.diff_files.get(*file_idx)?.display_path().clone();
+                Some(CommentLocation::Line {
+                    path,
+                    line: *line,
                     side: *side,
                     index: *comment_idx,
                 })
@@ -2645,27 +4241,530 @@ impl App {
         }
     }
 
+    fn comment_at(&self, location: &CommentLocation) -> Option<&Comment> {
+        match location {
+            CommentLocation::Review { index } => self.session.review_comments.get(*index),
+            CommentLocation::File { path, index } => {
+                self.session.files.get(path)?.file_comments.get(*index)
+            }
+            CommentLocation::Line {
+                path,
+                line,
+                side,
+                index,
+            } => self
+                .session
+                .files
+                .get(path)?
+                .line_comments
+                .get(line)?
+                .iter()
+                .filter(|c| c.side.unwrap_or(LineSide::New) == *side)
+                .nth(*index),
+        }
+    }
+
+    /// Returns the full content of the comment at the current cursor position, if any
+    pub fn comment_content_at_cursor(&self) -> Option<String> {
+        let location = self.find_comment_at_cursor()?;
+        Some(self.comment_at(&location)?.content.clone())
+    }
+
+    /// Start writing a reply to the comment under the cursor.
+    /// Replies to a reply join the same thread, so threads stay one level deep.
+    /// Request opening the current file in `$EDITOR` at the cursor line.
+    /// Only working-tree reviews are backed by the files on disk.
+    pub fn request_open_in_editor(&mut self) {
+        if matches!(
+            self.diff_source,
+            DiffSource::CommitRange(_) | DiffSource::PullRequest { .. }
+        ) {
+            self.set_warning("Opening in $EDITOR is only available for working tree reviews");
+            return;
+        }
+        let Some(file) = self.current_file() else {
+            return;
+        };
+        if file.is_commit_message || file.status == FileStatus::Deleted {
+            self.set_warning("File does not exist on disk");
+            return;
+        }
+
+        let path = self.vcs_info.root_path.join(file.display_path());
+        if !path.is_file() {
+            self.set_warning(format!("{} does not exist on disk", path.display()));
+            return;
+        }
+        let line = match self.focused_panel {
+            FocusedPanel::Diff => match self.get_line_at_cursor() {
+                Some((line, LineSide::New)) => Some(line),
+                _ => None,
+            },
+            _ => None,
+        };
+        self.pending_editor = Some((path, line));
+    }
+
+    /// Open the message of the commit under review in `$EDITOR`: the
+    /// working-copy description for jj, HEAD's message for git.
+    pub fn request_edit_commit_message(&mut self) {
+        if self.diff_source != DiffSource::WorkingTree {
+            self.set_warning(
+                "Editing the commit message is only available for working tree reviews",
+            );
+            return;
+        }
+        let message = match self.vcs.commit_message() {
+            Ok(message) => message,
+            Err(e) => {
+                self.set_error(format!("Can't edit commit message: {e}"));
+                return;
+            }
+        };
+
+        let path = std::env::temp_dir().join(format!("tuicr-message-{}.txt", uuid::Uuid::new_v4()));
+        if let Err(e) = std::fs::write(&path, &message) {
+            self.set_error(format!("Failed to write {}: {e}", path.display()));
+            return;
+        }
+        self.commit_message_draft = Some((path.clone(), message));
+        self.pending_editor = Some((path, None));
+    }
+
+    /// Open the comment being typed in `$EDITOR`. Comment mode stays active,
+    /// so the comment type and target are kept while the editor runs.
+    pub fn request_edit_comment_in_editor(&mut self) {
+        let path = std::env::temp_dir().join(format!("tuicr-comment-{}.md", uuid::Uuid::new_v4()));
+        if let Err(e) = std::fs::write(&path, &self.comment_buffer) {
+            self.set_error(format!("Failed to write {}: {e}", path.display()));
+            return;
+        }
+        self.comment_draft = Some(path.clone());
+        self.pending_editor = Some((path, None));
+    }
+
+    /// Load the comment saved in `$EDITOR` after
+    /// [`Self::request_edit_comment_in_editor`] back into the input, or keep
+    /// the original when the editor failed. Does nothing if no comment was
+    /// being edited.
+    pub fn finish_edit_comment(&mut self, editor_succeeded: bool) {
+        let Some(path) = self.comment_draft.take() else {
+            return;
+        };
+        let edited = std::fs::read_to_string(&path);
+        let _ = std::fs::remove_file(&path);
+        if !editor_succeeded {
+            self.set_warning("Editor failed; kept the original comment");
+            return;
+        }
+        match edited {
+            Ok(edited) => {
+                // Editors add a final newline that would otherwise end up in the comment
+                self.comment_buffer = edited.trim_end_matches(['\n', '\r']).to_string();
+                self.comment_cursor = self.comment_buffer.len();
+            }
+            Err(e) => self.set_error(format!("Failed to read {}: {e}", path.display())),
+        }
+    }
+
+    /// Apply the message saved in `$EDITOR` after [`Self::request_edit_commit_message`].
+    /// Does nothing if no commit message was being edited.
+    pub fn finish_edit_commit_message(&mut self, editor_succeeded: bool) {
+        let Some((path, original)) = self.commit_message_draft.take() else {
+            return;
+        };
+        let edited = std::fs::read_to_string(&path);
+        let _ = std::fs::remove_file(&path);
+        if !editor_succeeded {
+            return;
+        }
+        let edited = match edited {
+            Ok(edited) => edited,
+            Err(e) => {
+                self.set_error(format!("Failed to read {}: {e}", path.display()));
+                return;
+            }
+        };
+
+        let message = edited.trim_end();
+        if message == original.trim_end() {
+            self.set_message("Commit message unchanged");
+            return;
+        }
+        if let Err(e) = self.vcs.set_commit_message(&format!("{message}\n")) {
+            self.set_error(format!("Failed to update commit message: {e}"));
+            return;
+        }
+        if let Err(e) = self.reload_diff_files() {
+            self.set_warning(format!("Updated commit message, but reloading failed: {e}"));
+            return;
+        }
+        self.set_message("Updated commit message");
+    }
+
+    /// Show who last changed the line under the cursor. Added lines are
+    /// blamed in the revision the diff ends at (uncommitted for working tree
+    /// changes), deleted and context lines in the revision it starts from.
+    /// Pressed again on the same line, expands to the full commit.
+    pub fn show_blame_at_cursor(&mut self) {
+        let Some((file_idx, line)) = self.diff_line_at_cursor() else {
+            self.set_warning("Move the cursor onto a diff line to show blame");
+            return;
+        };
+        let file = &self.diff_files[file_idx];
+        let (path, lineno, side) = match (line.origin, line.new_lineno, line.old_lineno) {
+            (LineOrigin::Addition, Some(new), _) => {
+                (file.display_path().clone(), new, LineSide::New)
+            }
+            (_, _, Some(old)) => (
+                file.old_path
+                    .clone()
+                    .unwrap_or_else(|| file.display_path().clone()),
+                old,
+                LineSide::Old,
+            ),
+            _ => {
+                self.set_warning("No blame for this line");
+                return;
+            }
+        };
+
+        if let Some(popup) = &mut self.blame_popup
+            && popup.path == path
+            && popup.line == lineno
+            && popup.side == side
+        {
+            if popup.commit.is_some() {
+                popup.expanded = true;
+            }
+            return;
+        }
+
+        let commit_id = match self.blame_target(side) {
+            Some(target) => match self.vcs.blame_line(&path, lineno, target) {
+                Ok(id) => id,
+                Err(e) => {
+                    self.set_error(format!("Blame failed: {e}"));
+                    return;
+                }
+            },
+            None => None,
+        };
+        let commit = match commit_id {
+            Some(id) => match self.vcs.get_commits_info(std::slice::from_ref(&id)) {
+                Ok(mut infos) if !infos.is_empty() => Some(infos.remove(0)),
+                Ok(_) => {
+                    self.set_error(format!("Blame failed: commit {id} not found"));
+                    return;
+                }
+                Err(e) => {
+                    self.set_error(format!("Blame failed: {e}"));
+                    return;
+                }
+            },
+            None => None,
+        };
+
+        self.blame_popup = Some(BlamePopup {
+            path,
+            line: lineno,
+            side,
+            commit,
+            expanded: false,
+            scroll_offset: 0,
+            viewport_height: 0,
+            total_lines: 0,
+        });
+    }
+
+    /// Revision to blame a line on `side` of the diff in, or None for added
+    /// lines that exist only in the working tree.
+    fn blame_target(&self, side: LineSide) -> Option<BlameTarget<'_>> {
+        match (&self.diff_source, side) {
+            (
+                DiffSource::WorkingTree
+                | DiffSource::Staged
+                | DiffSource::Unstaged
+                | DiffSource::StagedAndUnstaged,
+                LineSide::New,
+            ) => None,
+            (
+                DiffSource::WorkingTree
+                | DiffSource::Staged
+                | DiffSource::Unstaged
+                | DiffSource::StagedAndUnstaged,
+                LineSide::Old,
+            ) => Some(BlameTarget::WorkingCopyParent),
+            (
+                DiffSource::CommitRange(ids)
+                | DiffSource::WorkingTreeAndCommits(ids)
+                | DiffSource::StagedUnstagedAndCommits(ids),
+                LineSide::Old,
+            ) => Some(ids.first().map_or(BlameTarget::WorkingCopyParent, |id| {
+                BlameTarget::ParentOf(id)
+            })),
+            (DiffSource::CommitRange(ids), LineSide::New) => Some(
+                ids.last()
+                    .map_or(BlameTarget::WorkingCopyParent, |id| BlameTarget::Commit(id)),
+            ),
+            (
+                DiffSource::WorkingTreeAndCommits(_) | DiffSource::StagedUnstagedAndCommits(_),
+                LineSide::New,
+            ) => Some(BlameTarget::WorkingCopy),
+            (
+                DiffSource::PullRequest {
+                    merge_base_commit, ..
+                },
+                LineSide::Old,
+            ) => Some(BlameTarget::Commit(merge_base_commit)),
+            (DiffSource::PullRequest { head_commit, .. }, LineSide::New) => {
+                Some(BlameTarget::Commit(head_commit))
+            }
+        }
+    }
+
+    /// Toggle the `:blame` column, which shows the commit and author that last
+    /// touched each context and deleted line of the current file. Only
+    /// working tree reviews are blamed, against the revision they start from.
+    pub fn toggle_blame_column(&mut self) {
+        if !matches!(
+            self.diff_source,
+            DiffSource::WorkingTree
+                | DiffSource::Staged
+                | DiffSource::Unstaged
+                | DiffSource::StagedAndUnstaged
+        ) {
+            self.set_warning("The blame column is only available for working tree reviews");
+            return;
+        }
+        self.show_blame_column = !self.show_blame_column;
+        let status = if self.show_blame_column { "on" } else { "off" };
+        self.set_message(format!("Blame column: {status}"));
+    }
+
+    /// Blame the current file for the `:blame` column unless it was already.
+    /// Called on every render, so each file is blamed at most once.
+    pub fn load_current_file_blame(&mut self) {
+        if !self.show_blame_column {
+            return;
+        }
+        let Some(file) = self.current_file() else {
+            return;
+        };
+        let path = file.display_path().clone();
+        if self.blame_cache.contains_key(&path) {
+            return;
+        }
+        let blame = match file.status {
+            FileStatus::Added => None,
+            _ => {
+                let old_path = file.old_path.as_ref().unwrap_or(&path);
+                self.vcs.blame_file(old_path).ok()
+            }
+        };
+        self.blame_cache.insert(path, blame);
+    }
+
+    /// Blame for the `:blame` column of file `file_idx`, indexed by old line
+    /// number minus one. Only the current file has one.
+    pub fn blame_column(&self, file_idx: usize) -> Option<&[BlameLine]> {
+        if !self.show_blame_column || file_idx != self.diff_state.current_file_idx {
+            return None;
+        }
+        let path = self.diff_files.get(file_idx)?.display_path();
+        self.blame_cache.get(path)?.as_deref()
+    }
+
+    pub fn start_reply_at_cursor(&mut self) {
+        let Some(location) = self.find_comment_at_cursor() else {
+            self.set_warning("Move the cursor onto a comment to reply");
+            return;
+        };
+        let Some(parent) = self.comment_at(&location) else {
+            return;
+        };
+        let root = parent
+            .parent_id
+            .clone()
+            .unwrap_or_else(|| parent.id.clone());
+        let line_range = parent.line_range;
+
+        match location {
+            CommentLocation::Review { .. } => self.enter_review_comment_mode(),
+            CommentLocation::File { .. } => {
+                self.enter_comment_mode(true, None);
+                self.comment_line_range = None;
+            }
+            CommentLocation::Line { line, side, .. } => {
+                self.enter_comment_mode(false, Some((line, side)));
+                self.comment_line_range = line_range.map(|range| (range, side));
+            }
+        }
+        self.editing_comment_id = None;
+        self.reply_to = Some(root);
+    }
+
+    /// Apply the ```` ```suggestion ```` block of the line comment under the cursor
+    /// to the working tree file, then mark the comment resolved.
+    pub fn apply_suggestion_at_cursor(&mut self) {
+        let Some(CommentLocation::Line {
+            path,
+            line,
+            side,
+            index,
+        }) = self.find_comment_at_cursor()
+        else {
+            self.set_warning("Move the cursor onto a line comment to apply its suggestion");
+            return;
+        };
+        let new_side_is_working_tree = matches!(
+            self.diff_source,
+            DiffSource::WorkingTree
+                | DiffSource::Unstaged
+                | DiffSource::StagedAndUnstaged
+                | DiffSource::WorkingTreeAndCommits(_)
+                | DiffSource::StagedUnstagedAndCommits(_)
+        );
+        if !new_side_is_working_tree || side != LineSide::New {
+            self.set_warning("Suggestions can only be applied to working tree lines");
+            return;
+        }
+
+        let Some(comment) = self.session.files.get(&path).and_then(|review| {
+            review
+                .line_comments
+                .get(&line)?
+                .iter()
+                .filter(|c| c.side.unwrap_or(LineSide::New) == side)
+                .nth(index)
+        }) else {
+            return;
+        };
+        if comment.resolved {
+            self.set_warning("Suggestion already applied");
+            return;
+        }
+        let Some(suggestion) = suggestion::parse_suggestion(&comment.content) else {
+            self.set_warning("Comment has no ```suggestion block");
+            return;
+        };
+        let comment_id = comment.id.clone();
+        let range = comment.line_range.unwrap_or(LineRange::single(line));
+
+        // Lines the diff showed, to detect edits made since it was loaded
+        let expected: Vec<(u32, String)> = self
+            .diff_files
+            .iter()
+            .find(|f| f.display_path() == &path)
+            .map(|file| {
+                file.hunks
+                    .iter()
+                    .flat_map(|hunk| &hunk.lines)
+                    .filter(|l| l.origin != LineOrigin::Deletion)
+                    .filter_map(|l| Some((l.new_lineno?, l.content.clone())))
+                    .filter(|(lineno, _)| range.contains(*lineno))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let file_path = self.vcs_info.root_path.join(&path);
+        if let Err(e) = suggestion::apply_suggestion(&file_path, range, &expected, &suggestion) {
+            self.set_error(format!("{e}"));
+            return;
+        }
+
+        if let Some(comment) = self
+            .session
+            .get_file_mut(&path)
+            .and_then(|review| review.line_comments.get_mut(&line))
+            .and_then(|comments| comments.iter_mut().find(|c| c.id == comment_id))
+        {
+            comment.resolved = true;
+            self.dirty = true;
+        }
+
+        self.reload_after_vcs_change();
+        if let FindSourceLineResult::Exact(idx) | FindSourceLineResult::Nearest(idx) =
+            find_source_line(
+                &self.line_annotations,
+                self.diff_state.current_file_idx,
+                range.start,
+            )
+        {
+            self.diff_state.cursor_line = idx;
+            self.ensure_cursor_visible();
+        }
+        self.set_message(format!(
+            "Applied suggestion to {}:{}",
+            path.display(),
+            range.start
+        ));
+    }
+
     /// Delete the comment at the current cursor position, if any
     /// Returns true if a comment was deleted
     pub fn delete_comment_at_cursor(&mut self) -> bool {
         let location = self.find_comment_at_cursor();
+        self.delete_comment_at(location)
+    }
+
+    fn delete_comment_at(&mut self, location: Option<CommentLocation>) -> bool {
+        // Replies go away with their parent; undo restores the whole thread
+        if let Some(comment) = location.as_ref().and_then(|l| self.comment_at(l))
+            && self.session.has_replies(&comment.id)
+        {
+            let id = comment.id.clone();
+            let anchor = location
+                .as_ref()
+                .map_or(CommentAnchor::Review, CommentLocation::anchor);
+            let snapshot = UndoAction::snapshot(&self.session);
+            let removed = self.session.remove_comment_thread(&id);
+            self.undo_history.record("delete comment thread", snapshot);
+            self.dirty = true;
+            self.set_message(format!(
+                "Comment and {} {} deleted",
+                removed - 1,
+                if removed == 2 { "reply" } else { "replies" }
+            ));
+            self.rebuild_annotations_at(&anchor);
+            return true;
+        }
 
         match location {
             Some(CommentLocation::Review { index })
                 if index < self.session.review_comments.len() =>
             {
-                self.session.review_comments.remove(index);
+                let comment = self.session.review_comments.remove(index);
+                self.undo_history.record(
+                    "delete review comment",
+                    UndoAction::InsertComment {
+                        anchor: CommentAnchor::Review,
+                        index,
+                        comment: Box::new(comment),
+                    },
+                );
                 self.dirty = true;
                 self.set_message("Review comment deleted");
-                self.rebuild_annotations();
+                self.rebuild_review_annotations();
                 return true;
             }
             Some(CommentLocation::File { path, index }) => {
-                if let Some(review) = self.session.get_file_mut(&path) {
-                    review.file_comments.remove(index);
+                if let Some(review) = self.session.get_file_mut(&path)
+                    && index < review.file_comments.len()
+                {
+                    let comment = review.file_comments.remove(index);
+                    let anchor = CommentAnchor::File(path);
+                    self.rebuild_annotations_at(&anchor);
+                    self.undo_history.record(
+                        "delete file comment",
+                        UndoAction::InsertComment {
+                            anchor,
+                            index,
+                            comment: Box::new(comment),
+                        },
+                    );
                     self.dirty = true;
                     self.set_message("Comment deleted");
-                    self.rebuild_annotations();
                     return true;
                 }
             }
@@ -2692,13 +4791,22 @@ impl App {
                         }
                     }
                     if let Some(idx) = actual_idx {
-                        comments.remove(idx);
+                        let comment = comments.remove(idx);
                         if comments.is_empty() {
                             review.line_comments.remove(&line);
                         }
+                        let anchor = CommentAnchor::Line(path, line);
+                        self.rebuild_annotations_at(&anchor);
+                        self.undo_history.record(
+                            format!("delete comment on line {line}"),
+                            UndoAction::InsertComment {
+                                anchor,
+                                index: idx,
+                                comment: Box::new(comment),
+                            },
+                        );
                         self.dirty = true;
                         self.set_message(format!("Comment on line {line} deleted"));
-                        self.rebuild_annotations();
                         return true;
                     }
                 }
@@ -2710,12 +4818,14 @@ impl App {
     }
 
     pub fn clear_comments(&mut self, scope: ClearScope) {
+        let snapshot = UndoAction::snapshot(&self.session);
         let (cleared, unreviewed) = self.session.clear_comments(scope);
         if cleared == 0 && unreviewed == 0 {
             self.set_message("No comments to clear");
             return;
         }
 
+        self.undo_history.record("clear comments", snapshot);
         self.dirty = true;
         self.rebuild_annotations();
         let msg = match (cleared, unreviewed) {
@@ -2726,11 +4836,41 @@ impl App {
         self.set_message(msg);
     }
 
-    /// Enter edit mode for the comment at the current cursor position
-    /// Returns true if a comment was found and edit mode entered
-    pub fn enter_edit_mode(&mut self) -> bool {
-        let location = self.find_comment_at_cursor();
-
+    /// Revert the most recent comment or review-state change
+    pub fn undo(&mut self) {
+        match self.undo_history.undo(&mut self.session) {
+            Some(Ok(description)) => {
+                self.dirty = true;
+                self.rebuild_annotations();
+                self.set_message(format!("Undid: {description}"));
+            }
+            Some(Err(description)) => {
+                self.set_warning(format!("Cannot undo: {description} (no longer applies)"));
+            }
+            None => self.set_message("Nothing to undo"),
+        }
+    }
+
+    /// Re-apply the most recently undone change
+    pub fn redo(&mut self) {
+        match self.undo_history.redo(&mut self.session) {
+            Some(Ok(description)) => {
+                self.dirty = true;
+                self.rebuild_annotations();
+                self.set_message(format!("Redid: {description}"));
+            }
+            Some(Err(description)) => {
+                self.set_warning(format!("Cannot redo: {description} (no longer applies)"));
+            }
+            None => self.set_message("Nothing to redo"),
+        }
+    }
+
+    /// Enter edit mode for the comment at the current cursor position
+    /// Returns true if a comment was found and edit mode entered
+    pub fn enter_edit_mode(&mut self) -> bool {
+        let location = self.find_comment_at_cursor();
+
         match location {
             Some(CommentLocation::Review { index }) => {
                 if let Some(comment) = self.session.review_comments.get(index) {
@@ -2742,6 +4882,7 @@ impl App {
                     self.comment_is_file_level = false;
                     self.comment_line = None;
                     self.editing_comment_id = Some(comment.id.clone());
+                    self.reply_to = comment.parent_id.clone();
                     return true;
                 }
             }
@@ -2757,6 +4898,7 @@ impl App {
                     self.comment_is_file_level = true;
                     self.comment_line = None;
                     self.editing_comment_id = Some(comment.id.clone());
+                    self.reply_to = comment.parent_id.clone();
                     return true;
                 }
             }
@@ -2783,6 +4925,7 @@ impl App {
                                 self.comment_is_file_level = false;
                                 self.comment_line = Some((line, side));
                                 self.editing_comment_id = Some(comment.id.clone());
+                                self.reply_to = comment.parent_id.clone();
                                 return true;
                             }
                             side_idx += 1;
@@ -2845,6 +4988,7 @@ impl App {
         self.comment_is_review_level = false;
         self.editing_comment_id = None;
         self.comment_line_range = None;
+        self.reply_to = None;
     }
 
     /// Enter visual selection mode, anchoring at the current cursor position
@@ -2887,6 +5031,62 @@ impl App {
         }
     }
 
+    /// Extend a mouse-drag selection to annotation `idx`, entering visual
+    /// mode anchored at the cursor on the first call. Rows on the other side
+    /// of the anchor, in another file, or without a line are skipped.
+    pub fn drag_selection_to(&mut self, idx: usize) {
+        if self.input_mode == InputMode::Normal {
+            let Some((line, side)) = self.get_line_at_cursor() else {
+                self.mouse_drag_in_diff = false;
+                return;
+            };
+            self.enter_visual_mode(line, side);
+        }
+        if self.is_drag_target(idx) {
+            self.move_cursor_to_annotation(idx);
+        }
+    }
+
+    /// Move a drag selection one selectable row past the cursor, scrolling
+    /// the viewport along, for drags held at the edge of the diff panel.
+    pub fn drag_selection_past_edge(&mut self, down: bool) {
+        let cursor = self.diff_state.cursor_line;
+        let target = if down {
+            (cursor + 1..self.line_annotations.len()).find(|&idx| self.is_drag_target(idx))
+        } else {
+            (0..cursor).rev().find(|&idx| self.is_drag_target(idx))
+        };
+        if let Some(idx) = target {
+            self.drag_selection_to(idx);
+        }
+    }
+
+    fn is_drag_target(&self, idx: usize) -> bool {
+        let Some((_, anchor_side)) = self.visual_anchor else {
+            return false;
+        };
+        let same_file = self
+            .line_annotations
+            .get(idx)
+            .and_then(annotation_file_idx)
+            .is_some_and(|file_idx| file_idx == self.diff_state.current_file_idx);
+        same_file
+            && self
+                .line_at_annotation(idx)
+                .is_some_and(|(_, side)| side == anchor_side)
+    }
+
+    /// Release of a mouse drag. The selection stays for `c` or `y`, unless
+    /// the button went up over a row that isn't a diff line.
+    pub fn end_drag_selection(&mut self, release_idx: Option<usize>) {
+        if std::mem::take(&mut self.mouse_drag_in_diff)
+            && self.input_mode == InputMode::VisualSelect
+            && release_idx.is_some_and(|idx| self.line_at_annotation(idx).is_none())
+        {
+            self.exit_visual_mode();
+        }
+    }
+
     /// Enter comment mode from visual selection
     pub fn enter_comment_from_visual(&mut self) {
         if let Some((range, side)) = self.get_visual_selection() {
@@ -2905,6 +5105,41 @@ impl App {
         }
     }
 
+    /// Fingerprint of the new-side line the comment being written is stored
+    /// on, for following it when the file changes on disk.
+    fn new_comment_fingerprint(&self, path: &Path) -> Option<LineFingerprint> {
+        if self.comment_is_file_level {
+            return None;
+        }
+        let (line, side) = self
+            .comment_line_range
+            .map(|(range, side)| (range.end, side))
+            .or(self.comment_line)?;
+        if side != LineSide::New {
+            return None;
+        }
+        let contents = self.working_tree_contents(path)?;
+        let lines: Vec<&str> = contents.lines().collect();
+        LineFingerprint::of(&lines, line)
+    }
+
+    /// The file as it is on disk, when the new side of the diff is the
+    /// working tree. Other sources don't change under an open review.
+    fn working_tree_contents(&self, path: &Path) -> Option<String> {
+        match self.diff_source {
+            DiffSource::WorkingTree
+            | DiffSource::Unstaged
+            | DiffSource::StagedAndUnstaged
+            | DiffSource::WorkingTreeAndCommits(_)
+            | DiffSource::StagedUnstagedAndCommits(_) => {
+                std::fs::read_to_string(self.vcs_info.root_path.join(path)).ok()
+            }
+            DiffSource::Staged | DiffSource::CommitRange(_) | DiffSource::PullRequest { .. } => {
+                None
+            }
+        }
+    }
+
     pub fn save_comment(&mut self) {
         if self.comment_buffer.trim().is_empty() {
             self.set_message("Comment cannot be empty");
@@ -2914,42 +5149,59 @@ impl App {
         let content = self.comment_buffer.trim().to_string();
 
         let mut message = "Error: Could not save comment".to_string();
+        // (description, inverse) recorded on the undo stack once the change is applied
+        let mut undo: Option<(String, UndoAction)> = None;
+        // Where the comment was saved, to refresh only that part of the annotations
+        let mut saved_at: Option<CommentAnchor> = None;
 
         // Check if we're editing an existing comment
         if let Some(editing_id) = &self.editing_comment_id {
+            let revert = |comment: &Comment| UndoAction::SetComment {
+                id: comment.id.clone(),
+                content: comment.content.clone(),
+                comment_type: comment.comment_type.clone(),
+            };
             if let Some(comment) = self
                 .session
                 .review_comments
                 .iter_mut()
                 .find(|c| &c.id == editing_id)
             {
+                undo = Some(("edit review comment".to_string(), revert(comment)));
                 comment.content = content.clone();
                 comment.comment_type = self.comment_type.clone();
+                comment.updated_at = Some(chrono::Utc::now());
                 message = "Review comment updated".to_string();
+                saved_at = Some(CommentAnchor::Review);
             } else if let Some(path) = self.current_file_path().cloned()
                 && let Some(review) = self.session.get_file_mut(&path)
             {
+                saved_at = Some(CommentAnchor::File(path.clone()));
                 if let Some(comment) = review
                     .file_comments
                     .iter_mut()
                     .find(|c| &c.id == editing_id)
                 {
+                    undo = Some(("edit file comment".to_string(), revert(comment)));
                     comment.content = content.clone();
                     comment.comment_type = self.comment_type.clone();
+                    comment.updated_at = Some(chrono::Utc::now());
                     message = "Comment updated".to_string();
                 } else {
                     // If not found in file comments, search in line comments
                     let mut found_comment = None;
-                    for comments in review.line_comments.values_mut() {
+                    for (line, comments) in review.line_comments.iter_mut() {
                         if let Some(comment) = comments.iter_mut().find(|c| &c.id == editing_id) {
-                            found_comment = Some(comment);
+                            found_comment = Some((*line, comment));
                             break;
                         }
                     }
 
-                    if let Some(comment) = found_comment {
+                    if let Some((line, comment)) = found_comment {
+                        undo = Some((format!("edit comment on line {line}"), revert(comment)));
                         comment.content = content.clone();
                         comment.comment_type = self.comment_type.clone();
+                        comment.updated_at = Some(chrono::Utc::now());
                         message = if let Some((line, _)) = self.comment_line {
                             format!("Comment on line {line} updated")
                         } else {
@@ -2961,62 +5213,136 @@ impl App {
                 }
             }
         } else if self.comment_is_review_level {
-            let comment = Comment::new(content, self.comment_type.clone(), None);
-            self.session.review_comments.push(comment);
-            message = "Review comment added".to_string();
+            let mut comment = Comment::new(content, self.comment_type.clone(), None);
+            comment.parent_id = self.reply_to.clone();
+            comment.author = self.comment_author.clone();
+            undo = Some((
+                "add review comment".to_string(),
+                UndoAction::RemoveComment {
+                    anchor: CommentAnchor::Review,
+                    id: comment.id.clone(),
+                },
+            ));
+            insert_threaded(&mut self.session.review_comments, comment);
+            saved_at = Some(CommentAnchor::Review);
+            message = if self.reply_to.is_some() {
+                "Reply added".to_string()
+            } else {
+                "Review comment added".to_string()
+            };
         } else if let Some(path) = self.current_file_path().cloned()
+            && let fingerprint = self.new_comment_fingerprint(&path)
             && let Some(review) = self.session.get_file_mut(&path)
         {
             // Create new comment
-            if self.comment_is_file_level {
+            let (anchor, mut comment) = if self.comment_is_file_level {
                 let comment = Comment::new(content, self.comment_type.clone(), None);
-                review.add_file_comment(comment);
                 message = "File comment added".to_string();
+                (CommentAnchor::File(path.clone()), comment)
             } else if let Some((range, side)) = self.comment_line_range {
                 // Range comment from visual selection
                 let comment =
                     Comment::new_with_range(content, self.comment_type.clone(), Some(side), range);
-                // Store by end line of the range
-                review.add_line_comment(range.end, comment);
                 if range.is_single() {
                     message = format!("Comment added to line {}", range.end);
                 } else {
                     message = format!("Comment added to lines {}-{}", range.start, range.end);
                 }
+                // Store by end line of the range
+                (CommentAnchor::Line(path.clone(), range.end), comment)
             } else if let Some((line, side)) = self.comment_line {
                 let comment = Comment::new(content, self.comment_type.clone(), Some(side));
-                review.add_line_comment(line, comment);
                 message = format!("Comment added to line {line}");
+                (CommentAnchor::Line(path.clone(), line), comment)
             } else {
                 // Fallback to file comment if no line specified
                 let comment = Comment::new(content, self.comment_type.clone(), None);
-                review.add_file_comment(comment);
                 message = "File comment added".to_string();
+                (CommentAnchor::File(path.clone()), comment)
+            };
+            let description = match &anchor {
+                CommentAnchor::Line(_, line) => format!("add comment on line {line}"),
+                _ => "add file comment".to_string(),
+            };
+            comment.author = self.comment_author.clone();
+            comment.fingerprint = fingerprint;
+            if self.reply_to.is_some() {
+                comment.parent_id = self.reply_to.clone();
+                message = "Reply added".to_string();
             }
+            let id = comment.id.clone();
+            match anchor {
+                CommentAnchor::Line(_, line) => review.add_line_comment(line, comment),
+                _ => review.add_file_comment(comment),
+            }
+            saved_at = Some(anchor.clone());
+            undo = Some((description, UndoAction::RemoveComment { anchor, id }));
         }
 
         if !message.starts_with("Error:") {
             self.dirty = true;
         }
+        if let Some((description, action)) = undo {
+            self.undo_history.record(description, action);
+        }
         self.set_message(message);
-        self.rebuild_annotations();
+        if let Some(anchor) = saved_at {
+            self.rebuild_annotations_at(&anchor);
+        }
 
         self.exit_comment_mode();
     }
 
     pub fn cycle_comment_type(&mut self) {
+        if let Some(next) = self.next_comment_type(&self.comment_type) {
+            self.comment_type = next;
+        }
+    }
+
+    /// The comment type after `current` in the configured order, wrapping around.
+    fn next_comment_type(&self, current: &CommentType) -> Option<CommentType> {
         if self.comment_types.is_empty() {
-            return;
+            return None;
         }
 
-        let current_id = self.comment_type.id();
+        let current_id = current.id();
         let current_index = self
             .comment_types
             .iter()
             .position(|comment_type| comment_type.id == current_id)
             .unwrap_or(0);
         let next_index = (current_index + 1) % self.comment_types.len();
-        self.comment_type = CommentType::from_id(&self.comment_types[next_index].id);
+        Some(CommentType::from_id(&self.comment_types[next_index].id))
+    }
+
+    /// Switch the comment under the cursor to the next comment type, keeping its text.
+    pub fn cycle_comment_type_at_cursor(&mut self) {
+        let Some(location) = self.find_comment_at_cursor() else {
+            self.set_message("No comment at cursor");
+            return;
+        };
+        let Some(comment) = self.comment_at(&location) else {
+            return;
+        };
+        let Some(comment_type) = self.next_comment_type(&comment.comment_type) else {
+            return;
+        };
+        let change = UndoAction::SetComment {
+            id: comment.id.clone(),
+            content: comment.content.clone(),
+            comment_type: comment_type.clone(),
+        };
+        let Some(revert) = change.apply(&mut self.session) else {
+            return;
+        };
+
+        self.undo_history.record("change comment type", revert);
+        self.dirty = true;
+        self.rebuild_annotations_at(&location.anchor());
+        self.set_message(format!(
+            "Comment type: {}",
+            self.comment_type_label(&comment_type)
+        ));
     }
 
     pub fn cycle_comment_type_reverse(&mut self) {
@@ -3077,22 +5403,362 @@ impl App {
     }
 
     pub fn exit_confirm_mode(&mut self) {
-        self.input_mode = InputMode::Normal;
+        self.input_mode = match self.pending_confirm {
+            Some(ConfirmAction::DeleteSession) => InputMode::SessionBrowser,
+            _ => InputMode::Normal,
+        };
         self.pending_confirm = None;
     }
 
+    pub fn confirm_message(&self) -> String {
+        match self.pending_confirm {
+            Some(ConfirmAction::DeleteSession) => {
+                match self
+                    .session_browser
+                    .entries
+                    .get(self.session_browser.selected)
+                {
+                    Some(entry) => format!(
+                        "Delete session on {}?",
+                        entry.session.branch_name.as_deref().unwrap_or("detached")
+                    ),
+                    None => "Delete session?".to_string(),
+                }
+            }
+            Some(ConfirmAction::DiscardHunk { file_idx, hunk_idx }) => {
+                let Some(file) = self.diff_files.get(file_idx) else {
+                    return "Discard hunk?".to_string();
+                };
+                match file.hunks.get(hunk_idx) {
+                    Some(hunk) if hunk.new_count > 0 => format!(
+                        "Discard lines {}-{} of {}?",
+                        hunk.new_start,
+                        hunk.new_start + hunk.new_count - 1,
+                        file.display_path().display()
+                    ),
+                    _ => format!("Discard hunk in {}?", file.display_path().display()),
+                }
+            }
+            Some(ConfirmAction::DeleteListedComment) => match self.selected_comment_list_entry() {
+                Some(CommentListEntry {
+                    path,
+                    line: Some((line, _)),
+                    ..
+                }) => format!("Delete comment on {}:{line}?", path.display()),
+                Some(entry) => format!("Delete comment on {}?", entry.path.display()),
+                None => "Delete comment?".to_string(),
+            },
+            Some(ConfirmAction::CopyAndQuit) | None => "Copy review to clipboard?".to_string(),
+        }
+    }
+
+    pub fn open_session_browser(&mut self) -> Result<()> {
+        let sessions = list_sessions_for_repo(&self.vcs_info.root_path)?;
+        let entries: Vec<SessionBrowserEntry> = sessions
+            .into_iter()
+            .map(|(path, session)| {
+                let stale = self.is_session_stale(&session);
+                SessionBrowserEntry {
+                    path,
+                    session,
+                    stale,
+                }
+            })
+            .collect();
+        let selected = entries
+            .iter()
+            .position(|entry| entry.session.id == self.session.id)
+            .unwrap_or(0);
+
+        self.session_browser = SessionBrowserState { entries, selected };
+        self.input_mode = InputMode::SessionBrowser;
+        Ok(())
+    }
+
+    pub fn close_session_browser(&mut self) {
+        self.session_browser = SessionBrowserState::default();
+        self.input_mode = InputMode::Normal;
+    }
+
+    pub fn session_browser_down(&mut self, n: usize) {
+        let last = self.session_browser.entries.len().saturating_sub(1);
+        self.session_browser.selected = (self.session_browser.selected + n).min(last);
+    }
+
+    pub fn session_browser_up(&mut self, n: usize) {
+        self.session_browser.selected = self.session_browser.selected.saturating_sub(n);
+    }
+
+    /// List local branches for `:branches`, selecting the current one.
+    pub fn open_branch_picker(&mut self) -> Result<()> {
+        let branches = self.vcs.list_branches()?;
+        if branches.is_empty() {
+            self.set_warning("No branches");
+            return Ok(());
+        }
+        let selected = branches
+            .iter()
+            .position(|branch| branch.is_current)
+            .unwrap_or(0);
+        self.branch_picker = BranchPickerState { branches, selected };
+        self.input_mode = InputMode::BranchPicker;
+        Ok(())
+    }
+
+    pub fn close_branch_picker(&mut self) {
+        self.branch_picker = BranchPickerState::default();
+        self.input_mode = InputMode::Normal;
+    }
+
+    pub fn branch_picker_down(&mut self, n: usize) {
+        let last = self.branch_picker.branches.len().saturating_sub(1);
+        self.branch_picker.selected = (self.branch_picker.selected + n).min(last);
+    }
+
+    pub fn branch_picker_up(&mut self, n: usize) {
+        self.branch_picker.selected = self.branch_picker.selected.saturating_sub(n);
+    }
+
+    /// Review the commits on the selected branch since it forked from the
+    /// main line. The picker stays open if they can't be loaded.
+    pub fn load_selected_branch(&mut self) {
+        let Some(branch) = self
+            .branch_picker
+            .branches
+            .get(self.branch_picker.selected)
+            .cloned()
+        else {
+            return;
+        };
+        if self.dirty {
+            self.set_error("No write since last change (:w first)");
+            return;
+        }
+        match self.diff_against_revisions(&branch.revisions) {
+            Ok(()) => {
+                self.close_branch_picker();
+                let count = self.review_commits.len();
+                let noun = if count == 1 { "commit" } else { "commits" };
+                self.set_message(format!("Reviewing {} ({count} {noun})", branch.name));
+            }
+            Err(TuicrError::NoChanges) => {
+                self.set_warning(format!("{} has no commits of its own", branch.name));
+            }
+            Err(e) => self.set_error(format!("Failed to load {}: {e}", branch.name)),
+        }
+    }
+
+    /// A session is stale when the commits it was reviewing can no longer be found.
+    fn is_session_stale(&self, session: &ReviewSession) -> bool {
+        match session.diff_source {
+            SessionDiffSource::WorkingTree
+            | SessionDiffSource::Staged
+            | SessionDiffSource::Unstaged
+            | SessionDiffSource::StagedAndUnstaged => false,
+            SessionDiffSource::CommitRange
+            | SessionDiffSource::WorkingTreeAndCommits
+            | SessionDiffSource::StagedUnstagedAndCommits => match &session.commit_range {
+                Some(range) => self.vcs.get_commits_info(range).is_err(),
+                // PR sessions don't record their range
+                None => true,
+            },
+        }
+    }
+
+    /// Fetch the diff a saved session was created against, along with the
+    /// commits it covers (oldest first).
+    fn resolve_session_diff(
+        &self,
+        session: &ReviewSession,
+    ) -> Result<(Vec<DiffFile>, DiffSource, Vec<CommitInfo>)> {
+        let vcs = self.vcs.as_ref();
+        let root = &self.vcs_info.root_path;
+        let path_filter = &self.path_filter;
+
+        let commit_range = || {
+            session.commit_range.clone().ok_or_else(|| {
+                TuicrError::CorruptedSession("session has no commit range".to_string())
+            })
+        };
+
+        match session.diff_source {
+            SessionDiffSource::WorkingTree => Ok((
+                Self::get_working_tree_diff_with_ignore(vcs, root, path_filter)?,
+                DiffSource::WorkingTree,
+                Vec::new(),
+            )),
+            SessionDiffSource::Staged => Ok((
+                Self::get_staged_diff_with_ignore(vcs, root, path_filter)?,
+                DiffSource::Staged,
+                Vec::new(),
+            )),
+            SessionDiffSource::Unstaged => Ok((
+                Self::get_unstaged_diff_with_ignore(vcs, root, path_filter)?,
+                DiffSource::Unstaged,
+                Vec::new(),
+            )),
+            SessionDiffSource::StagedAndUnstaged => Ok((
+                Self::get_working_tree_diff_with_ignore(vcs, root, path_filter)?,
+                DiffSource::StagedAndUnstaged,
+                Vec::new(),
+            )),
+            SessionDiffSource::CommitRange => {
+                let range = commit_range()?;
+                let diff_files =
+                    Self::get_commit_range_diff_with_ignore(vcs, root, &range, path_filter)?;
+                let commits = vcs.get_commits_info(&range)?;
+                Ok((diff_files, DiffSource::CommitRange(range), commits))
+            }
+            SessionDiffSource::WorkingTreeAndCommits
+            | SessionDiffSource::StagedUnstagedAndCommits => {
+                let range = commit_range()?;
+                let diff_files = Self::get_working_tree_with_commits_diff_with_ignore(
+                    vcs,
+                    root,
+                    &range,
+                    path_filter,
+                )?;
+                let commits = vcs.get_commits_info(&range)?;
+                Ok((
+                    diff_files,
+                    DiffSource::StagedUnstagedAndCommits(range),
+                    commits,
+                ))
+            }
+        }
+    }
+
+    /// Switch to the session selected in the session browser, re-fetching the
+    /// diff it was reviewing. Stale sessions keep the current diff, dropping
+    /// their files that aren't part of it.
+    pub fn load_selected_session(&mut self) {
+        let Some(entry) = self
+            .session_browser
+            .entries
+            .get(self.session_browser.selected)
+            .cloned()
+        else {
+            return;
+        };
+
+        if entry.session.id == self.session.id {
+            self.close_session_browser();
+            self.set_message("Already on this session");
+            return;
+        }
+
+        if self.dirty {
+            self.set_error("No write since last change (:w first)");
+            return;
+        }
+
+        let resolved = if entry.stale {
+            Err(TuicrError::VcsCommand(
+                "commits no longer resolve".to_string(),
+            ))
+        } else {
+            self.resolve_session_diff(&entry.session)
+        };
+
+        self.close_session_browser();
+        self.replace_session(entry.session);
+
+        match resolved {
+            Ok((diff_files, diff_source, commits)) => {
+                self.diff_files = diff_files;
+                self.diff_source = diff_source;
+                self.set_review_commits(commits.into_iter().rev().collect());
+                self.set_message(format!(
+                    "Loaded session from {}",
+                    self.session.updated_at.format("%Y-%m-%d %H:%M")
+                ));
+            }
+            Err(e) => {
+                let in_diff: HashSet<&PathBuf> =
+                    self.diff_files.iter().map(DiffFile::display_path).collect();
+                let before = self.session.files.len();
+                self.session.files.retain(|path, _| in_diff.contains(path));
+                let dropped = before - self.session.files.len();
+                let mut warning = format!("Loaded stale session, keeping current diff: {e}");
+                if dropped > 0 {
+                    let noun = if dropped == 1 { "file" } else { "files" };
+                    warning.push_str(&format!(" ({dropped} {noun} outside it dropped)"));
+                }
+                self.set_warning(warning);
+            }
+        }
+
+        for file in &self.diff_files {
+            let path = file.display_path().clone();
+            self.session.add_file(path, file.status, file.content_hash);
+        }
+
+        self.diff_state = DiffState::default();
+        self.file_list_state = FileListState::default();
+        self.clear_expanded_gaps();
+        self.sort_files_by_directory(true);
+        self.expand_all_dirs();
+        self.rebuild_annotations();
+    }
+
+    /// Delete the file of the session selected in the session browser.
+    pub fn delete_selected_session(&mut self) {
+        let selected = self.session_browser.selected;
+        let Some(entry) = self.session_browser.entries.get(selected) else {
+            return;
+        };
+
+        if entry.session.id == self.session.id {
+            self.set_error("Cannot delete the active session");
+            return;
+        }
+
+        match delete_session(&entry.path) {
+            Ok(()) => {
+                self.session_browser.entries.remove(selected);
+                self.session_browser_up(usize::from(
+                    selected >= self.session_browser.entries.len(),
+                ));
+                self.set_message("Session deleted");
+            }
+            Err(e) => self.set_error(format!("Delete failed: {e}")),
+        }
+    }
+
+    /// Start an empty session for the current diff.
+    pub fn start_new_session(&mut self) {
+        if self.dirty {
+            self.set_error("No write since last change (:w first)");
+            return;
+        }
+
+        let mut session = ReviewSession::new(
+            self.session.repo_path.clone(),
+            self.session.base_commit.clone(),
+            self.session.branch_name.clone(),
+            self.session.diff_source,
+        );
+        session.commit_range = self.session.commit_range.clone();
+        for file in &self.diff_files {
+            session.add_file(file.display_path().clone(), file.status, file.content_hash);
+        }
+
+        self.close_session_browser();
+        self.replace_session(session);
+        self.rebuild_annotations();
+        self.set_message("Started new session");
+    }
+
     pub fn enter_commit_select_mode(&mut self) -> Result<()> {
         // Save inline selection state if we have review commits
         if !self.review_commits.is_empty() {
             self.saved_inline_selection = self.commit_selection_range;
         }
 
-        let highlighter = self.theme.syntax_highlighter();
         let has_staged_changes = match Self::get_staged_diff_with_ignore(
             self.vcs.as_ref(),
             &self.vcs_info.root_path,
-            highlighter,
-            self.path_filter.as_deref(),
+            &self.path_filter,
         ) {
             Ok(_) => true,
             Err(TuicrError::NoChanges) => false,
@@ -3103,8 +5769,7 @@ impl App {
         let has_unstaged_changes = match Self::get_unstaged_diff_with_ignore(
             self.vcs.as_ref(),
             &self.vcs_info.root_path,
-            highlighter,
-            self.path_filter.as_deref(),
+            &self.path_filter,
         ) {
             Ok(_) => true,
             Err(TuicrError::NoChanges) => false,
@@ -3112,14 +5777,14 @@ impl App {
             Err(e) => return Err(e),
         };
 
-        let commits = self.vcs.get_recent_commits(0, VISIBLE_COMMIT_COUNT)?;
+        let commits = self.vcs.get_recent_commits(0, self.commit_depth)?;
         if commits.is_empty() && !has_staged_changes && !has_unstaged_changes {
             self.set_message("No commits or staged/unstaged changes found");
             return Ok(());
         }
 
         // Check if there might be more commits
-        self.has_more_commit = commits.len() >= VISIBLE_COMMIT_COUNT;
+        self.has_more_commit = commits.len() >= self.commit_depth;
         self.commit_list = commits;
         if has_staged_changes {
             self.commit_list.insert(0, Self::staged_commit_entry());
@@ -3136,8 +5801,11 @@ impl App {
     }
 
     pub fn enter_pr_mode(&mut self, base_ref: Option<&str>) -> Result<()> {
-        let highlighter = self.theme.syntax_highlighter();
-        let pr_diff = self.vcs.get_pull_request_diff(base_ref, highlighter)?;
+        let mut pr_diff = self.vcs.get_pull_request_diff(base_ref)?;
+        pr_diff.files = Self::filter_by_paths(
+            Self::filter_ignored_diff_files(&self.vcs_info.root_path, pr_diff.files),
+            &self.path_filter,
+        );
 
         let mut session = ReviewSession::new(
             self.vcs_info.root_path.clone(),
@@ -3150,7 +5818,7 @@ impl App {
             session.add_file(file.display_path().clone(), file.status, file.content_hash);
         }
 
-        self.session = session;
+        self.replace_session(session);
         self.diff_files = pr_diff.files;
         self.diff_source = DiffSource::PullRequest {
             base_ref: pr_diff.info.base_ref,
@@ -3181,46 +5849,148 @@ impl App {
         Ok(())
     }
 
-    pub fn exit_commit_select_mode(&mut self) -> Result<()> {
-        self.input_mode = InputMode::Normal;
+    /// `:diff <revset>`: review the commits `revisions` resolves to, as `-r`
+    /// does at startup. Nothing changes if the revset or its diff fails.
+    pub fn diff_against_revisions(&mut self, revisions: &str) -> Result<()> {
+        if self.dirty {
+            self.set_error("No write since last change (:w first)");
+            return Ok(());
+        }
+        let commit_ids = self.vcs.resolve_revisions(revisions)?;
+        self.load_commit_range(commit_ids)?;
 
-        // If we have review commits, restore the inline selector state
-        if !self.review_commits.is_empty() {
-            self.commit_list = self.review_commits.clone();
-            self.commit_selection_range = self.saved_inline_selection;
-            self.commit_list_cursor = 0;
-            self.commit_list_scroll_offset = 0;
-            self.visible_commit_count = self.review_commits.len();
-            self.has_more_commit = false;
-            self.saved_inline_selection = None;
+        let noun = if self.review_commits.len() == 1 {
+            "commit"
+        } else {
+            "commits"
+        };
+        self.set_message(format!(
+            "Diffing {revisions} ({} {noun})",
+            self.review_commits.len()
+        ));
+        Ok(())
+    }
 
-            // Reload diff for the restored selection
-            if self.commit_selection_range.is_some() {
-                self.reload_inline_selection()?;
-            }
+    /// `:show <rev>`: review the single commit `rev` resolves to, with its
+    /// message. Nothing changes if it doesn't resolve to exactly one commit.
+    pub fn show_revision(&mut self, rev: &str) -> Result<()> {
+        if self.dirty {
+            self.set_error("No write since last change (:w first)");
             return Ok(());
         }
+        let commit_ids = self.vcs.resolve_revisions(rev)?;
+        if commit_ids.len() != 1 {
+            self.set_error(format!(
+                "{rev} is {} commits; use :diff for a range",
+                commit_ids.len()
+            ));
+            return Ok(());
+        }
+        self.load_commit_range(commit_ids)?;
 
-        // If we were viewing commits, try to go back to working tree
-        if matches!(
-            self.diff_source,
-            DiffSource::CommitRange(_)
-                | DiffSource::WorkingTreeAndCommits(_)
-                | DiffSource::StagedUnstagedAndCommits(_)
-                | DiffSource::PullRequest { .. }
-        ) {
-            let highlighter = self.theme.syntax_highlighter();
-            match Self::get_working_tree_diff_with_ignore(
-                self.vcs.as_ref(),
-                &self.vcs_info.root_path,
-                highlighter,
-                self.path_filter.as_deref(),
-            ) {
-                Ok(diff_files) => {
-                    self.diff_files = diff_files;
-                    self.diff_source = DiffSource::StagedAndUnstaged;
-
-                    // Update session for new files
+        if let Some(commit) = self.review_commits.first() {
+            let message = format!("Showing {} {}", commit.short_id, commit.summary);
+            self.set_message(message);
+        }
+        Ok(())
+    }
+
+    /// Replace the diff with the commits `commit_ids` (oldest first) and
+    /// their session. The diff is loaded first, so an error changes nothing.
+    fn load_commit_range(&mut self, commit_ids: Vec<String>) -> Result<()> {
+        let diff_files = Self::get_commit_range_diff_with_ignore(
+            self.vcs.as_ref(),
+            &self.vcs_info.root_path,
+            &commit_ids,
+            &self.path_filter,
+        )?;
+        // Reverse to newest-first display order
+        let review_commits: Vec<CommitInfo> = self
+            .vcs
+            .get_commits_info(&commit_ids)?
+            .into_iter()
+            .rev()
+            .collect();
+
+        self.replace_session(Self::load_or_create_commit_range_session(
+            &self.vcs_info,
+            &commit_ids,
+        ));
+        for file in &diff_files {
+            let path = file.display_path().clone();
+            self.session.add_file(path, file.status, file.content_hash);
+        }
+
+        self.diff_files = diff_files;
+        self.diff_source = DiffSource::CommitRange(commit_ids);
+        self.input_mode = InputMode::Normal;
+
+        let wrap = self.diff_state.wrap_lines;
+        self.diff_state = DiffState::default();
+        self.diff_state.wrap_lines = wrap;
+        self.file_list_state = FileListState::default();
+        self.clear_expanded_gaps();
+
+        self.set_review_commits(review_commits);
+        self.insert_commit_message_if_single();
+        self.sort_files_by_directory(true);
+        self.expand_all_dirs();
+        self.rebuild_annotations();
+        Ok(())
+    }
+
+    /// `:diff worktree`: back to the staged and unstaged changes.
+    pub fn diff_against_worktree(&mut self) -> Result<()> {
+        if self.dirty {
+            self.set_error("No write since last change (:w first)");
+            return Ok(());
+        }
+        self.load_staged_and_unstaged_selection()?;
+        if self.diff_source == DiffSource::StagedAndUnstaged {
+            self.set_review_commits(Vec::new());
+            self.set_message("Diffing the working tree");
+        }
+        Ok(())
+    }
+
+    pub fn exit_commit_select_mode(&mut self) -> Result<()> {
+        self.input_mode = InputMode::Normal;
+
+        // If we have review commits, restore the inline selector state
+        if !self.review_commits.is_empty() {
+            self.commit_list = self.review_commits.clone();
+            self.commit_selection_range = self.saved_inline_selection;
+            self.commit_list_cursor = 0;
+            self.commit_list_scroll_offset = 0;
+            self.visible_commit_count = self.review_commits.len();
+            self.has_more_commit = false;
+            self.saved_inline_selection = None;
+
+            // Reload diff for the restored selection
+            if self.commit_selection_range.is_some() {
+                self.reload_inline_selection()?;
+            }
+            return Ok(());
+        }
+
+        // If we were viewing commits, try to go back to working tree
+        if matches!(
+            self.diff_source,
+            DiffSource::CommitRange(_)
+                | DiffSource::WorkingTreeAndCommits(_)
+                | DiffSource::StagedUnstagedAndCommits(_)
+                | DiffSource::PullRequest { .. }
+        ) {
+            match Self::get_working_tree_diff_with_ignore(
+                self.vcs.as_ref(),
+                &self.vcs_info.root_path,
+                &self.path_filter,
+            ) {
+                Ok(diff_files) => {
+                    self.diff_files = diff_files;
+                    self.diff_source = DiffSource::StagedAndUnstaged;
+
+                    // Update session for new files
                     for file in &self.diff_files {
                         let path = file.display_path().clone();
                         self.session.add_file(path, file.status, file.content_hash);
@@ -3251,6 +6021,52 @@ impl App {
         self.rebuild_annotations();
     }
 
+    /// View mode of a file: its override if set, else the global mode.
+    pub fn file_view_mode(&self, file_idx: usize) -> DiffViewMode {
+        match self.diff_files.get(file_idx) {
+            // Prose has no old side, so side-by-side would only halve its width
+            Some(file) if file.is_commit_message => DiffViewMode::Unified,
+            file => file
+                .and_then(|file| self.file_view_modes.get(file.display_path()))
+                .copied()
+                .unwrap_or(self.diff_view_mode),
+        }
+    }
+
+    /// Switch the current file between unified and side-by-side without
+    /// touching the other files.
+    pub fn toggle_file_view_mode(&mut self) {
+        let file_idx = self.diff_state.current_file_idx;
+        let Some(file) = self.diff_files.get(file_idx) else {
+            return;
+        };
+        if file.is_commit_message {
+            self.set_message("The commit message is always shown unified");
+            return;
+        }
+        let path = file.display_path().clone();
+        let mode = match self.file_view_mode(file_idx) {
+            DiffViewMode::Unified => DiffViewMode::SideBySide,
+            DiffViewMode::SideBySide => DiffViewMode::Unified,
+        };
+        if mode == self.diff_view_mode {
+            self.file_view_modes.remove(&path);
+        } else {
+            self.file_view_modes.insert(path.clone(), mode);
+        }
+        let mode_name = match mode {
+            DiffViewMode::Unified => "unified",
+            DiffViewMode::SideBySide => "side-by-side",
+        };
+        self.set_message(format!("{}: {mode_name}", path.display()));
+        self.rebuild_file_annotations(file_idx);
+        // Side-by-side pairs lines up, so keep the cursor inside the file
+        if let Some(&end) = self.annotation_file_starts.get(file_idx + 1) {
+            self.diff_state.cursor_line = self.diff_state.cursor_line.min(end.saturating_sub(1));
+            self.ensure_cursor_visible();
+        }
+    }
+
     pub fn toggle_file_list(&mut self) {
         self.show_file_list = !self.show_file_list;
         if !self.show_file_list && self.focused_panel == FocusedPanel::FileList {
@@ -3264,6 +6080,103 @@ impl App {
         self.set_message(format!("File list: {status}"));
     }
 
+    /// Widen the file list by a step, or narrow it when `grow` is false, and
+    /// save the new width to the config file.
+    pub fn resize_file_list(&mut self, grow: bool) {
+        let step = if grow {
+            FILE_LIST_WIDTH_STEP
+        } else {
+            -FILE_LIST_WIDTH_STEP
+        };
+        let width = self
+            .file_list_width_percent
+            .saturating_add_signed(step)
+            .clamp(
+                *FILE_LIST_WIDTH_PERCENT_RANGE.start(),
+                *FILE_LIST_WIDTH_PERCENT_RANGE.end(),
+            );
+        self.file_list_width_percent = width;
+        self.set_message(format!("File list width: {width}%"));
+        self.save_panel_size("file_list_width", width);
+    }
+
+    /// Let the inline commit selector take one more row, or one fewer when
+    /// `grow` is false, and save the new height to the config file.
+    pub fn resize_commit_selector(&mut self, grow: bool) {
+        let height = self
+            .commit_selector_height
+            .saturating_add_signed(if grow { 1 } else { -1 })
+            .clamp(
+                *COMMIT_SELECTOR_HEIGHT_RANGE.start(),
+                *COMMIT_SELECTOR_HEIGHT_RANGE.end(),
+            );
+        self.commit_selector_height = height;
+        self.set_message(format!("Commit selector height: {height} rows"));
+        self.save_panel_size("commit_selector_height", height);
+    }
+
+    /// Switch to the theme called `name` and save it as `theme` in the config
+    /// file. The highlighter is cached per theme, so a fresh one is built and
+    /// every file is highlighted again.
+    pub fn set_theme(&mut self, name: &str) {
+        let name = name.trim().to_ascii_lowercase();
+        let Some(arg) = ThemeArg::from_str(&name) else {
+            self.set_warning(format!(
+                "Unknown theme '{name}'. Valid options: {}",
+                ThemeArg::valid_values_display()
+            ));
+            return;
+        };
+        if self.theme.monochrome {
+            self.set_warning("Colors are disabled (--no-color / NO_COLOR)");
+            return;
+        }
+
+        let mut theme = resolve_theme(arg);
+        theme.downsample(self.theme.color_mode);
+        let mut warnings = theme.set_syntax_overrides(&self.syntax_overrides);
+        theme.set_max_highlight_lines(self.theme.syntax_highlighter().max_lines());
+        // Default comment types carry the old theme's colors; configured ones keep theirs
+        for definition in &mut self.comment_types {
+            let (old, new) = match definition.id.as_str() {
+                "note" => (self.theme.comment_note, theme.comment_note),
+                "suggestion" => (self.theme.comment_suggestion, theme.comment_suggestion),
+                "issue" => (self.theme.comment_issue, theme.comment_issue),
+                "praise" => (self.theme.comment_praise, theme.comment_praise),
+                _ => continue,
+            };
+            if definition.color == Some(old) {
+                definition.color = Some(new);
+            }
+        }
+        self.theme = theme;
+
+        self.highlight_pool = None;
+        self.highlight_in_flight.clear();
+        for file in &mut self.diff_files {
+            file.highlighted = false;
+        }
+
+        if let Some(path) = &self.config_path
+            && let Err(e) = crate::config::save_config_value(path, "theme", &format!("\"{name}\""))
+        {
+            warnings.push(format!("Failed to save theme to the config file: {e}"));
+        }
+        match warnings.into_iter().next() {
+            Some(warning) => self.set_warning(warning),
+            None => self.set_message(format!("Theme: {name}")),
+        }
+    }
+
+    fn save_panel_size(&mut self, key: &str, value: u16) {
+        let Some(path) = &self.config_path else {
+            return;
+        };
+        if let Err(e) = crate::config::save_config_value(path, key, &value.to_string()) {
+            self.set_warning(format!("Failed to save {key} to the config file: {e}"));
+        }
+    }
+
     /// Whether the inline commit selector panel should be displayed.
     pub fn has_inline_commit_selector(&self) -> bool {
         self.show_commit_selector
@@ -3271,6 +6184,120 @@ impl App {
             && !matches!(&self.diff_source, DiffSource::WorkingTree)
     }
 
+    /// Compute diffstats for up to [`COMMIT_STATS_BATCH`] commits of the inline
+    /// selector that don't have one yet. Called once per frame, so the stats
+    /// fill in without blocking; if a batch exceeds [`COMMIT_STATS_BUDGET`] or
+    /// the backend can't compute them, stats are dropped and left blank.
+    pub fn load_commit_stats(&mut self) {
+        if self.commit_stats_disabled || !self.has_inline_commit_selector() {
+            return;
+        }
+        let missing: Vec<String> = self
+            .review_commits
+            .iter()
+            .filter(|commit| {
+                !Self::is_special_commit(commit) && !self.commit_stats.contains_key(&commit.id)
+            })
+            .take(COMMIT_STATS_BATCH)
+            .map(|commit| commit.id.clone())
+            .collect();
+        if missing.is_empty() {
+            return;
+        }
+
+        let started = Instant::now();
+        for id in missing {
+            let Ok(stat) = self.vcs.commit_diffstat(&id) else {
+                self.disable_commit_stats();
+                return;
+            };
+            self.commit_stats.insert(id, stat);
+            if started.elapsed() > COMMIT_STATS_BUDGET {
+                self.disable_commit_stats();
+                return;
+            }
+        }
+    }
+
+    fn disable_commit_stats(&mut self) {
+        self.commit_stats_disabled = true;
+        self.commit_stats.clear();
+    }
+
+    /// Summed diffstat of the selected commits, when that's a strict subset
+    /// of the review and every selected commit has its stats loaded.
+    pub fn selected_commits_stat(&self) -> Option<DiffStat> {
+        let (start, end) = self.commit_selection_range?;
+        if end - start + 1 >= self.review_commits.len() {
+            return None;
+        }
+        let mut total = DiffStat::default();
+        for commit in self.review_commits.get(start..=end)? {
+            total += *self.commit_stats.get(&commit.id)?;
+        }
+        Some(total)
+    }
+
+    /// What the diff shows, for the header, e.g. `Commits abc1234..def5678 (3)`.
+    pub fn diff_source_label(&self) -> String {
+        match &self.diff_source {
+            DiffSource::WorkingTree => "Working Tree".to_string(),
+            DiffSource::Staged => "Staged".to_string(),
+            DiffSource::Unstaged => "Unstaged".to_string(),
+            DiffSource::StagedAndUnstaged => "Staged + Unstaged".to_string(),
+            DiffSource::CommitRange(ids) => self.commits_label(ids),
+            DiffSource::WorkingTreeAndCommits(ids) => {
+                format!("Working Tree + {}", self.commits_label(ids))
+            }
+            DiffSource::StagedUnstagedAndCommits(ids) => {
+                format!("Staged + Unstaged + {}", self.commits_label(ids))
+            }
+            DiffSource::PullRequest {
+                base_ref,
+                commit_count,
+                ..
+            } => {
+                let noun = if *commit_count == 1 {
+                    "commit"
+                } else {
+                    "commits"
+                };
+                format!("PR vs {base_ref} ({commit_count} {noun})")
+            }
+        }
+    }
+
+    /// `Commit abc1234` or `Commits abc1234..def5678 (3)`, narrowed to the
+    /// inline selector's commits when only part of the review is selected.
+    fn commits_label(&self, ids: &[String]) -> String {
+        let subrange: Vec<String> = match self.commit_selection_range {
+            Some((start, end)) if end - start + 1 < self.review_commits.len() => self
+                .review_commits
+                .get(start..=end)
+                .unwrap_or_default()
+                .iter()
+                .rev()
+                .filter(|commit| !Self::is_special_commit(commit))
+                .map(|commit| commit.id.clone())
+                .collect(),
+            _ => Vec::new(),
+        };
+        let selected = if subrange.is_empty() { ids } else { &subrange };
+
+        let short = |id: &str| id.chars().take(7).collect::<String>();
+        let count = if selected.len() < ids.len() {
+            format!("{} of {}", selected.len(), ids.len())
+        } else {
+            selected.len().to_string()
+        };
+        match selected {
+            [] => "Commits (0)".to_string(),
+            [id] if ids.len() == 1 => format!("Commit {}", short(id)),
+            [id] => format!("Commit {} (1 of {})", short(id), ids.len()),
+            [first, .., last] => format!("Commits {}..{} ({count})", short(first), short(last)),
+        }
+    }
+
     // Commit selection methods
 
     pub fn commit_select_up(&mut self) {
@@ -3506,13 +6533,11 @@ impl App {
         }
 
         // Get the diff for the selected commits
-        let highlighter = self.theme.syntax_highlighter();
         let diff_files = Self::get_commit_range_diff_with_ignore(
             self.vcs.as_ref(),
             &self.vcs_info.root_path,
             &selected_ids,
-            highlighter,
-            self.path_filter.as_deref(),
+            &self.path_filter,
         )?;
 
         if diff_files.is_empty() {
@@ -3549,6 +6574,7 @@ impl App {
         }
 
         self.session = session;
+        self.undo_history.clear();
 
         // Add files to session
         for file in &diff_files {
@@ -3653,14 +6679,12 @@ impl App {
             .map(|c| c.id.clone())
             .collect();
 
-        let highlighter = self.theme.syntax_highlighter();
         let diff_files = if (has_staged || has_unstaged) && !selected_ids.is_empty() {
             match Self::get_working_tree_with_commits_diff_with_ignore(
                 self.vcs.as_ref(),
                 &self.vcs_info.root_path,
                 &selected_ids,
-                highlighter,
-                self.path_filter.as_deref(),
+                &self.path_filter,
             ) {
                 Ok(files) => files,
                 Err(TuicrError::NoChanges) => Vec::new(),
@@ -3670,8 +6694,7 @@ impl App {
             match Self::get_working_tree_diff_with_ignore(
                 self.vcs.as_ref(),
                 &self.vcs_info.root_path,
-                highlighter,
-                self.path_filter.as_deref(),
+                &self.path_filter,
             ) {
                 Ok(files) => files,
                 Err(TuicrError::NoChanges) => Vec::new(),
@@ -3681,8 +6704,7 @@ impl App {
             match Self::get_staged_diff_with_ignore(
                 self.vcs.as_ref(),
                 &self.vcs_info.root_path,
-                highlighter,
-                self.path_filter.as_deref(),
+                &self.path_filter,
             ) {
                 Ok(files) => files,
                 Err(TuicrError::NoChanges) => Vec::new(),
@@ -3692,8 +6714,7 @@ impl App {
             match Self::get_unstaged_diff_with_ignore(
                 self.vcs.as_ref(),
                 &self.vcs_info.root_path,
-                highlighter,
-                self.path_filter.as_deref(),
+                &self.path_filter,
             ) {
                 Ok(files) => files,
                 Err(TuicrError::NoChanges) => Vec::new(),
@@ -3704,8 +6725,7 @@ impl App {
                 self.vcs.as_ref(),
                 &self.vcs_info.root_path,
                 &selected_ids,
-                highlighter,
-                self.path_filter.as_deref(),
+                &self.path_filter,
             ) {
                 Ok(files) => files,
                 Err(TuicrError::NoChanges) => Vec::new(),
@@ -3736,13 +6756,11 @@ impl App {
         selected_ids: Vec<String>,
         selected_commits: Vec<CommitInfo>,
     ) -> Result<()> {
-        let highlighter = self.theme.syntax_highlighter();
         let diff_files = match Self::get_working_tree_with_commits_diff_with_ignore(
             self.vcs.as_ref(),
             &self.vcs_info.root_path,
             &selected_ids,
-            highlighter,
-            self.path_filter.as_deref(),
+            &self.path_filter,
         ) {
             Ok(diff_files) => diff_files,
             Err(TuicrError::NoChanges) => {
@@ -3752,8 +6770,10 @@ impl App {
             Err(e) => return Err(e),
         };
 
-        self.session =
-            Self::load_or_create_staged_unstaged_and_commits_session(&self.vcs_info, &selected_ids);
+        self.replace_session(Self::load_or_create_staged_unstaged_and_commits_session(
+            &self.vcs_info,
+            &selected_ids,
+        ));
 
         for file in &diff_files {
             let path = file.display_path().clone();
@@ -3767,7 +6787,19 @@ impl App {
         self.file_list_state = FileListState::default();
 
         // Set up inline commit selector (newest-first display order)
-        self.review_commits = selected_commits.into_iter().rev().collect();
+        self.set_review_commits(selected_commits.into_iter().rev().collect());
+
+        self.insert_commit_message_if_single();
+        self.sort_files_by_directory(true);
+        self.expand_all_dirs();
+        self.rebuild_annotations();
+        Ok(())
+    }
+
+    /// Set up the inline commit selector for `commits` (newest first), with
+    /// every commit selected and the current diff as the combined range diff.
+    fn set_review_commits(&mut self, commits: Vec<CommitInfo>) {
+        self.review_commits = commits;
         self.range_diff_files = Some(self.diff_files.clone());
         self.commit_list = self.review_commits.clone();
         self.commit_list_cursor = 0;
@@ -3782,18 +6814,17 @@ impl App {
         self.show_commit_selector = self.review_commits.len() > 1;
         self.commit_diff_cache.clear();
         self.saved_inline_selection = None;
-
-        self.insert_commit_message_if_single();
-        self.sort_files_by_directory(true);
-        self.expand_all_dirs();
-        self.rebuild_annotations();
-        Ok(())
     }
 
     fn sort_files_by_directory(&mut self, reset_position: bool) {
         use std::collections::BTreeMap;
         use std::path::Path;
 
+        // Every diff (re)load passes through here
+        if self.show_ignored {
+            self.refresh_ignored_files();
+        }
+
         let current_path = if !reset_position {
             self.current_file_path().cloned()
         } else {
@@ -3900,7 +6931,7 @@ impl App {
     }
 
     /// Look up an expanded context line by sequential index across top + bottom.
-    fn get_expanded_line(&self, gap_id: &GapId, idx: usize) -> Option<&DiffLine> {
+    pub fn get_expanded_line(&self, gap_id: &GapId, idx: usize) -> Option<&DiffLine> {
         let top = self.expanded_top.get(gap_id);
         let top_len = top.map_or(0, |v| v.len());
         if idx < top_len {
@@ -3917,16 +6948,28 @@ impl App {
         gap_id: GapId,
         direction: ExpandDirection,
         limit: Option<usize>,
+    ) -> Result<()> {
+        self.fetch_gap_context(&gap_id, direction, limit)?;
+        self.rebuild_file_annotations(gap_id.file_idx);
+        Ok(())
+    }
+
+    /// Fetch context lines into a gap without rebuilding annotations.
+    fn fetch_gap_context(
+        &mut self,
+        gap_id: &GapId,
+        direction: ExpandDirection,
+        limit: Option<usize>,
     ) -> Result<()> {
         let (gap_start, gap_end) = self
-            .gap_boundaries(&gap_id)
+            .gap_boundaries(gap_id)
             .ok_or_else(|| TuicrError::CorruptedSession(format!("Invalid gap: {:?}", gap_id)))?;
 
         let file_path = self.diff_files[gap_id.file_idx].display_path().clone();
         let file_status = self.diff_files[gap_id.file_idx].status;
 
-        let top_len = self.expanded_top.get(&gap_id).map_or(0, |v| v.len()) as u32;
-        let bot_len = self.expanded_bottom.get(&gap_id).map_or(0, |v| v.len()) as u32;
+        let top_len = self.expanded_top.get(gap_id).map_or(0, |v| v.len()) as u32;
+        let bot_len = self.expanded_bottom.get(gap_id).map_or(0, |v| v.len()) as u32;
 
         // The unexpanded region runs from (gap_start + top_len) to (gap_end - bot_len)
         let inner_start = gap_start + top_len;
@@ -3961,7 +7004,7 @@ impl App {
                     inner_end,
                 )?;
                 // Prepend: new lines go before existing bottom lines
-                let existing = self.expanded_bottom.remove(&gap_id).unwrap_or_default();
+                let existing = self.expanded_bottom.remove(gap_id).unwrap_or_default();
                 let mut combined = new_lines;
                 combined.extend(existing);
                 self.expanded_bottom.insert(gap_id.clone(), combined);
@@ -3980,8 +7023,6 @@ impl App {
                     .extend(new_lines);
             }
         }
-
-        self.rebuild_annotations();
         Ok(())
     }
 
@@ -3989,7 +7030,76 @@ impl App {
     pub fn collapse_gap(&mut self, gap_id: GapId) {
         self.expanded_top.remove(&gap_id);
         self.expanded_bottom.remove(&gap_id);
-        self.rebuild_annotations();
+        self.rebuild_file_annotations(gap_id.file_idx);
+    }
+
+    /// Expand every gap in the current file (`:expand`). Gaps whose context
+    /// can't be fetched are skipped with a warning.
+    pub fn expand_all_gaps(&mut self) {
+        let file_idx = self.diff_state.current_file_idx;
+        let Some(file) = self.diff_files.get(file_idx) else {
+            return;
+        };
+        let gap_ids: Vec<GapId> = (0..file.hunks.len())
+            .map(|hunk_idx| GapId { file_idx, hunk_idx })
+            .filter(|gap_id| self.gap_boundaries(gap_id).is_some())
+            .collect();
+        if gap_ids.is_empty() {
+            self.set_message("No hidden context in this file");
+            return;
+        }
+
+        let failed = gap_ids
+            .iter()
+            .filter(|gap_id| {
+                self.fetch_gap_context(gap_id, ExpandDirection::Both, None)
+                    .is_err()
+            })
+            .count();
+        self.rebuild_gaps_keeping_cursor(file_idx);
+
+        if failed > 0 {
+            self.set_warning(format!(
+                "Expanded {} of {} gaps; failed to load context for {failed}",
+                gap_ids.len() - failed,
+                gap_ids.len()
+            ));
+        } else {
+            self.set_message(format!("Expanded {} gaps", gap_ids.len()));
+        }
+    }
+
+    /// Collapse every expanded gap in the current file (`:collapse`).
+    pub fn collapse_all_gaps(&mut self) {
+        let file_idx = self.diff_state.current_file_idx;
+        self.expanded_top
+            .retain(|gap_id, _| gap_id.file_idx != file_idx);
+        self.expanded_bottom
+            .retain(|gap_id, _| gap_id.file_idx != file_idx);
+        self.rebuild_gaps_keeping_cursor(file_idx);
+        self.set_message("Collapsed all gaps");
+    }
+
+    /// Rebuild a file's annotations after its gaps changed, keeping the
+    /// cursor on the same source line.
+    fn rebuild_gaps_keeping_cursor(&mut self, file_idx: usize) {
+        let cursor = self
+            .get_line_at_cursor()
+            .filter(|(_, side)| *side == LineSide::New)
+            .map(|(line, _)| line);
+        self.rebuild_file_annotations(file_idx);
+        if let Some(line) = cursor
+            && let FindSourceLineResult::Exact(idx) | FindSourceLineResult::Nearest(idx) =
+                find_source_line(&self.line_annotations, file_idx, line)
+        {
+            self.diff_state.cursor_line = idx;
+        } else {
+            self.diff_state.cursor_line = self
+                .diff_state
+                .cursor_line
+                .min(self.line_annotations.len().saturating_sub(1));
+        }
+        self.ensure_cursor_visible();
     }
 
     /// Clear all expanded gaps (called when reloading diffs)
@@ -4000,170 +7110,261 @@ impl App {
 
     /// Rebuild the line annotations cache. Call this when:
     /// - Diff files change (load/reload)
-    /// - Expansion state changes (expand/collapse gap)
-    /// - Comments are added/removed
     /// - Diff view mode changes
+    ///
+    /// Changes confined to one file (its comments, reviewed state or expanded
+    /// gaps) only need [`Self::rebuild_file_annotations`].
     pub fn rebuild_annotations(&mut self) {
-        self.line_annotations.clear();
+        let mut annotations = self.review_annotations();
+        let mut file_starts = Vec::with_capacity(self.diff_files.len() + 1);
+        for file_idx in 0..self.diff_files.len() {
+            file_starts.push(annotations.len());
+            annotations.extend(self.file_annotations(file_idx));
+        }
+        file_starts.push(annotations.len());
 
-        self.line_annotations
-            .push(AnnotatedLine::ReviewCommentsHeader);
+        self.line_annotations = annotations;
+        self.annotation_file_starts = file_starts;
+        self.scrollbar_ticks = None;
+        self.scrollbar_rows = None;
+    }
+
+    /// Regenerate the annotations of a single file, shifting those after it.
+    pub fn rebuild_file_annotations(&mut self, file_idx: usize) {
+        if self.annotation_file_starts.len() != self.diff_files.len() + 1 {
+            self.rebuild_annotations();
+            return;
+        }
+        let Some(&start) = self.annotation_file_starts.get(file_idx) else {
+            return;
+        };
+        let end = self.annotation_file_starts[file_idx + 1];
+        let annotations = self.file_annotations(file_idx);
+        let new_end = start + annotations.len();
+        self.line_annotations.splice(start..end, annotations);
+        for file_start in &mut self.annotation_file_starts[file_idx + 1..] {
+            *file_start = *file_start - end + new_end;
+        }
+        self.scrollbar_ticks = None;
+        self.scrollbar_rows = None;
+    }
+
+    /// Regenerate the review comment annotations at the top, shifting every file.
+    fn rebuild_review_annotations(&mut self) {
+        let Some(&end) = self.annotation_file_starts.first() else {
+            self.rebuild_annotations();
+            return;
+        };
+        let annotations = self.review_annotations();
+        let new_end = annotations.len();
+        self.line_annotations.splice(0..end, annotations);
+        for file_start in &mut self.annotation_file_starts {
+            *file_start = *file_start - end + new_end;
+        }
+        self.scrollbar_ticks = None;
+        self.scrollbar_rows = None;
+    }
+
+    /// Regenerate the annotations affected by a change to comments at `anchor`.
+    fn rebuild_annotations_at(&mut self, anchor: &CommentAnchor) {
+        match anchor {
+            CommentAnchor::Review => self.rebuild_review_annotations(),
+            CommentAnchor::File(path) | CommentAnchor::Line(path, _) => {
+                let file_indices: Vec<usize> = self
+                    .diff_files
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, file)| file.display_path() == path)
+                    .map(|(idx, _)| idx)
+                    .collect();
+                for file_idx in file_indices {
+                    self.rebuild_file_annotations(file_idx);
+                }
+            }
+        }
+    }
+
+    fn review_annotations(&self) -> Vec<AnnotatedLine> {
+        let mut out = vec![AnnotatedLine::ReviewCommentsHeader];
         for (comment_idx, comment) in self.session.review_comments.iter().enumerate() {
             let comment_lines = Self::comment_display_lines(comment);
             for _ in 0..comment_lines {
-                self.line_annotations
-                    .push(AnnotatedLine::ReviewComment { comment_idx });
+                out.push(AnnotatedLine::ReviewComment { comment_idx });
             }
         }
+        out
+    }
 
-        for (file_idx, file) in self.diff_files.iter().enumerate() {
-            let path = file.display_path();
+    fn file_annotations(&self, file_idx: usize) -> Vec<AnnotatedLine> {
+        let mut out = Vec::new();
+        let file = &self.diff_files[file_idx];
+        let path = file.display_path();
 
-            // File header
-            self.line_annotations
-                .push(AnnotatedLine::FileHeader { file_idx });
+        // File header
+        out.push(AnnotatedLine::FileHeader { file_idx });
 
-            // If reviewed, skip all content for this file
-            if self.session.is_file_reviewed(path) {
-                continue;
-            }
+        // If collapsed, skip all content for this file
+        if self.is_file_collapsed(file) {
+            return out;
+        }
 
-            // File comments
-            if let Some(review) = self.session.files.get(path) {
-                for (comment_idx, comment) in review.file_comments.iter().enumerate() {
-                    let comment_lines = Self::comment_display_lines(comment);
-                    for _ in 0..comment_lines {
-                        self.line_annotations.push(AnnotatedLine::FileComment {
-                            file_idx,
-                            comment_idx,
-                        });
-                    }
+        // File comments
+        if let Some(review) = self.session.files.get(path) {
+            for (comment_idx, comment) in review.file_comments.iter().enumerate() {
+                let comment_lines = Self::comment_display_lines(comment);
+                for _ in 0..comment_lines {
+                    out.push(AnnotatedLine::FileComment {
+                        file_idx,
+                        comment_idx,
+                    });
                 }
             }
+        }
 
-            if file.is_binary || file.hunks.is_empty() {
-                self.line_annotations
-                    .push(AnnotatedLine::BinaryOrEmpty { file_idx });
-            } else {
-                // Get line comments for this file
-                let line_comments = self
-                    .session
-                    .files
-                    .get(path)
-                    .map(|r| &r.line_comments)
-                    .cloned()
-                    .unwrap_or_default();
-
-                for (hunk_idx, hunk) in file.hunks.iter().enumerate() {
-                    // Calculate gap before this hunk
-                    let prev_hunk = if hunk_idx > 0 {
-                        file.hunks.get(hunk_idx - 1)
-                    } else {
-                        None
-                    };
-                    let gap = calculate_gap(
-                        prev_hunk.map(|h| (&h.new_start, &h.new_count)),
-                        hunk.new_start,
-                    );
-
-                    let gap_id = GapId { file_idx, hunk_idx };
+        let has_metadata = file.metadata_descriptor().is_some();
+        if has_metadata {
+            out.push(AnnotatedLine::FileMetadata { file_idx });
+        }
 
-                    if gap > 0 {
-                        let top_len = self.expanded_top.get(&gap_id).map_or(0, |v| v.len());
-                        let bot_len = self.expanded_bottom.get(&gap_id).map_or(0, |v| v.len());
-                        let remaining = (gap as usize).saturating_sub(top_len + bot_len);
-                        let is_top_of_file = hunk_idx == 0;
+        // A mode-only change is fully described by its metadata line
+        if file.is_binary
+            || file.is_too_large
+            || (file.hunks.is_empty() && !has_metadata && file.truncated_lines == 0)
+        {
+            out.push(AnnotatedLine::BinaryOrEmpty { file_idx });
+            if self.shows_image_preview(file) {
+                out.extend(std::iter::repeat_n(
+                    AnnotatedLine::ImagePreview { file_idx },
+                    PREVIEW_ROWS,
+                ));
+            }
+        } else {
+            // Get line comments for this file
+            let no_comments = HashMap::new();
+            let file_review = self.session.files.get(path);
+            let line_comments = file_review.map_or(&no_comments, |r| &r.line_comments);
+            // A commit message is all context, which would fold away entirely
+            let changes_only = self.changes_only && !file.is_commit_message;
 
-                        // Sequential line_idx counter across top + bottom
-                        let mut ctx_idx = 0;
+            for (hunk_idx, hunk) in file.hunks.iter().enumerate() {
+                // Calculate gap before this hunk
+                let prev_hunk = if hunk_idx > 0 {
+                    file.hunks.get(hunk_idx - 1)
+                } else {
+                    None
+                };
+                let gap = calculate_gap(
+                    prev_hunk.map(|h| (&h.new_start, &h.new_count)),
+                    hunk.new_start,
+                );
 
-                        // --- Top expanded lines (↓ direction) ---
-                        for _ in 0..top_len {
-                            self.line_annotations.push(AnnotatedLine::ExpandedContext {
-                                gap_id: gap_id.clone(),
-                                line_idx: ctx_idx,
-                            });
-                            ctx_idx += 1;
-                        }
+                let gap_id = GapId { file_idx, hunk_idx };
 
-                        // --- Expanders / hidden lines ---
-                        if remaining > 0 {
-                            if is_top_of_file {
-                                // Top-of-file: HiddenLines (if > batch) + ↑
-                                if remaining > GAP_EXPAND_BATCH {
-                                    self.line_annotations.push(AnnotatedLine::HiddenLines {
-                                        gap_id: gap_id.clone(),
-                                        count: remaining,
-                                    });
-                                }
-                                self.line_annotations.push(AnnotatedLine::Expander {
-                                    gap_id: gap_id.clone(),
-                                    direction: ExpandDirection::Up,
-                                });
-                            } else if remaining >= GAP_EXPAND_BATCH {
-                                // Between-hunk, large: ↓ + HiddenLines + ↑
-                                self.line_annotations.push(AnnotatedLine::Expander {
-                                    gap_id: gap_id.clone(),
-                                    direction: ExpandDirection::Down,
-                                });
-                                self.line_annotations.push(AnnotatedLine::HiddenLines {
+                if gap > 0 {
+                    let top_len = self.expanded_top.get(&gap_id).map_or(0, |v| v.len());
+                    let bot_len = self.expanded_bottom.get(&gap_id).map_or(0, |v| v.len());
+                    let remaining = (gap as usize).saturating_sub(top_len + bot_len);
+                    let is_top_of_file = hunk_idx == 0;
+
+                    // Sequential line_idx counter across top + bottom
+                    let mut ctx_idx = 0;
+
+                    // --- Top expanded lines (↓ direction) ---
+                    for _ in 0..top_len {
+                        out.push(AnnotatedLine::ExpandedContext {
+                            gap_id: gap_id.clone(),
+                            line_idx: ctx_idx,
+                        });
+                        ctx_idx += 1;
+                    }
+
+                    // --- Expanders / hidden lines ---
+                    if remaining > 0 {
+                        if is_top_of_file {
+                            // Top-of-file: HiddenLines (if > batch) + ↑
+                            if remaining > self.context_expand_lines {
+                                out.push(AnnotatedLine::HiddenLines {
                                     gap_id: gap_id.clone(),
                                     count: remaining,
                                 });
-                                self.line_annotations.push(AnnotatedLine::Expander {
-                                    gap_id: gap_id.clone(),
-                                    direction: ExpandDirection::Up,
-                                });
-                            } else {
-                                // Between-hunk, small: merged ↕
-                                self.line_annotations.push(AnnotatedLine::Expander {
-                                    gap_id: gap_id.clone(),
-                                    direction: ExpandDirection::Both,
-                                });
                             }
-                        }
-
-                        // --- Bottom expanded lines (↑ direction) ---
-                        for _ in 0..bot_len {
-                            self.line_annotations.push(AnnotatedLine::ExpandedContext {
+                            out.push(AnnotatedLine::Expander {
+                                gap_id: gap_id.clone(),
+                                direction: ExpandDirection::Up,
+                            });
+                        } else if remaining >= self.context_expand_lines {
+                            // Between-hunk, large: ↓ + HiddenLines + ↑
+                            out.push(AnnotatedLine::Expander {
+                                gap_id: gap_id.clone(),
+                                direction: ExpandDirection::Down,
+                            });
+                            out.push(AnnotatedLine::HiddenLines {
+                                gap_id: gap_id.clone(),
+                                count: remaining,
+                            });
+                            out.push(AnnotatedLine::Expander {
+                                gap_id: gap_id.clone(),
+                                direction: ExpandDirection::Up,
+                            });
+                        } else {
+                            // Between-hunk, small: merged ↕
+                            out.push(AnnotatedLine::Expander {
                                 gap_id: gap_id.clone(),
-                                line_idx: ctx_idx,
+                                direction: ExpandDirection::Both,
                             });
-                            ctx_idx += 1;
                         }
                     }
 
-                    // Hunk header
-                    self.line_annotations
-                        .push(AnnotatedLine::HunkHeader { file_idx, hunk_idx });
-
-                    // Diff lines - handle differently based on view mode
-                    match self.diff_view_mode {
-                        DiffViewMode::Unified => {
-                            Self::build_unified_diff_annotations(
-                                &mut self.line_annotations,
-                                file_idx,
-                                hunk_idx,
-                                &hunk.lines,
-                                &line_comments,
-                            );
-                        }
-                        DiffViewMode::SideBySide => {
-                            Self::build_side_by_side_annotations(
-                                &mut self.line_annotations,
-                                file_idx,
-                                hunk_idx,
-                                &hunk.lines,
-                                &line_comments,
-                            );
-                        }
+                    // --- Bottom expanded lines (↑ direction) ---
+                    for _ in 0..bot_len {
+                        out.push(AnnotatedLine::ExpandedContext {
+                            gap_id: gap_id.clone(),
+                            line_idx: ctx_idx,
+                        });
+                        ctx_idx += 1;
+                    }
+                }
+
+                // Hunk header
+                out.push(AnnotatedLine::HunkHeader { file_idx, hunk_idx });
+                if file_review.is_some_and(|review| review.is_hunk_reviewed(hunk)) {
+                    continue;
+                }
+
+                // Diff lines - handle differently based on view mode
+                match self.file_view_mode(file_idx) {
+                    DiffViewMode::Unified => {
+                        Self::build_unified_diff_annotations(
+                            &mut out,
+                            file_idx,
+                            hunk_idx,
+                            &hunk.lines,
+                            line_comments,
+                            changes_only,
+                        );
+                    }
+                    DiffViewMode::SideBySide => {
+                        Self::build_side_by_side_annotations(
+                            &mut out,
+                            file_idx,
+                            hunk_idx,
+                            &hunk.lines,
+                            line_comments,
+                            changes_only,
+                        );
                     }
                 }
             }
 
-            // Spacing line
-            self.line_annotations.push(AnnotatedLine::Spacing);
+            if file.truncated_lines > 0 {
+                out.push(AnnotatedLine::TruncatedFile { file_idx });
+            }
         }
+
+        // Spacing line
+        out.push(AnnotatedLine::Spacing);
+
+        out
     }
 
     fn push_comments(
@@ -4208,8 +7409,27 @@ impl App {
         hunk_idx: usize,
         lines: &[crate::model::DiffLine],
         line_comments: &std::collections::HashMap<u32, Vec<crate::model::Comment>>,
+        hide_context: bool,
     ) {
+        let mut hidden_until = 0;
         for (line_idx, diff_line) in lines.iter().enumerate() {
+            if line_idx < hidden_until {
+                continue;
+            }
+            let hidden = if hide_context {
+                hidden_context_run(lines, line_idx, line_comments)
+            } else {
+                0
+            };
+            if hidden > 0 {
+                annotations.push(AnnotatedLine::HiddenContext {
+                    file_idx,
+                    count: hidden,
+                });
+                hidden_until = line_idx + hidden;
+                continue;
+            }
+
             annotations.push(AnnotatedLine::DiffLine {
                 file_idx,
                 hunk_idx,
@@ -4249,11 +7469,26 @@ impl App {
         hunk_idx: usize,
         lines: &[crate::model::DiffLine],
         line_comments: &std::collections::HashMap<u32, Vec<crate::model::Comment>>,
+        hide_context: bool,
     ) {
         let mut i = 0;
         while i < lines.len() {
             let diff_line = &lines[i];
 
+            let hidden = if hide_context {
+                hidden_context_run(lines, i, line_comments)
+            } else {
+                0
+            };
+            if hidden > 0 {
+                annotations.push(AnnotatedLine::HiddenContext {
+                    file_idx,
+                    count: hidden,
+                });
+                i += hidden;
+                continue;
+            }
+
             match diff_line.origin {
                 LineOrigin::Context => {
                     annotations.push(AnnotatedLine::SideBySideLine {
@@ -4277,36 +7512,8 @@ impl App {
                 }
 
                 LineOrigin::Deletion => {
-                    // Find consecutive deletions
-                    let del_start = i;
-                    let mut del_end = i + 1;
-                    while del_end < lines.len() && lines[del_end].origin == LineOrigin::Deletion {
-                        del_end += 1;
-                    }
-
-                    // Find consecutive additions following deletions
-                    let add_start = del_end;
-                    let mut add_end = add_start;
-                    while add_end < lines.len() && lines[add_end].origin == LineOrigin::Addition {
-                        add_end += 1;
-                    }
-
-                    let del_count = del_end - del_start;
-                    let add_count = add_end - add_start;
-                    let max_lines = del_count.max(add_count);
-
-                    for offset in 0..max_lines {
-                        let del_idx = if offset < del_count {
-                            Some(del_start + offset)
-                        } else {
-                            None
-                        };
-                        let add_idx = if offset < add_count {
-                            Some(add_start + offset)
-                        } else {
-                            None
-                        };
-
+                    let (pairs, block_end) = pair_change_block(lines, i);
+                    for (del_idx, add_idx) in pairs {
                         let old_lineno = del_idx.and_then(|idx| lines[idx].old_lineno);
                         let new_lineno = add_idx.and_then(|idx| lines[idx].new_lineno);
 
@@ -4335,7 +7542,7 @@ impl App {
                         );
                     }
 
-                    i = add_end;
+                    i = block_end;
                 }
                 LineOrigin::Addition => {
                     annotations.push(AnnotatedLine::SideBySideLine {
@@ -4361,6 +7568,134 @@ impl App {
         }
     }
 
+    /// Index of the file whose "more lines" line the cursor is on
+    pub fn truncated_file_at_cursor(&self) -> Option<usize> {
+        match self.line_annotations.get(self.diff_state.cursor_line) {
+            Some(AnnotatedLine::TruncatedFile { file_idx }) => Some(*file_idx),
+            _ => None,
+        }
+    }
+
+    /// The generated file whose header is under the cursor.
+    pub fn generated_file_at_cursor(&self) -> Option<usize> {
+        match self.line_annotations.get(self.diff_state.cursor_line) {
+            Some(AnnotatedLine::FileHeader { file_idx })
+                if self.diff_files.get(*file_idx)?.is_generated =>
+            {
+                Some(*file_idx)
+            }
+            _ => None,
+        }
+    }
+
+    /// Load the rest of a file cut off by `max_file_lines`. The diff is
+    /// reloaded with the file exempt from the limit; comments are kept since
+    /// they live in the session, not in the loaded lines.
+    pub fn load_full_file(&mut self, file_idx: usize) {
+        let Some(file) = self.diff_files.get(file_idx) else {
+            return;
+        };
+        let path = file.display_path().clone();
+        let count = file.truncated_lines;
+        self.vcs.load_file_fully(&path);
+        match self.reload_diff_files() {
+            Ok(_) => self.set_message(format!("Loaded {count} more lines")),
+            Err(e) => self.set_error(format!("Failed to load file: {e}")),
+        }
+    }
+
+    /// Queue background highlighting for the files in the diff viewport that
+    /// haven't been highlighted yet.
+    pub fn highlight_visible_files(&mut self) {
+        let start = self
+            .diff_state
+            .scroll_offset
+            .min(self.line_annotations.len());
+        let end = (start + self.diff_state.viewport_height.max(1)).min(self.line_annotations.len());
+        let mut visible: Vec<usize> = self.line_annotations[start..end]
+            .iter()
+            .filter_map(annotation_file_idx)
+            .collect();
+        visible.dedup();
+        for file_idx in visible {
+            self.request_highlighting(file_idx);
+        }
+    }
+
+    fn request_highlighting(&mut self, file_idx: usize) {
+        let Some(file) = self.diff_files.get(file_idx) else {
+            return;
+        };
+        if file.highlighted
+            || file.hunks.is_empty()
+            || file.is_commit_message
+            || file.is_submodule
+            || file.is_symlink
+        {
+            return;
+        }
+        // Keyed by content rather than index, so results still land after
+        // a reload and identical files queued twice are highlighted once
+        let key = (
+            file.display_path().clone(),
+            DiffFile::compute_content_hash(&file.hunks),
+        );
+        if !self.highlight_in_flight.insert(key.clone()) {
+            return;
+        }
+
+        let job = HighlightJob {
+            path: key.0,
+            content_hash: key.1,
+            hunks: file
+                .hunks
+                .iter()
+                .map(|hunk| {
+                    hunk.lines
+                        .iter()
+                        .map(|line| (line.content.clone(), line.origin))
+                        .unzip()
+                })
+                .collect(),
+        };
+        self.highlight_pool
+            .get_or_insert_with(|| HighlightPool::new(self.theme.syntax_highlighter().clone()))
+            .submit(job);
+    }
+
+    /// Apply highlighting finished in the background. Returns true if any
+    /// file changed.
+    pub fn apply_highlight_results(&mut self) -> bool {
+        let Some(pool) = &self.highlight_pool else {
+            return false;
+        };
+        let mut changed = false;
+        for result in pool.finished() {
+            self.highlight_in_flight
+                .remove(&(result.path.clone(), result.content_hash));
+            for file in self.diff_files.iter_mut().filter(|file| {
+                !file.highlighted
+                    && *file.display_path() == result.path
+                    && DiffFile::compute_content_hash(&file.hunks) == result.content_hash
+                    && file.hunks.len() == result.hunks.len()
+                    && file
+                        .hunks
+                        .iter()
+                        .zip(&result.hunks)
+                        .all(|(hunk, spans)| hunk.lines.len() == spans.len())
+            }) {
+                for (hunk, spans) in file.hunks.iter_mut().zip(&result.hunks) {
+                    for (line, spans) in hunk.lines.iter_mut().zip(spans) {
+                        line.highlighted_spans = spans.clone();
+                    }
+                }
+                file.highlighted = true;
+                changed = true;
+            }
+        }
+        changed
+    }
+
     /// What the cursor is on in a gap region
     pub fn get_gap_at_cursor(&self) -> Option<GapCursorHit> {
         let target = self.diff_state.cursor_line;
@@ -4427,6 +7762,13 @@ impl App {
 
         for (file_idx, file) in self.diff_files.iter().enumerate() {
             let path = file.display_path();
+            if self
+                .file_filter
+                .as_ref()
+                .is_some_and(|filter| !filter.matches(path))
+            {
+                continue;
+            }
 
             let mut ancestors: Vec<String> = Vec::new();
             let mut current = path.parent();
@@ -4474,88 +7816,276 @@ impl App {
 }
 
 #[cfg(test)]
-mod tree_tests {
+pub(crate) mod test_support {
     use super::*;
-    use crate::model::{DiffFile, FileStatus};
+    use crate::model::{DiffHunk, DiffLine, FileStatus, LineOrigin};
+    use crate::vcs::traits::VcsType;
 
-    fn make_file(path: &str) -> DiffFile {
-        DiffFile {
-            old_path: None,
-            new_path: Some(PathBuf::from(path)),
-            status: FileStatus::Modified,
-            hunks: vec![],
-            is_binary: false,
-            is_too_large: false,
-            is_commit_message: false,
-            content_hash: 0,
-        }
+    struct MockVcs {
+        info: VcsInfo,
+        /// Total lines available in the "file" (1-indexed)
+        total_lines: u32,
+        /// Returned by `get_working_tree_diff` on reload
+        files: Vec<DiffFile>,
     }
 
-    struct TreeTestHarness {
-        diff_files: Vec<DiffFile>,
-        expanded_dirs: HashSet<String>,
-    }
+    impl VcsBackend for MockVcs {
+        fn info(&self) -> &VcsInfo {
+            &self.info
+        }
 
-    impl TreeTestHarness {
-        fn new(paths: &[&str]) -> Self {
-            Self {
-                diff_files: paths.iter().map(|p| make_file(p)).collect(),
-                expanded_dirs: HashSet::new(),
+        fn get_working_tree_diff(&self) -> Result<Vec<DiffFile>> {
+            if self.files.is_empty() {
+                return Err(TuicrError::NoChanges);
             }
+            Ok(self.files.clone())
         }
 
-        fn expand_all(&mut self) {
-            use std::path::Path;
-            for file in &self.diff_files {
-                let path = file.display_path();
-                let mut current = path.parent();
-                while let Some(parent) = current {
-                    if parent != Path::new("") {
-                        self.expanded_dirs
-                            .insert(parent.to_string_lossy().to_string());
-                    }
-                    current = parent.parent();
-                }
+        fn fetch_context_lines(
+            &self,
+            _file_path: &Path,
+            _file_status: FileStatus,
+            start_line: u32,
+            end_line: u32,
+        ) -> Result<Vec<DiffLine>> {
+            let mut result = Vec::new();
+            for line_num in start_line..=end_line.min(self.total_lines) {
+                result.push(DiffLine {
+                    origin: LineOrigin::Context,
+                    content: format!("line {line_num}"),
+                    old_lineno: Some(line_num),
+                    new_lineno: Some(line_num),
+                    highlighted_spans: None,
+                    whitespace_only: false,
+                    move_id: None,
+                });
             }
+            Ok(result)
         }
+    }
 
-        fn collapse_all(&mut self) {
-            self.expanded_dirs.clear();
+    pub(crate) fn make_hunk(new_start: u32, new_count: u32) -> DiffHunk {
+        let mut lines = Vec::new();
+        for i in 0..new_count {
+            lines.push(DiffLine {
+                origin: LineOrigin::Context,
+                content: format!("hunk line {}", new_start + i),
+                old_lineno: Some(new_start + i),
+                new_lineno: Some(new_start + i),
+                highlighted_spans: None,
+                whitespace_only: false,
+                move_id: None,
+            });
         }
-
-        fn toggle(&mut self, dir: &str) {
-            if self.expanded_dirs.contains(dir) {
-                self.expanded_dirs.remove(dir);
-            } else {
-                self.expanded_dirs.insert(dir.to_string());
-            }
+        DiffHunk {
+            header: format!("@@ -{new_start},{new_count} +{new_start},{new_count} @@"),
+            lines,
+            old_start: new_start,
+            old_count: new_count,
+            new_start,
+            new_count,
         }
+    }
 
-        fn build_visible_items(&self) -> Vec<FileTreeItem> {
-            use std::path::Path;
-            let mut items = Vec::new();
-            let mut seen_dirs: HashSet<String> = HashSet::new();
+    pub(crate) fn build_app_with_files(files: Vec<DiffFile>, total_lines: u32) -> App {
+        try_build_app(files, total_lines, None).expect("failed to build test app")
+    }
 
-            for (file_idx, file) in self.diff_files.iter().enumerate() {
-                let path = file.display_path();
-                let mut ancestors: Vec<String> = Vec::new();
-                let mut current = path.parent();
-                while let Some(parent) = current {
-                    if parent != Path::new("") {
-                        ancestors.push(parent.to_string_lossy().to_string());
-                    }
-                    current = parent.parent();
-                }
-                ancestors.reverse();
+    /// Like [`build_app_with_files`], with a backend that returns `files`
+    /// again when the diff is reloaded.
+    pub(crate) fn build_reloadable_app(files: Vec<DiffFile>, total_lines: u32) -> App {
+        let mut app = build_app_with_files(files.clone(), total_lines);
+        app.vcs = Box::new(MockVcs {
+            info: app.vcs_info.clone(),
+            total_lines,
+            files,
+        });
+        app
+    }
 
-                let mut visible = true;
-                for (depth, dir) in ancestors.iter().enumerate() {
-                    if !seen_dirs.contains(dir) && visible {
-                        let expanded = self.expanded_dirs.contains(dir);
-                        items.push(FileTreeItem::Directory {
-                            path: dir.clone(),
-                            depth,
-                            expanded,
+    /// An app over a real backend, with nothing loaded yet.
+    pub(crate) fn build_app_with_vcs(vcs: Box<dyn VcsBackend>) -> App {
+        let vcs_info = vcs.info().clone();
+        let session = ReviewSession::new(
+            vcs_info.root_path.clone(),
+            vcs_info.head_commit.clone(),
+            vcs_info.branch_name.clone(),
+            SessionDiffSource::WorkingTree,
+        );
+        App::build(
+            vcs,
+            vcs_info,
+            Theme::dark(),
+            None,
+            false,
+            Vec::new(),
+            session,
+            DiffSource::WorkingTree,
+            InputMode::Normal,
+            Vec::new(),
+            &[],
+            None,
+        )
+        .expect("failed to build test app")
+    }
+
+    /// Like [`build_app_with_files`], applying a `--files` glob.
+    pub(crate) fn try_build_app(
+        files: Vec<DiffFile>,
+        total_lines: u32,
+        files_filter: Option<&FileFilter>,
+    ) -> Result<App> {
+        let vcs_info = VcsInfo {
+            root_path: PathBuf::from("/tmp"),
+            head_commit: "abc123".to_string(),
+            branch_name: Some("main".to_string()),
+            vcs_type: VcsType::Git,
+        };
+        let session = ReviewSession::new(
+            vcs_info.root_path.clone(),
+            vcs_info.head_commit.clone(),
+            vcs_info.branch_name.clone(),
+            SessionDiffSource::WorkingTree,
+        );
+
+        App::build(
+            Box::new(MockVcs {
+                info: vcs_info.clone(),
+                total_lines,
+                files: Vec::new(),
+            }),
+            vcs_info,
+            Theme::dark(),
+            None,
+            false,
+            files,
+            session,
+            DiffSource::WorkingTree,
+            InputMode::Normal,
+            Vec::new(),
+            &[],
+            files_filter,
+        )
+    }
+
+    pub(crate) fn make_file_with_hunks(path: &str, hunks: Vec<DiffHunk>) -> DiffFile {
+        let content_hash = DiffFile::compute_content_hash(&hunks);
+        DiffFile {
+            old_path: None,
+            new_path: Some(PathBuf::from(path)),
+            status: FileStatus::Modified,
+            hunks,
+            is_binary: false,
+            binary_meta: None,
+            is_too_large: false,
+            truncated_lines: 0,
+            is_commit_message: false,
+            is_submodule: false,
+            old_mode: None,
+            new_mode: None,
+            is_symlink: false,
+            is_conflicted: false,
+            has_conflicts: false,
+            content_hash,
+            highlighted: false,
+            is_generated: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tree_tests {
+    use super::*;
+    use crate::model::{DiffFile, FileStatus};
+
+    fn make_file(path: &str) -> DiffFile {
+        DiffFile {
+            old_path: None,
+            new_path: Some(PathBuf::from(path)),
+            status: FileStatus::Modified,
+            hunks: vec![],
+            is_binary: false,
+            binary_meta: None,
+            is_too_large: false,
+            truncated_lines: 0,
+            is_commit_message: false,
+            is_submodule: false,
+            old_mode: None,
+            new_mode: None,
+            is_symlink: false,
+            is_conflicted: false,
+            has_conflicts: false,
+            content_hash: 0,
+            highlighted: false,
+            is_generated: false,
+        }
+    }
+
+    struct TreeTestHarness {
+        diff_files: Vec<DiffFile>,
+        expanded_dirs: HashSet<String>,
+    }
+
+    impl TreeTestHarness {
+        fn new(paths: &[&str]) -> Self {
+            Self {
+                diff_files: paths.iter().map(|p| make_file(p)).collect(),
+                expanded_dirs: HashSet::new(),
+            }
+        }
+
+        fn expand_all(&mut self) {
+            use std::path::Path;
+            for file in &self.diff_files {
+                let path = file.display_path();
+                let mut current = path.parent();
+                while let Some(parent) = current {
+                    if parent != Path::new("") {
+                        self.expanded_dirs
+                            .insert(parent.to_string_lossy().to_string());
+                    }
+                    current = parent.parent();
+                }
+            }
+        }
+
+        fn collapse_all(&mut self) {
+            self.expanded_dirs.clear();
+        }
+
+        fn toggle(&mut self, dir: &str) {
+            if self.expanded_dirs.contains(dir) {
+                self.expanded_dirs.remove(dir);
+            } else {
+                self.expanded_dirs.insert(dir.to_string());
+            }
+        }
+
+        fn build_visible_items(&self) -> Vec<FileTreeItem> {
+            use std::path::Path;
+            let mut items = Vec::new();
+            let mut seen_dirs: HashSet<String> = HashSet::new();
+
+            for (file_idx, file) in self.diff_files.iter().enumerate() {
+                let path = file.display_path();
+                let mut ancestors: Vec<String> = Vec::new();
+                let mut current = path.parent();
+                while let Some(parent) = current {
+                    if parent != Path::new("") {
+                        ancestors.push(parent.to_string_lossy().to_string());
+                    }
+                    current = parent.parent();
+                }
+                ancestors.reverse();
+
+                let mut visible = true;
+                for (depth, dir) in ancestors.iter().enumerate() {
+                    if !seen_dirs.contains(dir) && visible {
+                        let expanded = self.expanded_dirs.contains(dir);
+                        items.push(FileTreeItem::Directory {
+                            path: dir.clone(),
+                            depth,
+                            expanded,
                         });
                         seen_dirs.insert(dir.clone());
                     }
@@ -4667,6 +8197,28 @@ mod tree_tests {
 
         assert_eq!(h.visible_file_count(), 1); // only tests/test.rs
     }
+
+    #[test]
+    fn should_keep_selection_valid_when_collapsing_all() {
+        use super::test_support::{build_app_with_files, make_file_with_hunks, make_hunk};
+        let mut app = build_app_with_files(
+            vec![
+                make_file_with_hunks("src/ui/app.rs", vec![make_hunk(1, 3)]),
+                make_file_with_hunks("tests/test.rs", vec![make_hunk(1, 3)]),
+            ],
+            3,
+        );
+        app.file_list_state
+            .select(app.build_visible_items().len() - 1);
+
+        app.collapse_all_dirs();
+        let visible = app.build_visible_items().len();
+        assert_eq!(visible, 2);
+        assert!(app.file_list_state.selected() < visible);
+
+        app.expand_all_dirs();
+        assert_eq!(app.build_visible_items().len(), 5);
+    }
 }
 
 #[cfg(test)]
@@ -4684,7 +8236,7 @@ mod commit_selection_tests {
             &self.info
         }
 
-        fn get_working_tree_diff(&self, _highlighter: &SyntaxHighlighter) -> Result<Vec<DiffFile>> {
+        fn get_working_tree_diff(&self) -> Result<Vec<DiffFile>> {
             Err(TuicrError::NoChanges)
         }
 
@@ -4697,6 +8249,17 @@ mod commit_selection_tests {
         ) -> Result<Vec<DiffLine>> {
             Ok(Vec::new())
         }
+
+        fn commit_diffstat(&self, id: &str) -> Result<DiffStat> {
+            if id == "broken" {
+                return Err(TuicrError::VcsCommand("no such commit".into()));
+            }
+            Ok(DiffStat {
+                files_changed: 1,
+                insertions: id.len(),
+                deletions: 2,
+            })
+        }
     }
 
     fn build_app(commit_list: Vec<CommitInfo>) -> App {
@@ -4726,6 +8289,7 @@ mod commit_selection_tests {
             DiffSource::WorkingTree,
             InputMode::CommitSelect,
             commit_list,
+            &[],
             None,
         )
         .expect("failed to build test app")
@@ -4760,6 +8324,85 @@ mod commit_selection_tests {
 
         assert_eq!(app.special_commit_count(), 0);
     }
+
+    fn app_with_review_commits(ids: &[&str]) -> App {
+        let mut app = build_app(Vec::new());
+        app.review_commits = std::iter::once(App::unstaged_commit_entry())
+            .chain(ids.iter().map(|id| normal_commit(id)))
+            .collect();
+        app.diff_source = DiffSource::CommitRange(ids.iter().map(|id| id.to_string()).collect());
+        app.show_commit_selector = true;
+        app
+    }
+
+    #[test]
+    fn should_load_commit_stats_and_sum_the_selected_subrange() {
+        let mut app = app_with_review_commits(&["a", "bb", "ccc"]);
+
+        app.load_commit_stats();
+        assert_eq!(app.commit_stats.len(), 3);
+        assert_eq!(app.commit_stats["bb"].insertions, 2);
+
+        app.commit_selection_range = Some((1, 2));
+        assert_eq!(
+            app.selected_commits_stat(),
+            Some(DiffStat {
+                files_changed: 2,
+                insertions: 3,
+                deletions: 4,
+            })
+        );
+        // Selections including the unstaged entry have no stats to sum
+        app.commit_selection_range = Some((0, 1));
+        assert_eq!(app.selected_commits_stat(), None);
+        app.commit_selection_range = Some((0, 3));
+        assert_eq!(app.selected_commits_stat(), None);
+    }
+
+    #[test]
+    fn should_label_the_diff_source() {
+        let mut app = app_with_review_commits(&["ccc3333333", "bbb2222222", "aaa1111111"]);
+        app.diff_source = DiffSource::CommitRange(vec![
+            "aaa1111111".to_string(),
+            "bbb2222222".to_string(),
+            "ccc3333333".to_string(),
+        ]);
+
+        assert_eq!(app.diff_source_label(), "Commits aaa1111..ccc3333 (3)");
+
+        app.commit_selection_range = Some((1, 2));
+        assert_eq!(app.diff_source_label(), "Commits bbb2222..ccc3333 (2 of 3)");
+        app.commit_selection_range = Some((3, 3));
+        assert_eq!(app.diff_source_label(), "Commit aaa1111 (1 of 3)");
+
+        app.commit_selection_range = None;
+        app.diff_source = DiffSource::StagedUnstagedAndCommits(vec!["aaa1111111".to_string()]);
+        assert_eq!(
+            app.diff_source_label(),
+            "Staged + Unstaged + Commit aaa1111"
+        );
+
+        app.diff_source = DiffSource::PullRequest {
+            base_ref: "main".to_string(),
+            merge_base_commit: "aaa1111111".to_string(),
+            head_commit: "ccc3333333".to_string(),
+            commit_count: 3,
+        };
+        assert_eq!(app.diff_source_label(), "PR vs main (3 commits)");
+
+        app.diff_source = DiffSource::WorkingTree;
+        assert_eq!(app.diff_source_label(), "Working Tree");
+    }
+
+    #[test]
+    fn should_leave_commit_stats_blank_when_backend_fails() {
+        let mut app = app_with_review_commits(&["a", "broken", "ccc"]);
+
+        app.load_commit_stats();
+        assert!(app.commit_stats.is_empty());
+        app.load_commit_stats();
+        assert!(app.commit_stats.is_empty());
+    }
 }
 
 #[cfg(test)]
@@ -4904,6 +8547,120 @@ mod scroll_tests {
         assert_eq!(diff_state_no_wrap.viewport_height, 20);
         assert_eq!(diff_state_wrap.viewport_height, 20);
     }
+
+    fn two_file_app() -> App {
+        use super::test_support::{build_app_with_files, make_file_with_hunks, make_hunk};
+        let mut app = build_app_with_files(
+            vec![
+                make_file_with_hunks("a.rs", vec![make_hunk(1, 30)]),
+                make_file_with_hunks("b.rs", vec![make_hunk(1, 30)]),
+            ],
+            30,
+        );
+        app.diff_state.viewport_height = 10;
+        app
+    }
+
+    #[test]
+    fn should_jump_to_last_line_and_last_file() {
+        let mut app = two_file_app();
+
+        app.jump_to_bottom();
+
+        assert_eq!(app.diff_state.cursor_line, app.total_lines() - 1);
+        assert!(app.diff_state.scroll_offset + 10 > app.diff_state.cursor_line);
+        assert_eq!(app.diff_state.current_file_idx, 1);
+    }
+
+    #[test]
+    fn should_skip_reviewed_files_when_navigating() {
+        let mut app = two_file_app();
+        app.session
+            .files
+            .get_mut(Path::new("b.rs"))
+            .unwrap()
+            .reviewed = true;
+
+        app.next_file();
+        assert_eq!(app.diff_state.current_file_idx, 1);
+
+        app.jump_to_top();
+        app.skip_reviewed_in_navigation = true;
+        app.next_file();
+        assert_eq!(app.diff_state.current_file_idx, 0);
+
+        app.session
+            .files
+            .get_mut(Path::new("a.rs"))
+            .unwrap()
+            .reviewed = true;
+        app.next_file();
+        assert_eq!(app.diff_state.current_file_idx, 0);
+        assert_eq!(
+            app.message.as_ref().map(|m| m.content.as_str()),
+            Some("All files reviewed")
+        );
+    }
+
+    #[test]
+    fn should_jump_back_to_first_line_and_first_file() {
+        let mut app = two_file_app();
+        app.jump_to_bottom();
+
+        app.jump_to_top();
+
+        assert_eq!(app.diff_state.cursor_line, 0);
+        assert_eq!(app.diff_state.scroll_offset, 0);
+        assert_eq!(app.diff_state.current_file_idx, 0);
+    }
+
+    #[test]
+    fn should_scroll_cursor_to_top_and_bottom_of_viewport() {
+        let mut app = two_file_app();
+        app.diff_state.cursor_line = 30;
+
+        app.scroll_cursor_to_top();
+        assert_eq!(app.diff_state.scroll_offset, 30);
+        app.scroll_cursor_to_bottom();
+        assert_eq!(app.diff_state.scroll_offset, 21);
+
+        // Wrapped lines leave room for fewer logical lines
+        app.diff_state.wrap_lines = true;
+        app.diff_state.visible_line_count = 4;
+        app.scroll_cursor_to_bottom();
+        assert_eq!(app.diff_state.scroll_offset, 27);
+
+        // Clamped near the edges
+        app.diff_state.wrap_lines = false;
+        app.diff_state.cursor_line = app.total_lines() - 1;
+        app.scroll_cursor_to_top();
+        assert_eq!(app.diff_state.scroll_offset, app.max_scroll_offset());
+        app.diff_state.cursor_line = 2;
+        app.scroll_cursor_to_bottom();
+        assert_eq!(app.diff_state.scroll_offset, 0);
+    }
+
+    #[test]
+    fn should_jump_back_to_marks_and_center_them() {
+        let mut app = two_file_app();
+        let b_line = app.calculate_file_scroll_offset(1) + 5;
+        app.diff_state.cursor_line = b_line;
+        app.set_mark('a');
+        app.jump_to_top();
+        assert_eq!(app.diff_state.current_file_idx, 0);
+
+        app.jump_to_mark('a');
+        assert_eq!(app.diff_state.cursor_line, b_line);
+        assert_eq!(app.diff_state.scroll_offset, b_line - 5);
+        assert_eq!(app.diff_state.current_file_idx, 1);
+
+        app.jump_to_mark('q');
+        assert_eq!(app.diff_state.cursor_line, b_line);
+        assert_eq!(
+            app.message.as_ref().map(|m| m.content.as_str()),
+            Some("Mark 'q' not set")
+        );
+    }
 }
 
 #[cfg(test)]
@@ -5106,112 +8863,321 @@ mod find_source_line_tests {
 
 #[cfg(test)]
 mod expand_gap_tests {
+    use super::test_support::{build_app_with_files, make_file_with_hunks, make_hunk};
     use super::*;
-    use crate::model::{DiffHunk, DiffLine, FileStatus, LineOrigin};
-    use crate::vcs::traits::VcsType;
+    use crate::model::{FileStatus, LineOrigin};
 
-    struct MockVcs {
-        info: VcsInfo,
-        /// Total lines available in the "file" (1-indexed)
-        total_lines: u32,
+    #[test]
+    fn should_expand_and_collapse_all_gaps_in_current_file() {
+        let file = make_file_with_hunks("a.rs", vec![make_hunk(5, 3), make_hunk(40, 3)]);
+        let other = make_file_with_hunks("b.rs", vec![make_hunk(10, 3)]);
+        let mut app = build_app_with_files(vec![file, other], 50);
+        let expanders = |app: &App, idx: usize| {
+            app.line_annotations
+                .iter()
+                .filter(|a| {
+                    matches!(a, AnnotatedLine::Expander { gap_id, .. } if gap_id.file_idx == idx)
+                })
+                .count()
+        };
+        let cursor_line = |app: &App| app.get_line_at_cursor().map(|(line, _)| line);
+        app.diff_state.cursor_line = app
+            .line_annotations
+            .iter()
+            .position(|a| {
+                matches!(
+                    a,
+                    AnnotatedLine::DiffLine {
+                        new_lineno: Some(41),
+                        ..
+                    }
+                )
+            })
+            .unwrap();
+
+        app.expand_all_gaps();
+
+        assert_eq!(expanders(&app, 0), 0);
+        assert!(expanders(&app, 1) > 0);
+        assert_eq!(
+            app.expanded_top[&GapId {
+                file_idx: 0,
+                hunk_idx: 1
+            }]
+                .len(),
+            32
+        );
+        assert_eq!(cursor_line(&app), Some(41));
+
+        app.collapse_all_gaps();
+
+        assert!(expanders(&app, 0) > 0);
+        assert!(app.expanded_top.is_empty());
+        assert_eq!(cursor_line(&app), Some(41));
     }
 
-    impl VcsBackend for MockVcs {
-        fn info(&self) -> &VcsInfo {
-            &self.info
-        }
+    #[test]
+    fn should_collapse_generated_files_until_expanded() {
+        let mut generated = make_file_with_hunks("Cargo.lock", vec![make_hunk(1, 3)]);
+        generated.is_generated = true;
+        let file = make_file_with_hunks("src/lib.rs", vec![make_hunk(1, 3)]);
+        let mut app = build_app_with_files(vec![generated, file], 3);
+        let has_lines = |app: &App, idx: usize| {
+            app.line_annotations
+                .iter()
+                .any(|a| matches!(a, AnnotatedLine::DiffLine { file_idx, .. } if *file_idx == idx))
+        };
+        assert!(!has_lines(&app, 0));
+        assert!(has_lines(&app, 1));
 
-        fn get_working_tree_diff(&self, _highlighter: &SyntaxHighlighter) -> Result<Vec<DiffFile>> {
-            Err(TuicrError::NoChanges)
-        }
+        app.diff_state.cursor_line = app.calculate_file_scroll_offset(0);
+        assert_eq!(app.generated_file_at_cursor(), Some(0));
+        app.toggle_generated_file(0);
+        assert!(has_lines(&app, 0));
 
-        fn fetch_context_lines(
-            &self,
-            _file_path: &Path,
-            _file_status: FileStatus,
-            start_line: u32,
-            end_line: u32,
-        ) -> Result<Vec<DiffLine>> {
-            let mut result = Vec::new();
-            for line_num in start_line..=end_line.min(self.total_lines) {
-                result.push(DiffLine {
-                    origin: LineOrigin::Context,
-                    content: format!("line {line_num}"),
-                    old_lineno: Some(line_num),
-                    new_lineno: Some(line_num),
-                    highlighted_spans: None,
-                });
-            }
-            Ok(result)
-        }
+        app.toggle_generated_file(0);
+        assert!(!has_lines(&app, 0));
     }
 
-    fn make_hunk(new_start: u32, new_count: u32) -> DiffHunk {
-        let mut lines = Vec::new();
-        for i in 0..new_count {
-            lines.push(DiffLine {
-                origin: LineOrigin::Context,
-                content: format!("hunk line {}", new_start + i),
-                old_lineno: Some(new_start + i),
-                new_lineno: Some(new_start + i),
-                highlighted_spans: None,
-            });
-        }
-        DiffHunk {
-            header: format!("@@ -{new_start},{new_count} +{new_start},{new_count} @@"),
-            lines,
-            old_start: new_start,
-            old_count: new_count,
-            new_start,
-            new_count,
-        }
+    #[test]
+    fn should_list_jump_to_and_delete_comments() {
+        let files = vec![
+            make_file_with_hunks("b.rs", vec![make_hunk(1, 5)]),
+            make_file_with_hunks("a.rs", vec![make_hunk(1, 5)]),
+        ];
+        let mut app = build_app_with_files(files, 5);
+        let comment =
+            |content: &str, side| Comment::new(content.to_string(), CommentType::Note, side);
+        let b = app.session.files.get_mut(Path::new("b.rs")).unwrap();
+        b.add_line_comment(4, comment("on four", Some(LineSide::New)));
+        b.add_line_comment(2, comment("on two\nmore", Some(LineSide::New)));
+        b.add_file_comment(comment("whole file", None));
+        app.session
+            .files
+            .get_mut(Path::new("a.rs"))
+            .unwrap()
+            .add_line_comment(3, comment("in a", Some(LineSide::New)));
+        app.rebuild_annotations();
+
+        let listed: Vec<(String, Option<u32>, String)> = app
+            .comment_list_entries()
+            .into_iter()
+            .map(|entry| {
+                (
+                    entry.path.display().to_string(),
+                    entry.line.map(|(line, _)| line),
+                    entry.comment.content,
+                )
+            })
+            .collect();
+        assert_eq!(
+            listed,
+            [
+                ("a.rs".to_string(), Some(3), "in a".to_string()),
+                ("b.rs".to_string(), None, "whole file".to_string()),
+                ("b.rs".to_string(), Some(2), "on two\nmore".to_string()),
+                ("b.rs".to_string(), Some(4), "on four".to_string()),
+            ]
+        );
+
+        // Enter lands on the commented line, not the comment box
+        app.open_comment_list();
+        app.comment_list_down(2);
+        app.jump_to_listed_comment();
+        assert_eq!(app.focused_panel, FocusedPanel::Diff);
+        assert_eq!(app.get_line_at_cursor(), Some((2, LineSide::New)));
+        assert_eq!(app.current_file_path(), Some(&PathBuf::from("b.rs")));
+
+        // ]q / [q follow the diff order and wrap around
+        assert!(app.jump_to_comment(true));
+        assert_eq!(app.get_line_at_cursor(), Some((4, LineSide::New)));
+        assert!(app.jump_to_comment(true));
+        assert_eq!(app.get_line_at_cursor(), Some((3, LineSide::New)));
+        assert_eq!(app.current_file_path(), Some(&PathBuf::from("a.rs")));
+        assert!(app.jump_to_comment(false));
+        assert_eq!(app.get_line_at_cursor(), Some((4, LineSide::New)));
+
+        app.focused_panel = FocusedPanel::CommentList;
+        app.request_delete_listed_comment();
+        assert_eq!(app.input_mode, InputMode::Confirm);
+        assert_eq!(app.confirm_message(), "Delete comment on b.rs:2?");
+        app.exit_confirm_mode();
+        app.delete_listed_comment();
+        assert_eq!(app.comment_list_entries().len(), 3);
+        assert!(
+            !app.session.files[Path::new("b.rs")]
+                .line_comments
+                .contains_key(&2)
+        );
+        assert_eq!(app.focused_panel, FocusedPanel::CommentList);
     }
 
-    fn build_app_with_files(files: Vec<DiffFile>, total_lines: u32) -> App {
-        let vcs_info = VcsInfo {
-            root_path: PathBuf::from("/tmp"),
-            head_commit: "abc123".to_string(),
-            branch_name: Some("main".to_string()),
-            vcs_type: VcsType::Git,
+    #[test]
+    fn should_summarize_diff_by_file_status() {
+        let mut added = make_file_with_hunks("src/a.rs", vec![make_hunk(1, 3)]);
+        added.status = FileStatus::Added;
+        for line in &mut added.hunks[0].lines {
+            line.origin = LineOrigin::Addition;
+        }
+        let mut modified = make_file_with_hunks("src/b.rs", vec![make_hunk(1, 3)]);
+        modified.hunks[0].lines[0].origin = LineOrigin::Deletion;
+        let copied = DiffFile {
+            status: FileStatus::Copied,
+            ..make_file_with_hunks("src/c.rs", vec![make_hunk(1, 3)])
         };
-        let session = ReviewSession::new(
-            vcs_info.root_path.clone(),
-            vcs_info.head_commit.clone(),
-            vcs_info.branch_name.clone(),
-            SessionDiffSource::WorkingTree,
+        let mut app = build_app_with_files(vec![added, modified, copied], 3);
+        let cursor = app.diff_state.cursor_line;
+
+        app.show_stats();
+
+        assert_eq!(
+            app.stats_popup,
+            Some(DiffSummary {
+                files: 3,
+                additions: 3,
+                deletions: 1,
+                by_status: vec![
+                    (FileStatus::Added, 1),
+                    (FileStatus::Modified, 1),
+                    (FileStatus::Deleted, 0),
+                    (FileStatus::Renamed, 0),
+                    (FileStatus::Copied, 1),
+                ],
+                hunks: 3,
+                reviewed_hunks: 0,
+            })
         );
+        assert_eq!(app.diff_state.cursor_line, cursor);
+    }
 
-        App::build(
-            Box::new(MockVcs {
-                info: vcs_info.clone(),
-                total_lines,
-            }),
-            vcs_info,
-            Theme::dark(),
-            None,
-            false,
-            files,
-            session,
-            DiffSource::WorkingTree,
-            InputMode::Normal,
-            Vec::new(),
-            None,
-        )
-        .expect("failed to build test app")
+    #[test]
+    fn should_override_view_mode_for_the_current_file_only() {
+        let first = make_file_with_hunks("src/a.rs", vec![make_hunk(1, 3)]);
+        let second = make_file_with_hunks("src/b.rs", vec![make_hunk(1, 3)]);
+        let mut app = build_app_with_files(vec![first, second], 3);
+        let has = |app: &App, idx: usize, side_by_side: bool| {
+            app.line_annotations.iter().any(|a| match a {
+                AnnotatedLine::SideBySideLine { file_idx, .. } => side_by_side && *file_idx == idx,
+                AnnotatedLine::DiffLine { file_idx, .. } => !side_by_side && *file_idx == idx,
+                _ => false,
+            })
+        };
+
+        app.jump_to_file(1);
+        app.toggle_file_view_mode();
+        assert_eq!(app.file_view_mode(1), DiffViewMode::SideBySide);
+        assert!(has(&app, 1, true) && !has(&app, 1, false));
+        assert!(has(&app, 0, false));
+
+        // Toggling back to the global mode drops the override
+        app.toggle_file_view_mode();
+        assert!(app.file_view_modes.is_empty());
+        assert!(has(&app, 1, false));
+
+        app.toggle_file_view_mode();
+        app.toggle_diff_view_mode();
+        assert_eq!(app.file_view_mode(0), DiffViewMode::SideBySide);
+        assert_eq!(app.file_view_mode(1), DiffViewMode::SideBySide);
     }
 
-    fn make_file_with_hunks(path: &str, hunks: Vec<DiffHunk>) -> DiffFile {
-        let content_hash = DiffFile::compute_content_hash(&hunks);
-        DiffFile {
-            old_path: None,
-            new_path: Some(PathBuf::from(path)),
-            status: FileStatus::Modified,
-            hunks,
-            is_binary: false,
-            is_too_large: false,
-            is_commit_message: false,
-            content_hash,
+    #[test]
+    fn should_select_and_fold_files_on_header_clicks() {
+        let first = make_file_with_hunks("src/a.rs", vec![make_hunk(1, 3)]);
+        let second = make_file_with_hunks("src/b.rs", vec![make_hunk(1, 3)]);
+        let mut app = build_app_with_files(vec![first, second], 3);
+        let has_lines = |app: &App, idx: usize| {
+            app.line_annotations
+                .iter()
+                .any(|a| matches!(a, AnnotatedLine::DiffLine { file_idx, .. } if *file_idx == idx))
+        };
+        let header = app.calculate_file_scroll_offset(1);
+
+        app.click_diff_annotation(header, false);
+        assert_eq!(app.diff_state.cursor_line, header);
+        assert_eq!(app.diff_state.current_file_idx, 1);
+        assert_eq!(
+            Some(app.file_list_state.selected()),
+            app.file_idx_to_tree_idx(1)
+        );
+        assert!(has_lines(&app, 1));
+
+        app.click_diff_annotation(header, true);
+        assert!(!has_lines(&app, 1));
+        assert!(has_lines(&app, 0));
+        app.click_diff_annotation(header, true);
+        assert!(has_lines(&app, 1));
+    }
+
+    #[test]
+    fn should_extend_drag_selections_on_one_side_of_one_file() {
+        let mut hunk = make_hunk(1, 5);
+        hunk.lines[2].origin = LineOrigin::Deletion;
+        hunk.lines[2].new_lineno = None;
+        let first = make_file_with_hunks("src/a.rs", vec![hunk]);
+        let second = make_file_with_hunks("src/b.rs", vec![make_hunk(1, 5)]);
+        let mut app = build_app_with_files(vec![first, second], 5);
+        let row_of = |app: &App, file: usize, line: usize| {
+            app.line_annotations
+                .iter()
+                .position(|a| {
+                    matches!(a, AnnotatedLine::DiffLine { file_idx, line_idx, .. }
+                        if *file_idx == file && *line_idx == line)
+                })
+                .unwrap()
+        };
+        let header = app.calculate_file_scroll_offset(0);
+
+        app.click_diff_annotation(row_of(&app, 0, 0), false);
+        app.mouse_drag_in_diff = true;
+        app.drag_selection_to(row_of(&app, 0, 1));
+        assert_eq!(app.input_mode, InputMode::VisualSelect);
+        assert_eq!(
+            app.get_visual_selection(),
+            Some((LineRange::new(1, 2), LineSide::New))
+        );
+
+        // Deleted lines, other files and headers don't move the selection
+        for idx in [row_of(&app, 0, 2), row_of(&app, 1, 3), header] {
+            app.drag_selection_to(idx);
+            assert_eq!(app.diff_state.cursor_line, row_of(&app, 0, 1));
         }
+
+        // Holding the pointer at the bottom edge walks past the deleted line
+        app.drag_selection_past_edge(true);
+        assert_eq!(app.diff_state.cursor_line, row_of(&app, 0, 3));
+        app.drag_selection_past_edge(true);
+        app.drag_selection_past_edge(true);
+        assert_eq!(app.diff_state.cursor_line, row_of(&app, 0, 4));
+
+        app.end_drag_selection(Some(row_of(&app, 0, 4)));
+        assert_eq!(
+            app.get_visual_selection(),
+            Some((LineRange::new(1, 5), LineSide::New))
+        );
+
+        // Releasing over a file header cancels the selection
+        app.exit_visual_mode();
+        app.click_diff_annotation(row_of(&app, 0, 0), false);
+        app.mouse_drag_in_diff = true;
+        app.drag_selection_to(row_of(&app, 0, 1));
+        app.end_drag_selection(Some(header));
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert!(!app.mouse_drag_in_diff);
+    }
+
+    #[test]
+    fn should_detect_double_clicks_on_the_same_cell() {
+        let mut app = build_app_with_files(vec![make_file_with_hunks("a.rs", vec![])], 3);
+        let start = Instant::now();
+        let later = |ms| start + Duration::from_millis(ms);
+
+        assert!(!app.register_left_click(start, 5, 5));
+        assert!(app.register_left_click(later(300), 5, 5));
+        // A third click starts over
+        assert!(!app.register_left_click(later(400), 5, 5));
+        // Too slow, or on another cell
+        assert!(!app.register_left_click(later(900), 5, 5));
+        assert!(!app.register_left_click(later(1000), 6, 5));
     }
 
     #[test]
@@ -5574,4 +9540,2214 @@ mod expand_gap_tests {
             .count();
         assert_eq!(both_count, 1, "should merge to ↕ when <20 remaining");
     }
+
+    /// New line numbers of a gap's expanded lines, top then bottom
+    fn expanded_line_numbers(app: &App, gap_id: &GapId) -> Vec<u32> {
+        let count = app.expanded_top.get(gap_id).map_or(0, Vec::len)
+            + app.expanded_bottom.get(gap_id).map_or(0, Vec::len);
+        (0..count)
+            .filter_map(|idx| app.get_expanded_line(gap_id, idx)?.new_lineno)
+            .collect()
+    }
+
+    #[test]
+    fn should_fill_gap_exactly_when_both_ends_meet() {
+        // given: 40-line between-hunk gap (lines 6-45)
+        let file = make_file_with_hunks("test.rs", vec![make_hunk(1, 5), make_hunk(46, 5)]);
+        let mut app = build_app_with_files(vec![file], 100);
+        let gap_id = GapId {
+            file_idx: 0,
+            hunk_idx: 1,
+        };
+
+        // when: one chunk from each end
+        app.expand_gap(gap_id.clone(), ExpandDirection::Down, Some(20))
+            .unwrap();
+        app.expand_gap(gap_id.clone(), ExpandDirection::Up, Some(20))
+            .unwrap();
+        app.expand_gap(gap_id.clone(), ExpandDirection::Down, Some(20))
+            .unwrap();
+
+        // then: every line once, in order, and nothing left to expand
+        assert_eq!(
+            expanded_line_numbers(&app, &gap_id),
+            (6..=45).collect::<Vec<_>>()
+        );
+        assert!(!app.line_annotations.iter().any(|a| matches!(
+            a,
+            AnnotatedLine::Expander { .. } | AnnotatedLine::HiddenLines { .. }
+        )));
+    }
+
+    #[test]
+    fn should_expand_by_configured_chunk_until_ends_meet() {
+        // given: 45-line between-hunk gap (lines 6-50), 7 lines per chunk
+        let file = make_file_with_hunks("test.rs", vec![make_hunk(1, 5), make_hunk(51, 5)]);
+        let mut app = build_app_with_files(vec![file], 100);
+        app.context_expand_lines = 7;
+        app.rebuild_annotations();
+        let gap_id = GapId {
+            file_idx: 0,
+            hunk_idx: 1,
+        };
+        let expander_labels = |app: &App| -> Vec<String> {
+            (0..app.line_annotations.len())
+                .filter(|&idx| {
+                    matches!(
+                        app.line_annotations[idx],
+                        AnnotatedLine::Expander { .. } | AnnotatedLine::HiddenLines { .. }
+                    )
+                })
+                .filter_map(|idx| app.line_text_for_search(idx))
+                .collect()
+        };
+        assert_eq!(
+            expander_labels(&app),
+            [
+                "... ↓ expand (7 lines) ...",
+                "... 45 lines hidden ...",
+                "... ↑ expand (7 lines) ...",
+            ]
+        );
+
+        // when: 5 chunks below and 1 above leave 3 lines, one short of a chunk
+        for _ in 0..5 {
+            app.expand_gap(gap_id.clone(), ExpandDirection::Up, Some(7))
+                .unwrap();
+        }
+        app.expand_gap(gap_id.clone(), ExpandDirection::Down, Some(7))
+            .unwrap();
+
+        // then: the rest is offered as one merged expander
+        assert_eq!(expander_labels(&app), ["... ↕ expand (3 lines) ..."]);
+
+        // when: the next chunk below only has the 3 lines left
+        app.expand_gap(gap_id.clone(), ExpandDirection::Up, Some(7))
+            .unwrap();
+
+        // then: the ends meet without repeating lines
+        assert_eq!(
+            expanded_line_numbers(&app, &gap_id),
+            (6..=50).collect::<Vec<_>>()
+        );
+        assert!(expander_labels(&app).is_empty());
+    }
+}
+
+#[cfg(test)]
+mod yank_tests {
+    use super::test_support::{build_app_with_files, make_file_with_hunks, make_hunk};
+    use super::*;
+
+    fn cursor_to(app: &mut App, pred: impl Fn(&AnnotatedLine) -> bool) {
+        let idx = app
+            .line_annotations
+            .iter()
+            .position(pred)
+            .expect("annotation not found");
+        app.diff_state.cursor_line = idx;
+    }
+
+    #[test]
+    fn should_return_line_content_at_cursor() {
+        let file = make_file_with_hunks("a.rs", vec![make_hunk(1, 3)]);
+        let mut app = build_app_with_files(vec![file], 3);
+        cursor_to(&mut app, |a| {
+            matches!(
+                a,
+                AnnotatedLine::DiffLine {
+                    new_lineno: Some(2),
+                    ..
+                }
+            )
+        });
+
+        assert_eq!(app.line_content_at_cursor().as_deref(), Some("hunk line 2"));
+    }
+
+    #[test]
+    fn should_return_hunk_lines_from_hunk_header() {
+        let file = make_file_with_hunks("a.rs", vec![make_hunk(1, 3), make_hunk(20, 2)]);
+        let mut app = build_app_with_files(vec![file], 30);
+        cursor_to(&mut app, |a| {
+            matches!(a, AnnotatedLine::HunkHeader { hunk_idx: 1, .. })
+        });
+
+        assert_eq!(app.line_content_at_cursor(), None);
+        assert_eq!(
+            app.hunk_lines_at_cursor(),
+            Some(vec![
+                "@@ -20,2 +20,2 @@".to_string(),
+                " hunk line 20".to_string(),
+                " hunk line 21".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn should_return_none_when_cursor_not_in_hunk() {
+        let file = make_file_with_hunks("a.rs", vec![make_hunk(1, 3)]);
+        let mut app = build_app_with_files(vec![file], 3);
+        cursor_to(&mut app, |a| matches!(a, AnnotatedLine::FileHeader { .. }));
+
+        assert_eq!(app.line_content_at_cursor(), None);
+        assert_eq!(app.hunk_lines_at_cursor(), None);
+    }
+
+    fn line(origin: LineOrigin, content: &str, old: Option<u32>, new: Option<u32>) -> DiffLine {
+        DiffLine {
+            origin,
+            content: content.to_string(),
+            old_lineno: old,
+            new_lineno: new,
+            highlighted_spans: None,
+            whitespace_only: false,
+            move_id: None,
+        }
+    }
+
+    fn app_with_changed_line() -> App {
+        let mut hunk = make_hunk(1, 0);
+        hunk.lines = vec![
+            line(LineOrigin::Context, "one", Some(1), Some(1)),
+            line(LineOrigin::Deletion, "old two", Some(2), None),
+            line(LineOrigin::Addition, "new two", None, Some(2)),
+            line(LineOrigin::Context, "three", Some(3), Some(3)),
+        ];
+        let file = make_file_with_hunks("a.rs", vec![hunk]);
+        build_app_with_files(vec![file], 3)
+    }
+
+    fn select_new_lines(app: &mut App, from: u32, to: u32) {
+        let on_new_line = |n: u32| {
+            move |a: &AnnotatedLine| {
+                matches!(a, AnnotatedLine::DiffLine { new_lineno: Some(l), .. }
+                    | AnnotatedLine::SideBySideLine { new_lineno: Some(l), .. } if *l == n)
+            }
+        };
+        cursor_to(app, on_new_line(from));
+        app.enter_visual_mode(from, LineSide::New);
+        cursor_to(app, on_new_line(to));
+    }
+
+    #[test]
+    fn should_copy_selected_lines_with_and_without_markers() {
+        let mut app = app_with_changed_line();
+        select_new_lines(&mut app, 2, 3);
+
+        assert_eq!(
+            app.selected_lines(false),
+            Some(vec!["new two".to_string(), "three".to_string()])
+        );
+        assert_eq!(
+            app.selected_lines(true),
+            Some(vec!["+new two".to_string(), " three".to_string()])
+        );
+    }
+
+    #[test]
+    fn should_copy_selected_side_in_side_by_side_mode() {
+        let mut app = app_with_changed_line();
+        app.diff_view_mode = DiffViewMode::SideBySide;
+        app.rebuild_annotations();
+        select_new_lines(&mut app, 2, 2);
+
+        assert_eq!(app.selected_lines(true), Some(vec!["+new two".to_string()]));
+    }
+}
+
+#[cfg(test)]
+mod path_filter_tests {
+    use super::test_support::{make_file_with_hunks, make_hunk};
+    use super::*;
+
+    #[test]
+    fn should_keep_files_under_any_filter_path() {
+        let files = [
+            "src/parser/mod.rs",
+            "src/parser.rs",
+            "tests/a.rs",
+            "README.md",
+        ]
+        .into_iter()
+        .map(|path| make_file_with_hunks(path, vec![make_hunk(1, 1)]))
+        .collect();
+
+        let kept = App::filter_by_paths(files, &["src/parser".to_string(), "tests/".to_string()]);
+
+        let kept: Vec<_> = kept.iter().map(|f| f.display_path().clone()).collect();
+        assert_eq!(
+            kept,
+            [
+                PathBuf::from("src/parser/mod.rs"),
+                PathBuf::from("tests/a.rs")
+            ]
+        );
+    }
+
+    #[test]
+    fn should_make_absolute_paths_repo_relative() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("repo");
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        let path = |p: &Path| p.to_string_lossy().into_owned();
+
+        let relative = App::repo_relative_paths(
+            &root,
+            &[
+                path(&root.join("src")),
+                path(&root.join("src/../gone.rs")),
+                path(&root),
+                "docs".to_string(),
+            ],
+        )
+        .unwrap();
+        assert_eq!(relative, ["src", "gone.rs", "", "docs"]);
+
+        let outside = path(&dir.path().join("other"));
+        assert!(matches!(
+            App::repo_relative_paths(&root, std::slice::from_ref(&outside)),
+            Err(TuicrError::PathOutsideRepository(p)) if p == outside
+        ));
+    }
+}
+
+#[cfg(test)]
+mod open_in_editor_tests {
+    use super::test_support::{build_app_with_files, make_file_with_hunks, make_hunk};
+    use super::*;
+
+    fn app_in(root: &Path) -> App {
+        let file = make_file_with_hunks("a.rs", vec![make_hunk(1, 3)]);
+        let mut app = build_app_with_files(vec![file], 3);
+        app.vcs_info.root_path = root.to_path_buf();
+        app.focused_panel = FocusedPanel::Diff;
+        app
+    }
+
+    #[test]
+    fn should_request_editor_at_cursor_line() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.rs"), "1\n2\n3\n").unwrap();
+        let mut app = app_in(dir.path());
+        app.diff_state.cursor_line = app
+            .line_annotations
+            .iter()
+            .position(|a| {
+                matches!(
+                    a,
+                    AnnotatedLine::DiffLine {
+                        new_lineno: Some(2),
+                        ..
+                    }
+                )
+            })
+            .unwrap();
+
+        app.request_open_in_editor();
+
+        assert_eq!(app.pending_editor, Some((dir.path().join("a.rs"), Some(2))));
+    }
+
+    #[test]
+    fn should_refuse_files_missing_on_disk_or_outside_working_tree() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = app_in(dir.path());
+        app.request_open_in_editor();
+        assert_eq!(app.pending_editor, None);
+        assert!(app.message.is_some());
+
+        std::fs::write(dir.path().join("a.rs"), "1\n").unwrap();
+        app.diff_source = DiffSource::CommitRange(vec!["abc".to_string()]);
+        app.request_open_in_editor();
+        assert_eq!(app.pending_editor, None);
+    }
+
+    #[test]
+    fn should_load_comment_edited_in_editor_keeping_type_and_target() {
+        let mut app =
+            build_app_with_files(vec![make_file_with_hunks("a.rs", vec![make_hunk(1, 3)])], 3);
+        app.enter_comment_mode(false, Some((2, LineSide::New)));
+        app.cycle_comment_type();
+        let comment_type = app.comment_type.clone();
+        app.comment_buffer = "first".to_string();
+
+        app.request_edit_comment_in_editor();
+        let (path, line) = app.pending_editor.take().unwrap();
+        assert_eq!(line, None);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "first");
+        std::fs::write(&path, "first\n\nsecond paragraph\n").unwrap();
+        app.finish_edit_comment(true);
+
+        assert!(!path.exists());
+        assert_eq!(app.comment_buffer, "first\n\nsecond paragraph");
+        assert_eq!(app.comment_cursor, app.comment_buffer.len());
+        assert_eq!(app.input_mode, InputMode::Comment);
+        assert_eq!(app.comment_type, comment_type);
+        app.save_comment();
+        assert_eq!(
+            app.session.files[Path::new("a.rs")].line_comments[&2][0].content,
+            "first\n\nsecond paragraph"
+        );
+    }
+
+    #[test]
+    fn should_keep_comment_when_editor_fails() {
+        let mut app =
+            build_app_with_files(vec![make_file_with_hunks("a.rs", vec![make_hunk(1, 3)])], 3);
+        app.enter_comment_mode(true, None);
+        app.comment_buffer = "original".to_string();
+        app.request_edit_comment_in_editor();
+        let (path, _) = app.pending_editor.take().unwrap();
+        std::fs::write(&path, "discarded").unwrap();
+
+        app.finish_edit_comment(false);
+
+        assert_eq!(app.comment_buffer, "original");
+        assert!(app.message.is_some());
+        assert!(!path.exists());
+    }
+}
+
+#[cfg(test)]
+mod cycle_comment_type_at_cursor_tests {
+    use super::test_support::{build_app_with_files, make_file_with_hunks, make_hunk};
+    use super::*;
+
+    fn app_with_line_comment() -> App {
+        let mut app =
+            build_app_with_files(vec![make_file_with_hunks("a.rs", vec![make_hunk(1, 3)])], 3);
+        app.enter_comment_mode(false, Some((2, LineSide::New)));
+        app.comment_buffer = "keep this text".to_string();
+        app.save_comment();
+        app.diff_state.cursor_line = app
+            .line_annotations
+            .iter()
+            .position(|a| matches!(a, AnnotatedLine::LineComment { .. }))
+            .unwrap();
+        app.dirty = false;
+        app
+    }
+
+    fn line_comment(app: &App) -> &Comment {
+        &app.session.files[Path::new("a.rs")].line_comments[&2][0]
+    }
+
+    #[test]
+    fn should_cycle_type_of_comment_under_cursor() {
+        let mut app = app_with_line_comment();
+        let original = line_comment(&app).comment_type.clone();
+        let expected = app.next_comment_type(&original).unwrap();
+
+        app.cycle_comment_type_at_cursor();
+
+        assert_eq!(line_comment(&app).comment_type, expected);
+        assert_eq!(line_comment(&app).content, "keep this text");
+        assert!(app.dirty);
+        assert_eq!(
+            app.message.as_ref().unwrap().content,
+            format!("Comment type: {}", app.comment_type_label(&expected))
+        );
+
+        app.undo();
+        assert_eq!(line_comment(&app).comment_type, original);
+    }
+
+    #[test]
+    fn should_start_new_comments_as_the_configured_type_but_edit_with_their_own() {
+        let mut app = app_with_line_comment();
+        assert_eq!(line_comment(&app).comment_type, CommentType::Note);
+        app.preferred_comment_type = Some(CommentType::Issue);
+
+        app.enter_comment_mode(false, Some((1, LineSide::New)));
+        assert_eq!(app.comment_type, CommentType::Issue);
+        app.exit_comment_mode();
+        app.enter_review_comment_mode();
+        assert_eq!(app.comment_type, CommentType::Issue);
+        app.exit_comment_mode();
+
+        assert!(app.enter_edit_mode());
+        assert_eq!(app.comment_type, CommentType::Note);
+    }
+
+    #[test]
+    fn should_report_when_cursor_is_not_on_a_comment() {
+        let mut app = app_with_line_comment();
+        app.diff_state.cursor_line = 0;
+
+        app.cycle_comment_type_at_cursor();
+
+        assert!(!app.dirty);
+        assert_eq!(
+            app.message.as_ref().unwrap().content,
+            "No comment at cursor"
+        );
+    }
+}
+
+#[cfg(test)]
+mod edit_commit_message_tests {
+    use super::test_support::{build_app_with_files, make_file_with_hunks, make_hunk};
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Backend whose commit message lives in memory.
+    struct MessageVcs {
+        info: VcsInfo,
+        message: Arc<Mutex<String>>,
+    }
+
+    impl VcsBackend for MessageVcs {
+        fn info(&self) -> &VcsInfo {
+            &self.info
+        }
+
+        fn get_working_tree_diff(&self) -> Result<Vec<DiffFile>> {
+            Ok(vec![make_file_with_hunks("a.rs", vec![make_hunk(1, 3)])])
+        }
+
+        fn fetch_context_lines(
+            &self,
+            _file_path: &Path,
+            _file_status: FileStatus,
+            _start_line: u32,
+            _end_line: u32,
+        ) -> Result<Vec<DiffLine>> {
+            Ok(Vec::new())
+        }
+
+        fn commit_message(&self) -> Result<String> {
+            Ok(self.message.lock().unwrap().clone())
+        }
+
+        fn set_commit_message(&self, message: &str) -> Result<()> {
+            *self.message.lock().unwrap() = message.to_string();
+            Ok(())
+        }
+    }
+
+    fn app_with_message(message: &str) -> (App, Arc<Mutex<String>>) {
+        let mut app =
+            build_app_with_files(vec![make_file_with_hunks("a.rs", vec![make_hunk(1, 3)])], 3);
+        let message = Arc::new(Mutex::new(message.to_string()));
+        app.vcs = Box::new(MessageVcs {
+            info: app.vcs_info.clone(),
+            message: Arc::clone(&message),
+        });
+        (app, message)
+    }
+
+    #[test]
+    fn should_update_message_saved_in_editor() {
+        let (mut app, message) = app_with_message("Initial description\n");
+
+        app.request_edit_commit_message();
+        let (draft, line) = app.pending_editor.take().unwrap();
+        assert_eq!(line, None);
+        assert_eq!(
+            std::fs::read_to_string(&draft).unwrap(),
+            "Initial description\n"
+        );
+
+        std::fs::write(&draft, "Reworded\n\nWith a body\n\n").unwrap();
+        app.finish_edit_commit_message(true);
+
+        assert_eq!(*message.lock().unwrap(), "Reworded\n\nWith a body\n");
+        assert!(!draft.exists());
+        assert_eq!(
+            app.message.as_ref().unwrap().content,
+            "Updated commit message"
+        );
+    }
+
+    #[test]
+    fn should_keep_message_when_editor_fails_or_nothing_changed() {
+        let (mut app, message) = app_with_message("Initial\n");
+
+        app.request_edit_commit_message();
+        let (draft, _) = app.pending_editor.take().unwrap();
+        std::fs::write(&draft, "Discarded\n").unwrap();
+        app.finish_edit_commit_message(false);
+        assert_eq!(*message.lock().unwrap(), "Initial\n");
+        assert!(!draft.exists());
+
+        app.request_edit_commit_message();
+        app.pending_editor = None;
+        app.finish_edit_commit_message(true);
+        assert_eq!(*message.lock().unwrap(), "Initial\n");
+        assert_eq!(
+            app.message.as_ref().unwrap().content,
+            "Commit message unchanged"
+        );
+    }
+
+    #[test]
+    fn should_refuse_outside_working_tree_or_without_backend_support() {
+        let (mut app, _) = app_with_message("Initial\n");
+        app.diff_source = DiffSource::CommitRange(vec!["abc".to_string()]);
+        app.request_edit_commit_message();
+        assert_eq!(app.pending_editor, None);
+
+        let mut app =
+            build_app_with_files(vec![make_file_with_hunks("a.rs", vec![make_hunk(1, 3)])], 3);
+        app.request_edit_commit_message();
+        assert_eq!(app.pending_editor, None);
+        assert!(app.message.is_some());
+    }
+}
+
+#[cfg(test)]
+mod blame_tests {
+    use super::test_support::{build_app_with_files, make_file_with_hunks, make_hunk};
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Blame requests as (line, target), and whole-file blames as (0, path)
+    type Requests = Arc<Mutex<Vec<(u32, String)>>>;
+
+    /// Backend blaming every line on commit `c0ffee`.
+    struct BlameVcs {
+        info: VcsInfo,
+        requests: Requests,
+    }
+
+    impl VcsBackend for BlameVcs {
+        fn info(&self) -> &VcsInfo {
+            &self.info
+        }
+
+        fn get_working_tree_diff(&self) -> Result<Vec<DiffFile>> {
+            Ok(Vec::new())
+        }
+
+        fn fetch_context_lines(
+            &self,
+            _file_path: &Path,
+            _file_status: FileStatus,
+            _start_line: u32,
+            _end_line: u32,
+        ) -> Result<Vec<DiffLine>> {
+            Ok(Vec::new())
+        }
+
+        fn blame_line(
+            &self,
+            _path: &Path,
+            line: u32,
+            target: BlameTarget,
+        ) -> Result<Option<String>> {
+            self.requests
+                .lock()
+                .unwrap()
+                .push((line, format!("{target:?}")));
+            Ok(Some("c0ffee".to_string()))
+        }
+
+        fn blame_file(&self, path: &Path) -> Result<Vec<BlameLine>> {
+            self.requests
+                .lock()
+                .unwrap()
+                .push((0, path.display().to_string()));
+            Ok(vec![
+                BlameLine {
+                    commit_id: "c0ffee".to_string(),
+                    author: "Ada Lovelace".to_string(),
+                };
+                3
+            ])
+        }
+
+        fn get_commits_info(&self, ids: &[String]) -> Result<Vec<CommitInfo>> {
+            Ok(ids
+                .iter()
+                .map(|id| CommitInfo {
+                    id: id.clone(),
+                    short_id: id.clone(),
+                    branch_name: None,
+                    summary: "Add the thing".to_string(),
+                    body: None,
+                    author: "Test".to_string(),
+                    time: Utc::now(),
+                })
+                .collect())
+        }
+    }
+
+    /// App over a hunk whose first line is added and whose other two are context.
+    fn blame_app(diff_source: DiffSource) -> (App, Requests) {
+        let mut hunk = make_hunk(1, 3);
+        hunk.lines[0].origin = LineOrigin::Addition;
+        hunk.lines[0].old_lineno = None;
+        let mut app = build_app_with_files(vec![make_file_with_hunks("a.rs", vec![hunk])], 3);
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        app.vcs = Box::new(BlameVcs {
+            info: app.vcs_info.clone(),
+            requests: Arc::clone(&requests),
+        });
+        app.diff_source = diff_source;
+        (app, requests)
+    }
+
+    fn move_to_line(app: &mut App, line_idx: usize) {
+        app.diff_state.cursor_line = app
+            .line_annotations
+            .iter()
+            .position(
+                |a| matches!(a, AnnotatedLine::DiffLine { line_idx: l, .. } if *l == line_idx),
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn should_report_working_tree_additions_as_uncommitted() {
+        let (mut app, requests) = blame_app(DiffSource::WorkingTree);
+        move_to_line(&mut app, 0);
+
+        app.show_blame_at_cursor();
+
+        let popup = app.blame_popup.as_ref().unwrap();
+        assert_eq!(popup.line, 1);
+        assert!(popup.commit.is_none());
+        assert!(requests.lock().unwrap().is_empty());
+
+        // Nothing to expand for uncommitted lines
+        app.show_blame_at_cursor();
+        assert!(!app.blame_popup.as_ref().unwrap().expanded);
+    }
+
+    #[test]
+    fn should_blame_context_lines_in_parent_and_expand_on_second_press() {
+        let (mut app, requests) = blame_app(DiffSource::WorkingTree);
+        move_to_line(&mut app, 1);
+
+        app.show_blame_at_cursor();
+        let popup = app.blame_popup.as_ref().unwrap();
+        assert_eq!(popup.commit.as_ref().unwrap().id, "c0ffee");
+        assert!(!popup.expanded);
+        assert_eq!(
+            *requests.lock().unwrap(),
+            [(2, "WorkingCopyParent".to_string())]
+        );
+
+        app.show_blame_at_cursor();
+        assert!(app.blame_popup.as_ref().unwrap().expanded);
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn should_blame_the_current_file_once_for_the_blame_column() {
+        let (mut app, requests) = blame_app(DiffSource::WorkingTree);
+        app.load_current_file_blame();
+        assert!(requests.lock().unwrap().is_empty());
+
+        app.toggle_blame_column();
+        app.load_current_file_blame();
+        app.load_current_file_blame();
+
+        assert_eq!(app.blame_column(0).map(<[BlameLine]>::len), Some(3));
+        assert_eq!(*requests.lock().unwrap(), [(0, "a.rs".to_string())]);
+
+        app.toggle_blame_column();
+        assert_eq!(app.blame_column(0), None);
+    }
+
+    #[test]
+    fn should_not_show_the_blame_column_outside_working_tree_reviews() {
+        let (mut app, requests) = blame_app(DiffSource::CommitRange(vec!["old".into()]));
+
+        app.toggle_blame_column();
+        app.load_current_file_blame();
+
+        assert!(!app.show_blame_column);
+        assert_eq!(
+            app.message.as_ref().map(|m| &m.message_type),
+            Some(&MessageType::Warning)
+        );
+        assert!(requests.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn should_blame_commit_range_lines_at_either_end_of_the_range() {
+        let (mut app, requests) =
+            blame_app(DiffSource::CommitRange(vec!["old".into(), "new".into()]));
+
+        move_to_line(&mut app, 0);
+        app.show_blame_at_cursor();
+        move_to_line(&mut app, 2);
+        app.show_blame_at_cursor();
+
+        assert_eq!(
+            *requests.lock().unwrap(),
+            [
+                (1, "Commit(\"new\")".to_string()),
+                (3, "ParentOf(\"old\")".to_string())
+            ]
+        );
+    }
+}
+
+#[cfg(test)]
+mod ignore_whitespace_tests {
+    use super::test_support::{build_app_with_files, make_file_with_hunks, make_hunk};
+    use super::*;
+
+    #[test]
+    fn should_warn_when_backend_cannot_ignore_whitespace() {
+        let file = make_file_with_hunks("a.rs", vec![make_hunk(1, 3)]);
+        let mut app = build_app_with_files(vec![file], 3);
+
+        app.toggle_ignore_whitespace();
+
+        assert!(!app.ignore_whitespace);
+        assert_eq!(
+            app.message.as_ref().map(|m| &m.message_type),
+            Some(&MessageType::Warning)
+        );
+    }
+
+    #[test]
+    fn should_block_hunk_staging_while_ignoring_whitespace() {
+        let file = make_file_with_hunks("a.rs", vec![make_hunk(1, 3)]);
+        let mut app = build_app_with_files(vec![file], 3);
+        app.diff_source = DiffSource::WorkingTree;
+        app.ignore_whitespace = true;
+
+        app.request_discard_hunk();
+
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert_eq!(
+            app.message.as_ref().map(|m| &m.message_type),
+            Some(&MessageType::Warning)
+        );
+    }
+}
+
+#[cfg(test)]
+mod moved_block_tests {
+    use super::test_support::{build_app_with_files, make_file_with_hunks, make_hunk};
+    use super::*;
+
+    fn moved_line(origin: LineOrigin, lineno: u32) -> DiffLine {
+        DiffLine {
+            origin,
+            content: format!("moved {lineno}"),
+            old_lineno: (origin == LineOrigin::Deletion).then_some(lineno),
+            new_lineno: (origin == LineOrigin::Addition).then_some(lineno),
+            highlighted_spans: None,
+            whitespace_only: false,
+            move_id: Some(0),
+        }
+    }
+
+    fn cursor_on(app: &App, file: usize, line: usize) -> usize {
+        app.line_annotations
+            .iter()
+            .position(|a| {
+                matches!(
+                    a,
+                    AnnotatedLine::DiffLine { file_idx, line_idx, .. }
+                        if *file_idx == file && *line_idx == line
+                )
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn should_jump_between_sides_of_a_moved_block() {
+        let mut from = make_hunk(1, 1);
+        from.lines.push(moved_line(LineOrigin::Deletion, 2));
+        let mut to = make_hunk(10, 1);
+        to.lines.push(moved_line(LineOrigin::Addition, 11));
+        let mut app = build_app_with_files(
+            vec![
+                make_file_with_hunks("a.rs", vec![from]),
+                make_file_with_hunks("b.rs", vec![to]),
+            ],
+            20,
+        );
+        app.diff_state.cursor_line = cursor_on(&app, 0, 1);
+
+        app.jump_to_moved_counterpart();
+        assert_eq!(app.diff_state.cursor_line, cursor_on(&app, 1, 1));
+        assert_eq!(app.diff_state.current_file_idx, 1);
+
+        app.jump_to_moved_counterpart();
+        assert_eq!(app.diff_state.cursor_line, cursor_on(&app, 0, 1));
+    }
+}
+
+#[cfg(test)]
+mod discard_hunk_tests {
+    use super::test_support::{build_app_with_files, make_file_with_hunks, make_hunk};
+    use super::*;
+
+    fn app_with_cursor_on_hunk(file: DiffFile) -> App {
+        let mut app = build_app_with_files(vec![file], 30);
+        app.diff_state.cursor_line = app
+            .line_annotations
+            .iter()
+            .position(|a| matches!(a, AnnotatedLine::HunkHeader { hunk_idx: 1, .. }))
+            .expect("hunk header not found");
+        app
+    }
+
+    #[test]
+    fn should_confirm_with_file_and_line_range() {
+        let file = make_file_with_hunks("a.rs", vec![make_hunk(1, 3), make_hunk(20, 2)]);
+        let mut app = app_with_cursor_on_hunk(file);
+
+        app.request_discard_hunk();
+
+        assert_eq!(app.input_mode, InputMode::Confirm);
+        assert_eq!(
+            app.pending_confirm,
+            Some(ConfirmAction::DiscardHunk {
+                file_idx: 0,
+                hunk_idx: 1
+            })
+        );
+        assert_eq!(app.confirm_message(), "Discard lines 20-21 of a.rs?");
+    }
+
+    #[test]
+    fn should_refuse_renamed_files() {
+        let mut file = make_file_with_hunks("b.rs", vec![make_hunk(1, 3), make_hunk(20, 2)]);
+        file.old_path = Some(PathBuf::from("a.rs"));
+        file.status = FileStatus::Renamed;
+        let mut app = app_with_cursor_on_hunk(file);
+
+        app.request_discard_hunk();
+
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert!(app.pending_confirm.is_none());
+    }
+
+    #[test]
+    fn should_refuse_when_file_changed_since_load() {
+        // The mock backend reports no working tree changes, unlike the loaded diff
+        let file = make_file_with_hunks("a.rs", vec![make_hunk(1, 3), make_hunk(20, 2)]);
+        let mut app = app_with_cursor_on_hunk(file);
+
+        app.discard_hunk(0, 1);
+
+        let message = app.message.as_ref().expect("expected an error");
+        assert!(
+            message
+                .content
+                .contains("changed since the diff was loaded")
+        );
+        assert_eq!(app.diff_files[0].hunks.len(), 2);
+    }
+}
+
+#[cfg(test)]
+mod undo_tests {
+    use super::test_support::{build_app_with_files, make_file_with_hunks, make_hunk};
+    use super::*;
+
+    fn app_with_line_comment(content: &str) -> App {
+        let file = make_file_with_hunks("a.rs", vec![make_hunk(1, 3)]);
+        let mut app = build_app_with_files(vec![file], 3);
+        app.enter_comment_mode(false, Some((2, LineSide::New)));
+        app.comment_buffer = content.to_string();
+        app.save_comment();
+        app
+    }
+
+    fn move_to_line_comment(app: &mut App) {
+        let idx = app
+            .line_annotations
+            .iter()
+            .position(|a| matches!(a, AnnotatedLine::LineComment { .. }))
+            .expect("line comment annotation");
+        app.diff_state.cursor_line = idx;
+    }
+
+    fn line_comments(app: &App) -> Vec<Comment> {
+        app.session.files[&PathBuf::from("a.rs")]
+            .line_comments
+            .get(&2)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn should_restore_original_content_when_undoing_edit() {
+        let mut app = app_with_line_comment("original");
+        let id = line_comments(&app)[0].id.clone();
+
+        move_to_line_comment(&mut app);
+        assert!(app.enter_edit_mode());
+        app.comment_buffer = "changed".to_string();
+        app.save_comment();
+        assert_eq!(line_comments(&app)[0].content, "changed");
+        assert_eq!(app.editing_comment_id, None);
+
+        app.undo();
+        assert_eq!(line_comments(&app)[0].content, "original");
+        assert_eq!(app.editing_comment_id, None);
+        assert!(app.dirty);
+        assert_eq!(
+            app.message.as_ref().map(|m| m.content.as_str()),
+            Some("Undid: edit comment on line 2")
+        );
+
+        // Re-editing after undo targets the same comment with the restored text
+        move_to_line_comment(&mut app);
+        assert!(app.enter_edit_mode());
+        assert_eq!(app.editing_comment_id.as_deref(), Some(id.as_str()));
+        assert_eq!(app.comment_buffer, "original");
+        app.exit_comment_mode();
+
+        app.redo();
+        assert_eq!(line_comments(&app)[0].content, "changed");
+    }
+
+    #[test]
+    fn should_return_comment_content_at_cursor() {
+        let mut app = app_with_line_comment("first line\nsecond line");
+        assert_eq!(app.comment_content_at_cursor(), None);
+
+        move_to_line_comment(&mut app);
+        assert_eq!(
+            app.comment_content_at_cursor().as_deref(),
+            Some("first line\nsecond line")
+        );
+    }
+
+    #[test]
+    fn should_restore_deleted_comment_on_undo() {
+        let mut app = app_with_line_comment("keep me");
+        move_to_line_comment(&mut app);
+        assert!(app.delete_comment_at_cursor());
+        assert!(line_comments(&app).is_empty());
+
+        app.undo();
+        assert_eq!(line_comments(&app)[0].content, "keep me");
+        assert!(
+            app.line_annotations
+                .iter()
+                .any(|a| matches!(a, AnnotatedLine::LineComment { .. }))
+        );
+    }
+
+    #[test]
+    fn should_undo_added_comment_and_reviewed_toggle() {
+        let mut app = app_with_line_comment("new");
+        app.toggle_reviewed();
+        assert!(app.session.is_file_reviewed(&PathBuf::from("a.rs")));
+
+        app.undo();
+        assert!(!app.session.is_file_reviewed(&PathBuf::from("a.rs")));
+        app.undo();
+        assert!(line_comments(&app).is_empty());
+        app.undo();
+        assert_eq!(
+            app.message.as_ref().map(|m| m.content.as_str()),
+            Some("Nothing to undo")
+        );
+    }
+
+    #[test]
+    fn should_undo_clear_as_single_step() {
+        let mut app = app_with_line_comment("one");
+        app.enter_review_comment_mode();
+        app.comment_buffer = "overall".to_string();
+        app.save_comment();
+        app.toggle_reviewed();
+
+        app.clear_comments(ClearScope::CommentsAndReviewed);
+        assert!(!app.session.has_comments());
+
+        app.undo();
+        assert_eq!(line_comments(&app).len(), 1);
+        assert_eq!(app.session.review_comments.len(), 1);
+        assert!(app.session.is_file_reviewed(&PathBuf::from("a.rs")));
+    }
+
+    fn reply_at_cursor(app: &mut App, content: &str) {
+        app.start_reply_at_cursor();
+        assert_eq!(app.input_mode, InputMode::Comment);
+        app.comment_buffer = content.to_string();
+        app.save_comment();
+    }
+
+    #[test]
+    fn should_thread_replies_under_their_root_comment() {
+        let mut app = app_with_line_comment("parent");
+        app.enter_comment_mode(false, Some((2, LineSide::New)));
+        app.comment_buffer = "sibling".to_string();
+        app.save_comment();
+        let root = line_comments(&app)[0].id.clone();
+
+        move_to_line_comment(&mut app);
+        reply_at_cursor(&mut app, "reply");
+        assert_eq!(app.reply_to, None);
+
+        // Replying to a reply stays in the same thread
+        app.diff_state.cursor_line += App::comment_display_lines(&line_comments(&app)[0]);
+        reply_at_cursor(&mut app, "second reply");
+
+        let comments = line_comments(&app);
+        let contents: Vec<&str> = comments.iter().map(|c| c.content.as_str()).collect();
+        assert_eq!(contents, vec!["parent", "reply", "second reply", "sibling"]);
+        assert_eq!(comments[1].parent_id.as_deref(), Some(root.as_str()));
+        assert_eq!(comments[2].parent_id.as_deref(), Some(root.as_str()));
+        assert_eq!(comments[3].parent_id, None);
+    }
+
+    #[test]
+    fn should_delete_replies_with_parent_and_restore_them_on_undo() {
+        let mut app = app_with_line_comment("parent");
+        move_to_line_comment(&mut app);
+        reply_at_cursor(&mut app, "reply");
+        assert_eq!(line_comments(&app).len(), 2);
+
+        move_to_line_comment(&mut app);
+        assert!(app.delete_comment_at_cursor());
+        assert!(line_comments(&app).is_empty());
+        assert_eq!(
+            app.message.as_ref().map(|m| m.content.as_str()),
+            Some("Comment and 1 reply deleted")
+        );
+
+        app.undo();
+        let contents: Vec<String> = line_comments(&app).into_iter().map(|c| c.content).collect();
+        assert_eq!(contents, vec!["parent", "reply"]);
+    }
+}
+
+#[cfg(test)]
+mod comment_author_tests {
+    use super::test_support::{build_app_with_files, make_file_with_hunks, make_hunk};
+    use super::*;
+
+    fn line_comment_authors(app: &App) -> Vec<Option<String>> {
+        app.session.files[&PathBuf::from("a.rs")].line_comments[&2]
+            .iter()
+            .map(|comment| comment.author.clone())
+            .collect()
+    }
+
+    #[test]
+    fn should_attribute_new_comments_and_keep_author_on_edit() {
+        let file = make_file_with_hunks("a.rs", vec![make_hunk(1, 3)]);
+        let mut app = build_app_with_files(vec![file], 3);
+        app.comment_author = Some("alice".to_string());
+        app.enter_comment_mode(false, Some((2, LineSide::New)));
+        app.comment_buffer = "first".to_string();
+        app.save_comment();
+
+        app.set_comment_author(Some(" bob "));
+        assert_eq!(app.comment_author.as_deref(), Some("bob"));
+        app.enter_comment_mode(false, Some((2, LineSide::New)));
+        app.comment_buffer = "second".to_string();
+        app.save_comment();
+        assert_eq!(
+            line_comment_authors(&app),
+            vec![Some("alice".to_string()), Some("bob".to_string())]
+        );
+
+        // bob editing alice's comment leaves it attributed to alice
+        app.diff_state.cursor_line = app
+            .line_annotations
+            .iter()
+            .position(|a| matches!(a, AnnotatedLine::LineComment { .. }))
+            .unwrap();
+        assert!(app.enter_edit_mode());
+        app.comment_buffer = "first, edited".to_string();
+        app.save_comment();
+        let comments = &app.session.files[&PathBuf::from("a.rs")].line_comments[&2];
+        assert_eq!(comments[0].content, "first, edited");
+        assert_eq!(comments[0].author.as_deref(), Some("alice"));
+
+        app.set_comment_author(None);
+        assert_eq!(
+            app.message.as_ref().map(|m| m.content.as_str()),
+            Some("New comments are attributed to bob")
+        );
+    }
+}
+
+#[cfg(test)]
+mod session_browser_tests {
+    use super::test_support::{build_app_with_files, make_file_with_hunks, make_hunk};
+    use super::*;
+
+    fn app_with_saved_session(stale: bool) -> App {
+        let file = make_file_with_hunks("a.rs", vec![make_hunk(1, 3)]);
+        let mut app = build_app_with_files(vec![file], 3);
+
+        let mut saved = ReviewSession::new(
+            PathBuf::from("/tmp"),
+            "def456".to_string(),
+            Some("feature".to_string()),
+            SessionDiffSource::CommitRange,
+        );
+        saved.commit_range = Some(vec!["def456".to_string()]);
+        saved
+            .review_comments
+            .push(Comment::new("old".to_string(), CommentType::Note, None));
+
+        let current = app.session.clone();
+        app.session_browser = SessionBrowserState {
+            entries: vec![
+                SessionBrowserEntry {
+                    path: PathBuf::from("/tmp/saved.json"),
+                    session: saved,
+                    stale,
+                },
+                SessionBrowserEntry {
+                    path: PathBuf::from("/tmp/current.json"),
+                    session: current,
+                    stale: false,
+                },
+            ],
+            selected: 0,
+        };
+        app.input_mode = InputMode::SessionBrowser;
+        app
+    }
+
+    #[test]
+    fn should_load_stale_session_with_warning_and_keep_diff() {
+        let mut app = app_with_saved_session(true);
+        app.session_browser.entries[0].session.add_file(
+            PathBuf::from("gone.rs"),
+            FileStatus::Modified,
+            0,
+        );
+        app.toggle_reviewed();
+        app.dirty = false;
+
+        app.load_selected_session();
+
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert_eq!(app.session.branch_name.as_deref(), Some("feature"));
+        assert_eq!(app.session.review_comments.len(), 1);
+        assert_eq!(app.diff_files.len(), 1);
+        assert!(app.session.files.contains_key(&PathBuf::from("a.rs")));
+        assert!(!app.session.files.contains_key(&PathBuf::from("gone.rs")));
+        let message = app.message.as_ref().unwrap();
+        assert_eq!(message.message_type, MessageType::Warning);
+        assert!(message.content.contains("1 file outside it dropped"));
+        app.undo();
+        assert_eq!(app.message.as_ref().unwrap().content, "Nothing to undo");
+    }
+
+    #[test]
+    fn should_refuse_to_load_session_with_unsaved_changes() {
+        let mut app = app_with_saved_session(false);
+        let current_id = app.session.id.clone();
+        app.dirty = true;
+
+        app.load_selected_session();
+
+        assert_eq!(app.session.id, current_id);
+        assert_eq!(app.input_mode, InputMode::SessionBrowser);
+    }
+
+    #[test]
+    fn should_not_delete_active_session() {
+        let mut app = app_with_saved_session(false);
+        app.session_browser_down(1);
+        app.enter_confirm_mode(ConfirmAction::DeleteSession);
+        app.exit_confirm_mode();
+        assert_eq!(app.input_mode, InputMode::SessionBrowser);
+
+        app.delete_selected_session();
+
+        assert_eq!(app.session_browser.entries.len(), 2);
+        assert_eq!(
+            app.message.as_ref().map(|m| &m.message_type),
+            Some(&MessageType::Error)
+        );
+    }
+
+    #[test]
+    fn should_start_empty_session_for_current_diff() {
+        let mut app = app_with_saved_session(false);
+        app.session
+            .review_comments
+            .push(Comment::new("note".to_string(), CommentType::Note, None));
+        let old_id = app.session.id.clone();
+
+        app.start_new_session();
+
+        assert_ne!(app.session.id, old_id);
+        assert!(!app.session.has_comments());
+        assert!(app.session.files.contains_key(&PathBuf::from("a.rs")));
+        assert_eq!(app.input_mode, InputMode::Normal);
+    }
+}
+
+#[cfg(test)]
+mod autosave_tests {
+    use super::test_support::{build_app_with_files, make_file_with_hunks, make_hunk};
+    use super::*;
+    use crate::persistence::storage::tests::with_test_reviews_dir;
+
+    fn dirty_app() -> App {
+        let file = make_file_with_hunks("a.rs", vec![make_hunk(1, 3)]);
+        let mut app = build_app_with_files(vec![file], 3);
+        app.toggle_reviewed();
+        assert!(app.dirty);
+        app
+    }
+
+    #[test]
+    fn should_clear_dirty_flag_after_autosave() {
+        let _guard = with_test_reviews_dir();
+        let mut app = dirty_app();
+
+        app.autosave().unwrap();
+
+        assert!(!app.dirty);
+        assert!(app.show_saved_indicator());
+    }
+
+    #[test]
+    fn should_wait_for_interval_before_autosaving() {
+        let _guard = with_test_reviews_dir();
+        let mut app = dirty_app();
+        app.autosave_interval = Some(Duration::from_secs(3600));
+
+        app.autosave_if_due();
+        assert!(app.dirty);
+
+        app.autosave_interval = Some(Duration::ZERO);
+        app.autosave_if_due();
+        assert!(!app.dirty);
+    }
+
+    #[test]
+    fn should_not_autosave_when_disabled() {
+        let _guard = with_test_reviews_dir();
+        let mut app = dirty_app();
+        app.autosave_interval = None;
+
+        app.autosave().unwrap();
+
+        assert!(app.dirty);
+        assert!(!app.show_saved_indicator());
+    }
+
+    #[test]
+    fn should_cancel_the_first_quit_when_autosave_fails() {
+        let _guard = with_test_reviews_dir();
+        let not_a_dir =
+            std::env::temp_dir().join(format!("tuicr-not-a-dir-{}", uuid::Uuid::new_v4()));
+        std::fs::write(&not_a_dir, "").unwrap();
+        unsafe {
+            std::env::set_var("TUICR_REVIEWS_DIR", &not_a_dir);
+        }
+        let mut app = dirty_app();
+        app.should_quit = true;
+
+        assert!(!app.finish_quit());
+        assert!(!app.should_quit);
+        assert!(app.dirty);
+        assert!(
+            app.message
+                .as_ref()
+                .unwrap()
+                .content
+                .starts_with("Autosave failed")
+        );
+
+        app.should_quit = true;
+        assert!(app.finish_quit());
+        let _ = std::fs::remove_file(&not_a_dir);
+    }
+}
+
+#[cfg(test)]
+mod conflict_tests {
+    use super::test_support::{build_app_with_files, make_file_with_hunks, make_hunk};
+    use super::*;
+
+    fn files(conflicted: &[bool]) -> Vec<DiffFile> {
+        conflicted
+            .iter()
+            .enumerate()
+            .map(|(idx, &is_conflicted)| {
+                let mut file = make_file_with_hunks(&format!("f{idx}.rs"), vec![make_hunk(1, 2)]);
+                file.is_conflicted = is_conflicted;
+                file
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_jump_between_conflicted_files_with_wraparound() {
+        let mut app = build_app_with_files(files(&[false, true, false, true]), 2);
+
+        assert!(app.jump_to_conflicted_file(true));
+        assert_eq!(app.diff_state.current_file_idx, 1);
+        assert!(app.jump_to_conflicted_file(true));
+        assert_eq!(app.diff_state.current_file_idx, 3);
+        assert!(app.jump_to_conflicted_file(true));
+        assert_eq!(app.diff_state.current_file_idx, 1);
+        assert!(app.jump_to_conflicted_file(false));
+        assert_eq!(app.diff_state.current_file_idx, 3);
+    }
+
+    #[test]
+    fn should_report_no_conflicted_files() {
+        let mut app = build_app_with_files(files(&[false, false]), 2);
+
+        assert!(!app.jump_to_conflicted_file(true));
+        assert_eq!(app.diff_state.current_file_idx, 0);
+    }
+
+    #[test]
+    fn should_warn_when_marking_conflicted_file_reviewed() {
+        let mut app = build_app_with_files(files(&[true]), 2);
+
+        app.toggle_reviewed();
+
+        assert!(app.session.is_file_reviewed(&PathBuf::from("f0.rs")));
+        let message = app.message.as_ref().expect("expected a warning");
+        assert_eq!(message.message_type, MessageType::Warning);
+        assert!(message.content.contains("unresolved merge conflicts"));
+    }
+}
+
+#[cfg(test)]
+mod truncated_file_tests {
+    use super::test_support::{make_file_with_hunks, make_hunk};
+    use super::*;
+    use crate::model::Comment;
+    use crate::vcs::traits::VcsType;
+
+    /// Backend serving one 10-line file, cut to 4 lines until fully loaded.
+    struct TruncatingVcs {
+        info: VcsInfo,
+        fully_loaded: bool,
+    }
+
+    impl TruncatingVcs {
+        fn diff(&self) -> DiffFile {
+            let mut file = make_file_with_hunks("big.rs", vec![make_hunk(1, 10)]);
+            if !self.fully_loaded {
+                file.truncate_lines(4);
+            }
+            file
+        }
+    }
+
+    impl VcsBackend for TruncatingVcs {
+        fn info(&self) -> &VcsInfo {
+            &self.info
+        }
+
+        fn get_working_tree_diff(&self) -> Result<Vec<DiffFile>> {
+            Ok(vec![self.diff()])
+        }
+
+        fn fetch_context_lines(
+            &self,
+            _file_path: &Path,
+            _file_status: FileStatus,
+            _start_line: u32,
+            _end_line: u32,
+        ) -> Result<Vec<DiffLine>> {
+            Ok(Vec::new())
+        }
+
+        fn load_file_fully(&mut self, _path: &Path) {
+            self.fully_loaded = true;
+        }
+    }
+
+    fn build_app() -> App {
+        let vcs_info = VcsInfo {
+            root_path: PathBuf::from("/tmp"),
+            head_commit: "abc123".to_string(),
+            branch_name: None,
+            vcs_type: VcsType::Git,
+        };
+        let vcs = TruncatingVcs {
+            info: vcs_info.clone(),
+            fully_loaded: false,
+        };
+        let files = vec![vcs.diff()];
+        let session = ReviewSession::new(
+            vcs_info.root_path.clone(),
+            vcs_info.head_commit.clone(),
+            None,
+            SessionDiffSource::WorkingTree,
+        );
+        App::build(
+            Box::new(vcs),
+            vcs_info,
+            Theme::dark(),
+            None,
+            false,
+            files,
+            session,
+            DiffSource::WorkingTree,
+            InputMode::Normal,
+            Vec::new(),
+            &[],
+            None,
+        )
+        .expect("failed to build test app")
+    }
+
+    fn count_lines(app: &App) -> usize {
+        app.line_annotations
+            .iter()
+            .filter(|a| matches!(a, AnnotatedLine::DiffLine { .. }))
+            .count()
+    }
+
+    #[test]
+    fn should_load_remaining_lines_and_keep_comments() {
+        let mut app = build_app();
+        assert_eq!(count_lines(&app), 4);
+        assert_eq!(
+            app.diff_files[0].truncated_descriptor(),
+            "… 6 more lines (press Enter to load)"
+        );
+
+        app.session
+            .get_file_mut(&PathBuf::from("big.rs"))
+            .unwrap()
+            .add_line_comment(
+                2,
+                Comment::new(
+                    "keep me".to_string(),
+                    CommentType::Note,
+                    Some(LineSide::New),
+                ),
+            );
+        app.rebuild_annotations();
+        app.diff_state.cursor_line = app
+            .line_annotations
+            .iter()
+            .position(|a| matches!(a, AnnotatedLine::TruncatedFile { .. }))
+            .expect("expected a truncated file line");
+        assert_eq!(app.truncated_file_at_cursor(), Some(0));
+
+        app.load_full_file(0);
+
+        assert_eq!(count_lines(&app), 10);
+        assert_eq!(app.diff_files[0].truncated_lines, 0);
+        assert!(
+            app.line_annotations
+                .iter()
+                .any(|a| matches!(a, AnnotatedLine::LineComment { line: 2, .. }))
+        );
+        assert!(
+            !app.line_annotations
+                .iter()
+                .any(|a| matches!(a, AnnotatedLine::TruncatedFile { .. }))
+        );
+    }
+}
+
+#[cfg(test)]
+mod background_highlight_tests {
+    use super::test_support::{build_app_with_files, make_file_with_hunks, make_hunk};
+    use super::*;
+
+    fn wait_for_highlighting(app: &mut App) {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !app.apply_highlight_results() {
+            assert!(Instant::now() < deadline, "highlighting timed out");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn should_highlight_only_files_in_view() {
+        let mut app = build_app_with_files(
+            vec![
+                make_file_with_hunks("a.rs", vec![make_hunk(1, 3)]),
+                make_file_with_hunks("b.rs", vec![make_hunk(1, 3)]),
+            ],
+            10,
+        );
+        app.diff_state.scroll_offset = 0;
+        app.diff_state.viewport_height = 4;
+
+        app.highlight_visible_files();
+        // Re-requesting while the job is queued doesn't add duplicate work
+        app.highlight_visible_files();
+        assert_eq!(app.highlight_in_flight.len(), 1);
+        wait_for_highlighting(&mut app);
+
+        assert!(app.diff_files[0].highlighted);
+        assert!(
+            app.diff_files[0].hunks[0]
+                .lines
+                .iter()
+                .all(|line| line.highlighted_spans.is_some())
+        );
+        assert!(!app.diff_files[1].highlighted);
+        assert!(app.highlight_in_flight.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod file_filter_tests {
+    use super::test_support::{
+        build_app_with_files, make_file_with_hunks, make_hunk, try_build_app,
+    };
+    use super::*;
+
+    fn listed_files(app: &App) -> Vec<usize> {
+        app.build_visible_items()
+            .iter()
+            .filter_map(|item| match item {
+                FileTreeItem::File { file_idx, .. } => Some(*file_idx),
+                FileTreeItem::Directory { .. } => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_match_paths_and_bare_file_names() {
+        let filter = FileFilter::new("src/**/*.rs").unwrap();
+        assert!(filter.matches(Path::new("src/main.rs")));
+        assert!(filter.matches(Path::new("src/ui/app.rs")));
+        assert!(!filter.matches(Path::new("tests/app.rs")));
+
+        let filter = FileFilter::new("*.md").unwrap();
+        assert!(filter.matches(Path::new("docs/guide/README.md")));
+        assert!(!filter.matches(Path::new("src/main.rs")));
+    }
+
+    #[test]
+    fn should_restrict_file_list_and_navigation_to_matches() {
+        let mut app = build_app_with_files(
+            vec![
+                make_file_with_hunks("README.md", vec![make_hunk(1, 2)]),
+                make_file_with_hunks("src/a.rs", vec![make_hunk(1, 2)]),
+                make_file_with_hunks("src/b.toml", vec![make_hunk(1, 2)]),
+                make_file_with_hunks("src/c.rs", vec![make_hunk(1, 2)]),
+            ],
+            10,
+        );
+        app.expanded_dirs.insert("src".to_string());
+
+        app.set_file_filter(Some("src/**/*.rs"));
+        assert_eq!(listed_files(&app), [1, 3]);
+        assert_eq!(app.diff_state.current_file_idx, 1);
+
+        app.next_file();
+        assert_eq!(app.diff_state.current_file_idx, 3);
+        app.prev_file();
+        assert_eq!(app.diff_state.current_file_idx, 1);
+
+        app.set_file_filter(None);
+        assert!(app.file_filter.is_none());
+        assert_eq!(listed_files(&app), [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn should_keep_previous_filter_when_nothing_matches() {
+        let mut app = build_app_with_files(
+            vec![make_file_with_hunks("src/a.rs", vec![make_hunk(1, 2)])],
+            10,
+        );
+        app.set_file_filter(Some("*.rs"));
+
+        app.set_file_filter(Some("*.go"));
+        assert_eq!(app.file_filter.as_ref().unwrap().pattern, "*.rs");
+
+        app.set_file_filter(Some("src/[a"));
+        assert_eq!(app.file_filter.as_ref().unwrap().pattern, "*.rs");
+    }
+
+    #[test]
+    fn should_drop_files_outside_the_files_glob_at_startup() {
+        let filter = FileFilter::new("src/**").unwrap();
+        let app = try_build_app(
+            vec![
+                make_file_with_hunks("README.md", vec![make_hunk(1, 2)]),
+                make_file_with_hunks("src/a.rs", vec![make_hunk(1, 2)]),
+            ],
+            10,
+            Some(&filter),
+        )
+        .unwrap();
+
+        assert_eq!(app.diff_files.len(), 1);
+        assert_eq!(app.diff_files[0].display_path(), Path::new("src/a.rs"));
+        assert!(!app.session.files.contains_key(Path::new("README.md")));
+        assert_eq!(app.file_filter.as_ref().unwrap().pattern, "src/**");
+
+        let err = try_build_app(
+            vec![make_file_with_hunks("README.md", vec![make_hunk(1, 2)])],
+            10,
+            Some(&filter),
+        )
+        .err()
+        .unwrap();
+        assert_eq!(err.to_string(), "No changes to review match --files src/**");
+    }
+}
+
+#[cfg(test)]
+mod incremental_annotation_tests {
+    use super::test_support::{build_app_with_files, make_file_with_hunks, make_hunk};
+    use super::*;
+
+    /// Annotations and line counts kept up to date incrementally must equal
+    /// a full rebuild from scratch.
+    fn assert_matches_full_rebuild(app: &mut App) {
+        let file_offsets = |app: &App| -> Vec<usize> {
+            (0..app.diff_files.len())
+                .map(|idx| app.calculate_file_scroll_offset(idx))
+                .collect()
+        };
+        let incremental = (
+            app.line_annotations.clone(),
+            app.total_lines(),
+            file_offsets(app),
+        );
+        app.rebuild_annotations();
+        assert_eq!(
+            incremental,
+            (
+                app.line_annotations.clone(),
+                app.total_lines(),
+                file_offsets(app)
+            )
+        );
+        assert_eq!(app.total_lines(), app.line_annotations.len());
+    }
+
+    fn add_comment(app: &mut App, file_idx: usize, line: Option<u32>, text: &str) {
+        app.diff_state.current_file_idx = file_idx;
+        app.enter_comment_mode(line.is_none(), line.map(|l| (l, LineSide::New)));
+        app.comment_buffer = text.to_string();
+        app.save_comment();
+    }
+
+    #[test]
+    fn should_match_full_rebuild_after_scripted_edits() {
+        let mut app = build_app_with_files(
+            vec![
+                make_file_with_hunks("a.rs", vec![make_hunk(1, 3), make_hunk(40, 3)]),
+                make_file_with_hunks("b.rs", vec![make_hunk(30, 4)]),
+                make_file_with_hunks("c.rs", vec![make_hunk(5, 2), make_hunk(60, 2)]),
+            ],
+            100,
+        );
+
+        add_comment(&mut app, 1, Some(31), "first\nsecond line");
+        assert_matches_full_rebuild(&mut app);
+
+        add_comment(&mut app, 2, None, "file comment");
+        assert_matches_full_rebuild(&mut app);
+
+        app.enter_review_comment_mode();
+        app.comment_buffer = "overall".to_string();
+        app.save_comment();
+        assert_matches_full_rebuild(&mut app);
+
+        app.toggle_reviewed_for_file_idx(0, false);
+        assert_matches_full_rebuild(&mut app);
+
+        let gap_id = GapId {
+            file_idx: 2,
+            hunk_idx: 1,
+        };
+        app.expand_gap(gap_id.clone(), ExpandDirection::Down, Some(5))
+            .unwrap();
+        assert_matches_full_rebuild(&mut app);
+
+        let comment_line = app
+            .line_annotations
+            .iter()
+            .position(|a| matches!(a, AnnotatedLine::LineComment { file_idx: 1, .. }))
+            .unwrap();
+        app.diff_state.cursor_line = comment_line;
+        assert!(app.delete_comment_at_cursor());
+        assert_matches_full_rebuild(&mut app);
+
+        app.collapse_gap(gap_id);
+        app.toggle_reviewed_for_file_idx(0, false);
+        assert_matches_full_rebuild(&mut app);
+
+        app.diff_view_mode = DiffViewMode::SideBySide;
+        app.rebuild_annotations();
+        add_comment(&mut app, 0, Some(2), "in side-by-side");
+        assert_matches_full_rebuild(&mut app);
+    }
+
+    #[test]
+    fn should_leave_other_files_untouched_when_one_changes() {
+        let mut app = build_app_with_files(
+            vec![
+                make_file_with_hunks("a.rs", vec![make_hunk(1, 3)]),
+                make_file_with_hunks("b.rs", vec![make_hunk(1, 3)]),
+            ],
+            10,
+        );
+        let first_file_end = app.calculate_file_scroll_offset(1);
+        let before = app.line_annotations[..first_file_end].to_vec();
+
+        add_comment(&mut app, 1, Some(2), "note");
+
+        assert_eq!(app.line_annotations[..first_file_end], before[..]);
+        assert_eq!(app.calculate_file_scroll_offset(1), first_file_end);
+        assert_eq!(app.total_lines(), app.line_annotations.len());
+    }
+}
+
+#[cfg(test)]
+mod panel_size_tests {
+    use super::test_support::{build_app_with_files, make_file_with_hunks, make_hunk};
+
+    #[test]
+    fn should_resize_file_list_within_bounds_and_save_the_width() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("tuicr").join("config.toml");
+        let mut app =
+            build_app_with_files(vec![make_file_with_hunks("a.rs", vec![make_hunk(1, 3)])], 3);
+        app.config_path = Some(config.clone());
+
+        app.resize_file_list(true);
+        assert_eq!(app.file_list_width_percent, 25);
+        assert_eq!(
+            std::fs::read_to_string(&config).unwrap(),
+            "file_list_width = 25\n"
+        );
+
+        for _ in 0..20 {
+            app.resize_file_list(true);
+        }
+        assert_eq!(app.file_list_width_percent, 60);
+        for _ in 0..20 {
+            app.resize_file_list(false);
+        }
+        assert_eq!(app.file_list_width_percent, 15);
+
+        app.resize_commit_selector(false);
+        assert_eq!(app.commit_selector_height, 7);
+        assert_eq!(
+            std::fs::read_to_string(&config).unwrap(),
+            "file_list_width = 15\ncommit_selector_height = 7\n"
+        );
+    }
+
+    #[test]
+    fn should_switch_theme_live_and_save_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+        let mut file = make_file_with_hunks("a.rs", vec![make_hunk(1, 3)]);
+        file.highlighted = true;
+        let mut app = build_app_with_files(vec![file], 3);
+        app.config_path = Some(config.clone());
+
+        app.set_theme("Gruvbox-Light");
+        assert_eq!(
+            app.theme.panel_bg,
+            crate::theme::Theme::gruvbox_light().panel_bg
+        );
+        assert_eq!(
+            app.comment_type_color(&crate::model::CommentType::Issue),
+            app.theme.comment_issue
+        );
+        assert!(!app.diff_files[0].highlighted);
+        assert_eq!(
+            std::fs::read_to_string(&config).unwrap(),
+            "theme = \"gruvbox-light\"\n"
+        );
+
+        app.set_theme("neon");
+        assert_eq!(
+            app.theme.panel_bg,
+            crate::theme::Theme::gruvbox_light().panel_bg
+        );
+        let message = app.message.as_ref().unwrap();
+        assert!(
+            message
+                .content
+                .starts_with("Unknown theme 'neon'. Valid options: dark, light")
+        );
+    }
+}
+
+#[cfg(test)]
+mod bracket_motion_tests {
+    use super::test_support::{build_app_with_files, make_file_with_hunks, make_hunk};
+    use super::*;
+
+    /// a.rs with a file comment, b.rs with a comment on line 2, and c.rs.
+    fn app_with_comments() -> App {
+        let files = ["a.rs", "b.rs", "c.rs"]
+            .into_iter()
+            .map(|path| make_file_with_hunks(path, vec![make_hunk(1, 3)]))
+            .collect();
+        let mut app = build_app_with_files(files, 3);
+        let reviews = &mut app.session.files;
+        reviews
+            .get_mut(Path::new("a.rs"))
+            .unwrap()
+            .add_file_comment(Comment::new("file".to_string(), CommentType::Note, None));
+        reviews
+            .get_mut(Path::new("b.rs"))
+            .unwrap()
+            .add_line_comment(
+                2,
+                Comment::new("line".to_string(), CommentType::Issue, Some(LineSide::New)),
+            );
+        app.rebuild_annotations();
+        app
+    }
+
+    fn header_row(app: &App, idx: usize) -> usize {
+        app.line_annotations
+            .iter()
+            .position(|a| *a == AnnotatedLine::FileHeader { file_idx: idx })
+            .unwrap()
+    }
+
+    #[test]
+    fn should_move_between_comments_without_wrapping() {
+        let mut app = app_with_comments();
+        let file_comment = header_row(&app, 0) + 1;
+        let line_comment = app
+            .line_annotations
+            .iter()
+            .position(|a| matches!(a, AnnotatedLine::LineComment { .. }))
+            .unwrap();
+        assert!(matches!(
+            app.line_annotations[file_comment],
+            AnnotatedLine::FileComment { file_idx: 0, .. }
+        ));
+
+        app.jump_bracket_motion(BracketMotion::Comment, true);
+        assert_eq!(app.diff_state.cursor_line, file_comment);
+        app.jump_bracket_motion(BracketMotion::Comment, true);
+        assert_eq!(app.diff_state.cursor_line, line_comment);
+        assert_eq!(app.diff_state.current_file_idx, 1);
+
+        app.jump_bracket_motion(BracketMotion::Comment, true);
+        assert_eq!(app.diff_state.cursor_line, line_comment);
+        assert_eq!(
+            app.message.as_ref().map(|m| m.content.as_str()),
+            Some("No more comments below")
+        );
+
+        app.jump_bracket_motion(BracketMotion::Comment, false);
+        assert_eq!(app.diff_state.cursor_line, file_comment);
+        app.jump_bracket_motion(BracketMotion::Comment, false);
+        assert_eq!(app.diff_state.cursor_line, file_comment);
+        assert_eq!(
+            app.message.as_ref().map(|m| m.content.as_str()),
+            Some("No more comments above")
+        );
+    }
+
+    #[test]
+    fn should_move_between_file_headers() {
+        let mut app = app_with_comments();
+
+        app.jump_bracket_motion(BracketMotion::File, true);
+        assert_eq!(app.diff_state.cursor_line, header_row(&app, 0));
+        app.jump_bracket_motion(BracketMotion::File, true);
+        app.jump_bracket_motion(BracketMotion::File, true);
+        assert_eq!(app.diff_state.cursor_line, header_row(&app, 2));
+        assert_eq!(app.diff_state.current_file_idx, 2);
+
+        app.jump_bracket_motion(BracketMotion::File, true);
+        assert_eq!(app.diff_state.cursor_line, header_row(&app, 2));
+        assert_eq!(
+            app.message.as_ref().map(|m| m.content.as_str()),
+            Some("No more files below")
+        );
+        app.jump_bracket_motion(BracketMotion::File, false);
+        assert_eq!(app.diff_state.cursor_line, header_row(&app, 1));
+    }
+
+    #[test]
+    fn should_skip_reviewed_files_when_moving_to_unreviewed_ones() {
+        let mut app = app_with_comments();
+        app.toggle_reviewed_for_file_idx(1, false);
+        app.diff_state.cursor_line = 0;
+
+        app.jump_bracket_motion(BracketMotion::UnreviewedFile, true);
+        assert_eq!(app.diff_state.cursor_line, header_row(&app, 0));
+        app.jump_bracket_motion(BracketMotion::UnreviewedFile, true);
+        assert_eq!(app.diff_state.cursor_line, header_row(&app, 2));
+
+        app.jump_bracket_motion(BracketMotion::UnreviewedFile, true);
+        assert_eq!(
+            app.message.as_ref().map(|m| m.content.as_str()),
+            Some("No more unreviewed files below")
+        );
+        app.jump_bracket_motion(BracketMotion::UnreviewedFile, false);
+        assert_eq!(app.diff_state.cursor_line, header_row(&app, 0));
+    }
+}
+
+#[cfg(test)]
+mod changes_only_tests {
+    use super::test_support::{build_app_with_files, make_file_with_hunks, make_hunk};
+    use super::*;
+
+    fn line(origin: LineOrigin, old: Option<u32>, new: Option<u32>) -> DiffLine {
+        DiffLine {
+            origin,
+            content: format!("{origin:?}"),
+            old_lineno: old,
+            new_lineno: new,
+            highlighted_spans: None,
+            whitespace_only: false,
+            move_id: None,
+        }
+    }
+
+    /// Two context lines, a changed line, four context lines with a comment on
+    /// the third.
+    fn app_with_context() -> App {
+        use LineOrigin::{Addition, Context, Deletion};
+        let mut hunk = make_hunk(1, 0);
+        hunk.lines = vec![
+            line(Context, Some(1), Some(1)),
+            line(Context, Some(2), Some(2)),
+            line(Deletion, Some(3), None),
+            line(Addition, None, Some(3)),
+            line(Context, Some(4), Some(4)),
+            line(Context, Some(5), Some(5)),
+            line(Context, Some(6), Some(6)),
+            line(Context, Some(7), Some(7)),
+        ];
+        let mut app = build_app_with_files(vec![make_file_with_hunks("a.rs", vec![hunk])], 7);
+        app.session
+            .files
+            .get_mut(Path::new("a.rs"))
+            .unwrap()
+            .add_line_comment(
+                6,
+                Comment::new("keep".to_string(), CommentType::Note, Some(LineSide::New)),
+            );
+        app.rebuild_annotations();
+        app
+    }
+
+    fn hunk_annotations(app: &App) -> Vec<&AnnotatedLine> {
+        app.line_annotations
+            .iter()
+            .skip_while(|a| !matches!(a, AnnotatedLine::HunkHeader { .. }))
+            .skip(1)
+            .take_while(|a| !matches!(a, AnnotatedLine::Spacing))
+            .filter(|a| !matches!(a, AnnotatedLine::LineComment { .. }))
+            .collect()
+    }
+
+    #[test]
+    fn should_fold_uncommented_context_runs_into_one_row() {
+        let mut app = app_with_context();
+        let total = app.total_lines();
+
+        app.set_changes_only(true);
+
+        let diff_line = |line_idx| AnnotatedLine::DiffLine {
+            file_idx: 0,
+            hunk_idx: 0,
+            line_idx,
+            old_lineno: [None, None, Some(3), None, None, None, Some(6)][line_idx],
+            new_lineno: [None, None, None, Some(3), None, None, Some(6)][line_idx],
+        };
+        let hidden = |count| AnnotatedLine::HiddenContext { file_idx: 0, count };
+        assert_eq!(
+            hunk_annotations(&app),
+            [
+                &hidden(2),
+                &diff_line(2),
+                &diff_line(3),
+                &hidden(2),
+                &diff_line(6),
+                &hidden(1),
+            ]
+        );
+        assert_eq!(app.total_lines(), total - 8 + 6);
+        assert_eq!(app.total_lines(), app.line_annotations.len());
+
+        app.set_changes_only(false);
+        assert_eq!(app.total_lines(), total);
+    }
+
+    #[test]
+    fn should_keep_the_cursor_on_its_line_when_toggling() {
+        let mut app = app_with_context();
+        let row_of = |app: &App, new: u32| {
+            app.line_annotations
+                .iter()
+                .position(|a| {
+                    matches!(a, AnnotatedLine::DiffLine { new_lineno: Some(n), .. } if *n == new)
+                })
+                .unwrap()
+        };
+        app.diff_state.cursor_line = row_of(&app, 3);
+
+        app.set_changes_only(true);
+        assert_eq!(app.diff_state.cursor_line, row_of(&app, 3));
+
+        app.toggle_diff_view_mode();
+        app.set_changes_only(false);
+        app.set_changes_only(true);
+        assert!(
+            app.line_annotations
+                .contains(&AnnotatedLine::HiddenContext {
+                    file_idx: 0,
+                    count: 2
+                })
+        );
+    }
+}
+
+#[cfg(test)]
+mod reanchor_tests {
+    use super::test_support::{build_app_with_files, make_file_with_hunks, make_hunk};
+    use super::*;
+
+    #[test]
+    fn should_follow_comment_after_lines_are_inserted_above() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.rs");
+        std::fs::write(&file, "fn a() {\n    one();\n    two();\n}\n").unwrap();
+        let mut app =
+            build_app_with_files(vec![make_file_with_hunks("a.rs", vec![make_hunk(1, 4)])], 4);
+        app.vcs_info.root_path = dir.path().to_path_buf();
+
+        app.comment_is_file_level = false;
+        app.comment_line = Some((3, LineSide::New));
+        app.comment_buffer = "why twice?".to_string();
+        app.save_comment();
+        let path = PathBuf::from("a.rs");
+        let fingerprint = app.session.files[&path].line_comments[&3][0]
+            .fingerprint
+            .clone();
+        assert_eq!(fingerprint.map(|f| f.line), Some("two();".to_string()));
+
+        std::fs::write(
+            &file,
+            "use std::io;\n\nfn a() {\n    one();\n    two();\n}\n",
+        )
+        .unwrap();
+        app.dirty = false;
+        app.reanchor_comments();
+        let review = &app.session.files[&path];
+        assert_eq!(review.line_comments.keys().collect::<Vec<_>>(), [&5]);
+        assert!(app.dirty);
+
+        std::fs::write(&file, "use std::io;\n\nfn a() {\n    one();\n}\n").unwrap();
+        app.reanchor_comments();
+        let review = &app.session.files[&path];
+        assert!(review.line_comments.is_empty());
+        assert!(review.file_comments[0].orphaned);
+        assert_eq!(
+            review.file_comments[0].line_range,
+            Some(LineRange::single(5))
+        );
+    }
+}
+
+#[cfg(test)]
+mod hunk_review_tests {
+    use super::test_support::{build_app_with_files, make_file_with_hunks, make_hunk};
+    use super::*;
+
+    fn header_rows(app: &App) -> Vec<usize> {
+        app.line_annotations
+            .iter()
+            .enumerate()
+            .filter(|(_, a)| matches!(a, AnnotatedLine::HunkHeader { .. }))
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    #[test]
+    fn should_mark_file_reviewed_once_all_hunks_are() {
+        let hunks = vec![make_hunk(1, 3), make_hunk(20, 3)];
+        let mut app = build_app_with_files(vec![make_file_with_hunks("a.rs", hunks)], 30);
+        let path = PathBuf::from("a.rs");
+        let headers = header_rows(&app);
+
+        app.diff_state.cursor_line = headers[0] + 1;
+        app.toggle_hunk_reviewed_at_cursor();
+        assert_eq!(app.hunk_progress(), (0, 2));
+
+        app.diff_state.cursor_line = headers[0];
+        app.toggle_hunk_reviewed_at_cursor();
+        assert!(app.is_hunk_reviewed(0, 0));
+        assert!(!app.session.is_file_reviewed(&path));
+        assert_eq!(app.hunk_progress(), (1, 2));
+        // The first hunk's lines are folded into its header
+        assert_eq!(header_rows(&app)[1], headers[1] - 3);
+
+        let headers = header_rows(&app);
+        app.diff_state.cursor_line = 0;
+        app.next_hunk();
+        assert_eq!(app.diff_state.cursor_line, headers[0]);
+        app.skip_reviewed_in_navigation = true;
+        app.diff_state.cursor_line = 0;
+        app.next_hunk();
+        assert_eq!(app.diff_state.cursor_line, headers[1]);
+
+        app.toggle_hunk_reviewed_at_cursor();
+        assert!(app.session.is_file_reviewed(&path));
+        assert_eq!(app.hunk_progress(), (2, 2));
+        assert_eq!(
+            app.diff_state.cursor_line,
+            app.calculate_file_scroll_offset(0)
+        );
+
+        app.undo();
+        assert!(!app.session.is_file_reviewed(&path));
+        assert!(app.is_hunk_reviewed(0, 0));
+        assert!(!app.is_hunk_reviewed(0, 1));
+    }
+}
+
+#[cfg(test)]
+mod only_file_tests {
+    use super::test_support::{build_reloadable_app, make_file_with_hunks, make_hunk};
+    use super::*;
+
+    fn paths(app: &App) -> Vec<String> {
+        app.diff_files
+            .iter()
+            .map(|file| file.display_path().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn should_narrow_to_current_file_and_restore_with_all() {
+        let files = ["a.rs", "b.rs", "c.rs"]
+            .into_iter()
+            .map(|path| make_file_with_hunks(path, vec![make_hunk(1, 3)]))
+            .collect();
+        let mut app = build_reloadable_app(files, 3);
+        app.session
+            .files
+            .get_mut(Path::new("a.rs"))
+            .unwrap()
+            .add_file_comment(Comment::new("keep me".to_string(), CommentType::Note, None));
+        app.jump_to_file(1);
+
+        app.toggle_only_current_file();
+        assert_eq!(paths(&app), ["b.rs"]);
+        assert_eq!(app.path_filter, ["b.rs"]);
+        assert_eq!(app.session.files[Path::new("a.rs")].file_comments.len(), 1);
+
+        app.show_all_files();
+        assert_eq!(paths(&app), ["a.rs", "b.rs", "c.rs"]);
+        assert!(app.path_filter.is_empty());
+        assert_eq!(app.current_file_path(), Some(&PathBuf::from("b.rs")));
+
+        // `:only` twice toggles back as well
+        app.toggle_only_current_file();
+        app.toggle_only_current_file();
+        assert_eq!(paths(&app).len(), 3);
+        assert_eq!(app.only_saved_path_filter, None);
+    }
 }
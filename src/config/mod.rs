@@ -5,6 +5,11 @@ use std::path::{Path, PathBuf};
 use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
 use toml::Value;
+use unicode_width::UnicodeWidthStr;
+
+use crate::model::CommentType;
+use crate::theme::ColorMode;
+use crate::ui::status_bar;
 
 #[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, Eq)]
 #[serde(default)]
@@ -29,6 +34,36 @@ pub struct AppConfig {
     pub export_legend: Option<bool>,
     pub cursor_line: Option<bool>,
     pub mouse: Option<bool>,
+    pub autosave_interval_secs: Option<u64>,
+    pub watch: Option<bool>,
+    pub skip_reviewed_in_navigation: Option<bool>,
+    pub file_list_preview: Option<bool>,
+    pub github_request_changes: Option<bool>,
+    pub rename_threshold: Option<u16>,
+    pub detect_moves: Option<bool>,
+    pub max_file_lines: Option<usize>,
+    /// Files with more lines than this aren't syntax highlighted
+    pub max_highlight_lines: Option<usize>,
+    pub wrap_marker: Option<String>,
+    /// `[syntax]` overrides as (glob, syntax name or extension) pairs
+    pub syntax: Option<Vec<(String, String)>>,
+    pub color_mode: Option<String>,
+    pub inline_images: Option<bool>,
+    pub scrollbar: Option<bool>,
+    /// Rows of the `:comments` panel, including borders
+    pub comment_list_height: Option<u16>,
+    /// Name recorded on new comments instead of git's `user.name`
+    pub author: Option<String>,
+    /// Status bar template with `{placeholder}`s
+    pub status_line: Option<String>,
+    /// Type new comments start as, instead of the first of `comment_types`
+    pub default_comment_type: Option<CommentType>,
+    /// File list width in percent, saved when resized with `;<` / `;>`
+    pub file_list_width: Option<u16>,
+    /// Rows of the inline commit selector, saved when resized with `;+` / `;-`
+    pub commit_selector_height: Option<u16>,
+    /// Lines of hidden context loaded per press of a gap expander
+    pub context_expand_lines: Option<usize>,
 }
 
 /// Known top-level config keys. Used to warn about typos.
@@ -40,10 +75,32 @@ const KNOWN_KEYS: &[&str] = &[
     "comment_types",
     "show_file_list",
     "diff_view",
+    "diff_mode",
     "wrap",
     "export_legend",
     "cursor_line",
     "mouse",
+    "autosave_interval_secs",
+    "watch",
+    "skip_reviewed_in_navigation",
+    "file_list_preview",
+    "github_request_changes",
+    "rename_threshold",
+    "detect_moves",
+    "max_file_lines",
+    "max_highlight_lines",
+    "wrap_marker",
+    "syntax",
+    "color_mode",
+    "inline_images",
+    "scrollbar",
+    "comment_list_height",
+    "author",
+    "status_line",
+    "default_comment_type",
+    "file_list_width",
+    "commit_selector_height",
+    "context_expand_lines",
 ];
 
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
@@ -103,6 +160,54 @@ pub fn load_config() -> Result<ConfigLoadOutcome> {
     load_config_from_path(&path)
 }
 
+/// Set the top-level `key` to the TOML literal `value` in the config file at
+/// `path`, creating it if needed. The rest of the file, comments included,
+/// is kept as written.
+pub fn save_config_value(path: &Path, key: &str, value: &str) -> Result<()> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err.into()),
+    };
+    let updated = set_top_level_value(&contents, key, value);
+    // Refuse to write a file the next start would fail to load
+    toml::from_str::<Value>(&updated)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+/// Replace the unindented `key = …` line before the first table header with
+/// `key = value`, or add one just above that header when there is none.
+fn set_top_level_value(contents: &str, key: &str, value: &str) -> String {
+    let mut lines: Vec<&str> = contents.lines().collect();
+    let assignment = format!("{key} = {value}");
+    let top_level_end = lines
+        .iter()
+        .position(|line| line.starts_with('['))
+        .unwrap_or(lines.len());
+    let existing = lines[..top_level_end].iter().position(|line| {
+        line.strip_prefix(key)
+            .is_some_and(|rest| rest.trim_start().starts_with('='))
+    });
+    match existing {
+        Some(idx) => lines[idx] = &assignment,
+        None => {
+            // Keep the blank lines separating the top level from the first table
+            let mut idx = top_level_end;
+            while idx > 0 && lines[idx - 1].trim().is_empty() {
+                idx -= 1;
+            }
+            lines.insert(idx, &assignment);
+        }
+    }
+    let mut updated = lines.join("\n");
+    updated.push('\n');
+    updated
+}
+
 /// Read a string value from the table, pushing a warning if the type is wrong.
 fn read_string(table: &toml::Table, key: &str, warnings: &mut Vec<String>) -> Option<String> {
     let val = table.get(key)?;
@@ -129,6 +234,32 @@ fn read_bool(table: &toml::Table, key: &str, warnings: &mut Vec<String>) -> Opti
     }
 }
 
+/// Read a non-negative integer value from the table, pushing a warning if the type is wrong.
+fn read_u64(table: &toml::Table, key: &str, warnings: &mut Vec<String>) -> Option<u64> {
+    let val = table.get(key)?;
+    if let Some(n) = val.as_integer().and_then(|n| u64::try_from(n).ok()) {
+        Some(n)
+    } else {
+        warnings.push(format!(
+            "Warning: Config key '{key}' must be a non-negative integer; ignoring value"
+        ));
+        None
+    }
+}
+
+/// Read a percentage (0-100) from the table, pushing a warning if out of range.
+fn read_percentage(table: &toml::Table, key: &str, warnings: &mut Vec<String>) -> Option<u16> {
+    let n = read_u64(table, key, warnings)?;
+    if n <= 100 {
+        Some(n as u16)
+    } else {
+        warnings.push(format!(
+            "Warning: Config key '{key}' must be between 0 and 100; got {n}, ignoring"
+        ));
+        None
+    }
+}
+
 /// Read a string value constrained to a set of allowed values.
 fn read_enum(
     table: &toml::Table,
@@ -152,6 +283,83 @@ fn read_enum(
     }
 }
 
+/// The continuation marker for wrapped rows: one column wide, or empty to
+/// disable it.
+fn read_wrap_marker(table: &toml::Table, warnings: &mut Vec<String>) -> Option<String> {
+    let marker = read_string(table, "wrap_marker", warnings)?;
+    if marker.width() <= 1 {
+        Some(marker)
+    } else {
+        warnings.push(format!(
+            "Warning: Config key 'wrap_marker' must be a single character or empty; got \"{marker}\", ignoring"
+        ));
+        None
+    }
+}
+
+/// The `[syntax]` table mapping file globs to syntax names. Targets are
+/// checked against the syntax set when the highlighter is built.
+/// Read the `status_line` template, warning once about each placeholder the
+/// status bar doesn't know; those are shown as typed.
+fn read_status_line(table: &toml::Table, warnings: &mut Vec<String>) -> Option<String> {
+    let template = read_string(table, "status_line", warnings)?;
+    for name in status_bar::unknown_placeholders(&template) {
+        warnings.push(format!(
+            "Warning: Unknown placeholder '{{{name}}}' in config key 'status_line', shown as typed"
+        ));
+    }
+    Some(template)
+}
+
+/// Read `default_comment_type`, which must be the id of one of
+/// `comment_types`, or of a built-in type when those aren't configured.
+fn read_default_comment_type(
+    table: &toml::Table,
+    comment_types: Option<&[CommentTypeConfig]>,
+    warnings: &mut Vec<String>,
+) -> Option<CommentType> {
+    let raw = read_string(table, "default_comment_type", warnings)?;
+    let id = raw.trim().to_ascii_lowercase();
+    let ids: Vec<&str> = match comment_types {
+        Some(types) => types.iter().map(|t| t.id.as_str()).collect(),
+        None => vec!["note", "suggestion", "issue", "praise"],
+    };
+    if ids.contains(&id.as_str()) {
+        return Some(CommentType::from_id(&id));
+    }
+    warnings.push(format!(
+        "Warning: Config key 'default_comment_type' must be one of {}; got \"{raw}\", using {}",
+        ids.join(", "),
+        ids.first().copied().unwrap_or("note")
+    ));
+    None
+}
+
+fn read_syntax_overrides(
+    table: &toml::Table,
+    warnings: &mut Vec<String>,
+) -> Option<Vec<(String, String)>> {
+    let value = table.get("syntax")?;
+    let Some(entries) = value.as_table() else {
+        warnings.push(
+            "Warning: Config key 'syntax' must be a table of glob = \"syntax\"; ignoring value"
+                .to_string(),
+        );
+        return None;
+    };
+
+    let mut overrides = Vec::new();
+    for (pattern, target) in entries {
+        match target.as_str() {
+            Some(target) => overrides.push((pattern.clone(), target.to_string())),
+            None => warnings.push(format!(
+                "Warning: Config key 'syntax.\"{pattern}\"' must be a string; ignoring value"
+            )),
+        }
+    }
+    Some(overrides)
+}
+
 fn load_config_from_path(path: &Path) -> Result<ConfigLoadOutcome> {
     let contents = match fs::read_to_string(path) {
         Ok(contents) => contents,
@@ -165,26 +373,65 @@ fn load_config_from_path(path: &Path) -> Result<ConfigLoadOutcome> {
         .ok_or_else(|| anyhow!("Config root must be a TOML table"))?;
 
     let mut warnings = Vec::new();
+    let comment_types = table
+        .get("comment_types")
+        .and_then(|v| parse_comment_types(v, &mut warnings));
 
     let config = AppConfig {
         theme: read_string(table, "theme", &mut warnings),
         theme_dark: read_string(table, "theme_dark", &mut warnings),
         theme_light: read_string(table, "theme_light", &mut warnings),
         appearance: read_string(table, "appearance", &mut warnings),
-        comment_types: table
-            .get("comment_types")
-            .and_then(|v| parse_comment_types(v, &mut warnings)),
+        comment_types: comment_types.clone(),
+        default_comment_type: read_default_comment_type(
+            table,
+            comment_types.as_deref(),
+            &mut warnings,
+        ),
         show_file_list: read_bool(table, "show_file_list", &mut warnings),
+        // `diff_mode` matches the --diff-mode flag; `diff_view` is the older name
         diff_view: read_enum(
             table,
-            "diff_view",
+            "diff_mode",
             &["unified", "side-by-side"],
             &mut warnings,
-        ),
+        )
+        .or_else(|| {
+            read_enum(
+                table,
+                "diff_view",
+                &["unified", "side-by-side"],
+                &mut warnings,
+            )
+        }),
         wrap: read_bool(table, "wrap", &mut warnings),
         export_legend: read_bool(table, "export_legend", &mut warnings),
         cursor_line: read_bool(table, "cursor_line", &mut warnings),
         mouse: read_bool(table, "mouse", &mut warnings),
+        autosave_interval_secs: read_u64(table, "autosave_interval_secs", &mut warnings),
+        watch: read_bool(table, "watch", &mut warnings),
+        skip_reviewed_in_navigation: read_bool(table, "skip_reviewed_in_navigation", &mut warnings),
+        file_list_preview: read_bool(table, "file_list_preview", &mut warnings),
+        github_request_changes: read_bool(table, "github_request_changes", &mut warnings),
+        rename_threshold: read_percentage(table, "rename_threshold", &mut warnings),
+        detect_moves: read_bool(table, "detect_moves", &mut warnings),
+        max_file_lines: read_u64(table, "max_file_lines", &mut warnings).map(|n| n as usize),
+        max_highlight_lines: read_u64(table, "max_highlight_lines", &mut warnings)
+            .map(|n| n as usize),
+        wrap_marker: read_wrap_marker(table, &mut warnings),
+        syntax: read_syntax_overrides(table, &mut warnings),
+        color_mode: read_enum(table, "color_mode", &ColorMode::VALID_VALUES, &mut warnings),
+        inline_images: read_bool(table, "inline_images", &mut warnings),
+        scrollbar: read_bool(table, "scrollbar", &mut warnings),
+        comment_list_height: read_u64(table, "comment_list_height", &mut warnings)
+            .map(|n| n.clamp(3, u64::from(u16::MAX)) as u16),
+        author: read_string(table, "author", &mut warnings),
+        status_line: read_status_line(table, &mut warnings),
+        file_list_width: read_percentage(table, "file_list_width", &mut warnings),
+        commit_selector_height: read_u64(table, "commit_selector_height", &mut warnings)
+            .map(|n| n.min(u64::from(u16::MAX)) as u16),
+        context_expand_lines: read_u64(table, "context_expand_lines", &mut warnings)
+            .map(|n| n.clamp(1, u64::from(u32::MAX)) as usize),
     };
 
     for key in table.keys() {
@@ -530,6 +777,33 @@ mod tests {
         assert!(outcome.warnings.is_empty());
     }
 
+    #[test]
+    fn should_prefer_diff_mode_over_diff_view() {
+        let outcome = parse_config("diff_mode = \"side-by-side\"\ndiff_view = \"unified\"\n");
+        assert_eq!(
+            outcome
+                .config
+                .as_ref()
+                .and_then(|cfg| cfg.diff_view.as_deref()),
+            Some("side-by-side")
+        );
+        assert!(outcome.warnings.is_empty());
+    }
+
+    #[test]
+    fn should_warn_and_fall_back_to_unified_for_invalid_diff_mode() {
+        let outcome = parse_config("diff_mode = \"split\"\n");
+        assert_eq!(
+            outcome
+                .config
+                .as_ref()
+                .and_then(|cfg| cfg.diff_view.as_deref()),
+            None
+        );
+        assert_eq!(outcome.warnings.len(), 1);
+        assert!(outcome.warnings[0].contains("'diff_mode'"));
+    }
+
     #[test]
     fn should_warn_and_ignore_diff_view_with_invalid_value() {
         let outcome = parse_config("diff_view = \"split\"\n");
@@ -620,6 +894,325 @@ mod tests {
         );
     }
 
+    // autosave_interval_secs
+
+    #[test]
+    fn should_parse_autosave_interval() {
+        let outcome = parse_config("autosave_interval_secs = 10\n");
+        assert_eq!(
+            outcome
+                .config
+                .as_ref()
+                .and_then(|cfg| cfg.autosave_interval_secs),
+            Some(10)
+        );
+        assert!(outcome.warnings.is_empty());
+    }
+
+    #[test]
+    fn should_warn_and_ignore_negative_autosave_interval() {
+        let outcome = parse_config("autosave_interval_secs = -5\n");
+        assert_eq!(
+            outcome
+                .config
+                .as_ref()
+                .and_then(|cfg| cfg.autosave_interval_secs),
+            None
+        );
+        assert_eq!(
+            outcome.warnings,
+            vec![
+                "Warning: Config key 'autosave_interval_secs' must be a non-negative integer; ignoring value"
+                    .to_string()
+            ]
+        );
+    }
+
+    // watch
+
+    #[test]
+    fn should_parse_skip_reviewed_in_navigation() {
+        let outcome = parse_config("skip_reviewed_in_navigation = true\n");
+        assert_eq!(
+            outcome
+                .config
+                .as_ref()
+                .and_then(|cfg| cfg.skip_reviewed_in_navigation),
+            Some(true)
+        );
+        assert!(outcome.warnings.is_empty());
+    }
+
+    #[test]
+    fn should_parse_github_request_changes() {
+        let outcome = parse_config("github_request_changes = false\n");
+        assert_eq!(
+            outcome
+                .config
+                .as_ref()
+                .and_then(|cfg| cfg.github_request_changes),
+            Some(false)
+        );
+        assert!(outcome.warnings.is_empty());
+    }
+
+    #[test]
+    fn should_parse_rename_threshold() {
+        let outcome = parse_config("rename_threshold = 30\n");
+        assert_eq!(
+            outcome.config.as_ref().and_then(|cfg| cfg.rename_threshold),
+            Some(30)
+        );
+        assert!(outcome.warnings.is_empty());
+    }
+
+    #[test]
+    fn should_warn_and_ignore_out_of_range_rename_threshold() {
+        let outcome = parse_config("rename_threshold = 150\n");
+        assert_eq!(
+            outcome.config.as_ref().and_then(|cfg| cfg.rename_threshold),
+            None
+        );
+        assert_eq!(
+            outcome.warnings,
+            vec![
+                "Warning: Config key 'rename_threshold' must be between 0 and 100; got 150, ignoring"
+                    .to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn should_parse_max_file_lines() {
+        let outcome = parse_config("max_file_lines = 5000\n");
+        assert_eq!(
+            outcome.config.as_ref().and_then(|cfg| cfg.max_file_lines),
+            Some(5000)
+        );
+        assert!(outcome.warnings.is_empty());
+    }
+
+    #[test]
+    fn should_parse_max_highlight_lines() {
+        let outcome = parse_config("max_highlight_lines = 2000\n");
+        assert_eq!(
+            outcome
+                .config
+                .as_ref()
+                .and_then(|cfg| cfg.max_highlight_lines),
+            Some(2000)
+        );
+        assert!(outcome.warnings.is_empty());
+    }
+
+    #[test]
+    fn should_parse_context_expand_lines_of_at_least_one() {
+        let outcome = parse_config("context_expand_lines = 50\n");
+        assert_eq!(
+            outcome
+                .config
+                .as_ref()
+                .and_then(|cfg| cfg.context_expand_lines),
+            Some(50)
+        );
+
+        let outcome = parse_config("context_expand_lines = 0\n");
+        assert_eq!(
+            outcome
+                .config
+                .as_ref()
+                .and_then(|cfg| cfg.context_expand_lines),
+            Some(1)
+        );
+        assert!(outcome.warnings.is_empty());
+    }
+
+    #[test]
+    fn should_parse_wrap_marker() {
+        let outcome = parse_config("wrap_marker = \"\"\n");
+        assert_eq!(
+            outcome
+                .config
+                .as_ref()
+                .and_then(|cfg| cfg.wrap_marker.as_deref()),
+            Some("")
+        );
+        assert!(outcome.warnings.is_empty());
+
+        let outcome = parse_config("wrap_marker = \"->\"\n");
+        assert_eq!(
+            outcome
+                .config
+                .as_ref()
+                .and_then(|cfg| cfg.wrap_marker.as_deref()),
+            None
+        );
+        assert_eq!(outcome.warnings.len(), 1);
+    }
+
+    #[test]
+    fn should_parse_syntax_overrides() {
+        let outcome = parse_config(
+            "[syntax]\n\"*.gohtml\" = \"html\"\nTiltfile = \"python\"\n\"*.svc\" = 3\n",
+        );
+        assert_eq!(
+            outcome.config.and_then(|cfg| cfg.syntax),
+            Some(vec![
+                ("*.gohtml".to_string(), "html".to_string()),
+                ("Tiltfile".to_string(), "python".to_string()),
+            ])
+        );
+        assert_eq!(
+            outcome.warnings,
+            vec!["Warning: Config key 'syntax.\"*.svc\"' must be a string; ignoring value"]
+        );
+
+        let outcome = parse_config("syntax = \"html\"\n");
+        assert_eq!(outcome.config.and_then(|cfg| cfg.syntax), None);
+        assert_eq!(outcome.warnings.len(), 1);
+    }
+
+    #[test]
+    fn should_parse_color_mode() {
+        let outcome = parse_config("color_mode = \"256\"\n");
+        assert_eq!(
+            outcome.config.and_then(|cfg| cfg.color_mode).as_deref(),
+            Some("256")
+        );
+        assert!(outcome.warnings.is_empty());
+
+        let outcome = parse_config("color_mode = \"88\"\n");
+        assert_eq!(outcome.config.and_then(|cfg| cfg.color_mode), None);
+        assert_eq!(
+            outcome.warnings,
+            vec![
+                "Warning: Config key 'color_mode' must be \"truecolor\" or \"256\" or \"16\"; got \"88\", ignoring"
+            ]
+        );
+    }
+
+    #[test]
+    fn should_parse_inline_images() {
+        let outcome = parse_config("inline_images = true\n");
+        assert_eq!(outcome.config.and_then(|cfg| cfg.inline_images), Some(true));
+        assert!(outcome.warnings.is_empty());
+    }
+
+    #[test]
+    fn should_parse_scrollbar_false() {
+        let outcome = parse_config("scrollbar = false\n");
+        assert_eq!(outcome.config.and_then(|cfg| cfg.scrollbar), Some(false));
+        assert!(outcome.warnings.is_empty());
+    }
+
+    #[test]
+    fn should_warn_about_unknown_status_line_placeholders() {
+        let outcome = parse_config("status_line = \"{mode} {branch} {nope} {file}\"\n");
+        assert_eq!(
+            outcome
+                .config
+                .as_ref()
+                .and_then(|cfg| cfg.status_line.as_deref()),
+            Some("{mode} {branch} {nope} {file}")
+        );
+        assert_eq!(
+            outcome.warnings,
+            ["Warning: Unknown placeholder '{nope}' in config key 'status_line', shown as typed"]
+        );
+    }
+
+    #[test]
+    fn should_parse_default_comment_type_from_the_comment_types() {
+        let outcome = parse_config("default_comment_type = \"Issue\"\n");
+        assert_eq!(
+            outcome.config.and_then(|cfg| cfg.default_comment_type),
+            Some(CommentType::Issue)
+        );
+        assert!(outcome.warnings.is_empty());
+
+        let outcome = parse_config(
+            "default_comment_type = \"nit\"\ncomment_types = [{ id = \"question\" }, { id = \"nit\" }]\n",
+        );
+        assert_eq!(
+            outcome.config.and_then(|cfg| cfg.default_comment_type),
+            Some(CommentType::Custom("nit".to_string()))
+        );
+
+        let outcome = parse_config("default_comment_type = \"nit\"\n");
+        assert_eq!(
+            outcome.config.and_then(|cfg| cfg.default_comment_type),
+            None
+        );
+        assert_eq!(
+            outcome.warnings,
+            [
+                "Warning: Config key 'default_comment_type' must be one of note, suggestion, issue, praise; got \"nit\", using note"
+            ]
+        );
+    }
+
+    #[test]
+    fn should_set_top_level_values_keeping_the_rest_of_the_file() {
+        let contents = "# my config\ntheme = \"light\"\nfile_list_width = 20 # old\n\n[syntax]\n\"*.h\" = \"cpp\"\n";
+        assert_eq!(
+            set_top_level_value(contents, "file_list_width", "30"),
+            "# my config\ntheme = \"light\"\nfile_list_width = 30\n\n[syntax]\n\"*.h\" = \"cpp\"\n"
+        );
+        assert_eq!(
+            set_top_level_value(contents, "commit_selector_height", "5"),
+            "# my config\ntheme = \"light\"\nfile_list_width = 20 # old\ncommit_selector_height = 5\n\n[syntax]\n\"*.h\" = \"cpp\"\n"
+        );
+        assert_eq!(set_top_level_value("", "wrap", "true"), "wrap = true\n");
+    }
+
+    #[test]
+    fn should_not_save_a_value_that_breaks_the_config() {
+        let dir = tempdir().expect("failed to create temp dir");
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "file_list_width = [\n  1,\n]\n").unwrap();
+
+        assert!(save_config_value(&path, "file_list_width", "30").is_err());
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "file_list_width = [\n  1,\n]\n"
+        );
+    }
+
+    #[test]
+    fn should_parse_comment_list_height_with_a_minimum() {
+        let outcome = parse_config("comment_list_height = 15\n");
+        assert_eq!(
+            outcome.config.and_then(|cfg| cfg.comment_list_height),
+            Some(15)
+        );
+        let outcome = parse_config("comment_list_height = 1\n");
+        assert_eq!(
+            outcome.config.and_then(|cfg| cfg.comment_list_height),
+            Some(3)
+        );
+        assert!(outcome.warnings.is_empty());
+    }
+
+    #[test]
+    fn should_parse_detect_moves_false() {
+        let outcome = parse_config("detect_moves = false\n");
+        assert_eq!(
+            outcome.config.as_ref().and_then(|cfg| cfg.detect_moves),
+            Some(false)
+        );
+        assert!(outcome.warnings.is_empty());
+    }
+
+    #[test]
+    fn should_parse_watch_true() {
+        let outcome = parse_config("watch = true\n");
+        assert_eq!(
+            outcome.config.as_ref().and_then(|cfg| cfg.watch),
+            Some(true)
+        );
+        assert!(outcome.warnings.is_empty());
+    }
+
     // export_legend
 
     #[test]
@@ -0,0 +1,83 @@
+//! Launching the user's `$VISUAL` / `$EDITOR`.
+
+use std::path::Path;
+
+/// Editor used when neither `$VISUAL` nor `$EDITOR` is set.
+const FALLBACK_EDITOR: &str = "vi";
+
+/// The configured editor command, preferring `$VISUAL` over `$EDITOR`.
+pub fn resolve_editor() -> String {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| FALLBACK_EDITOR.to_string())
+}
+
+/// Program and arguments that open `path` in `editor`, at `line` if given.
+///
+/// `editor` may carry its own arguments (e.g. `code --wait`). VS Code-like
+/// editors take `--goto path:line`, a few others accept `path:line`, and
+/// everything else gets the conventional `+line path`.
+pub fn editor_command(editor: &str, path: &Path, line: Option<u32>) -> (String, Vec<String>) {
+    let mut parts = editor.split_whitespace().map(str::to_string);
+    let program = parts.next().unwrap_or_else(|| FALLBACK_EDITOR.to_string());
+    let mut args: Vec<String> = parts.collect();
+
+    let name = Path::new(&program)
+        .file_stem()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default()
+        .to_string();
+    let path = path.to_string_lossy().to_string();
+
+    match line {
+        Some(line) => match name.as_str() {
+            "code" | "code-insiders" | "codium" | "cursor" | "windsurf" => {
+                args.push("--goto".to_string());
+                args.push(format!("{path}:{line}"));
+            }
+            "subl" | "zed" | "hx" | "helix" => args.push(format!("{path}:{line}")),
+            _ => {
+                args.push(format!("+{line}"));
+                args.push(path);
+            }
+        },
+        None => args.push(path),
+    }
+    (program, args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(editor: &str, line: Option<u32>) -> Vec<String> {
+        let (program, mut args) = editor_command(editor, Path::new("src/main.rs"), line);
+        args.insert(0, program);
+        args
+    }
+
+    #[test]
+    fn should_use_plus_line_for_terminal_editors() {
+        assert_eq!(command("nvim", Some(12)), ["nvim", "+12", "src/main.rs"]);
+        assert_eq!(
+            command("/usr/bin/emacs -nw", Some(3)),
+            ["/usr/bin/emacs", "-nw", "+3", "src/main.rs"]
+        );
+    }
+
+    #[test]
+    fn should_use_goto_syntax_for_gui_editors() {
+        assert_eq!(
+            command("code --wait", Some(12)),
+            ["code", "--wait", "--goto", "src/main.rs:12"]
+        );
+        assert_eq!(command("hx", Some(7)), ["hx", "src/main.rs:7"]);
+    }
+
+    #[test]
+    fn should_open_file_without_line() {
+        assert_eq!(command("vim", None), ["vim", "src/main.rs"]);
+    }
+}
@@ -14,9 +14,15 @@ pub enum TuicrError {
     #[error("Not a repository")]
     NotARepository,
 
+    #[error("{0} is a bare repository with no working tree")]
+    BareRepository(String),
+
     #[error("No changes to review")]
     NoChanges,
 
+    #[error("No changes to review match --files {0}")]
+    NoMatchingFiles(String),
+
     #[error("No comments to export - skipping copy")]
     NoComments,
 
@@ -29,8 +35,20 @@ pub enum TuicrError {
     #[error("VCS command failed: {0}")]
     VcsCommand(String),
 
+    #[error("{0} changed since the diff was loaded")]
+    FileChanged(String),
+
+    #[error("Suggestion conflicts with changed lines: {0}")]
+    SuggestionConflict(String),
+
+    #[error("GitHub error: {0}")]
+    GitHub(String),
+
     #[error("Unsupported operation: {0}")]
     UnsupportedOperation(String),
+
+    #[error("{0} is outside the repository")]
+    PathOutsideRepository(String),
 }
 
 pub type Result<T> = std::result::Result<T, TuicrError>;
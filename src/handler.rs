@@ -1,3 +1,5 @@
+use std::time::Instant;
+
 use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
 use ratatui::layout::Position;
 
@@ -6,7 +8,12 @@ use crate::app::{
 };
 use crate::input::Action;
 use crate::model::ClearScope;
-use crate::output::{export_to_clipboard, generate_export_content};
+use crate::output::github::{build_github_review, post_github_review};
+use crate::output::rdjson::build_rdjson;
+use crate::output::{
+    copy_to_clipboard, export_to_clipboard, generate_checklist, generate_export_content,
+    generate_summary,
+};
 use crate::persistence::save_session;
 use crate::text_edit::{
     delete_char_before, delete_word_before, next_char_boundary, prev_char_boundary,
@@ -14,9 +21,9 @@ use crate::text_edit::{
 
 const WHEEL_LINES: usize = 3;
 
-/// Routes a crossterm mouse event. Drags are intentionally unhandled so users
-/// can hold the terminal's bypass modifier (commonly Shift or Option/Alt) to
-/// fall back to native text selection for copy.
+/// Routes a crossterm mouse event. Dragging over diff lines makes a visual
+/// selection; users can still hold the terminal's bypass modifier (commonly
+/// Shift or Option/Alt) to fall back to native text selection for copy.
 pub fn handle_mouse_event(app: &mut App, event: MouseEvent) {
     let pos = Position::new(event.column, event.row);
     match event.kind {
@@ -35,14 +42,43 @@ pub fn handle_mouse_event(app: &mut App, event: MouseEvent) {
                 _ => {}
             }
         }
-        MouseEventKind::Down(MouseButton::Left) if app.input_mode == InputMode::Normal => {
-            handle_left_click(app, pos);
+        MouseEventKind::Down(MouseButton::Left)
+            if matches!(app.input_mode, InputMode::Normal | InputMode::VisualSelect) =>
+        {
+            if app.input_mode == InputMode::VisualSelect {
+                app.exit_visual_mode();
+            }
+            let double_click = app.register_left_click(Instant::now(), pos.x, pos.y);
+            handle_left_click(app, pos, double_click);
+        }
+        MouseEventKind::Drag(MouseButton::Left) if app.mouse_drag_in_diff => {
+            handle_left_drag(app, pos);
+        }
+        MouseEventKind::Up(MouseButton::Left) => {
+            let release_idx = app
+                .diff_inner_area
+                .filter(|r| r.contains(pos))
+                .and_then(|_| app.diff_annotation_at_screen_row(pos.y));
+            app.end_drag_selection(release_idx);
         }
         _ => {}
     }
 }
 
-fn handle_left_click(app: &mut App, pos: Position) {
+fn handle_left_drag(app: &mut App, pos: Position) {
+    let Some(inner) = app.diff_inner_area else {
+        return;
+    };
+    if pos.y <= inner.y {
+        app.drag_selection_past_edge(false);
+    } else if pos.y + 1 >= inner.bottom() {
+        app.drag_selection_past_edge(true);
+    } else if let Some(idx) = app.diff_annotation_at_screen_row(pos.y) {
+        app.drag_selection_to(idx);
+    }
+}
+
+fn handle_left_click(app: &mut App, pos: Position, double_click: bool) {
     if app.file_list_inner_area.is_some_and(|r| r.contains(pos))
         && let Some(idx) = app.file_list_idx_at_screen_row(pos.y)
     {
@@ -64,7 +100,8 @@ fn handle_left_click(app: &mut App, pos: Position) {
         && let Some(idx) = app.diff_annotation_at_screen_row(pos.y)
     {
         app.focused_panel = FocusedPanel::Diff;
-        app.move_cursor_to_annotation(idx);
+        app.click_diff_annotation(idx, double_click);
+        app.mouse_drag_in_diff = app.get_line_at_cursor().is_some();
     }
 }
 
@@ -74,6 +111,7 @@ fn handle_export(app: &mut App) {
     if app.output_to_stdout {
         match generate_export_content(
             &app.session,
+            &app.diff_files,
             &app.diff_source,
             &app.comment_types,
             app.export_legend,
@@ -87,6 +125,7 @@ fn handle_export(app: &mut App) {
     } else {
         match export_to_clipboard(
             &app.session,
+            &app.diff_files,
             &app.diff_source,
             &app.comment_types,
             app.export_legend,
@@ -97,6 +136,113 @@ fn handle_export(app: &mut App) {
     }
 }
 
+/// Post the review to the current branch's GitHub PR (`:export github`).
+/// With `dry_run` or --stdout, prints the JSON payload and quits instead.
+fn handle_github_export(app: &mut App, dry_run: bool) {
+    let review =
+        match build_github_review(&app.session, &app.comment_types, app.github_request_changes) {
+            Ok(review) => review,
+            Err(e) => {
+                app.set_warning(format!("{e}"));
+                return;
+            }
+        };
+
+    if dry_run || app.output_to_stdout {
+        match serde_json::to_string_pretty(&review) {
+            Ok(json) => {
+                app.pending_stdout_output = Some(json);
+                app.should_quit = true;
+            }
+            Err(e) => app.set_error(format!("{e}")),
+        }
+        return;
+    }
+
+    match post_github_review(&app.vcs_info.root_path, &review) {
+        Ok(msg) => app.set_message(msg),
+        Err(e) => app.set_error(format!("{e}")),
+    }
+}
+
+/// Export reviewdog diagnostics (`:export rdjson`) to `target`, a file path
+/// or `--stdout`. Printing to stdout quits like the other stdout exports.
+fn handle_rdjson_export(app: &mut App, target: &str) {
+    let to_stdout = target == "--stdout" || (target.is_empty() && app.output_to_stdout);
+    if target.is_empty() && !to_stdout {
+        app.set_warning("Usage: :export rdjson --stdout | <path>");
+        return;
+    }
+
+    let result = match build_rdjson(&app.session) {
+        Ok(result) => result,
+        Err(e) => {
+            app.set_warning(format!("{e}"));
+            return;
+        }
+    };
+    let count = result.diagnostics.len();
+    let json = match serde_json::to_string_pretty(&result) {
+        Ok(json) => json,
+        Err(e) => {
+            app.set_error(format!("{e}"));
+            return;
+        }
+    };
+
+    if to_stdout {
+        app.pending_stdout_output = Some(json);
+        app.should_quit = true;
+        return;
+    }
+    match std::fs::write(target, json + "\n") {
+        Ok(()) => app.set_message(format!("Wrote {count} diagnostics to {target}")),
+        Err(e) => app.set_error(format!("Failed to write {target}: {e}")),
+    }
+}
+
+/// Export the reviewed/pending file checklist (`:checklist`) to `target`, a
+/// file path, or to the clipboard or stdout like `:clip` when empty.
+fn handle_checklist_export(app: &mut App, target: &str) {
+    let checklist = generate_checklist(&app.diff_files, &app.session);
+    if !target.is_empty() {
+        match std::fs::write(target, checklist + "\n") {
+            Ok(()) => app.set_message(format!("Wrote checklist to {target}")),
+            Err(e) => app.set_error(format!("Failed to write {target}: {e}")),
+        }
+    } else {
+        copy_or_print(app, "Checklist", checklist);
+    }
+}
+
+/// Copy the review summary (`:summary [markdown]`) to the clipboard, or
+/// print it on exit with `--stdout`.
+fn handle_summary_export(app: &mut App, markdown: bool) {
+    let summary = generate_summary(
+        &app.session,
+        &app.diff_files,
+        &app.diff_source,
+        &app.comment_types,
+        markdown,
+    );
+    copy_or_print(app, "Review summary", summary);
+}
+
+/// Hand `content` to stdout and quit with `--stdout`, else copy it to the
+/// clipboard, reporting it as `what`.
+fn copy_or_print(app: &mut App, what: &str, content: String) {
+    if app.output_to_stdout {
+        app.pending_stdout_output = Some(content);
+        app.should_quit = true;
+        return;
+    }
+    match copy_to_clipboard(&content) {
+        Ok(true) => app.set_message(format!("{what} copied to clipboard (via terminal)")),
+        Ok(false) => app.set_message(format!("{what} copied to clipboard")),
+        Err(e) => app.set_warning(format!("{e}")),
+    }
+}
+
 /// Export and quit (used by ZZ keybinding).
 /// When --stdout is set, stores export content and quits.
 /// Otherwise, exports to clipboard and quits.
@@ -105,6 +251,95 @@ pub fn handle_export_and_quit(app: &mut App) {
     app.should_quit = true;
 }
 
+/// Copy the diff line under the cursor (used by the yy keybinding).
+pub fn handle_yank_line(app: &mut App) {
+    match app.line_content_at_cursor() {
+        Some(line) => yank_lines(app, vec![line]),
+        None => app.set_message("Move cursor to a diff line to copy it"),
+    }
+}
+
+/// Copy the hunk under the cursor (used by the yh keybinding).
+pub fn handle_yank_hunk(app: &mut App) {
+    match app.hunk_lines_at_cursor() {
+        Some(lines) => yank_lines(app, lines),
+        None => app.set_message("Move cursor to a hunk to copy it"),
+    }
+}
+
+/// Copy the comment under the cursor (used by the yc keybinding).
+pub fn handle_yank_comment(app: &mut App) {
+    let Some(content) = app.comment_content_at_cursor() else {
+        app.set_message("No comment at cursor");
+        return;
+    };
+    if app.output_to_stdout {
+        println!("{content}");
+        app.set_message("Comment printed to stdout");
+        return;
+    }
+    match copy_to_clipboard(&content) {
+        Ok(_) => app.set_message("Comment copied"),
+        Err(e) => app.set_warning(format!("{e}")),
+    }
+}
+
+/// Copy the visually selected lines (visual y, or gy to keep diff markers)
+/// and leave visual mode.
+pub fn handle_yank_selection(app: &mut App, with_markers: bool) {
+    match app.selected_lines(with_markers) {
+        Some(lines) if !lines.is_empty() => yank_lines(app, lines),
+        _ => app.set_warning("Invalid selection - cannot span old and new lines"),
+    }
+    app.exit_visual_mode();
+}
+
+/// Copy a `path:line` reference for the cursor line or visual selection
+/// (yr / visual r). With `permalink`, copy a web link to the lines instead
+/// when the backend can build one (yp / visual Y).
+pub fn handle_yank_line_ref(app: &mut App, permalink: bool) {
+    let Some((path, range)) = app.line_ref_at_cursor() else {
+        app.set_message("Move cursor to a diff line to copy a reference");
+        return;
+    };
+    let link = permalink.then(|| app.vcs.permalink(&path, range)).flatten();
+    let text = link.unwrap_or_else(|| {
+        let path = path.display();
+        if range.is_single() {
+            format!("{path}:{}", range.start)
+        } else {
+            format!("{path}:{}-{}", range.start, range.end)
+        }
+    });
+
+    if app.output_to_stdout {
+        println!("{text}");
+        app.set_message(format!("Printed {text}"));
+        return;
+    }
+    match copy_to_clipboard(&text) {
+        Ok(_) => app.set_message(format!("Copied {text}")),
+        Err(e) => app.set_warning(format!("{e}")),
+    }
+}
+
+/// Copy lines to the clipboard, or print them when --stdout is set.
+fn yank_lines(app: &mut App, lines: Vec<String>) {
+    let count = lines.len();
+    let noun = if count == 1 { "line" } else { "lines" };
+    let text = lines.join("\n");
+    if app.output_to_stdout {
+        println!("{text}");
+        app.set_message(format!("Printed {count} {noun} to stdout"));
+        return;
+    }
+    match copy_to_clipboard(&text) {
+        Ok(true) => app.set_message(format!("Copied {count} {noun} (via terminal)")),
+        Ok(false) => app.set_message(format!("Copied {count} {noun}")),
+        Err(e) => app.set_warning(format!("{e}")),
+    }
+}
+
 fn comment_line_start(buffer: &str, cursor: usize) -> usize {
     let cursor = cursor.min(buffer.len());
     match buffer[..cursor].rfind('\n') {
@@ -209,6 +444,26 @@ pub fn handle_help_action(app: &mut App, action: Action) {
     }
 }
 
+/// Handle actions while the expanded `gb` blame popup is open; keys are
+/// mapped as in Help mode
+pub fn handle_blame_action(app: &mut App, action: Action) {
+    let Some(popup) = &mut app.blame_popup else {
+        return;
+    };
+    match action {
+        Action::CursorDown(n) | Action::MouseScrollDown(n) => popup.scroll_down(n),
+        Action::CursorUp(n) | Action::MouseScrollUp(n) => popup.scroll_up(n),
+        Action::HalfPageDown => popup.scroll_down(popup.viewport_height / 2),
+        Action::HalfPageUp => popup.scroll_up(popup.viewport_height / 2),
+        Action::PageDown => popup.scroll_down(popup.viewport_height),
+        Action::PageUp => popup.scroll_up(popup.viewport_height),
+        Action::GoToTop => popup.scroll_offset = 0,
+        Action::GoToBottom => popup.scroll_down(popup.total_lines),
+        Action::ToggleHelp => app.blame_popup = None,
+        _ => {}
+    }
+}
+
 /// Handle actions in Command mode (text input for :commands)
 pub fn handle_command_action(app: &mut App, action: Action) {
     match action {
@@ -249,15 +504,51 @@ pub fn handle_command_action(app: &mut App, action: Action) {
                 return;
             }
 
+            // `:diff` alone toggles the view mode, with an argument it picks what to diff
+            if let Some(target) = cmd
+                .strip_prefix("diff ")
+                .map(str::trim)
+                .filter(|target| !target.is_empty())
+            {
+                let result = if target == "worktree" {
+                    app.diff_against_worktree()
+                } else {
+                    app.diff_against_revisions(target)
+                };
+                if let Err(e) = result {
+                    app.set_error(format!("Failed to diff against {target}: {e}"));
+                }
+                app.exit_command_mode();
+                return;
+            }
+            if let Some(rev) = cmd
+                .strip_prefix("show ")
+                .map(str::trim)
+                .filter(|rev| !rev.is_empty())
+            {
+                if let Err(e) = app.show_revision(rev) {
+                    app.set_error(format!("Failed to show {rev}: {e}"));
+                }
+                app.exit_command_mode();
+                return;
+            }
+
             match cmd.as_str() {
                 "q" | "quit" => {
-                    if app.dirty {
+                    // With autosave on, unsaved changes are written on exit
+                    if app.dirty && app.autosave_interval.is_none() {
                         app.set_error("No write since last change (add ! to override)");
                     } else {
                         app.should_quit = true;
                     }
                 }
-                "q!" | "quit!" => app.should_quit = true,
+                "q!" | "quit!" => {
+                    app.discard_on_quit = true;
+                    app.should_quit = true;
+                }
+                "w" | "write" if !app.dirty => {
+                    app.set_message("No unsaved changes");
+                }
                 "w" | "write" => match save_session(&app.session) {
                     Ok(path) => {
                         app.dirty = false;
@@ -296,10 +587,16 @@ pub fn handle_command_action(app: &mut App, action: Action) {
                     Err(e) => app.set_error(format!("Reload failed: {e}")),
                 },
                 "clip" | "export" => handle_export(app),
+                "export github" => handle_github_export(app, false),
+                "export github --stdout" => handle_github_export(app, true),
+                "export rdjson" => handle_rdjson_export(app, ""),
+                "checklist" => handle_checklist_export(app, ""),
+                "summary" => handle_summary_export(app, false),
+                "summary markdown" | "summary md" => handle_summary_export(app, true),
                 "clear" => app.clear_comments(ClearScope::CommentsAndReviewed),
                 "clearc" => app.clear_comments(ClearScope::CommentsOnly),
-                "version" => {
-                    app.set_message(format!("tuicr v{}", env!("CARGO_PKG_VERSION")));
+                "version" | "about" => {
+                    app.set_message(format!("tuicr v{}", crate::update::version_string()));
                 }
                 "update" => match crate::update::check_for_updates() {
                     crate::update::UpdateCheckResult::UpdateAvailable(info) => {
@@ -323,6 +620,24 @@ pub fn handle_command_action(app: &mut App, action: Action) {
                 },
                 "set wrap" => app.set_diff_wrap(true),
                 "set wrap!" => app.toggle_diff_wrap(),
+                "set iw" => app.set_ignore_whitespace(true),
+                "set noiw" => app.set_ignore_whitespace(false),
+                "set iw!" => app.toggle_ignore_whitespace(),
+                "set skipreviewed" => app.set_skip_reviewed_in_navigation(true),
+                "set noskipreviewed" => app.set_skip_reviewed_in_navigation(false),
+                "set preview" => app.set_file_list_preview(true),
+                "set nopreview" => app.set_file_list_preview(false),
+                "set preview!" => app.set_file_list_preview(!app.file_list_preview),
+                "set changesonly" => app.set_changes_only(true),
+                "set nochangesonly" => app.set_changes_only(false),
+                "set changesonly!" => app.set_changes_only(!app.changes_only),
+                "set scrollbar" => app.show_scrollbar = true,
+                "set noscrollbar" => app.show_scrollbar = false,
+                "set scrollbar!" => app.show_scrollbar = !app.show_scrollbar,
+                "show-ignored" => app.toggle_show_ignored(),
+                "set skipreviewed!" => {
+                    app.set_skip_reviewed_in_navigation(!app.skip_reviewed_in_navigation)
+                }
                 "set commits" => {
                     app.show_commit_selector = true;
                     app.set_message("Commit selector: visible");
@@ -348,8 +663,35 @@ pub fn handle_command_action(app: &mut App, action: Action) {
                     };
                     app.set_message(format!("Commit selector: {status}"));
                 }
+                "filter" => app.set_file_filter(None),
+                "only" => app.toggle_only_current_file(),
+                "all" => app.show_all_files(),
+                "set-author" => app.set_comment_author(None),
                 "diff" => app.toggle_diff_view_mode(),
+                "view" => app.toggle_file_view_mode(),
+                "stats" => app.show_stats(),
+                "blame" => app.toggle_blame_column(),
+                "comments" => app.open_comment_list(),
+                "expand" => app.expand_all_gaps(),
+                "collapse" => app.collapse_all_gaps(),
                 "stage" => app.stage_reviewed_files(),
+                "describe" => handle_shared_normal_action(app, Action::EditCommitMessage),
+                "branches" => match app.open_branch_picker() {
+                    Ok(()) if app.input_mode == InputMode::BranchPicker => {
+                        app.command_buffer.clear();
+                        return;
+                    }
+                    Ok(()) => {}
+                    Err(e) => app.set_error(format!("Failed to list branches: {e}")),
+                },
+                "sessions" => {
+                    if let Err(e) = app.open_session_browser() {
+                        app.set_error(format!("Failed to list sessions: {e}"));
+                    } else {
+                        app.command_buffer.clear();
+                        return;
+                    }
+                }
                 "commits" => {
                     if let Err(e) = app.enter_commit_select_mode() {
                         app.set_error(format!("Failed to load commits: {e}"));
@@ -357,7 +699,24 @@ pub fn handle_command_action(app: &mut App, action: Action) {
                         return;
                     }
                 }
-                _ => app.set_message(format!("Unknown command: {cmd}")),
+                _ => {
+                    if let Some(target) = cmd.strip_prefix("export rdjson ") {
+                        handle_rdjson_export(app, target.trim());
+                    } else if let Some(target) = cmd.strip_prefix("checklist ") {
+                        handle_checklist_export(app, target.trim());
+                    } else if let Some(name) = cmd.strip_prefix("set-author ") {
+                        app.set_comment_author(Some(name));
+                    } else if let Some(pattern) = cmd.strip_prefix("filter ") {
+                        app.set_file_filter(Some(pattern.trim()));
+                    } else if let Some(name) = cmd.strip_prefix("theme ") {
+                        app.set_theme(name);
+                    } else if let Ok(line) = cmd.parse::<u32>() {
+                        // `:NN` jumps to source line NN of the current file
+                        app.go_to_source_line(line);
+                    } else {
+                        app.set_message(format!("Unknown command: {cmd}"));
+                    }
+                }
             }
             app.exit_command_mode();
         }
@@ -445,6 +804,7 @@ pub fn handle_comment_action(app: &mut App, action: Action) {
             app.comment_buffer.clear();
             app.comment_cursor = 0;
         }
+        Action::EditCommentInEditor => app.request_edit_comment_in_editor(),
         Action::Quit => app.should_quit = true,
         _ => {}
     }
@@ -453,11 +813,43 @@ pub fn handle_comment_action(app: &mut App, action: Action) {
 /// Handle actions in Confirm mode (Y/N prompts)
 pub fn handle_confirm_action(app: &mut App, action: Action) {
     match action {
+        Action::ConfirmYes if app.pending_confirm == Some(app::ConfirmAction::DeleteSession) => {
+            app.exit_confirm_mode();
+            app.delete_selected_session();
+        }
+        Action::ConfirmNo if app.pending_confirm == Some(app::ConfirmAction::DeleteSession) => {
+            app.exit_confirm_mode();
+        }
+        Action::ConfirmYes | Action::ConfirmNo
+            if app.pending_confirm == Some(app::ConfirmAction::DeleteListedComment) =>
+        {
+            app.exit_confirm_mode();
+            if action == Action::ConfirmYes {
+                app.delete_listed_comment();
+            }
+        }
+        Action::ConfirmYes | Action::ConfirmNo
+            if matches!(
+                app.pending_confirm,
+                Some(app::ConfirmAction::DiscardHunk { .. })
+            ) =>
+        {
+            let pending = app.pending_confirm;
+            app.exit_confirm_mode();
+            if let (
+                Action::ConfirmYes,
+                Some(app::ConfirmAction::DiscardHunk { file_idx, hunk_idx }),
+            ) = (action, pending)
+            {
+                app.discard_hunk(file_idx, hunk_idx);
+            }
+        }
         Action::ConfirmYes => {
             if let Some(app::ConfirmAction::CopyAndQuit) = app.pending_confirm {
                 if app.output_to_stdout {
                     match generate_export_content(
                         &app.session,
+                        &app.diff_files,
                         &app.diff_source,
                         &app.comment_types,
                         app.export_legend,
@@ -468,6 +860,7 @@ pub fn handle_confirm_action(app: &mut App, action: Action) {
                 } else {
                     match export_to_clipboard(
                         &app.session,
+                        &app.diff_files,
                         &app.diff_source,
                         &app.comment_types,
                         app.export_legend,
@@ -489,6 +882,34 @@ pub fn handle_confirm_action(app: &mut App, action: Action) {
     }
 }
 
+/// Handle actions in SessionBrowser mode (the `:sessions` overlay)
+pub fn handle_session_browser_action(app: &mut App, action: Action) {
+    match action {
+        Action::CursorDown(n) => app.session_browser_down(n),
+        Action::CursorUp(n) => app.session_browser_up(n),
+        Action::SelectFile => app.load_selected_session(),
+        Action::DeleteSession if !app.session_browser.entries.is_empty() => {
+            app.enter_confirm_mode(app::ConfirmAction::DeleteSession);
+        }
+        Action::NewSession => app.start_new_session(),
+        Action::ExitMode => app.close_session_browser(),
+        Action::Quit => app.should_quit = true,
+        _ => {}
+    }
+}
+
+/// Handle actions in BranchPicker mode (the `:branches` overlay)
+pub fn handle_branch_picker_action(app: &mut App, action: Action) {
+    match action {
+        Action::CursorDown(n) => app.branch_picker_down(n),
+        Action::CursorUp(n) => app.branch_picker_up(n),
+        Action::SelectFile => app.load_selected_branch(),
+        Action::ExitMode => app.close_branch_picker(),
+        Action::Quit => app.should_quit = true,
+        _ => {}
+    }
+}
+
 /// Handle actions in CommitSelect mode
 pub fn handle_commit_select_action(app: &mut App, action: Action) {
     match action {
@@ -542,6 +963,19 @@ pub fn handle_commit_selector_action(app: &mut App, action: Action) {
         Action::ExitMode => {
             app.focused_panel = FocusedPanel::Diff;
         }
+        Action::GrowCommitSelector => app.resize_commit_selector(true),
+        Action::ShrinkCommitSelector => app.resize_commit_selector(false),
+        _ => handle_shared_normal_action(app, action),
+    }
+}
+
+/// Handle actions when the `:comments` list is focused
+pub fn handle_comment_list_action(app: &mut App, action: Action) {
+    match action {
+        Action::CursorDown(n) | Action::MouseScrollDown(n) => app.comment_list_down(n),
+        Action::CursorUp(n) | Action::MouseScrollUp(n) => app.comment_list_up(n),
+        Action::SelectFile | Action::ToggleExpand => app.jump_to_listed_comment(),
+        Action::ExitMode | Action::Quit => app.close_comment_list(),
         _ => handle_shared_normal_action(app, action),
     }
 }
@@ -577,6 +1011,11 @@ pub fn handle_visual_action(app: &mut App, action: Action) {
                 app.exit_visual_mode();
             }
         }
+        Action::YankSelection => handle_yank_selection(app, false),
+        Action::YankLineRef | Action::YankPermalink => {
+            handle_yank_line_ref(app, action == Action::YankPermalink);
+            app.exit_visual_mode();
+        }
         Action::ExitMode => app.exit_visual_mode(),
         Action::Quit => app.should_quit = true,
         _ => {}
@@ -586,12 +1025,24 @@ pub fn handle_visual_action(app: &mut App, action: Action) {
 /// Handle actions when file list panel is focused
 pub fn handle_file_list_action(app: &mut App, action: Action) {
     match action {
-        Action::CursorDown(n) => app.file_list_down(n),
-        Action::CursorUp(n) => app.file_list_up(n),
+        Action::CursorDown(n) => {
+            app.file_list_down(n);
+            preview_selected_file(app);
+        }
+        Action::CursorUp(n) => {
+            app.file_list_up(n);
+            preview_selected_file(app);
+        }
         Action::ScrollLeft(n) => app.file_list_state.scroll_left(n),
         Action::ScrollRight(n) => app.file_list_state.scroll_right(n),
-        Action::MouseScrollDown(n) => app.file_list_viewport_scroll_down(n),
-        Action::MouseScrollUp(n) => app.file_list_viewport_scroll_up(n),
+        Action::MouseScrollDown(n) | Action::ScrollFileListDown(n) => {
+            app.file_list_viewport_scroll_down(n)
+        }
+        Action::MouseScrollUp(n) | Action::ScrollFileListUp(n) => {
+            app.file_list_viewport_scroll_up(n)
+        }
+        Action::GrowFileList => app.resize_file_list(true),
+        Action::ShrinkFileList => app.resize_file_list(false),
         Action::SelectFile | Action::ToggleExpand => {
             if let Some(item) = app.get_selected_tree_item() {
                 match item {
@@ -614,32 +1065,51 @@ pub fn handle_file_list_action(app: &mut App, action: Action) {
     }
 }
 
+/// With `:set preview`, scroll the diff to the file under the file-list
+/// cursor while the file list keeps focus.
+fn preview_selected_file(app: &mut App) {
+    if !app.file_list_preview {
+        return;
+    }
+    if let Some(FileTreeItem::File { file_idx, .. }) = app.get_selected_tree_item() {
+        app.jump_to_file(file_idx);
+    }
+}
+
 /// Handle actions when diff panel is focused
 pub fn handle_diff_action(app: &mut App, action: Action) {
     match action {
         Action::CursorDown(n) => app.cursor_down(n),
         Action::CursorUp(n) => app.cursor_up(n),
         Action::ScrollViewDown(n) => app.scroll_view_down(n),
+        Action::ScrollFileListDown(n) => app.scroll_file_list_down(n),
+        Action::ScrollFileListUp(n) => app.scroll_file_list_up(n),
         Action::ScrollViewUp(n) => app.scroll_view_up(n),
         Action::ScrollLeft(n) => app.scroll_left(n),
         Action::ScrollRight(n) => app.scroll_right(n),
         Action::MouseScrollDown(n) => app.scroll_view_down(n),
         Action::MouseScrollUp(n) => app.scroll_view_up(n),
         Action::SelectFile => {
-            if let Some(hit) = app.get_gap_at_cursor() {
+            if let Some(file_idx) = app.truncated_file_at_cursor() {
+                app.load_full_file(file_idx);
+            } else if let Some(file_idx) = app.generated_file_at_cursor() {
+                app.toggle_generated_file(file_idx);
+            } else if let Some(hit) = app.get_gap_at_cursor() {
                 match hit {
                     GapCursorHit::Expander(gap_id, dir) => {
                         let limit = if dir == ExpandDirection::Both {
                             None
                         } else {
-                            Some(20)
+                            Some(app.context_expand_lines)
                         };
                         if let Err(e) = app.expand_gap(gap_id, dir, limit) {
                             app.set_error(format!("Failed to expand: {e}"));
                         }
                     }
+                    // Lines next to the lower hunk come first
                     GapCursorHit::HiddenLines(gap_id) => {
-                        if let Err(e) = app.expand_gap(gap_id, ExpandDirection::Both, None) {
+                        let limit = Some(app.context_expand_lines);
+                        if let Err(e) = app.expand_gap(gap_id, ExpandDirection::Up, limit) {
                             app.set_error(format!("Failed to expand: {e}"));
                         }
                     }
@@ -650,7 +1120,9 @@ pub fn handle_diff_action(app: &mut App, action: Action) {
             }
         }
         Action::SelectFileFull => {
-            if let Some(hit) = app.get_gap_at_cursor() {
+            if let Some(file_idx) = app.truncated_file_at_cursor() {
+                app.load_full_file(file_idx);
+            } else if let Some(hit) = app.get_gap_at_cursor() {
                 match hit {
                     GapCursorHit::Expander(gap_id, _) | GapCursorHit::HiddenLines(gap_id) => {
                         if let Err(e) = app.expand_gap(gap_id, ExpandDirection::Both, None) {
@@ -663,6 +1135,32 @@ pub fn handle_diff_action(app: &mut App, action: Action) {
                 }
             }
         }
+        Action::ExpandGapFromTop => {
+            if let Some(GapCursorHit::Expander(gap_id, _) | GapCursorHit::HiddenLines(gap_id)) =
+                app.get_gap_at_cursor()
+            {
+                let limit = Some(app.context_expand_lines);
+                if let Err(e) = app.expand_gap(gap_id, ExpandDirection::Down, limit) {
+                    app.set_error(format!("Failed to expand: {e}"));
+                }
+            }
+        }
+        Action::CollapseGap => {
+            if let Some(
+                GapCursorHit::Expander(gap_id, _)
+                | GapCursorHit::HiddenLines(gap_id)
+                | GapCursorHit::ExpandedContent(gap_id),
+            ) = app.get_gap_at_cursor()
+            {
+                app.collapse_gap(gap_id);
+            }
+        }
+        Action::ToggleExpand => app.toggle_hunk_reviewed_at_cursor(),
+        Action::StageHunk => app.stage_hunk_at_cursor(false),
+        Action::UnstageHunk => app.stage_hunk_at_cursor(true),
+        Action::DiscardHunk => app.request_discard_hunk(),
+        Action::ApplySuggestion => app.apply_suggestion_at_cursor(),
+        Action::ReplyToComment => app.start_reply_at_cursor(),
         _ => handle_shared_normal_action(app, action),
     }
 }
@@ -675,8 +1173,9 @@ fn handle_shared_normal_action(app: &mut App, action: Action) {
     }
 
     match action {
+        Action::EditCommitMessage => app.request_edit_commit_message(),
         Action::Quit => {
-            if app.dirty && !app.quit_warned {
+            if app.dirty && app.autosave_interval.is_none() && !app.quit_warned {
                 app.set_warning("Unsaved changes. Press q again to quit.");
                 app.quit_warned = true;
             } else {
@@ -687,13 +1186,16 @@ fn handle_shared_normal_action(app: &mut App, action: Action) {
         Action::HalfPageUp => app.scroll_up(app.diff_state.viewport_height / 2),
         Action::PageDown => app.scroll_down(app.diff_state.viewport_height),
         Action::PageUp => app.scroll_up(app.diff_state.viewport_height),
-        Action::GoToTop => app.jump_to_file(0),
+        Action::GoToTop => app.jump_to_top(),
         Action::GoToBottom => app.jump_to_bottom(),
         Action::NextFile => app.next_file(),
         Action::PrevFile => app.prev_file(),
         Action::NextHunk => app.next_hunk(),
         Action::PrevHunk => app.prev_hunk(),
         Action::ToggleReviewed => app.toggle_reviewed(),
+        Action::OpenInEditor => app.request_open_in_editor(),
+        Action::ShowBlame => app.show_blame_at_cursor(),
+        Action::ToggleIgnoreWhitespace => app.toggle_ignore_whitespace(),
         Action::ToggleFocus => {
             let has_selector = app.has_inline_commit_selector();
             app.focused_panel = match (app.focused_panel, has_selector) {
@@ -701,6 +1203,7 @@ fn handle_shared_normal_action(app: &mut App, action: Action) {
                 (FocusedPanel::Diff, true) => FocusedPanel::CommitSelector,
                 (FocusedPanel::Diff, false) => FocusedPanel::FileList,
                 (FocusedPanel::CommitSelector, _) => FocusedPanel::FileList,
+                (FocusedPanel::CommentList, _) => FocusedPanel::Diff,
             };
         }
         Action::ToggleFocusReverse => {
@@ -709,7 +1212,9 @@ fn handle_shared_normal_action(app: &mut App, action: Action) {
                 (FocusedPanel::FileList, true) => FocusedPanel::CommitSelector,
                 (FocusedPanel::FileList, false) => FocusedPanel::Diff,
                 (FocusedPanel::Diff, _) => FocusedPanel::FileList,
-                (FocusedPanel::CommitSelector, _) => FocusedPanel::Diff,
+                (FocusedPanel::CommitSelector, _) | (FocusedPanel::CommentList, _) => {
+                    FocusedPanel::Diff
+                }
             };
         }
         Action::ExpandAll => {
@@ -735,6 +1240,8 @@ fn handle_shared_normal_action(app: &mut App, action: Action) {
         Action::EditComment if !app.enter_edit_mode() => {
             app.set_message("No comment at cursor");
         }
+        Action::Undo => app.undo(),
+        Action::Redo => app.redo(),
         Action::ExportToClipboard => handle_export(app),
         Action::SearchNext => {
             app.search_next_in_diff();
@@ -764,3 +1271,282 @@ fn handle_shared_normal_action(app: &mut App, action: Action) {
         _ => {}
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::test_support::build_app_with_vcs;
+    use crate::persistence::storage::tests::with_test_reviews_dir;
+    use crate::vcs::{GitBackend, JjBackend};
+    use std::fs;
+    use std::path::Path;
+    use std::process::Command;
+
+    fn run_command(app: &mut App, cmd: &str) {
+        app.input_mode = InputMode::Command;
+        app.command_buffer = cmd.to_string();
+        handle_command_action(app, Action::SubmitInput);
+    }
+
+    fn file_names(app: &App) -> Vec<String> {
+        app.diff_files
+            .iter()
+            .filter(|file| !file.is_commit_message)
+            .map(|file| file.display_path().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn should_quit_without_saving_on_forced_quit() {
+        use crate::app::test_support::{build_app_with_files, make_file_with_hunks, make_hunk};
+
+        let _guard = with_test_reviews_dir();
+        let file = make_file_with_hunks("a.rs", vec![make_hunk(1, 3)]);
+        let mut app = build_app_with_files(vec![file], 3);
+        app.toggle_reviewed();
+        assert!(app.dirty && app.autosave_interval.is_some());
+
+        run_command(&mut app, "q!");
+
+        assert!(app.should_quit && app.finish_quit());
+        assert!(app.dirty);
+        let saved = crate::persistence::list_sessions_for_repo(&app.vcs_info.root_path).unwrap();
+        assert!(saved.is_empty());
+    }
+
+    #[test]
+    fn should_preview_files_under_the_file_list_cursor_when_enabled() {
+        use crate::app::test_support::{build_app_with_files, make_file_with_hunks, make_hunk};
+
+        let mut app = build_app_with_files(
+            vec![
+                make_file_with_hunks("a.rs", vec![make_hunk(1, 5)]),
+                make_file_with_hunks("b.rs", vec![make_hunk(1, 5)]),
+                make_file_with_hunks("c.rs", vec![make_hunk(1, 5)]),
+            ],
+            20,
+        );
+        app.focused_panel = FocusedPanel::FileList;
+
+        // Off by default: only the selection moves
+        handle_file_list_action(&mut app, Action::CursorDown(1));
+        assert_eq!(app.diff_state.current_file_idx, 0);
+
+        run_command(&mut app, "set preview");
+        assert!(app.file_list_preview);
+        handle_file_list_action(&mut app, Action::CursorDown(1));
+        assert_eq!(app.diff_state.current_file_idx, 2);
+        assert_eq!(
+            app.diff_state.cursor_line,
+            app.calculate_file_scroll_offset(2)
+        );
+        handle_file_list_action(&mut app, Action::CursorUp(1));
+        assert_eq!(app.diff_state.current_file_idx, 1);
+        assert_eq!(app.focused_panel, FocusedPanel::FileList);
+
+        handle_file_list_action(&mut app, Action::SelectFile);
+        assert_eq!(app.diff_state.current_file_idx, 1);
+        assert_eq!(app.focused_panel, FocusedPanel::Diff);
+    }
+
+    fn git_commit(repo: &git2::Repository, file_name: &str, content: &str, message: &str) {
+        fs::write(repo.workdir().unwrap().join(file_name), content).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(file_name)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now("Test User", "test@example.com").unwrap();
+        let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+            .unwrap();
+    }
+
+    #[test]
+    fn should_switch_diff_to_git_revisions_and_back_to_worktree() {
+        let _guard = with_test_reviews_dir();
+        let temp = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(temp.path()).unwrap();
+        git_commit(&repo, "a.txt", "one\n", "First");
+        git_commit(&repo, "b.txt", "two\n", "Second");
+        git_commit(&repo, "a.txt", "one\nmore\n", "Third");
+        fs::write(temp.path().join("b.txt"), "two\nedited\n").unwrap();
+        let mut app = build_app_with_vcs(Box::new(GitBackend::from_path(temp.path()).unwrap()));
+
+        run_command(&mut app, "diff HEAD~2..HEAD");
+        assert!(matches!(&app.diff_source, DiffSource::CommitRange(ids) if ids.len() == 2));
+        assert_eq!(file_names(&app), ["a.txt", "b.txt"]);
+        assert_eq!(app.review_commits.len(), 2);
+        assert!(app.show_commit_selector);
+        assert_eq!(app.input_mode, InputMode::Normal);
+
+        run_command(&mut app, "diff HEAD");
+        assert_eq!(app.review_commits.len(), 1);
+        assert!(!app.show_commit_selector);
+        assert!(app.diff_files[0].is_commit_message);
+        assert_eq!(file_names(&app), ["a.txt"]);
+
+        // A bad revset leaves the current diff in place
+        let source = app.diff_source.clone();
+        run_command(&mut app, "diff no-such-ref");
+        assert_eq!(app.diff_source, source);
+        assert_eq!(file_names(&app), ["a.txt"]);
+        assert!(
+            app.message
+                .as_ref()
+                .is_some_and(|message| message.content.starts_with("Failed to diff"))
+        );
+
+        run_command(&mut app, "diff worktree");
+        assert_eq!(app.diff_source, DiffSource::StagedAndUnstaged);
+        assert_eq!(file_names(&app), ["b.txt"]);
+        assert!(app.review_commits.is_empty());
+    }
+
+    #[test]
+    fn should_show_a_single_git_commit_with_its_message() {
+        let _guard = with_test_reviews_dir();
+        let temp = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(temp.path()).unwrap();
+        git_commit(&repo, "a.txt", "one\n", "First");
+        git_commit(&repo, "b.txt", "two\n", "Second\n\nWith a body");
+        git_commit(&repo, "a.txt", "one\nmore\n", "Third");
+        let mut app = build_app_with_vcs(Box::new(GitBackend::from_path(temp.path()).unwrap()));
+
+        run_command(&mut app, "show HEAD~1");
+        assert!(matches!(&app.diff_source, DiffSource::CommitRange(ids) if ids.len() == 1));
+        assert_eq!(app.review_commits.len(), 1);
+        assert!(app.diff_files[0].is_commit_message);
+        assert_eq!(file_names(&app), ["b.txt"]);
+        let message = &app.message.as_ref().unwrap().content;
+        assert!(message.starts_with("Showing ") && message.ends_with(" Second"));
+
+        // Ranges and bad revisions leave the commit in place
+        let source = app.diff_source.clone();
+        for (rev, error) in [
+            ("HEAD~2..HEAD", "HEAD~2..HEAD is 2 commits"),
+            ("no-such-ref", "Failed to show no-such-ref"),
+        ] {
+            run_command(&mut app, &format!("show {rev}"));
+            assert_eq!(app.diff_source, source);
+            assert_eq!(file_names(&app), ["b.txt"]);
+            assert!(app.message.as_ref().unwrap().content.starts_with(error));
+        }
+    }
+
+    #[test]
+    fn should_load_depth_commits_into_the_selector_then_page_in_more() {
+        let _guard = with_test_reviews_dir();
+        let temp = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(temp.path()).unwrap();
+        for i in 0..5 {
+            git_commit(&repo, "a.txt", &format!("{i}\n"), &format!("Commit {i}"));
+        }
+        let mut app = build_app_with_vcs(Box::new(GitBackend::from_path(temp.path()).unwrap()));
+        app.commit_depth = 2;
+        app.commit_page_size = 2;
+
+        run_command(&mut app, "commits");
+        assert_eq!(app.input_mode, InputMode::CommitSelect);
+        assert_eq!(app.commit_list.len(), 2);
+        assert!(app.has_more_commit);
+
+        app.expand_commit().unwrap();
+        app.expand_commit().unwrap();
+        assert_eq!(app.commit_list.len(), 5);
+        assert!(!app.has_more_commit);
+    }
+
+    #[test]
+    fn should_review_a_branch_picked_from_branches() {
+        let _guard = with_test_reviews_dir();
+        let temp = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(temp.path()).unwrap();
+        git_commit(&repo, "a.txt", "one\n", "First");
+        let head_branch = repo.head().unwrap().shorthand().unwrap().to_string();
+        repo.find_branch(&head_branch, git2::BranchType::Local)
+            .unwrap()
+            .rename("main", true)
+            .unwrap();
+        git_commit(&repo, "a.txt", "one\ntwo\n", "On main");
+        let base = repo
+            .revparse_single("HEAD~1")
+            .unwrap()
+            .peel_to_commit()
+            .unwrap();
+        repo.branch("feature", &base, false).unwrap();
+        repo.set_head("refs/heads/feature").unwrap();
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+            .unwrap();
+        git_commit(&repo, "b.txt", "new\n", "Add b");
+        git_commit(&repo, "b.txt", "new\nmore\n", "Extend b");
+        let mut app = build_app_with_vcs(Box::new(GitBackend::from_path(temp.path()).unwrap()));
+
+        run_command(&mut app, "branches");
+        assert_eq!(app.input_mode, InputMode::BranchPicker);
+        let names: Vec<&str> = app
+            .branch_picker
+            .branches
+            .iter()
+            .map(|branch| branch.name.as_str())
+            .collect();
+        assert_eq!(names, ["feature", "main"]);
+        // The current branch is preselected
+        assert_eq!(app.branch_picker.selected, 0);
+
+        handle_branch_picker_action(&mut app, Action::SelectFile);
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert!(matches!(&app.diff_source, DiffSource::CommitRange(ids) if ids.len() == 2));
+        assert_eq!(file_names(&app), ["b.txt"]);
+        let summaries: Vec<&str> = app
+            .review_commits
+            .iter()
+            .map(|commit| commit.summary.as_str())
+            .collect();
+        assert_eq!(summaries, ["Extend b", "Add b"]);
+    }
+
+    #[test]
+    fn should_switch_diff_to_jj_revisions() {
+        let jj_available = Command::new("jj")
+            .arg("--version")
+            .output()
+            .is_ok_and(|output| output.status.success());
+        if !jj_available {
+            eprintln!("Skipping test: jj command not available");
+            return;
+        }
+        let _guard = with_test_reviews_dir();
+        let temp = tempfile::tempdir().unwrap();
+        let jj = |args: &[&str]| {
+            let output = Command::new("jj")
+                .args(args)
+                .current_dir(temp.path())
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "jj {args:?} failed");
+        };
+        jj(&["git", "init"]);
+        fs::write(temp.path().join("a.txt"), "one\n").unwrap();
+        jj(&["commit", "-m", "First"]);
+        fs::write(temp.path().join("b.txt"), "two\n").unwrap();
+        jj(&["commit", "-m", "Second"]);
+        fs::write(temp.path().join("a.txt"), "one\nedited\n").unwrap();
+        let mut app = build_app_with_vcs(Box::new(
+            JjBackend::from_path(temp.path().to_path_buf()).unwrap(),
+        ));
+
+        run_command(&mut app, "diff @--::@-");
+        assert!(matches!(&app.diff_source, DiffSource::CommitRange(ids) if ids.len() == 2));
+        assert_eq!(file_names(&app), ["a.txt", "b.txt"]);
+        assert!(app.show_commit_selector);
+
+        let source = app.diff_source.clone();
+        run_command(&mut app, "diff no_such_revision(");
+        assert_eq!(app.diff_source, source);
+
+        run_command(&mut app, "diff worktree");
+        assert_eq!(app.diff_source, DiffSource::StagedAndUnstaged);
+        assert_eq!(file_names(&app), ["a.txt"]);
+    }
+}
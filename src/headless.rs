@@ -0,0 +1,317 @@
+//! `tuicr export`: print the saved review for a diff without starting the TUI.
+
+use crate::app::{App, DiffSource};
+use crate::config::{self, AppConfig};
+use crate::error::{Result, TuicrError};
+use crate::model::{DiffFile, ReviewSession, SessionDiffSource};
+use crate::output::rdjson::build_rdjson;
+use crate::output::{ExportFormat, generate_export_content, generate_text};
+use crate::persistence::load_latest_session_for_context;
+use crate::theme::{CliArgs, Theme};
+use crate::vcs::{VcsInfo, detect_vcs};
+
+/// Exit code when the repository, revisions or config can't be read.
+pub const EXIT_ERROR: i32 = 1;
+/// Exit code when no saved session matches the diff.
+pub const EXIT_NO_SESSION: i32 = 2;
+
+/// Session sources tried for the working tree, as the TUI picks one of them
+/// depending on which kinds of changes exist.
+const WORKING_TREE_SOURCES: [SessionDiffSource; 4] = [
+    SessionDiffSource::StagedAndUnstaged,
+    SessionDiffSource::Staged,
+    SessionDiffSource::Unstaged,
+    SessionDiffSource::WorkingTree,
+];
+
+/// The diff selected by the command line and how its sessions are stored.
+struct ExportTarget {
+    vcs_info: VcsInfo,
+    head_commit: String,
+    commit_range: Option<Vec<String>>,
+    session_sources: Vec<SessionDiffSource>,
+    diff_source: DiffSource,
+    /// The diff itself, for the code snippets of Markdown exports
+    diff_files: Vec<DiffFile>,
+}
+
+/// Print the export to stdout and return the process exit code: 0 when a
+/// session was found, [`EXIT_NO_SESSION`] when an empty one was exported
+/// instead, and [`EXIT_ERROR`] on failure.
+pub fn run_export(cli_args: &CliArgs, format: ExportFormat) -> i32 {
+    let config = match config::load_config() {
+        Ok(outcome) => {
+            for warning in &outcome.warnings {
+                eprintln!("Warning: {warning}");
+            }
+            outcome.config
+        }
+        Err(e) => {
+            eprintln!("Error: Failed to load config: {e}");
+            return EXIT_ERROR;
+        }
+    };
+
+    let with_diff = format == ExportFormat::Markdown;
+    let result = resolve_target(cli_args, with_diff).and_then(|target| {
+        let found = load_session(&target)?;
+        let exit_code = if found.is_some() { 0 } else { EXIT_NO_SESSION };
+        let session = found.unwrap_or_else(|| empty_session(&target));
+        let output = render_export(
+            &session,
+            &target.diff_source,
+            &target.diff_files,
+            format,
+            config.as_ref(),
+        )?;
+        Ok((output, exit_code))
+    });
+
+    match result {
+        Ok((output, exit_code)) => {
+            print!("{output}");
+            if exit_code == EXIT_NO_SESSION {
+                eprintln!("No saved review session found for this diff");
+            }
+            exit_code
+        }
+        Err(e) => {
+            eprintln!("Error: {e}");
+            EXIT_ERROR
+        }
+    }
+}
+
+/// Pick the diff to export. With `with_diff` its files are loaded too; a diff
+/// that no longer loads (e.g. the changes were committed since) just leaves
+/// them out.
+fn resolve_target(cli_args: &CliArgs, with_diff: bool) -> Result<ExportTarget> {
+    let vcs = detect_vcs()?;
+    let vcs_info = vcs.info().clone();
+
+    if cli_args.pr_mode {
+        let pr_diff = vcs.get_pull_request_diff(cli_args.pr_base_ref.as_deref())?;
+        let info = pr_diff.info;
+        return Ok(ExportTarget {
+            vcs_info,
+            head_commit: info.head_commit.clone(),
+            commit_range: None,
+            session_sources: vec![SessionDiffSource::CommitRange],
+            diff_source: DiffSource::PullRequest {
+                base_ref: info.base_ref,
+                merge_base_commit: info.merge_base_commit,
+                head_commit: info.head_commit,
+                commit_count: info.commit_count,
+            },
+            diff_files: pr_diff.files,
+        });
+    }
+
+    if let Some(revisions) = &cli_args.revisions {
+        let commit_ids = vcs.resolve_revisions(revisions)?;
+        let head_commit = commit_ids.last().cloned().ok_or(TuicrError::NoChanges)?;
+        let (session_source, diff_source) = if cli_args.working_tree {
+            (
+                SessionDiffSource::StagedUnstagedAndCommits,
+                DiffSource::StagedUnstagedAndCommits(commit_ids.clone()),
+            )
+        } else {
+            (
+                SessionDiffSource::CommitRange,
+                DiffSource::CommitRange(commit_ids.clone()),
+            )
+        };
+        let diff_files = if !with_diff {
+            Vec::new()
+        } else if cli_args.working_tree {
+            vcs.get_working_tree_with_commits_diff(&commit_ids)
+                .unwrap_or_default()
+        } else {
+            vcs.get_commit_range_diff(&commit_ids).unwrap_or_default()
+        };
+        return Ok(ExportTarget {
+            vcs_info,
+            head_commit,
+            commit_range: Some(commit_ids),
+            session_sources: vec![session_source],
+            diff_source,
+            diff_files,
+        });
+    }
+
+    let diff_files = if with_diff {
+        vcs.get_working_tree_diff().unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+    Ok(ExportTarget {
+        head_commit: vcs_info.head_commit.clone(),
+        vcs_info,
+        commit_range: None,
+        session_sources: WORKING_TREE_SOURCES.to_vec(),
+        diff_source: DiffSource::StagedAndUnstaged,
+        diff_files,
+    })
+}
+
+/// The most recently updated session matching any of the target's sources.
+fn load_session(target: &ExportTarget) -> Result<Option<ReviewSession>> {
+    let mut latest: Option<ReviewSession> = None;
+    for &source in &target.session_sources {
+        let found = load_latest_session_for_context(
+            &target.vcs_info.root_path,
+            target.vcs_info.branch_name.as_deref(),
+            &target.head_commit,
+            source,
+            target.commit_range.as_deref(),
+        )?;
+        if let Some((_path, session)) = found
+            && latest
+                .as_ref()
+                .is_none_or(|best| session.updated_at > best.updated_at)
+        {
+            latest = Some(session);
+        }
+    }
+    Ok(latest)
+}
+
+fn empty_session(target: &ExportTarget) -> ReviewSession {
+    let mut session = ReviewSession::new(
+        target.vcs_info.root_path.clone(),
+        target.head_commit.clone(),
+        target.vcs_info.branch_name.clone(),
+        target.session_sources[0],
+    );
+    session.commit_range = target.commit_range.clone();
+    session
+}
+
+/// Working tree sessions record which changes they reviewed; describe the
+/// export by that rather than the default guess.
+fn session_diff_source(session: &ReviewSession, fallback: &DiffSource) -> DiffSource {
+    match session.diff_source {
+        SessionDiffSource::WorkingTree => DiffSource::WorkingTree,
+        SessionDiffSource::Staged => DiffSource::Staged,
+        SessionDiffSource::Unstaged => DiffSource::Unstaged,
+        SessionDiffSource::StagedAndUnstaged => DiffSource::StagedAndUnstaged,
+        _ => fallback.clone(),
+    }
+}
+
+fn render_export(
+    session: &ReviewSession,
+    diff_source: &DiffSource,
+    diff_files: &[DiffFile],
+    format: ExportFormat,
+    config: Option<&AppConfig>,
+) -> Result<String> {
+    let diff_source = session_diff_source(session, diff_source);
+    let comment_types = App::resolve_comment_types(
+        &Theme::dark(),
+        config.and_then(|cfg| cfg.comment_types.clone()),
+    );
+
+    let output = match format {
+        ExportFormat::Text => generate_text(session, &diff_source, &comment_types),
+        ExportFormat::Markdown => {
+            let show_legend = config.and_then(|cfg| cfg.export_legend) != Some(false);
+            match generate_export_content(
+                session,
+                diff_files,
+                &diff_source,
+                &comment_types,
+                show_legend,
+            ) {
+                Ok(content) => content,
+                Err(TuicrError::NoComments) => String::new(),
+                Err(e) => return Err(e),
+            }
+        }
+        ExportFormat::Json => serde_json::to_string_pretty(session)? + "\n",
+        ExportFormat::Rdjson => serde_json::to_string_pretty(&build_rdjson(session)?)? + "\n",
+    };
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::test_support::{make_file_with_hunks, make_hunk};
+    use crate::model::{Comment, CommentType, FileStatus, LineSide};
+    use std::path::PathBuf;
+
+    fn session() -> ReviewSession {
+        let mut session = ReviewSession::new(
+            PathBuf::from("/tmp/repo"),
+            "abc1234def".to_string(),
+            Some("main".to_string()),
+            SessionDiffSource::Staged,
+        );
+        let path = PathBuf::from("src/lib.rs");
+        session.add_file(path.clone(), FileStatus::Modified, 0);
+        if let Some(review) = session.get_file_mut(&path) {
+            review.add_line_comment(
+                7,
+                Comment::new(
+                    "Handle the error\ninstead of unwrapping".to_string(),
+                    CommentType::Issue,
+                    Some(LineSide::New),
+                ),
+            );
+        }
+        session
+    }
+
+    #[test]
+    fn should_render_each_export_format() {
+        let session = session();
+        let diff_files = [make_file_with_hunks("src/lib.rs", vec![make_hunk(5, 5)])];
+        let render = |format| {
+            render_export(
+                &session,
+                &DiffSource::StagedAndUnstaged,
+                &diff_files,
+                format,
+                None,
+            )
+            .unwrap()
+        };
+
+        assert_eq!(
+            render(ExportFormat::Text),
+            "src/lib.rs:7: [ISSUE] Handle the error\n    instead of unwrapping\n"
+        );
+
+        let markdown = render(ExportFormat::Markdown);
+        assert!(markdown.contains("Reviewing staged changes"));
+        assert!(markdown.contains("**[ISSUE]** `src/lib.rs:7`"));
+        assert!(markdown.contains("   ```rs\n   hunk line 7\n   ```"));
+
+        let json: serde_json::Value = serde_json::from_str(&render(ExportFormat::Json)).unwrap();
+        assert_eq!(json["base_commit"], "abc1234def");
+
+        let rdjson: serde_json::Value =
+            serde_json::from_str(&render(ExportFormat::Rdjson)).unwrap();
+        assert_eq!(rdjson["diagnostics"][0]["location"]["path"], "src/lib.rs");
+    }
+
+    #[test]
+    fn should_export_nothing_for_markdown_without_comments() {
+        let session = ReviewSession::new(
+            PathBuf::from("/tmp/repo"),
+            "abc1234def".to_string(),
+            None,
+            SessionDiffSource::CommitRange,
+        );
+        let output = render_export(
+            &session,
+            &DiffSource::CommitRange(vec!["abc1234def".to_string()]),
+            &[],
+            ExportFormat::Markdown,
+            None,
+        )
+        .unwrap();
+        assert_eq!(output, "");
+    }
+}
@@ -18,16 +18,29 @@ pub enum Action {
     PrevFile,
     NextHunk,
     PrevHunk,
+    PendingGCommand,
     PendingZCommand,
     PendingShiftZCommand,
     PendingSemicolonCommand,
+    PendingYCommand,
+    PendingMarkCommand,
+    PendingJumpToMarkCommand,
     ScrollLeft(usize),
     ScrollRight(usize),
     ScrollViewDown(usize),
     ScrollViewUp(usize),
+    /// Scroll the file list viewport, leaving its selection alone
+    ScrollFileListDown(usize),
+    ScrollFileListUp(usize),
     MouseScrollUp(usize),
     MouseScrollDown(usize),
 
+    // Panel sizes
+    GrowFileList,
+    ShrinkFileList,
+    GrowCommitSelector,
+    ShrinkCommitSelector,
+
     // Panel focus
     ToggleFocus,
     ToggleFocusReverse,
@@ -38,18 +51,39 @@ pub enum Action {
     AddLineComment,
     AddFileComment,
     EditComment,
+    ApplySuggestion,
+    ReplyToComment,
+    OpenInEditor,
+    EditCommitMessage,
+    /// Continue the comment being typed in `$EDITOR`
+    EditCommentInEditor,
+    /// Blame the line under the cursor (`gb`); again for the full commit
+    ShowBlame,
+    ToggleIgnoreWhitespace,
     PendingDCommand,
+    Undo,
+    Redo,
     SearchNext,
     SearchPrev,
+    StageHunk,
+    UnstageHunk,
+    DiscardHunk,
 
     // Visual selection mode
     EnterVisualMode,
     AddRangeComment,
+    YankSelection,
+    YankLineRef,
+    YankPermalink,
 
     // Session
     Quit,
     ExportToClipboard,
 
+    // Session browser
+    DeleteSession,
+    NewSession,
+
     // Mode changes
     EnterCommandMode,
     EnterSearchMode,
@@ -91,6 +125,10 @@ pub enum Action {
     ExpandAll,
     CollapseAll,
     SelectFileFull,
+    /// Load the next chunk of the gap under the cursor from its top (`E`)
+    ExpandGapFromTop,
+    /// Collapse the gap under the cursor (`zc`)
+    CollapseGap,
 
     // No-op
     None,
@@ -106,6 +144,33 @@ pub fn map_key_to_action(key: KeyEvent, mode: InputMode) -> Action {
         InputMode::Confirm => map_confirm_mode(key),
         InputMode::CommitSelect => map_commit_select_mode(key),
         InputMode::VisualSelect => map_visual_mode(key),
+        InputMode::SessionBrowser => map_session_browser_mode(key),
+        InputMode::BranchPicker => map_branch_picker_mode(key),
+    }
+}
+
+/// Largest count applied to repeated motions such as `}`, to keep huge
+/// counts from stalling the UI.
+const MAX_MOTION_REPEAT: usize = 1000;
+
+/// Apply a vim-style count prefix (e.g. the `5` in `5j`) to an action.
+/// Returns the action to run and how many times to run it; actions that
+/// don't take a count run once.
+pub fn apply_count(action: Action, count: usize) -> (Action, usize) {
+    let count = count.max(1);
+    match action {
+        Action::CursorDown(n) => (Action::CursorDown(n.saturating_mul(count)), 1),
+        Action::CursorUp(n) => (Action::CursorUp(n.saturating_mul(count)), 1),
+        Action::ScrollViewDown(n) => (Action::ScrollViewDown(n.saturating_mul(count)), 1),
+        Action::ScrollViewUp(n) => (Action::ScrollViewUp(n.saturating_mul(count)), 1),
+        Action::ScrollFileListDown(n) => (Action::ScrollFileListDown(n.saturating_mul(count)), 1),
+        Action::ScrollFileListUp(n) => (Action::ScrollFileListUp(n.saturating_mul(count)), 1),
+        Action::ScrollLeft(n) => (Action::ScrollLeft(n.saturating_mul(count)), 1),
+        Action::ScrollRight(n) => (Action::ScrollRight(n.saturating_mul(count)), 1),
+        Action::NextFile | Action::PrevFile | Action::NextHunk | Action::PrevHunk => {
+            (action, count.min(MAX_MOTION_REPEAT))
+        }
+        other => (other, 1),
     }
 }
 
@@ -116,17 +181,21 @@ fn map_normal_mode(key: KeyEvent) -> Action {
         (KeyCode::Char('k') | KeyCode::Up, KeyModifiers::NONE) => Action::CursorUp(1),
         (KeyCode::Char('e'), KeyModifiers::CONTROL) => Action::ScrollViewDown(1),
         (KeyCode::Char('y'), KeyModifiers::CONTROL) => Action::ScrollViewUp(1),
+        (KeyCode::Char('j'), KeyModifiers::CONTROL) => Action::ScrollFileListDown(1),
+        (KeyCode::Char('k'), KeyModifiers::CONTROL) => Action::ScrollFileListUp(1),
         (KeyCode::Char('d'), KeyModifiers::CONTROL) => Action::HalfPageDown,
         (KeyCode::Char('u'), KeyModifiers::CONTROL) => Action::HalfPageUp,
         (KeyCode::Char('f'), KeyModifiers::CONTROL) => Action::PageDown,
         (KeyCode::Char('b'), KeyModifiers::CONTROL) => Action::PageUp,
         (KeyCode::PageDown, KeyModifiers::NONE) => Action::PageDown,
         (KeyCode::PageUp, KeyModifiers::NONE) => Action::PageUp,
-        (KeyCode::Char('g'), KeyModifiers::NONE) => Action::GoToTop,
+        (KeyCode::Char('g'), KeyModifiers::NONE) => Action::PendingGCommand,
         (KeyCode::Char('G'), _) => Action::GoToBottom,
         (KeyCode::Char('z'), KeyModifiers::NONE) => Action::PendingZCommand,
         (KeyCode::Char('Z'), _) => Action::PendingShiftZCommand,
         (KeyCode::Char(';'), _) => Action::PendingSemicolonCommand,
+        (KeyCode::Char('m'), KeyModifiers::NONE) => Action::PendingMarkCommand,
+        (KeyCode::Char('`'), _) => Action::PendingJumpToMarkCommand,
 
         // File navigation (use _ for modifiers since shift is implicit in the character)
         (KeyCode::Char('}'), _) => Action::NextFile,
@@ -141,21 +210,38 @@ fn map_normal_mode(key: KeyEvent) -> Action {
         (KeyCode::BackTab, _) => Action::ToggleFocusReverse,
         (KeyCode::Enter, KeyModifiers::NONE) => Action::SelectFile,
         (KeyCode::Enter, KeyModifiers::SHIFT) => Action::SelectFileFull,
+        (KeyCode::Char('E'), _) => Action::ExpandGapFromTop,
 
         // Horizontal scrolling
         (KeyCode::Char('h') | KeyCode::Left, KeyModifiers::NONE) => Action::ScrollLeft(4),
         (KeyCode::Char('l') | KeyCode::Right, KeyModifiers::NONE) => Action::ScrollRight(4),
 
+        // Panel sizes; the focused panel is resized
+        (KeyCode::Right, KeyModifiers::CONTROL) => Action::GrowFileList,
+        (KeyCode::Left, KeyModifiers::CONTROL) => Action::ShrinkFileList,
+        (KeyCode::Down, KeyModifiers::CONTROL) => Action::GrowCommitSelector,
+        (KeyCode::Up, KeyModifiers::CONTROL) => Action::ShrinkCommitSelector,
+
         // Review actions
         (KeyCode::Char('r'), KeyModifiers::NONE) => Action::ToggleReviewed,
         (KeyCode::Char('c'), KeyModifiers::NONE) => Action::AddLineComment,
         (KeyCode::Char('C'), _) => Action::AddFileComment,
         (KeyCode::Char('i'), KeyModifiers::NONE) => Action::EditComment,
+        (KeyCode::Char('a'), KeyModifiers::NONE) => Action::ApplySuggestion,
+        (KeyCode::Char('R'), _) => Action::ReplyToComment,
+        (KeyCode::Char('e'), KeyModifiers::NONE) => Action::OpenInEditor,
+        (KeyCode::Char('w'), KeyModifiers::NONE) => Action::ToggleIgnoreWhitespace,
         (KeyCode::Char('d'), KeyModifiers::NONE) => Action::PendingDCommand,
+        (KeyCode::Char('u'), KeyModifiers::NONE) => Action::Undo,
+        (KeyCode::Char('r'), KeyModifiers::CONTROL) => Action::Redo,
         (KeyCode::Char('v') | KeyCode::Char('V'), _) => Action::EnterVisualMode,
-        (KeyCode::Char('y'), KeyModifiers::NONE) => Action::ExportToClipboard,
+        (KeyCode::Char('y'), KeyModifiers::NONE) => Action::PendingYCommand,
+        (KeyCode::Char('Y'), _) => Action::ExportToClipboard,
         (KeyCode::Char('n'), KeyModifiers::NONE) => Action::SearchNext,
         (KeyCode::Char('N'), _) => Action::SearchPrev,
+        (KeyCode::Char('s'), KeyModifiers::NONE) => Action::StageHunk,
+        (KeyCode::Char('S'), _) => Action::UnstageHunk,
+        (KeyCode::Char('X'), _) => Action::DiscardHunk,
 
         // Mode changes (use _ for shifted characters like : and ?)
         (KeyCode::Char(':'), _) => Action::EnterCommandMode,
@@ -255,6 +341,7 @@ fn map_comment_mode(key: KeyEvent) -> Action {
         (KeyCode::Backspace, KeyModifiers::NONE) => Action::DeleteChar,
         (KeyCode::Char('w'), KeyModifiers::CONTROL) => Action::DeleteWord,
         (KeyCode::Char('u'), KeyModifiers::CONTROL) => Action::ClearLine,
+        (KeyCode::Char('g'), KeyModifiers::CONTROL) => Action::EditCommentInEditor,
         (KeyCode::Char(c), _) => Action::InsertChar(c),
         _ => Action::None,
     }
@@ -301,6 +388,28 @@ fn map_commit_select_mode(key: KeyEvent) -> Action {
     }
 }
 
+fn map_session_browser_mode(key: KeyEvent) -> Action {
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down => Action::CursorDown(1),
+        KeyCode::Char('k') | KeyCode::Up => Action::CursorUp(1),
+        KeyCode::Enter => Action::SelectFile,
+        KeyCode::Char('d') => Action::DeleteSession,
+        KeyCode::Char('n') => Action::NewSession,
+        KeyCode::Esc | KeyCode::Char('q') => Action::ExitMode,
+        _ => Action::None,
+    }
+}
+
+fn map_branch_picker_mode(key: KeyEvent) -> Action {
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down => Action::CursorDown(1),
+        KeyCode::Char('k') | KeyCode::Up => Action::CursorUp(1),
+        KeyCode::Enter => Action::SelectFile,
+        KeyCode::Esc | KeyCode::Char('q') => Action::ExitMode,
+        _ => Action::None,
+    }
+}
+
 fn map_visual_mode(key: KeyEvent) -> Action {
     match (key.code, key.modifiers) {
         // Extend selection
@@ -309,6 +418,12 @@ fn map_visual_mode(key: KeyEvent) -> Action {
         // Create range comment
         (KeyCode::Char('c'), KeyModifiers::NONE) => Action::AddRangeComment,
         (KeyCode::Enter, KeyModifiers::NONE) => Action::AddRangeComment,
+        // Copy the selected lines (gy keeps diff markers), a file:line
+        // reference or a permalink for the selection
+        (KeyCode::Char('y'), KeyModifiers::NONE) => Action::YankSelection,
+        (KeyCode::Char('g'), KeyModifiers::NONE) => Action::PendingGCommand,
+        (KeyCode::Char('r'), KeyModifiers::NONE) => Action::YankLineRef,
+        (KeyCode::Char('Y'), _) => Action::YankPermalink,
         // Cancel selection
         (KeyCode::Esc, KeyModifiers::NONE) => Action::ExitMode,
         (KeyCode::Char('v') | KeyCode::Char('V'), _) => Action::ExitMode,
@@ -344,6 +459,62 @@ mod tests {
         }
     }
 
+    #[test]
+    fn should_multiply_cursor_motion_by_count() {
+        assert_eq!(
+            apply_count(Action::CursorDown(1), 5),
+            (Action::CursorDown(5), 1)
+        );
+        assert_eq!(
+            apply_count(Action::CursorUp(1), 10),
+            (Action::CursorUp(10), 1)
+        );
+    }
+
+    #[test]
+    fn should_repeat_file_and_hunk_jumps_by_count() {
+        assert_eq!(apply_count(Action::NextFile, 3), (Action::NextFile, 3));
+        assert_eq!(apply_count(Action::PrevHunk, 2), (Action::PrevHunk, 2));
+        assert_eq!(
+            apply_count(Action::NextHunk, 999_999),
+            (Action::NextHunk, MAX_MOTION_REPEAT)
+        );
+    }
+
+    #[test]
+    fn should_map_ctrl_j_and_ctrl_k_to_file_list_scroll() {
+        let ctrl = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+        assert_eq!(map_normal_mode(ctrl('j')), Action::ScrollFileListDown(1));
+        assert_eq!(map_normal_mode(ctrl('k')), Action::ScrollFileListUp(1));
+        assert_eq!(
+            apply_count(Action::ScrollFileListDown(1), 4),
+            (Action::ScrollFileListDown(4), 1)
+        );
+    }
+
+    #[test]
+    fn should_map_ctrl_arrows_to_panel_resizing() {
+        let ctrl = |code| KeyEvent::new(code, KeyModifiers::CONTROL);
+        assert_eq!(map_normal_mode(ctrl(KeyCode::Right)), Action::GrowFileList);
+        assert_eq!(map_normal_mode(ctrl(KeyCode::Left)), Action::ShrinkFileList);
+        assert_eq!(
+            map_normal_mode(ctrl(KeyCode::Down)),
+            Action::GrowCommitSelector
+        );
+        assert_eq!(
+            map_normal_mode(ctrl(KeyCode::Up)),
+            Action::ShrinkCommitSelector
+        );
+    }
+
+    #[test]
+    fn should_ignore_count_for_non_motion_actions() {
+        assert_eq!(
+            apply_count(Action::ToggleReviewed, 4),
+            (Action::ToggleReviewed, 1)
+        );
+    }
+
     #[test]
     fn should_map_uppercase_g_to_go_to_bottom_in_normal_mode() {
         let action = map_normal_mode(key_shift('G'));
@@ -351,9 +522,44 @@ mod tests {
     }
 
     #[test]
-    fn should_map_lowercase_g_to_go_to_top_in_normal_mode() {
+    fn should_map_lowercase_g_to_pending_g_in_normal_mode() {
         let action = map_normal_mode(key(KeyCode::Char('g')));
-        assert_eq!(action, Action::GoToTop);
+        assert_eq!(action, Action::PendingGCommand);
+    }
+
+    #[test]
+    fn should_map_lowercase_y_to_pending_y_in_normal_mode() {
+        let action = map_normal_mode(key(KeyCode::Char('y')));
+        assert_eq!(action, Action::PendingYCommand);
+    }
+
+    #[test]
+    fn should_map_yank_keys_in_visual_mode() {
+        assert_eq!(
+            map_visual_mode(key(KeyCode::Char('y'))),
+            Action::YankSelection
+        );
+        assert_eq!(
+            map_visual_mode(key(KeyCode::Char('r'))),
+            Action::YankLineRef
+        );
+        assert_eq!(
+            map_visual_mode(KeyEvent::new(KeyCode::Char('Y'), KeyModifiers::SHIFT)),
+            Action::YankPermalink
+        );
+    }
+
+    #[test]
+    fn should_map_s_to_hunk_staging_in_normal_mode() {
+        assert_eq!(map_normal_mode(key(KeyCode::Char('s'))), Action::StageHunk);
+        assert_eq!(map_normal_mode(key_shift('S')), Action::UnstageHunk);
+        assert_eq!(map_normal_mode(key_shift('X')), Action::DiscardHunk);
+    }
+
+    #[test]
+    fn should_map_uppercase_y_to_export_in_normal_mode() {
+        let action = map_normal_mode(key_shift('Y'));
+        assert_eq!(action, Action::ExportToClipboard);
     }
 
     #[test]
@@ -2,4 +2,4 @@ pub mod handler;
 pub mod keybindings;
 pub mod mode;
 
-pub use keybindings::{Action, map_key_to_action};
+pub use keybindings::{Action, apply_count, map_key_to_action};
@@ -1,22 +1,29 @@
 mod app;
 mod config;
+mod editor;
 mod error;
 mod handler;
 mod hash;
+mod headless;
 mod input;
 mod model;
 mod output;
 mod persistence;
+mod suggestion;
 mod syntax;
 mod text_edit;
 mod theme;
 mod tuicrignore;
 mod ui;
+mod undo;
 mod update;
 mod vcs;
+mod watch;
 
 use std::fs::File;
 use std::io::{self, Write};
+use std::path::Path;
+use std::process::Command;
 use std::sync::mpsc;
 use std::time::{Duration, Instant};
 
@@ -35,19 +42,76 @@ use ratatui::{Terminal, backend::CrosstermBackend};
 
 use app::{App, FocusedPanel, InputMode};
 use handler::{
-    handle_command_action, handle_comment_action, handle_commit_select_action,
-    handle_commit_selector_action, handle_confirm_action, handle_diff_action,
-    handle_file_list_action, handle_help_action, handle_mouse_event, handle_search_action,
-    handle_visual_action,
+    handle_branch_picker_action, handle_command_action, handle_comment_action,
+    handle_comment_list_action, handle_commit_select_action, handle_commit_selector_action,
+    handle_confirm_action, handle_diff_action, handle_file_list_action, handle_help_action,
+    handle_mouse_event, handle_search_action, handle_session_browser_action, handle_visual_action,
+};
+use input::{Action, apply_count, map_key_to_action};
+use theme::{
+    ColorMode, Theme, detect_color_mode, no_color_requested, parse_cli_args,
+    resolve_theme_with_config,
 };
-use input::{Action, map_key_to_action};
-use theme::{parse_cli_args, resolve_theme_with_config};
 
 /// Timeout for the "press Ctrl+C again to exit" feature
 const CTRL_C_EXIT_TIMEOUT: Duration = Duration::from_secs(2);
+const WATCH_MESSAGE_TIMEOUT: Duration = Duration::from_secs(3);
 /// Hide the file list by default on narrow terminals.
 const MIN_WIDTH_FOR_FILE_LIST: u16 = 100;
 
+/// Terminal features enabled at startup, restored after suspending the TUI.
+#[derive(Clone, Copy)]
+struct TerminalFlags {
+    mouse: bool,
+    keyboard_enhancement: bool,
+    /// The TUI renders to /dev/tty because stdout is reserved for --stdout output
+    tty_only: bool,
+}
+
+/// Suspend the TUI, open `path` in `$VISUAL`/`$EDITOR` and restore the terminal.
+fn run_editor<W: Write>(
+    terminal: &mut Terminal<CrosstermBackend<W>>,
+    flags: TerminalFlags,
+    path: &Path,
+    line: Option<u32>,
+) -> anyhow::Result<()> {
+    if flags.keyboard_enhancement {
+        let _ = execute!(terminal.backend_mut(), PopKeyboardEnhancementFlags);
+    }
+    if flags.mouse {
+        execute!(terminal.backend_mut(), DisableMouseCapture)?;
+    }
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    disable_raw_mode()?;
+
+    let (program, args) = editor::editor_command(&editor::resolve_editor(), path, line);
+    let mut command = Command::new(&program);
+    command.args(&args);
+    if flags.tty_only {
+        command.stdout(File::options().write(true).open("/dev/tty")?);
+    }
+    let status = command.status();
+
+    enable_raw_mode()?;
+    execute!(terminal.backend_mut(), EnterAlternateScreen)?;
+    if flags.mouse {
+        execute!(terminal.backend_mut(), EnableMouseCapture)?;
+    }
+    if flags.keyboard_enhancement {
+        let _ = execute!(
+            terminal.backend_mut(),
+            PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES)
+        );
+    }
+    terminal.clear()?;
+
+    let status = status.map_err(|e| anyhow::anyhow!("{program}: {e}"))?;
+    if !status.success() {
+        anyhow::bail!("{program} exited with {status}");
+    }
+    Ok(())
+}
+
 fn main() -> anyhow::Result<()> {
     // Setup panic hook to restore terminal on panic
     let original_hook = std::panic::take_hook();
@@ -63,6 +127,11 @@ fn main() -> anyhow::Result<()> {
     // This also configures syntax highlighting colors before diff parsing
     let mut cli_args = parse_cli_args();
 
+    // `tuicr export` prints the saved review and exits before any terminal setup
+    if let Some(format) = cli_args.export_format {
+        std::process::exit(headless::run_export(&cli_args, format));
+    }
+
     // Check keyboard enhancement support before enabling raw mode.
     // Skip when --stdout is used because the probe writes escape sequences to stdout,
     // which would leak into the captured export output.
@@ -72,10 +141,10 @@ fn main() -> anyhow::Result<()> {
         matches!(supports_keyboard_enhancement(), Ok(true))
     };
 
-    // --file is mutually exclusive with --path, -r, and -w
+    // --file is mutually exclusive with --path, path arguments, -r, and -w
     if cli_args.file_path.is_some() {
-        if cli_args.path_filter.is_some() {
-            eprintln!("Error: --file cannot be combined with --path");
+        if cli_args.path_filter.is_some() || !cli_args.paths.is_empty() {
+            eprintln!("Error: --file cannot be combined with --path or path arguments");
             std::process::exit(2);
         }
         if cli_args.revisions.is_some() {
@@ -92,6 +161,15 @@ fn main() -> anyhow::Result<()> {
     if cli_args.path_filter.is_some() && !cli_args.working_tree && cli_args.revisions.is_none() {
         cli_args.working_tree = true;
     }
+    // Path arguments are relative to the current directory, --path to the
+    // repository root; App::new makes both repository-relative
+    let cwd = std::env::current_dir()?;
+    let mut path_filter: Vec<String> = cli_args
+        .paths
+        .iter()
+        .map(|path| cwd.join(path).to_string_lossy().into_owned())
+        .collect();
+    path_filter.extend(cli_args.path_filter.clone());
     let mut startup_warnings = Vec::new();
     let config_outcome = match config::load_config() {
         Ok(outcome) => outcome,
@@ -122,6 +200,38 @@ fn main() -> anyhow::Result<()> {
             .and_then(|cfg| cfg.appearance.as_deref()),
     );
     startup_warnings.extend(theme_warnings);
+    let mut theme =
+        if no_color_requested(cli_args.no_color, std::env::var_os("NO_COLOR").as_deref()) {
+            Theme::monochrome()
+        } else {
+            theme
+        };
+    let color_mode = config_outcome
+        .config
+        .as_ref()
+        .and_then(|cfg| cfg.color_mode.as_deref())
+        .and_then(ColorMode::from_config)
+        .unwrap_or_else(|| {
+            detect_color_mode(
+                std::env::var("COLORTERM").ok().as_deref(),
+                std::env::var("TERM").ok().as_deref(),
+            )
+        });
+    theme.downsample(color_mode);
+    if let Some(overrides) = config_outcome
+        .config
+        .as_ref()
+        .and_then(|cfg| cfg.syntax.as_deref())
+    {
+        startup_warnings.extend(theme.set_syntax_overrides(overrides));
+    }
+    if let Some(max_lines) = config_outcome
+        .config
+        .as_ref()
+        .and_then(|cfg| cfg.max_highlight_lines)
+    {
+        theme.set_max_highlight_lines(max_lines);
+    }
 
     // Start update check in background (non-blocking)
     let update_rx = if !cli_args.no_update_check {
@@ -147,8 +257,18 @@ fn main() -> anyhow::Result<()> {
         cli_args.pr_mode,
         cli_args.pr_base_ref.as_deref(),
         cli_args.working_tree,
-        cli_args.path_filter.as_deref(),
+        &path_filter,
+        cli_args.files.as_deref(),
         cli_args.file_path.as_deref(),
+        config_outcome
+            .config
+            .as_ref()
+            .and_then(|cfg| cfg.rename_threshold),
+        config_outcome
+            .config
+            .as_ref()
+            .and_then(|cfg| cfg.max_file_lines),
+        cli_args.depth,
     ) {
         Ok(mut app) => {
             app.supports_keyboard_enhancement = keyboard_enhancement_supported;
@@ -159,9 +279,15 @@ fn main() -> anyhow::Result<()> {
         }
         Err(e) => {
             eprintln!("Error: {e}");
-            if cli_args.pr_mode {
+            if let error::TuicrError::NoMatchingFiles(_) = e {
+                eprintln!("\nCheck the --files glob, e.g. --files 'src/**'");
+            } else if let error::TuicrError::PathOutsideRepository(_) = e {
+                eprintln!("\nPath arguments must be inside the repository being reviewed.");
+            } else if let error::TuicrError::BareRepository(_) = e {
+                eprintln!("\nRun tuicr from one of its worktrees (see `git worktree list`).");
+            } else if cli_args.pr_mode {
                 eprintln!(
-                    "\nPR mode requires a git repository with commits ahead of the selected base ref."
+                    "\nPR mode requires a repository with commits ahead of the selected base ref."
                 );
                 eprintln!("Try: tuicr --pr --base origin/main");
             } else {
@@ -202,8 +328,10 @@ fn main() -> anyhow::Result<()> {
     let backend = CrosstermBackend::new(tty_output);
     let mut terminal = Terminal::new(backend)?;
 
-    // Apply config-driven defaults
+    // Apply config-driven defaults; resized panels are saved back to the file
+    app.config_path = config::config_path().ok();
     if let Some(ref cfg) = config_outcome.config {
+        app.syntax_overrides = cfg.syntax.clone().unwrap_or_default();
         if cfg.show_file_list == Some(false) {
             app.show_file_list = false;
             app.focused_panel = FocusedPanel::Diff;
@@ -220,6 +348,66 @@ fn main() -> anyhow::Result<()> {
         if cfg.cursor_line == Some(false) {
             app.cursor_line_highlight = false;
         }
+        if cfg.skip_reviewed_in_navigation == Some(true) {
+            app.skip_reviewed_in_navigation = true;
+        }
+        if cfg.file_list_preview == Some(true) {
+            app.file_list_preview = true;
+        }
+        if cfg.github_request_changes == Some(false) {
+            app.github_request_changes = false;
+        }
+        if cfg.detect_moves == Some(false) {
+            app.detect_moves = false;
+        }
+        if cfg.scrollbar == Some(false) {
+            app.show_scrollbar = false;
+        }
+        if let Some(height) = cfg.comment_list_height {
+            app.comment_list_height = height;
+        }
+        if let Some(width) = cfg.file_list_width {
+            app.file_list_width_percent = width.clamp(
+                *app::FILE_LIST_WIDTH_PERCENT_RANGE.start(),
+                *app::FILE_LIST_WIDTH_PERCENT_RANGE.end(),
+            );
+        }
+        if let Some(height) = cfg.commit_selector_height {
+            app.commit_selector_height = height.clamp(
+                *app::COMMIT_SELECTOR_HEIGHT_RANGE.start(),
+                *app::COMMIT_SELECTOR_HEIGHT_RANGE.end(),
+            );
+        }
+        if let Some(lines) = cfg.context_expand_lines {
+            app.context_expand_lines = lines;
+        }
+        if let Some(marker) = &cfg.wrap_marker {
+            app.wrap_marker = marker.clone();
+        }
+        if let Some(comment_type) = &cfg.default_comment_type {
+            app.preferred_comment_type = Some(comment_type.clone());
+        }
+        if let Some(template) = &cfg.status_line {
+            app.status_line = Some(template.clone());
+        }
+        if let Some(secs) = cfg.autosave_interval_secs {
+            app.autosave_interval = (secs > 0).then(|| Duration::from_secs(secs));
+        }
+        if let Some(author) = cfg
+            .author
+            .as_deref()
+            .filter(|author| !author.trim().is_empty())
+        {
+            app.comment_author = Some(author.trim().to_string());
+        }
+        if cfg.inline_images == Some(true) {
+            app.image_protocol =
+                ui::inline_image::detect_image_protocol(|key| std::env::var(key).ok());
+            app.rebuild_annotations();
+        }
+    }
+    if let Some(mode) = cli_args.diff_mode {
+        app.diff_view_mode = mode;
     }
 
     // On narrow terminals, start with only the diff panel visible.
@@ -230,23 +418,73 @@ fn main() -> anyhow::Result<()> {
         app.focused_panel = FocusedPanel::Diff;
     }
 
+    // Watch the repository for changes when --watch or `watch = true` is set
+    let watch_enabled = cli_args.watch
+        || config_outcome
+            .config
+            .as_ref()
+            .and_then(|cfg| cfg.watch)
+            .unwrap_or(false);
+    let watcher = if watch_enabled {
+        match watch::DiffWatcher::new(&app.vcs_info.root_path) {
+            Ok(watcher) => Some(watcher),
+            Err(e) => {
+                app.set_warning(format!("Watch mode disabled: {e}"));
+                None
+            }
+        }
+    } else {
+        None
+    };
+    // Reload requested by the watcher, deferred while a comment is being typed
+    let mut watch_reload_pending = false;
+    // Watch reload message and when it was shown, so it can be cleared
+    let mut watch_message: Option<(app::Message, Instant)> = None;
+
+    // Track pending g command for gg go-to-top
+    let mut pending_g = false;
     // Track pending z command for zz centering
     let mut pending_z = false;
     // Track pending Z command for ZZ export+quit / ZQ quit
     let mut pending_shift_z = false;
     // Track pending d command for dd delete
     let mut pending_d = false;
+    // Track pending y command for yy / yh / yc copy
+    let mut pending_y = false;
+    // Track pending m / ` commands for setting and jumping to marks
+    let mut pending_mark = false;
+    let mut pending_jump_to_mark = false;
     // Track pending ; command for ;e toggle file list
     let mut pending_semicolon = false;
+    // Track a `]`/`[` hunk jump for ]x / [x: (forward, cursor_line, scroll_offset) before it
+    let mut pending_bracket: Option<(bool, usize, usize)> = None;
     // Track pending Ctrl+C for "press twice to exit" (with timestamp for 2s timeout)
     let mut pending_ctrl_c: Option<Instant> = None;
 
+    // Image previews currently drawn over the diff
+    let mut inline_images = ui::inline_image::InlineImages::default();
+
     // Main loop
     loop {
+        app.apply_highlight_results();
+        app.load_commit_stats();
+
         // Render
-        terminal.draw(|frame| {
-            ui::render(frame, &mut app);
-        })?;
+        let frame_area = terminal
+            .draw(|frame| {
+                ui::render(frame, &mut app);
+            })?
+            .area;
+        if let Some(protocol) = app.image_protocol {
+            let placements = ui::inline_image::visible_placements(&app);
+            if inline_images.needs_repaint(protocol, frame_area, &placements) {
+                terminal.clear()?;
+                terminal.draw(|frame| {
+                    ui::render(frame, &mut app);
+                })?;
+            }
+            inline_images.draw(terminal.backend_mut(), protocol, frame_area, placements)?;
+        }
 
         // Check for update result (non-blocking)
         if let Some(ref rx) = update_rx
@@ -266,6 +504,32 @@ fn main() -> anyhow::Result<()> {
             app.message = None;
         }
 
+        if let Some(ref watcher) = watcher
+            && watcher.has_changes()
+        {
+            watch_reload_pending = true;
+        }
+        if watch_reload_pending && app.input_mode != InputMode::Comment {
+            watch_reload_pending = false;
+            match app.reload_changed_files() {
+                Ok(0) => {}
+                Ok(changed) => {
+                    let noun = if changed == 1 { "file" } else { "files" };
+                    app.set_message(format!("Diff reloaded ({changed} {noun} changed)"));
+                    watch_message = app.message.clone().map(|msg| (msg, Instant::now()));
+                }
+                Err(e) => app.set_warning(format!("Reload failed: {e}")),
+            }
+        }
+        if let Some((ref msg, shown_at)) = watch_message
+            && shown_at.elapsed() >= WATCH_MESSAGE_TIMEOUT
+        {
+            if app.message.as_ref() == Some(msg) {
+                app.message = None;
+            }
+            watch_message = None;
+        }
+
         // Handle events
         if event::poll(Duration::from_millis(100))? {
             let event = event::read()?;
@@ -302,12 +566,94 @@ fn main() -> anyhow::Result<()> {
                         app.message = None;
                     }
 
-                    // Handle pending z command for zz centering
+                    // The stats popup closes on any key, which is otherwise ignored
+                    if app.stats_popup.take().is_some() {
+                        continue;
+                    }
+
+                    // The expanded blame popup scrolls until closed; the one-line
+                    // summary closes on any key except `gb`, which expands it
+                    if let Some(popup) = &app.blame_popup {
+                        if popup.expanded {
+                            let action = map_key_to_action(key, InputMode::Help);
+                            handler::handle_blame_action(&mut app, action);
+                            continue;
+                        }
+                        let keep = if pending_g { 'b' } else { 'g' };
+                        if key.code != crossterm::event::KeyCode::Char(keep) {
+                            app.blame_popup = None;
+                            if key.code == crossterm::event::KeyCode::Esc {
+                                continue;
+                            }
+                        }
+                    }
+
+                    // Handle pending g command for gg go-to-top / gy copy selection with markers /
+                    // gm jump to the other side of a moved block / gt cycle comment type /
+                    // gb blame
+                    if pending_g {
+                        pending_g = false;
+                        if key.code == crossterm::event::KeyCode::Char('g') {
+                            app.jump_to_top();
+                            continue;
+                        }
+                        if key.code == crossterm::event::KeyCode::Char('y')
+                            && app.input_mode == InputMode::VisualSelect
+                        {
+                            handler::handle_yank_selection(&mut app, true);
+                            continue;
+                        }
+                        if key.code == crossterm::event::KeyCode::Char('m')
+                            && app.input_mode == InputMode::Normal
+                        {
+                            app.jump_to_moved_counterpart();
+                            continue;
+                        }
+                        if key.code == crossterm::event::KeyCode::Char('t')
+                            && app.input_mode == InputMode::Normal
+                        {
+                            app.cycle_comment_type_at_cursor();
+                            continue;
+                        }
+                        if key.code == crossterm::event::KeyCode::Char('b')
+                            && app.input_mode == InputMode::Normal
+                        {
+                            dispatch_action(&mut app, Action::ShowBlame);
+                            continue;
+                        }
+                        // Otherwise fall through to normal handling
+                    }
+
+                    // Handle pending z command for zz/zt/zb scrolling and
+                    // zM/zR to collapse/expand all directories
                     if pending_z {
                         pending_z = false;
-                        if key.code == crossterm::event::KeyCode::Char('z') {
-                            app.center_cursor();
-                            continue;
+                        match key.code {
+                            crossterm::event::KeyCode::Char('M') => {
+                                dispatch_action(&mut app, Action::CollapseAll);
+                                continue;
+                            }
+                            crossterm::event::KeyCode::Char('R') => {
+                                dispatch_action(&mut app, Action::ExpandAll);
+                                continue;
+                            }
+                            crossterm::event::KeyCode::Char('c') => {
+                                dispatch_action(&mut app, Action::CollapseGap);
+                                continue;
+                            }
+                            crossterm::event::KeyCode::Char('z') => {
+                                app.center_cursor();
+                                continue;
+                            }
+                            crossterm::event::KeyCode::Char('t') => {
+                                app.scroll_cursor_to_top();
+                                continue;
+                            }
+                            crossterm::event::KeyCode::Char('b') => {
+                                app.scroll_cursor_to_bottom();
+                                continue;
+                            }
+                            _ => {}
                         }
                         // Otherwise fall through to normal handling
                     }
@@ -348,6 +694,93 @@ fn main() -> anyhow::Result<()> {
                         // Otherwise fall through to normal handling
                     }
 
+                    // Handle pending y command for yy (copy line) / yh (copy hunk) / yc (copy comment)
+                    // / yr (copy file:line) / yp (copy permalink)
+                    if pending_y {
+                        pending_y = false;
+                        match key.code {
+                            crossterm::event::KeyCode::Char('y') => {
+                                handler::handle_yank_line(&mut app);
+                                continue;
+                            }
+                            crossterm::event::KeyCode::Char('h') => {
+                                handler::handle_yank_hunk(&mut app);
+                                continue;
+                            }
+                            crossterm::event::KeyCode::Char('c') => {
+                                handler::handle_yank_comment(&mut app);
+                                continue;
+                            }
+                            crossterm::event::KeyCode::Char('r') => {
+                                handler::handle_yank_line_ref(&mut app, false);
+                                continue;
+                            }
+                            crossterm::event::KeyCode::Char('p') => {
+                                handler::handle_yank_line_ref(&mut app, true);
+                                continue;
+                            }
+                            _ => {} // Fall through to normal handling
+                        }
+                    }
+
+                    // Handle m{letter} to set a mark and `{letter} to jump back to it
+                    if pending_mark || pending_jump_to_mark {
+                        let jump = pending_jump_to_mark;
+                        pending_mark = false;
+                        pending_jump_to_mark = false;
+                        if let crossterm::event::KeyCode::Char(c) = key.code
+                            && c.is_ascii_alphabetic()
+                        {
+                            if jump {
+                                app.jump_to_mark(c);
+                            } else {
+                                app.set_mark(c);
+                            }
+                            continue;
+                        }
+                        // Otherwise fall through to normal handling
+                    }
+
+                    // Handle ]x / [x conflicted file jumps, ]q / [q comment jumps and the
+                    // ]c / ]f / ]u motions: `]`/`[` already moved to the next hunk, so undo
+                    // that before jumping
+                    if let Some((forward, cursor_line, scroll_offset)) = pending_bracket.take()
+                        && matches!(
+                            key.code,
+                            crossterm::event::KeyCode::Char('x' | 'q' | 'c' | 'f' | 'u')
+                        )
+                        && app.input_mode == InputMode::Normal
+                    {
+                        app.diff_state.cursor_line = cursor_line;
+                        app.diff_state.scroll_offset = scroll_offset;
+                        app.update_current_file_from_cursor();
+                        match key.code {
+                            crossterm::event::KeyCode::Char('x') => {
+                                if !app.jump_to_conflicted_file(forward) {
+                                    app.set_message("No conflicted files");
+                                }
+                            }
+                            crossterm::event::KeyCode::Char('q') => {
+                                if !app.jump_to_comment(forward) {
+                                    app.set_message("No comments");
+                                }
+                            }
+                            crossterm::event::KeyCode::Char('c') => {
+                                app.jump_bracket_motion(app::BracketMotion::Comment, forward);
+                            }
+                            crossterm::event::KeyCode::Char('f') => {
+                                app.jump_bracket_motion(app::BracketMotion::File, forward);
+                            }
+                            _ => {
+                                app.jump_bracket_motion(
+                                    app::BracketMotion::UnreviewedFile,
+                                    forward,
+                                );
+                            }
+                        }
+                        continue;
+                    }
+
                     // Handle pending ; command for panel focus, file list toggle, and review comments
                     if pending_semicolon {
                         pending_semicolon = false;
@@ -378,15 +811,36 @@ fn main() -> anyhow::Result<()> {
                                 app.enter_review_comment_mode();
                                 continue;
                             }
+                            crossterm::event::KeyCode::Char('>') => {
+                                app.resize_file_list(true);
+                                continue;
+                            }
+                            crossterm::event::KeyCode::Char('<') => {
+                                app.resize_file_list(false);
+                                continue;
+                            }
+                            crossterm::event::KeyCode::Char('+') => {
+                                app.resize_commit_selector(true);
+                                continue;
+                            }
+                            crossterm::event::KeyCode::Char('-') => {
+                                app.resize_commit_selector(false);
+                                continue;
+                            }
                             _ => {}
                         }
                         // Otherwise fall through to normal handling
                     }
 
-                    let action = map_key_to_action(key, app.input_mode);
+                    let mut action = map_key_to_action(key, app.input_mode);
 
                     // Handle pending command setters (these work in any mode)
                     match action {
+                        Action::PendingGCommand => {
+                            pending_g = true;
+                            app.pending_count = None;
+                            continue;
+                        }
                         Action::PendingZCommand => {
                             pending_z = true;
                             app.pending_count = None;
@@ -397,6 +851,15 @@ fn main() -> anyhow::Result<()> {
                             app.pending_count = None;
                             continue;
                         }
+                        // `d` deletes the selected comment in the `:comments` list
+                        Action::PendingDCommand
+                            if app.input_mode == InputMode::Normal
+                                && app.focused_panel == FocusedPanel::CommentList =>
+                        {
+                            app.request_delete_listed_comment();
+                            app.pending_count = None;
+                            continue;
+                        }
                         Action::PendingDCommand => {
                             pending_d = true;
                             app.pending_count = None;
@@ -407,12 +870,31 @@ fn main() -> anyhow::Result<()> {
                             app.pending_count = None;
                             continue;
                         }
+                        Action::PendingYCommand => {
+                            pending_y = true;
+                            app.pending_count = None;
+                            continue;
+                        }
+                        Action::PendingMarkCommand if app.input_mode == InputMode::Normal => {
+                            pending_mark = true;
+                            app.pending_count = None;
+                            continue;
+                        }
+                        Action::PendingJumpToMarkCommand if app.input_mode == InputMode::Normal => {
+                            pending_jump_to_mark = true;
+                            app.pending_count = None;
+                            continue;
+                        }
                         _ => {}
                     }
 
-                    // Handle digit accumulation for {N}G jump-to-line (Normal mode only)
+                    // Handle count prefixes: {N}G jumps to a line, {N}j etc. repeat motions
+                    // (Normal mode only)
+                    let mut repeat = 1;
                     if app.input_mode == InputMode::Normal {
                         match action {
+                            // `0` alone is unused; it only extends a count
+                            Action::Digit(0) if app.pending_count.is_none() => continue,
                             Action::Digit(d) => {
                                 let n = app.pending_count.unwrap_or(0);
                                 app.pending_count = Some(
@@ -429,37 +911,52 @@ fn main() -> anyhow::Result<()> {
                                 continue;
                             }
                             _ => {
-                                app.pending_count = None;
+                                if let Some(count) = app.pending_count.take() {
+                                    (action, repeat) = apply_count(action, count);
+                                }
                             }
                         }
                     }
 
-                    // Dispatch by input mode
-                    match app.input_mode {
-                        InputMode::Help => handle_help_action(&mut app, action),
-                        InputMode::Command => handle_command_action(&mut app, action),
-                        InputMode::Search => handle_search_action(&mut app, action),
-                        InputMode::Comment => handle_comment_action(&mut app, action),
-                        InputMode::Confirm => handle_confirm_action(&mut app, action),
-                        InputMode::CommitSelect => handle_commit_select_action(&mut app, action),
-                        InputMode::VisualSelect => handle_visual_action(&mut app, action),
-                        InputMode::Normal => match app.focused_panel {
-                            FocusedPanel::FileList => handle_file_list_action(&mut app, action),
-                            FocusedPanel::Diff => handle_diff_action(&mut app, action),
-                            FocusedPanel::CommitSelector => {
-                                handle_commit_selector_action(&mut app, action)
-                            }
-                        },
+                    if app.input_mode == InputMode::Normal
+                        && matches!(action, Action::NextHunk | Action::PrevHunk)
+                    {
+                        pending_bracket = Some((
+                            action == Action::NextHunk,
+                            app.diff_state.cursor_line,
+                            app.diff_state.scroll_offset,
+                        ));
+                    }
+
+                    for _ in 1..repeat {
+                        dispatch_action(&mut app, action.clone());
                     }
+                    dispatch_action(&mut app, action);
                 }
                 Event::Mouse(mouse_event) => handle_mouse_event(&mut app, mouse_event),
                 _ => {}
             }
         }
 
-        if app.should_quit {
+        if let Some((path, line)) = app.pending_editor.take() {
+            let flags = TerminalFlags {
+                mouse: mouse_enabled,
+                keyboard_enhancement: keyboard_enhancement_supported,
+                tty_only: cli_args.output_to_stdout,
+            };
+            let result = run_editor(&mut terminal, flags, &path, line);
+            if let Err(e) = &result {
+                app.set_error(format!("Failed to run editor: {e}"));
+            }
+            app.finish_edit_commit_message(result.is_ok());
+            app.finish_edit_comment(result.is_ok());
+        }
+
+        if app.should_quit && app.finish_quit() {
             break;
         }
+
+        app.autosave_if_due();
     }
 
     // Restore terminal
@@ -477,3 +974,24 @@ fn main() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Route an action to the handler for the current input mode and focused panel.
+fn dispatch_action(app: &mut App, action: Action) {
+    match app.input_mode {
+        InputMode::Help => handle_help_action(app, action),
+        InputMode::Command => handle_command_action(app, action),
+        InputMode::Search => handle_search_action(app, action),
+        InputMode::Comment => handle_comment_action(app, action),
+        InputMode::Confirm => handle_confirm_action(app, action),
+        InputMode::CommitSelect => handle_commit_select_action(app, action),
+        InputMode::VisualSelect => handle_visual_action(app, action),
+        InputMode::SessionBrowser => handle_session_browser_action(app, action),
+        InputMode::BranchPicker => handle_branch_picker_action(app, action),
+        InputMode::Normal => match app.focused_panel {
+            FocusedPanel::FileList => handle_file_list_action(app, action),
+            FocusedPanel::Diff => handle_diff_action(app, action),
+            FocusedPanel::CommitSelector => handle_commit_selector_action(app, action),
+            FocusedPanel::CommentList => handle_comment_list_action(app, action),
+        },
+    }
+}
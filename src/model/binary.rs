@@ -0,0 +1,172 @@
+//! Size and image metadata for binary files, which have no hunks to show.
+
+/// Byte sizes and, for recognized images, pixel dimensions of both sides of
+/// a binary file change. `None` on a side that doesn't exist or couldn't be read.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BinaryMeta {
+    pub old_size: Option<u64>,
+    pub new_size: Option<u64>,
+    pub old_dimensions: Option<(u32, u32)>,
+    pub new_dimensions: Option<(u32, u32)>,
+}
+
+impl BinaryMeta {
+    pub fn from_contents(old: Option<&[u8]>, new: Option<&[u8]>) -> Self {
+        Self {
+            old_size: old.map(|bytes| bytes.len() as u64),
+            new_size: new.map(|bytes| bytes.len() as u64),
+            old_dimensions: old.and_then(image_dimensions),
+            new_dimensions: new.and_then(image_dimensions),
+        }
+    }
+
+    /// Summary such as `binary, 1024×768 → 800×600, 12.0 KiB → 15.1 KiB (+3.1 KiB)`.
+    pub fn describe(&self) -> String {
+        let mut parts = vec!["binary".to_string()];
+
+        let dimensions = |(w, h): (u32, u32)| format!("{w}×{h}");
+        match (self.old_dimensions, self.new_dimensions) {
+            (Some(old), Some(new)) if old != new => {
+                parts.push(format!("{} → {}", dimensions(old), dimensions(new)));
+            }
+            (_, Some(dims)) | (Some(dims), None) => parts.push(dimensions(dims)),
+            (None, None) => {}
+        }
+
+        match (self.old_size, self.new_size) {
+            (Some(old), Some(new)) => parts.push(format!(
+                "{} → {} ({})",
+                format_size(old),
+                format_size(new),
+                format_size_delta(old, new)
+            )),
+            (_, Some(size)) | (Some(size), None) => parts.push(format_size(size)),
+            (None, None) => {}
+        }
+        parts.join(", ")
+    }
+}
+
+/// Human-readable byte count using binary units, e.g. `512 B`, `14.2 KiB`.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+/// Signed size change, e.g. `+3.1 KiB` or `-512 B`.
+pub fn format_size_delta(old: u64, new: u64) -> String {
+    if new >= old {
+        format!("+{}", format_size(new - old))
+    } else {
+        format!("-{}", format_size(old - new))
+    }
+}
+
+/// Pixel dimensions of PNG, GIF and JPEG images, read from their headers.
+pub fn image_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        // The IHDR chunk always comes first: length, type, width, height
+        if bytes.get(12..16)? != b"IHDR" {
+            return None;
+        }
+        return Some((be_u32(bytes, 16)?, be_u32(bytes, 20)?));
+    }
+    if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        let width = u16::from_le_bytes([*bytes.get(6)?, *bytes.get(7)?]);
+        let height = u16::from_le_bytes([*bytes.get(8)?, *bytes.get(9)?]);
+        return Some((width.into(), height.into()));
+    }
+    if bytes.starts_with(&[0xFF, 0xD8]) {
+        return jpeg_dimensions(bytes);
+    }
+    None
+}
+
+fn be_u16(bytes: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(bytes.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn be_u32(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+/// Walk the JPEG segments up to the first start-of-frame marker.
+fn jpeg_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    let mut pos = 2;
+    loop {
+        if *bytes.get(pos)? != 0xFF {
+            return None;
+        }
+        let marker = *bytes.get(pos + 1)?;
+        match marker {
+            // Fill byte before a marker
+            0xFF => pos += 1,
+            // Markers without a length field
+            0x01 | 0xD0..=0xD7 => pos += 2,
+            // SOF0-SOF15, except DHT, JPG and DAC which share the range
+            0xC0..=0xCF if !matches!(marker, 0xC4 | 0xC8 | 0xCC) => {
+                let height = be_u16(bytes, pos + 5)?;
+                let width = be_u16(bytes, pos + 7)?;
+                return Some((width.into(), height.into()));
+            }
+            _ => pos += 2 + usize::from(be_u16(bytes, pos + 2)?),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut bytes = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR".to_vec();
+        bytes.extend(width.to_be_bytes());
+        bytes.extend(height.to_be_bytes());
+        bytes
+    }
+
+    #[test]
+    fn should_read_image_dimensions_from_headers() {
+        assert_eq!(image_dimensions(&png(1024, 768)), Some((1024, 768)));
+        assert_eq!(
+            image_dimensions(b"GIF89a\x20\x03\x58\x02rest"),
+            Some((800, 600))
+        );
+
+        // SOI, an APP0 segment, then SOF0 with height 600 and width 800
+        let jpeg = [
+            0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x04, 0x00, 0x00, 0xFF, 0xC0, 0x00, 0x11, 0x08, 0x02,
+            0x58, 0x03, 0x20,
+        ];
+        assert_eq!(image_dimensions(&jpeg), Some((800, 600)));
+
+        assert_eq!(image_dimensions(b"\x00\x01\x02 not an image"), None);
+        assert_eq!(image_dimensions(&png(1, 1)[..18]), None);
+    }
+
+    #[test]
+    fn should_describe_sizes_and_dimensions() {
+        let old = png(1024, 768);
+        let mut new = png(800, 600);
+        new.resize(3200, 0);
+
+        assert_eq!(
+            BinaryMeta::from_contents(Some(&old), Some(&new)).describe(),
+            "binary, 1024×768 → 800×600, 24 B → 3.1 KiB (+3.1 KiB)"
+        );
+        assert_eq!(
+            BinaryMeta::from_contents(None, Some(&[0; 2048])).describe(),
+            "binary, 2.0 KiB"
+        );
+        assert_eq!(format_size_delta(2048, 1024), "-1.0 KiB");
+    }
+}
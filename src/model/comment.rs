@@ -109,12 +109,73 @@ pub struct LineContext {
     pub content: String,
 }
 
+/// The text of a commented line and its neighbours, used to find the line
+/// again after the file changes underneath the comment
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineFingerprint {
+    /// Trimmed line above; None at the start of the file or when unknown
+    pub above: Option<String>,
+    pub line: String,
+    /// Trimmed line below; None at the end of the file or when unknown
+    pub below: Option<String>,
+}
+
+impl LineFingerprint {
+    /// Fingerprint of the 1-based `line` in `lines`
+    pub fn of(lines: &[&str], line: u32) -> Option<Self> {
+        let idx = (line as usize).checked_sub(1)?;
+        let text = lines.get(idx)?;
+        Some(Self {
+            above: idx
+                .checked_sub(1)
+                .and_then(|above| lines.get(above))
+                .map(|text| text.trim().to_string()),
+            line: text.trim().to_string(),
+            below: lines.get(idx + 1).map(|text| text.trim().to_string()),
+        })
+    }
+
+    /// Whether the 1-based `line` in `lines` still reads the same. Unknown
+    /// neighbours match anything.
+    pub fn matches(&self, lines: &[&str], line: u32) -> bool {
+        let Some(idx) = (line as usize).checked_sub(1) else {
+            return false;
+        };
+        let same = |expected: &Option<String>, idx: Option<usize>| match expected {
+            None => true,
+            Some(expected) => idx
+                .and_then(|idx| lines.get(idx))
+                .is_some_and(|text| text.trim() == expected),
+        };
+        lines.get(idx).is_some_and(|text| text.trim() == self.line)
+            && same(&self.above, idx.checked_sub(1))
+            && same(&self.below, Some(idx + 1))
+    }
+
+    /// Where the line went in `lines`, looking within `window` lines of
+    /// `line` first and then through the whole file. None unless exactly
+    /// one line matches.
+    pub fn locate(&self, lines: &[&str], line: u32, window: u32) -> Option<u32> {
+        let unique = |candidates: &mut dyn Iterator<Item = u32>| {
+            let mut matches = candidates.filter(|&candidate| self.matches(lines, candidate));
+            let first = matches.next()?;
+            matches.next().is_none().then_some(first)
+        };
+        let last = lines.len() as u32;
+        unique(&mut (line.saturating_sub(window).max(1)..=line.saturating_add(window).min(last)))
+            .or_else(|| unique(&mut (1..=last)))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Comment {
     pub id: String,
     pub content: String,
     pub comment_type: CommentType,
     pub created_at: DateTime<Utc>,
+    /// When the content or type was last edited; None if never edited
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
     pub line_context: Option<LineContext>,
     /// Which side of the diff this comment belongs to (for line comments)
     /// None for file-level comments, defaults to New for backward compatibility
@@ -124,6 +185,24 @@ pub struct Comment {
     /// None for file-level comments or single-line comments (backward compatibility)
     #[serde(default)]
     pub line_range: Option<LineRange>,
+    /// Set once a suggestion from this comment has been applied
+    #[serde(default)]
+    pub resolved: bool,
+    /// Id of the comment this one replies to. Replies always point at the
+    /// thread's root comment and are stored right after it.
+    #[serde(default)]
+    pub parent_id: Option<String>,
+    /// Who wrote the comment; None for comments from before authors were recorded
+    #[serde(default)]
+    pub author: Option<String>,
+    /// The commented line as it read when the comment was written (for
+    /// line comments on the new side)
+    #[serde(default)]
+    pub fingerprint: Option<LineFingerprint>,
+    /// Set when the commented line could not be found after the file
+    /// changed; the comment is then kept as a detached file comment
+    #[serde(default)]
+    pub orphaned: bool,
 }
 
 impl Comment {
@@ -133,9 +212,15 @@ impl Comment {
             content,
             comment_type,
             created_at: Utc::now(),
+            updated_at: None,
             line_context: None,
             side,
             line_range: None,
+            resolved: false,
+            parent_id: None,
+            author: None,
+            fingerprint: None,
+            orphaned: false,
         }
     }
 
@@ -151,13 +236,34 @@ impl Comment {
             content,
             comment_type,
             created_at: Utc::now(),
+            updated_at: None,
             line_context: None,
             side,
             line_range: Some(line_range),
+            resolved: false,
+            parent_id: None,
+            author: None,
+            fingerprint: None,
+            orphaned: false,
         }
     }
 }
 
+/// Add a comment to a list, keeping replies directly after their thread.
+///
+/// Comments without a parent (or whose parent is not in the list) are appended.
+pub fn insert_threaded(comments: &mut Vec<Comment>, comment: Comment) {
+    let thread_end = comment.parent_id.as_deref().and_then(|parent| {
+        comments
+            .iter()
+            .rposition(|c| c.id == parent || c.parent_id.as_deref() == Some(parent))
+    });
+    match thread_end {
+        Some(idx) => comments.insert(idx + 1, comment),
+        None => comments.push(comment),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -300,6 +406,23 @@ mod tests {
             assert_eq!(comment.side, Some(LineSide::Old));
         }
 
+        #[test]
+        fn fingerprint_matches_line_and_known_neighbours() {
+            let lines = ["fn a() {", "    body();", "}"];
+            let fingerprint = LineFingerprint::of(&lines, 1).unwrap();
+            assert_eq!(fingerprint.above, None);
+            assert_eq!(fingerprint.below.as_deref(), Some("body();"));
+
+            assert!(fingerprint.matches(&["", "  fn a() {", "body();"], 2));
+            assert!(!fingerprint.matches(&["fn a() {", "other();"], 1));
+            assert_eq!(
+                fingerprint.locate(&["x", "fn a() {", "body();"], 1, 20),
+                Some(2)
+            );
+            assert_eq!(LineFingerprint::of(&lines, 0), None);
+            assert_eq!(LineFingerprint::of(&lines, 4), None);
+        }
+
         #[test]
         fn comment_with_line_range_serializes_correctly() {
             let range = LineRange::new(10, 15);
@@ -331,6 +454,19 @@ mod tests {
             assert_eq!(comment.content, "Test comment");
         }
 
+        #[test]
+        fn comment_without_updated_at_deserializes_as_unedited() {
+            let json = r#"{
+                "id": "test-id",
+                "content": "Test comment",
+                "comment_type": "note",
+                "created_at": "2024-01-01T00:00:00Z",
+                "line_context": null
+            }"#;
+            let comment: Comment = serde_json::from_str(json).unwrap();
+            assert!(comment.updated_at.is_none());
+        }
+
         #[test]
         fn comment_with_line_range_deserializes_correctly() {
             let json = r#"{
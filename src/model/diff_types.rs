@@ -2,6 +2,7 @@ use ratatui::style::Style;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
+use super::binary::BinaryMeta;
 use crate::hash::Fnv1aHasher;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -42,6 +43,35 @@ pub struct DiffLine {
     /// Optional syntax-highlighted spans for this line
     /// If None, use the default diff coloring
     pub highlighted_spans: Option<Vec<(Style, String)>>,
+    /// Addition or deletion whose counterpart in the same change block differs
+    /// only in whitespace
+    pub whitespace_only: bool,
+    /// Shared by the deleted and added lines of a block moved elsewhere in
+    /// the diff (`git diff --color-moved`)
+    pub move_id: Option<u32>,
+}
+
+impl DiffLine {
+    /// The line as it appears in a unified diff, with its `+`/`-`/` ` marker.
+    pub fn to_patch_line(&self) -> String {
+        let marker = match self.origin {
+            LineOrigin::Addition => '+',
+            LineOrigin::Deletion => '-',
+            LineOrigin::Context => ' ',
+        };
+        format!("{marker}{}", self.content)
+    }
+    /// Whether the line is a merge conflict marker (`<<<<<<<`, `|||||||`,
+    /// `=======` or `>>>>>>>`).
+    pub fn is_conflict_marker(&self) -> bool {
+        let content = self.content.as_str();
+        content == "======="
+            || ["<<<<<<<", "|||||||", ">>>>>>>"].iter().any(|marker| {
+                content
+                    .strip_prefix(marker)
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with(' '))
+            })
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -49,10 +79,8 @@ pub struct DiffHunk {
     pub header: String,
     pub lines: Vec<DiffLine>,
     /// Starting line number in the old file (from @@ header)
-    #[allow(dead_code)]
     pub old_start: u32,
     /// Number of lines from the old file in this hunk
-    #[allow(dead_code)]
     pub old_count: u32,
     /// Starting line number in the new file (from @@ header)
     pub new_start: u32,
@@ -60,6 +88,44 @@ pub struct DiffHunk {
     pub new_count: u32,
 }
 
+impl DiffHunk {
+    /// Flag deletion/addition pairs that differ only in whitespace. A block
+    /// of deletions followed by an equally long block of additions is paired
+    /// line by line.
+    pub fn mark_whitespace_only_changes(&mut self) {
+        let squash = |s: &str| s.split_whitespace().collect::<String>();
+        let lines = &mut self.lines;
+        let mut i = 0;
+        while i < lines.len() {
+            if lines[i].origin != LineOrigin::Deletion {
+                i += 1;
+                continue;
+            }
+            let del_start = i;
+            while i < lines.len() && lines[i].origin == LineOrigin::Deletion {
+                i += 1;
+            }
+            let add_start = i;
+            while i < lines.len() && lines[i].origin == LineOrigin::Addition {
+                i += 1;
+            }
+            let count = add_start - del_start;
+            if i - add_start != count {
+                continue;
+            }
+            for offset in 0..count {
+                let (del, add) = (del_start + offset, add_start + offset);
+                if lines[del].content != lines[add].content
+                    && squash(&lines[del].content) == squash(&lines[add].content)
+                {
+                    lines[del].whitespace_only = true;
+                    lines[add].whitespace_only = true;
+                }
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct DiffFile {
     pub old_path: Option<PathBuf>,
@@ -67,9 +133,30 @@ pub struct DiffFile {
     pub status: FileStatus,
     pub hunks: Vec<DiffHunk>,
     pub is_binary: bool,
+    /// Sizes and image dimensions of a binary file, when the backend could read them
+    pub binary_meta: Option<BinaryMeta>,
     pub is_too_large: bool,
+    /// Diff lines past the `max_file_lines` limit that are not loaded yet
+    pub truncated_lines: usize,
     pub is_commit_message: bool,
+    /// Gitlink entry; the single hunk describes the submodule pointer change
+    pub is_submodule: bool,
+    /// Unix file modes (e.g. `0o100644`) when known on that side
+    pub old_mode: Option<u32>,
+    pub new_mode: Option<u32>,
+    /// Either side is a symlink; hunk lines hold the link targets
+    pub is_symlink: bool,
+    /// Unmerged in the index (git) or conflicted in the working copy (jj)
+    pub is_conflicted: bool,
+    /// The new side still contains `<<<<<<<`/`>>>>>>>` conflict markers
+    pub has_conflicts: bool,
     pub content_hash: u64,
+    /// Syntax highlighting has been applied; it runs in the background once
+    /// the file first scrolls into view
+    pub highlighted: bool,
+    /// Marked `linguist-generated` or `-diff` in `.gitattributes`; shown
+    /// collapsed until expanded
+    pub is_generated: bool,
 }
 
 impl DiffFile {
@@ -90,6 +177,65 @@ impl DiffFile {
         hasher.finish()
     }
 
+    /// Whether the new side of the hunks still contains a conflict region,
+    /// i.e. both a `<<<<<<<` and a `>>>>>>>` marker line.
+    pub fn contains_conflict_markers(hunks: &[DiffHunk]) -> bool {
+        let mut markers = hunks
+            .iter()
+            .flat_map(|h| &h.lines)
+            .filter(|l| l.origin != LineOrigin::Deletion && l.is_conflict_marker());
+        // `any` resumes where it stopped, so the end marker must follow the start
+        markers.any(|l| l.content.starts_with("<<<<<<<"))
+            && markers.any(|l| l.content.starts_with(">>>>>>>"))
+    }
+
+    /// Keep only the first `max_lines` diff lines, counting the dropped ones in
+    /// `truncated_lines`. A hunk cut short is shrunk to the lines it keeps.
+    pub fn truncate_lines(&mut self, max_lines: usize) {
+        let mut remaining = max_lines;
+        let Some(cut) = self.hunks.iter().position(|hunk| {
+            let fits = hunk.lines.len() <= remaining;
+            if fits {
+                remaining -= hunk.lines.len();
+            }
+            !fits
+        }) else {
+            return;
+        };
+
+        let dropped = self.hunks.split_off(cut + 1);
+        self.truncated_lines += dropped.iter().map(|h| h.lines.len()).sum::<usize>();
+        let hunk = &mut self.hunks[cut];
+        self.truncated_lines += hunk.lines.len() - remaining;
+        hunk.lines.truncate(remaining);
+        if hunk.lines.is_empty() {
+            self.hunks.pop();
+        } else {
+            hunk.old_count = hunk.lines.iter().filter(|l| l.old_lineno.is_some()).count() as u32;
+            hunk.new_count = hunk.lines.iter().filter(|l| l.new_lineno.is_some()).count() as u32;
+        }
+        self.content_hash = Self::compute_content_hash(&self.hunks);
+    }
+
+    /// Expander text for the lines past `max_file_lines`, e.g.
+    /// `… 4,312 more lines (press Enter to load)`.
+    pub fn truncated_descriptor(&self) -> String {
+        let digits = self.truncated_lines.to_string();
+        let mut count = String::new();
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                count.push(',');
+            }
+            count.push(digit);
+        }
+        format!("… {count} more lines (press Enter to load)")
+    }
+
+    /// Unmerged according to the VCS, or still containing conflict markers.
+    pub fn has_unresolved_conflicts(&self) -> bool {
+        self.is_conflicted || self.has_conflicts
+    }
+
     pub fn display_path(&self) -> &PathBuf {
         self.new_path
             .as_ref()
@@ -97,6 +243,45 @@ impl DiffFile {
             .expect("DiffFile must have at least one path")
     }
 
+    /// Body line of a binary file, e.g. "(binary, 1024×768 → 800×600, …)".
+    pub fn binary_descriptor(&self) -> String {
+        match &self.binary_meta {
+            Some(meta) => format!("({})", meta.describe()),
+            None => "(binary file)".to_string(),
+        }
+    }
+
+    /// One-line description of a mode change or symlink retarget, shown in
+    /// the file body, e.g. "mode changed 644 → 755" or "symlink: old → new".
+    pub fn metadata_descriptor(&self) -> Option<String> {
+        if self.is_symlink {
+            let target = |origin: LineOrigin| {
+                self.hunks
+                    .iter()
+                    .flat_map(|h| &h.lines)
+                    .find(|l| l.origin == origin)
+                    .map(|l| l.content.as_str())
+            };
+            return Some(
+                match (target(LineOrigin::Deletion), target(LineOrigin::Addition)) {
+                    (Some(old), Some(new)) => format!("symlink: {old} → {new}"),
+                    (None, Some(new)) => format!("symlink → {new}"),
+                    (Some(old), None) => format!("symlink: {old} (removed)"),
+                    (None, None) => "symlink".to_string(),
+                },
+            );
+        }
+
+        match (self.old_mode, self.new_mode) {
+            (Some(old), Some(new)) if old != new => Some(format!(
+                "mode changed {:o} → {:o}",
+                old & 0o777,
+                new & 0o777
+            )),
+            _ => None,
+        }
+    }
+
     /// Returns `(additions, deletions)` for this file.
     pub fn stat(&self) -> (usize, usize) {
         let mut additions = 0;
@@ -1,7 +1,12 @@
+pub mod binary;
 pub mod comment;
 pub mod diff_types;
+pub mod moved;
+pub mod pairing;
 pub mod review;
 
-pub use comment::{Comment, CommentType, LineRange, LineSide};
+pub use binary::BinaryMeta;
+pub use comment::{Comment, CommentType, LineFingerprint, LineRange, LineSide};
 pub use diff_types::{DiffFile, DiffHunk, DiffLine, FileStatus, LineOrigin};
-pub use review::{ClearScope, ReviewSession, SessionDiffSource};
+pub use moved::detect_moved_blocks;
+pub use review::{ClearScope, HunkKey, ReviewSession, SessionDiffSource};
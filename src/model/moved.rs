@@ -0,0 +1,252 @@
+//! Detect blocks of lines moved within or between files, like
+//! `git diff --color-moved`.
+
+use std::collections::HashMap;
+
+use super::{DiffFile, LineOrigin};
+
+/// Minimum number of consecutive lines for a deleted block to count as moved.
+const MIN_MOVED_LINES: usize = 3;
+/// Minimum number of alphanumeric characters in a moved block, so runs of
+/// closing braces or blank lines aren't reported (same limit as git).
+const MIN_MOVED_ALNUM: usize = 20;
+
+/// Position of a line as (file, hunk, line) indices.
+type LinePos = (usize, usize, usize);
+
+/// A run of consecutive added or deleted lines with their matching keys.
+struct Run {
+    lines: Vec<LinePos>,
+    keys: Vec<String>,
+}
+
+/// Whitespace-insensitive matching key of a line.
+fn line_key(content: &str) -> String {
+    content.split_whitespace().collect()
+}
+
+fn collect_runs(files: &[DiffFile], origin: LineOrigin) -> Vec<Run> {
+    let mut runs = Vec::new();
+    for (file_idx, file) in files.iter().enumerate() {
+        if file.is_commit_message || file.is_submodule {
+            continue;
+        }
+        for (hunk_idx, hunk) in file.hunks.iter().enumerate() {
+            let mut lines = Vec::new();
+            for (line_idx, line) in hunk.lines.iter().enumerate() {
+                if line.origin == origin {
+                    lines.push((file_idx, hunk_idx, line_idx));
+                } else if !lines.is_empty() {
+                    runs.push(std::mem::take(&mut lines));
+                }
+            }
+            if !lines.is_empty() {
+                runs.push(lines);
+            }
+        }
+    }
+
+    runs.into_iter()
+        .map(|lines| {
+            let keys = lines
+                .iter()
+                .map(|&(f, h, l)| line_key(&files[f].hunks[h].lines[l].content))
+                .collect();
+            Run { lines, keys }
+        })
+        .collect()
+}
+
+/// Whether the addition run directly replaces the deletion run, i.e. both
+/// belong to the same change block. Such pairs are edits, not moves.
+fn is_same_change_block(deleted: &Run, added: &Run) -> bool {
+    let (Some(&(df, dh, dl)), Some(&(af, ah, al))) = (deleted.lines.last(), added.lines.first())
+    else {
+        return false;
+    };
+    df == af && dh == ah && dl + 1 == al
+}
+
+/// Tag deleted blocks of at least [`MIN_MOVED_LINES`] lines that reappear as
+/// added lines elsewhere in the diff. Both sides of a match get the same
+/// `move_id`. Matching ignores whitespace, so re-indented moves are found.
+pub fn detect_moved_blocks(files: &mut [DiffFile]) {
+    let deleted = collect_runs(files, LineOrigin::Deletion);
+    let added = collect_runs(files, LineOrigin::Addition);
+
+    let mut index: HashMap<&str, Vec<(usize, usize)>> = HashMap::new();
+    for (run_idx, run) in added.iter().enumerate() {
+        for (offset, key) in run.keys.iter().enumerate() {
+            if !key.is_empty() {
+                index.entry(key).or_default().push((run_idx, offset));
+            }
+        }
+    }
+
+    let mut taken: Vec<Vec<bool>> = added.iter().map(|r| vec![false; r.lines.len()]).collect();
+    let mut matches = Vec::new();
+    for del_run in &deleted {
+        let mut i = 0;
+        while i < del_run.keys.len() {
+            let mut best: Option<(usize, usize, usize)> = None;
+            for &(run_idx, offset) in index.get(del_run.keys[i].as_str()).into_iter().flatten() {
+                let add_run = &added[run_idx];
+                if is_same_change_block(del_run, add_run) {
+                    continue;
+                }
+                let len = (0..)
+                    .take_while(|&n| {
+                        i + n < del_run.keys.len()
+                            && offset + n < add_run.keys.len()
+                            && !taken[run_idx][offset + n]
+                            && del_run.keys[i + n] == add_run.keys[offset + n]
+                    })
+                    .count();
+                if len > best.map_or(0, |(_, _, best_len)| best_len) {
+                    best = Some((run_idx, offset, len));
+                }
+            }
+
+            let alnum = |len: usize| {
+                del_run.keys[i..i + len]
+                    .iter()
+                    .flat_map(|key| key.chars())
+                    .filter(|c| c.is_alphanumeric())
+                    .count()
+            };
+            match best {
+                Some((run_idx, offset, len))
+                    if len >= MIN_MOVED_LINES && alnum(len) >= MIN_MOVED_ALNUM =>
+                {
+                    taken[run_idx][offset..offset + len].fill(true);
+                    matches.push((
+                        del_run.lines[i..i + len].to_vec(),
+                        added[run_idx].lines[offset..offset + len].to_vec(),
+                    ));
+                    i += len;
+                }
+                _ => i += 1,
+            }
+        }
+    }
+
+    for (move_id, (from, to)) in matches.into_iter().enumerate() {
+        for (f, h, l) in from.into_iter().chain(to) {
+            files[f].hunks[h].lines[l].move_id = Some(move_id as u32);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{DiffHunk, DiffLine, FileStatus};
+    use std::path::PathBuf;
+
+    fn file(path: &str, lines: &[&str]) -> DiffFile {
+        let lines = lines
+            .iter()
+            .map(|line| {
+                let (origin, content) = match line.split_at(1) {
+                    ("+", rest) => (LineOrigin::Addition, rest),
+                    ("-", rest) => (LineOrigin::Deletion, rest),
+                    (_, rest) => (LineOrigin::Context, rest),
+                };
+                DiffLine {
+                    origin,
+                    content: content.to_string(),
+                    old_lineno: None,
+                    new_lineno: None,
+                    highlighted_spans: None,
+                    whitespace_only: false,
+                    move_id: None,
+                }
+            })
+            .collect();
+        DiffFile {
+            old_path: Some(PathBuf::from(path)),
+            new_path: Some(PathBuf::from(path)),
+            status: FileStatus::Modified,
+            hunks: vec![DiffHunk {
+                header: String::new(),
+                lines,
+                old_start: 1,
+                old_count: 0,
+                new_start: 1,
+                new_count: 0,
+            }],
+            is_binary: false,
+            binary_meta: None,
+            is_too_large: false,
+            truncated_lines: 0,
+            is_commit_message: false,
+            is_submodule: false,
+            old_mode: None,
+            new_mode: None,
+            is_symlink: false,
+            is_conflicted: false,
+            has_conflicts: false,
+            content_hash: 0,
+            highlighted: false,
+            is_generated: false,
+        }
+    }
+
+    fn move_ids(file: &DiffFile) -> Vec<Option<u32>> {
+        file.hunks[0].lines.iter().map(|l| l.move_id).collect()
+    }
+
+    #[test]
+    fn should_match_block_moved_between_files_ignoring_indentation() {
+        let mut files = vec![
+            file(
+                "a.rs",
+                &[
+                    " fn keep() {}",
+                    "-fn helper(x: u32) -> u32 {",
+                    "-    x.saturating_mul(2)",
+                    "-}",
+                ],
+            ),
+            file(
+                "b.rs",
+                &[
+                    " mod util {",
+                    "+    fn helper(x: u32) -> u32 {",
+                    "+        x.saturating_mul(2)",
+                    "+    }",
+                    " }",
+                ],
+            ),
+        ];
+
+        detect_moved_blocks(&mut files);
+
+        assert_eq!(move_ids(&files[0]), [None, Some(0), Some(0), Some(0)]);
+        assert_eq!(move_ids(&files[1]), [None, Some(0), Some(0), Some(0), None]);
+    }
+
+    #[test]
+    fn should_ignore_short_blocks_and_in_place_edits() {
+        let mut files = vec![file(
+            "a.rs",
+            &[
+                "-let total = compute_total(items);",
+                "-}",
+                "-}",
+                "+    let total = compute_total(items);",
+                "+}",
+                "+}",
+                " ",
+                "-let first = one();",
+                "-let second = two();",
+                "+let first = one();",
+                "+let second = two();",
+            ],
+        )];
+
+        detect_moved_blocks(&mut files);
+
+        assert!(move_ids(&files[0]).iter().all(Option::is_none));
+    }
+}
@@ -0,0 +1,219 @@
+//! Pair the deleted and added lines of a change block into side-by-side
+//! rows, putting each rewritten line opposite its closest replacement.
+
+use super::{DiffLine, LineOrigin};
+
+/// Minimum similarity for a deletion and an addition to share a row.
+const MIN_SIMILARITY: f64 = 0.5;
+/// Blocks with more deletion/addition combinations than this are paired by
+/// position, keeping the quadratic alignment cheap on huge rewrites.
+const MAX_ALIGNED_PAIRS: usize = 4096;
+
+/// One side-by-side row: indices into the hunk's lines for each side.
+pub type LinePair = (Option<usize>, Option<usize>);
+
+/// Rows for the change block starting at `start`: its deletions and the
+/// additions right after them. Returns the rows and the index just past the
+/// block.
+///
+/// Deletions and additions are aligned in order so the sum of their
+/// similarities is largest. Lines without a similar counterpart sit opposite
+/// a blank.
+pub fn pair_change_block(lines: &[DiffLine], start: usize) -> (Vec<LinePair>, usize) {
+    let run_end = |from: usize, origin: LineOrigin| {
+        from + lines[from..]
+            .iter()
+            .take_while(|line| line.origin == origin)
+            .count()
+    };
+    let del_end = run_end(start, LineOrigin::Deletion);
+    let add_end = run_end(del_end, LineOrigin::Addition);
+    let deleted: Vec<usize> = (start..del_end).collect();
+    let added: Vec<usize> = (del_end..add_end).collect();
+
+    let rows = if deleted.is_empty()
+        || added.is_empty()
+        || deleted.len() * added.len() > MAX_ALIGNED_PAIRS
+    {
+        pair_by_position(&deleted, &added)
+    } else {
+        pair_by_similarity(lines, &deleted, &added)
+    };
+    (rows, add_end)
+}
+
+fn pair_by_position(deleted: &[usize], added: &[usize]) -> Vec<LinePair> {
+    (0..deleted.len().max(added.len()))
+        .map(|row| (deleted.get(row).copied(), added.get(row).copied()))
+        .collect()
+}
+
+fn pair_by_similarity(lines: &[DiffLine], deleted: &[usize], added: &[usize]) -> Vec<LinePair> {
+    let del_tokens: Vec<Vec<&str>> = deleted
+        .iter()
+        .map(|&idx| sorted_tokens(&lines[idx].content))
+        .collect();
+    let add_tokens: Vec<Vec<&str>> = added
+        .iter()
+        .map(|&idx| sorted_tokens(&lines[idx].content))
+        .collect();
+
+    // best[d][a]: largest total similarity aligning the first d deletions
+    // with the first a additions
+    let (rows, cols) = (deleted.len(), added.len());
+    let mut best = vec![vec![0.0_f64; cols + 1]; rows + 1];
+    let mut matched = vec![vec![false; cols + 1]; rows + 1];
+    for d in 1..=rows {
+        for a in 1..=cols {
+            let skip = best[d - 1][a].max(best[d][a - 1]);
+            let similarity = similarity(&del_tokens[d - 1], &add_tokens[a - 1]);
+            if similarity >= MIN_SIMILARITY && best[d - 1][a - 1] + similarity > skip {
+                best[d][a] = best[d - 1][a - 1] + similarity;
+                matched[d][a] = true;
+            } else {
+                best[d][a] = skip;
+            }
+        }
+    }
+
+    let mut pairs = Vec::new();
+    let (mut d, mut a) = (rows, cols);
+    while d > 0 && a > 0 {
+        if matched[d][a] {
+            pairs.push((d - 1, a - 1));
+            d -= 1;
+            a -= 1;
+        } else if best[d - 1][a] >= best[d][a - 1] {
+            d -= 1;
+        } else {
+            a -= 1;
+        }
+    }
+    pairs.reverse();
+
+    let mut out = Vec::new();
+    let (mut next_del, mut next_add) = (0, 0);
+    for (d, a) in pairs.into_iter().chain(std::iter::once((rows, cols))) {
+        out.extend(deleted[next_del..d].iter().map(|&idx| (Some(idx), None)));
+        out.extend(added[next_add..a].iter().map(|&idx| (None, Some(idx))));
+        if d < rows {
+            out.push((Some(deleted[d]), Some(added[a])));
+        }
+        (next_del, next_add) = (d + 1, a + 1);
+    }
+    out
+}
+
+/// Words of a line, sorted so two lines can be compared as multisets.
+/// Lines without words (`}`, `});`) are compared by their punctuation, which
+/// would otherwise make any two calls look alike.
+fn sorted_tokens(content: &str) -> Vec<&str> {
+    let is_word = |ch: char| ch.is_alphanumeric() || ch == '_';
+    let mut tokens: Vec<&str> = content
+        .split(|ch: char| !is_word(ch))
+        .filter(|word| !word.is_empty())
+        .collect();
+    if tokens.is_empty() {
+        tokens = content
+            .char_indices()
+            .filter(|(_, ch)| !ch.is_whitespace())
+            .map(|(idx, ch)| &content[idx..idx + ch.len_utf8()])
+            .collect();
+    }
+    tokens.sort_unstable();
+    tokens
+}
+
+/// Dice coefficient of two sorted token multisets, from 0 to 1. Two blank
+/// lines are identical.
+fn similarity(a: &[&str], b: &[&str]) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    let (mut i, mut j, mut common) = (0, 0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                common += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    2.0 * common as f64 / (a.len() + b.len()) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(origin: LineOrigin, content: &str) -> DiffLine {
+        DiffLine {
+            origin,
+            content: content.to_string(),
+            old_lineno: None,
+            new_lineno: None,
+            highlighted_spans: None,
+            whitespace_only: false,
+            move_id: None,
+        }
+    }
+
+    #[test]
+    fn should_align_a_rewritten_line_with_its_closest_replacement() {
+        let lines = vec![
+            line(LineOrigin::Deletion, "use std::fs;"),
+            line(LineOrigin::Deletion, "let total = items.len() + 1;"),
+            line(LineOrigin::Deletion, "drop(guard);"),
+            line(LineOrigin::Deletion, "}"),
+            line(LineOrigin::Addition, "// Count the header row too"),
+            line(LineOrigin::Addition, "#[inline]"),
+            line(LineOrigin::Addition, "fn header() -> usize {"),
+            line(LineOrigin::Addition, "let total = items.len() + header();"),
+            line(LineOrigin::Addition, "Ok(total)"),
+            line(LineOrigin::Context, "tail"),
+        ];
+
+        let (rows, end) = pair_change_block(&lines, 0);
+
+        assert_eq!(end, 9);
+        assert_eq!(
+            rows,
+            [
+                (Some(0), None),
+                (None, Some(4)),
+                (None, Some(5)),
+                (None, Some(6)),
+                (Some(1), Some(7)),
+                (Some(2), None),
+                (Some(3), None),
+                (None, Some(8)),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_pair_by_position_without_a_counterpart_side() {
+        let lines = vec![
+            line(LineOrigin::Deletion, "a"),
+            line(LineOrigin::Deletion, "b"),
+            line(LineOrigin::Context, "c"),
+        ];
+        assert_eq!(
+            pair_change_block(&lines, 0),
+            (vec![(Some(0), None), (Some(1), None)], 2)
+        );
+    }
+
+    #[test]
+    fn should_score_token_overlap() {
+        let a = sorted_tokens("let x = foo(bar);");
+        assert_eq!(similarity(&a, &a), 1.0);
+        assert_eq!(similarity(&a, &sorted_tokens("let y = foo(baz);")), 0.5);
+        assert_eq!(similarity(&a, &sorted_tokens("Ok(());")), 0.0);
+        assert_eq!(similarity(&sorted_tokens("});"), &sorted_tokens("}")), 0.5);
+        assert_eq!(similarity(&sorted_tokens("  "), &sorted_tokens("")), 1.0);
+    }
+}
@@ -1,10 +1,14 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 
-use super::comment::Comment;
-use super::diff_types::FileStatus;
+use super::comment::{Comment, LineRange, insert_threaded};
+use super::diff_types::{DiffHunk, FileStatus};
+
+/// How far from its old line a moved comment is looked for before searching
+/// the whole file.
+pub const REANCHOR_WINDOW: u32 = 20;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ClearScope {
@@ -12,6 +16,29 @@ pub enum ClearScope {
     CommentsAndReviewed,
 }
 
+/// Identifies a hunk across reloads. Hunk indices shift as other hunks come
+/// and go, and the new-side range moves with edits above the hunk, so the key
+/// is the old-side start and the section heading after the `@@` range.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct HunkKey {
+    pub old_start: u32,
+    pub header: String,
+}
+
+impl HunkKey {
+    pub fn of(hunk: &DiffHunk) -> Self {
+        let heading = hunk
+            .header
+            .strip_prefix("@@")
+            .and_then(|rest| rest.split_once("@@"))
+            .map_or(hunk.header.as_str(), |(_, heading)| heading);
+        Self {
+            old_start: hunk.old_start,
+            header: heading.trim().to_string(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileReview {
     pub path: PathBuf,
@@ -21,6 +48,9 @@ pub struct FileReview {
     pub line_comments: HashMap<u32, Vec<Comment>>,
     #[serde(default)]
     pub content_hash: Option<u64>,
+    /// Hunks marked reviewed on their own, before the whole file is
+    #[serde(default)]
+    pub reviewed_hunks: HashSet<HunkKey>,
 }
 
 impl FileReview {
@@ -32,6 +62,20 @@ impl FileReview {
             file_comments: Vec::new(),
             line_comments: HashMap::new(),
             content_hash: Some(content_hash),
+            reviewed_hunks: HashSet::new(),
+        }
+    }
+
+    pub fn is_hunk_reviewed(&self, hunk: &DiffHunk) -> bool {
+        self.reviewed_hunks.contains(&HunkKey::of(hunk))
+    }
+
+    /// Mark `hunk` reviewed or not. Returns whether it was reviewed before.
+    pub fn set_hunk_reviewed(&mut self, hunk: &HunkKey, reviewed: bool) -> bool {
+        if reviewed {
+            !self.reviewed_hunks.insert(hunk.clone())
+        } else {
+            self.reviewed_hunks.remove(hunk)
         }
     }
 
@@ -40,14 +84,85 @@ impl FileReview {
     }
 
     pub fn add_file_comment(&mut self, comment: Comment) {
-        self.file_comments.push(comment);
+        insert_threaded(&mut self.file_comments, comment);
     }
 
     pub fn add_line_comment(&mut self, line: u32, comment: Comment) {
-        self.line_comments.entry(line).or_default().push(comment);
+        insert_threaded(self.line_comments.entry(line).or_default(), comment);
+    }
+
+    /// Follow line comments to where their line moved in `lines`, the new
+    /// contents of the file. Threads whose line can't be found are detached:
+    /// they become file comments flagged `orphaned`, keeping their old line
+    /// range. Returns how many threads were moved and how many detached.
+    pub fn reanchor_comments(&mut self, lines: &[&str]) -> (usize, usize) {
+        let mut keys: Vec<u32> = self.line_comments.keys().copied().collect();
+        keys.sort_unstable();
+
+        let mut detached = 0;
+        let mut moved: Vec<(u32, Vec<Comment>)> = Vec::new();
+        for line in keys {
+            let comments = self.line_comments.remove(&line).unwrap_or_default();
+            let mut kept = Vec::new();
+            for mut thread in split_threads(comments) {
+                let target = match &thread[0].fingerprint {
+                    Some(fingerprint)
+                        if !thread[0].orphaned && !fingerprint.matches(lines, line) =>
+                    {
+                        fingerprint.locate(lines, line, REANCHOR_WINDOW)
+                    }
+                    _ => {
+                        kept.extend(thread);
+                        continue;
+                    }
+                };
+                match target {
+                    Some(target) => {
+                        let delta = i64::from(target) - i64::from(line);
+                        let shift =
+                            |n: u32| (i64::from(n) + delta).clamp(1, i64::from(u32::MAX)) as u32;
+                        for comment in &mut thread {
+                            comment.line_range = comment
+                                .line_range
+                                .map(|range| LineRange::new(shift(range.start), shift(range.end)));
+                        }
+                        moved.push((target, thread));
+                    }
+                    None => {
+                        detached += 1;
+                        for mut comment in thread {
+                            comment.orphaned = true;
+                            comment.line_range.get_or_insert(LineRange::single(line));
+                            self.file_comments.push(comment);
+                        }
+                    }
+                }
+            }
+            if !kept.is_empty() {
+                self.line_comments.insert(line, kept);
+            }
+        }
+        let moved_count = moved.len();
+        for (line, thread) in moved {
+            self.line_comments.entry(line).or_default().extend(thread);
+        }
+        (moved_count, detached)
     }
 }
 
+/// Split a comment list into threads: a root comment and the replies stored
+/// after it.
+fn split_threads(comments: Vec<Comment>) -> Vec<Vec<Comment>> {
+    let mut threads: Vec<Vec<Comment>> = Vec::new();
+    for comment in comments {
+        match threads.last_mut() {
+            Some(thread) if comment.parent_id.is_some() => thread.push(comment),
+            _ => threads.push(vec![comment]),
+        }
+    }
+    threads
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 #[derive(Default)]
@@ -110,6 +225,38 @@ impl ReviewSession {
         self.files.values().filter(|f| f.reviewed).count()
     }
 
+    pub fn comment_count(&self) -> usize {
+        self.review_comments.len()
+            + self
+                .files
+                .values()
+                .map(FileReview::comment_count)
+                .sum::<usize>()
+    }
+
+    /// Number of comments per author, most active first. Comments without
+    /// an author aren't counted.
+    pub fn comment_counts_by_author(&self) -> Vec<(String, usize)> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        let file_comments = self.files.values().flat_map(|review| {
+            review
+                .file_comments
+                .iter()
+                .chain(review.line_comments.values().flatten())
+        });
+        for comment in self.review_comments.iter().chain(file_comments) {
+            if let Some(author) = comment.author.as_deref() {
+                *counts.entry(author).or_default() += 1;
+            }
+        }
+        let mut counts: Vec<(String, usize)> = counts
+            .into_iter()
+            .map(|(author, count)| (author.to_string(), count))
+            .collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        counts
+    }
+
     /// Registers a file in the session. Returns true if the file was previously
     /// reviewed but its content changed, causing reviewed status to be reset.
     pub fn add_file(&mut self, path: PathBuf, status: FileStatus, content_hash: u64) -> bool {
@@ -143,14 +290,44 @@ impl ReviewSession {
             cleared += file.comment_count();
             file.file_comments.clear();
             file.line_comments.clear();
-            if scope == ClearScope::CommentsAndReviewed && file.reviewed {
-                file.reviewed = false;
-                unreviewed += 1;
+            if scope == ClearScope::CommentsAndReviewed {
+                file.reviewed_hunks.clear();
+                if file.reviewed {
+                    file.reviewed = false;
+                    unreviewed += 1;
+                }
             }
         }
         (cleared, unreviewed)
     }
 
+    /// Returns true if any comment in the session replies to `id`.
+    pub fn has_replies(&self, id: &str) -> bool {
+        let is_reply = |c: &Comment| c.parent_id.as_deref() == Some(id);
+        self.review_comments.iter().any(is_reply)
+            || self.files.values().any(|f| {
+                f.file_comments.iter().any(is_reply)
+                    || f.line_comments.values().flatten().any(is_reply)
+            })
+    }
+
+    /// Removes the comment `id` together with all of its replies.
+    /// Returns the number of comments removed.
+    pub fn remove_comment_thread(&mut self, id: &str) -> usize {
+        let in_thread = |c: &Comment| c.id == id || c.parent_id.as_deref() == Some(id);
+        let before = self.comment_count();
+        self.review_comments.retain(|c| !in_thread(c));
+        for file in self.files.values_mut() {
+            file.file_comments.retain(|c| !in_thread(c));
+            for comments in file.line_comments.values_mut() {
+                comments.retain(|c| !in_thread(c));
+            }
+            file.line_comments
+                .retain(|_, comments| !comments.is_empty());
+        }
+        before - self.comment_count()
+    }
+
     pub fn is_file_reviewed(&self, path: &PathBuf) -> bool {
         self.files.get(path).map(|r| r.reviewed).unwrap_or(false)
     }
@@ -159,7 +336,7 @@ impl ReviewSession {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::model::comment::{Comment, CommentType};
+    use crate::model::comment::{Comment, CommentType, LineFingerprint, LineSide};
 
     // Arbitrary hash value for tests that don't care about the specific hash.
     const SOME_HASH: u64 = 0xdeadbeef;
@@ -359,6 +536,7 @@ mod tests {
                 file_comments: Vec::new(),
                 line_comments: HashMap::new(),
                 content_hash: None,
+                reviewed_hunks: HashSet::new(),
             },
         );
 
@@ -367,4 +545,143 @@ mod tests {
         assert!(!session.is_file_reviewed(&path));
         assert_eq!(session.files.get(&path).unwrap().content_hash, Some(999));
     }
+
+    #[test]
+    fn should_keep_replies_after_their_thread() {
+        let mut review = FileReview::new(PathBuf::from("a.rs"), FileStatus::Modified, SOME_HASH);
+        let first = Comment::new("first".to_string(), CommentType::Note, None);
+        let first_id = first.id.clone();
+        review.add_file_comment(first);
+        review.add_file_comment(Comment::new("second".to_string(), CommentType::Note, None));
+        for reply in ["reply 1", "reply 2"] {
+            let mut comment = Comment::new(reply.to_string(), CommentType::Note, None);
+            comment.parent_id = Some(first_id.clone());
+            review.add_file_comment(comment);
+        }
+
+        let contents: Vec<&str> = review
+            .file_comments
+            .iter()
+            .map(|c| c.content.as_str())
+            .collect();
+        assert_eq!(contents, vec!["first", "reply 1", "reply 2", "second"]);
+    }
+
+    #[test]
+    fn should_remove_replies_with_their_parent() {
+        let mut session = test_session();
+        session.add_file(PathBuf::from("a.rs"), FileStatus::Modified, SOME_HASH);
+        let parent = Comment::new("parent".to_string(), CommentType::Issue, None);
+        let parent_id = parent.id.clone();
+        let mut reply = Comment::new("reply".to_string(), CommentType::Note, None);
+        reply.parent_id = Some(parent_id.clone());
+        let review = session.get_file_mut(&PathBuf::from("a.rs")).unwrap();
+        review.add_line_comment(3, parent);
+        review.add_line_comment(3, reply);
+        review.add_line_comment(
+            7,
+            Comment::new("other".to_string(), CommentType::Note, None),
+        );
+
+        assert!(session.has_replies(&parent_id));
+        assert_eq!(session.remove_comment_thread(&parent_id), 2);
+        assert!(!session.has_replies(&parent_id));
+        let review = &session.files[&PathBuf::from("a.rs")];
+        assert!(!review.line_comments.contains_key(&3));
+        assert_eq!(review.comment_count(), 1);
+    }
+
+    #[test]
+    fn should_count_comments_per_author() {
+        let mut session = ReviewSession::new(
+            PathBuf::from("/tmp/repo"),
+            "abc".to_string(),
+            None,
+            SessionDiffSource::WorkingTree,
+        );
+        let authored = |author: Option<&str>| {
+            let mut comment = Comment::new("c".to_string(), CommentType::Note, None);
+            comment.author = author.map(str::to_string);
+            comment
+        };
+        session.review_comments.push(authored(Some("bob")));
+        session.add_file(PathBuf::from("a.rs"), FileStatus::Modified, 0);
+        let file = session.get_file_mut(&PathBuf::from("a.rs")).unwrap();
+        file.add_file_comment(authored(Some("alice")));
+        file.add_line_comment(3, authored(Some("alice")));
+        file.add_line_comment(4, authored(None));
+
+        assert_eq!(
+            session.comment_counts_by_author(),
+            vec![("alice".to_string(), 2), ("bob".to_string(), 1)]
+        );
+    }
+
+    fn fingerprinted(lines: &[&str], line: u32) -> Comment {
+        let mut comment = Comment::new("c".to_string(), CommentType::Note, Some(LineSide::New));
+        comment.fingerprint = LineFingerprint::of(lines, line);
+        comment
+    }
+
+    #[test]
+    fn should_follow_comments_when_lines_are_inserted_above() {
+        let before = ["fn main() {", "    let x = 1;", "    run(x);", "}"];
+        let mut review = FileReview::new(PathBuf::from("a.rs"), FileStatus::Modified, SOME_HASH);
+        let mut root = fingerprinted(&before, 3);
+        root.line_range = Some(LineRange::new(2, 3));
+        let root_id = root.id.clone();
+        review.add_line_comment(3, root);
+        let mut reply = Comment::new("r".to_string(), CommentType::Note, Some(LineSide::New));
+        reply.parent_id = Some(root_id);
+        review.add_line_comment(3, reply);
+        review.add_line_comment(4, fingerprinted(&before, 4));
+
+        let after = [
+            "use std::io;",
+            "",
+            "fn main() {",
+            "    let x = 1;",
+            "    run(x);",
+            "}",
+        ];
+        assert_eq!(review.reanchor_comments(&after), (2, 0));
+
+        let thread = &review.line_comments[&5];
+        assert_eq!(thread.len(), 2);
+        assert_eq!(thread[0].line_range, Some(LineRange::new(4, 5)));
+        assert!(thread[1].parent_id.is_some());
+        assert_eq!(review.line_comments[&6].len(), 1);
+        assert!(!review.line_comments.contains_key(&3));
+
+        // Lines that still match stay put
+        assert_eq!(review.reanchor_comments(&after), (0, 0));
+    }
+
+    #[test]
+    fn should_detach_comments_whose_line_is_gone_or_ambiguous() {
+        let before = ["a", "b", "c", "b", "a"];
+        let mut review = FileReview::new(PathBuf::from("a.rs"), FileStatus::Modified, SOME_HASH);
+        review.add_line_comment(3, fingerprinted(&before, 3));
+        let mut ambiguous = fingerprinted(&before, 2);
+        ambiguous.fingerprint.as_mut().unwrap().above = None;
+        ambiguous.fingerprint.as_mut().unwrap().below = None;
+        review.add_line_comment(2, ambiguous);
+        review.add_line_comment(
+            4,
+            Comment::new("legacy".to_string(), CommentType::Note, None),
+        );
+
+        let after = ["x", "a", "b", "b", "a"];
+        assert_eq!(review.reanchor_comments(&after), (0, 2));
+
+        assert_eq!(review.file_comments.len(), 2);
+        assert!(review.file_comments.iter().all(|c| c.orphaned));
+        let lines: Vec<_> = review.file_comments.iter().map(|c| c.line_range).collect();
+        assert_eq!(
+            lines,
+            [Some(LineRange::single(2)), Some(LineRange::single(3))]
+        );
+        // Comments without a fingerprint are left alone
+        assert_eq!(review.line_comments.keys().collect::<Vec<_>>(), [&4]);
+    }
 }
@@ -0,0 +1,313 @@
+//! Export a review session as a GitHub pull request review using the `gh` CLI.
+
+use std::io::{ErrorKind, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use serde::Serialize;
+
+use super::markdown::{export_comment_type_label, format_author};
+use crate::app::CommentTypeDefinition;
+use crate::error::{Result, TuicrError};
+use crate::model::{Comment, CommentType, LineRange, LineSide, ReviewSession};
+
+/// Payload for `POST /repos/{owner}/{repo}/pulls/{number}/reviews`.
+#[derive(Debug, Serialize)]
+pub struct GithubReview {
+    pub body: String,
+    pub event: &'static str,
+    pub comments: Vec<GithubReviewComment>,
+}
+
+/// An inline review comment anchored with `line`/`side` (and `start_line` for ranges).
+#[derive(Debug, Serialize)]
+pub struct GithubReviewComment {
+    pub path: String,
+    pub body: String,
+    pub line: u32,
+    pub side: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_line: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_side: Option<&'static str>,
+}
+
+/// Convert the session into a GitHub review. Line comments become inline
+/// comments, review and file comments go into the review body. Any `issue`
+/// comment requests changes when `request_changes_on_issue` is set.
+pub fn build_github_review(
+    session: &ReviewSession,
+    comment_types: &[CommentTypeDefinition],
+    request_changes_on_issue: bool,
+) -> Result<GithubReview> {
+    if !session.has_comments() {
+        return Err(TuicrError::NoComments);
+    }
+
+    let format_body = |comment: &Comment| {
+        format!(
+            "**[{}]**{} {}",
+            export_comment_type_label(&comment.comment_type, comment_types),
+            format_author(comment.author.as_deref()),
+            comment.content
+        )
+    };
+    let mut has_issue = false;
+
+    let mut body_lines = Vec::new();
+    if let Some(notes) = &session.session_notes {
+        body_lines.push(notes.clone());
+        body_lines.push(String::new());
+    }
+    for comment in &session.review_comments {
+        has_issue |= comment.comment_type == CommentType::Issue;
+        body_lines.push(format_body(comment));
+    }
+
+    let mut files: Vec<_> = session.files.iter().collect();
+    files.sort_by_key(|(path, _)| path.to_string_lossy().to_string());
+
+    let mut file_comment_lines = Vec::new();
+    let mut comments = Vec::new();
+    for (path, review) in files {
+        let path_str = path.to_string_lossy().replace('\\', "/");
+
+        for comment in &review.file_comments {
+            has_issue |= comment.comment_type == CommentType::Issue;
+            file_comment_lines.push(format!("- `{path_str}`: {}", format_body(comment)));
+        }
+
+        let mut line_comments: Vec<_> = review.line_comments.iter().collect();
+        line_comments.sort_by_key(|(line, _)| **line);
+        for (line, line_comments) in line_comments {
+            for comment in line_comments {
+                has_issue |= comment.comment_type == CommentType::Issue;
+                let side = match comment.side.unwrap_or(LineSide::New) {
+                    LineSide::New => "RIGHT",
+                    LineSide::Old => "LEFT",
+                };
+                let range = comment.line_range.unwrap_or(LineRange::single(*line));
+                let is_range = !range.is_single();
+                comments.push(GithubReviewComment {
+                    path: path_str.clone(),
+                    body: format_body(comment),
+                    line: range.end,
+                    side,
+                    start_line: is_range.then_some(range.start),
+                    start_side: is_range.then_some(side),
+                });
+            }
+        }
+    }
+
+    if !file_comment_lines.is_empty() {
+        if !body_lines.is_empty() {
+            body_lines.push(String::new());
+        }
+        body_lines.push("### File comments".to_string());
+        body_lines.extend(file_comment_lines);
+    }
+
+    let event = if request_changes_on_issue && has_issue {
+        "REQUEST_CHANGES"
+    } else {
+        "COMMENT"
+    };
+    let mut body = body_lines.join("\n");
+    if body.is_empty() {
+        // GitHub rejects REQUEST_CHANGES reviews without a body
+        body = "Reviewed with tuicr".to_string();
+    }
+
+    Ok(GithubReview {
+        body,
+        event,
+        comments,
+    })
+}
+
+/// Post `review` to the pull request of the current branch.
+/// Returns a status message naming the PR.
+pub fn post_github_review(root: &Path, review: &GithubReview) -> Result<String> {
+    let number = run_gh(
+        root,
+        &["pr", "view", "--json", "number", "--jq", ".number"],
+        None,
+    )?;
+    let number = number.trim();
+    if number.is_empty() {
+        return Err(TuicrError::GitHub(
+            "no pull request found for the current branch".to_string(),
+        ));
+    }
+
+    let payload = serde_json::to_string(review)?;
+    let endpoint = format!("repos/{{owner}}/{{repo}}/pulls/{number}/reviews");
+    run_gh(
+        root,
+        &["api", &endpoint, "--method", "POST", "--input", "-"],
+        Some(&payload),
+    )?;
+
+    Ok(format!(
+        "Posted review to PR #{number} ({} inline comments)",
+        review.comments.len()
+    ))
+}
+
+fn run_gh(root: &Path, args: &[&str], stdin: Option<&str>) -> Result<String> {
+    let mut child = Command::new("gh")
+        .args(args)
+        .current_dir(root)
+        .stdin(if stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            ErrorKind::NotFound => TuicrError::GitHub(
+                "gh CLI not found; install it from https://cli.github.com".to_string(),
+            ),
+            _ => TuicrError::Io(e),
+        })?;
+
+    if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+        pipe.write_all(input.as_bytes())?;
+    }
+
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let message = stderr.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+        return Err(TuicrError::GitHub(format!(
+            "gh {} failed: {}",
+            args[0],
+            message.trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Comment, FileStatus, SessionDiffSource};
+    use std::path::PathBuf;
+
+    fn session_with(comments: Vec<(u32, Comment)>, file_comment: Option<Comment>) -> ReviewSession {
+        let mut session = ReviewSession::new(
+            PathBuf::from("/tmp/test-repo"),
+            "abc1234def".to_string(),
+            Some("main".to_string()),
+            SessionDiffSource::WorkingTree,
+        );
+        let path = PathBuf::from("src/main.rs");
+        session.add_file(path.clone(), FileStatus::Modified, 0);
+        let review = session.get_file_mut(&path).unwrap();
+        for (line, comment) in comments {
+            review.add_line_comment(line, comment);
+        }
+        if let Some(comment) = file_comment {
+            review.add_file_comment(comment);
+        }
+        session
+    }
+
+    #[test]
+    fn should_map_line_comments_to_inline_comments() {
+        let session = session_with(
+            vec![
+                (
+                    12,
+                    Comment::new_with_range(
+                        "Extract a helper".to_string(),
+                        CommentType::Suggestion,
+                        Some(LineSide::New),
+                        LineRange::new(10, 12),
+                    ),
+                ),
+                (
+                    3,
+                    Comment::new(
+                        "Why remove this?".to_string(),
+                        CommentType::Note,
+                        Some(LineSide::Old),
+                    ),
+                ),
+            ],
+            Some(Comment::new(
+                "Needs docs".to_string(),
+                CommentType::Note,
+                None,
+            )),
+        );
+
+        let review = build_github_review(&session, &[], true).unwrap();
+
+        assert_eq!(review.event, "COMMENT");
+        assert!(review.body.contains("### File comments"));
+        assert!(
+            review
+                .body
+                .contains("- `src/main.rs`: **[NOTE]** Needs docs")
+        );
+        assert_eq!(review.comments.len(), 2);
+        assert_eq!(review.comments[0].line, 3);
+        assert_eq!(review.comments[0].side, "LEFT");
+        assert_eq!(review.comments[0].start_line, None);
+        assert_eq!(review.comments[1].line, 12);
+        assert_eq!(review.comments[1].start_line, Some(10));
+        assert_eq!(review.comments[1].body, "**[SUGGESTION]** Extract a helper");
+    }
+
+    #[test]
+    fn should_request_changes_for_issues_when_enabled() {
+        let issue = || {
+            vec![(
+                1,
+                Comment::new(
+                    "Broken".to_string(),
+                    CommentType::Issue,
+                    Some(LineSide::New),
+                ),
+            )]
+        };
+
+        let review = build_github_review(&session_with(issue(), None), &[], true).unwrap();
+        assert_eq!(review.event, "REQUEST_CHANGES");
+        assert!(!review.body.is_empty());
+
+        let review = build_github_review(&session_with(issue(), None), &[], false).unwrap();
+        assert_eq!(review.event, "COMMENT");
+    }
+
+    #[test]
+    fn should_serialize_without_empty_range_fields() {
+        let session = session_with(
+            vec![(
+                5,
+                Comment::new("Nice".to_string(), CommentType::Praise, Some(LineSide::New)),
+            )],
+            None,
+        );
+        let review = build_github_review(&session, &[], true).unwrap();
+
+        let json = serde_json::to_string(&review).unwrap();
+
+        assert!(json.contains(r#""line":5"#));
+        assert!(json.contains(r#""side":"RIGHT""#));
+        assert!(!json.contains("start_line"));
+    }
+
+    #[test]
+    fn should_refuse_empty_review() {
+        let session = session_with(Vec::new(), None);
+        assert!(matches!(
+            build_github_review(&session, &[], true),
+            Err(TuicrError::NoComments)
+        ));
+    }
+}
@@ -7,15 +7,23 @@ use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 
 use crate::app::{CommentTypeDefinition, DiffSource};
 use crate::error::{Result, TuicrError};
-use crate::model::{CommentType, LineRange, LineSide, ReviewSession};
-
-/// (file_path, line_range, side, comment_type, content)
-type CommentEntry<'a> = (String, Option<LineRange>, Option<LineSide>, String, &'a str);
+use crate::model::{CommentType, DiffFile, LineRange, LineSide, ReviewSession};
+
+/// (file_path, line_range, side, comment_type, content, author)
+type CommentEntry<'a> = (
+    String,
+    Option<LineRange>,
+    Option<LineSide>,
+    String,
+    &'a str,
+    Option<&'a str>,
+);
 
 /// Generate markdown content from the review session.
 /// Returns the markdown string or an error if there are no comments.
 pub fn generate_export_content(
     session: &ReviewSession,
+    diff_files: &[DiffFile],
     diff_source: &DiffSource,
     comment_types: &[CommentTypeDefinition],
     show_legend: bool,
@@ -25,6 +33,7 @@ pub fn generate_export_content(
     }
     Ok(generate_markdown(
         session,
+        diff_files,
         diff_source,
         comment_types,
         show_legend,
@@ -33,25 +42,61 @@ pub fn generate_export_content(
 
 pub fn export_to_clipboard(
     session: &ReviewSession,
+    diff_files: &[DiffFile],
     diff_source: &DiffSource,
     comment_types: &[CommentTypeDefinition],
     show_legend: bool,
 ) -> Result<String> {
-    let content = generate_export_content(session, diff_source, comment_types, show_legend)?;
+    let content =
+        generate_export_content(session, diff_files, diff_source, comment_types, show_legend)?;
+
+    if copy_to_clipboard(&content)? {
+        Ok("Review copied to clipboard (via terminal)".to_string())
+    } else {
+        Ok("Review copied to clipboard".to_string())
+    }
+}
 
+/// Build a Markdown checklist of the diff files, checked when reviewed,
+/// followed by a summary line with the counts.
+pub fn generate_checklist(files: &[DiffFile], session: &ReviewSession) -> String {
+    let mut md = String::new();
+    let mut reviewed = 0;
+    let mut total = 0;
+    for file in files.iter().filter(|f| !f.is_commit_message) {
+        let path = file.display_path();
+        let checked = session.is_file_reviewed(path);
+        if checked {
+            reviewed += 1;
+        }
+        total += 1;
+        let mark = if checked { 'x' } else { ' ' };
+        let _ = writeln!(md, "- [{mark}] {}", path.display());
+    }
+    let _ = write!(
+        md,
+        "\n{reviewed}/{total} files reviewed, {} pending",
+        total - reviewed
+    );
+    md
+}
+
+/// Copy arbitrary text to the clipboard.
+/// Returns true if the text was sent via the terminal (OSC 52) rather than the system clipboard.
+pub fn copy_to_clipboard(text: &str) -> Result<bool> {
     // Prefer OSC 52 in tmux/SSH where arboard may silently fail
     if should_prefer_osc52() {
-        copy_osc52(&content)?;
-        return Ok("Review copied to clipboard (via terminal)".to_string());
+        copy_osc52(text)?;
+        return Ok(true);
     }
 
     // Try arboard (system clipboard) first, fall back to OSC 52 for SSH/remote sessions
-    match Clipboard::new().and_then(|mut cb| cb.set_text(&content)) {
-        Ok(_) => Ok("Review copied to clipboard".to_string()),
+    match Clipboard::new().and_then(|mut cb| cb.set_text(text)) {
+        Ok(_) => Ok(false),
         Err(_) => {
             // Fall back to OSC 52 escape sequence (works over SSH)
-            copy_osc52(&content)?;
-            Ok("Review copied to clipboard (via terminal)".to_string())
+            copy_osc52(text)?;
+            Ok(true)
         }
     }
 }
@@ -121,7 +166,7 @@ fn write_osc52<W: IoWrite>(writer: &mut W, text: &str) -> Result<()> {
     Ok(())
 }
 
-fn review_scope_label(diff_source: &DiffSource) -> String {
+pub(super) fn review_scope_label(diff_source: &DiffSource) -> String {
     let scope = match diff_source {
         DiffSource::WorkingTree => "working tree changes".to_string(),
         DiffSource::StagedAndUnstaged => "staged + unstaged changes".to_string(),
@@ -141,6 +186,7 @@ fn review_scope_label(diff_source: &DiffSource) -> String {
 
 fn generate_markdown(
     session: &ReviewSession,
+    diff_files: &[DiffFile],
     diff_source: &DiffSource,
     comment_types: &[CommentTypeDefinition],
     show_legend: bool,
@@ -258,7 +304,92 @@ fn generate_markdown(
         let _ = writeln!(md);
     }
 
-    // Collect all comments into a flat list
+    for (i, (file, line_range, side, comment_type, content, author)) in
+        collect_comment_entries(session, diff_source, comment_types)
+            .iter()
+            .enumerate()
+    {
+        let marker = format!("{}. ", i + 1);
+        let _ = writeln!(
+            md,
+            "{marker}**[{}]** `{}`{} - {}",
+            comment_type,
+            format_location(file, *line_range, *side),
+            format_author(*author),
+            content
+        );
+        if let Some(snippet) = format_snippet(diff_files, file, *line_range, *side) {
+            // Indented to stay inside the list item
+            let indent = " ".repeat(marker.len());
+            for line in snippet.lines() {
+                let _ = writeln!(md, "{indent}{line}");
+            }
+        }
+    }
+
+    md
+}
+
+/// The lines a line comment refers to as a fenced code block, taken from the
+/// old or new side of the diff. None for file and review comments, or when
+/// the lines aren't part of the loaded diff.
+fn format_snippet(
+    diff_files: &[DiffFile],
+    file: &str,
+    line_range: Option<LineRange>,
+    side: Option<LineSide>,
+) -> Option<String> {
+    let range = line_range?;
+    let diff_file = diff_files
+        .iter()
+        .find(|diff_file| diff_file.display_path().to_string_lossy() == file)?;
+    let lines: Vec<&str> = diff_file
+        .hunks
+        .iter()
+        .flat_map(|hunk| &hunk.lines)
+        .filter(|line| {
+            let lineno = match side {
+                Some(LineSide::Old) => line.old_lineno,
+                _ => line.new_lineno,
+            };
+            lineno.is_some_and(|n| range.start <= n && n <= range.end)
+        })
+        .map(|line| line.content.trim_end_matches(['\n', '\r']))
+        .collect();
+    if lines.is_empty() {
+        return None;
+    }
+
+    // A fence longer than any backtick run in the code can't be closed by it
+    let longest_run = lines
+        .iter()
+        .flat_map(|line| line.split(|c| c != '`'))
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    let fence = "`".repeat((longest_run + 1).max(3));
+    let language = diff_file
+        .display_path()
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let mut snippet = format!("{fence}{language}\n");
+    for line in lines {
+        snippet.push_str(line);
+        snippet.push('\n');
+    }
+    snippet.push_str(&fence);
+    Some(snippet)
+}
+
+/// All comments of the session in export order: review comments first, then
+/// file and line comments sorted by path and line.
+fn collect_comment_entries<'a>(
+    session: &'a ReviewSession,
+    diff_source: &DiffSource,
+    comment_types: &[CommentTypeDefinition],
+) -> Vec<CommentEntry<'a>> {
     let mut all_comments: Vec<CommentEntry> = Vec::new();
     let review_comment_location = review_scope_label(diff_source);
 
@@ -269,6 +400,7 @@ fn generate_markdown(
             None,
             export_comment_type_label(&comment.comment_type, comment_types),
             &comment.content,
+            comment.author.as_deref(),
         ));
     }
 
@@ -287,6 +419,7 @@ fn generate_markdown(
                 None,
                 export_comment_type_label(&comment.comment_type, comment_types),
                 &comment.content,
+                comment.author.as_deref(),
             ));
         }
 
@@ -306,42 +439,69 @@ fn generate_markdown(
                     comment.side,
                     export_comment_type_label(&comment.comment_type, comment_types),
                     &comment.content,
+                    comment.author.as_deref(),
                 ));
             }
         }
     }
 
-    // Output numbered list
-    for (i, (file, line_range, side, comment_type, content)) in all_comments.iter().enumerate() {
-        let location = match (line_range, side) {
-            // Range on deleted side (old lines)
-            (Some(range), Some(LineSide::Old)) if range.is_single() => {
-                format!("`{}:~{}`", file, range.start)
-            }
-            (Some(range), Some(LineSide::Old)) => {
-                format!("`{}:~{}-~{}`", file, range.start, range.end)
-            }
-            // Range on new/context side
-            (Some(range), _) if range.is_single() => {
-                format!("`{}:{}`", file, range.start)
-            }
-            (Some(range), _) => {
-                format!("`{}:{}-{}`", file, range.start, range.end)
-            }
-            // File comment
-            (None, _) => format!("`{file}`"),
-        };
+    all_comments
+}
+
+/// `file:line`, `file:start-end`, or `file:~line` for lines on the old side.
+pub(super) fn format_location(
+    file: &str,
+    line_range: Option<LineRange>,
+    side: Option<LineSide>,
+) -> String {
+    match (line_range, side) {
+        // Range on deleted side (old lines)
+        (Some(range), Some(LineSide::Old)) if range.is_single() => {
+            format!("{}:~{}", file, range.start)
+        }
+        (Some(range), Some(LineSide::Old)) => {
+            format!("{}:~{}-~{}", file, range.start, range.end)
+        }
+        // Range on new/context side
+        (Some(range), _) if range.is_single() => format!("{}:{}", file, range.start),
+        (Some(range), _) => format!("{}:{}-{}", file, range.start, range.end),
+        // File comment
+        (None, _) => file.to_string(),
+    }
+}
+
+/// ` (by name)` after a comment's location, or nothing without an author.
+pub(super) fn format_author(author: Option<&str>) -> String {
+    author
+        .map(|name| format!(" (by {name})"))
+        .unwrap_or_default()
+}
+
+/// Plain-text export: one `location: [TYPE] comment` line per comment, with
+/// continuation lines of multi-line comments indented.
+pub fn generate_text(
+    session: &ReviewSession,
+    diff_source: &DiffSource,
+    comment_types: &[CommentTypeDefinition],
+) -> String {
+    let mut text = String::new();
+    for (file, line_range, side, comment_type, content, author) in
+        collect_comment_entries(session, diff_source, comment_types)
+    {
+        let mut lines = content.lines();
         let _ = writeln!(
-            md,
-            "{}. **[{}]** {} - {}",
-            i + 1,
+            text,
+            "{}: [{}]{} {}",
+            format_location(&file, line_range, side),
             comment_type,
-            location,
-            content
+            format_author(author),
+            lines.next().unwrap_or_default()
         );
+        for line in lines {
+            let _ = writeln!(text, "    {line}");
+        }
     }
-
-    md
+    text
 }
 
 fn collect_used_comment_type_ids(session: &ReviewSession) -> HashSet<String> {
@@ -362,7 +522,7 @@ fn collect_used_comment_type_ids(session: &ReviewSession) -> HashSet<String> {
     ids
 }
 
-fn export_comment_type_label(
+pub(super) fn export_comment_type_label(
     comment_type: &CommentType,
     comment_types: &[CommentTypeDefinition],
 ) -> String {
@@ -380,7 +540,10 @@ fn export_comment_type_label(
 mod tests {
     use super::*;
     use crate::app::CommentTypeDefinition;
-    use crate::model::{Comment, CommentType, FileStatus, LineRange, LineSide, SessionDiffSource};
+    use crate::model::{
+        Comment, CommentType, DiffFile, DiffHunk, DiffLine, FileStatus, LineOrigin, LineRange,
+        LineSide, SessionDiffSource,
+    };
     use std::path::PathBuf;
 
     fn comment_types() -> Vec<CommentTypeDefinition> {
@@ -449,7 +612,7 @@ mod tests {
         let diff_source = DiffSource::WorkingTree;
 
         // when
-        let markdown = generate_markdown(&session, &diff_source, &comment_types(), true);
+        let markdown = generate_markdown(&session, &[], &diff_source, &comment_types(), true);
 
         // then
         assert!(markdown.contains("I reviewed your code and have the following comments"));
@@ -490,7 +653,8 @@ mod tests {
             color: None,
         }];
 
-        let markdown = generate_markdown(&session, &DiffSource::WorkingTree, &custom_types, true);
+        let markdown =
+            generate_markdown(&session, &[], &DiffSource::WorkingTree, &custom_types, true);
 
         assert!(markdown.contains("Comment types: QUESTION (ask for clarification)"));
         assert!(markdown.contains("**[QUESTION]**"));
@@ -503,7 +667,7 @@ mod tests {
         let diff_source = DiffSource::WorkingTree;
 
         // when
-        let markdown = generate_markdown(&session, &diff_source, &comment_types(), true);
+        let markdown = generate_markdown(&session, &[], &diff_source, &comment_types(), true);
 
         // then
         // Should have 2 numbered comments
@@ -520,8 +684,13 @@ mod tests {
             None,
         ));
 
-        let markdown =
-            generate_markdown(&session, &DiffSource::WorkingTree, &comment_types(), true);
+        let markdown = generate_markdown(
+            &session,
+            &[],
+            &DiffSource::WorkingTree,
+            &comment_types(),
+            true,
+        );
 
         assert!(markdown
             .contains("`Review Comment (scope: working tree changes)` - Please split this into smaller commits"));
@@ -538,6 +707,7 @@ mod tests {
 
         let markdown = generate_markdown(
             &session,
+            &[],
             &DiffSource::CommitRange(vec!["abc1234567890".to_string()]),
             &comment_types(),
             true,
@@ -560,7 +730,7 @@ mod tests {
         let diff_source = DiffSource::WorkingTree;
 
         // when
-        let result = export_to_clipboard(&session, &diff_source, &comment_types(), true);
+        let result = export_to_clipboard(&session, &[], &diff_source, &comment_types(), true);
 
         // then
         assert!(result.is_err());
@@ -574,7 +744,7 @@ mod tests {
         let diff_source = DiffSource::WorkingTree;
 
         // when
-        let result = generate_export_content(&session, &diff_source, &comment_types(), true);
+        let result = generate_export_content(&session, &[], &diff_source, &comment_types(), true);
 
         // then
         assert!(result.is_ok());
@@ -596,7 +766,7 @@ mod tests {
         let diff_source = DiffSource::WorkingTree;
 
         // when
-        let result = generate_export_content(&session, &diff_source, &comment_types(), true);
+        let result = generate_export_content(&session, &[], &diff_source, &comment_types(), true);
 
         // then
         assert!(result.is_err());
@@ -613,7 +783,7 @@ mod tests {
         ]);
 
         // when
-        let markdown = generate_markdown(&session, &diff_source, &comment_types(), true);
+        let markdown = generate_markdown(&session, &[], &diff_source, &comment_types(), true);
 
         // then
         assert!(markdown.contains("Reviewing commits: abc1234, def4567"));
@@ -626,7 +796,7 @@ mod tests {
         let diff_source = DiffSource::CommitRange(vec!["abc1234567890".to_string()]);
 
         // when
-        let markdown = generate_markdown(&session, &diff_source, &comment_types(), true);
+        let markdown = generate_markdown(&session, &[], &diff_source, &comment_types(), true);
 
         // then
         assert!(markdown.contains("Reviewing commit: abc1234"));
@@ -687,7 +857,7 @@ mod tests {
         // given - simulate what would be copied during export
         let session = create_test_session();
         let diff_source = DiffSource::WorkingTree;
-        let markdown = generate_markdown(&session, &diff_source, &comment_types(), true);
+        let markdown = generate_markdown(&session, &[], &diff_source, &comment_types(), true);
         let mut buffer: Vec<u8> = Vec::new();
 
         // when
@@ -729,7 +899,7 @@ mod tests {
         let diff_source = DiffSource::WorkingTree;
 
         // when
-        let markdown = generate_markdown(&session, &diff_source, &comment_types(), true);
+        let markdown = generate_markdown(&session, &[], &diff_source, &comment_types(), true);
 
         // then
         assert!(markdown.contains("`src/main.rs:42`"));
@@ -762,7 +932,7 @@ mod tests {
         let diff_source = DiffSource::WorkingTree;
 
         // when
-        let markdown = generate_markdown(&session, &diff_source, &comment_types(), true);
+        let markdown = generate_markdown(&session, &[], &diff_source, &comment_types(), true);
 
         // then
         assert!(markdown.contains("`src/main.rs:10-15`"));
@@ -795,7 +965,7 @@ mod tests {
         let diff_source = DiffSource::WorkingTree;
 
         // when
-        let markdown = generate_markdown(&session, &diff_source, &comment_types(), true);
+        let markdown = generate_markdown(&session, &[], &diff_source, &comment_types(), true);
 
         // then
         assert!(markdown.contains("`src/main.rs:~20-~25`"));
@@ -827,7 +997,7 @@ mod tests {
         let diff_source = DiffSource::WorkingTree;
 
         // when
-        let markdown = generate_markdown(&session, &diff_source, &comment_types(), true);
+        let markdown = generate_markdown(&session, &[], &diff_source, &comment_types(), true);
 
         // then
         assert!(markdown.contains("`src/main.rs:~30`"));
@@ -859,7 +1029,7 @@ mod tests {
         let diff_source = DiffSource::WorkingTree;
 
         // when
-        let markdown = generate_markdown(&session, &diff_source, &comment_types(), true);
+        let markdown = generate_markdown(&session, &[], &diff_source, &comment_types(), true);
 
         // then
         assert!(markdown.contains("`src/main.rs:50`"));
@@ -870,7 +1040,7 @@ mod tests {
         let session = create_test_session();
         let diff_source = DiffSource::WorkingTree;
 
-        let markdown = generate_markdown(&session, &diff_source, &comment_types(), false);
+        let markdown = generate_markdown(&session, &[], &diff_source, &comment_types(), false);
 
         assert!(!markdown.contains("Comment types:"));
         assert!(markdown.contains("[SUGGESTION]"));
@@ -894,8 +1064,13 @@ mod tests {
             ));
         }
 
-        let markdown =
-            generate_markdown(&session, &DiffSource::WorkingTree, &comment_types(), true);
+        let markdown = generate_markdown(
+            &session,
+            &[],
+            &DiffSource::WorkingTree,
+            &comment_types(),
+            true,
+        );
 
         assert!(markdown.contains("Comment types: PRAISE (positive feedback)"));
         assert!(!markdown.contains("NOTE"));
@@ -935,9 +1110,171 @@ mod tests {
             },
         ];
 
-        let markdown = generate_markdown(&session, &DiffSource::WorkingTree, &custom_types, true);
+        let markdown =
+            generate_markdown(&session, &[], &DiffSource::WorkingTree, &custom_types, true);
 
         assert!(markdown.contains("Comment types: QUESTION (ask for clarification)"));
         assert!(!markdown.contains("ISSUE"));
     }
+
+    #[test]
+    fn should_include_source_snippets_for_line_comments() {
+        let line = |origin, content: &str, old_lineno, new_lineno| DiffLine {
+            origin,
+            content: content.to_string(),
+            old_lineno,
+            new_lineno,
+            highlighted_spans: None,
+            whitespace_only: false,
+            move_id: None,
+        };
+        let mut file = DiffFile {
+            old_path: None,
+            new_path: Some(PathBuf::from("src/main.rs")),
+            status: FileStatus::Modified,
+            hunks: Vec::new(),
+            is_binary: false,
+            binary_meta: None,
+            is_too_large: false,
+            truncated_lines: 0,
+            is_commit_message: false,
+            is_submodule: false,
+            old_mode: None,
+            new_mode: None,
+            is_symlink: false,
+            is_conflicted: false,
+            has_conflicts: false,
+            content_hash: 0,
+            highlighted: false,
+            is_generated: false,
+        };
+        file.hunks.push(DiffHunk {
+            header: "@@ -40,3 +40,3 @@".to_string(),
+            lines: vec![
+                line(LineOrigin::Context, "fn run() {", Some(40), Some(40)),
+                line(
+                    LineOrigin::Context,
+                    "    let s = \"```\";",
+                    Some(41),
+                    Some(41),
+                ),
+                line(LineOrigin::Deletion, "    sleep(30);", Some(42), None),
+                line(LineOrigin::Addition, "    sleep(42);", None, Some(42)),
+            ],
+            old_start: 40,
+            old_count: 3,
+            new_start: 40,
+            new_count: 3,
+        });
+
+        let mut session = create_test_session();
+        let review = session.get_file_mut(&PathBuf::from("src/main.rs")).unwrap();
+        review.add_line_comment(
+            42,
+            Comment::new(
+                "Was 30 before".to_string(),
+                CommentType::Note,
+                Some(LineSide::Old),
+            ),
+        );
+        let mut range_comment = Comment::new(
+            "Extract this".to_string(),
+            CommentType::Suggestion,
+            Some(LineSide::New),
+        );
+        range_comment.line_range = Some(LineRange::new(41, 42));
+        review.add_line_comment(42, range_comment);
+
+        let markdown = generate_markdown(
+            &session,
+            &[file],
+            &DiffSource::WorkingTree,
+            &comment_types(),
+            false,
+        );
+
+        // File comments still export without a snippet
+        assert!(
+            markdown
+                .contains("1. **[SUGGESTION]** `src/main.rs` - Consider adding documentation\n2. ")
+        );
+        assert!(markdown.contains(
+            "`src/main.rs:42` - Magic number should be a constant\n   ```rs\n       sleep(42);\n   ```\n"
+        ));
+        assert!(
+            markdown.contains(
+                "`src/main.rs:~42` - Was 30 before\n   ```rs\n       sleep(30);\n   ```\n"
+            )
+        );
+        assert!(markdown.contains(
+            "`src/main.rs:41-42` - Extract this\n   ````rs\n       let s = \"```\";\n       sleep(42);\n   ````\n"
+        ));
+    }
+
+    #[test]
+    fn should_include_comment_authors_in_exports() {
+        let mut session = create_test_session();
+        let review = session.get_file_mut(&PathBuf::from("src/main.rs")).unwrap();
+        review.file_comments[0].author = Some("alice".to_string());
+
+        let markdown = generate_markdown(
+            &session,
+            &[],
+            &DiffSource::WorkingTree,
+            &comment_types(),
+            false,
+        );
+        assert!(markdown.contains(
+            "1. **[SUGGESTION]** `src/main.rs` (by alice) - Consider adding documentation"
+        ));
+        assert!(markdown.contains("2. **[ISSUE]** `src/main.rs:42` - Magic number"));
+
+        let text = generate_text(&session, &DiffSource::WorkingTree, &comment_types());
+        assert!(
+            text.contains("src/main.rs: [SUGGESTION] (by alice) Consider adding documentation")
+        );
+    }
+
+    #[test]
+    fn should_generate_checklist_with_summary() {
+        let mut session = ReviewSession::new(
+            PathBuf::from("/tmp/test-repo"),
+            "abc1234def".to_string(),
+            Some("main".to_string()),
+            SessionDiffSource::WorkingTree,
+        );
+        let file = |path: &str| DiffFile {
+            old_path: None,
+            new_path: Some(PathBuf::from(path)),
+            status: FileStatus::Modified,
+            hunks: Vec::new(),
+            is_binary: false,
+            binary_meta: None,
+            is_too_large: false,
+            truncated_lines: 0,
+            is_commit_message: false,
+            is_submodule: false,
+            old_mode: None,
+            new_mode: None,
+            is_symlink: false,
+            is_conflicted: false,
+            has_conflicts: false,
+            content_hash: 0,
+            highlighted: false,
+            is_generated: false,
+        };
+        let files = vec![file("src/main.rs"), file("src/lib.rs"), file("README.md")];
+        for f in &files {
+            session.add_file(f.display_path().clone(), FileStatus::Modified, 0);
+        }
+        session
+            .get_file_mut(&PathBuf::from("src/lib.rs"))
+            .unwrap()
+            .reviewed = true;
+
+        assert_eq!(
+            generate_checklist(&files, &session),
+            "- [ ] src/main.rs\n- [x] src/lib.rs\n- [ ] README.md\n\n1/3 files reviewed, 2 pending"
+        );
+    }
 }
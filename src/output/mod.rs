@@ -1,3 +1,34 @@
+pub mod github;
 pub mod markdown;
+pub mod rdjson;
+pub mod summary;
 
-pub use markdown::{export_to_clipboard, generate_export_content};
+pub use markdown::{
+    copy_to_clipboard, export_to_clipboard, generate_checklist, generate_export_content,
+    generate_text,
+};
+pub use summary::generate_summary;
+
+/// Output format of `tuicr export`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportFormat {
+    Text,
+    #[default]
+    Markdown,
+    Json,
+    Rdjson,
+}
+
+impl ExportFormat {
+    pub const VALID_VALUES: &str = "text, markdown, json, rdjson";
+
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "text" => Some(Self::Text),
+            "markdown" | "md" => Some(Self::Markdown),
+            "json" => Some(Self::Json),
+            "rdjson" => Some(Self::Rdjson),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,268 @@
+//! Export a review session in reviewdog's Diagnostic Format (RDJSON).
+//!
+//! See <https://github.com/reviewdog/reviewdog/tree/master/proto/rdf>.
+
+use serde::Serialize;
+
+use super::markdown::format_author;
+use crate::error::{Result, TuicrError};
+use crate::model::{Comment, CommentType, LineRange, LineSide, ReviewSession};
+use crate::suggestion::parse_suggestion;
+
+#[derive(Debug, Serialize)]
+pub struct DiagnosticResult {
+    pub source: Source,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Source {
+    pub name: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Diagnostic {
+    pub message: String,
+    pub location: Location,
+    pub severity: &'static str,
+    pub code: Code,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub suggestions: Vec<Suggestion>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Location {
+    pub path: String,
+    pub range: Range,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Range {
+    pub start: Position,
+    pub end: Position,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Position {
+    pub line: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Code {
+    pub value: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Suggestion {
+    pub range: Range,
+    pub text: String,
+}
+
+impl Range {
+    fn lines(range: LineRange) -> Self {
+        Self {
+            start: Position {
+                line: range.start,
+                column: None,
+            },
+            end: Position {
+                line: range.end,
+                column: None,
+            },
+        }
+    }
+}
+
+fn severity(comment_type: &CommentType) -> &'static str {
+    match comment_type {
+        CommentType::Issue => "ERROR",
+        CommentType::Suggestion => "WARNING",
+        CommentType::Note | CommentType::Praise | CommentType::Custom(_) => "INFO",
+    }
+}
+
+fn diagnostic(path: &str, comment: &Comment, message: String, range: LineRange) -> Diagnostic {
+    Diagnostic {
+        message: message + &format_author(comment.author.as_deref()),
+        location: Location {
+            path: path.to_string(),
+            range: Range::lines(range),
+        },
+        severity: severity(&comment.comment_type),
+        code: Code {
+            value: comment.comment_type.id().to_string(),
+        },
+        suggestions: Vec::new(),
+    }
+}
+
+/// Convert the file and line comments of the session into reviewdog diagnostics.
+///
+/// File comments and comments on deleted lines have no line in the new file,
+/// so they are reported on line 1 with a `[file]` or `[old Lx]` prefix.
+/// Review-level comments have no location and are not exported.
+pub fn build_rdjson(session: &ReviewSession) -> Result<DiagnosticResult> {
+    let mut files: Vec<_> = session.files.iter().collect();
+    files.sort_by_key(|(path, _)| path.to_string_lossy().to_string());
+
+    let mut diagnostics = Vec::new();
+    for (path, review) in files {
+        let path_str = path.to_string_lossy().replace('\\', "/");
+
+        for comment in &review.file_comments {
+            let message = format!("[file] {}", comment.content);
+            diagnostics.push(diagnostic(
+                &path_str,
+                comment,
+                message,
+                LineRange::single(1),
+            ));
+        }
+
+        let mut line_comments: Vec<_> = review.line_comments.iter().collect();
+        line_comments.sort_by_key(|(line, _)| **line);
+        for (line, comments) in line_comments {
+            for comment in comments {
+                let range = comment.line_range.unwrap_or(LineRange::single(*line));
+                if comment.side == Some(LineSide::Old) {
+                    let lines = if range.is_single() {
+                        format!("L{}", range.start)
+                    } else {
+                        format!("L{}-L{}", range.start, range.end)
+                    };
+                    let message = format!("[old {lines}] {}", comment.content);
+                    diagnostics.push(diagnostic(
+                        &path_str,
+                        comment,
+                        message,
+                        LineRange::single(1),
+                    ));
+                    continue;
+                }
+
+                let mut diagnostic = diagnostic(&path_str, comment, comment.content.clone(), range);
+                if let Some(lines) = parse_suggestion(&comment.content) {
+                    // Replace whole lines: from the start of the first line to
+                    // the start of the line after the range
+                    diagnostic.suggestions.push(Suggestion {
+                        range: Range {
+                            start: Position {
+                                line: range.start,
+                                column: Some(1),
+                            },
+                            end: Position {
+                                line: range.end + 1,
+                                column: Some(1),
+                            },
+                        },
+                        text: lines.iter().map(|l| format!("{l}\n")).collect(),
+                    });
+                }
+                diagnostics.push(diagnostic);
+            }
+        }
+    }
+
+    if diagnostics.is_empty() {
+        return Err(TuicrError::NoComments);
+    }
+
+    Ok(DiagnosticResult {
+        source: Source { name: "tuicr" },
+        diagnostics,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{FileStatus, SessionDiffSource};
+    use std::path::PathBuf;
+
+    fn golden_session() -> ReviewSession {
+        let mut session = ReviewSession::new(
+            PathBuf::from("/tmp/test-repo"),
+            "abc1234def".to_string(),
+            Some("main".to_string()),
+            SessionDiffSource::WorkingTree,
+        );
+        session.review_comments.push(Comment::new(
+            "Looks good overall".to_string(),
+            CommentType::Praise,
+            None,
+        ));
+
+        let lib = PathBuf::from("src/lib.rs");
+        session.add_file(lib.clone(), FileStatus::Modified, 0);
+        let review = session.get_file_mut(&lib).unwrap();
+        review.add_file_comment(Comment::new(
+            "Missing module docs".to_string(),
+            CommentType::Note,
+            None,
+        ));
+        review.add_line_comment(
+            12,
+            Comment::new_with_range(
+                "Use a constant\n```suggestion\nlet limit = MAX_LIMIT;\n```".to_string(),
+                CommentType::Suggestion,
+                Some(LineSide::New),
+                LineRange::new(11, 12),
+            ),
+        );
+        review.add_line_comment(
+            3,
+            Comment::new(
+                "This panics on empty input".to_string(),
+                CommentType::Issue,
+                Some(LineSide::New),
+            ),
+        );
+        review.add_line_comment(
+            5,
+            Comment::new(
+                "Why was this removed?".to_string(),
+                CommentType::Custom("question".to_string()),
+                Some(LineSide::Old),
+            ),
+        );
+
+        let main = PathBuf::from("src/main.rs");
+        session.add_file(main.clone(), FileStatus::Added, 0);
+        session.get_file_mut(&main).unwrap().add_line_comment(
+            1,
+            Comment::new("Nice entry point".to_string(), CommentType::Praise, None),
+        );
+        session
+    }
+
+    #[test]
+    fn should_match_golden_rdjson() {
+        let result = build_rdjson(&golden_session()).unwrap();
+
+        let actual = serde_json::to_value(&result).unwrap();
+        let expected: serde_json::Value =
+            serde_json::from_str(include_str!("testdata/review.rdjson")).unwrap();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn should_map_comment_types_to_severity() {
+        assert_eq!(severity(&CommentType::Issue), "ERROR");
+        assert_eq!(severity(&CommentType::Suggestion), "WARNING");
+        assert_eq!(severity(&CommentType::Note), "INFO");
+        assert_eq!(severity(&CommentType::Praise), "INFO");
+    }
+
+    #[test]
+    fn should_fail_without_file_or_line_comments() {
+        let mut session = golden_session();
+        session.files.clear();
+
+        assert!(matches!(
+            build_rdjson(&session),
+            Err(TuicrError::NoComments)
+        ));
+    }
+}
@@ -0,0 +1,352 @@
+//! `:summary`: a short wrap-up of the review to paste into a PR or commit
+//! message, as opposed to the full comment export.
+
+use std::fmt::Write;
+
+use super::markdown::{export_comment_type_label, format_location, review_scope_label};
+use crate::app::{CommentTypeDefinition, DiffSource};
+use crate::model::{Comment, CommentType, DiffFile, LineRange, ReviewSession};
+
+/// An issue comment and where it was left.
+struct Issue<'a> {
+    location: String,
+    comment: &'a Comment,
+}
+
+/// Comment counts per type, the issues raised, the files reviewed without
+/// comments and a verdict. With `markdown`, sections get headings and
+/// locations are set as code.
+pub fn generate_summary(
+    session: &ReviewSession,
+    diff_files: &[DiffFile],
+    diff_source: &DiffSource,
+    comment_types: &[CommentTypeDefinition],
+    markdown: bool,
+) -> String {
+    let mut comments: Vec<(String, &Comment)> = session
+        .review_comments
+        .iter()
+        .map(|comment| (review_scope_label(diff_source), comment))
+        .collect();
+    let mut files: Vec<_> = session.files.iter().collect();
+    files.sort_by_key(|(path, _)| path.to_string_lossy().to_string());
+    for (path, review) in files {
+        let path = path.display().to_string();
+        comments.extend(
+            review
+                .file_comments
+                .iter()
+                .map(|comment| (path.clone(), comment)),
+        );
+        let mut line_comments: Vec<_> = review.line_comments.iter().collect();
+        line_comments.sort_by_key(|(line, _)| *line);
+        for (line, thread) in line_comments {
+            for comment in thread {
+                let range = comment.line_range.unwrap_or(LineRange::single(*line));
+                comments.push((format_location(&path, Some(range), comment.side), comment));
+            }
+        }
+    }
+
+    // Counts in the configured type order, then any types no longer configured
+    let mut counts: Vec<(String, usize)> = Vec::new();
+    for (_, comment) in &comments {
+        let label = export_comment_type_label(&comment.comment_type, comment_types);
+        match counts.iter_mut().find(|(existing, _)| *existing == label) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((label, 1)),
+        }
+    }
+    let type_order = |label: &String| {
+        comment_types
+            .iter()
+            .position(|definition| definition.label.to_ascii_uppercase() == *label)
+            .unwrap_or(usize::MAX)
+    };
+    counts.sort_by_key(|(label, _)| type_order(label));
+
+    // Replies discuss an issue rather than raise a new one
+    let issues: Vec<Issue> = comments
+        .iter()
+        .filter(|(_, comment)| {
+            comment.comment_type == CommentType::Issue && comment.parent_id.is_none()
+        })
+        .map(|(location, comment)| Issue {
+            location: location.clone(),
+            comment,
+        })
+        .collect();
+    let unresolved = issues
+        .iter()
+        .filter(|issue| !issue.comment.resolved)
+        .count();
+
+    let uncommented: Vec<String> = diff_files
+        .iter()
+        .filter(|file| !file.is_commit_message)
+        .map(|file| file.display_path())
+        .filter(|path| {
+            session
+                .files
+                .get(*path)
+                .is_some_and(|review| review.reviewed && review.comment_count() == 0)
+        })
+        .map(|path| path.display().to_string())
+        .collect();
+
+    let code = |text: &str| {
+        if markdown {
+            format!("`{text}`")
+        } else {
+            text.to_string()
+        }
+    };
+    let heading = |title: &str| {
+        if markdown {
+            format!("### {title}")
+        } else {
+            format!("{title}:")
+        }
+    };
+
+    let mut out = String::new();
+    if markdown {
+        out.push_str("## Review summary\n\n");
+    }
+    let noun = if comments.len() == 1 {
+        "comment"
+    } else {
+        "comments"
+    };
+    let _ = write!(out, "{} {noun}", comments.len());
+    if !counts.is_empty() {
+        let breakdown: Vec<String> = counts
+            .iter()
+            .map(|(label, count)| format!("{count} {label}"))
+            .collect();
+        let _ = write!(out, " ({})", breakdown.join(", "));
+    }
+    out.push('\n');
+
+    if !issues.is_empty() {
+        let _ = writeln!(out, "\n{}", heading("Issues"));
+        for issue in &issues {
+            let resolved = if issue.comment.resolved {
+                " (resolved)"
+            } else {
+                ""
+            };
+            let _ = writeln!(
+                out,
+                "- {} {}{resolved}",
+                code(&issue.location),
+                first_sentence(&issue.comment.content)
+            );
+        }
+    }
+
+    if !uncommented.is_empty() {
+        let _ = writeln!(out, "\n{}", heading("Reviewed without comments"));
+        for path in &uncommented {
+            let _ = writeln!(out, "- {}", code(path));
+        }
+    }
+
+    let verdict = match unresolved {
+        0 => "Approved".to_string(),
+        1 => "Changes requested (1 unresolved issue)".to_string(),
+        n => format!("Changes requested ({n} unresolved issues)"),
+    };
+    if markdown {
+        let _ = write!(out, "\n**Verdict:** {verdict}");
+    } else {
+        let _ = write!(out, "\nVerdict: {verdict}");
+    }
+    out
+}
+
+/// The comment's first sentence, or its first line when that ends first.
+fn first_sentence(content: &str) -> &str {
+    let line = content.lines().next().unwrap_or_default().trim();
+    line.char_indices()
+        .find(|&(idx, ch)| {
+            matches!(ch, '.' | '!' | '?')
+                && line[idx + ch.len_utf8()..].starts_with(char::is_whitespace)
+        })
+        .map_or(line, |(idx, ch)| &line[..idx + ch.len_utf8()])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{FileStatus, LineSide, SessionDiffSource};
+    use std::path::PathBuf;
+
+    fn comment_types() -> Vec<CommentTypeDefinition> {
+        ["note", "suggestion", "issue", "praise"]
+            .into_iter()
+            .map(|id| CommentTypeDefinition {
+                id: id.to_string(),
+                label: id.to_string(),
+                definition: None,
+                color: None,
+            })
+            .collect()
+    }
+
+    fn diff_file(path: &str) -> DiffFile {
+        DiffFile {
+            old_path: None,
+            new_path: Some(PathBuf::from(path)),
+            status: FileStatus::Modified,
+            hunks: Vec::new(),
+            is_binary: false,
+            binary_meta: None,
+            is_too_large: false,
+            truncated_lines: 0,
+            is_commit_message: false,
+            is_submodule: false,
+            old_mode: None,
+            new_mode: None,
+            is_symlink: false,
+            is_conflicted: false,
+            has_conflicts: false,
+            content_hash: 0,
+            highlighted: false,
+            is_generated: false,
+        }
+    }
+
+    /// One comment of every type, a resolved issue, a reply to an issue,
+    /// and one reviewed file without comments.
+    fn fixture() -> (ReviewSession, Vec<DiffFile>) {
+        let mut session = ReviewSession::new(
+            PathBuf::from("/tmp/test-repo"),
+            "abc1234def".to_string(),
+            Some("main".to_string()),
+            SessionDiffSource::WorkingTree,
+        );
+        let files = vec![
+            diff_file("src/main.rs"),
+            diff_file("src/lib.rs"),
+            diff_file("README.md"),
+        ];
+        for file in &files {
+            session.add_file(file.display_path().clone(), FileStatus::Modified, 0);
+        }
+        session.review_comments.push(Comment::new(
+            "Nice split into modules.".to_string(),
+            CommentType::Praise,
+            None,
+        ));
+
+        let main = session.get_file_mut(&PathBuf::from("src/main.rs")).unwrap();
+        main.add_file_comment(Comment::new(
+            "Consider adding docs".to_string(),
+            CommentType::Suggestion,
+            None,
+        ));
+        let issue = Comment::new(
+            "Magic number should be a constant. It is used twice.".to_string(),
+            CommentType::Issue,
+            Some(LineSide::New),
+        );
+        let mut reply = Comment::new("Agreed".to_string(), CommentType::Issue, None);
+        reply.parent_id = Some(issue.id.clone());
+        main.add_line_comment(42, issue);
+        main.add_line_comment(42, reply);
+
+        let lib = session.get_file_mut(&PathBuf::from("src/lib.rs")).unwrap();
+        let mut resolved = Comment::new(
+            "Unwrap can panic here!\nUse ? instead.".to_string(),
+            CommentType::Issue,
+            Some(LineSide::Old),
+        );
+        resolved.resolved = true;
+        lib.add_line_comment(7, resolved);
+        lib.add_line_comment(
+            9,
+            Comment::new(
+                "Checked the edge cases".to_string(),
+                CommentType::Note,
+                Some(LineSide::New),
+            ),
+        );
+        lib.add_file_comment(Comment::new(
+            "Ask the team".to_string(),
+            CommentType::Custom("question".to_string()),
+            None,
+        ));
+        lib.reviewed = true;
+
+        session
+            .get_file_mut(&PathBuf::from("README.md"))
+            .unwrap()
+            .reviewed = true;
+        (session, files)
+    }
+
+    #[test]
+    fn should_summarize_counts_issues_and_uncommented_files() {
+        let (session, files) = fixture();
+
+        let summary = generate_summary(
+            &session,
+            &files,
+            &DiffSource::WorkingTree,
+            &comment_types(),
+            false,
+        );
+
+        assert_eq!(
+            summary,
+            "7 comments (1 NOTE, 1 SUGGESTION, 3 ISSUE, 1 PRAISE, 1 QUESTION)\n\
+             \n\
+             Issues:\n\
+             - src/lib.rs:~7 Unwrap can panic here! (resolved)\n\
+             - src/main.rs:42 Magic number should be a constant.\n\
+             \n\
+             Reviewed without comments:\n\
+             - README.md\n\
+             \n\
+             Verdict: Changes requested (1 unresolved issue)"
+        );
+    }
+
+    #[test]
+    fn should_wrap_the_summary_in_markdown_headings() {
+        let (mut session, files) = fixture();
+        for comment in session
+            .get_file_mut(&PathBuf::from("src/main.rs"))
+            .unwrap()
+            .line_comments
+            .values_mut()
+            .flatten()
+        {
+            comment.resolved = true;
+        }
+
+        let summary = generate_summary(
+            &session,
+            &files,
+            &DiffSource::WorkingTree,
+            &comment_types(),
+            true,
+        );
+
+        assert!(summary.starts_with("## Review summary\n\n7 comments ("));
+        assert!(
+            summary.contains("### Issues\n- `src/lib.rs:~7` Unwrap can panic here! (resolved)\n")
+        );
+        assert!(summary.contains("### Reviewed without comments\n- `README.md`\n"));
+        assert!(summary.ends_with("\n**Verdict:** Approved"));
+    }
+
+    #[test]
+    fn should_cut_comments_at_their_first_sentence() {
+        assert_eq!(first_sentence("Fix this. Then that."), "Fix this.");
+        assert_eq!(first_sentence("Use v1.2 here"), "Use v1.2 here");
+        assert_eq!(first_sentence("  Why?\nBecause."), "Why?");
+        assert_eq!(first_sentence(""), "");
+    }
+}
@@ -1,3 +1,5 @@
 pub mod storage;
 
-pub use storage::{load_latest_session_for_context, save_session};
+pub use storage::{
+    delete_session, list_sessions_for_repo, load_latest_session_for_context, save_session,
+};
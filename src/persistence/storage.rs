@@ -1,5 +1,6 @@
 use directories::ProjectDirs;
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime};
 
@@ -166,11 +167,30 @@ pub fn save_session(session: &ReviewSession) -> Result<PathBuf> {
     let path = reviews_dir.join(&filename);
 
     let json = serde_json::to_string_pretty(session)?;
-    fs::write(&path, json)?;
+    write_atomic(&path, json.as_bytes())?;
 
     Ok(path)
 }
 
+/// Write `contents` to a temporary file next to `path` and rename it into
+/// place, so a crash mid-write never leaves a truncated session behind.
+fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(format!(".{}.tmp", std::process::id()));
+    let tmp_path = path.with_file_name(tmp_name);
+
+    let written = fs::File::create(&tmp_path).and_then(|mut file| {
+        file.write_all(contents)?;
+        file.sync_all()
+    });
+    if let Err(e) = written.and_then(|()| fs::rename(&tmp_path, path)) {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(e.into());
+    }
+
+    Ok(())
+}
+
 pub fn load_session(path: &PathBuf) -> Result<ReviewSession> {
     let contents = fs::read_to_string(path)?;
     let session: ReviewSession =
@@ -314,14 +334,59 @@ pub fn load_latest_session_for_context(
     Ok(legacy_candidate)
 }
 
-#[cfg(test)]
-fn delete_session(path: &PathBuf) -> Result<()> {
+/// List every saved session for the repository, most recently updated first.
+pub fn list_sessions_for_repo(repo_path: &Path) -> Result<Vec<(PathBuf, ReviewSession)>> {
+    let current_repo_path = normalize_repo_path(repo_path);
+    let current_fingerprint = repo_path_fingerprint(repo_path);
+    let reviews_dir = get_reviews_dir()?;
+
+    let mut sessions: Vec<(PathBuf, ReviewSession)> = fs::read_dir(&reviews_dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            if !path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+            {
+                return false;
+            }
+
+            // Skip files that are clearly for another repo without parsing them
+            let Some(filename) = path.file_name().and_then(|f| f.to_str()) else {
+                return false;
+            };
+            match parse_session_filename(filename) {
+                Some(parts) => parts
+                    .repo_fingerprints
+                    .iter()
+                    .any(|fingerprint| fingerprint == &current_fingerprint),
+                None => true,
+            }
+        })
+        .filter_map(|path| {
+            let session = load_session(&path).ok()?;
+            (normalize_repo_path(&session.repo_path) == current_repo_path)
+                .then_some((path, session))
+        })
+        .collect();
+
+    sessions.sort_by(|(a_path, a), (b_path, b)| {
+        b.updated_at
+            .cmp(&a.updated_at)
+            .then_with(|| a_path.cmp(b_path))
+    });
+
+    Ok(sessions)
+}
+
+pub fn delete_session(path: &Path) -> Result<()> {
     fs::remove_file(path)?;
     Ok(())
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
     use crate::model::FileStatus;
     use std::path::PathBuf;
@@ -344,7 +409,7 @@ mod tests {
 
     static TEST_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
 
-    struct TestReviewsDirGuard<'a> {
+    pub(crate) struct TestReviewsDirGuard<'a> {
         _lock: std::sync::MutexGuard<'a, ()>,
         path: PathBuf,
     }
@@ -358,7 +423,7 @@ mod tests {
         }
     }
 
-    fn with_test_reviews_dir() -> TestReviewsDirGuard<'static> {
+    pub(crate) fn with_test_reviews_dir() -> TestReviewsDirGuard<'static> {
         let lock = TEST_LOCK.get_or_init(|| Mutex::new(())).lock().unwrap();
         let path =
             std::env::temp_dir().join(format!("tuicr-reviews-test-{}", uuid::Uuid::new_v4()));
@@ -474,6 +539,53 @@ mod tests {
         let _ = delete_session(&path);
     }
 
+    #[test]
+    fn should_roundtrip_reviewed_hunks_when_hunk_indices_shift() {
+        use crate::model::{DiffHunk, HunkKey};
+
+        let _guard = with_test_reviews_dir();
+        let hunk = |old_start: u32, new_start: u32, heading: &str| DiffHunk {
+            header: format!("@@ -{old_start},3 +{new_start},3 @@ {heading}"),
+            lines: Vec::new(),
+            old_start,
+            old_count: 3,
+            new_start,
+            new_count: 3,
+        };
+        let mut session = create_test_session();
+        let path = PathBuf::from("src/main.rs");
+        session
+            .get_file_mut(&path)
+            .unwrap()
+            .set_hunk_reviewed(&HunkKey::of(&hunk(40, 40, "fn b()")), true);
+
+        let saved = save_session(&session).unwrap();
+        let loaded = load_session(&saved).unwrap();
+        let review = &loaded.files[&path];
+
+        // A hunk added above moves `fn b` to a later index and new-side line
+        let reloaded = [
+            hunk(1, 1, ""),
+            hunk(10, 15, "fn a()"),
+            hunk(40, 45, "fn b()"),
+        ];
+        let reviewed: Vec<bool> = reloaded
+            .iter()
+            .map(|hunk| review.is_hunk_reviewed(hunk))
+            .collect();
+        assert_eq!(reviewed, [false, false, true]);
+
+        // Sessions from before hunk review load with no hunks reviewed
+        let mut value = serde_json::to_value(&session).unwrap();
+        value["files"]["src/main.rs"]
+            .as_object_mut()
+            .unwrap()
+            .remove("reviewed_hunks");
+        let legacy: ReviewSession = serde_json::from_value(value).unwrap();
+        assert!(legacy.files[&path].reviewed_hunks.is_empty());
+        let _ = delete_session(&saved);
+    }
+
     #[test]
     fn should_sanitize_branch_name_in_filename() {
         let session = create_session(
@@ -878,6 +990,101 @@ mod tests {
         assert!(loaded.is_none());
     }
 
+    #[test]
+    fn should_replace_file_contents_atomically() {
+        let _guard = with_test_reviews_dir();
+        let dir = get_reviews_dir().unwrap();
+        let path = dir.join("session.json");
+        fs::write(&path, "old").unwrap();
+
+        write_atomic(&path, b"new").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        let leftovers: Vec<_> = fs::read_dir(&dir)
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path() != path)
+            .collect();
+        assert!(leftovers.is_empty());
+    }
+
+    #[test]
+    fn should_clean_up_temp_file_when_rename_fails() {
+        let _guard = with_test_reviews_dir();
+        let dir = get_reviews_dir().unwrap();
+        // Renaming a file over a non-empty directory fails on every platform
+        let target = dir.join("occupied");
+        fs::create_dir_all(target.join("child")).unwrap();
+
+        assert!(write_atomic(&target, b"data").is_err());
+
+        let entries: Vec<_> = fs::read_dir(&dir)
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .collect();
+        assert_eq!(entries, vec![target]);
+    }
+
+    #[test]
+    fn should_list_all_sessions_for_repo_newest_first() {
+        let _guard = with_test_reviews_dir();
+        let base = std::env::temp_dir().join(format!("tuicr-repos-{}", uuid::Uuid::new_v4()));
+        let repo = base.join("repo");
+        let other = base.join("other");
+        fs::create_dir_all(&repo).unwrap();
+        fs::create_dir_all(&other).unwrap();
+
+        let mut older = create_session(
+            repo.clone(),
+            "head-1",
+            Some("main"),
+            SessionDiffSource::WorkingTree,
+            None,
+        );
+        older.updated_at -= chrono::Duration::hours(1);
+        let _ = save_session(&older).unwrap();
+        let commits = create_session(
+            repo.clone(),
+            "head-2",
+            Some("feature"),
+            SessionDiffSource::CommitRange,
+            Some(vec!["commit-1".to_string()]),
+        );
+        let _ = save_session(&commits).unwrap();
+        let foreign = create_session(
+            other,
+            "head-3",
+            Some("main"),
+            SessionDiffSource::WorkingTree,
+            None,
+        );
+        let _ = save_session(&foreign).unwrap();
+
+        let sessions = list_sessions_for_repo(&repo).unwrap();
+        let ids: Vec<&str> = sessions.iter().map(|(_, s)| s.id.as_str()).collect();
+        assert_eq!(ids, vec![commits.id.as_str(), older.id.as_str()]);
+    }
+
+    #[test]
+    fn should_delete_listed_session() {
+        let _guard = with_test_reviews_dir();
+        let repo_path = std::env::temp_dir().join(format!("tuicr-repo-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&repo_path).unwrap();
+
+        let session = create_session(
+            repo_path.clone(),
+            "head",
+            Some("main"),
+            SessionDiffSource::WorkingTree,
+            None,
+        );
+        let path = save_session(&session).unwrap();
+        delete_session(&path).unwrap();
+
+        assert!(list_sessions_for_repo(&repo_path).unwrap().is_empty());
+    }
+
     #[test]
     fn should_disambiguate_repos_with_same_folder_name() {
         let _guard = with_test_reviews_dir();
@@ -0,0 +1,152 @@
+//! Applying GitHub-style ```` ```suggestion ```` blocks from comments to the working tree.
+
+use std::fs;
+use std::path::Path;
+
+use crate::error::{Result, TuicrError};
+use crate::model::LineRange;
+
+/// Extract the replacement lines of the first ```` ```suggestion ```` block in `content`.
+/// An empty block suggests deleting the commented lines.
+pub fn parse_suggestion(content: &str) -> Option<Vec<String>> {
+    let mut lines = content.lines();
+    lines.find(|line| line.trim() == "```suggestion")?;
+
+    let mut block = Vec::new();
+    for line in lines {
+        if line.trim() == "```" {
+            return Some(block);
+        }
+        block.push(line.to_string());
+    }
+    // Unterminated fence
+    None
+}
+
+/// Replace `range` in `file` with `suggestion`.
+///
+/// `expected` holds the diff content of the lines in `range` that the diff
+/// showed; if any of them differ from the file, nothing is written and the
+/// conflicting line numbers are reported.
+pub fn apply_suggestion(
+    file: &Path,
+    range: LineRange,
+    expected: &[(u32, String)],
+    suggestion: &[String],
+) -> Result<()> {
+    let content = fs::read_to_string(file)?;
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+
+    let start = range.start.saturating_sub(1) as usize;
+    let end = range.end as usize;
+    if start >= end || end > lines.len() {
+        return Err(TuicrError::SuggestionConflict(format!(
+            "L{}-L{} (file has {} lines)",
+            range.start,
+            range.end,
+            lines.len()
+        )));
+    }
+
+    let conflicts: Vec<String> = expected
+        .iter()
+        .filter(|(lineno, text)| {
+            lines
+                .get(lineno.saturating_sub(1) as usize)
+                .is_none_or(|line| strip_eol(line) != strip_eol(text))
+        })
+        .map(|(lineno, _)| format!("L{lineno}"))
+        .collect();
+    if !conflicts.is_empty() {
+        return Err(TuicrError::SuggestionConflict(conflicts.join(", ")));
+    }
+
+    let eol = if content.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    // A single unindented line keeps the indentation of the line it replaces
+    let indent = match suggestion {
+        [only] if !only.starts_with(char::is_whitespace) => {
+            let first = lines[start];
+            &first[..first.len() - first.trim_start().len()]
+        }
+        _ => "",
+    };
+    let mut replacement: String = suggestion
+        .iter()
+        .map(|line| format!("{indent}{line}{eol}"))
+        .collect();
+    if end == lines.len() && !content.ends_with('\n') {
+        replacement.truncate(replacement.len().saturating_sub(eol.len()));
+    }
+
+    let output = [lines[..start].concat(), replacement, lines[end..].concat()].concat();
+    fs::write(file, output)?;
+    Ok(())
+}
+
+fn strip_eol(line: &str) -> &str {
+    line.trim_end_matches(['\n', '\r'])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_suggestion_block() {
+        let content = "Use a constant here\n```suggestion\nconst MAX: usize = 10;\n```\n";
+        assert_eq!(
+            parse_suggestion(content),
+            Some(vec!["const MAX: usize = 10;".to_string()])
+        );
+        assert_eq!(parse_suggestion("```suggestion\n```"), Some(Vec::new()));
+        assert_eq!(parse_suggestion("no block here"), None);
+        assert_eq!(parse_suggestion("```suggestion\nunterminated"), None);
+    }
+
+    #[test]
+    fn should_replace_range_and_keep_indentation() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.rs");
+        fs::write(&file, "fn main() {\n    let x = 1;\n}\n").unwrap();
+
+        apply_suggestion(
+            &file,
+            LineRange::single(2),
+            &[(2, "    let x = 1;".to_string())],
+            &["let x = 2;".to_string()],
+        )
+        .unwrap();
+
+        let content = fs::read_to_string(&file).unwrap();
+        assert_eq!(content, "fn main() {\n    let x = 2;\n}\n");
+    }
+
+    #[test]
+    fn should_refuse_when_lines_changed() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.rs");
+        fs::write(&file, "one\nedited\nthree\n").unwrap();
+
+        let result = apply_suggestion(
+            &file,
+            LineRange::new(1, 3),
+            &[
+                (1, "one".to_string()),
+                (2, "two".to_string()),
+                (3, "three".to_string()),
+            ],
+            &["replaced".to_string()],
+        );
+
+        match result {
+            Err(TuicrError::SuggestionConflict(lines)) => assert_eq!(lines, "L2"),
+            other => panic!("expected conflict, got {other:?}"),
+        }
+        let content = fs::read_to_string(&file).unwrap();
+        assert_eq!(content, "one\nedited\nthree\n");
+    }
+}
@@ -1,23 +1,56 @@
+mod pool;
+
+pub use pool::{HighlightJob, HighlightPool};
+
+use globset::{GlobBuilder, GlobMatcher};
 use ratatui::style::{Color, Modifier, Style};
 use std::path::Path;
+use std::sync::Arc;
+use syntect::parsing::SyntaxReference;
 use two_face::theme::EmbeddedThemeName;
 
 use crate::model::diff_types::LineOrigin;
+use crate::theme::ColorMode;
 
 /// A single line of highlighted spans (style + text pairs).
-type HighlightedSpans = Vec<(Style, String)>;
+pub type HighlightedSpans = Vec<(Style, String)>;
 
 /// Per-line highlight results for a file: `Some` if the line was highlighted, `None` on failure.
-type HighlightedLines = Vec<Option<HighlightedSpans>>;
+pub type HighlightedLines = Vec<Option<HighlightedSpans>>;
+
+/// `[syntax]` target that turns highlighting off for matching files.
+const NO_SYNTAX: &str = "none";
+
+/// Line count above which files are shown without highlighting, unless
+/// `max_highlight_lines` is configured.
+pub const DEFAULT_MAX_HIGHLIGHT_LINES: usize = 5000;
+
+/// A `[syntax]` config entry: files matching `pattern` use the syntax at
+/// index `syntax` in the syntax set, or aren't highlighted when it's `None`.
+struct SyntaxOverride {
+    pattern: String,
+    matcher: GlobMatcher,
+    syntax: Option<usize>,
+}
 
-/// Helper to highlight lines of code from a diff
+/// Helper to highlight lines of code from a diff. Cheap to clone, so it can
+/// be handed to background highlighting threads.
+#[derive(Clone)]
 pub struct SyntaxHighlighter {
-    pub syntax_set: syntect::parsing::SyntaxSet,
-    pub theme: syntect::highlighting::Theme,
+    pub syntax_set: Arc<syntect::parsing::SyntaxSet>,
+    pub theme: Arc<syntect::highlighting::Theme>,
     /// Background color for added lines
     pub add_bg: Color,
     /// Background color for deleted lines
     pub del_bg: Color,
+    /// When false, nothing is highlighted (monochrome mode)
+    enabled: bool,
+    /// User overrides, checked in order before the built-in lookup
+    overrides: Arc<Vec<SyntaxOverride>>,
+    /// Colors the terminal can show; syntax colors are converted to fit
+    color_mode: ColorMode,
+    /// Longer files are left plain, since highlighting them is slow
+    max_lines: usize,
 }
 
 pub(crate) struct DiffHighlightSequences {
@@ -45,17 +78,114 @@ impl SyntaxHighlighter {
         let theme = theme_set[theme_name].clone();
 
         Self {
-            syntax_set,
-            theme,
+            syntax_set: Arc::new(syntax_set),
+            theme: Arc::new(theme),
             add_bg,
             del_bg,
+            enabled: true,
+            overrides: Arc::default(),
+            color_mode: ColorMode::TrueColor,
+            max_lines: DEFAULT_MAX_HIGHLIGHT_LINES,
+        }
+    }
+
+    /// Limit highlighting and diff background colors to what `mode` can show.
+    pub fn with_color_mode(mut self, mode: ColorMode) -> Self {
+        self.color_mode = mode;
+        self.add_bg = mode.convert(self.add_bg);
+        self.del_bg = mode.convert(self.del_bg);
+        self
+    }
+
+    /// Leave files with more than `max_lines` lines unhighlighted.
+    pub fn with_max_lines(mut self, max_lines: usize) -> Self {
+        self.max_lines = max_lines;
+        self
+    }
+
+    pub fn max_lines(&self) -> usize {
+        self.max_lines
+    }
+
+    /// Add `[syntax]` overrides mapping globs to a syntax name or extension,
+    /// or to `none` to skip highlighting. Patterns without `/` match file
+    /// names and `.ext` is short for `*.ext`. Returns warnings for entries
+    /// that can't be used.
+    pub fn with_overrides(mut self, overrides: &[(String, String)]) -> (Self, Vec<String>) {
+        let mut warnings = Vec::new();
+        let mut parsed = Vec::new();
+
+        for (pattern, target) in overrides {
+            let glob = if pattern.starts_with('.') && !pattern.contains(['*', '/']) {
+                format!("*{pattern}")
+            } else {
+                pattern.clone()
+            };
+            let matcher = match GlobBuilder::new(&glob).literal_separator(true).build() {
+                Ok(glob) => glob.compile_matcher(),
+                Err(e) => {
+                    warnings.push(format!(
+                        "Warning: Invalid glob '{pattern}' in [syntax]: {e}; ignoring entry"
+                    ));
+                    continue;
+                }
+            };
+
+            let syntax = if target.eq_ignore_ascii_case(NO_SYNTAX) {
+                None
+            } else if let Some(found) = self.syntax_set.find_syntax_by_token(target) {
+                self.syntax_set
+                    .syntaxes()
+                    .iter()
+                    .position(|syntax| syntax.name == found.name)
+            } else {
+                warnings.push(format!(
+                    "Warning: Unknown syntax \"{target}\" for '{pattern}' in [syntax]; ignoring entry. Valid choices: {NO_SYNTAX}, {}",
+                    self.syntax_names().join(", ")
+                ));
+                continue;
+            };
+
+            parsed.push(SyntaxOverride {
+                pattern: glob,
+                matcher,
+                syntax,
+            });
+        }
+
+        self.overrides = Arc::new(parsed);
+        (self, warnings)
+    }
+
+    /// Names of the syntaxes overrides can refer to.
+    fn syntax_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self
+            .syntax_set
+            .syntaxes()
+            .iter()
+            .filter(|syntax| !syntax.hidden)
+            .map(|syntax| syntax.name.as_str())
+            .collect();
+        names.sort_unstable_by_key(|name| name.to_ascii_lowercase());
+        names
+    }
+
+    /// A highlighter that leaves every line unstyled, for colorless output.
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            ..Self::new(
+                EmbeddedThemeName::Base16EightiesDark,
+                Color::Reset,
+                Color::Reset,
+            )
         }
     }
 
     /// Highlight all lines in a file's content.
     ///
-    /// Returns `None` when no syntax can be resolved for the file (by path or shebang).
-    /// Otherwise returns one entry per input line:
+    /// Returns `None` when no syntax can be resolved for the file (by path or shebang),
+    /// or when it has more lines than `max_lines`. Otherwise returns one entry per input line:
     /// - `Some(spans)` if that line was highlighted successfully (including empty spans)
     /// - `None` if highlighting failed for that specific line
     pub fn highlight_file_lines(
@@ -65,12 +195,12 @@ impl SyntaxHighlighter {
     ) -> Option<HighlightedLines> {
         use syntect::easy::HighlightLines;
 
+        if !self.enabled || lines.len() > self.max_lines {
+            return None;
+        }
+
         // Get syntax definition
-        let syntax = self.get_syntax(file_path).or_else(|| {
-            lines
-                .first()
-                .and_then(|line| self.syntax_set.find_syntax_by_first_line(line))
-        })?;
+        let syntax = self.resolve_syntax(file_path, lines)?;
 
         // Create highlighter
         let mut highlighter = HighlightLines::new(syntax, &self.theme);
@@ -84,7 +214,7 @@ impl SyntaxHighlighter {
                     let mut spans: Vec<(Style, String)> = ranges
                         .into_iter()
                         .map(|(style, text)| {
-                            (Self::syntect_to_ratatui_style(style), text.to_string())
+                            (self.syntect_to_ratatui_style(style), text.to_string())
                         })
                         .collect();
                     // Strip trailing \n that syntect includes from the input.
@@ -103,6 +233,34 @@ impl SyntaxHighlighter {
         }))
     }
 
+    /// Highlight the lines of one hunk with the diff background applied,
+    /// returning one entry per line. The old and new sides are highlighted
+    /// as separate sequences so the parser state stays valid across them.
+    pub fn highlight_diff_lines(
+        &self,
+        file_path: &Path,
+        line_contents: &[String],
+        line_origins: &[LineOrigin],
+    ) -> HighlightedLines {
+        let sequences = Self::split_diff_lines_for_highlighting(line_contents, line_origins);
+        let old_highlighted_lines = self.highlight_file_lines(file_path, &sequences.old_lines);
+        let new_highlighted_lines = self.highlight_file_lines(file_path, &sequences.new_lines);
+
+        line_origins
+            .iter()
+            .enumerate()
+            .map(|(idx, &origin)| {
+                self.highlighted_line_for_diff_with_background(
+                    old_highlighted_lines.as_deref(),
+                    new_highlighted_lines.as_deref(),
+                    sequences.old_line_indices[idx],
+                    sequences.new_line_indices[idx],
+                    origin,
+                )
+            })
+            .collect()
+    }
+
     fn collect_line_highlights<F>(lines: &[String], mut highlight_line: F) -> HighlightedLines
     where
         F: FnMut(&str) -> Option<HighlightedSpans>,
@@ -185,8 +343,12 @@ impl SyntaxHighlighter {
         Some(self.apply_diff_background(spans, origin))
     }
 
-    fn syntect_to_ratatui_style(style: syntect::highlighting::Style) -> Style {
-        let fg_color = Color::Rgb(style.foreground.r, style.foreground.g, style.foreground.b);
+    fn syntect_to_ratatui_style(&self, style: syntect::highlighting::Style) -> Style {
+        let fg_color = self.color_mode.convert(Color::Rgb(
+            style.foreground.r,
+            style.foreground.g,
+            style.foreground.b,
+        ));
         let mut ratatui_style = Style::default().fg(fg_color);
 
         if style
@@ -232,10 +394,35 @@ impl SyntaxHighlighter {
         }
     }
 
+    /// Resolve the syntax of a file: `[syntax]` overrides first, then the
+    /// path via [`Self::get_syntax`], then a shebang on the first line.
+    fn resolve_syntax(&self, file_path: &Path, lines: &[String]) -> Option<&SyntaxReference> {
+        if let Some(syntax) = self.syntax_override(file_path) {
+            return syntax;
+        }
+        self.get_syntax(file_path).or_else(|| {
+            lines
+                .first()
+                .and_then(|line| self.syntax_set.find_syntax_by_first_line(line))
+        })
+    }
+
+    /// The first override matching the path: `Some(None)` when it turns
+    /// highlighting off, `None` when no override applies.
+    fn syntax_override(&self, file_path: &Path) -> Option<Option<&SyntaxReference>> {
+        let file_name = file_path.file_name();
+        let entry = self.overrides.iter().find(|entry| {
+            entry.matcher.is_match(file_path)
+                || (!entry.pattern.contains('/')
+                    && file_name.is_some_and(|name| entry.matcher.is_match(name)))
+        })?;
+        Some(entry.syntax.map(|idx| &self.syntax_set.syntaxes()[idx]))
+    }
+
     /// Resolve syntax from a file path using this lookup order:
     /// extension -> lowercase extension (when different) -> fallback extension ->
     /// filename token -> filename name -> fallback filename.
-    fn get_syntax(&self, file_path: &Path) -> Option<&syntect::parsing::SyntaxReference> {
+    fn get_syntax(&self, file_path: &Path) -> Option<&SyntaxReference> {
         // Try by extension first
         if let Some(ext) = file_path.extension().and_then(|e| e.to_str()) {
             if let Some(syntax) = self.syntax_set.find_syntax_by_extension(ext) {
@@ -330,6 +517,25 @@ mod tests {
         assert!(highlighted.iter().all(|line| line.is_some()));
     }
 
+    #[test]
+    fn should_skip_highlighting_files_over_the_line_limit() {
+        let highlighter = SyntaxHighlighter::default().with_max_lines(3);
+        let lines: Vec<String> = (0..3).map(|i| format!("let x{i} = {i};")).collect();
+
+        let highlighted = highlighter
+            .highlight_file_lines(Path::new("main.rs"), &lines)
+            .expect("files within the limit are highlighted");
+        assert!(highlighted.iter().flatten().all(|spans| !spans.is_empty()));
+
+        let mut longer = lines.clone();
+        longer.push("let x3 = 3;".to_string());
+        assert!(
+            highlighter
+                .highlight_file_lines(Path::new("main.rs"), &longer)
+                .is_none()
+        );
+    }
+
     #[test]
     fn should_keep_file_highlighting_when_one_line_fails() {
         let lines = vec!["first".to_string(), "bad".to_string(), "third".to_string()];
@@ -421,6 +627,99 @@ mod tests {
         assert_eq!(highlighted.unwrap().len(), lines.len());
     }
 
+    fn overridden(overrides: &[(&str, &str)]) -> (SyntaxHighlighter, Vec<String>) {
+        let overrides: Vec<(String, String)> = overrides
+            .iter()
+            .map(|(pattern, target)| (pattern.to_string(), target.to_string()))
+            .collect();
+        SyntaxHighlighter::default().with_overrides(&overrides)
+    }
+
+    fn syntax_name(
+        highlighter: &SyntaxHighlighter,
+        path: &str,
+        first_line: &str,
+    ) -> Option<String> {
+        highlighter
+            .resolve_syntax(Path::new(path), &[first_line.to_string()])
+            .map(|syntax| syntax.name.clone())
+    }
+
+    #[test]
+    fn should_prefer_user_overrides_then_fallbacks_then_shebang() {
+        let (highlighter, warnings) = overridden(&[
+            ("*.mdx", "rust"),
+            (".svc", "python"),
+            ("Tiltfile", "py"),
+            ("deploy/*.conf", "yaml"),
+        ]);
+        assert!(warnings.is_empty(), "{warnings:?}");
+        let shebang = "#!/usr/bin/env ruby";
+
+        // User map beats the built-in fallback and the shebang
+        assert_eq!(
+            syntax_name(&highlighter, "docs/a.mdx", shebang).as_deref(),
+            Some("Rust")
+        );
+        assert_eq!(
+            syntax_name(&highlighter, "api/x.svc", "").as_deref(),
+            Some("Python")
+        );
+        assert_eq!(
+            syntax_name(&highlighter, "Tiltfile", shebang).as_deref(),
+            Some("Python")
+        );
+        assert_eq!(
+            syntax_name(&highlighter, "deploy/app.conf", "").as_deref(),
+            Some("YAML")
+        );
+        assert_ne!(
+            syntax_name(&highlighter, "deploy/sub/app.conf", "").as_deref(),
+            Some("YAML")
+        );
+
+        // Built-in fallback beats the shebang
+        let plain = SyntaxHighlighter::default();
+        assert_eq!(
+            syntax_name(&plain, "docs/a.mdx", shebang).as_deref(),
+            Some("Markdown")
+        );
+        assert_eq!(
+            syntax_name(&plain, "script", shebang).as_deref(),
+            Some("Ruby")
+        );
+    }
+
+    #[test]
+    fn should_skip_highlighting_files_mapped_to_none() {
+        let (highlighter, warnings) = overridden(&[("*.min.js", "none")]);
+        assert!(warnings.is_empty());
+
+        let lines = vec!["var a=1;".to_string()];
+        assert!(
+            highlighter
+                .highlight_file_lines(Path::new("app.min.js"), &lines)
+                .is_none()
+        );
+        assert!(
+            highlighter
+                .highlight_file_lines(Path::new("app.js"), &lines)
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn should_warn_about_unknown_syntaxes_and_bad_globs() {
+        let (highlighter, warnings) = overridden(&[("*.svc", "klingon"), ("a[", "rust")]);
+
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].starts_with("Warning: Unknown syntax \"klingon\" for '*.svc'"));
+        assert!(warnings[0].contains("Valid choices: none, "));
+        assert!(warnings[0].contains("Rust"));
+        assert!(warnings[1].starts_with("Warning: Invalid glob 'a['"));
+        assert!(highlighter.overrides.is_empty());
+    }
+
     #[test]
     fn should_preserve_empty_line_highlight_results() {
         let lines = vec!["value".to_string(), "".to_string()];
@@ -0,0 +1,161 @@
+//! Background syntax highlighting, so opening a large diff doesn't wait for
+//! every file to be highlighted.
+
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+use super::{HighlightedLines, SyntaxHighlighter};
+use crate::model::LineOrigin;
+
+/// Upper bound on highlighting threads; more gains little for a handful of
+/// files in the viewport.
+const MAX_WORKERS: usize = 4;
+
+/// The lines of one file to highlight, identified by its path and content hash.
+pub struct HighlightJob {
+    pub path: PathBuf,
+    pub content_hash: u64,
+    /// Contents and origins of each hunk's lines
+    pub hunks: Vec<(Vec<String>, Vec<LineOrigin>)>,
+}
+
+/// Highlighted spans for each line of each hunk of a [`HighlightJob`].
+pub struct HighlightResult {
+    pub path: PathBuf,
+    pub content_hash: u64,
+    pub hunks: Vec<HighlightedLines>,
+}
+
+#[derive(Default)]
+struct Queue {
+    jobs: Vec<HighlightJob>,
+    closed: bool,
+}
+
+/// Thread pool that highlights files off the UI thread. Jobs submitted last
+/// run first, so the files currently on screen win over ones scrolled past.
+pub struct HighlightPool {
+    queue: Arc<(Mutex<Queue>, Condvar)>,
+    results: Receiver<HighlightResult>,
+}
+
+impl HighlightPool {
+    pub fn new(highlighter: SyntaxHighlighter) -> Self {
+        let queue = Arc::new((Mutex::new(Queue::default()), Condvar::new()));
+        let (results_tx, results) = mpsc::channel();
+
+        let workers = thread::available_parallelism()
+            .map_or(1, |n| n.get())
+            .min(MAX_WORKERS);
+        for _ in 0..workers {
+            let queue = Arc::clone(&queue);
+            let highlighter = highlighter.clone();
+            let results_tx = results_tx.clone();
+            thread::spawn(move || work(&queue, &highlighter, &results_tx));
+        }
+
+        Self { queue, results }
+    }
+
+    pub fn submit(&self, job: HighlightJob) {
+        let (lock, ready) = &*self.queue;
+        if let Ok(mut queue) = lock.lock() {
+            queue.jobs.push(job);
+            ready.notify_one();
+        }
+    }
+
+    /// Results finished since the last call, without blocking.
+    pub fn finished(&self) -> impl Iterator<Item = HighlightResult> + '_ {
+        self.results.try_iter()
+    }
+}
+
+impl Drop for HighlightPool {
+    fn drop(&mut self) {
+        let (lock, ready) = &*self.queue;
+        if let Ok(mut queue) = lock.lock() {
+            queue.closed = true;
+            queue.jobs.clear();
+        }
+        ready.notify_all();
+    }
+}
+
+fn work(
+    queue: &(Mutex<Queue>, Condvar),
+    highlighter: &SyntaxHighlighter,
+    results: &Sender<HighlightResult>,
+) {
+    let (lock, ready) = queue;
+    loop {
+        let job = {
+            let Ok(mut queue) = lock.lock() else {
+                return;
+            };
+            loop {
+                if queue.closed {
+                    return;
+                }
+                if let Some(job) = queue.jobs.pop() {
+                    break job;
+                }
+                queue = match ready.wait(queue) {
+                    Ok(queue) => queue,
+                    Err(_) => return,
+                };
+            }
+        };
+
+        let hunks = job
+            .hunks
+            .iter()
+            .map(|(contents, origins)| {
+                highlighter.highlight_diff_lines(&job.path, contents, origins)
+            })
+            .collect();
+        let result = HighlightResult {
+            path: job.path,
+            content_hash: job.content_hash,
+            hunks,
+        };
+        if results.send(result).is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn should_highlight_submitted_files_in_background() {
+        let pool = HighlightPool::new(SyntaxHighlighter::default());
+        pool.submit(HighlightJob {
+            path: PathBuf::from("src/main.rs"),
+            content_hash: 7,
+            hunks: vec![(
+                vec!["fn main() {".to_string(), "}".to_string()],
+                vec![LineOrigin::Context, LineOrigin::Addition],
+            )],
+        });
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let result = loop {
+            if let Some(result) = pool.finished().next() {
+                break result;
+            }
+            assert!(Instant::now() < deadline, "highlighting timed out");
+            thread::sleep(Duration::from_millis(10));
+        };
+
+        assert_eq!(result.path, PathBuf::from("src/main.rs"));
+        assert_eq!(result.content_hash, 7);
+        assert_eq!(result.hunks.len(), 1);
+        assert!(result.hunks[0].iter().all(Option::is_some));
+    }
+}
@@ -0,0 +1,215 @@
+//! Color fallbacks for terminals without 24-bit color support.
+
+use ratatui::style::Color;
+
+/// How many colors the terminal can show.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorMode {
+    #[default]
+    TrueColor,
+    /// The xterm 256-color palette
+    Ansi256,
+    /// The 16 named ANSI colors
+    Ansi16,
+}
+
+impl ColorMode {
+    /// Values accepted by the `color_mode` config key.
+    pub const VALID_VALUES: [&str; 3] = ["truecolor", "256", "16"];
+
+    pub fn from_config(value: &str) -> Option<Self> {
+        match value {
+            "truecolor" => Some(Self::TrueColor),
+            "256" => Some(Self::Ansi256),
+            "16" => Some(Self::Ansi16),
+            _ => None,
+        }
+    }
+
+    /// Nearest color this mode can show. Named colors and `Reset` are kept.
+    pub fn convert(self, color: Color) -> Color {
+        match (self, color) {
+            (Self::TrueColor, _) => color,
+            (Self::Ansi256, Color::Rgb(r, g, b)) => Color::Indexed(nearest_ansi256((r, g, b))),
+            (Self::Ansi16, Color::Rgb(r, g, b)) => nearest_ansi16((r, g, b)),
+            (Self::Ansi16, Color::Indexed(index)) => nearest_ansi16(indexed_to_rgb(index)),
+            _ => color,
+        }
+    }
+}
+
+/// Guess the color support from `COLORTERM` and `TERM`. Terminals that
+/// don't say are assumed to handle true color, as before detection existed.
+pub fn detect_color_mode(colorterm: Option<&str>, term: Option<&str>) -> ColorMode {
+    if colorterm.is_some_and(|value| matches!(value, "truecolor" | "24bit")) {
+        return ColorMode::TrueColor;
+    }
+    let Some(term) = term.filter(|term| !term.is_empty()) else {
+        return ColorMode::TrueColor;
+    };
+    if term.ends_with("-direct")
+        || matches!(
+            term,
+            "xterm-kitty" | "xterm-ghostty" | "alacritty" | "wezterm" | "foot" | "contour"
+        )
+    {
+        ColorMode::TrueColor
+    } else if term.contains("256color") {
+        ColorMode::Ansi256
+    } else {
+        ColorMode::Ansi16
+    }
+}
+
+/// Channel levels of the 6×6×6 color cube at indices 16-231.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The 16 ANSI colors with xterm's default RGB values.
+const ANSI16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let d = |a: u8, b: u8| (i32::from(a) - i32::from(b)).pow(2) as u32;
+    d(r1, r2) + d(g1, g2) + d(b1, b2)
+}
+
+/// Index of the closest cube level to a channel value.
+fn nearest_cube_level(value: u8) -> usize {
+    (0..CUBE_LEVELS.len())
+        .min_by_key(|&i| value.abs_diff(CUBE_LEVELS[i]))
+        .unwrap_or(0)
+}
+
+/// Closest entry of the color cube or the grayscale ramp (232-255). The
+/// first 16 entries are skipped since terminals theme them freely.
+fn nearest_ansi256(rgb: (u8, u8, u8)) -> u8 {
+    let (r, g, b) = (
+        nearest_cube_level(rgb.0),
+        nearest_cube_level(rgb.1),
+        nearest_cube_level(rgb.2),
+    );
+    let cube_index = 16 + 36 * r + 6 * g + b;
+    let cube_rgb = (CUBE_LEVELS[r], CUBE_LEVELS[g], CUBE_LEVELS[b]);
+
+    let average = (u32::from(rgb.0) + u32::from(rgb.1) + u32::from(rgb.2)) / 3;
+    let gray_step = (average.saturating_sub(8) + 5) / 10;
+    let gray_step = gray_step.min(23) as u8;
+    let gray_value = 8 + 10 * gray_step;
+    let gray_rgb = (gray_value, gray_value, gray_value);
+
+    if distance(rgb, gray_rgb) < distance(rgb, cube_rgb) {
+        232 + gray_step
+    } else {
+        cube_index as u8
+    }
+}
+
+fn nearest_ansi16(rgb: (u8, u8, u8)) -> Color {
+    ANSI16
+        .iter()
+        .min_by_key(|(_, entry)| distance(rgb, *entry))
+        .map_or(Color::Reset, |(color, _)| *color)
+}
+
+/// RGB value of an xterm 256-color palette entry.
+fn indexed_to_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => ANSI16[usize::from(index)].1,
+        16..=231 => {
+            let i = usize::from(index - 16);
+            (
+                CUBE_LEVELS[i / 36],
+                CUBE_LEVELS[(i / 6) % 6],
+                CUBE_LEVELS[i % 6],
+            )
+        }
+        _ => {
+            let value = 8 + 10 * (index - 232);
+            (value, value, value)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_map_palette_entries_to_nearest_256_color() {
+        let convert = |r, g, b| ColorMode::Ansi256.convert(Color::Rgb(r, g, b));
+
+        assert_eq!(convert(255, 255, 255), Color::Indexed(231));
+        assert_eq!(convert(0, 0, 0), Color::Indexed(16));
+        // Dracula pink and green
+        assert_eq!(convert(255, 121, 198), Color::Indexed(212));
+        assert_eq!(convert(80, 250, 123), Color::Indexed(84));
+        // Dark diff backgrounds land on the grayscale ramp or a dark cube entry
+        assert_eq!(convert(0, 35, 12), Color::Indexed(233));
+        assert_eq!(convert(40, 40, 40), Color::Indexed(235));
+        assert_eq!(convert(0, 95, 0), Color::Indexed(22));
+
+        assert_eq!(ColorMode::Ansi256.convert(Color::Cyan), Color::Cyan);
+        assert_eq!(ColorMode::Ansi256.convert(Color::Reset), Color::Reset);
+    }
+
+    #[test]
+    fn should_map_palette_entries_to_nearest_16_color() {
+        let convert = |r, g, b| ColorMode::Ansi16.convert(Color::Rgb(r, g, b));
+
+        assert_eq!(convert(255, 85, 85), Color::LightRed);
+        assert_eq!(convert(60, 240, 60), Color::LightGreen);
+        assert_eq!(convert(40, 200, 210), Color::Cyan);
+        assert_eq!(convert(98, 114, 164), Color::DarkGray);
+        assert_eq!(convert(0, 35, 12), Color::Black);
+        assert_eq!(convert(248, 248, 242), Color::White);
+        assert_eq!(
+            ColorMode::Ansi16.convert(Color::Indexed(196)),
+            Color::LightRed
+        );
+        assert_eq!(ColorMode::Ansi16.convert(Color::Indexed(4)), Color::Blue);
+
+        assert_eq!(
+            ColorMode::TrueColor.convert(Color::Rgb(1, 2, 3)),
+            Color::Rgb(1, 2, 3)
+        );
+    }
+
+    #[test]
+    fn should_detect_color_mode_from_environment() {
+        assert_eq!(
+            detect_color_mode(Some("truecolor"), Some("xterm")),
+            ColorMode::TrueColor
+        );
+        assert_eq!(
+            detect_color_mode(None, Some("xterm-256color")),
+            ColorMode::Ansi256
+        );
+        assert_eq!(
+            detect_color_mode(None, Some("screen-256color")),
+            ColorMode::Ansi256
+        );
+        assert_eq!(
+            detect_color_mode(None, Some("xterm-kitty")),
+            ColorMode::TrueColor
+        );
+        assert_eq!(detect_color_mode(None, Some("xterm")), ColorMode::Ansi16);
+        assert_eq!(detect_color_mode(None, Some("linux")), ColorMode::Ansi16);
+        assert_eq!(detect_color_mode(None, None), ColorMode::TrueColor);
+    }
+}
@@ -7,9 +7,15 @@ use std::{process::Command, sync::OnceLock};
 use ratatui::style::Color;
 use two_face::theme::EmbeddedThemeName;
 
+use crate::app::DiffViewMode;
 use crate::config::config_path_hint;
+use crate::output::ExportFormat;
 use crate::syntax::SyntaxHighlighter;
 
+mod color;
+
+pub use color::{ColorMode, detect_color_mode};
+
 /// Complete color theme for the application
 pub struct Theme {
     /// Cached syntax highlighter (lazily initialized)
@@ -30,6 +36,12 @@ pub struct Theme {
     pub diff_context: Color,
     pub diff_hunk_header: Color,
     pub expanded_context_fg: Color,
+    /// Merge conflict marker lines (`<<<<<<<`, `=======`, `>>>>>>>`)
+    pub conflict_marker: Color,
+    /// Deleted lines that reappear elsewhere in the diff
+    pub diff_moved_from: Color,
+    /// Added lines that were moved from elsewhere in the diff
+    pub diff_moved_to: Color,
 
     // Syntax highlighting diff backgrounds (for syntax-highlighted code)
     pub syntax_add_bg: Color,
@@ -76,6 +88,12 @@ pub struct Theme {
     // Mode indicator colors
     pub mode_fg: Color,
     pub mode_bg: Color,
+
+    /// Colorless rendering: styles use bold, dim and reverse instead of
+    /// colors, and syntax highlighting is off
+    pub monochrome: bool,
+    /// Colors the terminal can show; set by [`Theme::downsample`]
+    pub color_mode: ColorMode,
 }
 
 impl Default for Theme {
@@ -105,6 +123,9 @@ impl Theme {
             diff_context: Color::Rgb(200, 200, 200),
             diff_hunk_header: Color::Rgb(90, 200, 255),
             expanded_context_fg: Color::Rgb(140, 140, 140),
+            conflict_marker: Color::Rgb(255, 150, 50),
+            diff_moved_from: Color::Rgb(200, 120, 230),
+            diff_moved_to: Color::Rgb(80, 200, 220),
 
             // Syntax highlighting diff backgrounds
             syntax_add_bg: Color::Rgb(0, 35, 12),
@@ -151,6 +172,73 @@ impl Theme {
             // Mode indicator colors
             mode_fg: Color::Black,
             mode_bg: Color::Rgb(90, 200, 255),
+            monochrome: false,
+            color_mode: ColorMode::TrueColor,
+        }
+    }
+
+    /// Create the colorless theme used for `--no-color` and `NO_COLOR`.
+    /// Every color is the terminal default, so styles fall back to modifiers.
+    pub fn monochrome() -> Self {
+        let plain = Color::Reset;
+        Self {
+            highlighter: OnceLock::new(),
+
+            panel_bg: plain,
+            bg_highlight: plain,
+            fg_primary: plain,
+            fg_secondary: plain,
+            fg_dim: plain,
+
+            diff_add: plain,
+            diff_add_bg: plain,
+            diff_del: plain,
+            diff_del_bg: plain,
+            diff_context: plain,
+            diff_hunk_header: plain,
+            expanded_context_fg: plain,
+            conflict_marker: plain,
+            diff_moved_from: plain,
+            diff_moved_to: plain,
+
+            syntax_add_bg: plain,
+            syntax_del_bg: plain,
+            syntect_theme: EmbeddedThemeName::Base16EightiesDark,
+
+            file_added: plain,
+            file_modified: plain,
+            file_deleted: plain,
+            file_renamed: plain,
+
+            reviewed: plain,
+            pending: plain,
+
+            comment_note: plain,
+            comment_suggestion: plain,
+            comment_issue: plain,
+            comment_praise: plain,
+
+            border_focused: plain,
+            border_unfocused: plain,
+            status_bar_bg: plain,
+            cursor_color: plain,
+            cursor_line_bg: plain,
+            branch_name: plain,
+            help_indicator: plain,
+
+            message_info_fg: plain,
+            message_info_bg: plain,
+            message_warning_fg: plain,
+            message_warning_bg: plain,
+            message_error_fg: plain,
+            message_error_bg: plain,
+            update_badge_fg: plain,
+            update_badge_bg: plain,
+
+            mode_fg: plain,
+            mode_bg: plain,
+            monochrome: true,
+            color_mode: ColorMode::TrueColor,
         }
     }
 
@@ -175,6 +263,9 @@ impl Theme {
             diff_context: Color::Rgb(0, 0, 0),      // Black for max readability
             diff_hunk_header: Color::Rgb(0, 60, 140),
             expanded_context_fg: Color::Rgb(60, 60, 60),
+            conflict_marker: Color::Rgb(200, 90, 0),
+            diff_moved_from: Color::Rgb(130, 40, 150),
+            diff_moved_to: Color::Rgb(0, 110, 130),
 
             // Syntax highlighting diff backgrounds (lighter for light theme)
             syntax_add_bg: Color::Rgb(220, 255, 220), // Very light green
@@ -221,6 +312,8 @@ impl Theme {
             // Mode indicator colors
             mode_fg: Color::White,
             mode_bg: Color::Rgb(0, 80, 160),
+            monochrome: false,
+            color_mode: ColorMode::TrueColor,
         }
     }
 
@@ -256,6 +349,9 @@ impl Theme {
             diff_context: base00,
             diff_hunk_header: blue,
             expanded_context_fg: base1,
+            conflict_marker: orange,
+            diff_moved_from: violet,
+            diff_moved_to: cyan,
 
             syntax_add_bg: Color::Rgb(222, 240, 205),
             syntax_del_bg: Color::Rgb(252, 225, 224),
@@ -294,6 +390,8 @@ impl Theme {
 
             mode_fg: base3,
             mode_bg: blue,
+            monochrome: false,
+            color_mode: ColorMode::TrueColor,
         }
     }
 
@@ -328,6 +426,9 @@ impl Theme {
             diff_context: base0,
             diff_hunk_header: blue,
             expanded_context_fg: base01,
+            conflict_marker: orange,
+            diff_moved_from: violet,
+            diff_moved_to: cyan,
 
             syntax_add_bg: Color::Rgb(0, 60, 20),
             syntax_del_bg: Color::Rgb(70, 0, 0),
@@ -366,6 +467,8 @@ impl Theme {
 
             mode_fg: base3,
             mode_bg: blue,
+            monochrome: false,
+            color_mode: ColorMode::TrueColor,
         }
     }
 
@@ -484,6 +587,9 @@ impl Theme {
             diff_context: Color::Rgb(92, 103, 115),
             diff_hunk_header: Color::Rgb(54, 163, 217),
             expanded_context_fg: Color::Rgb(130, 140, 153),
+            conflict_marker: Color::Rgb(250, 141, 62),
+            diff_moved_from: Color::Rgb(163, 122, 204),
+            diff_moved_to: Color::Rgb(76, 191, 153),
 
             // Syntax highlighting diff backgrounds
             syntax_add_bg: Color::Rgb(244, 251, 228),
@@ -530,6 +636,8 @@ impl Theme {
             // Mode indicator colors
             mode_fg: Color::White,
             mode_bg: Color::Rgb(255, 106, 0),
+            monochrome: false,
+            color_mode: ColorMode::TrueColor,
         }
     }
 
@@ -552,6 +660,9 @@ impl Theme {
             diff_context: Color::Rgb(171, 178, 191),
             diff_hunk_header: Color::Rgb(86, 182, 194),
             expanded_context_fg: Color::Rgb(92, 99, 112),
+            conflict_marker: Color::Rgb(209, 154, 102),
+            diff_moved_from: Color::Rgb(198, 120, 221),
+            diff_moved_to: Color::Rgb(86, 182, 194),
 
             // Syntax highlighting diff backgrounds
             syntax_add_bg: Color::Rgb(37, 49, 38),
@@ -598,6 +709,94 @@ impl Theme {
             // Mode indicator colors
             mode_fg: Color::Rgb(40, 44, 52),
             mode_bg: Color::Rgb(97, 175, 239),
+            monochrome: false,
+            color_mode: ColorMode::TrueColor,
+        }
+    }
+
+    pub fn dracula() -> Self {
+        let background = rgb(40, 42, 54); // #282a36
+        let current_line = rgb(68, 71, 90); // #44475a
+        let darker = rgb(33, 34, 44); // #21222c
+        let foreground = rgb(248, 248, 242); // #f8f8f2
+        let comment = rgb(98, 114, 164); // #6272a4
+        let cyan = rgb(139, 233, 253); // #8be9fd
+        let green = rgb(80, 250, 123); // #50fa7b
+        let orange = rgb(255, 184, 108); // #ffb86c
+        let pink = rgb(255, 121, 198); // #ff79c6
+        let purple = rgb(189, 147, 249); // #bd93f9
+        let red = rgb(255, 85, 85); // #ff5555
+        let yellow = rgb(241, 250, 140); // #f1fa8c
+
+        Self {
+            highlighter: OnceLock::new(),
+
+            // Base colors
+            panel_bg: background,
+            bg_highlight: current_line,
+            fg_primary: foreground,
+            fg_secondary: blend(foreground, comment, 30),
+            fg_dim: comment,
+
+            // Diff colors
+            diff_add: green,
+            diff_add_bg: blend(background, green, 18),
+            diff_del: red,
+            diff_del_bg: blend(background, red, 18),
+            diff_context: foreground,
+            diff_hunk_header: cyan,
+            expanded_context_fg: comment,
+            conflict_marker: orange,
+            diff_moved_from: pink,
+            diff_moved_to: cyan,
+
+            // Syntax highlighting diff backgrounds
+            syntax_add_bg: blend(background, green, 14),
+            syntax_del_bg: blend(background, red, 14),
+
+            // Syntect theme for syntax highlighting
+            syntect_theme: EmbeddedThemeName::Dracula,
+
+            // File status colors
+            file_added: green,
+            file_modified: yellow,
+            file_deleted: red,
+            file_renamed: pink,
+
+            // Review status colors
+            reviewed: green,
+            pending: yellow,
+
+            // Comment type colors
+            comment_note: cyan,
+            comment_suggestion: purple,
+            comment_issue: red,
+            comment_praise: green,
+
+            // UI element colors
+            border_focused: purple,
+            border_unfocused: current_line,
+            status_bar_bg: darker,
+            cursor_color: orange,
+            cursor_line_bg: current_line,
+            branch_name: cyan,
+            help_indicator: comment,
+
+            // Message/update badge colors
+            message_info_fg: background,
+            message_info_bg: cyan,
+            message_warning_fg: background,
+            message_warning_bg: yellow,
+            message_error_fg: background,
+            message_error_bg: red,
+            update_badge_fg: background,
+            update_badge_bg: orange,
+
+            // Mode indicator colors
+            mode_fg: background,
+            mode_bg: purple,
+            monochrome: false,
+            color_mode: ColorMode::TrueColor,
         }
     }
 
@@ -840,6 +1039,9 @@ fn catppuccin_theme(flavor: CatppuccinFlavor, syntect_theme: EmbeddedThemeName)
         diff_context: flavor.text,
         diff_hunk_header: flavor.blue,
         expanded_context_fg: flavor.overlay1,
+        conflict_marker: flavor.peach,
+        diff_moved_from: flavor.pink,
+        diff_moved_to: flavor.teal,
 
         // Syntax highlighting diff backgrounds
         syntax_add_bg,
@@ -886,6 +1088,8 @@ fn catppuccin_theme(flavor: CatppuccinFlavor, syntect_theme: EmbeddedThemeName)
         // Mode indicator colors
         mode_fg: accent_fg,
         mode_bg: flavor.lavender,
+        monochrome: false,
+        color_mode: ColorMode::TrueColor,
     }
 }
 
@@ -915,6 +1119,9 @@ fn gruvbox_theme(flavor: GruvboxFlavor) -> Theme {
         diff_context: flavor.fg0,
         diff_hunk_header: flavor.blue,
         expanded_context_fg: flavor.grey1,
+        conflict_marker: flavor.orange,
+        diff_moved_from: flavor.purple,
+        diff_moved_to: flavor.aqua,
 
         // Syntax highlighting diff backgrounds
         syntax_add_bg: flavor.bg_green,
@@ -961,6 +1168,8 @@ fn gruvbox_theme(flavor: GruvboxFlavor) -> Theme {
         // Mode indicator colors
         mode_fg: accent_fg,
         mode_bg: flavor.green,
+        monochrome: false,
+        color_mode: ColorMode::TrueColor,
     }
 }
 
@@ -987,6 +1196,9 @@ fn nord_theme(flavor: NordFlavor) -> Theme {
         diff_context: flavor.fg0,
         diff_hunk_header: flavor.frost1,
         expanded_context_fg: flavor.bg3,
+        conflict_marker: flavor.orange,
+        diff_moved_from: flavor.yellow,
+        diff_moved_to: flavor.frost0,
 
         syntax_add_bg,
         syntax_del_bg,
@@ -1025,6 +1237,8 @@ fn nord_theme(flavor: NordFlavor) -> Theme {
 
         mode_fg: accent_fg,
         mode_bg: flavor.frost1,
+        monochrome: false,
+        color_mode: ColorMode::TrueColor,
     }
 }
 
@@ -1040,6 +1254,7 @@ pub enum ThemeArg {
     CatppuccinFrappe,
     CatppuccinMacchiato,
     CatppuccinMocha,
+    Dracula,
     GruvboxDark,
     GruvboxLight,
     NordDark,
@@ -1050,7 +1265,7 @@ pub enum ThemeArg {
     SolarizedDark,
 }
 
-const THEME_CHOICES: [(&str, ThemeArg); 16] = [
+const THEME_CHOICES: [(&str, ThemeArg); 17] = [
     ("dark", ThemeArg::Dark),
     ("light", ThemeArg::Light),
     ("ayu-light", ThemeArg::AyuLight),
@@ -1059,6 +1274,7 @@ const THEME_CHOICES: [(&str, ThemeArg); 16] = [
     ("catppuccin-frappe", ThemeArg::CatppuccinFrappe),
     ("catppuccin-macchiato", ThemeArg::CatppuccinMacchiato),
     ("catppuccin-mocha", ThemeArg::CatppuccinMocha),
+    ("dracula", ThemeArg::Dracula),
     ("gruvbox-dark", ThemeArg::GruvboxDark),
     ("gruvbox-light", ThemeArg::GruvboxLight),
     ("nord-dark", ThemeArg::NordDark),
@@ -1088,8 +1304,59 @@ pub struct CliArgs {
     pub working_tree: bool,
     /// Filter diff to a specific file or directory path
     pub path_filter: Option<String>,
+    /// Files or directories (relative to the current directory) to limit the
+    /// review to, given as positional arguments
+    pub paths: Vec<String>,
+    /// Glob limiting the diff to matching files (`--files 'src/**'`)
+    pub files: Option<String>,
     /// Open a single file for annotation (no VCS required)
     pub file_path: Option<String>,
+    /// Reload the diff automatically when files change on disk
+    pub watch: bool,
+    /// Initial diff layout (`--diff-mode unified|side-by-side`)
+    pub diff_mode: Option<DiffViewMode>,
+    /// Commits loaded into the commit selector up front (`--depth N`)
+    pub depth: Option<usize>,
+    /// Render without colors (`--no-color`)
+    pub no_color: bool,
+    /// Print the saved review without starting the TUI (`tuicr export`)
+    pub export_format: Option<ExportFormat>,
+}
+
+const DIFF_MODE_CHOICES: [(&str, DiffViewMode); 2] = [
+    ("unified", DiffViewMode::Unified),
+    ("side-by-side", DiffViewMode::SideBySide),
+];
+
+fn parse_files_glob(value: &str) -> Result<String, String> {
+    globset::Glob::new(value).map_err(|e| format!("Invalid --files glob '{value}': {e}"))?;
+    Ok(value.to_string())
+}
+
+fn parse_diff_mode(value: &str) -> Result<DiffViewMode, String> {
+    let normalized = value.trim().to_ascii_lowercase();
+    DIFF_MODE_CHOICES
+        .iter()
+        .find(|(name, _)| *name == normalized)
+        .map(|(_, mode)| *mode)
+        .ok_or_else(|| format!("Unknown diff mode '{value}'. Valid options: unified, side-by-side"))
+}
+
+fn parse_depth(value: &str) -> Result<usize, String> {
+    value
+        .parse::<usize>()
+        .ok()
+        .filter(|&depth| depth > 0)
+        .ok_or_else(|| format!("Invalid --depth '{value}': expected a positive integer"))
+}
+
+fn parse_export_format(value: &str) -> Result<ExportFormat, String> {
+    ExportFormat::from_str(value).ok_or_else(|| {
+        format!(
+            "Unknown export format '{value}'. Valid options: {}",
+            ExportFormat::VALID_VALUES
+        )
+    })
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -1121,7 +1388,7 @@ impl ThemeArg {
         })
     }
 
-    fn valid_values_display() -> String {
+    pub(crate) fn valid_values_display() -> String {
         Self::choices()
             .iter()
             .map(|(name, _)| *name)
@@ -1166,6 +1433,7 @@ pub fn resolve_theme(arg: ThemeArg) -> Theme {
         ThemeArg::CatppuccinFrappe => Theme::catppuccin_frappe(),
         ThemeArg::CatppuccinMacchiato => Theme::catppuccin_macchiato(),
         ThemeArg::CatppuccinMocha => Theme::catppuccin_mocha(),
+        ThemeArg::Dracula => Theme::dracula(),
         ThemeArg::GruvboxDark => Theme::gruvbox_dark(),
         ThemeArg::GruvboxLight => Theme::gruvbox_light(),
         ThemeArg::NordDark => Theme::nord_dark(),
@@ -1400,18 +1668,116 @@ pub fn resolve_theme_with_config(
     }
 }
 
+/// Whether colors are turned off by `--no-color` or a non-empty `NO_COLOR`
+/// (see <https://no-color.org>).
+pub fn no_color_requested(cli_no_color: bool, env_value: Option<&std::ffi::OsStr>) -> bool {
+    cli_no_color || env_value.is_some_and(|value| !value.is_empty())
+}
+
 impl Theme {
     /// Get the syntax highlighter for this theme (lazily initialized, cached)
     pub fn syntax_highlighter(&self) -> &SyntaxHighlighter {
-        self.highlighter.get_or_init(|| {
+        self.highlighter
+            .get_or_init(|| self.new_syntax_highlighter())
+    }
+
+    /// Apply `[syntax]` config overrides to the highlighter, returning
+    /// warnings for entries that can't be used.
+    pub fn set_syntax_overrides(&mut self, overrides: &[(String, String)]) -> Vec<String> {
+        if overrides.is_empty() {
+            return Vec::new();
+        }
+        let highlighter = self
+            .highlighter
+            .take()
+            .unwrap_or_else(|| self.new_syntax_highlighter());
+        let (highlighter, warnings) = highlighter.with_overrides(overrides);
+        self.highlighter = OnceLock::from(highlighter);
+        warnings
+    }
+
+    /// Leave files longer than `max_lines` unhighlighted.
+    pub fn set_max_highlight_lines(&mut self, max_lines: usize) {
+        let highlighter = self
+            .highlighter
+            .take()
+            .unwrap_or_else(|| self.new_syntax_highlighter());
+        self.highlighter = OnceLock::from(highlighter.with_max_lines(max_lines));
+    }
+
+    fn new_syntax_highlighter(&self) -> SyntaxHighlighter {
+        if self.monochrome {
+            SyntaxHighlighter::disabled()
+        } else {
             SyntaxHighlighter::new(self.syntect_theme, self.syntax_add_bg, self.syntax_del_bg)
-        })
+                .with_color_mode(self.color_mode)
+        }
+    }
+
+    /// Replace every RGB color with the nearest one `mode` can show,
+    /// including the colors of syntax highlighting.
+    pub fn downsample(&mut self, mode: ColorMode) {
+        self.color_mode = mode;
+        if mode == ColorMode::TrueColor {
+            return;
+        }
+        for color in [
+            &mut self.panel_bg,
+            &mut self.bg_highlight,
+            &mut self.fg_primary,
+            &mut self.fg_secondary,
+            &mut self.fg_dim,
+            &mut self.diff_add,
+            &mut self.diff_add_bg,
+            &mut self.diff_del,
+            &mut self.diff_del_bg,
+            &mut self.diff_context,
+            &mut self.diff_hunk_header,
+            &mut self.expanded_context_fg,
+            &mut self.conflict_marker,
+            &mut self.diff_moved_from,
+            &mut self.diff_moved_to,
+            &mut self.syntax_add_bg,
+            &mut self.syntax_del_bg,
+            &mut self.file_added,
+            &mut self.file_modified,
+            &mut self.file_deleted,
+            &mut self.file_renamed,
+            &mut self.reviewed,
+            &mut self.pending,
+            &mut self.comment_note,
+            &mut self.comment_suggestion,
+            &mut self.comment_issue,
+            &mut self.comment_praise,
+            &mut self.border_focused,
+            &mut self.border_unfocused,
+            &mut self.status_bar_bg,
+            &mut self.cursor_color,
+            &mut self.cursor_line_bg,
+            &mut self.branch_name,
+            &mut self.help_indicator,
+            &mut self.message_info_fg,
+            &mut self.message_info_bg,
+            &mut self.message_warning_fg,
+            &mut self.message_warning_bg,
+            &mut self.message_error_fg,
+            &mut self.message_error_bg,
+            &mut self.update_badge_fg,
+            &mut self.update_badge_bg,
+            &mut self.mode_fg,
+            &mut self.mode_bg,
+        ] {
+            *color = mode.convert(*color);
+        }
+        if let Some(highlighter) = self.highlighter.take() {
+            self.highlighter = OnceLock::from(highlighter.with_color_mode(mode));
+        }
     }
 }
 
 /// Print version and exit
 fn print_version() -> ! {
-    println!("tuicr {}", env!("CARGO_PKG_VERSION"));
+    println!("tuicr {}", crate::update::version_string());
     std::process::exit(0);
 }
 
@@ -1431,7 +1797,11 @@ fn print_help() -> ! {
     println!(
         "tuicr - Review AI-generated diffs like a GitHub pull request
 
-Usage: {name} [OPTIONS]
+Usage: {name} [OPTIONS] [PATH]...
+       {name} export [--format <FORMAT>] [OPTIONS]
+
+Arguments:
+  [PATH]...                 Only review changes under these files or directories
 
 Options:
   -r, --revisions <REVSET>  Commit range/Revset to review (syntax depends on VCS backend)
@@ -1439,16 +1809,22 @@ Options:
   --base <REF>              Base ref for PR mode (implies --pr), e.g. origin/main
   --theme <THEME>        Color theme to use
                          Valid values: {valid_values}
+  --diff-mode <MODE>     Initial diff layout: unified, side-by-side
+  --depth <N>            Commits to load into the commit selector up front (default: 10)
   --appearance <MODE>    Appearance mode for default theme
                          Valid values: {appearance_values}
                          Used when no explicit theme is set
                          Precedence: --appearance > {config_path} > system
   -p, --path <PATH>     Filter diff to a specific file or directory
+  --files <GLOB>         Only review files matching a glob, e.g. 'src/**'
   -w, --working-tree     Include uncommitted changes (skip commit selector when used alone,
                          combine with commits when used with -r)
   --file <PATH>          Open a file for annotation (no VCS required)
+  --format <FORMAT>      Format of `export`: text, markdown, json, rdjson
   --stdout               Output to stdout instead of clipboard when exporting
+  --watch                Reload the diff automatically when files change
   --no-update-check      Skip checking for updates on startup
+  --no-color             Render without colors (also enabled by the NO_COLOR env var)
   -V, --version          Print version
   -h, --help             Print this help message
 
@@ -1472,6 +1848,7 @@ pub fn parse_cli_args() -> CliArgs {
 
 fn parse_cli_args_from(args: &[String]) -> Result<CliArgs, String> {
     let mut cli_args = CliArgs::default();
+    let mut format = None;
 
     let mut i = if args.is_empty() { 0 } else { 1 };
     while i < args.len() {
@@ -1479,6 +1856,19 @@ fn parse_cli_args_from(args: &[String]) -> Result<CliArgs, String> {
 
         match arg.as_str() {
             "--help" | "-h" => print_help(),
+            "export" if i == 1 => {
+                cli_args.export_format = Some(ExportFormat::default());
+                i += 1;
+                continue;
+            }
+            "--format" => {
+                let value = args.get(i + 1).ok_or_else(|| {
+                    format!("--format requires a value ({})", ExportFormat::VALID_VALUES)
+                })?;
+                format = Some(parse_export_format(value)?);
+                i += 2;
+                continue;
+            }
             "--version" | "-V" => print_version(),
             "--stdout" => {
                 cli_args.output_to_stdout = true;
@@ -1490,6 +1880,16 @@ fn parse_cli_args_from(args: &[String]) -> Result<CliArgs, String> {
                 i += 1;
                 continue;
             }
+            "--no-color" => {
+                cli_args.no_color = true;
+                i += 1;
+                continue;
+            }
+            "--watch" => {
+                cli_args.watch = true;
+                i += 1;
+                continue;
+            }
             "-w" | "--working-tree" => {
                 cli_args.working_tree = true;
                 i += 1;
@@ -1530,6 +1930,25 @@ fn parse_cli_args_from(args: &[String]) -> Result<CliArgs, String> {
                 i += 2;
                 continue;
             }
+            "--diff-mode" => {
+                let value = args.get(i + 1).ok_or_else(|| {
+                    "--diff-mode requires a value (unified, side-by-side)".to_string()
+                })?;
+                if value.starts_with('-') {
+                    return Err("--diff-mode requires a value (unified, side-by-side)".to_string());
+                }
+                cli_args.diff_mode = Some(parse_diff_mode(value)?);
+                i += 2;
+                continue;
+            }
+            "--depth" => {
+                let value = args
+                    .get(i + 1)
+                    .ok_or_else(|| "--depth requires a number of commits".to_string())?;
+                cli_args.depth = Some(parse_depth(value)?);
+                i += 2;
+                continue;
+            }
             "-r" | "--revisions" => {
                 if let Some(value) = args.get(i + 1) {
                     if value.starts_with('-') {
@@ -1557,6 +1976,17 @@ fn parse_cli_args_from(args: &[String]) -> Result<CliArgs, String> {
                 i += 2;
                 continue;
             }
+            "--files" => {
+                let value = args
+                    .get(i + 1)
+                    .ok_or_else(|| "--files requires a glob".to_string())?;
+                if value.starts_with('-') {
+                    return Err("--files requires a glob".to_string());
+                }
+                cli_args.files = Some(parse_files_glob(value)?);
+                i += 2;
+                continue;
+            }
             "--file" => {
                 let value = args
                     .get(i + 1)
@@ -1594,6 +2024,21 @@ fn parse_cli_args_from(args: &[String]) -> Result<CliArgs, String> {
             continue;
         }
 
+        if let Some(value) = arg.strip_prefix("--diff-mode=") {
+            if value.is_empty() {
+                return Err("--diff-mode requires a value (unified, side-by-side)".to_string());
+            }
+            cli_args.diff_mode = Some(parse_diff_mode(value)?);
+            i += 1;
+            continue;
+        }
+
+        if let Some(value) = arg.strip_prefix("--depth=") {
+            cli_args.depth = Some(parse_depth(value)?);
+            i += 1;
+            continue;
+        }
+
         if let Some(value) = arg.strip_prefix("--appearance=") {
             let valid_values = AppearanceArg::valid_values_display();
             if value.is_empty() {
@@ -1637,6 +2082,15 @@ fn parse_cli_args_from(args: &[String]) -> Result<CliArgs, String> {
             continue;
         }
 
+        if let Some(value) = arg.strip_prefix("--files=") {
+            if value.is_empty() {
+                return Err("--files requires a glob".to_string());
+            }
+            cli_args.files = Some(parse_files_glob(value)?);
+            i += 1;
+            continue;
+        }
+
         if let Some(value) = arg.strip_prefix("--file=") {
             if value.is_empty() {
                 return Err("--file requires a file path".to_string());
@@ -1646,6 +2100,12 @@ fn parse_cli_args_from(args: &[String]) -> Result<CliArgs, String> {
             continue;
         }
 
+        if let Some(value) = arg.strip_prefix("--format=") {
+            format = Some(parse_export_format(value)?);
+            i += 1;
+            continue;
+        }
+
         if let Some(value) = arg.strip_prefix("--revisions=") {
             cli_args.revisions = Some(value.to_string());
             i += 1;
@@ -1656,15 +2116,24 @@ fn parse_cli_args_from(args: &[String]) -> Result<CliArgs, String> {
             return Err(format!("Unknown option '{arg}'. Use --help for usage."));
         }
 
-        return Err(format!(
-            "Unexpected argument '{arg}'. Use --help for usage."
-        ));
+        cli_args.paths.push(arg.clone());
+        i += 1;
     }
 
     if cli_args.pr_mode && cli_args.revisions.is_some() {
         return Err("--pr/--base cannot be combined with --revisions".to_string());
     }
 
+    if let Some(format) = format {
+        let Some(export_format) = cli_args.export_format.as_mut() else {
+            return Err("--format is only valid with the export command".to_string());
+        };
+        *export_format = format;
+    }
+    if cli_args.export_format.is_some() && cli_args.file_path.is_some() {
+        return Err("export cannot be combined with --file".to_string());
+    }
+
     Ok(cli_args)
 }
 
@@ -1709,6 +2178,13 @@ mod tests {
         assert_eq!(parsed.theme, Some(ThemeArg::Onedark));
     }
 
+    #[test]
+    fn should_parse_dracula_theme() {
+        let parsed =
+            parse_for_test(&["tuicr", "--theme", "dracula"]).expect("parse should succeed");
+        assert_eq!(parsed.theme, Some(ThemeArg::Dracula));
+    }
+
     #[test]
     fn should_parse_gruvbox_themes() {
         let parsed =
@@ -1770,6 +2246,82 @@ mod tests {
         assert!(err.contains("--theme requires a value"));
     }
 
+    #[test]
+    fn should_parse_diff_mode() {
+        let parsed = parse_for_test(&["tuicr", "--diff-mode", "side-by-side"])
+            .expect("parse should succeed");
+        assert_eq!(parsed.diff_mode, Some(DiffViewMode::SideBySide));
+
+        let parsed =
+            parse_for_test(&["tuicr", "--diff-mode=unified"]).expect("parse should succeed");
+        assert_eq!(parsed.diff_mode, Some(DiffViewMode::Unified));
+
+        let parsed = parse_for_test(&["tuicr"]).expect("parse should succeed");
+        assert_eq!(parsed.diff_mode, None);
+    }
+
+    #[test]
+    fn should_fail_for_unknown_diff_mode() {
+        let err =
+            parse_for_test(&["tuicr", "--diff-mode", "split"]).expect_err("parse should fail");
+        assert!(err.contains("Unknown diff mode 'split'"));
+
+        let err = parse_for_test(&["tuicr", "--diff-mode"]).expect_err("parse should fail");
+        assert!(err.contains("--diff-mode requires a value"));
+    }
+
+    #[test]
+    fn should_parse_depth() {
+        let parsed = parse_for_test(&["tuicr", "--depth", "50"]).expect("parse should succeed");
+        assert_eq!(parsed.depth, Some(50));
+        let parsed = parse_for_test(&["tuicr", "--depth=3"]).expect("parse should succeed");
+        assert_eq!(parsed.depth, Some(3));
+        let parsed = parse_for_test(&["tuicr"]).expect("parse should succeed");
+        assert_eq!(parsed.depth, None);
+
+        for value in ["0", "-1", "ten"] {
+            let err = parse_for_test(&["tuicr", "--depth", value]).expect_err("parse should fail");
+            assert!(err.contains(&format!("Invalid --depth '{value}'")), "{err}");
+        }
+        let err = parse_for_test(&["tuicr", "--depth"]).expect_err("parse should fail");
+        assert!(err.contains("--depth requires a number of commits"));
+    }
+
+    #[test]
+    fn should_parse_watch_flag() {
+        let parsed = parse_for_test(&["tuicr", "--watch"]).expect("parse should succeed");
+        assert!(parsed.watch);
+    }
+
+    #[test]
+    fn should_parse_no_color_flag() {
+        let parsed = parse_for_test(&["tuicr", "--no-color"]).expect("parse should succeed");
+        assert!(parsed.no_color);
+    }
+
+    #[test]
+    fn should_honor_non_empty_no_color_env() {
+        use std::ffi::OsStr;
+        assert!(no_color_requested(true, None));
+        assert!(no_color_requested(false, Some(OsStr::new("1"))));
+        assert!(!no_color_requested(false, Some(OsStr::new(""))));
+        assert!(!no_color_requested(false, None));
+    }
+
+    #[test]
+    fn should_disable_syntax_highlighting_for_monochrome_theme() {
+        let theme = Theme::monochrome();
+        assert!(theme.monochrome);
+        assert_eq!(theme.diff_add, Color::Reset);
+        let lines = vec!["fn main() {}".to_string()];
+        assert!(
+            theme
+                .syntax_highlighter()
+                .highlight_file_lines(std::path::Path::new("main.rs"), &lines)
+                .is_none()
+        );
+    }
+
     #[test]
     fn should_parse_pr_flag() {
         let parsed = parse_for_test(&["tuicr", "--pr"]).expect("parse should succeed");
@@ -1826,6 +2378,23 @@ mod tests {
         assert!(err.contains("--pr/--base cannot be combined with --revisions"));
     }
 
+    #[test]
+    fn should_parse_export_command() {
+        let parsed = parse_for_test(&["tuicr", "export", "--format", "json", "-r", "main..HEAD"])
+            .expect("parse should succeed");
+        assert_eq!(parsed.export_format, Some(ExportFormat::Json));
+        assert_eq!(parsed.revisions.as_deref(), Some("main..HEAD"));
+
+        let parsed = parse_for_test(&["tuicr", "export"]).expect("parse should succeed");
+        assert_eq!(parsed.export_format, Some(ExportFormat::Markdown));
+
+        let err = parse_for_test(&["tuicr", "--format=text"]).expect_err("parse should fail");
+        assert!(err.contains("--format is only valid with the export command"));
+        let err =
+            parse_for_test(&["tuicr", "export", "--format=xml"]).expect_err("parse should fail");
+        assert!(err.contains("Unknown export format 'xml'"));
+    }
+
     #[test]
     fn should_error_for_unknown_option() {
         let err = parse_for_test(&["tuicr", "--fmt"]).expect_err("parse should fail");
@@ -2039,6 +2608,50 @@ mod tests {
         assert_eq!(theme.syntect_theme, EmbeddedThemeName::OneHalfDark);
     }
 
+    #[test]
+    fn should_resolve_dracula_to_dracula_syntect_theme() {
+        let theme = resolve_theme(ThemeArg::Dracula);
+        assert_eq!(theme.syntect_theme, EmbeddedThemeName::Dracula);
+        assert_eq!(theme.panel_bg, Color::Rgb(40, 42, 54)); // #282a36
+        assert_eq!(theme.border_focused, Color::Rgb(189, 147, 249)); // purple
+    }
+
+    #[test]
+    fn should_downsample_theme_and_syntax_colors() {
+        let mut theme = Theme::dracula();
+        theme.downsample(ColorMode::Ansi256);
+        assert_eq!(theme.color_mode, ColorMode::Ansi256);
+        assert_eq!(theme.border_focused, Color::Indexed(141));
+
+        let mut theme = Theme::dracula();
+        theme.downsample(ColorMode::Ansi16);
+        assert!(!matches!(theme.panel_bg, Color::Rgb(..)));
+        let highlighted = theme
+            .syntax_highlighter()
+            .highlight_file_lines(std::path::Path::new("main.rs"), &["fn main() {}".into()])
+            .unwrap();
+        let spans = highlighted[0].as_ref().unwrap();
+        assert!(
+            spans
+                .iter()
+                .all(|(style, _)| !matches!(style.fg, Some(Color::Rgb(..))))
+        );
+    }
+
+    #[test]
+    fn should_resolve_solarized_dark_to_solarized_dark_syntect_theme() {
+        let theme = resolve_theme(ThemeArg::SolarizedDark);
+        assert_eq!(theme.syntect_theme, EmbeddedThemeName::SolarizedDark);
+        assert_eq!(theme.panel_bg, Color::Rgb(0, 43, 54)); // base03
+    }
+
+    #[test]
+    fn should_resolve_solarized_light_to_solarized_light_syntect_theme() {
+        let theme = resolve_theme(ThemeArg::SolarizedLight);
+        assert_eq!(theme.syntect_theme, EmbeddedThemeName::SolarizedLight);
+        assert_eq!(theme.panel_bg, Color::Rgb(253, 246, 227)); // base3
+    }
+
     #[test]
     fn should_use_dark_flavor_base_for_catppuccin_mode_foreground() {
         let theme = Theme::catppuccin_mocha();
@@ -2126,6 +2739,14 @@ mod tests {
         assert!(parsed.working_tree);
     }
 
+    #[test]
+    fn should_collect_positional_path_arguments() {
+        let parsed = parse_for_test(&["tuicr", "src/parser", "-r", "HEAD~3..", "tests/"])
+            .expect("parse should succeed");
+        assert_eq!(parsed.paths, ["src/parser", "tests/"]);
+        assert_eq!(parsed.revisions, Some("HEAD~3..".to_string()));
+    }
+
     #[test]
     fn should_parse_path_with_revisions() {
         let parsed = parse_for_test(&["tuicr", "--path", "src/", "-r", "HEAD~3.."])
@@ -2133,4 +2754,20 @@ mod tests {
         assert_eq!(parsed.path_filter, Some("src/".to_string()));
         assert_eq!(parsed.revisions, Some("HEAD~3..".to_string()));
     }
+
+    #[test]
+    fn should_parse_files_glob() {
+        let parsed = parse_for_test(&["tuicr", "--files", "src/**"]).expect("parse should succeed");
+        assert_eq!(parsed.files, Some("src/**".to_string()));
+        let parsed = parse_for_test(&["tuicr", "--files=*.rs"]).expect("parse should succeed");
+        assert_eq!(parsed.files, Some("*.rs".to_string()));
+    }
+
+    #[test]
+    fn should_error_on_invalid_files_glob() {
+        let err = parse_for_test(&["tuicr", "--files", "src/[a"]).expect_err("parse should fail");
+        assert!(err.contains("Invalid --files glob"));
+        let err = parse_for_test(&["tuicr", "--files"]).expect_err("parse should fail");
+        assert!(err.contains("--files requires a glob"));
+    }
 }
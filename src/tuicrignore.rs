@@ -1,34 +1,88 @@
-use std::path::Path;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
 
-use ignore::gitignore::GitignoreBuilder;
+use globset::{GlobBuilder, GlobMatcher};
+use ignore::Match;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
 
 use crate::model::DiffFile;
 
-/// Apply `.tuicrignore` rules from the repository root to a diff file set.
+/// Apply `.gitignore` and `.tuicrignore` rules to a diff file set, and flag
+/// the files `.gitattributes` marks as generated.
 pub fn filter_diff_files(repo_root: &Path, diff_files: Vec<DiffFile>) -> Vec<DiffFile> {
-    let Some(matcher) = load_matcher(repo_root) else {
-        return diff_files;
-    };
+    let mut rules = IgnoreRules::new(repo_root);
+    let mut diff_files: Vec<DiffFile> = diff_files
+        .into_iter()
+        .filter(|file| !rules.is_ignored(file.display_path()))
+        .collect();
+    mark_generated_files(repo_root, &mut diff_files);
+    diff_files
+}
 
+/// Paths of the diff files that [`filter_diff_files`] leaves out.
+pub fn ignored_paths(repo_root: &Path, diff_files: &[DiffFile]) -> Vec<PathBuf> {
+    let mut rules = IgnoreRules::new(repo_root);
     diff_files
-        .into_iter()
-        .filter(|file| {
-            !matcher
-                .matched_path_or_any_parents(file.display_path(), false)
-                .is_ignore()
-        })
+        .iter()
+        .map(|file| file.display_path())
+        .filter(|path| rules.is_ignored(path))
+        .cloned()
         .collect()
 }
 
-fn load_matcher(repo_root: &Path) -> Option<ignore::gitignore::Gitignore> {
-    let gitignore_file = repo_root.join(".gitignore");
-    let tuicrignore_file = repo_root.join(".tuicrignore");
+/// Ignore files found in the repository root and in the directories of the
+/// paths checked so far. Each directory's `.gitignore` and `.tuicrignore`
+/// combine into one matcher, `.tuicrignore` last so its `!` patterns can
+/// re-include files; the directory closest to a path decides first.
+struct IgnoreRules {
+    repo_root: PathBuf,
+    /// Matcher per repository-relative directory, `None` without ignore files
+    matchers: HashMap<PathBuf, Option<Gitignore>>,
+}
+
+impl IgnoreRules {
+    fn new(repo_root: &Path) -> Self {
+        Self {
+            repo_root: repo_root.to_path_buf(),
+            matchers: HashMap::new(),
+        }
+    }
+
+    fn is_ignored(&mut self, path: &Path) -> bool {
+        let dirs: Vec<PathBuf> = path.ancestors().skip(1).map(Path::to_path_buf).collect();
+        for dir in dirs {
+            let Some(matcher) = self.matcher(&dir) else {
+                continue;
+            };
+            let relative = path.strip_prefix(&dir).unwrap_or(path);
+            match matcher.matched_path_or_any_parents(relative, false) {
+                Match::Ignore(_) => return true,
+                Match::Whitelist(_) => return false,
+                Match::None => {}
+            }
+        }
+        false
+    }
+
+    fn matcher(&mut self, dir: &Path) -> Option<&Gitignore> {
+        let repo_root = &self.repo_root;
+        self.matchers
+            .entry(dir.to_path_buf())
+            .or_insert_with(|| load_matcher(&repo_root.join(dir)))
+            .as_ref()
+    }
+}
+
+fn load_matcher(dir: &Path) -> Option<Gitignore> {
+    let gitignore_file = dir.join(".gitignore");
+    let tuicrignore_file = dir.join(".tuicrignore");
 
     if !gitignore_file.is_file() && !tuicrignore_file.is_file() {
         return None;
     }
 
-    let mut builder = GitignoreBuilder::new(repo_root);
+    let mut builder = GitignoreBuilder::new(dir);
 
     // Load .gitignore first so .tuicrignore rules can override with `!` patterns.
     if gitignore_file.is_file() {
@@ -41,6 +95,89 @@ fn load_matcher(repo_root: &Path) -> Option<ignore::gitignore::Gitignore> {
     builder.build().ok()
 }
 
+/// One `.gitattributes` line: a path pattern and how it sets the
+/// `linguist-generated` and `diff` attributes, if at all.
+struct AttributeRule {
+    matcher: GlobMatcher,
+    generated: Option<bool>,
+    diff: Option<bool>,
+}
+
+/// Set `is_generated` on files marked `linguist-generated` or `-diff` (or
+/// `binary`) in the root `.gitattributes`. Later lines override earlier ones.
+fn mark_generated_files(repo_root: &Path, diff_files: &mut [DiffFile]) {
+    let Ok(contents) = fs::read_to_string(repo_root.join(".gitattributes")) else {
+        return;
+    };
+    let rules = parse_gitattributes(&contents);
+    if rules.is_empty() {
+        return;
+    }
+
+    for file in diff_files {
+        let path = file.display_path();
+        let (mut generated, mut diff) = (false, true);
+        for rule in rules.iter().filter(|rule| rule.matcher.is_match(path)) {
+            generated = rule.generated.unwrap_or(generated);
+            diff = rule.diff.unwrap_or(diff);
+        }
+        file.is_generated = generated || !diff;
+    }
+}
+
+fn parse_gitattributes(contents: &str) -> Vec<AttributeRule> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let pattern = fields.next()?;
+            // Comments, macro definitions and directory patterns (which
+            // never match files in .gitattributes)
+            if pattern.starts_with('#') || pattern.starts_with("[attr]") || pattern.ends_with('/') {
+                return None;
+            }
+
+            let (mut generated, mut diff) = (None, None);
+            for attr in fields {
+                // `!attr` resets to unspecified, i.e. a generated-free text diff
+                match attr {
+                    "linguist-generated" | "linguist-generated=true" => generated = Some(true),
+                    "-linguist-generated" | "!linguist-generated" | "linguist-generated=false" => {
+                        generated = Some(false)
+                    }
+                    "-diff" | "binary" => diff = Some(false),
+                    // `diff=<driver>` still shows a textual diff
+                    _ if attr == "diff" || attr == "!diff" || attr.starts_with("diff=") => {
+                        diff = Some(true)
+                    }
+                    _ => {}
+                }
+            }
+            if generated.is_none() && diff.is_none() {
+                return None;
+            }
+
+            // Patterns without a slash match at any depth, others are
+            // relative to the repository root
+            let glob = match pattern.strip_prefix('/') {
+                Some(anchored) => anchored.to_string(),
+                None if pattern.contains('/') => pattern.to_string(),
+                None => format!("**/{pattern}"),
+            };
+            let matcher = GlobBuilder::new(&glob)
+                .literal_separator(true)
+                .build()
+                .ok()?
+                .compile_matcher();
+            Some(AttributeRule {
+                matcher,
+                generated,
+                diff,
+            })
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs;
@@ -58,9 +195,19 @@ mod tests {
             status: FileStatus::Modified,
             hunks: Vec::new(),
             is_binary: false,
+            binary_meta: None,
             is_too_large: false,
+            truncated_lines: 0,
             is_commit_message: false,
+            is_submodule: false,
+            old_mode: None,
+            new_mode: None,
+            is_symlink: false,
+            is_conflicted: false,
+            has_conflicts: false,
             content_hash: 0,
+            highlighted: false,
+            is_generated: false,
         }
     }
 
@@ -197,9 +344,19 @@ mod tests {
             status: FileStatus::Deleted,
             hunks: Vec::new(),
             is_binary: false,
+            binary_meta: None,
             is_too_large: false,
+            truncated_lines: 0,
             is_commit_message: false,
+            is_submodule: false,
+            old_mode: None,
+            new_mode: None,
+            is_symlink: false,
+            is_conflicted: false,
+            has_conflicts: false,
             content_hash: 0,
+            highlighted: false,
+            is_generated: false,
         };
         let kept = make_diff_file("src/lib.rs");
 
@@ -211,4 +368,149 @@ mod tests {
 
         assert_eq!(kept_paths, vec!["src/lib.rs"]);
     }
+
+    /// Ignore files as (path, contents), the diff path, and whether it's ignored.
+    type PrecedenceCase = (&'static [(&'static str, &'static str)], &'static str, bool);
+
+    #[test]
+    fn applies_pattern_precedence() {
+        let cases: &[PrecedenceCase] = &[
+            (
+                &[(".tuicrignore", "*.snap\n!keep.snap\n")],
+                "a/keep.snap",
+                false,
+            ),
+            (
+                &[(".tuicrignore", "*.snap\n!keep.snap\n")],
+                "a/drop.snap",
+                true,
+            ),
+            (
+                &[(".tuicrignore", "!keep.snap\n*.snap\n")],
+                "keep.snap",
+                true,
+            ),
+            (&[(".tuicrignore", "/dist/\n")], "dist/app.js", true),
+            (&[(".tuicrignore", "/dist/\n")], "web/dist/app.js", false),
+            (&[(".tuicrignore", "dist/\n")], "web/dist/app.js", true),
+            (
+                &[(".tuicrignore", "docs/**/*.png\n")],
+                "docs/a/b/c.png",
+                true,
+            ),
+            (
+                &[(".tuicrignore", "docs/**/*.png\n")],
+                "src/docs/c.png",
+                false,
+            ),
+            (
+                &[(".tuicrignore", "**/fixtures\n")],
+                "a/b/fixtures/x.json",
+                true,
+            ),
+            (
+                &[
+                    (".gitignore", "*.lock\n"),
+                    (".tuicrignore", "!Cargo.lock\n"),
+                ],
+                "Cargo.lock",
+                false,
+            ),
+            (
+                &[
+                    (".tuicrignore", "*.rs\n"),
+                    ("src/.tuicrignore", "!main.rs\n"),
+                ],
+                "src/main.rs",
+                false,
+            ),
+            (
+                &[
+                    (".tuicrignore", "*.rs\n"),
+                    ("src/.tuicrignore", "!main.rs\n"),
+                ],
+                "src/lib.rs",
+                true,
+            ),
+            (
+                &[(".tuicrignore", "!*.rs\n"), ("src/.tuicrignore", "*.rs\n")],
+                "src/main.rs",
+                true,
+            ),
+            (
+                &[(".tuicrignore", "!*.rs\n"), ("src/.tuicrignore", "*.rs\n")],
+                "main.rs",
+                false,
+            ),
+            (&[("src/.tuicrignore", "/gen/\n")], "src/gen/a.rs", true),
+            (&[("src/.tuicrignore", "/gen/\n")], "gen/a.rs", false),
+            (
+                &[(".tuicrignore", "*.rs\n"), ("src/.gitignore", "!lib.rs\n")],
+                "src/lib.rs",
+                false,
+            ),
+            (
+                &[
+                    (".tuicrignore", "*.rs\n"),
+                    ("src/.tuicrignore", "# comment\n"),
+                ],
+                "src/lib.rs",
+                true,
+            ),
+        ];
+
+        for (files, path, ignored) in cases {
+            let dir = tempdir().expect("failed to create temp dir");
+            for (name, contents) in *files {
+                let file = dir.path().join(name);
+                fs::create_dir_all(file.parent().unwrap()).expect("failed to create dir");
+                fs::write(&file, contents).expect("failed to write ignore file");
+            }
+
+            let paths = ignored_paths(dir.path(), &[make_diff_file(path)]);
+
+            assert_eq!(!paths.is_empty(), *ignored, "{path} with {files:?}");
+        }
+    }
+
+    #[test]
+    fn marks_generated_files_from_gitattributes() {
+        let dir = tempdir().expect("failed to create temp dir");
+        fs::write(
+            dir.path().join(".gitattributes"),
+            "# generated code\n\
+             *.min.js linguist-generated\n\
+             Cargo.lock -diff\n\
+             gen/** linguist-generated=true\n\
+             gen/keep.rs -linguist-generated\n\
+             *.svg binary\n\
+             *.rs diff=rust\n",
+        )
+        .expect("failed to write .gitattributes");
+
+        let files = vec![
+            make_diff_file("web/app.min.js"),
+            make_diff_file("Cargo.lock"),
+            make_diff_file("gen/schema.rs"),
+            make_diff_file("gen/keep.rs"),
+            make_diff_file("assets/logo.svg"),
+            make_diff_file("src/main.rs"),
+        ];
+
+        let generated: Vec<String> = filter_diff_files(dir.path(), files)
+            .iter()
+            .filter(|f| f.is_generated)
+            .map(|f| f.display_path().display().to_string())
+            .collect();
+
+        assert_eq!(
+            generated,
+            vec![
+                "web/app.min.js",
+                "Cargo.lock",
+                "gen/schema.rs",
+                "assets/logo.svg"
+            ]
+        );
+    }
 }
@@ -1,19 +1,26 @@
+use std::borrow::Cow;
+
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Rect},
-    style::Style,
+    style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
 };
 use unicode_width::UnicodeWidthStr;
 
 use crate::app::{
-    AnnotatedLine, App, DiffViewMode, ExpandDirection, FileTreeItem, FocusedPanel,
-    GAP_EXPAND_BATCH, GapId, InputMode,
+    AnnotatedLine, App, ConfirmAction, DiffViewMode, ExpandDirection, FileTreeItem, FocusedPanel,
+    GapId, InputMode, hidden_context_run,
 };
-use crate::model::{LineOrigin, LineRange, LineSide};
+use crate::model::pairing::pair_change_block;
+use crate::model::{DiffFile, LineOrigin, LineRange, LineSide};
 use crate::theme::Theme;
-use crate::ui::{comment_panel, help_popup, status_bar, styles};
+use crate::ui::{
+    blame_popup, branch_picker, comment_list, comment_panel, help_popup, scrollbar,
+    session_browser, stats_popup, status_bar, styles,
+};
+use crate::vcs::DiffStat;
 use crate::vcs::git::calculate_gap;
 
 pub fn render(frame: &mut Frame, app: &mut App) {
@@ -44,16 +51,30 @@ pub fn render(frame: &mut Frame, app: &mut App) {
     render_main_content(frame, app, chunks[1]);
     status_bar::render_status_bar(frame, app, chunks[2]);
 
+    blame_popup::render_blame_popup(frame, app);
+    stats_popup::render_stats_popup(frame, app);
+
     // Render help popup on top if in help mode
     if app.input_mode == InputMode::Help {
         help_popup::render_help(frame, app);
     }
 
+    // Session browser stays visible behind its delete confirmation
+    if app.input_mode == InputMode::SessionBrowser
+        || app.pending_confirm == Some(ConfirmAction::DeleteSession)
+    {
+        session_browser::render_session_browser(frame, app);
+    }
+
+    if app.input_mode == InputMode::BranchPicker {
+        branch_picker::render_branch_picker(frame, app);
+    }
+
     // Comment input is now rendered inline in the diff view
 
     // Render confirm dialog if in confirm mode
     if app.input_mode == InputMode::Confirm {
-        comment_panel::render_confirm_dialog(frame, app, "Copy review to clipboard?");
+        comment_panel::render_confirm_dialog(frame, app, &app.confirm_message());
     }
 
     // Position terminal cursor for IME when in Comment mode
@@ -257,7 +278,8 @@ fn truncate_str(s: &str, max_len: usize) -> String {
 
 fn render_main_content(frame: &mut Frame, app: &mut App, area: Rect) {
     let content_area = if app.has_inline_commit_selector() {
-        let selector_height = (app.review_commits.len() as u16 + 2).min(8); // N items + 2 borders, capped
+        // N items + 2 borders, capped
+        let selector_height = (app.review_commits.len() as u16 + 2).min(app.commit_selector_height);
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([Constraint::Length(selector_height), Constraint::Min(0)])
@@ -268,12 +290,24 @@ fn render_main_content(frame: &mut Frame, app: &mut App, area: Rect) {
         area
     };
 
+    let content_area = if app.show_comment_list {
+        let [content, list] = Layout::vertical([
+            Constraint::Min(0),
+            Constraint::Length(app.comment_list_height),
+        ])
+        .areas(content_area);
+        comment_list::render_comment_list(frame, app, list);
+        content
+    } else {
+        content_area
+    };
+
     if app.show_file_list {
         let chunks = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([
-                Constraint::Percentage(20), // File list
-                Constraint::Percentage(80), // Diff view
+                Constraint::Percentage(app.file_list_width_percent), // File list
+                Constraint::Percentage(100 - app.file_list_width_percent), // Diff view
             ])
             .split(content_area);
 
@@ -292,8 +326,14 @@ fn render_main_content(frame: &mut Frame, app: &mut App, area: Rect) {
 
 fn render_inline_commit_selector(frame: &mut Frame, app: &mut App, area: Rect) {
     let focused = app.focused_panel == FocusedPanel::CommitSelector;
+    let mut title = vec![Span::raw(" Commits ")];
+    if let Some(stat) = app.selected_commits_stat() {
+        title.push(Span::raw("\u{2014} selected: "));
+        title.extend(commit_stat_spans(&app.theme, &stat));
+        title.push(Span::raw(" "));
+    }
     let block = Block::default()
-        .title(" Commits ")
+        .title(Line::from(title))
         .borders(Borders::ALL)
         .border_style(styles::border_style(&app.theme, focused));
 
@@ -373,6 +413,14 @@ fn render_inline_commit_selector(frame: &mut Frame, app: &mut App, area: Rect) {
                     Style::default().fg(app.theme.fg_secondary),
                 ));
 
+                if let Some(stat) = app.commit_stats.get(&commit.id) {
+                    let stat_spans = commit_stat_spans(&app.theme, stat);
+                    let used: usize = spans.iter().chain(&stat_spans).map(Span::width).sum();
+                    let padding = (inner.width as usize).saturating_sub(used).max(1);
+                    spans.push(Span::raw(" ".repeat(padding)));
+                    spans.extend(stat_spans);
+                }
+
                 Line::from(spans)
             })
             .collect();
@@ -439,8 +487,13 @@ fn render_file_list(frame: &mut Frame, app: &mut App, area: Rect) {
 
     // When diff panel is focused, sync file list selection to current file
     // But preserve the current offset to not interfere with manual scrolling
+    let mut follow_selection = true;
     if app.focused_panel == FocusedPanel::Diff {
         let current_file_idx = app.diff_state.current_file_idx;
+        // Only a new current file brings the selection back into view, so
+        // the list can be scrolled on its own with Ctrl-j/Ctrl-k
+        follow_selection = app.file_list_state.synced_file_idx != Some(current_file_idx);
+        app.file_list_state.synced_file_idx = Some(current_file_idx);
         for (tree_idx, item) in visible_items.iter().enumerate() {
             if let FileTreeItem::File { file_idx, .. } = item
                 && *file_idx == current_file_idx
@@ -459,7 +512,7 @@ fn render_file_list(frame: &mut Frame, app: &mut App, area: Rect) {
 
     let selected_idx = app.file_list_state.selected();
 
-    let items: Vec<ListItem> = visible_items
+    let mut items: Vec<ListItem> = visible_items
         .iter()
         .enumerate()
         .map(|(i, item)| {
@@ -535,6 +588,26 @@ fn render_file_list(frame: &mut Frame, app: &mut App, area: Rect) {
                                 styles::file_status_style(&app.theme, status),
                             ),
                             Span::styled(filename.to_string(), style),
+                            Span::styled(
+                                if file.is_submodule { " ⎇" } else { "" },
+                                styles::dim_style(&app.theme),
+                            ),
+                            Span::styled(
+                                if file.is_generated {
+                                    " (generated)"
+                                } else {
+                                    ""
+                                },
+                                styles::dim_style(&app.theme),
+                            ),
+                            Span::styled(
+                                if file.has_unresolved_conflicts() {
+                                    " !"
+                                } else {
+                                    ""
+                                },
+                                styles::conflict_marker_style(&app.theme),
+                            ),
                         ])
                     };
 
@@ -544,11 +617,37 @@ fn render_file_list(frame: &mut Frame, app: &mut App, area: Rect) {
         })
         .collect();
 
+    // Ignored files trail the tree and can't be selected
+    if app.show_ignored && !app.ignored_files.is_empty() {
+        let dim = styles::dim_style(&app.theme);
+        let count = app.ignored_files.len();
+        let noun = if count == 1 { "file" } else { "files" };
+        items.push(ListItem::new(apply_horizontal_scroll(
+            Line::from(Span::styled(format!("▾ ignored ({count} {noun})"), dim)),
+            scroll_x,
+        )));
+        items.extend(app.ignored_files.iter().map(|path| {
+            let line = Line::from(Span::styled(format!("  {}", path.display()), dim));
+            ListItem::new(apply_horizontal_scroll(line, scroll_x))
+        }));
+    }
+
+    let item_count = items.len();
     let list = List::new(items)
         .style(styles::panel_style(&app.theme))
         .block(block);
 
-    frame.render_stateful_widget(list, area, &mut app.file_list_state.list_state);
+    if follow_selection {
+        frame.render_stateful_widget(list, area, &mut app.file_list_state.list_state);
+    } else {
+        let offset = app.file_list_state.list_state.offset();
+        let mut state = ListState::default().with_offset(offset);
+        frame.render_stateful_widget(list, area, &mut state);
+        *app.file_list_state.list_state.offset_mut() = state.offset();
+    }
+    if app.show_scrollbar {
+        scrollbar::render_file_list_scrollbar(frame, app, area, item_count);
+    }
 }
 
 fn render_diff_view(frame: &mut Frame, app: &mut App, area: Rect) {
@@ -556,6 +655,50 @@ fn render_diff_view(frame: &mut Frame, app: &mut App, area: Rect) {
         DiffViewMode::Unified => render_unified_diff(frame, app, area),
         DiffViewMode::SideBySide => render_side_by_side_diff(frame, app, area),
     }
+    if app.show_scrollbar {
+        scrollbar::render_diff_scrollbar(frame, app, area);
+    }
+    // Files scrolled into view are highlighted in the background and drawn
+    // with plain diff colors until the result arrives
+    app.highlight_visible_files();
+}
+
+/// Panel title naming the current file and the view mode it is shown in.
+fn diff_title(app: &App) -> String {
+    let mode = match app.file_view_mode(app.diff_state.current_file_idx) {
+        DiffViewMode::Unified => "Unified",
+        DiffViewMode::SideBySide => "Side-by-Side",
+    };
+    match app.current_file_path() {
+        Some(path) if !app.is_cursor_in_overview() => {
+            format!(" Diff ({mode}) \u{2014} {} ", path.display())
+        }
+        _ => format!(" Diff ({mode}) \u{2014} Overview "),
+    }
+}
+
+/// Diffstat of one or more commits, e.g. `3 files +120 −45`.
+fn commit_stat_spans(theme: &Theme, stat: &DiffStat) -> Vec<Span<'static>> {
+    let noun = if stat.files_changed == 1 {
+        "file"
+    } else {
+        "files"
+    };
+    vec![
+        Span::styled(
+            format!("{} {noun} ", stat.files_changed),
+            styles::dim_style(theme),
+        ),
+        Span::styled(
+            format!("+{}", stat.insertions),
+            Style::default().fg(theme.diff_add),
+        ),
+        Span::raw(" "),
+        Span::styled(
+            format!("\u{2212}{}", stat.deletions),
+            Style::default().fg(theme.diff_del),
+        ),
+    ]
 }
 
 /// Build a right-aligned title showing diff stats for the current scope.
@@ -586,14 +729,7 @@ fn diff_stat_title(app: &App) -> Line<'static> {
 fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
     let focused = app.focused_panel == FocusedPanel::Diff;
 
-    let title = if app.is_cursor_in_overview() || app.current_file_path().is_none() {
-        " Diff (Unified) \u{2014} Overview ".to_string()
-    } else {
-        format!(
-            " Diff (Unified) \u{2014} {} ",
-            app.current_file_path().unwrap().display()
-        )
-    };
+    let title = diff_title(app);
 
     let block = Block::default()
         .title(title)
@@ -611,6 +747,7 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
 
     // Reset comment input annotation offset (will be set if a comment input box is rendered)
     app.comment_input_annotation_offset = None;
+    app.load_current_file_blame();
 
     // Build all diff lines for infinite scroll
     // Track line index to mark the current line (cursor position)
@@ -656,6 +793,7 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                 None,
                 true,
                 app.supports_keyboard_enhancement,
+                comment_input_width(app),
             );
             comment_cursor_logical_line = Some(line_idx + cursor_info.line_offset);
             comment_cursor_column = 1 + cursor_info.column;
@@ -677,7 +815,7 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
         } else {
             let comment_lines = comment_panel::format_comment_lines(
                 &app.theme,
-                comment_type_presentation(app, &comment.comment_type),
+                comment_presentation(app, comment),
                 &comment.content,
                 None,
             );
@@ -702,6 +840,7 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
             None,
             false,
             app.supports_keyboard_enhancement,
+            comment_input_width(app),
         );
         comment_cursor_logical_line = Some(line_idx + cursor_info.line_offset);
         comment_cursor_column = 1 + cursor_info.column;
@@ -719,7 +858,27 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
         }
     }
 
+    // Review comments come first, so the scrolling that reveals their input
+    // box is known here and the window goes where the viewport ends up
+    let mut scroll_offset = app.diff_state.scroll_offset;
+    scroll_comment_input_into_view(
+        &mut scroll_offset,
+        comment_input_box_range,
+        comment_cursor_logical_line,
+        inner.height as usize,
+        app.total_lines(),
+    );
+    let review_lines = lines;
+    let mut lines = WindowedLines::new(app, scroll_offset);
+    lines.extend(review_lines);
     for (file_idx, file) in app.diff_files.iter().enumerate() {
+        let height = app.file_render_height(file_idx);
+        if !lines.overlaps(line_idx, height) {
+            lines.skip(height);
+            line_idx += height;
+            continue;
+        }
+
         let path = file.display_path();
         let status = file.status.as_char();
         let is_reviewed = app.session.is_file_reviewed(path);
@@ -732,6 +891,20 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
 
         let header_text = if file.is_commit_message {
             format!("═══ {}Commit Message ", review_mark)
+        } else if file.is_submodule {
+            format!(
+                "═══ {}{} [{}] submodule ",
+                review_mark,
+                path.display(),
+                status
+            )
+        } else if file.is_generated {
+            format!(
+                "═══ {}{} [{}] generated ",
+                review_mark,
+                path.display(),
+                status
+            )
         } else {
             format!("═══ {}{} [{}] ", review_mark, path.display(), status)
         };
@@ -742,8 +915,8 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
         ]));
         line_idx += 1;
 
-        // If file is reviewed, skip rendering the body (fold it away)
-        if is_reviewed {
+        // If file is collapsed, skip rendering the body (fold it away)
+        if app.is_file_collapsed(file) {
             continue;
         }
 
@@ -769,6 +942,7 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                         None,
                         true,
                         app.supports_keyboard_enhancement,
+                        comment_input_width(app),
                     );
                     // Track cursor position: logical line = current line_idx + cursor offset within input
                     comment_cursor_logical_line = Some(line_idx + cursor_info.line_offset);
@@ -795,9 +969,9 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                 } else {
                     let comment_lines = comment_panel::format_comment_lines(
                         &app.theme,
-                        comment_type_presentation(app, &comment.comment_type),
+                        comment_presentation(app, comment),
                         &comment.content,
-                        None,
+                        comment.line_range.filter(|_| comment.orphaned),
                     );
                     for mut comment_line in comment_lines {
                         let indicator = cursor_indicator(line_idx, current_line_idx);
@@ -825,6 +999,7 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                 None,
                 false,
                 app.supports_keyboard_enhancement,
+                comment_input_width(app),
             );
             // Track cursor position
             comment_cursor_logical_line = Some(line_idx + cursor_info.line_offset);
@@ -844,6 +1019,16 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
             }
         }
 
+        let metadata = file.metadata_descriptor();
+        if let Some(descriptor) = &metadata {
+            let indicator = cursor_indicator_spaced(line_idx, current_line_idx);
+            lines.push(Line::from(vec![
+                Span::styled(indicator, styles::current_line_indicator_style(&app.theme)),
+                Span::styled(descriptor.clone(), styles::dim_style(&app.theme)),
+            ]));
+            line_idx += 1;
+        }
+
         if file.is_too_large {
             let indicator = cursor_indicator_spaced(line_idx, current_line_idx);
             lines.push(Line::from(vec![
@@ -855,10 +1040,11 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
             let indicator = cursor_indicator_spaced(line_idx, current_line_idx);
             lines.push(Line::from(vec![
                 Span::styled(indicator, styles::current_line_indicator_style(&app.theme)),
-                Span::styled("(binary file)", styles::dim_style(&app.theme)),
+                Span::styled(file.binary_descriptor(), styles::dim_style(&app.theme)),
             ]));
             line_idx += 1;
-        } else if file.hunks.is_empty() {
+            lines.extend(image_preview_lines(app, &mut line_idx, current_line_idx));
+        } else if file.hunks.is_empty() && metadata.is_none() && file.truncated_lines == 0 {
             let indicator = cursor_indicator_spaced(line_idx, current_line_idx);
             lines.push(Line::from(vec![
                 Span::styled(indicator, styles::current_line_indicator_style(&app.theme)),
@@ -905,6 +1091,7 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                                 &mut line_idx,
                                 current_line_idx,
                                 expanded_line,
+                                blame_cell(app, file_idx, expanded_line.old_lineno),
                                 &app.theme,
                             );
                         }
@@ -913,7 +1100,7 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                     // Render expanders / hidden lines
                     if remaining > 0 {
                         if is_top_of_file {
-                            if remaining > GAP_EXPAND_BATCH {
+                            if remaining > app.context_expand_lines {
                                 render_hidden_lines(
                                     &mut lines,
                                     &mut line_idx,
@@ -927,16 +1114,16 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                                 &mut line_idx,
                                 current_line_idx,
                                 ExpandDirection::Up,
-                                remaining,
+                                remaining.min(app.context_expand_lines),
                                 &app.theme,
                             );
-                        } else if remaining >= GAP_EXPAND_BATCH {
+                        } else if remaining >= app.context_expand_lines {
                             render_expander_line(
                                 &mut lines,
                                 &mut line_idx,
                                 current_line_idx,
                                 ExpandDirection::Down,
-                                remaining,
+                                remaining.min(app.context_expand_lines),
                                 &app.theme,
                             );
                             render_hidden_lines(
@@ -951,7 +1138,7 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                                 &mut line_idx,
                                 current_line_idx,
                                 ExpandDirection::Up,
-                                remaining,
+                                remaining.min(app.context_expand_lines),
                                 &app.theme,
                             );
                         } else {
@@ -960,7 +1147,7 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                                 &mut line_idx,
                                 current_line_idx,
                                 ExpandDirection::Both,
-                                remaining,
+                                remaining.min(app.context_expand_lines),
                                 &app.theme,
                             );
                         }
@@ -974,6 +1161,7 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                                 &mut line_idx,
                                 current_line_idx,
                                 expanded_line,
+                                blame_cell(app, file_idx, expanded_line.old_lineno),
                                 &app.theme,
                             );
                         }
@@ -982,379 +1170,54 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
 
                 // Hunk header
                 let indicator = cursor_indicator_spaced(line_idx, current_line_idx);
-                lines.push(Line::from(vec![
-                    Span::styled(indicator, styles::current_line_indicator_style(&app.theme)),
-                    Span::styled(
-                        hunk.header.to_string(),
-                        styles::diff_hunk_header_style(&app.theme),
-                    ),
-                ]));
+                let hunk_reviewed = app.is_hunk_reviewed(file_idx, hunk_idx);
+                lines.push(hunk_header_line(app, indicator, hunk, hunk_reviewed));
                 line_idx += 1;
+                if hunk_reviewed {
+                    continue;
+                }
 
                 // Diff lines
-                for diff_line in &hunk.lines {
-                    let (prefix, base_style) = match diff_line.origin {
-                        LineOrigin::Addition => ("+", styles::diff_add_style(&app.theme)),
-                        LineOrigin::Deletion => ("-", styles::diff_del_style(&app.theme)),
-                        LineOrigin::Context => (" ", styles::diff_context_style(&app.theme)),
-                    };
-
-                    // Check if this line is in visual selection
-                    let is_in_visual_selection = {
-                        let line_num = match diff_line.origin {
-                            LineOrigin::Addition | LineOrigin::Context => diff_line.new_lineno,
-                            LineOrigin::Deletion => diff_line.old_lineno,
-                        };
-                        let side = match diff_line.origin {
-                            LineOrigin::Addition | LineOrigin::Context => LineSide::New,
-                            LineOrigin::Deletion => LineSide::Old,
-                        };
-                        line_num
-                            .map(|ln| app.is_line_in_visual_selection(ln, side))
-                            .unwrap_or(false)
-                    };
-
-                    // Apply visual selection highlighting if applicable
-                    let style = if is_in_visual_selection {
-                        base_style.patch(styles::visual_selection_style(&app.theme))
-                    } else {
-                        base_style
-                    };
-
-                    let line_num_str = match diff_line.origin {
-                        LineOrigin::Addition => diff_line
-                            .new_lineno
-                            .map(|n| format!("{n:>4} "))
-                            .unwrap_or_else(|| "     ".to_string()),
-                        LineOrigin::Deletion => diff_line
-                            .old_lineno
-                            .map(|n| format!("{n:>4} "))
-                            .unwrap_or_else(|| "     ".to_string()),
-                        _ => diff_line
-                            .new_lineno
-                            .or(diff_line.old_lineno)
-                            .map(|n| format!("{n:>4} "))
-                            .unwrap_or_else(|| "     ".to_string()),
-                    };
-
-                    let indicator = cursor_indicator(line_idx, current_line_idx);
-
-                    // Build line spans - use syntax highlighting if available
-                    let line_num_style = if is_in_visual_selection {
-                        styles::dim_style(&app.theme)
-                            .patch(styles::visual_selection_style(&app.theme))
-                    } else {
-                        styles::dim_style(&app.theme)
-                    };
-
-                    let mut line_spans = vec![
-                        Span::styled(indicator, styles::current_line_indicator_style(&app.theme)),
-                        Span::styled(line_num_str, line_num_style),
-                        Span::styled(format!("{prefix} "), style),
-                    ];
-
-                    // Add content spans
-                    if let Some(ref highlighted) = diff_line.highlighted_spans {
-                        // Use syntax-highlighted spans
-                        for (span_style, span_text) in highlighted {
-                            let final_style = if is_in_visual_selection {
-                                span_style.patch(styles::visual_selection_style(&app.theme))
-                            } else {
-                                *span_style
-                            };
-                            line_spans.push(Span::styled(span_text.clone(), final_style));
-                        }
-                    } else {
-                        // Fall back to default diff styling
-                        line_spans.push(Span::styled(diff_line.content.clone(), style));
-                    }
-
-                    // Mark add/del lines with their effective EOL style so we can paint full
-                    // row backgrounds later (including wrapped visual rows).
-                    if matches!(
-                        diff_line.origin,
-                        LineOrigin::Addition | LineOrigin::Deletion
-                    ) {
-                        let eol_style = match diff_line.highlighted_spans.as_ref() {
-                            // For syntax-highlighted lines (including empty highlighted lines),
-                            // use syntax diff background so row fill matches code spans.
-                            Some(_) => {
-                                let syntax_bg = match diff_line.origin {
-                                    LineOrigin::Addition => app.theme.syntax_add_bg,
-                                    LineOrigin::Deletion => app.theme.syntax_del_bg,
-                                    LineOrigin::Context => app.theme.panel_bg,
-                                };
-                                let base = line_spans.last().map(|s| s.style).unwrap_or(style);
-                                base.bg(syntax_bg)
-                            }
-                            // Non-highlighted lines keep classic diff background.
-                            None => line_spans.last().map(|s| s.style).unwrap_or(style),
-                        };
-                        // Zero-width marker span carrying the background style.
-                        line_spans.push(Span::styled(String::new(), eol_style));
-                    }
-
-                    lines.push(Line::from(line_spans));
-                    line_idx += 1;
-
-                    // Show line comments for both old side (deleted lines) and new side (added/context)
-                    // Old side comments (for deleted lines)
-                    if let Some(old_ln) = diff_line.old_lineno {
-                        // Check if we're adding/editing a comment on this line (old side)
-                        let is_line_comment_mode = app.input_mode == InputMode::Comment
-                            && !app.comment_is_file_level
-                            && file_idx == app.diff_state.current_file_idx
-                            && app.comment_line == Some((old_ln, LineSide::Old));
-
-                        if let Some(comments) = line_comments.get(&old_ln) {
-                            for comment in comments {
-                                if comment.side == Some(LineSide::Old) {
-                                    // Skip if this comment is being edited
-                                    let is_being_edited = is_line_comment_mode
-                                        && app.editing_comment_id.as_ref() == Some(&comment.id);
-
-                                    if is_being_edited {
-                                        let line_range = app
-                                            .comment_line_range
-                                            .map(|(r, _)| r)
-                                            .or_else(|| Some(LineRange::single(old_ln)));
-                                        let (input_lines, cursor_info) =
-                                            comment_panel::format_comment_input_lines(
-                                                &app.theme,
-                                                comment_type_presentation(app, &app.comment_type),
-                                                &app.comment_buffer,
-                                                app.comment_cursor,
-                                                line_range,
-                                                true,
-                                                app.supports_keyboard_enhancement,
-                                            );
-                                        comment_cursor_logical_line =
-                                            Some(line_idx + cursor_info.line_offset);
-                                        comment_cursor_column = 1 + cursor_info.column;
-                                        comment_input_box_range = Some((
-                                            line_idx,
-                                            line_idx + input_lines.len().saturating_sub(1),
-                                        ));
-                                        let annotations_replaced =
-                                            2 + comment.content.split('\n').count();
-                                        app.comment_input_annotation_offset = Some((
-                                            line_idx,
-                                            input_lines.len(),
-                                            annotations_replaced,
-                                        ));
-
-                                        for mut input_line in input_lines {
-                                            let indicator =
-                                                cursor_indicator(line_idx, current_line_idx);
-                                            input_line.spans.insert(
-                                                0,
-                                                Span::styled(
-                                                    indicator,
-                                                    styles::current_line_indicator_style(
-                                                        &app.theme,
-                                                    ),
-                                                ),
-                                            );
-                                            lines.push(input_line);
-                                            line_idx += 1;
-                                        }
-                                    } else {
-                                        let line_range = comment
-                                            .line_range
-                                            .or_else(|| Some(LineRange::single(old_ln)));
-                                        let comment_lines = comment_panel::format_comment_lines(
-                                            &app.theme,
-                                            comment_type_presentation(app, &comment.comment_type),
-                                            &comment.content,
-                                            line_range,
-                                        );
-                                        for mut comment_line in comment_lines {
-                                            let is_current = line_idx == current_line_idx;
-                                            let indicator = if is_current { "▶" } else { " " };
-                                            comment_line.spans.insert(
-                                                0,
-                                                Span::styled(
-                                                    indicator,
-                                                    styles::current_line_indicator_style(
-                                                        &app.theme,
-                                                    ),
-                                                ),
-                                            );
-                                            lines.push(comment_line);
-                                            line_idx += 1;
-                                        }
-                                    }
-                                }
-                            }
-                        }
-
-                        // Render inline input for new line comment (old side)
-                        if is_line_comment_mode && app.editing_comment_id.is_none() {
-                            let line_range = app
-                                .comment_line_range
-                                .map(|(r, _)| r)
-                                .or_else(|| Some(LineRange::single(old_ln)));
-                            let (input_lines, cursor_info) =
-                                comment_panel::format_comment_input_lines(
-                                    &app.theme,
-                                    comment_type_presentation(app, &app.comment_type),
-                                    &app.comment_buffer,
-                                    app.comment_cursor,
-                                    line_range,
-                                    false,
-                                    app.supports_keyboard_enhancement,
-                                );
-                            comment_cursor_logical_line = Some(line_idx + cursor_info.line_offset);
-                            comment_cursor_column = 1 + cursor_info.column;
-                            comment_input_box_range =
-                                Some((line_idx, line_idx + input_lines.len().saturating_sub(1)));
-                            app.comment_input_annotation_offset =
-                                Some((line_idx, input_lines.len(), 0));
-
-                            for mut input_line in input_lines {
-                                let indicator = cursor_indicator(line_idx, current_line_idx);
-                                input_line.spans.insert(
-                                    0,
-                                    Span::styled(
-                                        indicator,
-                                        styles::current_line_indicator_style(&app.theme),
-                                    ),
-                                );
-                                lines.push(input_line);
-                                line_idx += 1;
-                            }
-                        }
-                    }
-
-                    // New side comments (for added/context lines)
-                    if let Some(new_ln) = diff_line.new_lineno {
-                        // Check if we're adding/editing a comment on this line (new side)
-                        let is_line_comment_mode = app.input_mode == InputMode::Comment
-                            && !app.comment_is_file_level
-                            && file_idx == app.diff_state.current_file_idx
-                            && app.comment_line == Some((new_ln, LineSide::New));
-
-                        if let Some(comments) = line_comments.get(&new_ln) {
-                            for comment in comments {
-                                if comment.side != Some(LineSide::Old) {
-                                    // Skip if this comment is being edited
-                                    let is_being_edited = is_line_comment_mode
-                                        && app.editing_comment_id.as_ref() == Some(&comment.id);
-
-                                    if is_being_edited {
-                                        let line_range = app
-                                            .comment_line_range
-                                            .map(|(r, _)| r)
-                                            .or_else(|| Some(LineRange::single(new_ln)));
-                                        let (input_lines, cursor_info) =
-                                            comment_panel::format_comment_input_lines(
-                                                &app.theme,
-                                                comment_type_presentation(app, &app.comment_type),
-                                                &app.comment_buffer,
-                                                app.comment_cursor,
-                                                line_range,
-                                                true,
-                                                app.supports_keyboard_enhancement,
-                                            );
-                                        comment_cursor_logical_line =
-                                            Some(line_idx + cursor_info.line_offset);
-                                        comment_cursor_column = 1 + cursor_info.column;
-                                        comment_input_box_range = Some((
-                                            line_idx,
-                                            line_idx + input_lines.len().saturating_sub(1),
-                                        ));
-                                        let annotations_replaced =
-                                            2 + comment.content.split('\n').count();
-                                        app.comment_input_annotation_offset = Some((
-                                            line_idx,
-                                            input_lines.len(),
-                                            annotations_replaced,
-                                        ));
-
-                                        for mut input_line in input_lines {
-                                            let indicator =
-                                                cursor_indicator(line_idx, current_line_idx);
-                                            input_line.spans.insert(
-                                                0,
-                                                Span::styled(
-                                                    indicator,
-                                                    styles::current_line_indicator_style(
-                                                        &app.theme,
-                                                    ),
-                                                ),
-                                            );
-                                            lines.push(input_line);
-                                            line_idx += 1;
-                                        }
-                                    } else {
-                                        let line_range = comment
-                                            .line_range
-                                            .or_else(|| Some(LineRange::single(new_ln)));
-                                        let comment_lines = comment_panel::format_comment_lines(
-                                            &app.theme,
-                                            comment_type_presentation(app, &comment.comment_type),
-                                            &comment.content,
-                                            line_range,
-                                        );
-                                        for mut comment_line in comment_lines {
-                                            let indicator =
-                                                cursor_indicator(line_idx, current_line_idx);
-                                            comment_line.spans.insert(
-                                                0,
-                                                Span::styled(
-                                                    indicator,
-                                                    styles::current_line_indicator_style(
-                                                        &app.theme,
-                                                    ),
-                                                ),
-                                            );
-                                            lines.push(comment_line);
-                                            line_idx += 1;
-                                        }
-                                    }
-                                }
-                            }
-                        }
-
-                        // Render inline input for new line comment (new side)
-                        if is_line_comment_mode && app.editing_comment_id.is_none() {
-                            let line_range = app
-                                .comment_line_range
-                                .map(|(r, _)| r)
-                                .or_else(|| Some(LineRange::single(new_ln)));
-                            let (input_lines, cursor_info) =
-                                comment_panel::format_comment_input_lines(
-                                    &app.theme,
-                                    comment_type_presentation(app, &app.comment_type),
-                                    &app.comment_buffer,
-                                    app.comment_cursor,
-                                    line_range,
-                                    false,
-                                    app.supports_keyboard_enhancement,
-                                );
-                            comment_cursor_logical_line = Some(line_idx + cursor_info.line_offset);
-                            comment_cursor_column = 1 + cursor_info.column;
-                            comment_input_box_range =
-                                Some((line_idx, line_idx + input_lines.len().saturating_sub(1)));
-                            app.comment_input_annotation_offset =
-                                Some((line_idx, input_lines.len(), 0));
-
-                            for mut input_line in input_lines {
-                                let indicator = cursor_indicator(line_idx, current_line_idx);
-                                input_line.spans.insert(
-                                    0,
-                                    Span::styled(
-                                        indicator,
-                                        styles::current_line_indicator_style(&app.theme),
-                                    ),
-                                );
-                                lines.push(input_line);
-                                line_idx += 1;
-                            }
-                        }
-                    }
+                let mut ctx = HunkRenderContext::new(app);
+                ctx.set_column_widths(file_idx, inner.width);
+                let (new_line_idx, cursor_info) = match app.file_view_mode(file_idx) {
+                    DiffViewMode::Unified => render_hunk_lines_unified(
+                        &hunk.lines,
+                        &line_comments,
+                        &ctx,
+                        file_idx,
+                        line_idx,
+                        &mut lines,
+                    ),
+                    DiffViewMode::SideBySide => render_hunk_lines_side_by_side(
+                        &hunk.lines,
+                        &line_comments,
+                        &ctx,
+                        file_idx,
+                        line_idx,
+                        &mut lines,
+                    ),
+                };
+                line_idx = new_line_idx;
+                if let Some((line, col, box_start, box_end, annotations_replaced)) = cursor_info {
+                    comment_cursor_logical_line = Some(line);
+                    comment_cursor_column = col;
+                    comment_input_box_range = Some((box_start, box_end));
+                    let box_len = box_end - box_start + 1;
+                    app.comment_input_annotation_offset =
+                        Some((box_start, box_len, annotations_replaced));
                 }
             }
+
+            if file.truncated_lines > 0 {
+                render_truncated_lines(
+                    &mut lines,
+                    &mut line_idx,
+                    current_line_idx,
+                    file,
+                    &app.theme,
+                );
+            }
         }
 
         // Spacing between files
@@ -1377,11 +1240,8 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
         lines.len(),
     );
 
-    let visible_lines_unscrolled: Vec<Line> = lines
-        .into_iter()
-        .skip(app.diff_state.scroll_offset)
-        .take(inner.height as usize)
-        .collect();
+    let visible_lines_unscrolled: Vec<Line> =
+        lines.into_visible(app.diff_state.scroll_offset, inner.height as usize);
 
     // Calculate the width of each line for max_content_width and visible line count
     let line_widths: Vec<usize> = visible_lines_unscrolled
@@ -1421,7 +1281,7 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
     let scroll_x = app.diff_state.scroll_x;
     let visible_lines_unscrolled_for_bg = visible_lines_unscrolled.clone();
     let visible_lines: Vec<Line> = if app.diff_state.wrap_lines {
-        visible_lines_unscrolled
+        wrap_lines(visible_lines_unscrolled, inner.width as usize, app)
     } else {
         visible_lines_unscrolled
             .into_iter()
@@ -1441,10 +1301,7 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
     );
 
     // Keep paragraph bg unset so pre-painted per-row diff backgrounds remain visible.
-    let mut diff = Paragraph::new(visible_lines).style(Style::default().fg(app.theme.fg_primary));
-    if app.diff_state.wrap_lines {
-        diff = diff.wrap(Wrap { trim: false });
-    }
+    let diff = Paragraph::new(visible_lines).style(Style::default().fg(app.theme.fg_primary));
     frame.render_widget(diff, inner);
 
     // Calculate screen position for comment cursor if in Comment mode
@@ -1471,11 +1328,7 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                 for i in 0..logical_offset {
                     if i < line_widths.len() {
                         let width = line_widths[i];
-                        let rows = if width == 0 {
-                            1
-                        } else {
-                            width.div_ceil(viewport_width)
-                        };
+                        let rows = wrapped_row_count(width, viewport_width);
                         visual_row += rows as u16;
                     } else {
                         visual_row += 1;
@@ -1496,13 +1349,17 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
 
 /// Cursor info for the inline comment input box in side-by-side view:
 /// (cursor_logical_line, cursor_column, box_start_line, box_end_line)
-type SideBySideCursorInfo = (usize, u16, usize, usize, usize);
+type CommentCursorInfo = (usize, u16, usize, usize, usize);
 
-/// Context for rendering side-by-side diff lines
-struct SideBySideContext<'a> {
+/// Context for rendering the diff lines of a hunk
+struct HunkRenderContext<'a> {
     app: &'a App,
     theme: &'a Theme,
     content_width: usize,
+    /// Digits in the line-number gutter of the file being rendered
+    lineno_width: usize,
+    /// Characters cut off the start of both side-by-side content columns
+    scroll_x: usize,
     current_line_idx: usize,
     // Comment input state for inline editing
     comment_input_mode: bool,
@@ -1516,6 +1373,114 @@ struct SideBySideContext<'a> {
     current_file_idx: usize,
 }
 
+impl<'a> HunkRenderContext<'a> {
+    fn new(app: &'a App) -> Self {
+        Self {
+            app,
+            theme: &app.theme,
+            content_width: 0,
+            lineno_width: 0,
+            scroll_x: 0,
+            current_line_idx: app.diff_state.cursor_line,
+            // Line comment mode (not file-level)
+            comment_input_mode: app.input_mode == InputMode::Comment
+                && !app.comment_is_file_level
+                && !app.comment_is_review_level,
+            comment_line: app.comment_line,
+            comment_type: app.comment_type.clone(),
+            comment_buffer: &app.comment_buffer,
+            comment_cursor: app.comment_cursor,
+            comment_line_range: app.comment_line_range.map(|(r, _)| r),
+            editing_comment_id: app.editing_comment_id.as_deref(),
+            supports_keyboard_enhancement: app.supports_keyboard_enhancement,
+            current_file_idx: app.diff_state.current_file_idx,
+        }
+    }
+
+    /// Size the side-by-side columns for the gutter of `file_idx`.
+    fn set_column_widths(&mut self, file_idx: usize, inner_width: u16) {
+        self.lineno_width = lineno_gutter_width(self.app, file_idx);
+        self.content_width = side_by_side_content_width(inner_width, self.lineno_width);
+    }
+}
+
+/// Rows of the diff built into `Line`s this frame. Rows outside the window
+/// are only counted, so very long diffs and very long files stay cheap to
+/// draw; whole files outside it are skipped by their cached height.
+struct WindowedLines<'a> {
+    start: usize,
+    end: usize,
+    /// Rows pushed or skipped so far
+    len: usize,
+    lines: Vec<Line<'a>>,
+}
+
+impl<'a> WindowedLines<'a> {
+    /// The viewport at `scroll_offset` plus one viewport of margin on each
+    /// side, which absorbs the scrolling done after the lines are built to
+    /// reveal the comment input. The file holding that input is built whole.
+    fn new(app: &App, scroll_offset: usize) -> Self {
+        let height = app.diff_state.viewport_height;
+        let mut window = Self {
+            start: scroll_offset.saturating_sub(height),
+            end: scroll_offset + 2 * height,
+            len: 0,
+            lines: Vec::with_capacity(3 * height),
+        };
+        if app.input_mode == InputMode::Comment && !app.comment_is_review_level {
+            let file_idx = app.diff_state.current_file_idx;
+            let file_start = app.calculate_file_scroll_offset(file_idx);
+            let file_end = file_start + app.file_render_height(file_idx);
+            window.start = window.start.min(file_start.saturating_sub(height));
+            window.end = window.end.max(file_end + height);
+        }
+        window
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether any of the `height` rows from `start` falls in the window.
+    fn overlaps(&self, start: usize, height: usize) -> bool {
+        start < self.end && start + height > self.start
+    }
+
+    fn push(&mut self, line: Line<'a>) {
+        self.push_with(|| line);
+    }
+
+    /// Push the row `line` builds, calling it only inside the window.
+    fn push_with(&mut self, line: impl FnOnce() -> Line<'a>) {
+        if self.overlaps(self.len, 1) {
+            self.lines.push(line());
+        }
+        self.len += 1;
+    }
+
+    fn skip(&mut self, rows: usize) {
+        self.len += rows;
+    }
+
+    /// The `height` rows from `offset`, which lie inside the window.
+    fn into_visible(self, offset: usize, height: usize) -> Vec<Line<'a>> {
+        debug_assert!(offset >= self.start);
+        self.lines
+            .into_iter()
+            .skip(offset.saturating_sub(self.start))
+            .take(height)
+            .collect()
+    }
+}
+
+impl<'a> Extend<Line<'a>> for WindowedLines<'a> {
+    fn extend<I: IntoIterator<Item = Line<'a>>>(&mut self, lines: I) {
+        for line in lines {
+            self.push(line);
+        }
+    }
+}
+
 /// Get cursor indicator (single character for inline content)
 /// Populates `out` with the visual-row -> annotation-index map for the diff
 /// viewport and returns how many logical lines fit. Reuses the buffer's
@@ -1534,11 +1499,7 @@ fn populate_row_to_annotation(
         let mut visual_rows_used = 0;
         let mut logical_lines_visible = 0;
         for (i, &width) in line_widths.iter().enumerate() {
-            let rows_for_line = if width == 0 {
-                1
-            } else {
-                width.div_ceil(viewport_width)
-            };
+            let rows_for_line = wrapped_row_count(width, viewport_width);
             if visual_rows_used + rows_for_line > viewport_height {
                 break;
             }
@@ -1557,6 +1518,76 @@ fn populate_row_to_annotation(
     }
 }
 
+/// Visual rows a line `width` columns wide takes when wrapped. Continuation
+/// rows give their first column to the wrap marker, so they hold one less.
+pub(crate) fn wrapped_row_count(width: usize, viewport_width: usize) -> usize {
+    if width <= viewport_width || viewport_width <= 1 {
+        1
+    } else {
+        1 + (width - viewport_width).div_ceil(viewport_width - 1)
+    }
+}
+
+/// Wrap each line into rows of at most `viewport_width` columns.
+fn wrap_lines<'a>(lines: Vec<Line<'a>>, viewport_width: usize, app: &App) -> Vec<Line<'a>> {
+    let marker = if app.wrap_marker.is_empty() {
+        " ".to_string()
+    } else {
+        app.wrap_marker.clone()
+    };
+    let marker = Span::styled(marker, styles::dim_style(&app.theme));
+    lines
+        .into_iter()
+        .flat_map(|line| wrap_line(line, viewport_width, &marker))
+        .collect()
+}
+
+/// Split `line` into rows. Continuation rows start with `marker` in the
+/// column the cursor indicator takes on the first row, so the wrapped text
+/// stays in the columns it would occupy unwrapped.
+fn wrap_line<'a>(line: Line<'a>, viewport_width: usize, marker: &Span<'static>) -> Vec<Line<'a>> {
+    if line.width() <= viewport_width || viewport_width <= 1 {
+        return vec![line];
+    }
+
+    let style = line.style;
+    let mut rows = vec![Line::default().style(style)];
+    let mut row_width = 0;
+    for span in line.spans {
+        let mut chunk = String::new();
+        for c in span.content.chars() {
+            let char_width = unicode_width::UnicodeWidthChar::width(c).unwrap_or(0);
+            if row_width + char_width > viewport_width {
+                if !chunk.is_empty() {
+                    let text = std::mem::take(&mut chunk);
+                    rows.last_mut()
+                        .unwrap()
+                        .spans
+                        .push(Span::styled(text, span.style));
+                }
+                rows.push(Line::from(marker.clone()).style(style));
+                row_width = marker.width();
+            }
+            chunk.push(c);
+            row_width += char_width;
+        }
+        if !chunk.is_empty() {
+            rows.last_mut()
+                .unwrap()
+                .spans
+                .push(Span::styled(chunk, span.style));
+        }
+    }
+    rows
+}
+
+/// Columns the inline comment input box can use: the diff area less the
+/// cursor indicator in front of each row.
+fn comment_input_width(app: &App) -> usize {
+    app.diff_inner_area
+        .map_or(0, |inner| inner.width.saturating_sub(1) as usize)
+}
+
 fn cursor_indicator(line_idx: usize, current_line_idx: usize) -> &'static str {
     if line_idx == current_line_idx {
         "▶"
@@ -1574,39 +1605,99 @@ fn cursor_indicator_spaced(line_idx: usize, current_line_idx: usize) -> &'static
     }
 }
 
-/// Render a single expanded context line (shared by unified + side-by-side via unified path)
+/// Blank rows reserved below an image's descriptor; the image itself is
+/// drawn over them after the frame (see `inline_image`).
+fn image_preview_lines(
+    app: &App,
+    line_idx: &mut usize,
+    current_line_idx: usize,
+) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    while matches!(
+        app.line_annotations.get(*line_idx),
+        Some(AnnotatedLine::ImagePreview { .. })
+    ) {
+        lines.push(Line::from(Span::styled(
+            cursor_indicator_spaced(*line_idx, current_line_idx),
+            styles::current_line_indicator_style(&app.theme),
+        )));
+        *line_idx += 1;
+    }
+    lines
+}
+
+/// Render a single expanded context line (shared by unified + side-by-side via unified path)
 fn render_expanded_context_line(
-    lines: &mut Vec<Line<'_>>,
+    lines: &mut WindowedLines<'_>,
     line_idx: &mut usize,
     current_line_idx: usize,
     expanded_line: &crate::model::DiffLine,
+    blame: Option<Span<'static>>,
     theme: &Theme,
 ) {
     let indicator = cursor_indicator(*line_idx, current_line_idx);
-    let line_num = expanded_line
-        .new_lineno
-        .map(|n| format!("{n:>4} "))
-        .unwrap_or_else(|| "     ".to_string());
-    let line_spans = vec![
-        Span::styled(indicator, styles::current_line_indicator_style(theme)),
-        Span::styled(line_num, styles::expanded_context_style(theme)),
-        Span::styled("  ", styles::expanded_context_style(theme)),
-        Span::styled(
-            expanded_line.content.clone(),
-            styles::expanded_context_style(theme),
-        ),
-    ];
-    lines.push(Line::from(line_spans));
+    lines.push_with(|| {
+        let line_num = expanded_line
+            .new_lineno
+            .map(|n| format!("{n:>4} "))
+            .unwrap_or_else(|| "     ".to_string());
+        let mut line_spans = vec![
+            Span::styled(indicator, styles::current_line_indicator_style(theme)),
+            Span::styled(line_num, styles::expanded_context_style(theme)),
+        ];
+        line_spans.extend(blame);
+        line_spans.extend([
+            Span::styled("  ", styles::expanded_context_style(theme)),
+            Span::styled(
+                expanded_line.content.clone(),
+                styles::expanded_context_style(theme),
+            ),
+        ]);
+        Line::from(line_spans)
+    });
     *line_idx += 1;
 }
 
+/// Columns of a `:blame` cell: short commit id, author initials and spacing.
+pub(crate) const BLAME_COLUMN_WIDTH: usize = 11;
+
+/// `:blame` cell for the line at `old_lineno` of file `file_idx`, blank for
+/// lines the blamed revision doesn't have. None for files without blame.
+fn blame_cell(app: &App, file_idx: usize, old_lineno: Option<u32>) -> Option<Span<'static>> {
+    let blame = app.blame_column(file_idx)?;
+    let text = old_lineno
+        .and_then(|line| blame.get(line.checked_sub(1)? as usize))
+        .map(|line| {
+            let short_id: String = line.commit_id.chars().take(7).collect();
+            format!("{short_id:<7} {}", author_initials(&line.author))
+        })
+        .unwrap_or_default();
+    Some(Span::styled(
+        format!("{text:<width$}", width = BLAME_COLUMN_WIDTH),
+        styles::dim_style(&app.theme),
+    ))
+}
+
+/// `AL` for "Ada Lovelace": the first letters of the first and last names.
+fn author_initials(author: &str) -> String {
+    let mut names = author.split_whitespace();
+    let first = names.next();
+    let last = names.last();
+    [first, last]
+        .into_iter()
+        .flatten()
+        .filter_map(|name| name.chars().next())
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
 /// Render an expander line with direction arrow
 fn render_expander_line(
-    lines: &mut Vec<Line<'_>>,
+    lines: &mut WindowedLines<'_>,
     line_idx: &mut usize,
     current_line_idx: usize,
     direction: ExpandDirection,
-    remaining: usize,
+    count: usize,
     theme: &Theme,
 ) {
     let arrow = match direction {
@@ -1614,7 +1705,6 @@ fn render_expander_line(
         ExpandDirection::Up => "↑",
         ExpandDirection::Both => "↕",
     };
-    let count = remaining.min(GAP_EXPAND_BATCH);
     let indicator = cursor_indicator_spaced(*line_idx, current_line_idx);
     lines.push(Line::from(vec![
         Span::styled(indicator, styles::current_line_indicator_style(theme)),
@@ -1628,18 +1718,19 @@ fn render_expander_line(
 
 /// Render a single expanded context line in side-by-side mode
 fn render_sbs_expanded_context_line(
-    lines: &mut Vec<Line<'_>>,
+    lines: &mut WindowedLines<'_>,
     line_idx: &mut usize,
     current_line_idx: usize,
     expanded_line: &crate::model::DiffLine,
     content_width: usize,
+    lineno_width: usize,
     theme: &Theme,
 ) {
     let indicator = cursor_indicator(*line_idx, current_line_idx);
-    let line_num = expanded_line
-        .new_lineno
-        .map(|n| format!("{n:>4} "))
-        .unwrap_or_else(|| "     ".to_string());
+    let line_num = format!(
+        "{} ",
+        format_gutter_lineno(expanded_line.new_lineno, lineno_width)
+    );
     let line_spans = vec![
         Span::styled(indicator, styles::current_line_indicator_style(theme)),
         Span::styled(line_num.clone(), styles::expanded_context_style(theme)),
@@ -1662,7 +1753,7 @@ fn render_sbs_expanded_context_line(
 
 /// Render a "N lines hidden" informational line
 fn render_hidden_lines(
-    lines: &mut Vec<Line<'_>>,
+    lines: &mut WindowedLines<'_>,
     line_idx: &mut usize,
     current_line_idx: usize,
     count: usize,
@@ -1679,6 +1770,43 @@ fn render_hidden_lines(
     *line_idx += 1;
 }
 
+/// Render the row standing in for unchanged lines in changes-only view
+fn render_hidden_context(
+    lines: &mut WindowedLines<'_>,
+    line_idx: &mut usize,
+    current_line_idx: usize,
+    count: usize,
+    theme: &Theme,
+) {
+    let indicator = cursor_indicator_spaced(*line_idx, current_line_idx);
+    lines.push(Line::from(vec![
+        Span::styled(indicator, styles::current_line_indicator_style(theme)),
+        Span::styled(
+            format!("       ┄┄┄ {count} unchanged lines ┄┄┄"),
+            styles::dim_style(theme),
+        ),
+    ]));
+    *line_idx += 1;
+}
+
+fn render_truncated_lines(
+    lines: &mut WindowedLines<'_>,
+    line_idx: &mut usize,
+    current_line_idx: usize,
+    file: &DiffFile,
+    theme: &Theme,
+) {
+    let indicator = cursor_indicator_spaced(*line_idx, current_line_idx);
+    lines.push(Line::from(vec![
+        Span::styled(indicator, styles::current_line_indicator_style(theme)),
+        Span::styled(
+            format!("       {}", file.truncated_descriptor()),
+            styles::dim_style(theme),
+        ),
+    ]));
+    *line_idx += 1;
+}
+
 fn comment_type_presentation(
     app: &App,
     comment_type: &crate::model::CommentType,
@@ -1686,7 +1814,59 @@ fn comment_type_presentation(
     comment_panel::CommentTypePresentation {
         label: app.comment_type_label(comment_type),
         color: app.comment_type_color(comment_type),
+        reply: app.reply_to.is_some(),
+        age: None,
+        author: None,
+    }
+}
+
+/// Presentation of a saved comment; resolved comments get a check mark and
+/// the header shows when the comment was written and last edited.
+/// A hunk's `@@` header, marked like reviewed files when it is reviewed and
+/// its lines are collapsed.
+fn hunk_header_line(
+    app: &App,
+    indicator: &'static str,
+    hunk: &crate::model::DiffHunk,
+    reviewed: bool,
+) -> Line<'static> {
+    let mut spans = vec![Span::styled(
+        indicator,
+        styles::current_line_indicator_style(&app.theme),
+    )];
+    if reviewed {
+        spans.push(Span::styled("✓ ", styles::reviewed_style(&app.theme)));
     }
+    spans.push(Span::styled(
+        hunk.header.to_string(),
+        styles::diff_hunk_header_style(&app.theme),
+    ));
+    Line::from(spans)
+}
+
+fn comment_presentation(
+    app: &App,
+    comment: &crate::model::Comment,
+) -> comment_panel::CommentTypePresentation {
+    let mut presentation = comment_type_presentation(app, &comment.comment_type);
+    if comment.resolved {
+        presentation.label.push_str(" ✓");
+    }
+    if comment.orphaned {
+        presentation.label.push_str(" detached");
+    }
+    presentation.reply = comment.parent_id.is_some();
+    presentation.author = comment.author.clone();
+    let now = chrono::Utc::now();
+    let created = comment_panel::format_age(comment.created_at, now);
+    presentation.age = Some(match comment.updated_at {
+        Some(updated) => format!(
+            "{created}, edited {}",
+            comment_panel::format_age(updated, now)
+        ),
+        None => created,
+    });
+    presentation
 }
 
 /// Adjust scroll_offset so the comment input box is visible in the viewport.
@@ -1737,14 +1917,7 @@ fn scroll_comment_input_into_view(
 fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
     let focused = app.focused_panel == FocusedPanel::Diff;
 
-    let title = if app.is_cursor_in_overview() || app.current_file_path().is_none() {
-        " Diff (Side-by-Side) \u{2014} Overview ".to_string()
-    } else {
-        format!(
-            " Diff (Side-by-Side) \u{2014} {} ",
-            app.current_file_path().unwrap().display()
-        )
-    };
+    let title = diff_title(app);
 
     let block = Block::default()
         .title(title)
@@ -1763,32 +1936,27 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
     // Reset comment input annotation offset (will be set if a comment input box is rendered)
     app.comment_input_annotation_offset = None;
 
-    // Calculate column widths (split the area in half)
-    // Layout: indicator(1) + linenum(4) + space(1) + prefix(1) + content + " │ "(3) + linenum(4) + space(1) + prefix(1) + content
-    // Total overhead: 1 + 5 + 1 + 3 + 5 + 1 = 16
-    let available_width = inner.width.saturating_sub(16) as usize;
-    let content_width = available_width / 2;
-
-    // Determine if we're in line comment mode (not file-level)
-    let comment_input_mode = app.input_mode == InputMode::Comment
-        && !app.comment_is_file_level
-        && !app.comment_is_review_level;
+    // Both content columns scroll together, so the gutters, separator and
+    // comments stay in place
+    let visible_rows =
+        app.diff_state.scroll_offset..app.diff_state.scroll_offset + inner.height as usize;
+    let current_lineno_width = lineno_gutter_width(app, app.diff_state.current_file_idx);
+    app.diff_state.viewport_width = side_by_side_content_width(inner.width, current_lineno_width);
+    app.diff_state.max_content_width = app.widest_pane_content(visible_rows);
+    let max_scroll_x = app
+        .diff_state
+        .max_content_width
+        .saturating_sub(app.diff_state.viewport_width);
+    if app.diff_state.scroll_x > max_scroll_x {
+        app.diff_state.scroll_x = max_scroll_x;
+    }
+    if app.diff_state.wrap_lines {
+        app.diff_state.scroll_x = 0;
+    }
 
-    let ctx = SideBySideContext {
-        app,
-        theme: &app.theme,
-        content_width,
-        current_line_idx: app.diff_state.cursor_line,
-        comment_input_mode,
-        comment_line: app.comment_line,
-        comment_type: app.comment_type.clone(),
-        comment_buffer: &app.comment_buffer,
-        comment_cursor: app.comment_cursor,
-        comment_line_range: app.comment_line_range.map(|(r, _)| r),
-        editing_comment_id: app.editing_comment_id.as_deref(),
-        supports_keyboard_enhancement: app.supports_keyboard_enhancement,
-        current_file_idx: app.diff_state.current_file_idx,
-    };
+    // Column widths depend on the gutter width, so they are set per file
+    let mut ctx = HunkRenderContext::new(app);
+    ctx.scroll_x = app.diff_state.scroll_x;
 
     // Build all diff lines for side-by-side view
     let mut lines: Vec<Line> = Vec::new();
@@ -1832,6 +2000,7 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                 None,
                 true,
                 app.supports_keyboard_enhancement,
+                comment_input_width(app),
             );
             comment_cursor_logical_line = Some(line_idx + cursor_info.line_offset);
             comment_cursor_column = 1 + cursor_info.column;
@@ -1852,7 +2021,7 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
         } else {
             let comment_lines = comment_panel::format_comment_lines(
                 &app.theme,
-                comment_type_presentation(app, &comment.comment_type),
+                comment_presentation(app, comment),
                 &comment.content,
                 None,
             );
@@ -1877,6 +2046,7 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
             None,
             false,
             app.supports_keyboard_enhancement,
+            comment_input_width(app),
         );
         comment_cursor_logical_line = Some(line_idx + cursor_info.line_offset);
         comment_cursor_column = 1 + cursor_info.column;
@@ -1894,8 +2064,29 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
         }
     }
 
+    // Review comments come first, so the scrolling that reveals their input
+    // box is known here and the window goes where the viewport ends up
+    let mut scroll_offset = app.diff_state.scroll_offset;
+    scroll_comment_input_into_view(
+        &mut scroll_offset,
+        comment_input_box_range,
+        comment_cursor_logical_line,
+        inner.height as usize,
+        app.total_lines(),
+    );
+    let review_lines = lines;
+    let mut lines = WindowedLines::new(app, scroll_offset);
+    lines.extend(review_lines);
     for (file_idx, file) in app.diff_files.iter().enumerate() {
+        let height = app.file_render_height(file_idx);
+        if !lines.overlaps(line_idx, height) {
+            lines.skip(height);
+            line_idx += height;
+            continue;
+        }
+
         let path = file.display_path();
+        ctx.set_column_widths(file_idx, inner.width);
         let status = file.status.as_char();
         let is_reviewed = app.session.is_file_reviewed(path);
 
@@ -1906,6 +2097,20 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
 
         let header_text = if file.is_commit_message {
             format!("═══ {}Commit Message ", review_mark)
+        } else if file.is_submodule {
+            format!(
+                "═══ {}{} [{}] submodule ",
+                review_mark,
+                path.display(),
+                status
+            )
+        } else if file.is_generated {
+            format!(
+                "═══ {}{} [{}] generated ",
+                review_mark,
+                path.display(),
+                status
+            )
         } else {
             format!("═══ {}{} [{}] ", review_mark, path.display(), status)
         };
@@ -1916,8 +2121,8 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
         ]));
         line_idx += 1;
 
-        // If file is reviewed, skip rendering the body
-        if is_reviewed {
+        // If file is collapsed, skip rendering the body
+        if app.is_file_collapsed(file) {
             continue;
         }
 
@@ -1943,6 +2148,7 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                         None,
                         true,
                         app.supports_keyboard_enhancement,
+                        comment_input_width(app),
                     );
                     comment_cursor_logical_line = Some(line_idx + cursor_info.line_offset);
                     comment_cursor_column = 1 + cursor_info.column;
@@ -1966,9 +2172,9 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                 } else {
                     let comment_lines = comment_panel::format_comment_lines(
                         &app.theme,
-                        comment_type_presentation(app, &comment.comment_type),
+                        comment_presentation(app, comment),
                         &comment.content,
-                        None,
+                        comment.line_range.filter(|_| comment.orphaned),
                     );
                     for mut comment_line in comment_lines {
                         let indicator = cursor_indicator(line_idx, ctx.current_line_idx);
@@ -1996,6 +2202,7 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                 None,
                 false,
                 app.supports_keyboard_enhancement,
+                comment_input_width(app),
             );
             comment_cursor_logical_line = Some(line_idx + cursor_info.line_offset);
             comment_cursor_column = 1 + cursor_info.column;
@@ -2014,6 +2221,16 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
             }
         }
 
+        let metadata = file.metadata_descriptor();
+        if let Some(descriptor) = &metadata {
+            let indicator = cursor_indicator_spaced(line_idx, ctx.current_line_idx);
+            lines.push(Line::from(vec![
+                Span::styled(indicator, styles::current_line_indicator_style(&app.theme)),
+                Span::styled(descriptor.clone(), styles::dim_style(&app.theme)),
+            ]));
+            line_idx += 1;
+        }
+
         if file.is_too_large {
             let indicator = cursor_indicator_spaced(line_idx, ctx.current_line_idx);
             lines.push(Line::from(vec![
@@ -2025,10 +2242,15 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
             let indicator = cursor_indicator_spaced(line_idx, ctx.current_line_idx);
             lines.push(Line::from(vec![
                 Span::styled(indicator, styles::current_line_indicator_style(&app.theme)),
-                Span::styled("(binary file)", styles::dim_style(&app.theme)),
+                Span::styled(file.binary_descriptor(), styles::dim_style(&app.theme)),
             ]));
             line_idx += 1;
-        } else if file.hunks.is_empty() {
+            lines.extend(image_preview_lines(
+                app,
+                &mut line_idx,
+                ctx.current_line_idx,
+            ));
+        } else if file.hunks.is_empty() && metadata.is_none() && file.truncated_lines == 0 {
             let indicator = cursor_indicator_spaced(line_idx, ctx.current_line_idx);
             lines.push(Line::from(vec![
                 Span::styled(indicator, styles::current_line_indicator_style(&app.theme)),
@@ -2073,8 +2295,9 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                                 &mut lines,
                                 &mut line_idx,
                                 ctx.current_line_idx,
-                                expanded_line,
+                                &scrolled_line(expanded_line, ctx.scroll_x),
                                 ctx.content_width,
+                                ctx.lineno_width,
                                 &app.theme,
                             );
                         }
@@ -2083,7 +2306,7 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                     // Render expanders / hidden lines
                     if remaining > 0 {
                         if is_top_of_file {
-                            if remaining > GAP_EXPAND_BATCH {
+                            if remaining > app.context_expand_lines {
                                 render_hidden_lines(
                                     &mut lines,
                                     &mut line_idx,
@@ -2097,16 +2320,16 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                                 &mut line_idx,
                                 ctx.current_line_idx,
                                 ExpandDirection::Up,
-                                remaining,
+                                remaining.min(app.context_expand_lines),
                                 &app.theme,
                             );
-                        } else if remaining >= GAP_EXPAND_BATCH {
+                        } else if remaining >= app.context_expand_lines {
                             render_expander_line(
                                 &mut lines,
                                 &mut line_idx,
                                 ctx.current_line_idx,
                                 ExpandDirection::Down,
-                                remaining,
+                                remaining.min(app.context_expand_lines),
                                 &app.theme,
                             );
                             render_hidden_lines(
@@ -2121,7 +2344,7 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                                 &mut line_idx,
                                 ctx.current_line_idx,
                                 ExpandDirection::Up,
-                                remaining,
+                                remaining.min(app.context_expand_lines),
                                 &app.theme,
                             );
                         } else {
@@ -2130,7 +2353,7 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                                 &mut line_idx,
                                 ctx.current_line_idx,
                                 ExpandDirection::Both,
-                                remaining,
+                                remaining.min(app.context_expand_lines),
                                 &app.theme,
                             );
                         }
@@ -2143,8 +2366,9 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                                 &mut lines,
                                 &mut line_idx,
                                 ctx.current_line_idx,
-                                expanded_line,
+                                &scrolled_line(expanded_line, ctx.scroll_x),
                                 ctx.content_width,
+                                ctx.lineno_width,
                                 &app.theme,
                             );
                         }
@@ -2153,17 +2377,19 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
 
                 // Hunk header
                 let indicator = cursor_indicator_spaced(line_idx, ctx.current_line_idx);
-                lines.push(Line::from(vec![
-                    Span::styled(indicator, styles::current_line_indicator_style(&app.theme)),
-                    Span::styled(
-                        hunk.header.to_string(),
-                        styles::diff_hunk_header_style(&app.theme),
-                    ),
-                ]));
+                let hunk_reviewed = app.is_hunk_reviewed(file_idx, hunk_idx);
+                lines.push(hunk_header_line(app, indicator, hunk, hunk_reviewed));
                 line_idx += 1;
+                if hunk_reviewed {
+                    continue;
+                }
 
-                // Process diff lines in side-by-side format
-                let (new_line_idx, cursor_info) = render_hunk_lines_side_by_side(
+                // Process diff lines in the file's format
+                let render_hunk_lines = match app.file_view_mode(file_idx) {
+                    DiffViewMode::Unified => render_hunk_lines_unified,
+                    DiffViewMode::SideBySide => render_hunk_lines_side_by_side,
+                };
+                let (new_line_idx, cursor_info) = render_hunk_lines(
                     &hunk.lines,
                     &line_comments,
                     &ctx,
@@ -2180,6 +2406,16 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                     annotation_offset = Some((box_start, box_len, annotations_replaced));
                 }
             }
+
+            if file.truncated_lines > 0 {
+                render_truncated_lines(
+                    &mut lines,
+                    &mut line_idx,
+                    ctx.current_line_idx,
+                    file,
+                    &app.theme,
+                );
+            }
         }
 
         // Spacing between files
@@ -2203,11 +2439,8 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
         lines.len(),
     );
 
-    let visible_lines_unscrolled: Vec<Line> = lines
-        .into_iter()
-        .skip(app.diff_state.scroll_offset)
-        .take(inner.height as usize)
-        .collect();
+    let visible_lines_unscrolled: Vec<Line> =
+        lines.into_visible(app.diff_state.scroll_offset, inner.height as usize);
 
     // Calculate the width of each line for max_content_width and visible line count
     let line_widths: Vec<usize> = visible_lines_unscrolled
@@ -2220,11 +2453,6 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
         })
         .collect();
 
-    let max_content_width = line_widths.iter().copied().max().unwrap_or(0);
-
-    app.diff_state.viewport_width = inner.width as usize;
-    app.diff_state.max_content_width = max_content_width;
-
     let scroll_offset = app.diff_state.scroll_offset;
     let wrap = app.diff_state.wrap_lines;
     app.diff_state.visible_line_count = populate_row_to_annotation(
@@ -2236,28 +2464,14 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
         scroll_offset,
     );
 
-    let max_scroll_x = max_content_width.saturating_sub(inner.width as usize);
-    if app.diff_state.scroll_x > max_scroll_x {
-        app.diff_state.scroll_x = max_scroll_x;
-    }
-    if app.diff_state.wrap_lines {
-        app.diff_state.scroll_x = 0;
-    }
-
-    let scroll_x = app.diff_state.scroll_x;
+    // Content columns were already scrolled while building the rows
     let visible_lines: Vec<Line> = if app.diff_state.wrap_lines {
-        visible_lines_unscrolled
+        wrap_lines(visible_lines_unscrolled, inner.width as usize, app)
     } else {
         visible_lines_unscrolled
-            .into_iter()
-            .map(|line| apply_horizontal_scroll(line, scroll_x))
-            .collect()
     };
 
-    let mut diff = Paragraph::new(visible_lines).style(styles::panel_style(&app.theme));
-    if app.diff_state.wrap_lines {
-        diff = diff.wrap(Wrap { trim: false });
-    }
+    let diff = Paragraph::new(visible_lines).style(styles::panel_style(&app.theme));
     frame.render_widget(diff, inner);
 
     // Paint cursor/selection line highlights for side-by-side view
@@ -2297,26 +2511,463 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                 for i in 0..logical_offset {
                     if i < line_widths.len() {
                         let width = line_widths[i];
-                        let rows = if width == 0 {
-                            1
-                        } else {
-                            width.div_ceil(viewport_width)
-                        };
+                        let rows = wrapped_row_count(width, viewport_width);
                         visual_row += rows as u16;
                     } else {
                         visual_row += 1;
                     }
                 }
-            } else {
-                visual_row = logical_offset as u16;
+            } else {
+                visual_row = logical_offset as u16;
+            }
+
+            let screen_col = inner.x + comment_cursor_column;
+            let screen_row_abs = inner.y + visual_row;
+
+            app.comment_cursor_screen_pos = Some((screen_col, screen_row_abs));
+        }
+    }
+}
+
+/// Process and render all diff lines in a hunk for unified view
+/// Returns (new_line_idx, optional cursor info for inline comment input)
+/// Index of the trailer block (`Signed-off-by:`, `Co-authored-by:`, ...)
+/// closing a commit message: its last paragraph, when every line of it is a
+/// `Token: value` pair and it isn't the summary.
+fn commit_trailers_start(message: &[crate::model::DiffLine]) -> Option<usize> {
+    let blank = message
+        .iter()
+        .rposition(|line| line.content.trim().is_empty())?;
+    let paragraph = &message[blank + 1..];
+    let is_trailer = |text: &str| {
+        text.split_once(": ").is_some_and(|(token, value)| {
+            !token.is_empty()
+                && !value.trim().is_empty()
+                && token.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+    };
+    (!paragraph.is_empty() && paragraph.iter().all(|line| is_trailer(&line.content)))
+        .then_some(blank + 1)
+}
+
+fn render_hunk_lines_unified(
+    hunk_lines: &[crate::model::DiffLine],
+    line_comments: &std::collections::HashMap<u32, Vec<crate::model::Comment>>,
+    ctx: &HunkRenderContext,
+    file_idx: usize,
+    mut line_idx: usize,
+    lines: &mut WindowedLines,
+) -> (usize, Option<CommentCursorInfo>) {
+    let app = ctx.app;
+    let current_line_idx = ctx.current_line_idx;
+    let show_conflicts = shows_conflict_markers(ctx, file_idx);
+    let mut comment_cursor_logical_line: Option<usize> = None;
+    let mut comment_cursor_column: u16 = 0;
+    let mut comment_input_box_range: Option<(usize, usize)> = None;
+    let mut annotation_offset: Option<(usize, usize, usize)> = None;
+    let is_commit_message = app.diff_files[file_idx].is_commit_message;
+    let trailers_start = if is_commit_message {
+        commit_trailers_start(hunk_lines)
+    } else {
+        None
+    };
+
+    let mut hidden_until = 0;
+    for (i, diff_line) in hunk_lines.iter().enumerate() {
+        if i < hidden_until {
+            continue;
+        }
+        let hidden = if app.changes_only && !is_commit_message {
+            hidden_context_run(hunk_lines, i, line_comments)
+        } else {
+            0
+        };
+        if hidden > 0 {
+            render_hidden_context(lines, &mut line_idx, current_line_idx, hidden, &app.theme);
+            hidden_until = i + hidden;
+            continue;
+        }
+
+        // Only rows inside the render window are built
+        lines.push_with(|| {
+            let (prefix, base_style) = match diff_line.origin {
+                LineOrigin::Addition => ("+", styles::diff_add_style(&app.theme)),
+                LineOrigin::Deletion => ("-", styles::diff_del_style(&app.theme)),
+                _ if is_commit_message && i == 0 => (" ", styles::commit_summary_style(&app.theme)),
+                _ if trailers_start.is_some_and(|start| i >= start) => {
+                    (" ", styles::dim_style(&app.theme))
+                }
+                LineOrigin::Context => (" ", styles::diff_context_style(&app.theme)),
+            };
+            let moved = app.detect_moves && diff_line.move_id.is_some();
+            let base_style = if moved {
+                styles::diff_moved_style(&app.theme, diff_line.origin)
+            } else {
+                base_style
+            };
+
+            // Check if this line is in visual selection
+            let is_in_visual_selection = {
+                let line_num = match diff_line.origin {
+                    LineOrigin::Addition | LineOrigin::Context => diff_line.new_lineno,
+                    LineOrigin::Deletion => diff_line.old_lineno,
+                };
+                let side = match diff_line.origin {
+                    LineOrigin::Addition | LineOrigin::Context => LineSide::New,
+                    LineOrigin::Deletion => LineSide::Old,
+                };
+                line_num
+                    .map(|ln| app.is_line_in_visual_selection(ln, side))
+                    .unwrap_or(false)
+            };
+
+            // Apply visual selection highlighting if applicable
+            let style = if is_in_visual_selection {
+                base_style.patch(styles::visual_selection_style(&app.theme))
+            } else {
+                base_style
+            };
+
+            let line_num_str = match diff_line.origin {
+                LineOrigin::Addition => diff_line
+                    .new_lineno
+                    .map(|n| format!("{n:>4} "))
+                    .unwrap_or_else(|| "     ".to_string()),
+                LineOrigin::Deletion => diff_line
+                    .old_lineno
+                    .map(|n| format!("{n:>4} "))
+                    .unwrap_or_else(|| "     ".to_string()),
+                _ => diff_line
+                    .new_lineno
+                    .or(diff_line.old_lineno)
+                    .map(|n| format!("{n:>4} "))
+                    .unwrap_or_else(|| "     ".to_string()),
+            };
+
+            let indicator = cursor_indicator(line_idx, current_line_idx);
+
+            // Build line spans - use syntax highlighting if available
+            let line_num_style = if is_in_visual_selection {
+                styles::dim_style(&app.theme).patch(styles::visual_selection_style(&app.theme))
+            } else {
+                styles::dim_style(&app.theme)
+            };
+
+            let mut line_spans = vec![
+                Span::styled(indicator, styles::current_line_indicator_style(&app.theme)),
+                Span::styled(line_num_str, line_num_style),
+            ];
+            let blamed_line = match diff_line.origin {
+                LineOrigin::Addition => None,
+                LineOrigin::Deletion | LineOrigin::Context => diff_line.old_lineno,
+            };
+            line_spans.extend(blame_cell(app, file_idx, blamed_line));
+            line_spans.push(Span::styled(format!("{prefix} "), style));
+
+            // Add content spans
+            if show_conflicts && diff_line.is_conflict_marker() {
+                line_spans.push(Span::styled(
+                    diff_line.content.clone(),
+                    style.patch(styles::conflict_marker_style(&app.theme)),
+                ));
+            } else if moved {
+                // Moved blocks use their own color instead of syntax highlighting
+                line_spans.push(Span::styled(
+                    diff_line.content.clone(),
+                    whitespace_only_style(style, diff_line),
+                ));
+            } else if let Some(ref highlighted) = diff_line.highlighted_spans {
+                // Use syntax-highlighted spans
+                for (span_style, span_text) in highlighted {
+                    let final_style = if is_in_visual_selection {
+                        span_style.patch(styles::visual_selection_style(&app.theme))
+                    } else {
+                        *span_style
+                    };
+                    line_spans.push(Span::styled(
+                        span_text.clone(),
+                        whitespace_only_style(final_style, diff_line),
+                    ));
+                }
+            } else {
+                // Fall back to default diff styling
+                line_spans.push(Span::styled(
+                    diff_line.content.clone(),
+                    whitespace_only_style(style, diff_line),
+                ));
+            }
+
+            // Mark add/del lines with their effective EOL style so we can paint full
+            // row backgrounds later (including wrapped visual rows).
+            if matches!(
+                diff_line.origin,
+                LineOrigin::Addition | LineOrigin::Deletion
+            ) {
+                let eol_style = match diff_line.highlighted_spans.as_ref() {
+                    // For syntax-highlighted lines (including empty highlighted lines),
+                    // use syntax diff background so row fill matches code spans.
+                    Some(_) => {
+                        let syntax_bg = match diff_line.origin {
+                            LineOrigin::Addition => app.theme.syntax_add_bg,
+                            LineOrigin::Deletion => app.theme.syntax_del_bg,
+                            LineOrigin::Context => app.theme.panel_bg,
+                        };
+                        let base = line_spans.last().map(|s| s.style).unwrap_or(style);
+                        base.bg(syntax_bg)
+                    }
+                    // Non-highlighted lines keep classic diff background.
+                    None => line_spans.last().map(|s| s.style).unwrap_or(style),
+                };
+                // Zero-width marker span carrying the background style.
+                line_spans.push(Span::styled(String::new(), eol_style));
+            }
+
+            Line::from(line_spans)
+        });
+        line_idx += 1;
+
+        // Show line comments for both old side (deleted lines) and new side (added/context)
+        // Old side comments (for deleted lines)
+        if let Some(old_ln) = diff_line.old_lineno {
+            // Check if we're adding/editing a comment on this line (old side)
+            let is_line_comment_mode = app.input_mode == InputMode::Comment
+                && !app.comment_is_file_level
+                && file_idx == app.diff_state.current_file_idx
+                && app.comment_line == Some((old_ln, LineSide::Old));
+
+            if let Some(comments) = line_comments.get(&old_ln) {
+                for comment in comments {
+                    if comment.side == Some(LineSide::Old) {
+                        // Skip if this comment is being edited
+                        let is_being_edited = is_line_comment_mode
+                            && app.editing_comment_id.as_ref() == Some(&comment.id);
+
+                        if is_being_edited {
+                            let line_range = app
+                                .comment_line_range
+                                .map(|(r, _)| r)
+                                .or_else(|| Some(LineRange::single(old_ln)));
+                            let (input_lines, cursor_info) =
+                                comment_panel::format_comment_input_lines(
+                                    &app.theme,
+                                    comment_type_presentation(app, &app.comment_type),
+                                    &app.comment_buffer,
+                                    app.comment_cursor,
+                                    line_range,
+                                    true,
+                                    app.supports_keyboard_enhancement,
+                                    comment_input_width(app),
+                                );
+                            comment_cursor_logical_line = Some(line_idx + cursor_info.line_offset);
+                            comment_cursor_column = 1 + cursor_info.column;
+                            comment_input_box_range =
+                                Some((line_idx, line_idx + input_lines.len().saturating_sub(1)));
+                            let annotations_replaced = 2 + comment.content.split('\n').count();
+                            annotation_offset =
+                                Some((line_idx, input_lines.len(), annotations_replaced));
+
+                            for mut input_line in input_lines {
+                                let indicator = cursor_indicator(line_idx, current_line_idx);
+                                input_line.spans.insert(
+                                    0,
+                                    Span::styled(
+                                        indicator,
+                                        styles::current_line_indicator_style(&app.theme),
+                                    ),
+                                );
+                                lines.push(input_line);
+                                line_idx += 1;
+                            }
+                        } else {
+                            let line_range = comment
+                                .line_range
+                                .or_else(|| Some(LineRange::single(old_ln)));
+                            let comment_lines = comment_panel::format_comment_lines(
+                                &app.theme,
+                                comment_presentation(app, comment),
+                                &comment.content,
+                                line_range,
+                            );
+                            for mut comment_line in comment_lines {
+                                let is_current = line_idx == current_line_idx;
+                                let indicator = if is_current { "▶" } else { " " };
+                                comment_line.spans.insert(
+                                    0,
+                                    Span::styled(
+                                        indicator,
+                                        styles::current_line_indicator_style(&app.theme),
+                                    ),
+                                );
+                                lines.push(comment_line);
+                                line_idx += 1;
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Render inline input for new line comment (old side)
+            if is_line_comment_mode && app.editing_comment_id.is_none() {
+                let line_range = app
+                    .comment_line_range
+                    .map(|(r, _)| r)
+                    .or_else(|| Some(LineRange::single(old_ln)));
+                let (input_lines, cursor_info) = comment_panel::format_comment_input_lines(
+                    &app.theme,
+                    comment_type_presentation(app, &app.comment_type),
+                    &app.comment_buffer,
+                    app.comment_cursor,
+                    line_range,
+                    false,
+                    app.supports_keyboard_enhancement,
+                    comment_input_width(app),
+                );
+                comment_cursor_logical_line = Some(line_idx + cursor_info.line_offset);
+                comment_cursor_column = 1 + cursor_info.column;
+                comment_input_box_range =
+                    Some((line_idx, line_idx + input_lines.len().saturating_sub(1)));
+                annotation_offset = Some((line_idx, input_lines.len(), 0));
+
+                for mut input_line in input_lines {
+                    let indicator = cursor_indicator(line_idx, current_line_idx);
+                    input_line.spans.insert(
+                        0,
+                        Span::styled(indicator, styles::current_line_indicator_style(&app.theme)),
+                    );
+                    lines.push(input_line);
+                    line_idx += 1;
+                }
+            }
+        }
+
+        // New side comments (for added/context lines)
+        if let Some(new_ln) = diff_line.new_lineno {
+            // Check if we're adding/editing a comment on this line (new side)
+            let is_line_comment_mode = app.input_mode == InputMode::Comment
+                && !app.comment_is_file_level
+                && file_idx == app.diff_state.current_file_idx
+                && app.comment_line == Some((new_ln, LineSide::New));
+
+            if let Some(comments) = line_comments.get(&new_ln) {
+                for comment in comments {
+                    if comment.side != Some(LineSide::Old) {
+                        // Skip if this comment is being edited
+                        let is_being_edited = is_line_comment_mode
+                            && app.editing_comment_id.as_ref() == Some(&comment.id);
+
+                        if is_being_edited {
+                            let line_range = app
+                                .comment_line_range
+                                .map(|(r, _)| r)
+                                .or_else(|| Some(LineRange::single(new_ln)));
+                            let (input_lines, cursor_info) =
+                                comment_panel::format_comment_input_lines(
+                                    &app.theme,
+                                    comment_type_presentation(app, &app.comment_type),
+                                    &app.comment_buffer,
+                                    app.comment_cursor,
+                                    line_range,
+                                    true,
+                                    app.supports_keyboard_enhancement,
+                                    comment_input_width(app),
+                                );
+                            comment_cursor_logical_line = Some(line_idx + cursor_info.line_offset);
+                            comment_cursor_column = 1 + cursor_info.column;
+                            comment_input_box_range =
+                                Some((line_idx, line_idx + input_lines.len().saturating_sub(1)));
+                            let annotations_replaced = 2 + comment.content.split('\n').count();
+                            annotation_offset =
+                                Some((line_idx, input_lines.len(), annotations_replaced));
+
+                            for mut input_line in input_lines {
+                                let indicator = cursor_indicator(line_idx, current_line_idx);
+                                input_line.spans.insert(
+                                    0,
+                                    Span::styled(
+                                        indicator,
+                                        styles::current_line_indicator_style(&app.theme),
+                                    ),
+                                );
+                                lines.push(input_line);
+                                line_idx += 1;
+                            }
+                        } else {
+                            let line_range = comment
+                                .line_range
+                                .or_else(|| Some(LineRange::single(new_ln)));
+                            let comment_lines = comment_panel::format_comment_lines(
+                                &app.theme,
+                                comment_presentation(app, comment),
+                                &comment.content,
+                                line_range,
+                            );
+                            for mut comment_line in comment_lines {
+                                let indicator = cursor_indicator(line_idx, current_line_idx);
+                                comment_line.spans.insert(
+                                    0,
+                                    Span::styled(
+                                        indicator,
+                                        styles::current_line_indicator_style(&app.theme),
+                                    ),
+                                );
+                                lines.push(comment_line);
+                                line_idx += 1;
+                            }
+                        }
+                    }
+                }
             }
 
-            let screen_col = inner.x + comment_cursor_column;
-            let screen_row_abs = inner.y + visual_row;
+            // Render inline input for new line comment (new side)
+            if is_line_comment_mode && app.editing_comment_id.is_none() {
+                let line_range = app
+                    .comment_line_range
+                    .map(|(r, _)| r)
+                    .or_else(|| Some(LineRange::single(new_ln)));
+                let (input_lines, cursor_info) = comment_panel::format_comment_input_lines(
+                    &app.theme,
+                    comment_type_presentation(app, &app.comment_type),
+                    &app.comment_buffer,
+                    app.comment_cursor,
+                    line_range,
+                    false,
+                    app.supports_keyboard_enhancement,
+                    comment_input_width(app),
+                );
+                comment_cursor_logical_line = Some(line_idx + cursor_info.line_offset);
+                comment_cursor_column = 1 + cursor_info.column;
+                comment_input_box_range =
+                    Some((line_idx, line_idx + input_lines.len().saturating_sub(1)));
+                annotation_offset = Some((line_idx, input_lines.len(), 0));
 
-            app.comment_cursor_screen_pos = Some((screen_col, screen_row_abs));
+                for mut input_line in input_lines {
+                    let indicator = cursor_indicator(line_idx, current_line_idx);
+                    input_line.spans.insert(
+                        0,
+                        Span::styled(indicator, styles::current_line_indicator_style(&app.theme)),
+                    );
+                    lines.push(input_line);
+                    line_idx += 1;
+                }
+            }
         }
     }
+
+    let cursor_info = comment_cursor_logical_line
+        .zip(comment_input_box_range)
+        .zip(annotation_offset)
+        .map(
+            |((line, (box_start, box_end)), (_, _, annotations_replaced))| {
+                (
+                    line,
+                    comment_cursor_column,
+                    box_start,
+                    box_end,
+                    annotations_replaced,
+                )
+            },
+        );
+    (line_idx, cursor_info)
 }
 
 /// Process and render all diff lines in a hunk for side-by-side view
@@ -2324,17 +2975,34 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
 fn render_hunk_lines_side_by_side(
     hunk_lines: &[crate::model::DiffLine],
     line_comments: &std::collections::HashMap<u32, Vec<crate::model::Comment>>,
-    ctx: &SideBySideContext,
+    ctx: &HunkRenderContext,
     file_idx: usize,
     mut line_idx: usize,
-    lines: &mut Vec<Line>,
-) -> (usize, Option<SideBySideCursorInfo>) {
+    lines: &mut WindowedLines,
+) -> (usize, Option<CommentCursorInfo>) {
     let mut i = 0;
-    let mut cursor_info_out: Option<SideBySideCursorInfo> = None;
+    let mut cursor_info_out: Option<CommentCursorInfo> = None;
 
     while i < hunk_lines.len() {
         let diff_line = &hunk_lines[i];
 
+        let hidden = if ctx.app.changes_only {
+            hidden_context_run(hunk_lines, i, line_comments)
+        } else {
+            0
+        };
+        if hidden > 0 {
+            render_hidden_context(
+                lines,
+                &mut line_idx,
+                ctx.current_line_idx,
+                hidden,
+                ctx.theme,
+            );
+            i += hidden;
+            continue;
+        }
+
         match diff_line.origin {
             LineOrigin::Context => {
                 let (new_line_idx, cursor_info) = render_context_line_side_by_side(
@@ -2393,67 +3061,73 @@ fn render_hunk_lines_side_by_side(
 fn render_context_line_side_by_side(
     diff_line: &crate::model::DiffLine,
     line_comments: &std::collections::HashMap<u32, Vec<crate::model::Comment>>,
-    ctx: &SideBySideContext,
+    ctx: &HunkRenderContext,
     file_idx: usize,
     mut line_idx: usize,
-    lines: &mut Vec<Line>,
-) -> (usize, Option<SideBySideCursorInfo>) {
-    let line_num = diff_line
-        .old_lineno
-        .or(diff_line.new_lineno)
-        .map(|n| format!("{n:>4}"))
-        .unwrap_or_else(|| "    ".to_string());
-
-    let indicator = cursor_indicator(line_idx, ctx.current_line_idx);
-
-    let mut spans = vec![
-        Span::styled(indicator, styles::current_line_indicator_style(ctx.theme)),
-        Span::styled(format!("{line_num} "), styles::dim_style(ctx.theme)),
-        Span::styled(" ".to_string(), styles::diff_context_style(ctx.theme)),
-    ];
-
-    // Left side content - use syntax highlighting if available
-    if let Some(ref highlighted) = diff_line.highlighted_spans {
-        let content_spans = truncate_or_pad_spans(
-            highlighted,
-            ctx.content_width,
-            styles::diff_context_style(ctx.theme),
+    lines: &mut WindowedLines,
+) -> (usize, Option<CommentCursorInfo>) {
+    lines.push_with(|| {
+        let old_num = format_gutter_lineno(
+            diff_line.old_lineno.or(diff_line.new_lineno),
+            ctx.lineno_width,
         );
-        spans.extend(content_spans);
-    } else {
-        let content = truncate_or_pad(&diff_line.content, ctx.content_width);
-        spans.push(Span::styled(content, styles::diff_context_style(ctx.theme)));
-    }
+        let new_num = format_gutter_lineno(
+            diff_line.new_lineno.or(diff_line.old_lineno),
+            ctx.lineno_width,
+        );
+        let scrolled = scrolled_line(diff_line, ctx.scroll_x);
 
-    // Separator
-    spans.push(Span::styled(" │ ", styles::dim_style(ctx.theme)));
-    spans.push(Span::styled(
-        format!("{line_num} "),
-        styles::dim_style(ctx.theme),
-    ));
-    spans.push(Span::styled(
-        " ".to_string(),
-        styles::diff_context_style(ctx.theme),
-    ));
+        let indicator = cursor_indicator(line_idx, ctx.current_line_idx);
 
-    // Right side content - use same highlighting
-    if let Some(ref highlighted) = diff_line.highlighted_spans {
-        let content_spans = truncate_or_pad_spans(
-            highlighted,
-            ctx.content_width,
+        let mut spans = vec![
+            Span::styled(indicator, styles::current_line_indicator_style(ctx.theme)),
+            Span::styled(format!("{old_num} "), styles::dim_style(ctx.theme)),
+            Span::styled(" ".to_string(), styles::diff_context_style(ctx.theme)),
+        ];
+
+        // Left side content - use syntax highlighting if available
+        if let Some(ref highlighted) = scrolled.highlighted_spans {
+            let content_spans = truncate_or_pad_spans(
+                highlighted,
+                ctx.content_width,
+                styles::diff_context_style(ctx.theme),
+            );
+            spans.extend(content_spans);
+        } else {
+            let content = truncate_or_pad(&scrolled.content, ctx.content_width);
+            spans.push(Span::styled(content, styles::diff_context_style(ctx.theme)));
+        }
+
+        // Separator
+        spans.push(Span::styled(" │ ", styles::dim_style(ctx.theme)));
+        spans.push(Span::styled(
+            format!("{new_num} "),
+            styles::dim_style(ctx.theme),
+        ));
+        spans.push(Span::styled(
+            " ".to_string(),
             styles::diff_context_style(ctx.theme),
-        );
-        spans.extend(content_spans);
-    } else {
-        let content = truncate_or_pad(&diff_line.content, ctx.content_width);
-        spans.push(Span::styled(content, styles::diff_context_style(ctx.theme)));
-    }
+        ));
+
+        // Right side content - use same highlighting
+        if let Some(ref highlighted) = scrolled.highlighted_spans {
+            let content_spans = truncate_or_pad_spans(
+                highlighted,
+                ctx.content_width,
+                styles::diff_context_style(ctx.theme),
+            );
+            spans.extend(content_spans);
+        } else {
+            let content = truncate_or_pad(&scrolled.content, ctx.content_width);
+            spans.push(Span::styled(content, styles::diff_context_style(ctx.theme)));
+        }
 
-    lines.push(Line::from(spans));
+        Line::from(spans)
+    });
     line_idx += 1;
 
     // Add comments if any
-    let mut cursor_info_out: Option<SideBySideCursorInfo> = None;
+    let mut cursor_info_out: Option<CommentCursorInfo> = None;
     if let Some(new_ln) = diff_line.new_lineno {
         let (new_line_idx, cursor_info) = add_comments_to_line(
             new_ln,
@@ -2471,68 +3145,71 @@ fn render_context_line_side_by_side(
     (line_idx, cursor_info_out)
 }
 
-/// Render paired deletions and additions side-by-side
+/// Render paired deletions and additions side-by-side, in the rows
+/// `pair_change_block` gives the annotations
 /// Returns (line_idx, skip_count, optional cursor info for inline comment input)
 fn render_deletion_addition_pair_side_by_side(
     hunk_lines: &[crate::model::DiffLine],
     start_idx: usize,
     line_comments: &std::collections::HashMap<u32, Vec<crate::model::Comment>>,
-    ctx: &SideBySideContext,
+    ctx: &HunkRenderContext,
     file_idx: usize,
     mut line_idx: usize,
-    lines: &mut Vec<Line>,
-) -> (usize, usize, Option<SideBySideCursorInfo>) {
-    // Find the range of consecutive deletions
-    let mut del_end = start_idx + 1;
-    while del_end < hunk_lines.len() && hunk_lines[del_end].origin == LineOrigin::Deletion {
-        del_end += 1;
-    }
-
-    // Find the range of consecutive additions following the deletions
-    let add_start = del_end;
-    let mut add_end = add_start;
-    while add_end < hunk_lines.len() && hunk_lines[add_end].origin == LineOrigin::Addition {
-        add_end += 1;
-    }
-
-    let del_count = del_end - start_idx;
-    let add_count = add_end - add_start;
-    let max_lines = del_count.max(add_count);
-    let mut cursor_info_out: Option<SideBySideCursorInfo> = None;
+    lines: &mut WindowedLines,
+) -> (usize, usize, Option<CommentCursorInfo>) {
+    let (pairs, block_end) = pair_change_block(hunk_lines, start_idx);
+    let mut cursor_info_out: Option<CommentCursorInfo> = None;
 
     // Render each pair of deletion/addition
-    for offset in 0..max_lines {
-        let indicator = cursor_indicator(line_idx, ctx.current_line_idx);
-
-        let mut spans = vec![Span::styled(
-            indicator,
-            styles::current_line_indicator_style(ctx.theme),
-        )];
-
-        // Left side (deletion)
-        if offset < del_count {
-            let del_line = &hunk_lines[start_idx + offset];
-            add_deletion_spans(ctx.theme, &mut spans, del_line, ctx.content_width);
-        } else {
-            add_empty_column_spans(&mut spans, ctx.content_width);
-        }
+    for (del_idx, add_idx) in pairs {
+        lines.push_with(|| {
+            let indicator = cursor_indicator(line_idx, ctx.current_line_idx);
 
-        spans.push(Span::styled(" │ ", styles::dim_style(ctx.theme)));
+            let mut spans = vec![Span::styled(
+                indicator,
+                styles::current_line_indicator_style(ctx.theme),
+            )];
+
+            // Left side (deletion)
+            if let Some(del_idx) = del_idx {
+                let del_line = &hunk_lines[del_idx];
+                add_deletion_spans(
+                    ctx.theme,
+                    &mut spans,
+                    &scrolled_line(del_line, ctx.scroll_x),
+                    ctx.content_width,
+                    ctx.lineno_width,
+                    ctx.app.detect_moves,
+                );
+            } else {
+                add_empty_column_spans(&mut spans, ctx.content_width, ctx.lineno_width);
+            }
 
-        // Right side (addition)
-        if offset < add_count {
-            let add_line = &hunk_lines[add_start + offset];
-            add_addition_spans(ctx.theme, &mut spans, add_line, ctx.content_width);
-        } else {
-            add_empty_column_spans(&mut spans, ctx.content_width);
-        }
+            spans.push(Span::styled(" │ ", styles::dim_style(ctx.theme)));
+
+            // Right side (addition)
+            if let Some(add_idx) = add_idx {
+                let add_line = &hunk_lines[add_idx];
+                add_addition_spans(
+                    ctx.theme,
+                    &mut spans,
+                    &scrolled_line(add_line, ctx.scroll_x),
+                    ctx.content_width,
+                    ctx.lineno_width,
+                    shows_conflict_markers(ctx, file_idx),
+                    ctx.app.detect_moves,
+                );
+            } else {
+                add_empty_column_spans(&mut spans, ctx.content_width, ctx.lineno_width);
+            }
 
-        lines.push(Line::from(spans));
+            Line::from(spans)
+        });
         line_idx += 1;
 
         // Add comments for deletion
-        if offset < del_count {
-            let del_line = &hunk_lines[start_idx + offset];
+        if let Some(del_idx) = del_idx {
+            let del_line = &hunk_lines[del_idx];
             if let Some(old_ln) = del_line.old_lineno {
                 let (new_line_idx, cursor_info) = add_comments_to_line(
                     old_ln,
@@ -2551,8 +3228,8 @@ fn render_deletion_addition_pair_side_by_side(
         }
 
         // Add comments for addition
-        if offset < add_count {
-            let add_line = &hunk_lines[add_start + offset];
+        if let Some(add_idx) = add_idx {
+            let add_line = &hunk_lines[add_idx];
             if let Some(new_ln) = add_line.new_lineno {
                 let (new_line_idx, cursor_info) = add_comments_to_line(
                     new_ln,
@@ -2571,7 +3248,7 @@ fn render_deletion_addition_pair_side_by_side(
         }
     }
 
-    (line_idx, add_end, cursor_info_out)
+    (line_idx, block_end, cursor_info_out)
 }
 
 /// Render a standalone addition (no matching deletion)
@@ -2579,26 +3256,36 @@ fn render_deletion_addition_pair_side_by_side(
 fn render_standalone_addition_side_by_side(
     diff_line: &crate::model::DiffLine,
     line_comments: &std::collections::HashMap<u32, Vec<crate::model::Comment>>,
-    ctx: &SideBySideContext,
+    ctx: &HunkRenderContext,
     file_idx: usize,
     mut line_idx: usize,
-    lines: &mut Vec<Line>,
-) -> (usize, Option<SideBySideCursorInfo>) {
-    let indicator = cursor_indicator(line_idx, ctx.current_line_idx);
+    lines: &mut WindowedLines,
+) -> (usize, Option<CommentCursorInfo>) {
+    lines.push_with(|| {
+        let indicator = cursor_indicator(line_idx, ctx.current_line_idx);
 
-    let mut spans = vec![Span::styled(
-        indicator,
-        styles::current_line_indicator_style(ctx.theme),
-    )];
-    add_empty_column_spans(&mut spans, ctx.content_width);
-    spans.push(Span::styled(" │ ", styles::dim_style(ctx.theme)));
-    add_addition_spans(ctx.theme, &mut spans, diff_line, ctx.content_width);
+        let mut spans = vec![Span::styled(
+            indicator,
+            styles::current_line_indicator_style(ctx.theme),
+        )];
+        add_empty_column_spans(&mut spans, ctx.content_width, ctx.lineno_width);
+        spans.push(Span::styled(" │ ", styles::dim_style(ctx.theme)));
+        add_addition_spans(
+            ctx.theme,
+            &mut spans,
+            &scrolled_line(diff_line, ctx.scroll_x),
+            ctx.content_width,
+            ctx.lineno_width,
+            shows_conflict_markers(ctx, file_idx),
+            ctx.app.detect_moves,
+        );
 
-    lines.push(Line::from(spans));
+        Line::from(spans)
+    });
     line_idx += 1;
 
     // Add comments if any
-    let mut cursor_info_out: Option<SideBySideCursorInfo> = None;
+    let mut cursor_info_out: Option<CommentCursorInfo> = None;
     if let Some(new_ln) = diff_line.new_lineno {
         let (new_line_idx, cursor_info) = add_comments_to_line(
             new_ln,
@@ -2622,11 +3309,10 @@ fn add_deletion_spans(
     spans: &mut Vec<Span>,
     diff_line: &crate::model::DiffLine,
     content_width: usize,
+    lineno_width: usize,
+    show_moves: bool,
 ) {
-    let line_num = diff_line
-        .old_lineno
-        .map(|n| format!("{n:>4}"))
-        .unwrap_or_else(|| "    ".to_string());
+    let line_num = format_gutter_lineno(diff_line.old_lineno, lineno_width);
 
     spans.push(Span::styled(
         format!("{line_num} "),
@@ -2634,29 +3320,49 @@ fn add_deletion_spans(
     ));
     spans.push(Span::styled("-".to_string(), styles::diff_del_style(theme)));
 
-    // Use syntax highlighting if available
-    if let Some(ref highlighted) = diff_line.highlighted_spans {
+    if show_moves && diff_line.move_id.is_some() {
+        let content = truncate_or_pad(&diff_line.content, content_width);
+        spans.push(Span::styled(
+            content,
+            whitespace_only_style(
+                styles::diff_moved_style(theme, LineOrigin::Deletion),
+                diff_line,
+            ),
+        ));
+    } else if let Some(ref highlighted) = diff_line.highlighted_spans {
+        // Use syntax highlighting if available
         let syntax_pad_style = Style::default().fg(theme.diff_del).bg(theme.syntax_del_bg);
         let content_spans = truncate_or_pad_spans(highlighted, content_width, syntax_pad_style);
-        spans.extend(content_spans);
+        spans.extend(dim_whitespace_only(content_spans, diff_line));
     } else {
         // Fall back to plain text
         let content = truncate_or_pad(&diff_line.content, content_width);
-        spans.push(Span::styled(content, styles::diff_del_style(theme)));
+        spans.push(Span::styled(
+            content,
+            whitespace_only_style(styles::diff_del_style(theme), diff_line),
+        ));
     }
 }
 
+/// Whether conflict marker lines of the file get the conflict marker color.
+fn shows_conflict_markers(ctx: &HunkRenderContext, file_idx: usize) -> bool {
+    ctx.app
+        .diff_files
+        .get(file_idx)
+        .is_some_and(|f| f.has_unresolved_conflicts())
+}
+
 /// Add addition line spans to the spans vector
 fn add_addition_spans(
     theme: &Theme,
     spans: &mut Vec<Span>,
     diff_line: &crate::model::DiffLine,
     content_width: usize,
+    lineno_width: usize,
+    show_conflicts: bool,
+    show_moves: bool,
 ) {
-    let line_num = diff_line
-        .new_lineno
-        .map(|n| format!("{n:>4}"))
-        .unwrap_or_else(|| "    ".to_string());
+    let line_num = format_gutter_lineno(diff_line.new_lineno, lineno_width);
 
     spans.push(Span::styled(
         format!("{line_num} "),
@@ -2664,23 +3370,100 @@ fn add_addition_spans(
     ));
     spans.push(Span::styled("+".to_string(), styles::diff_add_style(theme)));
 
-    // Use syntax highlighting if available
-    if let Some(ref highlighted) = diff_line.highlighted_spans {
+    if show_conflicts && diff_line.is_conflict_marker() {
+        let content = truncate_or_pad(&diff_line.content, content_width);
+        spans.push(Span::styled(
+            content,
+            styles::diff_add_style(theme).patch(styles::conflict_marker_style(theme)),
+        ));
+    } else if show_moves && diff_line.move_id.is_some() {
+        let content = truncate_or_pad(&diff_line.content, content_width);
+        spans.push(Span::styled(
+            content,
+            whitespace_only_style(
+                styles::diff_moved_style(theme, LineOrigin::Addition),
+                diff_line,
+            ),
+        ));
+    } else if let Some(ref highlighted) = diff_line.highlighted_spans {
         let syntax_pad_style = Style::default().fg(theme.diff_add).bg(theme.syntax_add_bg);
         let content_spans = truncate_or_pad_spans(highlighted, content_width, syntax_pad_style);
-        spans.extend(content_spans);
+        spans.extend(dim_whitespace_only(content_spans, diff_line));
     } else {
         // Fall back to plain text
         let content = truncate_or_pad(&diff_line.content, content_width);
-        spans.push(Span::styled(content, styles::diff_add_style(theme)));
+        spans.push(Span::styled(
+            content,
+            whitespace_only_style(styles::diff_add_style(theme), diff_line),
+        ));
+    }
+}
+
+/// Dim the content of lines whose change is whitespace only.
+fn whitespace_only_style(style: Style, diff_line: &crate::model::DiffLine) -> Style {
+    if diff_line.whitespace_only {
+        style.add_modifier(Modifier::DIM)
+    } else {
+        style
+    }
+}
+
+fn dim_whitespace_only<'a>(
+    spans: Vec<Span<'a>>,
+    diff_line: &crate::model::DiffLine,
+) -> impl Iterator<Item = Span<'a>> {
+    spans.into_iter().map(move |span| {
+        let style = whitespace_only_style(span.style, diff_line);
+        span.style(style)
+    })
+}
+
+/// Digits needed for the largest line number shown for the file, so the
+/// side-by-side gutters are only as wide as they have to be.
+fn lineno_gutter_width(app: &App, file_idx: usize) -> usize {
+    let Some(file) = app.diff_files.get(file_idx) else {
+        return 1;
+    };
+    let hunk_max = file
+        .hunks
+        .iter()
+        .map(|h| (h.old_start + h.old_count).max(h.new_start + h.new_count))
+        .max()
+        .unwrap_or(0);
+    let expanded_max = app
+        .expanded_top
+        .iter()
+        .chain(&app.expanded_bottom)
+        .filter(|(gap_id, _)| gap_id.file_idx == file_idx)
+        .flat_map(|(_, lines)| lines)
+        .filter_map(|line| line.old_lineno.max(line.new_lineno))
+        .max()
+        .unwrap_or(0);
+    hunk_max
+        .max(expanded_max)
+        .checked_ilog10()
+        .map_or(1, |digits| digits as usize + 1)
+}
+
+/// Width of each content column. Besides the two columns a row holds the
+/// indicator(1), gutter + space, prefix(1), " │ "(3), gutter + space and prefix(1).
+fn side_by_side_content_width(inner_width: u16, lineno_width: usize) -> usize {
+    usize::from(inner_width).saturating_sub(2 * lineno_width + 8) / 2
+}
+
+/// Right-aligned line number for a gutter `width` digits wide, blank if `None`.
+fn format_gutter_lineno(lineno: Option<u32>, width: usize) -> String {
+    match lineno {
+        Some(n) => format!("{n:>width$}"),
+        None => " ".repeat(width),
     }
 }
 
 /// Add empty column spans (for when one side has no content)
-fn add_empty_column_spans(spans: &mut Vec<Span>, content_width: usize) {
-    // line_num(4) + space(1) + prefix(1) + content
+fn add_empty_column_spans(spans: &mut Vec<Span>, content_width: usize, lineno_width: usize) {
+    // line_num + space(1) + prefix(1) + content
     spans.push(Span::styled(
-        " ".repeat(5 + 1 + content_width),
+        " ".repeat(lineno_width + 2 + content_width),
         Style::default(),
     ));
 }
@@ -2691,16 +3474,16 @@ fn add_comments_to_line(
     line_num: u32,
     line_comments: &std::collections::HashMap<u32, Vec<crate::model::Comment>>,
     side: LineSide,
-    ctx: &SideBySideContext,
+    ctx: &HunkRenderContext,
     file_idx: usize,
     mut line_idx: usize,
-    lines: &mut Vec<Line>,
-) -> (usize, Option<SideBySideCursorInfo>) {
+    lines: &mut WindowedLines,
+) -> (usize, Option<CommentCursorInfo>) {
     // Check if we're adding/editing a comment on this line and side
     let is_line_comment_mode = ctx.comment_input_mode
         && file_idx == ctx.current_file_idx
         && ctx.comment_line == Some((line_num, side));
-    let mut cursor_info_out: Option<SideBySideCursorInfo> = None;
+    let mut cursor_info_out: Option<CommentCursorInfo> = None;
 
     if let Some(comments) = line_comments.get(&line_num) {
         for comment in comments {
@@ -2725,6 +3508,7 @@ fn add_comments_to_line(
                         line_range,
                         true,
                         ctx.supports_keyboard_enhancement,
+                        comment_input_width(ctx.app),
                     );
                     let box_end = line_idx + input_lines.len().saturating_sub(1);
                     let annotations_replaced = 2 + comment.content.split('\n').count();
@@ -2754,7 +3538,7 @@ fn add_comments_to_line(
                         .or_else(|| Some(LineRange::single(line_num)));
                     let comment_lines = comment_panel::format_comment_lines(
                         ctx.theme,
-                        comment_type_presentation(ctx.app, &comment.comment_type),
+                        comment_presentation(ctx.app, comment),
                         &comment.content,
                         line_range,
                     );
@@ -2788,6 +3572,7 @@ fn add_comments_to_line(
             line_range,
             false,
             ctx.supports_keyboard_enhancement,
+            comment_input_width(ctx.app),
         );
         let box_end = line_idx + input_lines.len().saturating_sub(1);
         cursor_info_out = Some((
@@ -2992,12 +3777,7 @@ fn paint_unified_diff_row_backgrounds(
         }
 
         let rows_for_line = if wrap_lines && viewport_width > 0 {
-            let width = line_widths.get(idx).copied().unwrap_or(0);
-            if width == 0 {
-                1
-            } else {
-                width.div_ceil(viewport_width)
-            }
+            wrapped_row_count(line_widths.get(idx).copied().unwrap_or(0), viewport_width)
         } else {
             1
         };
@@ -3029,6 +3809,33 @@ fn paint_unified_diff_row_backgrounds(
     }
 }
 
+/// `diff_line` with its first `scroll_x` characters cut off, as shown in a
+/// horizontally scrolled side-by-side column.
+fn scrolled_line(
+    diff_line: &crate::model::DiffLine,
+    scroll_x: usize,
+) -> Cow<'_, crate::model::DiffLine> {
+    if scroll_x == 0 {
+        return Cow::Borrowed(diff_line);
+    }
+    let mut line = diff_line.clone();
+    line.content = line.content.chars().skip(scroll_x).collect();
+    if let Some(spans) = &mut line.highlighted_spans {
+        let mut to_skip = scroll_x;
+        spans.retain_mut(|(_, text)| {
+            let chars = text.chars().count();
+            if to_skip >= chars {
+                to_skip -= chars;
+                return false;
+            }
+            *text = text.chars().skip(to_skip).collect();
+            to_skip = 0;
+            true
+        });
+    }
+    Cow::Owned(line)
+}
+
 /// Apply horizontal scroll to a line while preserving the first span (cursor indicator)
 fn apply_horizontal_scroll(line: Line, scroll_x: usize) -> Line {
     if scroll_x == 0 || line.spans.is_empty() {
@@ -3206,4 +4013,525 @@ mod tests {
             "padded spans should have exactly {width} chars, got {total_chars}"
         );
     }
+
+    fn app_with_many_files(file_count: usize, lines_per_file: u32) -> App {
+        use crate::app::test_support::{build_app_with_files, make_file_with_hunks, make_hunk};
+
+        let files = (0..file_count)
+            .map(|i| {
+                make_file_with_hunks(&format!("file{i}.rs"), vec![make_hunk(1, lines_per_file)])
+            })
+            .collect();
+        build_app_with_files(files, lines_per_file)
+    }
+
+    fn draw(
+        terminal: &mut ratatui::Terminal<ratatui::backend::TestBackend>,
+        app: &mut App,
+    ) -> Vec<String> {
+        terminal.draw(|frame| render(frame, app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_render_files_far_down_a_long_diff_at_their_offset() {
+        for mode in [DiffViewMode::Unified, DiffViewMode::SideBySide] {
+            let mut app = app_with_many_files(30, 20);
+            app.diff_view_mode = mode;
+            app.rebuild_annotations();
+            let mut terminal =
+                ratatui::Terminal::new(ratatui::backend::TestBackend::new(160, 30)).unwrap();
+            draw(&mut terminal, &mut app);
+
+            app.diff_state.scroll_offset = app.calculate_file_scroll_offset(12);
+            let rows = draw(&mut terminal, &mut app);
+
+            assert!(rows.iter().any(|row| row.contains("═══ file12.rs")));
+            assert!(rows.iter().any(|row| row.contains("hunk line 1 ")));
+            assert!(!rows.iter().any(|row| row.contains("═══ file11.rs")));
+        }
+    }
+
+    #[test]
+    fn should_render_lines_deep_in_a_long_file_at_their_offset() {
+        for mode in [DiffViewMode::Unified, DiffViewMode::SideBySide] {
+            let mut app = app_with_many_files(1, 2_000);
+            app.diff_view_mode = mode;
+            app.rebuild_annotations();
+            let mut terminal =
+                ratatui::Terminal::new(ratatui::backend::TestBackend::new(160, 30)).unwrap();
+            draw(&mut terminal, &mut app);
+
+            app.diff_state.scroll_offset = app
+                .line_annotations
+                .iter()
+                .position(|line| match line {
+                    AnnotatedLine::DiffLine { line_idx, .. } => *line_idx == 1_499,
+                    AnnotatedLine::SideBySideLine { add_line_idx, .. } => {
+                        *add_line_idx == Some(1_499)
+                    }
+                    _ => false,
+                })
+                .unwrap();
+            let rows = draw(&mut terminal, &mut app);
+
+            let first = rows
+                .iter()
+                .position(|row| row.contains("hunk line "))
+                .unwrap();
+            assert!(rows[first].contains("hunk line 1500 "), "{mode:?}");
+            assert!(rows[first + 1].contains("hunk line 1501 "), "{mode:?}");
+        }
+    }
+
+    #[test]
+    fn should_size_the_file_list_by_its_width_percent() {
+        let mut app = app_with_many_files(3, 2);
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(160, 30)).unwrap();
+
+        draw(&mut terminal, &mut app);
+        assert_eq!(app.file_list_area.map(|area| area.width), Some(32));
+
+        app.file_list_width_percent = 35;
+        draw(&mut terminal, &mut app);
+        assert_eq!(app.file_list_area.map(|area| area.width), Some(56));
+        assert_eq!(app.diff_area.map(|area| area.width), Some(104));
+    }
+
+    #[test]
+    fn should_show_the_blame_column_next_to_the_current_file_only() {
+        let mut app = app_with_many_files(2, 3);
+        app.show_blame_column = true;
+        let blamed = crate::vcs::BlameLine {
+            commit_id: "c0ffee1234".to_string(),
+            author: "Ada King Lovelace".to_string(),
+        };
+        app.blame_cache.insert(
+            std::path::PathBuf::from("file0.rs"),
+            Some(vec![blamed.clone(), blamed]),
+        );
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(160, 30)).unwrap();
+
+        let rows = draw(&mut terminal, &mut app);
+        let rows_with =
+            |text: &str| -> Vec<&String> { rows.iter().filter(|row| row.contains(text)).collect() };
+
+        let first_lines = rows_with("hunk line 1");
+        assert_eq!(first_lines.len(), 2);
+        assert!(first_lines[0].contains("   1 c0ffee1 AL   hunk line 1"));
+        assert!(first_lines[1].contains("   1   hunk line 1"));
+        // Lines past the end of the blame get a blank cell
+        assert!(
+            rows_with("hunk line 3")[0].contains(&format!("   3{}hunk line 3", " ".repeat(14)))
+        );
+        assert_eq!(author_initials("ada"), "A");
+        assert_eq!(author_initials(""), "");
+    }
+
+    #[test]
+    fn should_scroll_file_list_without_moving_the_current_file() {
+        let mut app = app_with_many_files(30, 4);
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(160, 20)).unwrap();
+        let file_list = |rows: &[String]| -> Vec<String> {
+            rows.iter()
+                .map(|row| row.chars().take(30).collect())
+                .collect()
+        };
+        let rows = draw(&mut terminal, &mut app);
+        assert!(file_list(&rows)[2].contains("file0.rs"));
+
+        app.scroll_file_list_down(10);
+        let rows = draw(&mut terminal, &mut app);
+        assert!(!file_list(&rows).iter().any(|row| row.contains("file0.rs")));
+        assert_eq!(app.file_list_state.list_state.offset(), 10);
+        assert_eq!(app.diff_state.current_file_idx, 0);
+        assert_eq!(app.file_list_state.selected(), 0);
+
+        // Moving to another file brings its entry back into view
+        app.jump_to_file(1);
+        let rows = draw(&mut terminal, &mut app);
+        assert!(file_list(&rows).iter().any(|row| row.contains("file1.rs")));
+        assert_eq!(app.diff_state.current_file_idx, 1);
+    }
+
+    #[test]
+    fn should_render_per_file_view_modes_at_their_offsets() {
+        let mut app = app_with_many_files(3, 4);
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(160, 30)).unwrap();
+        draw(&mut terminal, &mut app);
+        app.jump_to_file(1);
+        app.toggle_file_view_mode();
+        assert_eq!(app.file_view_mode(1), DiffViewMode::SideBySide);
+        assert_eq!(app.file_view_mode(0), DiffViewMode::Unified);
+
+        app.diff_state.scroll_offset = app.calculate_file_scroll_offset(1);
+        let rows = draw(&mut terminal, &mut app);
+        // Rows below the status bar and the panel border
+        assert!(rows[1].contains("Diff (Side-by-Side)"));
+        assert!(rows[2].contains("═══ file1.rs"));
+        assert!(rows[3 + 4].contains("│ 4  hunk line 4"));
+        let next = app.calculate_file_scroll_offset(2) - app.diff_state.scroll_offset;
+        assert!(rows[2 + next].contains("═══ file2.rs"));
+    }
+
+    #[test]
+    fn should_mark_wrapped_continuation_rows() {
+        let marker = Span::raw("↪");
+        let line = Line::from(vec![Span::raw(" "), Span::raw("abcdefghij")]);
+
+        let rows: Vec<String> = wrap_line(line, 5, &marker)
+            .iter()
+            .map(|row| row.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect();
+
+        assert_eq!(rows, [" abcd", "↪efgh", "↪ij"]);
+        assert_eq!(wrapped_row_count(11, 5), 3);
+        assert_eq!(wrapped_row_count(5, 5), 1);
+    }
+
+    #[test]
+    fn should_size_side_by_side_gutter_to_largest_line_number() {
+        use crate::app::test_support::{make_file_with_hunks, make_hunk};
+
+        let mut app = app_with_many_files(1, 3);
+        app.diff_files
+            .push(make_file_with_hunks("big.rs", vec![make_hunk(995, 10)]));
+
+        assert_eq!(lineno_gutter_width(&app, 0), 1);
+        assert_eq!(lineno_gutter_width(&app, 1), 4);
+        assert_eq!(side_by_side_content_width(56, 4), 20);
+        assert_eq!(format_gutter_lineno(Some(7), 3), "  7");
+        assert_eq!(format_gutter_lineno(None, 3), "   ");
+    }
+
+    #[test]
+    fn should_align_side_by_side_columns_with_the_gutter() {
+        use crate::app::test_support::{build_app_with_files, make_file_with_hunks, make_hunk};
+
+        let mut app = build_app_with_files(
+            vec![make_file_with_hunks("a.rs", vec![make_hunk(8, 5)])],
+            20,
+        );
+        app.diff_view_mode = DiffViewMode::SideBySide;
+        app.rebuild_annotations();
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(160, 30)).unwrap();
+        let rows = draw(&mut terminal, &mut app);
+
+        let hunk_rows: Vec<&String> = rows
+            .iter()
+            .filter(|row| row.contains("hunk line"))
+            .collect();
+        assert_eq!(hunk_rows.len(), 5);
+        assert!(hunk_rows[1].contains(" 9  hunk line 9"));
+        let separator_at = |row: &str| row.find(" │  9").or_else(|| row.find(" │ 1"));
+        let separator = separator_at(hunk_rows[1]).unwrap();
+        assert_eq!(separator_at(hunk_rows[4]), Some(separator));
+    }
+
+    #[test]
+    fn should_scroll_side_by_side_content_behind_fixed_gutters() {
+        use crate::app::test_support::{build_app_with_files, make_file_with_hunks, make_hunk};
+
+        let mut hunk = make_hunk(1, 3);
+        hunk.lines[1].content = format!("start {} end", "-".repeat(200));
+        let mut app = build_app_with_files(vec![make_file_with_hunks("a.rs", vec![hunk])], 3);
+        app.diff_view_mode = DiffViewMode::SideBySide;
+        app.diff_state.wrap_lines = false;
+        app.rebuild_annotations();
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(160, 30)).unwrap();
+        let long_row = |rows: Vec<String>| {
+            rows.into_iter()
+                .find(|row| row.contains("2 ") && row.contains("---"))
+                .unwrap()
+        };
+
+        let before = long_row(draw(&mut terminal, &mut app));
+        assert_eq!(before.matches("start").count(), 2);
+        assert!(!before.contains("end"));
+
+        app.scroll_right(1000);
+        let after = long_row(draw(&mut terminal, &mut app));
+        assert_eq!(
+            app.diff_state.scroll_x,
+            app.diff_state.max_content_width - app.diff_state.viewport_width
+        );
+        assert_eq!(after.matches("--- end").count(), 2);
+        assert!(!after.contains("start"));
+        // Gutters and the separator don't move
+        assert_eq!(before.find(" │ "), after.find(" │ "));
+        assert_eq!(before.find(" 2 "), after.find(" 2 "));
+    }
+
+    #[test]
+    fn should_render_changes_only_rows_in_step_with_annotations() {
+        use crate::app::test_support::{build_app_with_files, make_file_with_hunks, make_hunk};
+
+        for mode in [DiffViewMode::Unified, DiffViewMode::SideBySide] {
+            let mut hunk = make_hunk(1, 5);
+            hunk.lines[2].origin = LineOrigin::Addition;
+            hunk.lines[2].old_lineno = None;
+            let mut app = build_app_with_files(vec![make_file_with_hunks("a.rs", vec![hunk])], 5);
+            app.diff_view_mode = mode;
+            app.set_changes_only(true);
+            app.diff_state.cursor_line = app
+                .line_annotations
+                .iter()
+                .rposition(|a| matches!(a, AnnotatedLine::HiddenContext { .. }))
+                .unwrap();
+            let mut terminal =
+                ratatui::Terminal::new(ratatui::backend::TestBackend::new(160, 30)).unwrap();
+
+            let rows = draw(&mut terminal, &mut app);
+
+            let hidden: Vec<&String> = rows
+                .iter()
+                .filter(|row| row.contains("┄┄┄ 2 unchanged lines ┄┄┄"))
+                .collect();
+            assert_eq!(hidden.len(), 2, "{mode:?}");
+            assert!(hidden[1].contains('▶'), "{mode:?}");
+            assert_eq!(
+                rows.iter().filter(|row| row.contains("hunk line")).count(),
+                1,
+                "{mode:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn should_wrap_comment_input_and_keep_cursor_on_its_row() {
+        use crate::app::test_support::{build_app_with_files, make_file_with_hunks, make_hunk};
+
+        let mut app =
+            build_app_with_files(vec![make_file_with_hunks("a.rs", vec![make_hunk(1, 3)])], 3);
+        app.enter_comment_mode(false, Some((2, crate::model::LineSide::New)));
+        app.comment_buffer = "word ".repeat(40) + "tail";
+        app.comment_cursor = app.comment_buffer.len();
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(100, 30)).unwrap();
+
+        let rows = draw(&mut terminal, &mut app);
+
+        let box_rows: Vec<&String> = rows.iter().filter(|row| row.contains("│ word")).collect();
+        assert!(box_rows.len() > 1);
+        let (_, cursor_row) = app.comment_cursor_screen_pos.unwrap();
+        assert!(rows[cursor_row as usize].contains("│ word tail"));
+    }
+
+    #[test]
+    fn should_render_reviewed_hunks_as_their_header() {
+        use crate::app::test_support::{build_app_with_files, make_file_with_hunks, make_hunk};
+
+        for mode in [DiffViewMode::Unified, DiffViewMode::SideBySide] {
+            let hunks = vec![make_hunk(1, 3), make_hunk(20, 3)];
+            let mut app = build_app_with_files(vec![make_file_with_hunks("a.rs", hunks)], 30);
+            app.diff_view_mode = mode;
+            app.rebuild_annotations();
+            let headers: Vec<usize> = app
+                .line_annotations
+                .iter()
+                .enumerate()
+                .filter(|(_, a)| matches!(a, AnnotatedLine::HunkHeader { .. }))
+                .map(|(idx, _)| idx)
+                .collect();
+            app.diff_state.cursor_line = headers[0];
+            app.toggle_hunk_reviewed_at_cursor();
+            app.next_hunk();
+            app.diff_state.scroll_offset = 0;
+            let mut terminal =
+                ratatui::Terminal::new(ratatui::backend::TestBackend::new(160, 30)).unwrap();
+
+            let rows = draw(&mut terminal, &mut app);
+
+            assert!(
+                rows.iter().any(|row| row.contains("✓ @@ -1,3 +1,3 @@")),
+                "{mode:?}"
+            );
+            assert!(
+                !rows.iter().any(|row| row.contains("hunk line 1 ")),
+                "{mode:?}"
+            );
+            assert_eq!(
+                rows.iter()
+                    .filter(|row| row.contains("hunk line 2"))
+                    .count(),
+                3,
+                "{mode:?}"
+            );
+            let cursor_row = rows.iter().find(|row| row.contains('▶')).unwrap();
+            assert!(cursor_row.contains("@@ -20,3 +20,3 @@"), "{mode:?}");
+        }
+    }
+
+    #[test]
+    fn should_render_rewritten_lines_opposite_their_closest_replacement() {
+        use crate::app::test_support::{build_app_with_files, make_file_with_hunks, make_hunk};
+
+        let mut hunk = make_hunk(1, 3);
+        let contents = [
+            (LineOrigin::Deletion, "remove_me();", Some(1), None),
+            (
+                LineOrigin::Deletion,
+                "let value = compute(1);",
+                Some(2),
+                None,
+            ),
+            (LineOrigin::Addition, "setup();", None, Some(1)),
+            (
+                LineOrigin::Addition,
+                "let value = compute(2);",
+                None,
+                Some(2),
+            ),
+        ];
+        hunk.lines = contents
+            .into_iter()
+            .map(
+                |(origin, content, old_lineno, new_lineno)| crate::model::DiffLine {
+                    origin,
+                    content: content.to_string(),
+                    old_lineno,
+                    new_lineno,
+                    highlighted_spans: None,
+                    whitespace_only: false,
+                    move_id: None,
+                },
+            )
+            .collect();
+        let mut app = build_app_with_files(vec![make_file_with_hunks("a.rs", vec![hunk])], 3);
+        app.diff_view_mode = DiffViewMode::SideBySide;
+        app.rebuild_annotations();
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(160, 30)).unwrap();
+
+        let rows = draw(&mut terminal, &mut app);
+
+        let row_of = |text: &str| rows.iter().position(|row| row.contains(text)).unwrap();
+        assert_eq!(row_of("compute(1)"), row_of("compute(2)"));
+        assert_ne!(row_of("remove_me"), row_of("setup"));
+        let paired = app
+            .line_annotations
+            .iter()
+            .filter(|a| {
+                matches!(
+                    a,
+                    AnnotatedLine::SideBySideLine {
+                        del_line_idx: Some(_),
+                        add_line_idx: Some(_),
+                        ..
+                    }
+                )
+            })
+            .count();
+        assert_eq!(paired, 1);
+    }
+
+    #[test]
+    fn should_render_commit_message_unified_with_summary_and_trailers_styled() {
+        use crate::app::test_support::{build_app_with_files, make_file_with_hunks, make_hunk};
+        use ratatui::style::Modifier;
+
+        let message = [
+            "Fix the parser",
+            "",
+            &"long body text ".repeat(12),
+            "",
+            "Signed-off-by: A U Thor <a@example.com>",
+        ];
+        let mut hunk = make_hunk(1, message.len() as u32);
+        for (line, text) in hunk.lines.iter_mut().zip(message) {
+            line.content = text.to_string();
+        }
+        let mut file = make_file_with_hunks("Commit Message", vec![hunk]);
+        file.is_commit_message = true;
+        let mut app = build_app_with_files(vec![file], message.len() as u32);
+        app.diff_view_mode = DiffViewMode::SideBySide;
+        app.set_changes_only(true);
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(100, 30)).unwrap();
+
+        let rows = draw(&mut terminal, &mut app);
+
+        let find = |text: &str| {
+            let y = rows.iter().position(|row| row.contains(text)).unwrap();
+            let x = rows[y][..rows[y].find(text).unwrap()].chars().count();
+            terminal.backend().buffer()[(x as u16, y as u16)].style()
+        };
+        assert!(find("Fix the parser").add_modifier.contains(Modifier::BOLD));
+        assert_eq!(find("Signed-off-by").fg, Some(app.theme.fg_dim));
+        assert!(!find("long body").add_modifier.contains(Modifier::BOLD));
+        assert!(rows.iter().filter(|row| row.contains("long body")).count() > 1);
+        assert!(!rows.iter().any(|row| row.contains("unchanged lines")));
+    }
+
+    #[test]
+    fn should_detect_commit_trailers_in_the_last_paragraph_only() {
+        let lines = |text: &[&str]| -> Vec<crate::model::DiffLine> {
+            let mut hunk = crate::app::test_support::make_hunk(1, text.len() as u32);
+            for (line, text) in hunk.lines.iter_mut().zip(text) {
+                line.content = text.to_string();
+            }
+            hunk.lines
+        };
+
+        assert_eq!(
+            commit_trailers_start(&lines(&[
+                "Summary",
+                "",
+                "Body",
+                "",
+                "Signed-off-by: A <a@b>",
+                "Co-authored-by: B <b@c>",
+            ])),
+            Some(4)
+        );
+        assert_eq!(
+            commit_trailers_start(&lines(&["Summary", "", "Reviewed-by: C <c@d>"])),
+            Some(2)
+        );
+        assert_eq!(
+            commit_trailers_start(&lines(&["Summary", "", "Fixes: #1", "and more words"])),
+            None
+        );
+        assert_eq!(commit_trailers_start(&lines(&["Fixes: #1"])), None);
+    }
+
+    #[test]
+    #[ignore = "benchmark; run with --ignored --release"]
+    fn should_draw_frames_of_a_50k_line_diff_within_budget() {
+        // Many files, then a single file as long as all of them
+        for mut app in [
+            app_with_many_files(500, 100),
+            app_with_many_files(1, 50_000),
+        ] {
+            let mut terminal =
+                ratatui::Terminal::new(ratatui::backend::TestBackend::new(200, 60)).unwrap();
+            draw(&mut terminal, &mut app);
+            assert!(app.total_lines() > 50_000);
+
+            let frames = 20;
+            let start = std::time::Instant::now();
+            for frame in 0..frames {
+                app.diff_state.scroll_offset = frame * 2_500;
+                draw(&mut terminal, &mut app);
+            }
+            let per_frame = start.elapsed() / frames as u32;
+            assert!(
+                per_frame < std::time::Duration::from_millis(20),
+                "frame took {per_frame:?}"
+            );
+        }
+    }
 }
@@ -0,0 +1,149 @@
+use chrono::Utc;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::app::{App, BlamePopup};
+use crate::theme::Theme;
+use crate::ui::{comment_panel::format_age, styles};
+
+/// Width of the one-line blame summary, including borders.
+const SUMMARY_WIDTH: u16 = 80;
+
+pub fn render_blame_popup(frame: &mut Frame, app: &mut App) {
+    let theme = &app.theme;
+    let Some(popup) = &mut app.blame_popup else {
+        return;
+    };
+    if popup.expanded {
+        render_commit(frame, theme, popup);
+    } else {
+        render_summary(frame, theme, popup);
+    }
+}
+
+/// Short id, author, age and summary of the blamed commit, e.g.
+/// `a1b2c3d  Jane Doe  3d ago  Fix the parser`.
+fn render_summary(frame: &mut Frame, theme: &Theme, popup: &BlamePopup) {
+    let area = frame.area();
+    let width = SUMMARY_WIDTH.min(area.width.saturating_sub(4));
+    let [area] = Layout::horizontal([Constraint::Length(width)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::vertical([Constraint::Length(3)])
+        .flex(Flex::Center)
+        .areas(area);
+
+    let line = match &popup.commit {
+        Some(commit) => Line::from(vec![
+            Span::styled(commit.short_id.clone(), styles::hash_style(theme)),
+            Span::raw("  "),
+            Span::styled(
+                commit.author.clone(),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("  "),
+            Span::styled(
+                format_age(commit.time, Utc::now()),
+                styles::dim_style(theme),
+            ),
+            Span::raw("  "),
+            Span::raw(commit.summary.clone()),
+        ]),
+        None => Line::from(Span::styled(
+            "Not committed yet",
+            styles::pending_style(theme),
+        )),
+    };
+
+    let title = match popup.commit {
+        Some(_) => format!(
+            " Blame {}:{} - gb:full commit ",
+            popup.path.display(),
+            popup.line
+        ),
+        None => format!(" Blame {}:{} ", popup.path.display(), popup.line),
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .style(styles::popup_style(theme))
+        .border_style(styles::border_style(theme, true));
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(line)
+            .block(block)
+            .style(styles::popup_style(theme)),
+        area,
+    );
+}
+
+/// The full commit: id, author, date and message, scrollable like help.
+fn render_commit(frame: &mut Frame, theme: &Theme, popup: &mut BlamePopup) {
+    let Some(commit) = &popup.commit else {
+        return;
+    };
+    let area = centered_rect(70, 70, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Commit (j/k to scroll) - Press q or Esc to close ")
+        .borders(Borders::ALL)
+        .style(styles::popup_style(theme))
+        .border_style(styles::border_style(theme, true));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("commit ", styles::dim_style(theme)),
+            Span::styled(commit.id.clone(), styles::hash_style(theme)),
+        ]),
+        Line::from(vec![
+            Span::styled("Author: ", styles::dim_style(theme)),
+            Span::raw(commit.author.clone()),
+        ]),
+        Line::from(vec![
+            Span::styled("Date:   ", styles::dim_style(theme)),
+            Span::raw(format!(
+                "{} ({})",
+                commit.time.format("%Y-%m-%d %H:%M"),
+                format_age(commit.time, Utc::now())
+            )),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled(
+            commit.summary.clone(),
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+    ];
+    if let Some(body) = commit.body.as_deref().filter(|b| !b.trim().is_empty()) {
+        lines.push(Line::from(""));
+        lines.extend(body.lines().map(|line| Line::from(line.to_string())));
+    }
+
+    popup.total_lines = lines.len();
+    popup.viewport_height = inner.height as usize;
+    popup.scroll_offset = popup
+        .scroll_offset
+        .min(popup.total_lines.saturating_sub(popup.viewport_height));
+
+    let visible: Vec<Line> = lines.into_iter().skip(popup.scroll_offset).collect();
+    frame.render_widget(
+        Paragraph::new(visible).style(styles::popup_style(theme)),
+        inner,
+    );
+}
+
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::vertical([Constraint::Percentage(percent_y)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Percentage(percent_x)]).flex(Flex::Center);
+    let [area] = vertical.areas(area);
+    let [area] = horizontal.areas(area);
+    area
+}
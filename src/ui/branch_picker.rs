@@ -0,0 +1,67 @@
+//! The `:branches` picker: local branches to start a commit-range review from.
+
+use ratatui::{
+    Frame,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState},
+};
+
+use crate::app::App;
+use crate::ui::session_browser::centered_rect;
+use crate::ui::styles;
+
+pub fn render_branch_picker(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = centered_rect(70, 60, frame.area());
+
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Branches - Enter:review  Esc:close ")
+        .borders(Borders::ALL)
+        .style(styles::popup_style(theme))
+        .border_style(styles::border_style(theme, true));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let name_width = app
+        .branch_picker
+        .branches
+        .iter()
+        .map(|branch| branch.name.chars().count())
+        .max()
+        .unwrap_or(0);
+    let items: Vec<ListItem> = app
+        .branch_picker
+        .branches
+        .iter()
+        .map(|branch| {
+            let (marker, name_style) = if branch.is_current {
+                (
+                    "* ",
+                    styles::branch_style(theme).add_modifier(Modifier::BOLD),
+                )
+            } else {
+                ("  ", styles::branch_style(theme))
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(marker, Style::default().add_modifier(Modifier::BOLD)),
+                Span::styled(format!("{:<name_width$}", branch.name), name_style),
+                Span::raw("  "),
+                Span::styled(branch.short_id.clone(), styles::hash_style(theme)),
+                Span::raw("  "),
+                Span::raw(branch.summary.clone()),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .highlight_style(styles::selected_style(theme))
+        .highlight_symbol("> ");
+
+    let mut state = ListState::default();
+    state.select(Some(app.branch_picker.selected));
+    frame.render_stateful_widget(list, inner, &mut state);
+}
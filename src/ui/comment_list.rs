@@ -0,0 +1,85 @@
+//! The `:comments` list: every file and line comment, like a quickfix list.
+
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+};
+
+use crate::app::{App, CommentListEntry, FocusedPanel};
+use crate::model::LineSide;
+use crate::ui::styles;
+
+pub fn render_comment_list(frame: &mut Frame, app: &mut App, area: Rect) {
+    let focused = app.focused_panel == FocusedPanel::CommentList;
+    let entries = app.comment_list_entries();
+    let theme = &app.theme;
+
+    let block = Block::default()
+        .title(format!(
+            " Comments ({}) - Enter:jump  d:delete  Esc:close ",
+            entries.len()
+        ))
+        .borders(Borders::ALL)
+        .border_style(styles::border_style(theme, focused));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    if entries.is_empty() {
+        app.comment_list_state.select(Some(0));
+        frame.render_widget(
+            Paragraph::new(Span::styled("  No comments yet", styles::dim_style(theme))),
+            inner,
+        );
+        return;
+    }
+
+    // Comments may have been deleted since the selection was made
+    let selected = app.comment_list_state.selected().unwrap_or(0);
+    app.comment_list_state
+        .select(Some(selected.min(entries.len() - 1)));
+
+    let items: Vec<ListItem> = entries
+        .iter()
+        .map(|entry| ListItem::new(entry_line(app, entry)))
+        .collect();
+    let list = List::new(items)
+        .highlight_style(styles::selected_style(&app.theme))
+        .highlight_symbol("> ");
+    frame.render_stateful_widget(list, inner, &mut app.comment_list_state);
+}
+
+/// `[ISSUE] ✓ src/app.rs:42  First line of the comment`
+fn entry_line(app: &App, entry: &CommentListEntry) -> Line<'static> {
+    let theme = &app.theme;
+    let comment = &entry.comment;
+    let location = match entry.line {
+        Some((line, LineSide::Old)) => format!("{}:{line} (old)", entry.path.display()),
+        Some((line, LineSide::New)) => format!("{}:{line}", entry.path.display()),
+        None => entry.path.display().to_string(),
+    };
+    let resolved = if comment.resolved {
+        Span::styled("\u{2713} ", styles::reviewed_style(theme))
+    } else {
+        Span::raw("  ")
+    };
+    Line::from(vec![
+        Span::styled(
+            format!("[{}] ", app.comment_type_label(&comment.comment_type)),
+            Style::default().fg(app.comment_type_color(&comment.comment_type)),
+        ),
+        resolved,
+        Span::styled(location, styles::dim_style(theme)),
+        Span::raw("  "),
+        Span::raw(
+            comment
+                .content
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+        ),
+    ])
+}
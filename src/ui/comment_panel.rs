@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use ratatui::{
     Frame,
     layout::{Constraint, Flex, Layout, Rect},
@@ -5,7 +6,7 @@ use ratatui::{
     text::{Line, Span},
     widgets::{Block, Borders, Clear, Paragraph},
 };
-use unicode_width::UnicodeWidthStr;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 use crate::app::App;
 use crate::model::LineRange;
@@ -28,13 +29,41 @@ pub struct CommentCursorInfo {
 pub struct CommentTypePresentation {
     pub label: String,
     pub color: Color,
+    /// Replies are drawn indented under the comment they answer
+    pub reply: bool,
+    /// Relative creation/edit time shown dimmed in the header, e.g. `3h ago`
+    pub age: Option<String>,
+    /// Who wrote the comment, shown before its age
+    pub author: Option<String>,
+}
+
+/// Coarse relative time from `then` to `now`, e.g. `just now`, `5m ago`,
+/// `3h ago`, `2d ago`; older than a month shows the date.
+pub fn format_age(then: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let secs = (now - then).num_seconds().max(0);
+    match secs {
+        0..60 => "just now".to_string(),
+        60..3_600 => format!("{}m ago", secs / 60),
+        3_600..86_400 => format!("{}h ago", secs / 3_600),
+        86_400..2_592_000 => format!("{}d ago", secs / 86_400),
+        _ => then.format("%Y-%m-%d").to_string(),
+    }
+}
+
+/// Left margin of a comment box, deeper for replies.
+fn box_margin(reply: bool) -> &'static str {
+    if reply { "         " } else { "     " }
 }
 
 /// Format a comment input as multiple lines with a box border for inline editing.
 /// This mimics the normal comment display but shows it's being edited.
 ///
+/// Text is soft-wrapped to fit `width` columns, the space the box is drawn in;
+/// a width of 0 leaves lines unwrapped.
+///
 /// Returns a tuple of (lines, cursor_info) where cursor_info contains the position
 /// of the cursor within the formatted output for IME positioning.
+#[allow(clippy::too_many_arguments)]
 pub fn format_comment_input_lines(
     theme: &Theme,
     comment_type: CommentTypePresentation,
@@ -43,6 +72,7 @@ pub fn format_comment_input_lines(
     line_range: Option<LineRange>,
     is_editing: bool,
     supports_keyboard_enhancement: bool,
+    width: usize,
 ) -> (Vec<Line<'static>>, CommentCursorInfo) {
     let type_style = styles::comment_type_style(theme, comment_type.color);
     let border_style = styles::comment_border_style(theme, comment_type.color);
@@ -50,7 +80,12 @@ pub fn format_comment_input_lines(
         .fg(theme.cursor_color)
         .add_modifier(Modifier::UNDERLINED);
 
-    let action = if is_editing { "Edit" } else { "Add" };
+    let action = match (is_editing, comment_type.reply) {
+        (true, _) => "Edit",
+        (false, true) => "Reply",
+        (false, false) => "Add",
+    };
+    let margin = box_margin(comment_type.reply);
     let line_info = match line_range {
         Some(range) if range.is_single() => format!("L{} ", range.start),
         Some(range) => format!("L{}-L{} ", range.start, range.end),
@@ -66,14 +101,14 @@ pub fn format_comment_input_lines(
     let mut result = Vec::new();
     // Track cursor position: line offset within result, column (display width)
     // Default to first content line (index 1) with cursor at start of content (after border)
-    let border_prefix = "     │ ";
+    let border_prefix = format!("{margin}│ ");
     let border_width = border_prefix.width() as u16;
     let mut cursor_line_offset: usize = 1; // First content line (after header)
     let mut cursor_column: u16 = border_width; // After the border prefix
 
     // Top border with type label and hints
     result.push(Line::from(vec![
-        Span::styled("     ╭─ ", border_style),
+        Span::styled(format!("{margin}╭─ "), border_style),
         Span::styled(format!("{} ", action), styles::dim_style(theme)),
         Span::styled(format!("[{}] ", comment_type.label), type_style),
         Span::styled(line_info, styles::dim_style(theme)),
@@ -90,7 +125,7 @@ pub fn format_comment_input_lines(
     if buffer.is_empty() {
         // Show placeholder with cursor at start
         result.push(Line::from(vec![
-            Span::styled(border_prefix, border_style),
+            Span::styled(border_prefix.clone(), border_style),
             Span::styled(" ", cursor_style),
             Span::styled("Type your comment...", styles::dim_style(theme)),
         ]));
@@ -100,6 +135,8 @@ pub fn format_comment_input_lines(
         // Split buffer into lines and render with cursor
         let buffer_lines: Vec<&str> = buffer.split('\n').collect();
         let mut char_offset = 0;
+        // Keep a column free for the cursor after the last character
+        let text_width = width.saturating_sub(border_width as usize + 1);
 
         for (line_idx, text) in buffer_lines.iter().enumerate() {
             let line_start = char_offset;
@@ -109,36 +146,47 @@ pub fn format_comment_input_lines(
             let cursor_on_this_line = cursor_pos >= line_start
                 && (cursor_pos <= line_end
                     || (line_idx == buffer_lines.len() - 1 && cursor_pos == buffer.len()));
-
-            let mut line_spans = vec![Span::styled(border_prefix, border_style)];
-
-            if cursor_on_this_line {
-                let cursor_pos_in_line = cursor_pos - line_start;
-                let cursor_pos_in_line = cursor_pos_in_line.min(text.len());
-                let (before_cursor, after_cursor) = text.split_at(cursor_pos_in_line);
-
-                // Track cursor position for IME
-                // line_offset: header (1) + current content line index
-                cursor_line_offset = 1 + line_idx;
-                // column: border width + display width of text before cursor
-                cursor_column = border_width + before_cursor.width() as u16;
-
-                if after_cursor.is_empty() {
-                    line_spans.push(Span::raw(before_cursor.to_string()));
-                    line_spans.push(Span::styled(" ", cursor_style));
+            let cursor_pos_in_line = cursor_pos.saturating_sub(line_start).min(text.len());
+
+            let rows = wrap_rows(text, text_width);
+            for (row_idx, &(row_start, row_end)) in rows.iter().enumerate() {
+                let row_text = &text[row_start..row_end];
+                let mut line_spans = vec![Span::styled(border_prefix.clone(), border_style)];
+
+                // A cursor at a row break is drawn at the start of the next row
+                let last_row = row_idx + 1 == rows.len();
+                let cursor_on_this_row = cursor_on_this_line
+                    && cursor_pos_in_line >= row_start
+                    && (cursor_pos_in_line < row_end
+                        || (last_row && cursor_pos_in_line == row_end));
+
+                if cursor_on_this_row {
+                    let (before_cursor, after_cursor) =
+                        row_text.split_at(cursor_pos_in_line - row_start);
+
+                    // Track cursor position for IME
+                    // line_offset: header (1) + rows rendered so far
+                    cursor_line_offset = result.len();
+                    // column: border width + display width of text before cursor
+                    cursor_column = border_width + before_cursor.width() as u16;
+
+                    if after_cursor.is_empty() {
+                        line_spans.push(Span::raw(before_cursor.to_string()));
+                        line_spans.push(Span::styled(" ", cursor_style));
+                    } else {
+                        let mut chars = after_cursor.chars();
+                        let cursor_char = chars.next().unwrap();
+                        let remaining = chars.as_str();
+                        line_spans.push(Span::raw(before_cursor.to_string()));
+                        line_spans.push(Span::styled(cursor_char.to_string(), cursor_style));
+                        line_spans.push(Span::raw(remaining.to_string()));
+                    }
                 } else {
-                    let mut chars = after_cursor.chars();
-                    let cursor_char = chars.next().unwrap();
-                    let remaining = chars.as_str();
-                    line_spans.push(Span::raw(before_cursor.to_string()));
-                    line_spans.push(Span::styled(cursor_char.to_string(), cursor_style));
-                    line_spans.push(Span::raw(remaining.to_string()));
+                    line_spans.push(Span::raw(row_text.to_string()));
                 }
-            } else {
-                line_spans.push(Span::raw(text.to_string()));
-            }
 
-            result.push(Line::from(line_spans));
+                result.push(Line::from(line_spans));
+            }
 
             // Account for newline character (except for last line)
             char_offset = line_end + 1;
@@ -147,7 +195,7 @@ pub fn format_comment_input_lines(
 
     // Bottom border
     result.push(Line::from(vec![Span::styled(
-        "     ╰".to_string() + &"─".repeat(38),
+        format!("{margin}╰") + &"─".repeat(38),
         border_style,
     )]));
 
@@ -159,6 +207,33 @@ pub fn format_comment_input_lines(
     (result, cursor_info)
 }
 
+/// Byte ranges splitting `text` into rows of at most `width` columns, broken
+/// after the last space that fits when there is one. A width of 0 keeps the
+/// text on one row.
+fn wrap_rows(text: &str, width: usize) -> Vec<(usize, usize)> {
+    let mut rows = Vec::new();
+    let mut start = 0;
+    let mut row_width = 0;
+    // Byte offset just past the last space on the current row
+    let mut last_break = None;
+    for (idx, ch) in text.char_indices() {
+        let ch_width = ch.width().unwrap_or(0);
+        if width > 0 && idx > start && row_width + ch_width > width {
+            let end = last_break.filter(|&end| end > start).unwrap_or(idx);
+            rows.push((start, end));
+            row_width = text[end..idx].width();
+            start = end;
+            last_break = None;
+        }
+        row_width += ch_width;
+        if ch == ' ' {
+            last_break = Some(idx + 1);
+        }
+    }
+    rows.push((start, text.len()));
+    rows
+}
+
 /// Format a comment as multiple lines with a box border (themed version)
 pub fn format_comment_lines(
     theme: &Theme,
@@ -175,28 +250,43 @@ pub fn format_comment_lines(
         None => String::new(),
     };
     let content_lines: Vec<&str> = content.split('\n').collect();
+    let margin = box_margin(comment_type.reply);
 
     let mut result = Vec::new();
 
     // Top border with type label
     result.push(Line::from(vec![
-        Span::styled("     ╭─ ", border_style),
+        Span::styled(format!("{margin}╭─ "), border_style),
         Span::styled(format!("[{}] ", comment_type.label), type_style),
         Span::styled(line_info, styles::dim_style(theme)),
+        Span::styled(
+            comment_type
+                .author
+                .map(|author| format!("{author} "))
+                .unwrap_or_default(),
+            styles::comment_author_style(theme),
+        ),
+        Span::styled(
+            comment_type
+                .age
+                .map(|age| format!("{age} "))
+                .unwrap_or_default(),
+            styles::dim_style(theme),
+        ),
         Span::styled("─".repeat(30), border_style),
     ]));
 
     // Content lines
     for line in &content_lines {
         result.push(Line::from(vec![
-            Span::styled("     │ ", border_style),
+            Span::styled(format!("{margin}│ "), border_style),
             Span::raw(line.to_string()),
         ]));
     }
 
     // Bottom border
     result.push(Line::from(vec![Span::styled(
-        "     ╰".to_string() + &"─".repeat(38),
+        format!("{margin}╰") + &"─".repeat(38),
         border_style,
     )]));
 
@@ -265,12 +355,16 @@ mod tests {
             CommentTypePresentation {
                 label: "NOTE".to_string(),
                 color: Color::Blue,
+                reply: false,
+                age: None,
+                author: None,
             },
             "",
             0,
             None,
             false,
             false,
+            80,
         );
 
         // then
@@ -292,12 +386,16 @@ mod tests {
             CommentTypePresentation {
                 label: "NOTE".to_string(),
                 color: Color::Blue,
+                reply: false,
+                age: None,
+                author: None,
             },
             buffer,
             cursor_pos,
             None,
             false,
             false,
+            80,
         );
 
         // then
@@ -318,12 +416,16 @@ mod tests {
             CommentTypePresentation {
                 label: "NOTE".to_string(),
                 color: Color::Blue,
+                reply: false,
+                age: None,
+                author: None,
             },
             buffer,
             cursor_pos,
             None,
             false,
             false,
+            80,
         );
 
         // then
@@ -345,12 +447,16 @@ mod tests {
             CommentTypePresentation {
                 label: "NOTE".to_string(),
                 color: Color::Blue,
+                reply: false,
+                age: None,
+                author: None,
             },
             buffer,
             cursor_pos,
             None,
             false,
             false,
+            80,
         );
 
         // then
@@ -371,12 +477,16 @@ mod tests {
             CommentTypePresentation {
                 label: "NOTE".to_string(),
                 color: Color::Blue,
+                reply: false,
+                age: None,
+                author: None,
             },
             buffer,
             cursor_pos,
             None,
             false,
             false,
+            80,
         );
 
         // then
@@ -398,12 +508,16 @@ mod tests {
             CommentTypePresentation {
                 label: "NOTE".to_string(),
                 color: Color::Blue,
+                reply: false,
+                age: None,
+                author: None,
             },
             buffer,
             cursor_pos,
             None,
             false,
             false,
+            80,
         );
 
         // then
@@ -411,4 +525,69 @@ mod tests {
         // "a" = 1 display width, "좋" = 2 display width, total = 3
         assert_eq!(cursor_info.column, 7 + 3);
     }
+
+    #[test]
+    fn should_wrap_long_lines_and_place_cursor_on_wrapped_row() {
+        // given: 20 columns leave 12 for text after the border and cursor
+        let theme = test_theme();
+        let buffer = "hello world again\nx";
+        let format = |cursor_pos| {
+            format_comment_input_lines(
+                &theme,
+                CommentTypePresentation {
+                    label: "NOTE".to_string(),
+                    color: Color::Blue,
+                    reply: false,
+                    age: None,
+                    author: None,
+                },
+                buffer,
+                cursor_pos,
+                None,
+                false,
+                false,
+                20,
+            )
+        };
+
+        // when
+        let (lines, at_break) = format(12);
+        let (_, at_end) = format(17);
+        let (_, next_line) = format(18);
+
+        // then
+        let text: Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+        assert_eq!(text[1], "     │ hello world ");
+        assert_eq!(text[2], "     │ again");
+        assert_eq!(text[3], "     │ x");
+        assert_eq!(lines.len(), 5);
+        assert_eq!((at_break.line_offset, at_break.column), (2, 7));
+        assert_eq!((at_end.line_offset, at_end.column), (2, 7 + 5));
+        assert_eq!((next_line.line_offset, next_line.column), (3, 7));
+    }
+
+    #[test]
+    fn should_break_words_longer_than_a_row() {
+        assert_eq!(wrap_rows("abcdefgh", 3), [(0, 3), (3, 6), (6, 8)]);
+        assert_eq!(wrap_rows("ab cd", 0), [(0, 5)]);
+        assert_eq!(wrap_rows("", 3), [(0, 0)]);
+        // Wide characters are measured by display width
+        assert_eq!(wrap_rows("안녕하", 4), [(0, 6), (6, 9)]);
+    }
+
+    #[test]
+    fn should_format_comment_age_relative_to_now() {
+        let now = DateTime::parse_from_rfc3339("2025-03-20T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let ago = |secs| format_age(now - chrono::Duration::seconds(secs), now);
+
+        assert_eq!(ago(30), "just now");
+        assert_eq!(ago(5 * 60), "5m ago");
+        assert_eq!(ago(3 * 3_600), "3h ago");
+        assert_eq!(ago(2 * 86_400), "2d ago");
+        assert_eq!(ago(90 * 86_400), "2024-12-20");
+        // Clock skew from a session saved on another machine
+        assert_eq!(ago(-120), "just now");
+    }
 }
@@ -18,6 +18,9 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
 
     let block = Block::default()
         .title(" Help (j/k to scroll) - Press ? or Esc to close ")
+        .title_bottom(
+            Line::from(format!(" tuicr {} ", crate::update::version_string())).right_aligned(),
+        )
         .borders(Borders::ALL)
         .style(styles::popup_style(theme))
         .border_style(styles::border_style(theme, true));
@@ -45,6 +48,13 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
             ),
             Span::raw("Scroll view down/up"),
         ]),
+        Line::from(vec![
+            Span::styled(
+                "  Ctrl-j/k  ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Scroll file list down/up"),
+        ]),
         Line::from(vec![
             Span::styled(
                 "  Ctrl-d/u  ",
@@ -61,10 +71,10 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
         ]),
         Line::from(vec![
             Span::styled(
-                "  g/G       ",
+                "  gg/G      ",
                 Style::default().add_modifier(Modifier::BOLD),
             ),
-            Span::raw("Go to first/last file"),
+            Span::raw("Go to first/last line"),
         ]),
         Line::from(vec![
             Span::styled(
@@ -73,6 +83,13 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
             ),
             Span::raw("Go to source line N in current file"),
         ]),
+        Line::from(vec![
+            Span::styled(
+                "  {N}j/k    ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Repeat a motion N times (also {N}{ } [ ])"),
+        ]),
         Line::from(vec![
             Span::styled(
                 "  {/}       ",
@@ -87,6 +104,48 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
             ),
             Span::raw("Jump to prev/next hunk"),
         ]),
+        Line::from(vec![
+            Span::styled(
+                "  [x/]x     ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Jump to prev/next conflicted file"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  [q/]q     ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Jump to prev/next comment"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  [c/]c     ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Move to prev/next comment, without wrapping"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  [f/]f     ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Move to prev/next file header"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  [u/]u     ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Move to prev/next unreviewed file"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  gm        ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Jump to other side of moved block"),
+        ]),
         Line::from(vec![
             Span::styled(
                 "  /         ",
@@ -101,12 +160,33 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
             ),
             Span::raw("Next/prev search match"),
         ]),
+        Line::from(vec![
+            Span::styled(
+                "  zz/zt/zb  ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Scroll cursor to center/top/bottom"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  m{a-z}    ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Mark the cursor line"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  `{a-z}    ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Jump to a mark"),
+        ]),
         Line::from(vec![
             Span::styled(
                 "  Enter     ",
                 Style::default().add_modifier(Modifier::BOLD),
             ),
-            Span::raw("Expand/collapse context (20 lines)"),
+            Span::raw("Expand/collapse context or generated file, load rest"),
         ]),
         Line::from(vec![
             Span::styled(
@@ -115,6 +195,20 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
             ),
             Span::raw("Expand/collapse all hidden context"),
         ]),
+        Line::from(vec![
+            Span::styled(
+                "  E         ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Expand context from the top of the gap"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  zc        ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Collapse context"),
+        ]),
         Line::from(vec![
             Span::styled(
                 "  Tab/S-Tab ",
@@ -143,6 +237,20 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
             ),
             Span::raw("Toggle file list visibility"),
         ]),
+        Line::from(vec![
+            Span::styled(
+                "  ;>/;<     ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Widen/narrow file list"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  ;+/;-     ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Grow/shrink commit selector"),
+        ]),
         Line::from(""),
         Line::from(Span::styled(
             "Commit Selector (multi-commit reviews)",
@@ -199,14 +307,14 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
         ]),
         Line::from(vec![
             Span::styled(
-                "  o         ",
+                "  o / zR    ",
                 Style::default().add_modifier(Modifier::BOLD),
             ),
             Span::raw("Expand all directories"),
         ]),
         Line::from(vec![
             Span::styled(
-                "  O         ",
+                "  O / zM    ",
                 Style::default().add_modifier(Modifier::BOLD),
             ),
             Span::raw("Collapse all directories"),
@@ -224,6 +332,13 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
             ),
             Span::raw("Toggle file reviewed"),
         ]),
+        Line::from(vec![
+            Span::styled(
+                "  Space     ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Toggle hunk reviewed (on a hunk header)"),
+        ]),
         Line::from(vec![
             Span::styled(
                 "  c         ",
@@ -252,6 +367,41 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
             ),
             Span::raw("Edit comment at cursor"),
         ]),
+        Line::from(vec![
+            Span::styled(
+                "  R         ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Reply to comment at cursor"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  e         ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Open file in $EDITOR at cursor line"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  gb        ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Blame line at cursor (again: full commit)"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  w         ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Toggle ignoring whitespace changes"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  a         ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Apply ```suggestion block of comment at cursor"),
+        ]),
         Line::from(vec![
             Span::styled(
                 "  dd        ",
@@ -261,7 +411,77 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
         ]),
         Line::from(vec![
             Span::styled(
-                "  y         ",
+                "  gt        ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Cycle type of comment at cursor"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  u         ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Undo last comment or reviewed change"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  Ctrl-r    ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Redo"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  s / S     ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Stage / unstage hunk under cursor (git)"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  X         ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Discard hunk under cursor (working tree)"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  yy        ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Yank (copy) line under cursor"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  yh        ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Yank (copy) raw hunk under cursor"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  yc        ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Yank (copy) comment under cursor"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  yr        ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Yank path:line reference"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  yp        ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Yank permalink to line (git origin)"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  Y         ",
                 Style::default().add_modifier(Modifier::BOLD),
             ),
             Span::raw("Yank (copy) review to clipboard"),
@@ -293,6 +513,34 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
             ),
             Span::raw("Create comment for selected range"),
         ]),
+        Line::from(vec![
+            Span::styled(
+                "  y         ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Yank (copy) selected lines"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  gy        ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Yank selected lines with +/- markers"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  r         ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Yank path:line range reference"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  Y         ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Yank permalink to range"),
+        ]),
         Line::from(vec![
             Span::styled(
                 "  Esc/v/V   ",
@@ -334,6 +582,13 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
             ),
             Span::raw("Insert newline"),
         ]),
+        Line::from(vec![
+            Span::styled(
+                "  Ctrl-G    ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Edit comment in $EDITOR"),
+        ]),
         Line::from(vec![
             Span::styled(
                 "  Ctrl-A/E  ",
@@ -370,7 +625,7 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
         Line::from(""),
         Line::from(vec![
             Span::styled(
-                "  :w        ",
+                "  :w/:write ",
                 Style::default().add_modifier(Modifier::BOLD),
             ),
             Span::raw("Save review session"),
@@ -389,6 +644,34 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
             ),
             Span::raw("Copy review to clipboard"),
         ]),
+        Line::from(vec![
+            Span::styled(
+                "  :export github",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("  Post review to GitHub PR (gh)"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :export rdjson <path>",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("  Write reviewdog diagnostics"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :checklist [path]",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("  Copy reviewed/pending file checklist"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :summary [markdown]",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("  Copy a review summary with a verdict"),
+        ]),
         Line::from(vec![
             Span::styled(
                 "  :set wrap ",
@@ -403,6 +686,45 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
             ),
             Span::raw("Toggle line wrap in diff view"),
         ]),
+        Line::from(vec![
+            Span::styled("  :set iw!", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("  Toggle ignoring whitespace changes"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :set skipreviewed!",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("  Toggle skipping reviewed files"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :set preview!",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("  Toggle diff following the file list cursor"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :set changesonly!",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("  Toggle hiding unchanged lines in hunks"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :set scrollbar!",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("  Toggle diff and file list scrollbars"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :show-ignored",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("  Toggle listing ignored files"),
+        ]),
         Line::from(vec![
             Span::styled(
                 "  :stage    ",
@@ -410,6 +732,13 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
             ),
             Span::raw("Stage reviewed files"),
         ]),
+        Line::from(vec![
+            Span::styled(
+                "  :describe ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Edit the commit message in $EDITOR"),
+        ]),
         Line::from(vec![
             Span::styled(
                 "  :diff     ",
@@ -417,6 +746,69 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
             ),
             Span::raw("Toggle unified/side-by-side diff view"),
         ]),
+        Line::from(vec![
+            Span::styled(
+                "  :diff <revset>",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("  Review commits, like -r"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :diff worktree",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("  Back to uncommitted changes"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :show <rev>",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("  Review a single commit"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :view     ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Toggle unified/side-by-side for the current file"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :comments ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("List all comments (Enter:jump  d:delete)"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :stats    ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Show file and line totals for the diff"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :blame    ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Toggle commit and author column (working tree)"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :expand   ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Expand all hidden context in the current file"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :collapse ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Collapse expanded context in the current file"),
+        ]),
         Line::from(vec![
             Span::styled(
                 "  :commits  ",
@@ -424,6 +816,62 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
             ),
             Span::raw("Select commits or staged/unstaged changes"),
         ]),
+        Line::from(vec![
+            Span::styled(
+                "  :sessions ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Browse saved sessions (Enter/d/n: load/delete/new)"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :branches ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Review a branch's commits since it forked"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :{N}      ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Go to source line N in current file"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :filter [glob]",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("  Only list matching files (no glob clears)"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :only     ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Review only the current file (again restores)"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :all      ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Restore the full diff after :only"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :set-author <name>",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("  Attribute new comments to name"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :theme <name>",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("  Switch and save the color theme"),
+        ]),
         Line::from(vec![
             Span::styled(
                 "  :set commits",
@@ -482,10 +930,10 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
         ]),
         Line::from(vec![
             Span::styled(
-                "  :version  ",
+                "  :about    ",
                 Style::default().add_modifier(Modifier::BOLD),
             ),
-            Span::raw("Show tuicr version"),
+            Span::raw("Show tuicr version (also :version)"),
         ]),
         Line::from(vec![
             Span::styled(
@@ -0,0 +1,319 @@
+//! Inline previews of changed images for terminals with a graphics protocol.
+//!
+//! Binary image files get [`PREVIEW_ROWS`] blank rows below their descriptor.
+//! After each frame is drawn, the image is written over those rows with the
+//! Kitty or iTerm2 escape sequence, read from the working tree.
+
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use crossterm::cursor::{MoveTo, RestorePosition, SavePosition};
+use crossterm::queue;
+use ratatui::layout::Rect;
+
+use crate::app::{AnnotatedLine, App, InputMode};
+use crate::model::binary::image_dimensions;
+
+/// Rows reserved below a binary file's descriptor for its preview.
+pub const PREVIEW_ROWS: usize = 12;
+
+/// Width of the cursor indicator in front of each diff row.
+const INDICATOR_WIDTH: u16 = 2;
+
+/// Pixel size assumed for a terminal cell when sizing previews.
+const CELL_PIXELS: (u32, u32) = (8, 16);
+
+/// Base64 bytes per Kitty graphics command; the protocol caps chunks at 4096.
+const KITTY_CHUNK_SIZE: usize = 4096;
+
+/// Deletes every Kitty image placement on screen.
+const KITTY_DELETE_ALL: &str = "\x1b_Ga=d,d=A,q=2\x1b\\";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageProtocol {
+    /// Kitty graphics protocol, also spoken by Ghostty and WezTerm
+    Kitty,
+    /// iTerm2 inline images (OSC 1337)
+    ITerm2,
+}
+
+impl ImageProtocol {
+    /// Whether the protocol can show the image at `path`. Kitty only
+    /// accepts PNG data, iTerm2 decodes JPEGs as well.
+    pub fn supports(self, path: &Path) -> bool {
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase);
+        match extension.as_deref() {
+            Some("png") => true,
+            Some("jpg" | "jpeg") => self == Self::ITerm2,
+            _ => false,
+        }
+    }
+}
+
+/// Guess the terminal's image protocol from its environment variables.
+/// tmux drops graphics escapes unless passthrough is configured, so no
+/// protocol is assumed inside it.
+pub fn detect_image_protocol(env: impl Fn(&str) -> Option<String>) -> Option<ImageProtocol> {
+    if env("TMUX").is_some() {
+        return None;
+    }
+    let term = env("TERM").unwrap_or_default();
+    let term_program = env("TERM_PROGRAM").unwrap_or_default();
+    if env("KITTY_WINDOW_ID").is_some()
+        || matches!(term.as_str(), "xterm-kitty" | "xterm-ghostty")
+        || matches!(term_program.as_str(), "ghostty" | "WezTerm")
+    {
+        Some(ImageProtocol::Kitty)
+    } else if term_program == "iTerm.app" || env("LC_TERMINAL").as_deref() == Some("iTerm2") {
+        Some(ImageProtocol::ITerm2)
+    } else {
+        None
+    }
+}
+
+/// An image file and the screen cells its preview may fill.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImagePlacement {
+    pub path: PathBuf,
+    pub area: Rect,
+}
+
+/// Previews whose reserved rows are fully visible in the last rendered diff.
+/// Nothing is placed while a popup could be covering the diff, or while a
+/// comment input box shifts rendered rows away from their annotations.
+pub fn visible_placements(app: &App) -> Vec<ImagePlacement> {
+    let covered = matches!(
+        app.input_mode,
+        InputMode::Help
+            | InputMode::Confirm
+            | InputMode::CommitSelect
+            | InputMode::SessionBrowser
+            | InputMode::BranchPicker
+            | InputMode::Comment
+    ) || app.blame_popup.is_some()
+        || app.stats_popup.is_some();
+    let Some(inner) = app.diff_inner_area.filter(|_| !covered) else {
+        return Vec::new();
+    };
+    if inner.width <= INDICATOR_WIDTH {
+        return Vec::new();
+    }
+
+    let preview_file = |row: usize| match app
+        .diff_row_to_annotation
+        .get(row)
+        .and_then(|&idx| app.line_annotations.get(idx))
+    {
+        Some(AnnotatedLine::ImagePreview { file_idx }) => Some(*file_idx),
+        _ => None,
+    };
+
+    let mut placements = Vec::new();
+    let mut row = 0;
+    while row < app.diff_row_to_annotation.len() {
+        let Some(file_idx) = preview_file(row) else {
+            row += 1;
+            continue;
+        };
+        let start = row;
+        while preview_file(row) == Some(file_idx) {
+            row += 1;
+        }
+        if row - start < PREVIEW_ROWS {
+            continue;
+        }
+        let Some(file) = app.diff_files.get(file_idx) else {
+            continue;
+        };
+        placements.push(ImagePlacement {
+            path: app.vcs_info.root_path.join(file.display_path()),
+            area: Rect {
+                x: inner.x + INDICATOR_WIDTH,
+                y: inner.y + start as u16,
+                width: inner.width - INDICATOR_WIDTH,
+                height: PREVIEW_ROWS as u16,
+            },
+        });
+    }
+    placements
+}
+
+/// Cells to draw a `width`×`height` pixel image in: its natural size,
+/// shrunk to fit within `max_cols`×`max_rows` keeping the aspect ratio.
+pub fn fit_cells((width, height): (u32, u32), max_cols: u16, max_rows: u16) -> (u16, u16) {
+    let cols = f64::from(width.div_ceil(CELL_PIXELS.0).max(1));
+    let rows = f64::from(height.div_ceil(CELL_PIXELS.1).max(1));
+    let scale = (f64::from(max_cols) / cols)
+        .min(f64::from(max_rows) / rows)
+        .min(1.0);
+    (
+        (cols * scale).round().max(1.0) as u16,
+        (rows * scale).round().max(1.0) as u16,
+    )
+}
+
+/// Escape sequence drawing `bytes` at the cursor, scaled to `cols`×`rows` cells.
+pub fn image_sequence(protocol: ImageProtocol, bytes: &[u8], cols: u16, rows: u16) -> String {
+    let encoded = BASE64.encode(bytes);
+    let mut out = String::new();
+    match protocol {
+        ImageProtocol::Kitty => {
+            // Base64 output is ASCII, so byte chunks are valid strings
+            let chunks: Vec<&str> = encoded
+                .as_bytes()
+                .chunks(KITTY_CHUNK_SIZE)
+                .filter_map(|chunk| std::str::from_utf8(chunk).ok())
+                .collect();
+            for (i, chunk) in chunks.iter().enumerate() {
+                let more = u8::from(i + 1 < chunks.len());
+                if i == 0 {
+                    let _ = write!(
+                        out,
+                        "\x1b_Ga=T,f=100,q=2,C=1,c={cols},r={rows},m={more};{chunk}\x1b\\"
+                    );
+                } else {
+                    let _ = write!(out, "\x1b_Gm={more};{chunk}\x1b\\");
+                }
+            }
+        }
+        ImageProtocol::ITerm2 => {
+            let _ = write!(
+                out,
+                "\x1b]1337;File=inline=1;size={};width={cols};height={rows};preserveAspectRatio=1:{encoded}\x07",
+                bytes.len()
+            );
+        }
+    }
+    out
+}
+
+/// Tracks the previews on screen so images are only sent when they move.
+#[derive(Debug, Default)]
+pub struct InlineImages {
+    shown: Vec<ImagePlacement>,
+    frame: Rect,
+}
+
+impl InlineImages {
+    /// Whether the frame must be repainted before drawing `placements`.
+    /// iTerm2 images live in the cell grid, so stale ones are only wiped
+    /// by redrawing the cells under them.
+    pub fn needs_repaint(
+        &self,
+        protocol: ImageProtocol,
+        frame: Rect,
+        placements: &[ImagePlacement],
+    ) -> bool {
+        protocol == ImageProtocol::ITerm2
+            && !self.shown.is_empty()
+            && (self.frame != frame || self.shown != placements)
+    }
+
+    /// Draw `placements` over the rendered frame. Images that can't be read
+    /// keep the textual descriptor above their blank rows.
+    pub fn draw(
+        &mut self,
+        out: &mut impl Write,
+        protocol: ImageProtocol,
+        frame: Rect,
+        placements: Vec<ImagePlacement>,
+    ) -> io::Result<()> {
+        if self.frame == frame && self.shown == placements {
+            return Ok(());
+        }
+        queue!(out, SavePosition)?;
+        if protocol == ImageProtocol::Kitty && !self.shown.is_empty() {
+            out.write_all(KITTY_DELETE_ALL.as_bytes())?;
+        }
+        for placement in &placements {
+            let Ok(bytes) = std::fs::read(&placement.path) else {
+                continue;
+            };
+            let Some(dimensions) = image_dimensions(&bytes) else {
+                continue;
+            };
+            let (cols, rows) = fit_cells(dimensions, placement.area.width, placement.area.height);
+            queue!(out, MoveTo(placement.area.x, placement.area.y))?;
+            out.write_all(image_sequence(protocol, &bytes, cols, rows).as_bytes())?;
+        }
+        queue!(out, RestorePosition)?;
+        out.flush()?;
+        self.shown = placements;
+        self.frame = frame;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn detect(vars: &[(&str, &str)]) -> Option<ImageProtocol> {
+        let vars: HashMap<&str, &str> = vars.iter().copied().collect();
+        detect_image_protocol(|key| vars.get(key).map(|value| value.to_string()))
+    }
+
+    #[test]
+    fn should_detect_image_protocol_from_environment() {
+        assert_eq!(
+            detect(&[("TERM", "xterm-kitty")]),
+            Some(ImageProtocol::Kitty)
+        );
+        assert_eq!(
+            detect(&[("TERM", "xterm-256color"), ("TERM_PROGRAM", "WezTerm")]),
+            Some(ImageProtocol::Kitty)
+        );
+        assert_eq!(
+            detect(&[("TERM_PROGRAM", "iTerm.app")]),
+            Some(ImageProtocol::ITerm2)
+        );
+        assert_eq!(
+            detect(&[("LC_TERMINAL", "iTerm2")]),
+            Some(ImageProtocol::ITerm2)
+        );
+        assert_eq!(detect(&[("TERM", "xterm-256color")]), None);
+        assert_eq!(
+            detect(&[("TERM", "xterm-kitty"), ("TMUX", "/tmp/tmux-1000/default")]),
+            None
+        );
+
+        assert!(ImageProtocol::Kitty.supports(Path::new("logo.PNG")));
+        assert!(!ImageProtocol::Kitty.supports(Path::new("photo.jpg")));
+        assert!(ImageProtocol::ITerm2.supports(Path::new("photo.jpeg")));
+        assert!(!ImageProtocol::ITerm2.supports(Path::new("icon.gif")));
+    }
+
+    #[test]
+    fn should_fit_images_within_preview_area() {
+        // Small images keep their natural size
+        assert_eq!(fit_cells((64, 32), 80, 12), (8, 2));
+        // Tall images are limited by rows, wide ones by columns
+        assert_eq!(fit_cells((800, 1600), 80, 12), (12, 12));
+        assert_eq!(fit_cells((1920, 160), 80, 12), (80, 3));
+        assert_eq!(fit_cells((1, 1), 80, 12), (1, 1));
+    }
+
+    #[test]
+    fn should_build_image_escape_sequences() {
+        let kitty = image_sequence(ImageProtocol::Kitty, b"png", 4, 2);
+        assert_eq!(kitty, "\x1b_Ga=T,f=100,q=2,C=1,c=4,r=2,m=0;cG5n\x1b\\");
+
+        let large = vec![0u8; KITTY_CHUNK_SIZE];
+        let kitty = image_sequence(ImageProtocol::Kitty, &large, 4, 2);
+        assert_eq!(kitty.matches("\x1b_G").count(), 2);
+        assert!(kitty.contains(",m=1;"));
+        assert!(kitty.contains("\x1b_Gm=0;"));
+
+        let iterm = image_sequence(ImageProtocol::ITerm2, b"png", 4, 2);
+        assert_eq!(
+            iterm,
+            "\x1b]1337;File=inline=1;size=3;width=4;height=2;preserveAspectRatio=1:cG5n\x07"
+        );
+    }
+}
@@ -1,8 +1,15 @@
 pub mod app_layout;
+pub mod blame_popup;
+pub mod branch_picker;
+pub mod comment_list;
 pub mod comment_panel;
 pub mod diff_view;
 pub mod file_list;
 pub mod help_popup;
+pub mod inline_image;
+pub mod scrollbar;
+pub mod session_browser;
+pub mod stats_popup;
 pub mod status_bar;
 pub mod styles;
 
@@ -0,0 +1,357 @@
+//! Scrollbars over the right border of the diff and file list panels.
+
+use ratatui::{
+    Frame,
+    layout::{Margin, Rect},
+    style::Style,
+    widgets::{Scrollbar, ScrollbarOrientation, ScrollbarState},
+};
+
+use unicode_width::UnicodeWidthStr;
+
+use crate::app::{AnnotatedLine, App};
+use crate::model::{CommentType, LineOrigin};
+use crate::ui::app_layout::{BLAME_COLUMN_WIDTH, wrapped_row_count};
+
+/// A diff line marked on the scrollbar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScrollbarTick {
+    File,
+    /// First row of a comment, colored by its type
+    Comment(CommentType),
+}
+
+/// Lines starting a file or a comment. Computed once per annotation rebuild
+/// and cached in `App::scrollbar_ticks`.
+pub fn scrollbar_ticks(app: &App) -> Vec<(usize, ScrollbarTick)> {
+    let mut ticks = Vec::new();
+    let mut previous = None;
+    for (idx, annotation) in app.line_annotations.iter().enumerate() {
+        let tick = match annotation {
+            AnnotatedLine::FileHeader { .. } => Some(ScrollbarTick::File),
+            AnnotatedLine::ReviewComment { .. }
+            | AnnotatedLine::FileComment { .. }
+            | AnnotatedLine::LineComment { .. }
+                if previous != Some(annotation) =>
+            {
+                app.comment_at_annotation(idx)
+                    .map(|comment| ScrollbarTick::Comment(comment.comment_type.clone()))
+            }
+            _ => None,
+        };
+        if let Some(tick) = tick {
+            ticks.push((idx, tick));
+        }
+        previous = Some(annotation);
+    }
+    ticks
+}
+
+/// Visual row each diff line starts at with wrapping on, measured for one
+/// viewport width and blamed file. Cached in `App::scrollbar_rows`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VisualRows {
+    width: usize,
+    blamed_file: Option<usize>,
+    /// One entry per line plus the total
+    starts: Vec<usize>,
+}
+
+impl VisualRows {
+    /// Wrap every line by its unwrapped width, the way the diff view does,
+    /// without building the lines.
+    fn measure(app: &App, width: usize) -> Self {
+        let mut starts = Vec::with_capacity(app.line_annotations.len() + 1);
+        let mut row = 0;
+        for annotation in &app.line_annotations {
+            starts.push(row);
+            row += wrapped_row_count(unwrapped_width(app, annotation), width);
+        }
+        starts.push(row);
+        Self {
+            width,
+            blamed_file: blamed_file(app),
+            starts,
+        }
+    }
+
+    fn is_current(&self, app: &App, width: usize) -> bool {
+        self.width == width && self.blamed_file == blamed_file(app)
+    }
+
+    fn start(&self, line: usize) -> usize {
+        self.starts
+            .get(line)
+            .or(self.starts.last())
+            .copied()
+            .unwrap_or(line)
+    }
+}
+
+/// The file drawn with a `:blame` column, which widens its lines.
+fn blamed_file(app: &App) -> Option<usize> {
+    let file_idx = app.diff_state.current_file_idx;
+    app.blame_column(file_idx).map(|_| file_idx)
+}
+
+/// Columns a unified diff or expanded context line takes before wrapping.
+/// Other rows, side-by-side ones included, are cut to the viewport.
+fn unwrapped_width(app: &App, annotation: &AnnotatedLine) -> usize {
+    let (file_idx, line, lineno) = match annotation {
+        AnnotatedLine::DiffLine {
+            file_idx,
+            hunk_idx,
+            line_idx,
+            ..
+        } => {
+            let Some(line) = app
+                .diff_files
+                .get(*file_idx)
+                .and_then(|file| file.hunks.get(*hunk_idx))
+                .and_then(|hunk| hunk.lines.get(*line_idx))
+            else {
+                return 0;
+            };
+            let lineno = match line.origin {
+                LineOrigin::Addition => line.new_lineno,
+                LineOrigin::Deletion => line.old_lineno,
+                LineOrigin::Context => line.new_lineno.or(line.old_lineno),
+            };
+            (*file_idx, line, lineno)
+        }
+        AnnotatedLine::ExpandedContext { gap_id, line_idx } => {
+            let Some(line) = app.get_expanded_line(gap_id, *line_idx) else {
+                return 0;
+            };
+            (gap_id.file_idx, line, line.new_lineno)
+        }
+        _ => return 0,
+    };
+    let blame = if blamed_file(app) == Some(file_idx) {
+        BLAME_COLUMN_WIDTH
+    } else {
+        0
+    };
+    let gutter = lineno.map_or(4, |n| n.to_string().len().max(4)) + 1;
+    // Cursor indicator, gutter, blame, origin marker and content
+    1 + gutter + blame + 2 + line.content.width()
+}
+
+/// Scroll positions, position and viewport of the diff scrollbar. With
+/// wrapping on they count the visual rows the lines wrap into, and the
+/// viewport is the rows the diff view filled this frame.
+fn diff_scrollbar_state(app: &mut App) -> ScrollbarState {
+    if !app.diff_state.wrap_lines {
+        return ScrollbarState::new(app.max_scroll_offset() + 1)
+            .position(app.diff_state.scroll_offset)
+            .viewport_content_length(app.diff_state.visible_line_count.max(1));
+    }
+    let width = app.diff_state.viewport_width;
+    if !app
+        .scrollbar_rows
+        .as_ref()
+        .is_some_and(|rows| rows.is_current(app, width))
+    {
+        app.scrollbar_rows = Some(VisualRows::measure(app, width));
+    }
+    let rows = app.scrollbar_rows.as_ref().expect("measured above");
+    let total = rows.start(app.total_lines());
+    let viewport = app.diff_row_to_annotation.len().max(1);
+    ScrollbarState::new(total.saturating_sub(viewport) + 1)
+        .position(rows.start(app.diff_state.scroll_offset))
+        .viewport_content_length(viewport)
+}
+
+/// Draw the diff scrollbar. The thumb follows `scroll_offset`, in visual rows
+/// when wrapping is on.
+pub fn render_diff_scrollbar(frame: &mut Frame, app: &mut App, area: Rect) {
+    if area.width < 2 || area.height < 3 {
+        return;
+    }
+    if app.scrollbar_ticks.is_none() {
+        app.scrollbar_ticks = Some(scrollbar_ticks(app));
+    }
+    let mut state = diff_scrollbar_state(app);
+    let app = &*app;
+
+    let track = area.inner(Margin {
+        vertical: 1,
+        horizontal: 0,
+    });
+    frame.render_stateful_widget(scrollbar(app), track, &mut state);
+
+    let visual_rows = app
+        .scrollbar_rows
+        .as_ref()
+        .filter(|_| app.diff_state.wrap_lines);
+    let row_of = |line: usize| visual_rows.map_or(line, |rows| rows.start(line));
+    let ticks: Vec<_> = app
+        .scrollbar_ticks
+        .iter()
+        .flatten()
+        .map(|(line, tick)| (row_of(*line), tick.clone()))
+        .collect();
+    let rows = tick_rows(&ticks, row_of(app.total_lines()), track.height as usize);
+    let x = track.right() - 1;
+    let buf = frame.buffer_mut();
+    for (row, tick) in rows.into_iter().enumerate() {
+        let (symbol, color) = match tick {
+            None => continue,
+            Some(ScrollbarTick::File) => ("─", app.theme.fg_dim),
+            Some(ScrollbarTick::Comment(comment_type)) => {
+                ("●", app.comment_type_color(comment_type))
+            }
+        };
+        buf[(x, track.y + row as u16)]
+            .set_symbol(symbol)
+            .set_style(Style::default().fg(color));
+    }
+}
+
+/// Draw the file list scrollbar when its `item_count` rows don't fit.
+pub fn render_file_list_scrollbar(frame: &mut Frame, app: &App, area: Rect, item_count: usize) {
+    let track = area.inner(Margin {
+        vertical: 1,
+        horizontal: 0,
+    });
+    let height = track.height as usize;
+    if area.width < 2 || height == 0 || item_count <= height {
+        return;
+    }
+    let mut state = ScrollbarState::new(item_count - height + 1)
+        .position(app.file_list_state.list_state.offset())
+        .viewport_content_length(height);
+    frame.render_stateful_widget(scrollbar(app), track, &mut state);
+}
+
+/// Thumb only, so the panel border stays visible as the track.
+fn scrollbar(app: &App) -> Scrollbar<'static> {
+    Scrollbar::new(ScrollbarOrientation::VerticalRight)
+        .begin_symbol(None)
+        .end_symbol(None)
+        .track_symbol(None)
+        .thumb_symbol("┃")
+        .thumb_style(Style::default().fg(app.theme.cursor_color))
+}
+
+/// Map ticks on `total` lines onto `height` rows. A comment wins over a file
+/// boundary sharing its row.
+fn tick_rows(
+    ticks: &[(usize, ScrollbarTick)],
+    total: usize,
+    height: usize,
+) -> Vec<Option<&ScrollbarTick>> {
+    let mut rows = vec![None; height];
+    if total == 0 || height == 0 {
+        return rows;
+    }
+    for (line, tick) in ticks.iter().filter(|(line, _)| *line < total) {
+        let row = &mut rows[(line * height / total).min(height - 1)];
+        if !matches!(row, Some(ScrollbarTick::Comment(_))) {
+            *row = Some(tick);
+        }
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::test_support::{build_app_with_files, make_file_with_hunks, make_hunk};
+    use crate::model::{Comment, LineSide};
+
+    #[test]
+    fn should_tick_files_and_the_first_row_of_each_comment() {
+        let files = vec![
+            make_file_with_hunks("a.rs", vec![make_hunk(1, 3)]),
+            make_file_with_hunks("b.rs", vec![make_hunk(1, 3)]),
+        ];
+        let mut app = build_app_with_files(files, 3);
+        app.session
+            .files
+            .get_mut(std::path::Path::new("b.rs"))
+            .unwrap()
+            .add_line_comment(
+                2,
+                Comment::new(
+                    "two\nlines".to_string(),
+                    CommentType::Issue,
+                    Some(LineSide::New),
+                ),
+            );
+        app.scrollbar_ticks = Some(Vec::new());
+        app.rebuild_annotations();
+        assert_eq!(app.scrollbar_ticks, None);
+
+        let ticks: Vec<ScrollbarTick> = scrollbar_ticks(&app)
+            .into_iter()
+            .map(|(_, tick)| tick)
+            .collect();
+        assert_eq!(
+            ticks,
+            [
+                ScrollbarTick::File,
+                ScrollbarTick::File,
+                ScrollbarTick::Comment(CommentType::Issue)
+            ]
+        );
+    }
+
+    #[test]
+    fn should_measure_the_thumb_in_wrapped_rows() {
+        let mut hunk = make_hunk(1, 12);
+        for line in [2, 5] {
+            hunk.lines[line].content = "x".repeat(200);
+        }
+        let mut app = build_app_with_files(vec![make_file_with_hunks("a.rs", vec![hunk])], 12);
+        app.diff_state.wrap_lines = true;
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(100, 40)).unwrap();
+        terminal
+            .draw(|frame| crate::ui::app_layout::render(frame, &mut app))
+            .unwrap();
+
+        // Every line fits on screen, so the renderer's row map has them all
+        let rows = app.scrollbar_rows.clone().unwrap();
+        let drawn = &app.diff_row_to_annotation;
+        assert!(drawn.len() > app.total_lines());
+        for line in 0..=app.total_lines() {
+            let above = drawn
+                .iter()
+                .filter(|&&drawn_line| drawn_line < line)
+                .count();
+            assert_eq!(rows.start(line), above, "line {line}");
+        }
+
+        app.diff_state.scroll_offset = 8;
+        terminal
+            .draw(|frame| crate::ui::app_layout::render(frame, &mut app))
+            .unwrap();
+        let total = rows.start(app.total_lines());
+        let viewport = app.diff_row_to_annotation.len();
+        assert_eq!(
+            diff_scrollbar_state(&mut app),
+            ScrollbarState::new(total - viewport + 1)
+                .position(rows.start(8))
+                .viewport_content_length(viewport)
+        );
+    }
+
+    #[test]
+    fn should_map_ticks_onto_rows() {
+        let ticks = [
+            (0, ScrollbarTick::File),
+            (50, ScrollbarTick::File),
+            (52, ScrollbarTick::Comment(CommentType::Issue)),
+            (90, ScrollbarTick::Comment(CommentType::Note)),
+        ];
+
+        let rows = tick_rows(&ticks, 100, 10);
+
+        assert_eq!(rows[0], Some(&ScrollbarTick::File));
+        assert_eq!(rows[1], None);
+        assert_eq!(rows[5], Some(&ScrollbarTick::Comment(CommentType::Issue)));
+        assert_eq!(rows[9], Some(&ScrollbarTick::Comment(CommentType::Note)));
+        assert!(tick_rows(&ticks, 0, 10).iter().all(Option::is_none));
+    }
+}
@@ -0,0 +1,138 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+};
+
+use crate::app::App;
+use crate::model::{ReviewSession, SessionDiffSource};
+use crate::ui::styles;
+
+pub fn render_session_browser(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = centered_rect(70, 70, frame.area());
+
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Sessions - Enter:load  d:delete  n:new  Esc:close ")
+        .borders(Borders::ALL)
+        .style(styles::popup_style(theme))
+        .border_style(styles::border_style(theme, true));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    if app.session_browser.entries.is_empty() {
+        let empty = Paragraph::new(vec![
+            Line::from(""),
+            Line::from(Span::styled(
+                "  No saved sessions for this repository",
+                styles::dim_style(theme),
+            )),
+        ])
+        .style(styles::popup_style(theme));
+        frame.render_widget(empty, inner);
+        return;
+    }
+
+    let items: Vec<ListItem> = app
+        .session_browser
+        .entries
+        .iter()
+        .map(|entry| {
+            let is_current = entry.session.id == app.session.id;
+            let mut title = vec![
+                Span::styled(
+                    entry
+                        .session
+                        .branch_name
+                        .as_deref()
+                        .unwrap_or("detached")
+                        .to_string(),
+                    styles::branch_style(theme),
+                ),
+                Span::raw("  "),
+                Span::styled(source_label(&entry.session), styles::hash_style(theme)),
+            ];
+            if is_current {
+                title.push(Span::styled(
+                    "  (current)",
+                    Style::default().add_modifier(Modifier::BOLD),
+                ));
+            }
+            if entry.stale {
+                title.push(Span::styled("  (stale)", styles::pending_style(theme)));
+            }
+
+            ListItem::new(vec![
+                Line::from(title),
+                Line::from(Span::styled(
+                    details_label(&entry.session),
+                    styles::dim_style(theme),
+                )),
+            ])
+        })
+        .collect();
+
+    let list = List::new(items)
+        .highlight_style(styles::selected_style(theme))
+        .highlight_symbol("> ");
+
+    let mut state = ListState::default();
+    state.select(Some(app.session_browser.selected));
+    frame.render_stateful_widget(list, inner, &mut state);
+}
+
+fn short_id(id: &str) -> &str {
+    &id[..7.min(id.len())]
+}
+
+fn source_label(session: &ReviewSession) -> String {
+    let commits = match session.commit_range.as_deref() {
+        Some([only]) => format!("commit {}", short_id(only)),
+        Some([first, .., last]) => format!("{}..{}", short_id(first), short_id(last)),
+        _ => format!("commits @ {}", short_id(&session.base_commit)),
+    };
+
+    match session.diff_source {
+        SessionDiffSource::WorkingTree => format!("worktree @ {}", short_id(&session.base_commit)),
+        SessionDiffSource::Staged => "staged".to_string(),
+        SessionDiffSource::Unstaged => "unstaged".to_string(),
+        SessionDiffSource::StagedAndUnstaged => "staged + unstaged".to_string(),
+        SessionDiffSource::CommitRange => commits,
+        SessionDiffSource::WorkingTreeAndCommits | SessionDiffSource::StagedUnstagedAndCommits => {
+            format!("staged + unstaged + {commits}")
+        }
+    }
+}
+
+fn details_label(session: &ReviewSession) -> String {
+    let mut label = format!(
+        "    created {}  updated {}  {} comments  {}/{} reviewed",
+        session.created_at.format("%Y-%m-%d %H:%M"),
+        session.updated_at.format("%Y-%m-%d %H:%M"),
+        session.comment_count(),
+        session.reviewed_count(),
+        session.files.len()
+    );
+    let authors: Vec<String> = session
+        .comment_counts_by_author()
+        .into_iter()
+        .map(|(author, count)| format!("{author} {count}"))
+        .collect();
+    if !authors.is_empty() {
+        label.push_str(&format!("  ({})", authors.join(", ")));
+    }
+    label
+}
+
+pub(crate) fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::vertical([Constraint::Percentage(percent_y)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Percentage(percent_x)]).flex(Flex::Center);
+    let [area] = vertical.areas(area);
+    let [area] = horizontal.areas(area);
+    area
+}
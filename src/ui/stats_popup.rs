@@ -0,0 +1,137 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Flex, Layout},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::app::{App, DiffSummary};
+use crate::model::FileStatus;
+use crate::theme::Theme;
+use crate::ui::styles;
+
+/// Width of the `:stats` popup, including borders.
+const POPUP_WIDTH: u16 = 36;
+
+/// Totals over the diff, closed by the next key press.
+pub fn render_stats_popup(frame: &mut Frame, app: &App) {
+    let Some(summary) = &app.stats_popup else {
+        return;
+    };
+    let lines = summary_lines(&app.theme, summary);
+
+    let area = frame.area();
+    let width = POPUP_WIDTH.min(area.width.saturating_sub(4));
+    let [area] = Layout::horizontal([Constraint::Length(width)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::vertical([Constraint::Length(lines.len() as u16 + 2)])
+        .flex(Flex::Center)
+        .areas(area);
+
+    let block = Block::default()
+        .title(" Diff stats ")
+        .borders(Borders::ALL)
+        .style(styles::popup_style(&app.theme))
+        .border_style(styles::border_style(&app.theme, true));
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .style(styles::popup_style(&app.theme)),
+        area,
+    );
+}
+
+/// `N files changed, +A -D`, hunk review progress, then one row per file status.
+fn summary_lines(theme: &Theme, summary: &DiffSummary) -> Vec<Line<'static>> {
+    let noun = if summary.files == 1 { "file" } else { "files" };
+    let mut lines = vec![
+        Line::from(vec![
+            Span::raw(format!(" {} {noun} changed, ", summary.files)),
+            Span::styled(
+                format!("+{}", summary.additions),
+                Style::default().fg(theme.diff_add),
+            ),
+            Span::raw(" "),
+            Span::styled(
+                format!("-{}", summary.deletions),
+                Style::default().fg(theme.diff_del),
+            ),
+        ]),
+        Line::from(vec![
+            Span::raw(" "),
+            Span::styled(
+                format!("{}/{}", summary.reviewed_hunks, summary.hunks),
+                if summary.reviewed_hunks == summary.hunks {
+                    styles::reviewed_style(theme)
+                } else {
+                    styles::pending_style(theme)
+                },
+            ),
+            Span::raw(" hunks reviewed"),
+        ]),
+        Line::default(),
+    ];
+    for (status, count) in &summary.by_status {
+        let label = match status {
+            FileStatus::Added => "Added",
+            FileStatus::Modified => "Modified",
+            FileStatus::Deleted => "Deleted",
+            FileStatus::Renamed => "Renamed",
+            FileStatus::Copied => "Copied",
+        };
+        let style = if *count == 0 {
+            styles::dim_style(theme)
+        } else {
+            styles::file_status_style(theme, status.as_char())
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!(" {} {label:<10}", status.as_char()), style),
+            Span::styled(format!("{count:>5}"), style),
+        ]));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_list_totals_then_one_row_per_status() {
+        let summary = DiffSummary {
+            files: 3,
+            additions: 12,
+            deletions: 4,
+            by_status: vec![
+                (FileStatus::Added, 1),
+                (FileStatus::Modified, 2),
+                (FileStatus::Deleted, 0),
+                (FileStatus::Renamed, 0),
+            ],
+            hunks: 5,
+            reviewed_hunks: 2,
+        };
+
+        let text: Vec<String> = summary_lines(&Theme::default(), &summary)
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+
+        assert_eq!(
+            text,
+            [
+                " 3 files changed, +12 -4",
+                " 2/5 hunks reviewed",
+                "",
+                " A Added         1",
+                " M Modified      2",
+                " D Deleted       0",
+                " R Renamed       0",
+            ]
+        );
+    }
+}
@@ -6,7 +6,9 @@ use ratatui::{
     widgets::{Block, Paragraph},
 };
 
-use crate::app::{App, DiffSource, InputMode, Message, MessageType};
+use unicode_width::UnicodeWidthStr;
+
+use crate::app::{AnnotatedLine, App, InputMode, Message, MessageType};
 use crate::theme::Theme;
 use crate::ui::styles;
 
@@ -48,6 +50,32 @@ pub fn build_right_aligned_spans<'a>(
     left_spans
 }
 
+/// Cells of the review progress bar in the status bar.
+const PROGRESS_BAR_WIDTH: usize = 10;
+
+/// A bar of reviewed files followed by "N/M reviewed", or nothing without
+/// files. Cells only fill up completely once every file is reviewed.
+fn progress_spans(theme: &Theme, reviewed: usize, total: usize) -> Vec<Span<'static>> {
+    if total == 0 {
+        return Vec::new();
+    }
+    let filled = reviewed.min(total) * PROGRESS_BAR_WIDTH / total;
+    let fraction_style = if reviewed == total {
+        styles::reviewed_style(theme)
+    } else {
+        styles::pending_style(theme)
+    };
+    vec![
+        Span::raw(" "),
+        Span::styled("█".repeat(filled), Style::default().fg(theme.reviewed)),
+        Span::styled(
+            "░".repeat(PROGRESS_BAR_WIDTH - filled),
+            Style::default().fg(theme.pending),
+        ),
+        Span::styled(format!(" {reviewed}/{total} reviewed "), fraction_style),
+    ]
+}
+
 pub fn render_header(frame: &mut Frame, app: &App, area: Rect) {
     let theme = &app.theme;
     let vcs_type = &app.vcs_info.vcs_type;
@@ -56,55 +84,10 @@ pub fn render_header(frame: &mut Frame, app: &App, area: Rect) {
     let title = " tuicr - Code Review ".to_string();
     let vcs_info = format!("[{vcs_type}:{branch}] ");
 
-    // Show diff source info
-    let source_info = match &app.diff_source {
-        DiffSource::WorkingTree => String::new(),
-        DiffSource::Staged => "[staged] ".to_string(),
-        DiffSource::Unstaged => "[unstaged] ".to_string(),
-        DiffSource::StagedAndUnstaged => "[staged + unstaged] ".to_string(),
-        DiffSource::CommitRange(commits) => {
-            if commits.len() == 1 {
-                format!("[commit {}] ", &commits[0][..7.min(commits[0].len())])
-            } else {
-                match app.commit_selection_range {
-                    Some((start, end)) if end - start + 1 < app.review_commits.len() => {
-                        format!(
-                            "[{}/{} commits] ",
-                            end - start + 1,
-                            app.review_commits.len()
-                        )
-                    }
-                    _ => format!("[{} commits] ", commits.len()),
-                }
-            }
-        }
-        DiffSource::WorkingTreeAndCommits(commits)
-        | DiffSource::StagedUnstagedAndCommits(commits) => {
-            if commits.len() == 1 {
-                format!(
-                    "[staged + unstaged + commit {}] ",
-                    &commits[0][..7.min(commits[0].len())]
-                )
-            } else {
-                format!("[staged + unstaged + {} commits] ", commits.len())
-            }
-        }
-        DiffSource::PullRequest {
-            base_ref,
-            head_commit,
-            commit_count,
-            ..
-        } => {
-            let short_head = &head_commit[..7.min(head_commit.len())];
-            format!("[pr {base_ref}..{short_head} ({commit_count} commits)] ")
-        }
-    };
-
     let progress = format!("{}/{} reviewed ", app.reviewed_count(), app.file_count());
 
     let title_span = Span::styled(title, styles::header_style(theme));
     let vcs_span = Span::styled(vcs_info, Style::default().fg(theme.fg_secondary));
-    let source_span = Span::styled(source_info, Style::default().fg(theme.diff_hunk_header));
     let progress_span = Span::styled(
         progress,
         if app.reviewed_count() == app.file_count() {
@@ -114,10 +97,20 @@ pub fn render_header(frame: &mut Frame, app: &App, area: Rect) {
         },
     );
 
+    let filter_info = match app.path_filter.len() {
+        0 => String::new(),
+        1 => "(filtered: 1 path) ".to_string(),
+        n => format!("(filtered: {n} paths) "),
+    };
+    let filter_span = Span::styled(filter_info, styles::dim_style(theme));
+
     let (update_span, update_width) = if let Some(ref info) = app.update_info {
         if info.update_available {
-            let text = format!(" v{} available ", info.latest_version);
-            let width = text.len();
+            let text = format!(
+                " v{} → v{} available ",
+                info.current_version, info.latest_version
+            );
+            let width = text.width();
             (
                 Span::styled(
                     text,
@@ -148,9 +141,25 @@ pub fn render_header(frame: &mut Frame, app: &App, area: Rect) {
         (Span::raw(""), 0)
     };
 
-    let left_spans = vec![title_span, vcs_span, source_span, progress_span];
-    let left_width: usize = left_spans.iter().map(|s| s.content.len()).sum();
+    // The source label gets whatever width the rest of the header leaves
     let total_width = area.width as usize;
+    let fixed_width: usize = [&title_span, &vcs_span, &progress_span, &filter_span]
+        .iter()
+        .map(|s| s.content.width())
+        .sum::<usize>()
+        + update_width;
+    let source_info = format!("[{}] ", app.diff_source_label());
+    let source_info = truncate_to_width(&source_info, total_width.saturating_sub(fixed_width));
+    let source_span = Span::styled(source_info, Style::default().fg(theme.diff_hunk_header));
+
+    let left_spans = vec![
+        title_span,
+        vcs_span,
+        source_span,
+        progress_span,
+        filter_span,
+    ];
+    let left_width: usize = left_spans.iter().map(|s| s.content.width()).sum();
     let padding_width = total_width.saturating_sub(left_width + update_width);
 
     let mut spans = left_spans;
@@ -168,6 +177,235 @@ pub fn render_header(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(header, area);
 }
 
+/// Cut `text` to `max_width` columns, ending in `…` when shortened. Labels
+/// too narrow to say anything are dropped.
+fn truncate_to_width(text: &str, max_width: usize) -> String {
+    const MIN_WIDTH: usize = 6;
+    if text.width() <= max_width {
+        return text.to_string();
+    }
+    if max_width < MIN_WIDTH {
+        return String::new();
+    }
+    let mut truncated = String::new();
+    let mut width = 0;
+    for c in text.chars() {
+        let c_width = c.to_string().width();
+        if width + c_width + 2 > max_width {
+            break;
+        }
+        truncated.push(c);
+        width += c_width;
+    }
+    truncated.push_str("… ");
+    truncated
+}
+
+/// Template reproducing the built-in status bar; the `status_line` config
+/// key replaces it.
+pub const DEFAULT_STATUS_LINE: &str = "{mode}{hints}{whitespace}{filter}{dirty}";
+
+/// Placeholders understood in a `status_line` template.
+pub const STATUS_LINE_PLACEHOLDERS: &[&str] = &[
+    "mode",
+    "hints",
+    "vcs",
+    "branch",
+    "head",
+    "file",
+    "line",
+    "old_line",
+    "new_line",
+    "reviewed",
+    "total",
+    "reviewed_hunks",
+    "hunks",
+    "comments",
+    "source",
+    "search",
+    "whitespace",
+    "filter",
+    "dirty",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Segment<'a> {
+    Text(&'a str),
+    Placeholder(&'a str),
+}
+
+/// Split a template into literal text and `{name}` placeholders. Braces not
+/// enclosing a lowercase name stay literal.
+fn parse_status_line(template: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        let name = rest[open + 1..]
+            .find('}')
+            .map(|close| &rest[open + 1..open + 1 + close])
+            .filter(|name| {
+                !name.is_empty() && name.chars().all(|c| c.is_ascii_lowercase() || c == '_')
+            });
+        let Some(name) = name else {
+            segments.push(Segment::Text(&rest[..=open]));
+            rest = &rest[open + 1..];
+            continue;
+        };
+        if open > 0 {
+            segments.push(Segment::Text(&rest[..open]));
+        }
+        segments.push(Segment::Placeholder(name));
+        rest = &rest[open + name.len() + 2..];
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Text(rest));
+    }
+    segments
+}
+
+/// Placeholders in `template` that the status bar doesn't know.
+pub fn unknown_placeholders(template: &str) -> Vec<&str> {
+    parse_status_line(template)
+        .into_iter()
+        .filter_map(|segment| match segment {
+            Segment::Placeholder(name) if !STATUS_LINE_PLACEHOLDERS.contains(&name) => Some(name),
+            _ => None,
+        })
+        .collect()
+}
+
+fn mode_label(app: &App) -> String {
+    match app.input_mode {
+        InputMode::Normal => {
+            if let Some(count) = app.pending_count {
+                format!(" NORMAL {count} ")
+            } else {
+                " NORMAL ".to_string()
+            }
+        }
+        InputMode::Command => " COMMAND ".to_string(),
+        InputMode::Search => " SEARCH ".to_string(),
+        InputMode::Comment => " COMMENT ".to_string(),
+        InputMode::Help => " HELP ".to_string(),
+        InputMode::Confirm => " CONFIRM ".to_string(),
+        InputMode::CommitSelect => " SELECT ".to_string(),
+        InputMode::SessionBrowser => " SESSIONS ".to_string(),
+        InputMode::BranchPicker => " BRANCHES ".to_string(),
+        InputMode::VisualSelect => {
+            if let Some((range, _)) = app.get_visual_selection() {
+                if range.is_single() {
+                    format!(" VISUAL L{} ", range.start)
+                } else {
+                    format!(" VISUAL L{}-L{} ", range.start, range.end)
+                }
+            } else {
+                " VISUAL ".to_string()
+            }
+        }
+    }
+}
+
+fn mode_hints(mode: InputMode) -> &'static str {
+    match mode {
+        InputMode::Normal => {
+            " j/k:scroll  {/}:file  r:reviewed  c:comment  ;c:review  V:visual  /:search  ?:help  :q:quit "
+        }
+        InputMode::Command => " Enter:execute  Esc:cancel ",
+        InputMode::Search => " Enter:search  Esc:cancel ",
+        InputMode::Comment => " Ctrl-S:save  Esc:cancel ",
+        InputMode::Help => " q/?/Esc:close ",
+        InputMode::Confirm => " y:yes  n:no ",
+        InputMode::CommitSelect => " j/k:navigate  Space:select  Enter:confirm  Esc:back  q:quit ",
+        InputMode::VisualSelect => " j/k:extend  c/Enter:comment  Esc/V:cancel ",
+        InputMode::SessionBrowser => " j/k:navigate  Enter:load  d:delete  n:new  Esc:close ",
+        InputMode::BranchPicker => " j/k:navigate  Enter:review  Esc:close ",
+    }
+}
+
+/// Old and new line numbers of the diff line under the cursor.
+fn cursor_line_numbers(app: &App) -> (Option<u32>, Option<u32>) {
+    match app.line_annotations.get(app.diff_state.cursor_line) {
+        Some(AnnotatedLine::DiffLine {
+            old_lineno,
+            new_lineno,
+            ..
+        })
+        | Some(AnnotatedLine::SideBySideLine {
+            old_lineno,
+            new_lineno,
+            ..
+        }) => (*old_lineno, *new_lineno),
+        _ => (None, None),
+    }
+}
+
+/// The span for placeholder `name`, or `None` if it isn't one.
+fn placeholder_span(app: &App, name: &str) -> Option<Span<'static>> {
+    let theme = &app.theme;
+    let number = |n: Option<u32>| n.map(|n| n.to_string()).unwrap_or_default();
+    let span = match name {
+        "mode" => Span::styled(mode_label(app), styles::mode_style(theme)),
+        "hints" => Span::styled(
+            mode_hints(app.input_mode),
+            Style::default().fg(theme.fg_secondary),
+        ),
+        "vcs" => Span::raw(app.vcs_info.vcs_type.to_string()),
+        "branch" => Span::raw(
+            app.vcs_info
+                .branch_name
+                .clone()
+                .unwrap_or_else(|| "detached".to_string()),
+        ),
+        "head" => Span::raw(app.vcs_info.head_commit.chars().take(7).collect::<String>()),
+        "file" => Span::raw(
+            app.current_file_path()
+                .map(|path| path.display().to_string())
+                .unwrap_or_default(),
+        ),
+        "line" => Span::raw(number(app.get_line_at_cursor().map(|(line, _)| line))),
+        "old_line" => Span::raw(number(cursor_line_numbers(app).0)),
+        "new_line" => Span::raw(number(cursor_line_numbers(app).1)),
+        "reviewed" => Span::raw(app.reviewed_count().to_string()),
+        "total" => Span::raw(app.file_count().to_string()),
+        "reviewed_hunks" => Span::raw(app.hunk_progress().0.to_string()),
+        "hunks" => Span::raw(app.hunk_progress().1.to_string()),
+        "comments" => Span::raw(app.session.comment_count().to_string()),
+        "source" => Span::raw(app.diff_source_label()),
+        "search" => Span::raw(app.last_search_pattern.clone().unwrap_or_default()),
+        "whitespace" if app.ignore_whitespace => Span::styled(
+            " [ignoring whitespace] ",
+            Style::default().fg(theme.fg_secondary),
+        ),
+        "filter" => match &app.file_filter {
+            Some(filter) => Span::styled(
+                format!(" [filter: {}] ", filter.pattern),
+                Style::default().fg(theme.fg_secondary),
+            ),
+            None => Span::raw(""),
+        },
+        "dirty" if app.dirty => Span::styled(" [modified] ", Style::default().fg(theme.pending)),
+        "dirty" if app.show_saved_indicator() => {
+            Span::styled(" [saved] ", Style::default().fg(theme.reviewed))
+        }
+        "whitespace" | "dirty" => Span::raw(""),
+        _ => return None,
+    };
+    Some(span)
+}
+
+/// Fill in `template`. Unknown placeholders are shown as typed.
+fn status_line_spans(app: &App, template: &str) -> Vec<Span<'static>> {
+    parse_status_line(template)
+        .into_iter()
+        .map(|segment| match segment {
+            Segment::Text(text) => Span::raw(text.to_string()),
+            Segment::Placeholder(name) => {
+                placeholder_span(app, name).unwrap_or_else(|| Span::raw(format!("{{{name}}}")))
+            }
+        })
+        .collect()
+}
+
 pub fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
     let theme = &app.theme;
 
@@ -189,64 +427,21 @@ pub fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
             Style::default().fg(theme.fg_primary),
         )]
     } else {
-        let mode_str = match app.input_mode {
-            InputMode::Normal => {
-                if let Some(count) = app.pending_count {
-                    format!(" NORMAL {count} ")
-                } else {
-                    " NORMAL ".to_string()
-                }
-            }
-            InputMode::Command => " COMMAND ".to_string(),
-            InputMode::Search => " SEARCH ".to_string(),
-            InputMode::Comment => " COMMENT ".to_string(),
-            InputMode::Help => " HELP ".to_string(),
-            InputMode::Confirm => " CONFIRM ".to_string(),
-            InputMode::CommitSelect => " SELECT ".to_string(),
-            InputMode::VisualSelect => {
-                if let Some((range, _)) = app.get_visual_selection() {
-                    if range.is_single() {
-                        format!(" VISUAL L{} ", range.start)
-                    } else {
-                        format!(" VISUAL L{}-L{} ", range.start, range.end)
-                    }
-                } else {
-                    " VISUAL ".to_string()
-                }
-            }
-        };
-
-        let mode_span = Span::styled(mode_str, styles::mode_style(theme));
-
-        let hints = match app.input_mode {
-            InputMode::Normal => {
-                " j/k:scroll  {/}:file  r:reviewed  c:comment  ;c:review  V:visual  /:search  ?:help  :q:quit "
-            }
-            InputMode::Command => " Enter:execute  Esc:cancel ",
-            InputMode::Search => " Enter:search  Esc:cancel ",
-            InputMode::Comment => " Ctrl-S:save  Esc:cancel ",
-            InputMode::Help => " q/?/Esc:close ",
-            InputMode::Confirm => " y:yes  n:no ",
-            InputMode::CommitSelect => {
-                " j/k:navigate  Space:select  Enter:confirm  Esc:back  q:quit "
-            }
-            InputMode::VisualSelect => " j/k:extend  c/Enter:comment  Esc/V:cancel ",
-        };
-        let hints_span = Span::styled(hints, Style::default().fg(theme.fg_secondary));
-
-        let dirty_indicator = if app.dirty {
-            Span::styled(" [modified] ", Style::default().fg(theme.pending))
-        } else {
-            Span::raw("")
-        };
-
-        vec![mode_span, hints_span, dirty_indicator]
+        status_line_spans(
+            app,
+            app.status_line.as_deref().unwrap_or(DEFAULT_STATUS_LINE),
+        )
     };
 
+    // Review progress takes the right edge, with the message just before it
+    let progress = progress_spans(theme, app.reviewed_count(), app.file_count());
+    let progress_width: usize = progress.iter().map(|s| s.content.width()).sum();
+
     // Build message span and create right-aligned layout
     let (message_span, message_width) = build_message_span(app.message.as_ref(), theme);
-    let total_width = area.width as usize;
-    let spans = build_right_aligned_spans(left_spans, message_span, message_width, total_width);
+    let total_width = (area.width as usize).saturating_sub(progress_width);
+    let mut spans = build_right_aligned_spans(left_spans, message_span, message_width, total_width);
+    spans.extend(progress);
 
     let line = Line::from(spans);
 
@@ -260,6 +455,8 @@ pub fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::app::test_support::{build_app_with_files, make_file_with_hunks, make_hunk};
+    use ratatui::buffer::Buffer;
 
     fn test_message(message_type: MessageType) -> Message {
         Message {
@@ -277,6 +474,96 @@ mod tests {
         assert_eq!(width, " hello ".len());
     }
 
+    #[test]
+    fn should_draw_review_progress_as_a_bar_and_fraction() {
+        let theme = Theme::dark();
+        let text = |reviewed, total| -> String {
+            progress_spans(&theme, reviewed, total)
+                .iter()
+                .map(|span| span.content.as_ref())
+                .collect()
+        };
+
+        assert_eq!(text(7, 20), " ███░░░░░░░ 7/20 reviewed ");
+        assert_eq!(text(19, 20), " █████████░ 19/20 reviewed ");
+        assert_eq!(text(3, 3), " ██████████ 3/3 reviewed ");
+        assert_eq!(text(0, 0), "");
+
+        let spans = progress_spans(&theme, 1, 2);
+        assert_eq!(spans[1].style.fg, Some(theme.reviewed));
+        assert_eq!(spans[2].style.fg, Some(theme.pending));
+    }
+
+    #[test]
+    fn should_truncate_labels_to_the_available_width() {
+        assert_eq!(truncate_to_width("[Working Tree] ", 20), "[Working Tree] ");
+        assert_eq!(truncate_to_width("[Working Tree] ", 10), "[Working… ");
+        assert_eq!(truncate_to_width("[Working Tree] ", 4), "");
+    }
+
+    fn render_status_line(app: &App, width: u16) -> ratatui::buffer::Buffer {
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(width, 1)).unwrap();
+        terminal
+            .draw(|frame| render_status_bar(frame, app, frame.area()))
+            .unwrap();
+        terminal.backend().buffer().clone()
+    }
+
+    #[test]
+    fn should_render_the_built_in_status_bar_from_the_default_template() {
+        let mut app =
+            build_app_with_files(vec![make_file_with_hunks("a.rs", vec![make_hunk(1, 3)])], 3);
+        app.ignore_whitespace = true;
+        let theme = Theme::dark();
+        let mode = " NORMAL ";
+        let hints = " j/k:scroll  {/}:file  r:reviewed  c:comment  ;c:review  V:visual  /:search  ?:help  :q:quit ";
+        let flag = " [ignoring whitespace] ";
+        let bar = "░".repeat(10);
+        let fraction = " 0/1 reviewed ";
+        let padding = " ".repeat(101 - flag.len());
+
+        let mut expected =
+            Buffer::with_lines([format!("{mode}{hints}{flag}{padding}{bar}{fraction}")]);
+        expected.set_style(expected.area, styles::status_bar_style(&theme));
+        let mut x = 0;
+        for (text, style) in [
+            (mode, styles::mode_style(&theme)),
+            (hints, Style::default().fg(theme.fg_secondary)),
+            (flag, Style::default().fg(theme.fg_secondary)),
+            (&padding, styles::status_bar_style(&theme)),
+            (&bar, Style::default().fg(theme.pending)),
+            (fraction, styles::pending_style(&theme)),
+        ] {
+            let width = text.width() as u16;
+            expected.set_style(Rect::new(x, 0, width, 1), style);
+            x += width;
+        }
+
+        assert_eq!(render_status_line(&app, expected.area.width), expected);
+        app.status_line = Some(DEFAULT_STATUS_LINE.to_string());
+        assert_eq!(render_status_line(&app, expected.area.width), expected);
+    }
+
+    #[test]
+    fn should_fill_in_status_line_placeholders() {
+        let mut app =
+            build_app_with_files(vec![make_file_with_hunks("a.rs", vec![make_hunk(1, 3)])], 3);
+        app.status_line =
+            Some("{mode}{branch} {file}:{line} {reviewed}/{total} {x} {oops".to_string());
+        app.diff_state.cursor_line = app
+            .line_annotations
+            .iter()
+            .position(|line| matches!(line, AnnotatedLine::DiffLine { .. }))
+            .unwrap();
+
+        let buffer = render_status_line(&app, 60);
+        let text: String = buffer.content().iter().map(|cell| cell.symbol()).collect();
+
+        assert!(text.starts_with(" NORMAL main a.rs:1 0/1 {x} {oops "));
+        assert_eq!(unknown_placeholders("{mode} {x} {} {file} {y}"), ["x", "y"]);
+    }
+
     #[test]
     fn should_return_empty_span_when_message_is_none() {
         let theme = Theme::dark();
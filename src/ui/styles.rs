@@ -1,5 +1,6 @@
 use ratatui::style::{Color, Modifier, Style};
 
+use crate::model::LineOrigin;
 use crate::theme::Theme;
 
 pub fn header_style(theme: &Theme) -> Style {
@@ -10,6 +11,9 @@ pub fn header_style(theme: &Theme) -> Style {
 }
 
 pub fn selected_style(theme: &Theme) -> Style {
+    if theme.monochrome {
+        return Style::default().add_modifier(Modifier::REVERSED);
+    }
     Style::default().bg(theme.bg_highlight).fg(theme.fg_primary)
 }
 
@@ -18,21 +22,57 @@ pub fn dim_style(theme: &Theme) -> Style {
 }
 
 pub fn diff_add_style(theme: &Theme) -> Style {
+    if theme.monochrome {
+        return Style::default().add_modifier(Modifier::BOLD);
+    }
     Style::default().fg(theme.diff_add).bg(theme.diff_add_bg)
 }
 
 pub fn diff_del_style(theme: &Theme) -> Style {
+    if theme.monochrome {
+        return Style::default().add_modifier(Modifier::DIM);
+    }
     Style::default().fg(theme.diff_del).bg(theme.diff_del_bg)
 }
 
+/// Added or deleted line that belongs to a moved block.
+pub fn diff_moved_style(theme: &Theme, origin: LineOrigin) -> Style {
+    if theme.monochrome {
+        let style = match origin {
+            LineOrigin::Deletion => diff_del_style(theme),
+            _ => diff_add_style(theme),
+        };
+        return style.add_modifier(Modifier::ITALIC);
+    }
+    match origin {
+        LineOrigin::Deletion => Style::default()
+            .fg(theme.diff_moved_from)
+            .bg(theme.diff_del_bg),
+        _ => Style::default()
+            .fg(theme.diff_moved_to)
+            .bg(theme.diff_add_bg),
+    }
+}
+
 pub fn diff_context_style(theme: &Theme) -> Style {
     Style::default().fg(theme.diff_context)
 }
 
+/// First line of the commit message pseudo-file.
+pub fn commit_summary_style(theme: &Theme) -> Style {
+    diff_context_style(theme).add_modifier(Modifier::BOLD)
+}
+
 pub fn expanded_context_style(theme: &Theme) -> Style {
     Style::default().fg(theme.expanded_context_fg)
 }
 
+pub fn conflict_marker_style(theme: &Theme) -> Style {
+    Style::default()
+        .fg(theme.conflict_marker)
+        .add_modifier(Modifier::BOLD)
+}
+
 pub fn diff_hunk_header_style(theme: &Theme) -> Style {
     Style::default()
         .fg(theme.diff_hunk_header)
@@ -76,6 +116,9 @@ pub fn status_bar_style(theme: &Theme) -> Style {
 }
 
 pub fn mode_style(theme: &Theme) -> Style {
+    if theme.monochrome {
+        return Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD);
+    }
     Style::default()
         .fg(theme.mode_fg)
         .bg(theme.mode_bg)
@@ -117,7 +160,16 @@ pub fn comment_border_style(_theme: &Theme, color: Color) -> Style {
     Style::default().fg(color)
 }
 
+pub fn comment_author_style(theme: &Theme) -> Style {
+    Style::default()
+        .fg(theme.fg_secondary)
+        .add_modifier(Modifier::ITALIC)
+}
+
 pub fn visual_selection_style(theme: &Theme) -> Style {
+    if theme.monochrome {
+        return Style::default().add_modifier(Modifier::REVERSED);
+    }
     Style::default().bg(theme.bg_highlight)
 }
 
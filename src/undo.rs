@@ -0,0 +1,416 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
+
+use crate::model::{Comment, CommentType, HunkKey, ReviewSession};
+
+/// Maximum number of entries kept on the undo stack.
+pub const MAX_UNDO_ENTRIES: usize = 100;
+
+/// Where a comment lives inside a review session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommentAnchor {
+    Review,
+    File(PathBuf),
+    Line(PathBuf, u32),
+}
+
+/// Saved comment and reviewed state for a single file.
+#[derive(Debug, Clone)]
+pub struct FileSnapshot {
+    pub reviewed: bool,
+    pub reviewed_hunks: HashSet<HunkKey>,
+    pub file_comments: Vec<Comment>,
+    pub line_comments: HashMap<u32, Vec<Comment>>,
+}
+
+/// A reversible change to the review session. Applying an action performs it
+/// and returns the action that reverts it.
+#[derive(Debug, Clone)]
+pub enum UndoAction {
+    /// Remove the comment with the given id from `anchor`.
+    RemoveComment { anchor: CommentAnchor, id: String },
+    /// Insert a comment at `index` within `anchor`.
+    InsertComment {
+        anchor: CommentAnchor,
+        index: usize,
+        comment: Box<Comment>,
+    },
+    /// Set the content and type of the comment with the given id.
+    SetComment {
+        id: String,
+        content: String,
+        comment_type: CommentType,
+    },
+    /// Set the reviewed flag of a file.
+    SetReviewed { path: PathBuf, reviewed: bool },
+    /// Set the reviewed flag of a hunk, and of its file, which follows
+    /// whether all of its hunks are reviewed.
+    SetHunkReviewed {
+        path: PathBuf,
+        hunk: HunkKey,
+        reviewed: bool,
+        file_reviewed: bool,
+    },
+    /// Replace all comments and reviewed flags with a snapshot.
+    RestoreAll {
+        review_comments: Vec<Comment>,
+        files: HashMap<PathBuf, FileSnapshot>,
+    },
+}
+
+impl UndoAction {
+    /// Snapshot every comment and reviewed flag in the session.
+    pub fn snapshot(session: &ReviewSession) -> Self {
+        let files = session
+            .files
+            .iter()
+            .map(|(path, review)| {
+                (
+                    path.clone(),
+                    FileSnapshot {
+                        reviewed: review.reviewed,
+                        reviewed_hunks: review.reviewed_hunks.clone(),
+                        file_comments: review.file_comments.clone(),
+                        line_comments: review.line_comments.clone(),
+                    },
+                )
+            })
+            .collect();
+        UndoAction::RestoreAll {
+            review_comments: session.review_comments.clone(),
+            files,
+        }
+    }
+
+    /// Apply the action to the session and return its inverse.
+    /// Returns None if the target no longer exists.
+    pub fn apply(self, session: &mut ReviewSession) -> Option<UndoAction> {
+        match self {
+            UndoAction::RemoveComment { anchor, id } => {
+                let comments = comments_mut(session, &anchor)?;
+                let index = comments.iter().position(|c| c.id == id)?;
+                let comment = comments.remove(index);
+                if let CommentAnchor::Line(path, line) = &anchor
+                    && let Some(review) = session.files.get_mut(path)
+                    && review.line_comments.get(line).is_some_and(|v| v.is_empty())
+                {
+                    review.line_comments.remove(line);
+                }
+                Some(UndoAction::InsertComment {
+                    anchor,
+                    index,
+                    comment: Box::new(comment),
+                })
+            }
+            UndoAction::InsertComment {
+                anchor,
+                index,
+                comment,
+            } => {
+                let id = comment.id.clone();
+                let comments = match &anchor {
+                    CommentAnchor::Line(path, line) => session
+                        .files
+                        .get_mut(path)?
+                        .line_comments
+                        .entry(*line)
+                        .or_default(),
+                    _ => comments_mut(session, &anchor)?,
+                };
+                comments.insert(index.min(comments.len()), *comment);
+                Some(UndoAction::RemoveComment { anchor, id })
+            }
+            UndoAction::SetComment {
+                id,
+                content,
+                comment_type,
+            } => {
+                let comment = find_comment_mut(session, &id)?;
+                let old_content = std::mem::replace(&mut comment.content, content);
+                let old_type = std::mem::replace(&mut comment.comment_type, comment_type);
+                Some(UndoAction::SetComment {
+                    id,
+                    content: old_content,
+                    comment_type: old_type,
+                })
+            }
+            UndoAction::SetReviewed { path, reviewed } => {
+                let review = session.files.get_mut(&path)?;
+                let old = std::mem::replace(&mut review.reviewed, reviewed);
+                Some(UndoAction::SetReviewed {
+                    path,
+                    reviewed: old,
+                })
+            }
+            UndoAction::SetHunkReviewed {
+                path,
+                hunk,
+                reviewed,
+                file_reviewed,
+            } => {
+                let review = session.files.get_mut(&path)?;
+                let old = review.set_hunk_reviewed(&hunk, reviewed);
+                let old_file = std::mem::replace(&mut review.reviewed, file_reviewed);
+                Some(UndoAction::SetHunkReviewed {
+                    path,
+                    hunk,
+                    reviewed: old,
+                    file_reviewed: old_file,
+                })
+            }
+            UndoAction::RestoreAll {
+                review_comments,
+                mut files,
+            } => {
+                let inverse = UndoAction::snapshot(session);
+                session.review_comments = review_comments;
+                for (path, review) in session.files.iter_mut() {
+                    if let Some(snapshot) = files.remove(path) {
+                        review.reviewed = snapshot.reviewed;
+                        review.reviewed_hunks = snapshot.reviewed_hunks;
+                        review.file_comments = snapshot.file_comments;
+                        review.line_comments = snapshot.line_comments;
+                    }
+                }
+                Some(inverse)
+            }
+        }
+    }
+}
+
+fn comments_mut<'a>(
+    session: &'a mut ReviewSession,
+    anchor: &CommentAnchor,
+) -> Option<&'a mut Vec<Comment>> {
+    match anchor {
+        CommentAnchor::Review => Some(&mut session.review_comments),
+        CommentAnchor::File(path) => Some(&mut session.files.get_mut(path)?.file_comments),
+        CommentAnchor::Line(path, line) => session.files.get_mut(path)?.line_comments.get_mut(line),
+    }
+}
+
+fn find_comment_mut<'a>(session: &'a mut ReviewSession, id: &str) -> Option<&'a mut Comment> {
+    if let Some(comment) = session.review_comments.iter_mut().find(|c| c.id == id) {
+        return Some(comment);
+    }
+    session.files.values_mut().find_map(|review| {
+        review
+            .file_comments
+            .iter_mut()
+            .chain(review.line_comments.values_mut().flatten())
+            .find(|c| c.id == id)
+    })
+}
+
+/// A described undo step, e.g. "delete comment on line 42".
+#[derive(Debug, Clone)]
+pub struct UndoEntry {
+    pub description: String,
+    pub action: UndoAction,
+}
+
+/// Bounded undo/redo stacks.
+#[derive(Debug, Default)]
+pub struct UndoHistory {
+    undo: VecDeque<UndoEntry>,
+    redo: Vec<UndoEntry>,
+}
+
+impl UndoHistory {
+    /// Record a new change. `action` must revert the change. Clears the redo stack.
+    pub fn record(&mut self, description: impl Into<String>, action: UndoAction) {
+        if self.undo.len() == MAX_UNDO_ENTRIES {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(UndoEntry {
+            description: description.into(),
+            action,
+        });
+        self.redo.clear();
+    }
+
+    /// Undo the most recent change, returning its description. None when
+    /// there is nothing to undo; `Err` when its target no longer exists, in
+    /// which case the change is dropped so older ones can still be undone.
+    pub fn undo(&mut self, session: &mut ReviewSession) -> Option<Result<String, String>> {
+        let entry = self.undo.pop_back()?;
+        let Some(inverse) = entry.action.apply(session) else {
+            return Some(Err(entry.description));
+        };
+        self.redo.push(UndoEntry {
+            description: entry.description.clone(),
+            action: inverse,
+        });
+        Some(Ok(entry.description))
+    }
+
+    /// Redo the most recently undone change, like [`UndoHistory::undo`].
+    pub fn redo(&mut self, session: &mut ReviewSession) -> Option<Result<String, String>> {
+        let entry = self.redo.pop()?;
+        let Some(inverse) = entry.action.apply(session) else {
+            return Some(Err(entry.description));
+        };
+        self.undo.push_back(UndoEntry {
+            description: entry.description.clone(),
+            action: inverse,
+        });
+        Some(Ok(entry.description))
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{FileStatus, SessionDiffSource};
+
+    fn test_session() -> ReviewSession {
+        let mut session = ReviewSession::new(
+            PathBuf::from("/repo"),
+            "abc123".to_string(),
+            None,
+            SessionDiffSource::WorkingTree,
+        );
+        session.add_file(PathBuf::from("a.rs"), FileStatus::Modified, 0);
+        session
+    }
+
+    fn note(content: &str) -> Comment {
+        Comment::new(content.to_string(), CommentType::Note, None)
+    }
+
+    #[test]
+    fn should_undo_and_redo_line_comment_deletion() {
+        let mut session = test_session();
+        let path = PathBuf::from("a.rs");
+        let comment = note("hello");
+        session
+            .get_file_mut(&path)
+            .unwrap()
+            .add_line_comment(42, comment.clone());
+
+        let mut history = UndoHistory::default();
+        let anchor = CommentAnchor::Line(path.clone(), 42);
+        let inverse = UndoAction::RemoveComment {
+            anchor: anchor.clone(),
+            id: comment.id.clone(),
+        }
+        .apply(&mut session)
+        .unwrap();
+        history.record("delete comment on line 42", inverse);
+        assert!(session.files[&path].line_comments.is_empty());
+
+        assert_eq!(
+            history.undo(&mut session),
+            Some(Ok("delete comment on line 42".to_string()))
+        );
+        assert_eq!(session.files[&path].line_comments[&42][0].content, "hello");
+
+        history.redo(&mut session);
+        assert!(session.files[&path].line_comments.is_empty());
+    }
+
+    #[test]
+    fn should_restore_everything_after_clear() {
+        let mut session = test_session();
+        let path = PathBuf::from("a.rs");
+        session.review_comments.push(note("review"));
+        let file = session.get_file_mut(&path).unwrap();
+        file.add_file_comment(note("file"));
+        file.reviewed = true;
+
+        let mut history = UndoHistory::default();
+        history.record("clear comments", UndoAction::snapshot(&session));
+        session.clear_comments(crate::model::ClearScope::CommentsAndReviewed);
+        assert!(!session.has_comments());
+
+        history.undo(&mut session);
+        assert_eq!(session.review_comments.len(), 1);
+        assert_eq!(session.files[&path].file_comments.len(), 1);
+        assert!(session.files[&path].reviewed);
+    }
+
+    #[test]
+    fn should_clear_redo_stack_on_new_change() {
+        let mut session = test_session();
+        let path = PathBuf::from("a.rs");
+        let mut history = UndoHistory::default();
+        session.get_file_mut(&path).unwrap().reviewed = true;
+        history.record(
+            "mark a.rs reviewed",
+            UndoAction::SetReviewed {
+                path: path.clone(),
+                reviewed: false,
+            },
+        );
+        history.undo(&mut session);
+
+        history.record(
+            "mark a.rs reviewed",
+            UndoAction::SetReviewed {
+                path,
+                reviewed: false,
+            },
+        );
+        assert!(history.redo(&mut session).is_none());
+    }
+
+    #[test]
+    fn should_report_and_skip_changes_whose_target_is_gone() {
+        let mut session = test_session();
+        let path = PathBuf::from("a.rs");
+        let mut history = UndoHistory::default();
+        history.record(
+            "mark a.rs reviewed",
+            UndoAction::SetReviewed {
+                path: path.clone(),
+                reviewed: false,
+            },
+        );
+        history.record(
+            "edit comment",
+            UndoAction::SetComment {
+                id: "gone".to_string(),
+                content: "old".to_string(),
+                comment_type: CommentType::Note,
+            },
+        );
+        session.get_file_mut(&path).unwrap().reviewed = true;
+
+        assert_eq!(
+            history.undo(&mut session),
+            Some(Err("edit comment".to_string()))
+        );
+        assert_eq!(
+            history.undo(&mut session),
+            Some(Ok("mark a.rs reviewed".to_string()))
+        );
+        assert!(!session.files[&path].reviewed);
+    }
+
+    #[test]
+    fn should_drop_oldest_entry_when_full() {
+        let mut session = test_session();
+        let path = PathBuf::from("a.rs");
+        let mut history = UndoHistory::default();
+        for _ in 0..MAX_UNDO_ENTRIES + 5 {
+            history.record(
+                "toggle",
+                UndoAction::SetReviewed {
+                    path: path.clone(),
+                    reviewed: false,
+                },
+            );
+        }
+
+        let mut undone = 0;
+        while history.undo(&mut session).is_some() {
+            undone += 1;
+        }
+        assert_eq!(undone, MAX_UNDO_ENTRIES);
+    }
+}
@@ -19,6 +19,19 @@ pub enum UpdateCheckResult {
     Failed(String),
 }
 
+/// Version of this build, e.g. `0.5.0 (a1b2c3d)`. The commit hash is only
+/// present when the build set `TUICR_BUILD_SHA`.
+pub fn version_string() -> String {
+    format_version(env!("CARGO_PKG_VERSION"), option_env!("TUICR_BUILD_SHA"))
+}
+
+fn format_version(version: &str, sha: Option<&str>) -> String {
+    match sha.map(str::trim).filter(|sha| !sha.is_empty()) {
+        Some(sha) => format!("{version} ({})", &sha[..sha.len().min(7)]),
+        None => version.to_string(),
+    }
+}
+
 /// Check for updates from crates.io (3-second timeout)
 pub fn check_for_updates() -> UpdateCheckResult {
     let current_version = env!("CARGO_PKG_VERSION");
@@ -108,6 +121,16 @@ mod tests {
         assert!(!is_newer_version("1.0.0", "0.9.9"));
     }
 
+    #[test]
+    fn test_format_version() {
+        assert_eq!(format_version("0.5.0", None), "0.5.0");
+        assert_eq!(format_version("0.5.0", Some("")), "0.5.0");
+        assert_eq!(
+            format_version("0.5.0", Some("a1b2c3d4e5f6a7b8")),
+            "0.5.0 (a1b2c3d)"
+        );
+    }
+
     #[test]
     fn test_ahead_of_release_detection() {
         // When current > latest, we can detect "ahead of release"
@@ -3,11 +3,11 @@
 //! Parses unified diff format output from CLI tools into DiffFile structures.
 //! Git uses the native git2 library instead and has its own parser.
 
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 
 use crate::error::{Result, TuicrError};
-use crate::model::{DiffFile, DiffHunk, DiffLine, FileStatus, LineOrigin};
-use crate::syntax::SyntaxHighlighter;
+use crate::model::{BinaryMeta, DiffFile, DiffHunk, DiffLine, FileStatus, LineOrigin};
 
 /// Diff format variants for different VCS tools.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -19,11 +19,7 @@ pub enum DiffFormat {
 }
 
 /// Parse unified diff output into DiffFile structures.
-pub fn parse_unified_diff(
-    diff_text: &str,
-    format: DiffFormat,
-    highlighter: &SyntaxHighlighter,
-) -> Result<Vec<DiffFile>> {
+pub fn parse_unified_diff(diff_text: &str, format: DiffFormat) -> Result<Vec<DiffFile>> {
     let mut files: Vec<DiffFile> = Vec::new();
     let mut lines = diff_text.lines().peekable();
 
@@ -34,7 +30,8 @@ pub fn parse_unified_diff(
 
     while let Some(line) = lines.next() {
         if line.starts_with(header_prefix) {
-            let (mut old_path, mut new_path, status) = parse_file_header(&mut lines, format);
+            let (mut old_path, mut new_path, status, modes) = parse_file_header(&mut lines, format);
+            let is_symlink = modes.old == Some(SYMLINK_MODE) || modes.new == Some(SYMLINK_MODE);
 
             // For git-style diffs (jj, git patches), if parse_file_header didn't find
             // ---/+++ or rename/copy lines (e.g. empty new files, mode-only changes),
@@ -62,14 +59,23 @@ pub fn parse_unified_diff(
                     status,
                     hunks: Vec::new(),
                     is_binary: true,
+                    binary_meta: None,
                     is_too_large: false,
+                    truncated_lines: 0,
                     is_commit_message: false,
+                    is_submodule: false,
+                    old_mode: modes.old,
+                    new_mode: modes.new,
+                    is_symlink,
+                    is_conflicted: false,
+                    has_conflicts: false,
                     content_hash: 0,
+                    highlighted: false,
+                    is_generated: false,
                 });
                 continue;
             }
 
-            let file_path = new_path.as_ref().or(old_path.as_ref());
             let mut hunks = Vec::new();
 
             // Parse hunks until next file or end
@@ -78,7 +84,7 @@ pub fn parse_unified_diff(
                     if peek_line.starts_with("diff ") {
                         break;
                     } else if peek_line.starts_with("@@") {
-                        if let Some(hunk) = parse_hunk(&mut lines, file_path, highlighter) {
+                        if let Some(hunk) = parse_hunk(&mut lines) {
                             hunks.push(hunk);
                         }
                     } else {
@@ -88,15 +94,26 @@ pub fn parse_unified_diff(
             }
 
             let content_hash = DiffFile::compute_content_hash(&hunks);
+            let has_conflicts = DiffFile::contains_conflict_markers(&hunks);
             files.push(DiffFile {
                 old_path,
                 new_path,
                 status,
                 hunks,
                 is_binary: false,
+                binary_meta: None,
                 is_too_large: false,
+                truncated_lines: 0,
                 is_commit_message: false,
+                is_submodule: false,
+                old_mode: modes.old,
+                new_mode: modes.new,
+                is_symlink,
+                is_conflicted: false,
+                has_conflicts,
                 content_hash,
+                highlighted: false,
+                is_generated: false,
             });
         }
     }
@@ -108,16 +125,69 @@ pub fn parse_unified_diff(
     Ok(files)
 }
 
+/// Fill in [`BinaryMeta`] for binary files of a working copy diff. The new
+/// side is read from the working copy under `root` and the old side through
+/// `read_old`, which gets the repository-relative old path.
+pub(crate) fn attach_binary_meta(
+    files: &mut [DiffFile],
+    root: &Path,
+    read_old: impl Fn(&Path) -> Option<Vec<u8>>,
+) {
+    for file in files.iter_mut().filter(|f| f.is_binary) {
+        let old = match (&file.status, &file.old_path) {
+            (FileStatus::Added, _) | (_, None) => None,
+            (_, Some(path)) => read_old(path),
+        };
+        let new = match (&file.status, &file.new_path) {
+            (FileStatus::Deleted, _) | (_, None) => None,
+            (_, Some(path)) => std::fs::read(root.join(path)).ok(),
+        };
+        file.binary_meta = Some(BinaryMeta::from_contents(old.as_deref(), new.as_deref()));
+    }
+}
+
+/// Truncate files to `max_lines` diff lines, except those in `fully_loaded`.
+pub(crate) fn truncate_files(
+    files: &mut [DiffFile],
+    max_lines: Option<usize>,
+    fully_loaded: &HashSet<PathBuf>,
+) {
+    let Some(max_lines) = max_lines else {
+        return;
+    };
+    for file in files
+        .iter_mut()
+        .filter(|f| !fully_loaded.contains(f.display_path()))
+    {
+        file.truncate_lines(max_lines);
+    }
+}
+
+/// Git mode of a symbolic link.
+const SYMLINK_MODE: u32 = 0o120000;
+
+/// File modes from git-style `old mode`/`new mode`/`index` header lines.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct FileModes {
+    old: Option<u32>,
+    new: Option<u32>,
+}
+
+fn parse_mode(s: &str) -> Option<u32> {
+    u32::from_str_radix(s.trim(), 8).ok()
+}
+
 fn parse_file_header<'a, I>(
     lines: &mut std::iter::Peekable<I>,
     format: DiffFormat,
-) -> (Option<PathBuf>, Option<PathBuf>, FileStatus)
+) -> (Option<PathBuf>, Option<PathBuf>, FileStatus, FileModes)
 where
     I: Iterator<Item = &'a str>,
 {
     let mut old_path: Option<PathBuf> = None;
     let mut new_path: Option<PathBuf> = None;
     let mut status = FileStatus::Modified;
+    let mut modes = FileModes::default();
 
     // Parse --- and +++ lines and metadata
     while let Some(line) = lines.peek() {
@@ -145,11 +215,28 @@ where
             }
             lines.next();
             break; // Done with file header
+        } else if let Some(mode) = line.strip_prefix("old mode ") {
+            modes.old = parse_mode(mode);
+            lines.next();
+        } else if let Some(mode) = line.strip_prefix("new mode ") {
+            modes.new = parse_mode(mode);
+            lines.next();
         } else if line.starts_with("new file") {
             status = FileStatus::Added;
+            modes.new = line.strip_prefix("new file mode ").and_then(parse_mode);
             lines.next();
         } else if line.starts_with("deleted file") {
             status = FileStatus::Deleted;
+            modes.old = line.strip_prefix("deleted file mode ").and_then(parse_mode);
+            lines.next();
+        } else if let Some(index) = line.strip_prefix("index ") {
+            // "index abc..def 100644" carries the mode when it is unchanged
+            if let Some((_, mode)) = index.split_once(' ')
+                && let Some(mode) = parse_mode(mode)
+            {
+                modes.old = modes.old.or(Some(mode));
+                modes.new = modes.new.or(Some(mode));
+            }
             lines.next();
         } else if let Some(path) = line.strip_prefix("rename from ") {
             status = FileStatus::Renamed;
@@ -193,14 +280,10 @@ where
         }
     }
 
-    (old_path, new_path, status)
+    (old_path, new_path, status, modes)
 }
 
-fn parse_hunk<'a, I>(
-    lines: &mut std::iter::Peekable<I>,
-    file_path: Option<&PathBuf>,
-    highlighter: &SyntaxHighlighter,
-) -> Option<DiffHunk>
+fn parse_hunk<'a, I>(lines: &mut std::iter::Peekable<I>) -> Option<DiffHunk>
 where
     I: Iterator<Item = &'a str>,
 {
@@ -270,49 +353,33 @@ where
         line_numbers.push((old_ln, new_ln));
     }
 
-    // Apply syntax highlighting by side-specific sequence to keep parser state valid.
-    let highlight_sequences =
-        SyntaxHighlighter::split_diff_lines_for_highlighting(&line_contents, &line_origins);
-    let (old_highlighted_lines, new_highlighted_lines) = if let Some(path) = file_path {
-        (
-            highlighter.highlight_file_lines(path, &highlight_sequences.old_lines),
-            highlighter.highlight_file_lines(path, &highlight_sequences.new_lines),
-        )
-    } else {
-        (None, None)
-    };
-
     // Build DiffLines
     let mut diff_lines: Vec<DiffLine> = Vec::with_capacity(line_contents.len());
     for (idx, content) in line_contents.into_iter().enumerate() {
         let origin = line_origins[idx];
         let (old_lineno, new_lineno) = line_numbers[idx];
 
-        let highlighted_spans = highlighter.highlighted_line_for_diff_with_background(
-            old_highlighted_lines.as_deref(),
-            new_highlighted_lines.as_deref(),
-            highlight_sequences.old_line_indices[idx],
-            highlight_sequences.new_line_indices[idx],
-            origin,
-        );
-
         diff_lines.push(DiffLine {
             origin,
             content,
             old_lineno,
             new_lineno,
-            highlighted_spans,
+            highlighted_spans: None,
+            whitespace_only: false,
+            move_id: None,
         });
     }
 
-    Some(DiffHunk {
+    let mut hunk = DiffHunk {
         header: header_line.to_string(),
         lines: diff_lines,
         old_start,
         old_count,
         new_start,
         new_count,
-    })
+    };
+    hunk.mark_whitespace_only_changes();
+    Some(hunk)
 }
 
 fn parse_hunk_header(line: &str) -> Option<(u32, u32, u32, u32)> {
@@ -408,11 +475,11 @@ mod tests {
     #[test]
     fn should_return_no_changes_for_empty_diff() {
         assert!(matches!(
-            parse_unified_diff("", DiffFormat::Hg, &SyntaxHighlighter::default()),
+            parse_unified_diff("", DiffFormat::Hg),
             Err(TuicrError::NoChanges)
         ));
         assert!(matches!(
-            parse_unified_diff("", DiffFormat::GitStyle, &SyntaxHighlighter::default()),
+            parse_unified_diff("", DiffFormat::GitStyle),
             Err(TuicrError::NoChanges)
         ));
     }
@@ -474,8 +541,7 @@ mod tests {
  }
 "#;
 
-        let result =
-            parse_unified_diff(diff, DiffFormat::Hg, &SyntaxHighlighter::default()).unwrap();
+        let result = parse_unified_diff(diff, DiffFormat::Hg).unwrap();
         assert_eq!(result.len(), 1);
         assert_eq!(result[0].status, FileStatus::Modified);
         assert_eq!(result[0].hunks.len(), 1);
@@ -492,8 +558,7 @@ mod tests {
 +	new
 "#;
 
-        let result =
-            parse_unified_diff(diff, DiffFormat::Hg, &SyntaxHighlighter::default()).unwrap();
+        let result = parse_unified_diff(diff, DiffFormat::Hg).unwrap();
         let lines = &result[0].hunks[0].lines;
 
         assert_eq!(lines[0].content, "    old");
@@ -511,8 +576,7 @@ mod tests {
 +}
 "#;
 
-        let result =
-            parse_unified_diff(diff, DiffFormat::Hg, &SyntaxHighlighter::default()).unwrap();
+        let result = parse_unified_diff(diff, DiffFormat::Hg).unwrap();
         assert_eq!(result.len(), 1);
         assert_eq!(result[0].status, FileStatus::Added);
         assert!(result[0].old_path.is_none());
@@ -532,8 +596,7 @@ mod tests {
 -}
 "#;
 
-        let result =
-            parse_unified_diff(diff, DiffFormat::Hg, &SyntaxHighlighter::default()).unwrap();
+        let result = parse_unified_diff(diff, DiffFormat::Hg).unwrap();
         assert_eq!(result.len(), 1);
         assert_eq!(result[0].status, FileStatus::Deleted);
         assert_eq!(
@@ -559,8 +622,7 @@ diff -r abc123 file2.rs
 -remove
 "#;
 
-        let result =
-            parse_unified_diff(diff, DiffFormat::Hg, &SyntaxHighlighter::default()).unwrap();
+        let result = parse_unified_diff(diff, DiffFormat::Hg).unwrap();
         assert_eq!(result.len(), 2);
         assert_eq!(
             result[0].new_path.as_ref().unwrap().to_str().unwrap(),
@@ -588,8 +650,7 @@ diff -r abc123 file2.rs
  }
 "#;
 
-        let result =
-            parse_unified_diff(diff, DiffFormat::Hg, &SyntaxHighlighter::default()).unwrap();
+        let result = parse_unified_diff(diff, DiffFormat::Hg).unwrap();
         assert_eq!(result.len(), 1);
         assert_eq!(result[0].hunks.len(), 2);
         assert_eq!(result[0].hunks[0].old_start, 1);
@@ -608,8 +669,7 @@ rename to new_name.rs
 +new content
 "#;
 
-        let result =
-            parse_unified_diff(diff, DiffFormat::Hg, &SyntaxHighlighter::default()).unwrap();
+        let result = parse_unified_diff(diff, DiffFormat::Hg).unwrap();
         assert_eq!(result.len(), 1);
         assert_eq!(result[0].status, FileStatus::Renamed);
         assert_eq!(
@@ -628,8 +688,7 @@ rename to new_name.rs
 Binary file image.png has changed
 "#;
 
-        let result =
-            parse_unified_diff(diff, DiffFormat::Hg, &SyntaxHighlighter::default()).unwrap();
+        let result = parse_unified_diff(diff, DiffFormat::Hg).unwrap();
         assert_eq!(result.len(), 1);
         assert!(result[0].is_binary);
         assert!(result[0].hunks.is_empty());
@@ -643,8 +702,7 @@ rename from old_name.rs
 rename to new_name.rs
 "#;
 
-        let result =
-            parse_unified_diff(diff, DiffFormat::Hg, &SyntaxHighlighter::default()).unwrap();
+        let result = parse_unified_diff(diff, DiffFormat::Hg).unwrap();
         assert_eq!(result.len(), 1);
         assert_eq!(result[0].status, FileStatus::Renamed);
         assert_eq!(result[0].old_path, Some(PathBuf::from("old_name.rs")));
@@ -660,8 +718,7 @@ copy from source.rs
 copy to dest.rs
 "#;
 
-        let result =
-            parse_unified_diff(diff, DiffFormat::Hg, &SyntaxHighlighter::default()).unwrap();
+        let result = parse_unified_diff(diff, DiffFormat::Hg).unwrap();
         assert_eq!(result.len(), 1);
         assert_eq!(result[0].status, FileStatus::Copied);
         assert_eq!(result[0].old_path, Some(PathBuf::from("source.rs")));
@@ -682,8 +739,7 @@ copy to dest.rs
 +added line
 "#;
 
-        let result =
-            parse_unified_diff(diff, DiffFormat::Hg, &SyntaxHighlighter::default()).unwrap();
+        let result = parse_unified_diff(diff, DiffFormat::Hg).unwrap();
         assert_eq!(result.len(), 1);
         assert_eq!(result[0].status, FileStatus::Copied);
         assert_eq!(result[0].old_path, Some(PathBuf::from("source.rs")));
@@ -703,8 +759,7 @@ copy to dest.rs
 \ No newline at end of file
 "#;
 
-        let result =
-            parse_unified_diff(diff, DiffFormat::Hg, &SyntaxHighlighter::default()).unwrap();
+        let result = parse_unified_diff(diff, DiffFormat::Hg).unwrap();
         assert_eq!(result.len(), 1);
         assert_eq!(result[0].hunks[0].lines.len(), 2);
     }
@@ -722,8 +777,7 @@ copy to dest.rs
  context at 7->8
 "#;
 
-        let result =
-            parse_unified_diff(diff, DiffFormat::Hg, &SyntaxHighlighter::default()).unwrap();
+        let result = parse_unified_diff(diff, DiffFormat::Hg).unwrap();
         let lines = &result[0].hunks[0].lines;
 
         assert_eq!(lines[0].origin, LineOrigin::Context);
@@ -760,8 +814,7 @@ copy to dest.rs
  line2
  line3
 "#;
-        let files =
-            parse_unified_diff(diff, DiffFormat::GitStyle, &SyntaxHighlighter::default()).unwrap();
+        let files = parse_unified_diff(diff, DiffFormat::GitStyle).unwrap();
         assert_eq!(files.len(), 1);
         assert_eq!(files[0].new_path, Some(PathBuf::from("file.txt")));
         assert_eq!(files[0].status, FileStatus::Modified);
@@ -778,8 +831,7 @@ copy to dest.rs
 -	old
 +	new
 "#;
-        let files =
-            parse_unified_diff(diff, DiffFormat::GitStyle, &SyntaxHighlighter::default()).unwrap();
+        let files = parse_unified_diff(diff, DiffFormat::GitStyle).unwrap();
         let lines = &files[0].hunks[0].lines;
 
         assert_eq!(lines[0].content, "    old");
@@ -800,16 +852,21 @@ copy to dest.rs
  );
 "#;
 
-        let files =
-            parse_unified_diff(diff, DiffFormat::GitStyle, &SyntaxHighlighter::default()).unwrap();
+        let files = parse_unified_diff(diff, DiffFormat::GitStyle).unwrap();
         let lines = &files[0].hunks[0].lines;
-
         assert_eq!(lines.len(), 5);
-        for (idx, line) in lines.iter().enumerate() {
-            assert!(
-                line.highlighted_spans.is_some(),
-                "line {idx} should retain highlighting"
-            );
+        // Highlighting happens later, in the background
+        assert!(lines.iter().all(|line| line.highlighted_spans.is_none()));
+
+        let contents: Vec<String> = lines.iter().map(|l| l.content.clone()).collect();
+        let origins: Vec<LineOrigin> = lines.iter().map(|l| l.origin).collect();
+        let highlighted = crate::syntax::SyntaxHighlighter::default().highlight_diff_lines(
+            files[0].display_path(),
+            &contents,
+            &origins,
+        );
+        for (idx, spans) in highlighted.iter().enumerate() {
+            assert!(spans.is_some(), "line {idx} should retain highlighting");
         }
     }
 
@@ -823,8 +880,7 @@ new file mode 100644
 +line1
 +line2
 "#;
-        let files =
-            parse_unified_diff(diff, DiffFormat::GitStyle, &SyntaxHighlighter::default()).unwrap();
+        let files = parse_unified_diff(diff, DiffFormat::GitStyle).unwrap();
         assert_eq!(files.len(), 1);
         assert_eq!(files[0].status, FileStatus::Added);
     }
@@ -839,8 +895,7 @@ deleted file mode 100644
 -line1
 -line2
 "#;
-        let files =
-            parse_unified_diff(diff, DiffFormat::GitStyle, &SyntaxHighlighter::default()).unwrap();
+        let files = parse_unified_diff(diff, DiffFormat::GitStyle).unwrap();
         assert_eq!(files.len(), 1);
         assert_eq!(files[0].status, FileStatus::Deleted);
     }
@@ -852,8 +907,7 @@ deleted file mode 100644
 rename from old.txt
 rename to new.txt
 "#;
-        let files =
-            parse_unified_diff(diff, DiffFormat::GitStyle, &SyntaxHighlighter::default()).unwrap();
+        let files = parse_unified_diff(diff, DiffFormat::GitStyle).unwrap();
         assert_eq!(files.len(), 1);
         assert_eq!(files[0].status, FileStatus::Renamed);
         assert_eq!(files[0].old_path, Some(PathBuf::from("old.txt")));
@@ -873,8 +927,7 @@ rename to new.txt
 -old content
 +new content
 "#;
-        let files =
-            parse_unified_diff(diff, DiffFormat::GitStyle, &SyntaxHighlighter::default()).unwrap();
+        let files = parse_unified_diff(diff, DiffFormat::GitStyle).unwrap();
         assert_eq!(files.len(), 1);
         assert_eq!(files[0].status, FileStatus::Renamed);
         assert_eq!(files[0].old_path, Some(PathBuf::from("old.txt")));
@@ -889,8 +942,7 @@ rename to new.txt
 copy from source.txt
 copy to dest.txt
 "#;
-        let files =
-            parse_unified_diff(diff, DiffFormat::GitStyle, &SyntaxHighlighter::default()).unwrap();
+        let files = parse_unified_diff(diff, DiffFormat::GitStyle).unwrap();
         assert_eq!(files.len(), 1);
         assert_eq!(files[0].status, FileStatus::Copied);
         assert_eq!(files[0].old_path, Some(PathBuf::from("source.txt")));
@@ -910,8 +962,7 @@ copy to dest.txt
  original
 +added line
 "#;
-        let files =
-            parse_unified_diff(diff, DiffFormat::GitStyle, &SyntaxHighlighter::default()).unwrap();
+        let files = parse_unified_diff(diff, DiffFormat::GitStyle).unwrap();
         assert_eq!(files.len(), 1);
         assert_eq!(files[0].status, FileStatus::Copied);
         assert_eq!(files[0].old_path, Some(PathBuf::from("source.txt")));
@@ -926,8 +977,7 @@ new file mode 100644
 index 0000000000..abc1234567
 Binary files /dev/null and b/image.png differ
 "#;
-        let files =
-            parse_unified_diff(diff, DiffFormat::GitStyle, &SyntaxHighlighter::default()).unwrap();
+        let files = parse_unified_diff(diff, DiffFormat::GitStyle).unwrap();
         assert_eq!(files.len(), 1);
         assert!(files[0].is_binary);
         assert_eq!(files[0].status, FileStatus::Added);
@@ -942,8 +992,7 @@ deleted file mode 100644
 index abc1234567..0000000000
 Binary files a/image.png and /dev/null differ
 "#;
-        let files =
-            parse_unified_diff(diff, DiffFormat::GitStyle, &SyntaxHighlighter::default()).unwrap();
+        let files = parse_unified_diff(diff, DiffFormat::GitStyle).unwrap();
         assert_eq!(files.len(), 1);
         assert!(files[0].is_binary);
         assert_eq!(files[0].status, FileStatus::Deleted);
@@ -957,8 +1006,7 @@ Binary files a/image.png and /dev/null differ
 index abc1234567..def7890123 100644
 Binary files a/image.png and b/image.png differ
 "#;
-        let files =
-            parse_unified_diff(diff, DiffFormat::GitStyle, &SyntaxHighlighter::default()).unwrap();
+        let files = parse_unified_diff(diff, DiffFormat::GitStyle).unwrap();
         assert_eq!(files.len(), 1);
         assert!(files[0].is_binary);
         assert_eq!(files[0].status, FileStatus::Modified);
@@ -981,8 +1029,7 @@ diff --git a/b.txt b/b.txt
 -foo
 +bar
 "#;
-        let files =
-            parse_unified_diff(diff, DiffFormat::GitStyle, &SyntaxHighlighter::default()).unwrap();
+        let files = parse_unified_diff(diff, DiffFormat::GitStyle).unwrap();
         assert_eq!(files.len(), 2);
         assert_eq!(files[0].new_path, Some(PathBuf::from("a.txt")));
         assert_eq!(files[1].new_path, Some(PathBuf::from("b.txt")));
@@ -1000,8 +1047,7 @@ diff --git a/b.txt b/b.txt
 +added2
  more
 "#;
-        let files =
-            parse_unified_diff(diff, DiffFormat::GitStyle, &SyntaxHighlighter::default()).unwrap();
+        let files = parse_unified_diff(diff, DiffFormat::GitStyle).unwrap();
         let hunk = &files[0].hunks[0];
 
         assert_eq!(hunk.lines[0].old_lineno, Some(5));
@@ -1027,8 +1073,7 @@ diff --git a/b.txt b/b.txt
 new file mode 100644
 index 0000000000..e69de29bb2
 "#;
-        let files =
-            parse_unified_diff(diff, DiffFormat::GitStyle, &SyntaxHighlighter::default()).unwrap();
+        let files = parse_unified_diff(diff, DiffFormat::GitStyle).unwrap();
         assert_eq!(files.len(), 1);
         assert_eq!(files[0].status, FileStatus::Added);
         assert!(files[0].old_path.is_none());
@@ -1045,13 +1090,110 @@ index 0000000000..e69de29bb2
 old mode 100644
 new mode 100755
 "#;
-        let files =
-            parse_unified_diff(diff, DiffFormat::GitStyle, &SyntaxHighlighter::default()).unwrap();
+        let files = parse_unified_diff(diff, DiffFormat::GitStyle).unwrap();
         assert_eq!(files.len(), 1);
         assert_eq!(files[0].status, FileStatus::Modified);
         assert_eq!(files[0].old_path, Some(PathBuf::from("script.sh")));
         assert_eq!(files[0].new_path, Some(PathBuf::from("script.sh")));
         assert!(files[0].hunks.is_empty());
         let _path = files[0].display_path();
+        assert_eq!(files[0].old_mode, Some(0o100644));
+        assert_eq!(files[0].new_mode, Some(0o100755));
+        assert_eq!(
+            files[0].metadata_descriptor().as_deref(),
+            Some("mode changed 644 → 755")
+        );
+    }
+
+    #[test]
+    fn jj_should_parse_symlink_retarget() {
+        let diff = r#"diff --git a/current b/current
+index 1234567..89abcde 120000
+--- a/current
++++ b/current
+@@ -1,1 +1,1 @@
+-releases/v1
+\ No newline at end of file
++releases/v2
+\ No newline at end of file
+"#;
+        let files = parse_unified_diff(diff, DiffFormat::GitStyle).unwrap();
+        assert_eq!(files.len(), 1);
+        assert!(files[0].is_symlink);
+        assert_eq!(
+            files[0].metadata_descriptor().as_deref(),
+            Some("symlink: releases/v1 → releases/v2")
+        );
+    }
+
+    #[test]
+    fn jj_should_not_describe_unchanged_mode() {
+        let diff = r#"diff --git a/file.txt b/file.txt
+index 1234567..89abcde 100644
+--- a/file.txt
++++ b/file.txt
+@@ -1,1 +1,1 @@
+-old
++new
+"#;
+        let files = parse_unified_diff(diff, DiffFormat::GitStyle).unwrap();
+        assert_eq!(files[0].old_mode, Some(0o100644));
+        assert!(!files[0].is_symlink);
+        assert_eq!(files[0].metadata_descriptor(), None);
+    }
+
+    #[test]
+    fn jj_should_detect_conflict_markers() {
+        let diff = r#"diff --git a/lib.rs b/lib.rs
+--- a/lib.rs
++++ b/lib.rs
+@@ -1,1 +1,5 @@
++<<<<<<< ours
+ let x = 1;
++=======
++let x = 2;
++>>>>>>> theirs
+"#;
+        let files = parse_unified_diff(diff, DiffFormat::GitStyle).unwrap();
+        assert!(files[0].has_conflicts);
+        assert!(files[0].has_unresolved_conflicts());
+    }
+
+    #[test]
+    fn jj_should_not_flag_resolved_or_partial_markers() {
+        // Removing markers resolves the conflict; a lone `=======` is a heading
+        let diff = r#"diff --git a/lib.rs b/lib.rs
+--- a/lib.rs
++++ b/lib.rs
+@@ -1,4 +1,2 @@
+-<<<<<<< ours
+ let x = 1;
+-=======
+->>>>>>> theirs
++=======
+"#;
+        let files = parse_unified_diff(diff, DiffFormat::GitStyle).unwrap();
+        assert!(!files[0].has_conflicts);
+    }
+
+    #[test]
+    fn should_flag_whitespace_only_line_pairs() {
+        let diff = r#"diff --git a/lib.rs b/lib.rs
+--- a/lib.rs
++++ b/lib.rs
+@@ -1,3 +1,3 @@
+-fn main() {
+-    let x=1;
++fn main()  {
++    let x = 2;
+ }
+"#;
+        let files = parse_unified_diff(diff, DiffFormat::GitStyle).unwrap();
+        let flags: Vec<bool> = files[0].hunks[0]
+            .lines
+            .iter()
+            .map(|line| line.whitespace_only)
+            .collect();
+        assert_eq!(flags, [true, false, true, false, false]);
     }
 }
@@ -2,7 +2,6 @@ use std::path::{Path, PathBuf};
 
 use crate::error::{Result, TuicrError};
 use crate::model::{DiffFile, DiffHunk, DiffLine, FileStatus, LineOrigin};
-use crate::syntax::SyntaxHighlighter;
 
 use super::traits::{VcsBackend, VcsInfo, VcsType};
 
@@ -54,7 +53,7 @@ impl VcsBackend for FileBackend {
         &self.info
     }
 
-    fn get_working_tree_diff(&self, highlighter: &SyntaxHighlighter) -> Result<Vec<DiffFile>> {
+    fn get_working_tree_diff(&self) -> Result<Vec<DiffFile>> {
         let content = std::fs::read_to_string(&self.file_path)?;
         let lines: Vec<&str> = content.lines().collect();
 
@@ -62,35 +61,18 @@ impl VcsBackend for FileBackend {
             return Err(TuicrError::NoChanges);
         }
 
-        // Build line contents and origins for syntax highlighting
-        let line_contents: Vec<String> = lines.iter().map(|l| l.replace('\t', "    ")).collect();
-        let line_origins: Vec<LineOrigin> = vec![LineOrigin::Addition; line_contents.len()];
-
-        // Apply syntax highlighting
-        let highlight_sequences =
-            SyntaxHighlighter::split_diff_lines_for_highlighting(&line_contents, &line_origins);
-        let new_highlighted_lines =
-            highlighter.highlight_file_lines(&self.file_path, &highlight_sequences.new_lines);
-
-        // Build DiffLines
         let mut diff_lines = Vec::with_capacity(lines.len());
-        for (i, content) in line_contents.iter().enumerate() {
+        for (i, line) in lines.iter().enumerate() {
             let line_num = (i + 1) as u32;
 
-            let highlighted_spans = highlighter.highlighted_line_for_diff_with_background(
-                None,
-                new_highlighted_lines.as_deref(),
-                None,
-                highlight_sequences.new_line_indices[i],
-                LineOrigin::Addition,
-            );
-
             diff_lines.push(DiffLine {
                 origin: LineOrigin::Addition,
-                content: content.clone(),
+                content: line.replace('\t', "    "),
                 old_lineno: None,
                 new_lineno: Some(line_num),
-                highlighted_spans,
+                highlighted_spans: None,
+                whitespace_only: false,
+                move_id: None,
             });
         }
 
@@ -120,9 +102,19 @@ impl VcsBackend for FileBackend {
             status: FileStatus::Added,
             hunks,
             is_binary: false,
+            binary_meta: None,
             is_too_large: false,
+            truncated_lines: 0,
             is_commit_message: false,
+            is_submodule: false,
+            old_mode: None,
+            new_mode: None,
+            is_symlink: false,
+            is_conflicted: false,
+            has_conflicts: false,
             content_hash,
+            highlighted: false,
+            is_generated: false,
         };
 
         Ok(vec![file])
@@ -152,6 +144,8 @@ impl VcsBackend for FileBackend {
                     old_lineno: Some(line_num),
                     new_lineno: Some(line_num),
                     highlighted_spans: None,
+                    whitespace_only: false,
+                    move_id: None,
                 });
             }
         }
@@ -19,6 +19,8 @@ pub fn fetch_context_lines(
         return Ok(Vec::new());
     }
 
+    // Submodules are gitlinks: a directory in the working tree and a commit
+    // in the tree, with no lines of their own
     let content = match file_status {
         FileStatus::Deleted => {
             // Read from HEAD blob for deleted files
@@ -28,6 +30,9 @@ pub fn fetch_context_lines(
             // Read from working tree for all other statuses
             let workdir = repo.workdir().ok_or(TuicrError::NotARepository)?;
             let full_path = workdir.join(file_path);
+            if full_path.is_dir() {
+                return Ok(Vec::new());
+            }
             std::fs::read_to_string(&full_path)?
         }
     };
@@ -44,6 +49,8 @@ pub fn fetch_context_lines(
                 old_lineno: Some(line_num),
                 new_lineno: Some(line_num),
                 highlighted_spans: None,
+                whitespace_only: false,
+                move_id: None,
             });
         }
     }
@@ -55,6 +62,9 @@ pub fn fetch_context_lines(
 fn fetch_blob_content(repo: &Repository, file_path: &Path) -> Result<String> {
     let head = repo.head()?.peel_to_tree()?;
     let entry = head.get_path(file_path)?;
+    if entry.kind() != Some(git2::ObjectType::Blob) {
+        return Ok(String::new());
+    }
     let blob = repo.find_blob(entry.id())?;
     let content = std::str::from_utf8(blob.content())
         .map_err(|e| TuicrError::CorruptedSession(format!("Invalid UTF-8 in file: {e}")))?;
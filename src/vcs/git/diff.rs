@@ -1,52 +1,183 @@
-use git2::{Delta, Diff, DiffOptions, Oid, Repository};
-use std::path::PathBuf;
+use git2::{
+    Delta, Diff, DiffFile as GitDiffFile, DiffFindOptions, DiffOptions, FileMode, Oid, Repository,
+};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 
 use crate::error::{Result, TuicrError};
-use crate::model::{DiffFile, DiffHunk, DiffLine, FileStatus, LineOrigin};
-use crate::syntax::SyntaxHighlighter;
+use crate::model::{BinaryMeta, DiffFile, DiffHunk, DiffLine, FileStatus, LineOrigin};
 use crate::vcs::{PullRequestDiff, PullRequestInfo};
 
-pub fn get_working_tree_diff(
-    repo: &Repository,
-    highlighter: &SyntaxHighlighter,
+/// Git's default similarity index for rename and copy detection.
+const DEFAULT_RENAME_THRESHOLD: u16 = 50;
+
+/// Settings applied to every diff the git backend produces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffSettings {
+    /// Ignore whitespace changes (`git diff -w`)
+    pub ignore_whitespace: bool,
+    /// Minimum similarity percentage for renames and copies (`git diff -M<n>%`)
+    pub rename_threshold: u16,
+    /// Diff lines loaded per file; the rest is loaded on request
+    pub max_file_lines: Option<usize>,
+    /// Files loaded in full regardless of `max_file_lines`
+    pub fully_loaded: HashSet<PathBuf>,
+}
+
+impl Default for DiffSettings {
+    fn default() -> Self {
+        Self {
+            ignore_whitespace: false,
+            rename_threshold: DEFAULT_RENAME_THRESHOLD,
+            max_file_lines: None,
+            fully_loaded: HashSet::new(),
+        }
+    }
+}
+
+impl DiffSettings {
+    /// Maximum number of diff lines to load for the file at `path`.
+    fn line_limit(&self, path: Option<&PathBuf>) -> Option<usize> {
+        self.max_file_lines
+            .filter(|_| path.is_none_or(|path| !self.fully_loaded.contains(path)))
+    }
+}
+
+/// Diff options shared by every diff, honouring the ignore-whitespace toggle.
+fn diff_options(settings: &DiffSettings) -> DiffOptions {
+    let mut opts = DiffOptions::new();
+    opts.ignore_whitespace(settings.ignore_whitespace);
+    opts
+}
+
+/// Pair deleted and added files into renames and copies like `git diff -M -C`.
+fn detect_renames(diff: &mut Diff, settings: &DiffSettings) -> Result<()> {
+    let mut opts = DiffFindOptions::new();
+    opts.renames(true)
+        .copies(true)
+        .rename_threshold(settings.rename_threshold)
+        .copy_threshold(settings.rename_threshold)
+        .ignore_whitespace(settings.ignore_whitespace);
+    diff.find_similar(Some(&mut opts))?;
+    Ok(())
+}
+
+/// With whitespace ignored libgit2 still reports files whose changes were all
+/// whitespace, just without hunks. Drop them like `git diff -w` does.
+fn drop_whitespace_only_files(
+    files: Vec<DiffFile>,
+    settings: &DiffSettings,
 ) -> Result<Vec<DiffFile>> {
+    if !settings.ignore_whitespace {
+        return Ok(files);
+    }
+    let files: Vec<_> = files
+        .into_iter()
+        .filter(|file| {
+            !file.hunks.is_empty()
+                || file.status != FileStatus::Modified
+                || file.is_binary
+                || file.metadata_descriptor().is_some()
+        })
+        .collect();
+    if files.is_empty() {
+        return Err(TuicrError::NoChanges);
+    }
+    Ok(files)
+}
+
+pub fn get_working_tree_diff(repo: &Repository, settings: &DiffSettings) -> Result<Vec<DiffFile>> {
     let head = repo.head()?.peel_to_tree()?;
 
-    let mut opts = DiffOptions::new();
+    let mut opts = diff_options(settings);
     opts.include_untracked(true);
     opts.show_untracked_content(true);
     opts.recurse_untracked_dirs(true);
 
-    let diff = repo.diff_tree_to_workdir_with_index(Some(&head), Some(&mut opts))?;
+    let mut diff = repo.diff_tree_to_workdir_with_index(Some(&head), Some(&mut opts))?;
+    detect_renames(&mut diff, settings)?;
 
-    parse_diff(&diff, highlighter)
+    let mut files = parse_diff(repo, &diff, settings)?;
+    mark_conflicted(repo, &mut files, settings)?;
+    drop_whitespace_only_files(files, settings)
 }
 
 /// Get the staged diff (index vs HEAD)
 /// On repos with no commits (unborn HEAD), diffs against an empty tree.
-pub fn get_staged_diff(
-    repo: &Repository,
-    highlighter: &SyntaxHighlighter,
-) -> Result<Vec<DiffFile>> {
+pub fn get_staged_diff(repo: &Repository, settings: &DiffSettings) -> Result<Vec<DiffFile>> {
     let head = repo.head().ok().and_then(|h| h.peel_to_tree().ok());
     let index = repo.index()?;
-    let diff = repo.diff_tree_to_index(head.as_ref(), Some(&index), None)?;
-    parse_diff(&diff, highlighter)
+    let mut diff = repo.diff_tree_to_index(
+        head.as_ref(),
+        Some(&index),
+        Some(&mut diff_options(settings)),
+    )?;
+    detect_renames(&mut diff, settings)?;
+    drop_whitespace_only_files(parse_diff(repo, &diff, settings)?, settings)
 }
 
 /// Get the unstaged diff (working tree vs index)
-pub fn get_unstaged_diff(
-    repo: &Repository,
-    highlighter: &SyntaxHighlighter,
-) -> Result<Vec<DiffFile>> {
+pub fn get_unstaged_diff(repo: &Repository, settings: &DiffSettings) -> Result<Vec<DiffFile>> {
     let index = repo.index()?;
-    let mut opts = DiffOptions::new();
+    let mut opts = diff_options(settings);
     opts.include_untracked(true);
     opts.show_untracked_content(true);
     opts.recurse_untracked_dirs(true);
 
-    let diff = repo.diff_index_to_workdir(Some(&index), Some(&mut opts))?;
-    parse_diff(&diff, highlighter)
+    let mut diff = repo.diff_index_to_workdir(Some(&index), Some(&mut opts))?;
+    detect_renames(&mut diff, settings)?;
+    let mut files = parse_diff(repo, &diff, settings)?;
+    mark_conflicted(repo, &mut files, settings)?;
+    drop_whitespace_only_files(files, settings)
+}
+
+/// Flag files with unmerged index entries, i.e. unresolved merge conflicts.
+/// libgit2 reports no content for these against the index, so their hunks
+/// are rebuilt from HEAD to the working tree file, conflict markers included.
+fn mark_conflicted(
+    repo: &Repository,
+    files: &mut [DiffFile],
+    settings: &DiffSettings,
+) -> Result<()> {
+    let index = repo.index()?;
+    if !index.has_conflicts() {
+        return Ok(());
+    }
+
+    let mut paths = HashSet::new();
+    for conflict in index.conflicts()? {
+        let conflict = conflict?;
+        for entry in [conflict.ancestor, conflict.our, conflict.their]
+            .into_iter()
+            .flatten()
+        {
+            paths.insert(PathBuf::from(
+                String::from_utf8_lossy(&entry.path).into_owned(),
+            ));
+        }
+    }
+
+    let head = repo.head().ok().and_then(|h| h.peel_to_tree().ok());
+    for file in files {
+        file.is_conflicted = paths.contains(file.display_path());
+        if !file.is_conflicted || !file.hunks.is_empty() || file.is_binary {
+            continue;
+        }
+
+        let mut opts = diff_options(settings);
+        opts.pathspec(file.display_path())
+            .disable_pathspec_match(true);
+        let diff = repo.diff_tree_to_workdir(head.as_ref(), Some(&mut opts))?;
+        if let Some(worktree_file) = parse_diff(repo, &diff, settings)
+            .ok()
+            .and_then(|files| files.into_iter().next())
+        {
+            file.hunks = worktree_file.hunks;
+            file.content_hash = worktree_file.content_hash;
+            file.has_conflicts = worktree_file.has_conflicts;
+        }
+    }
+    Ok(())
 }
 
 /// Get the diff for a range of commits.
@@ -55,7 +186,7 @@ pub fn get_unstaged_diff(
 pub fn get_commit_range_diff(
     repo: &Repository,
     commit_ids: &[String],
-    highlighter: &SyntaxHighlighter,
+    settings: &DiffSettings,
 ) -> Result<Vec<DiffFile>> {
     if commit_ids.is_empty() {
         return Err(TuicrError::NoChanges);
@@ -78,9 +209,14 @@ pub fn get_commit_range_diff(
 
     let new_tree = newest_commit.tree()?;
 
-    let diff = repo.diff_tree_to_tree(old_tree.as_ref(), Some(&new_tree), None)?;
+    let mut diff = repo.diff_tree_to_tree(
+        old_tree.as_ref(),
+        Some(&new_tree),
+        Some(&mut diff_options(settings)),
+    )?;
+    detect_renames(&mut diff, settings)?;
 
-    parse_diff(&diff, highlighter)
+    drop_whitespace_only_files(parse_diff(repo, &diff, settings)?, settings)
 }
 
 /// Get a combined diff from the parent of the oldest commit through to the working tree.
@@ -88,7 +224,7 @@ pub fn get_commit_range_diff(
 pub fn get_working_tree_with_commits_diff(
     repo: &Repository,
     commit_ids: &[String],
-    highlighter: &SyntaxHighlighter,
+    settings: &DiffSettings,
 ) -> Result<Vec<DiffFile>> {
     if commit_ids.is_empty() {
         return Err(TuicrError::NoChanges);
@@ -105,21 +241,22 @@ pub fn get_working_tree_with_commits_diff(
         None
     };
 
-    let mut opts = DiffOptions::new();
+    let mut opts = diff_options(settings);
     opts.include_untracked(true);
     opts.show_untracked_content(true);
     opts.recurse_untracked_dirs(true);
 
-    let diff = repo.diff_tree_to_workdir_with_index(old_tree.as_ref(), Some(&mut opts))?;
+    let mut diff = repo.diff_tree_to_workdir_with_index(old_tree.as_ref(), Some(&mut opts))?;
+    detect_renames(&mut diff, settings)?;
 
-    parse_diff(&diff, highlighter)
+    drop_whitespace_only_files(parse_diff(repo, &diff, settings)?, settings)
 }
 
 /// Get a PR-style diff from merge-base(base_ref, HEAD) to HEAD.
 pub fn get_pull_request_diff(
     repo: &Repository,
     base_ref: Option<&str>,
-    highlighter: &SyntaxHighlighter,
+    settings: &DiffSettings,
 ) -> Result<PullRequestDiff> {
     let head_commit = repo.head()?.peel_to_commit()?;
     let head_oid = head_commit.id();
@@ -138,8 +275,13 @@ pub fn get_pull_request_diff(
 
     let merge_base_commit = repo.find_commit(merge_base_oid)?;
     let merge_base_tree = merge_base_commit.tree()?;
-    let diff = repo.diff_tree_to_tree(Some(&merge_base_tree), Some(&head_tree), None)?;
-    let files = parse_diff(&diff, highlighter)?;
+    let mut diff = repo.diff_tree_to_tree(
+        Some(&merge_base_tree),
+        Some(&head_tree),
+        Some(&mut diff_options(settings)),
+    )?;
+    detect_renames(&mut diff, settings)?;
+    let files = drop_whitespace_only_files(parse_diff(repo, &diff, settings)?, settings)?;
 
     let commit_count = count_commits_between(repo, merge_base_oid, head_oid)?;
 
@@ -168,12 +310,21 @@ fn count_commits_between(repo: &Repository, merge_base_oid: Oid, head_oid: Oid)
     Ok(count)
 }
 
-fn resolve_base_reference(repo: &Repository, explicit_base: Option<&str>) -> Result<(String, Oid)> {
+pub(super) fn resolve_base_reference(
+    repo: &Repository,
+    explicit_base: Option<&str>,
+) -> Result<(String, Oid)> {
     if let Some(base_ref) = explicit_base {
-        let oid = resolve_ref_to_oid(repo, base_ref).map_err(|_| {
-            TuicrError::VcsCommand(format!("Could not resolve base reference '{base_ref}'"))
-        })?;
-        return Ok((base_ref.to_string(), oid));
+        // A bare branch name may only exist on the remote
+        let remote_ref = format!("origin/{base_ref}");
+        for candidate in [base_ref, remote_ref.as_str()] {
+            if let Ok(oid) = resolve_ref_to_oid(repo, candidate) {
+                return Ok((candidate.to_string(), oid));
+            }
+        }
+        return Err(TuicrError::VcsCommand(format!(
+            "Could not resolve base reference '{base_ref}' (tried '{base_ref}' and '{remote_ref}')"
+        )));
     }
 
     if let Ok(origin_head) = repo.find_reference("refs/remotes/origin/HEAD")
@@ -201,7 +352,7 @@ fn resolve_ref_to_oid(repo: &Repository, reference: &str) -> Result<Oid> {
     Ok(commit.id())
 }
 
-fn parse_diff(diff: &Diff, highlighter: &SyntaxHighlighter) -> Result<Vec<DiffFile>> {
+fn parse_diff(repo: &Repository, diff: &Diff, settings: &DiffSettings) -> Result<Vec<DiffFile>> {
     let mut files: Vec<DiffFile> = Vec::new();
 
     // Untracked files larger than this are shown in the file list but their
@@ -224,25 +375,60 @@ fn parse_diff(diff: &Diff, highlighter: &SyntaxHighlighter) -> Result<Vec<DiffFi
         let is_too_large =
             delta.status() == Delta::Untracked && delta.new_file().size() > MAX_UNTRACKED_FILE_SIZE;
 
-        // Use new_path for highlighting (the current version of the file)
         let file_path = new_path.as_ref().or(old_path.as_ref());
 
-        let hunks = if is_binary || is_too_large {
-            Vec::new()
+        let is_submodule = is_gitlink(&delta.old_file()) || is_gitlink(&delta.new_file());
+        let (hunks, truncated_lines) = if is_submodule {
+            let path = file_path.ok_or(TuicrError::NoChanges)?;
+            let hunk = submodule_hunk(repo, path, delta.old_file().id(), delta.new_file().id());
+            (vec![hunk], 0)
+        } else if is_binary || is_too_large {
+            (Vec::new(), 0)
         } else {
-            parse_hunks(diff, delta_idx, file_path, highlighter)?
+            parse_hunks(diff, delta_idx, settings.line_limit(file_path))?
         };
 
+        // libgit2 only knows a file is binary once its content is loaded,
+        // which for unflagged files happens while building the patch
+        let is_binary = is_binary
+            || (hunks.is_empty()
+                && !is_submodule
+                && !is_too_large
+                && patch_is_binary(diff, delta_idx));
+
+        let old_mode = file_mode(&delta.old_file());
+        let new_mode = file_mode(&delta.new_file());
+        let is_symlink =
+            delta.old_file().mode() == FileMode::Link || delta.new_file().mode() == FileMode::Link;
+
+        let binary_meta = is_binary.then(|| {
+            BinaryMeta::from_contents(
+                binary_contents(repo, &delta.old_file()).as_deref(),
+                binary_contents(repo, &delta.new_file()).as_deref(),
+            )
+        });
+
         let content_hash = DiffFile::compute_content_hash(&hunks);
+        let has_conflicts = DiffFile::contains_conflict_markers(&hunks);
         files.push(DiffFile {
             old_path,
             new_path,
             status,
             hunks,
             is_binary,
+            binary_meta,
             is_too_large,
+            truncated_lines,
             is_commit_message: false,
+            is_submodule,
+            old_mode,
+            new_mode,
+            is_symlink,
+            is_conflicted: false,
+            has_conflicts,
             content_hash,
+            highlighted: false,
+            is_generated: false,
         });
     }
 
@@ -253,33 +439,140 @@ fn parse_diff(diff: &Diff, highlighter: &SyntaxHighlighter) -> Result<Vec<DiffFi
     Ok(files)
 }
 
+/// Most commits listed for a submodule pointer change.
+const MAX_SUBMODULE_LOG: usize = 100;
+
+/// The file's mode, or `None` when the file doesn't exist on that side.
+fn file_mode(file: &GitDiffFile) -> Option<u32> {
+    match file.mode() {
+        FileMode::Unreadable => None,
+        mode => Some(u32::from(mode)),
+    }
+}
+
+fn patch_is_binary(diff: &Diff, delta_idx: usize) -> bool {
+    git2::Patch::from_diff(diff, delta_idx)
+        .ok()
+        .flatten()
+        .is_some_and(|patch| patch.delta().flags().is_binary())
+}
+
+/// Content of one side of a binary file: the blob when it is in the object
+/// database, otherwise the working tree file.
+fn binary_contents(repo: &Repository, file: &GitDiffFile) -> Option<Vec<u8>> {
+    if !file.exists() {
+        return None;
+    }
+    if let Ok(blob) = repo.find_blob(file.id()) {
+        return Some(blob.content().to_vec());
+    }
+    std::fs::read(repo.workdir()?.join(file.path()?)).ok()
+}
+
+fn is_gitlink(file: &GitDiffFile) -> bool {
+    file.mode() == FileMode::Commit
+}
+
+/// Describe a submodule pointer change as a single hunk: a
+/// `Subproject commit old → new` line followed, when the submodule is checked
+/// out, by the `log --oneline old..new` of the commits it pulls in.
+fn submodule_hunk(repo: &Repository, path: &Path, old: Oid, new: Oid) -> DiffHunk {
+    let short = |oid: Oid| oid.to_string()[..7].to_string();
+    let summary = match (old.is_zero(), new.is_zero()) {
+        (true, _) => format!("Subproject commit {} (added)", short(new)),
+        (_, true) => format!("Subproject commit {} (removed)", short(old)),
+        _ if old == new => format!("Subproject commit {} (modified content)", short(new)),
+        _ => format!("Subproject commit {} → {}", short(old), short(new)),
+    };
+
+    let mut contents = vec![summary];
+    if !old.is_zero() && !new.is_zero() && old != new {
+        contents.extend(submodule_log(repo, path, old, new).unwrap_or_default());
+    }
+
+    let lines: Vec<DiffLine> = contents
+        .into_iter()
+        .zip(1..)
+        .map(|(content, lineno)| DiffLine {
+            origin: LineOrigin::Context,
+            content,
+            old_lineno: Some(lineno),
+            new_lineno: Some(lineno),
+            highlighted_spans: None,
+            whitespace_only: false,
+            move_id: None,
+        })
+        .collect();
+    let count = lines.len() as u32;
+    DiffHunk {
+        header: format!("@@ -1,{count} +1,{count} @@ submodule"),
+        lines,
+        old_start: 1,
+        old_count: count,
+        new_start: 1,
+        new_count: count,
+    }
+}
+
+/// `<short id> <summary>` for each commit in `old..new` of the submodule at
+/// `path`. Fails if the submodule is not initialized or lacks either commit.
+fn submodule_log(repo: &Repository, path: &Path, old: Oid, new: Oid) -> Result<Vec<String>> {
+    let workdir = repo.workdir().ok_or(TuicrError::NotARepository)?;
+    let sub = Repository::open(workdir.join(path))?;
+    let mut revwalk = sub.revwalk()?;
+    revwalk.push(new)?;
+    revwalk.hide(old)?;
+
+    let mut log = Vec::new();
+    for oid in revwalk.take(MAX_SUBMODULE_LOG) {
+        let commit = sub.find_commit(oid?)?;
+        let id = commit.id().to_string();
+        log.push(format!(
+            "{} {}",
+            &id[..7],
+            commit.summary().unwrap_or_default()
+        ));
+    }
+    Ok(log)
+}
+
+/// Parse the hunks of a delta, loading at most `max_lines` diff lines. Returns
+/// the hunks and the number of diff lines left unloaded.
 fn parse_hunks(
     diff: &Diff,
     delta_idx: usize,
-    file_path: Option<&PathBuf>,
-    highlighter: &SyntaxHighlighter,
-) -> Result<Vec<DiffHunk>> {
+    max_lines: Option<usize>,
+) -> Result<(Vec<DiffHunk>, usize)> {
     let mut hunks: Vec<DiffHunk> = Vec::new();
+    let mut budget = max_lines.unwrap_or(usize::MAX);
+    let mut truncated_lines = 0;
 
     let patch = git2::Patch::from_diff(diff, delta_idx)?;
 
     if let Some(patch) = patch {
         for hunk_idx in 0..patch.num_hunks() {
+            let total_lines = patch.num_lines_in_hunk(hunk_idx)?;
+            let line_count = total_lines.min(budget);
+            budget -= line_count;
+            truncated_lines += total_lines - line_count;
+            if line_count == 0 {
+                continue;
+            }
+
             let (hunk, _) = patch.hunk(hunk_idx)?;
 
             let header = String::from_utf8_lossy(hunk.header()).trim().to_string();
             let old_start = hunk.old_start();
-            let old_count = hunk.old_lines();
+            let mut old_count = hunk.old_lines();
             let new_start = hunk.new_start();
-            let new_count = hunk.new_lines();
+            let mut new_count = hunk.new_lines();
 
             let mut lines: Vec<DiffLine> = Vec::new();
 
-            // First, collect all line content for syntax highlighting
             let mut line_contents: Vec<String> = Vec::new();
             let mut line_origins: Vec<LineOrigin> = Vec::new();
 
-            for line_idx in 0..patch.num_lines_in_hunk(hunk_idx)? {
+            for line_idx in 0..line_count {
                 let line = patch.line_in_hunk(hunk_idx, line_idx)?;
 
                 let origin = match line.origin() {
@@ -299,56 +592,50 @@ fn parse_hunks(
                 line_origins.push(origin);
             }
 
-            // Apply syntax highlighting if we have a file path
-            let highlight_sequences =
-                SyntaxHighlighter::split_diff_lines_for_highlighting(&line_contents, &line_origins);
-            let (old_highlighted_lines, new_highlighted_lines) = if let Some(path) = file_path {
-                (
-                    highlighter.highlight_file_lines(path, &highlight_sequences.old_lines),
-                    highlighter.highlight_file_lines(path, &highlight_sequences.new_lines),
-                )
-            } else {
-                (None, None)
-            };
+            // A partially loaded hunk only spans the lines it holds
+            if line_count < total_lines {
+                let count = |side: LineOrigin| {
+                    line_origins
+                        .iter()
+                        .filter(|&&origin| origin != side)
+                        .count() as u32
+                };
+                old_count = count(LineOrigin::Addition);
+                new_count = count(LineOrigin::Deletion);
+            }
 
-            // Now create DiffLines with syntax highlighting applied
-            for line_idx in 0..patch.num_lines_in_hunk(hunk_idx)? {
+            for line_idx in 0..line_count {
                 let line = patch.line_in_hunk(hunk_idx, line_idx)?;
                 let old_lineno = line.old_lineno();
                 let new_lineno = line.new_lineno();
                 let content = line_contents[line_idx].clone();
                 let origin = line_origins[line_idx];
 
-                // Get highlighted spans and apply diff background
-                let highlighted_spans = highlighter.highlighted_line_for_diff_with_background(
-                    old_highlighted_lines.as_deref(),
-                    new_highlighted_lines.as_deref(),
-                    highlight_sequences.old_line_indices[line_idx],
-                    highlight_sequences.new_line_indices[line_idx],
-                    origin,
-                );
-
                 lines.push(DiffLine {
                     origin,
                     content,
                     old_lineno,
                     new_lineno,
-                    highlighted_spans,
+                    highlighted_spans: None,
+                    whitespace_only: false,
+                    move_id: None,
                 });
             }
 
-            hunks.push(DiffHunk {
+            let mut hunk = DiffHunk {
                 header,
                 lines,
                 old_start,
                 old_count,
                 new_start,
                 new_count,
-            });
+            };
+            hunk.mark_whitespace_only_changes();
+            hunks.push(hunk);
         }
     }
 
-    Ok(hunks)
+    Ok((hunks, truncated_lines))
 }
 
 #[cfg(test)]
@@ -357,7 +644,7 @@ mod tests {
     use std::fs;
     use std::path::Path;
 
-    fn create_initial_commit(repo: &Repository, file_name: &str, content: &str) {
+    fn create_initial_commit(repo: &Repository, file_name: &str, content: impl AsRef<[u8]>) {
         fs::write(repo.workdir().unwrap().join(file_name), content)
             .expect("failed to write initial file");
 
@@ -376,6 +663,35 @@ mod tests {
             .expect("failed to create commit");
     }
 
+    #[test]
+    fn should_fall_back_to_origin_for_pull_request_base() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let repo = Repository::init(temp_dir.path()).expect("failed to init repo");
+        create_initial_commit(&repo, "file.txt", "base\n");
+        let base = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.reference("refs/remotes/origin/develop", base.id(), false, "test")
+            .expect("failed to create remote ref");
+
+        fs::write(temp_dir.path().join("file.txt"), "changed\n").expect("failed to update file");
+        let mut index = repo.index().expect("failed to open index");
+        index.add_path(Path::new("file.txt")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now("Test User", "test@example.com").unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "change", &tree, &[&base])
+            .expect("failed to commit");
+
+        let pr_diff = get_pull_request_diff(&repo, Some("develop"), &DiffSettings::default())
+            .expect("PR diff failed");
+        assert_eq!(pr_diff.info.base_ref, "origin/develop");
+        assert_eq!(pr_diff.info.merge_base_commit, base.id().to_string());
+        assert_eq!(pr_diff.info.commit_count, 1);
+
+        let err = get_pull_request_diff(&repo, Some("nope"), &DiffSettings::default())
+            .expect_err("unknown base should fail");
+        assert!(err.to_string().contains("tried 'nope' and 'origin/nope'"));
+    }
+
     #[test]
     fn should_return_no_changes_for_clean_repo() {
         // given
@@ -384,10 +700,9 @@ mod tests {
         let diff = repo
             .diff_tree_to_tree(Some(&head), Some(&head), None)
             .unwrap();
-        let highlighter = SyntaxHighlighter::default();
 
         // when
-        let result = parse_diff(&diff, &highlighter);
+        let result = parse_diff(&repo, &diff, &DiffSettings::default());
 
         // then
         assert!(matches!(result, Err(TuicrError::NoChanges)));
@@ -410,8 +725,8 @@ mod tests {
         )
         .expect("failed to update file");
 
-        let files = get_working_tree_diff(&repo, &SyntaxHighlighter::default())
-            .expect("failed to get diff");
+        let files =
+            get_working_tree_diff(&repo, &DiffSettings::default()).expect("failed to get diff");
 
         assert_eq!(files.len(), 1);
         let lines = &files[0].hunks[0].lines;
@@ -432,12 +747,11 @@ mod tests {
 
         fs::write(temp_dir.path().join("file.txt"), "unstaged\n").expect("failed to update file");
 
-        let highlighter = SyntaxHighlighter::default();
-
-        let unstaged = get_unstaged_diff(&repo, &highlighter).expect("unstaged diff failed");
+        let unstaged =
+            get_unstaged_diff(&repo, &DiffSettings::default()).expect("unstaged diff failed");
         assert_eq!(unstaged.len(), 1);
         assert!(matches!(
-            get_staged_diff(&repo, &highlighter),
+            get_staged_diff(&repo, &DiffSettings::default()),
             Err(TuicrError::NoChanges)
         ));
 
@@ -447,11 +761,258 @@ mod tests {
             .expect("failed to add file to index");
         index.write().expect("failed to write index");
 
-        let staged = get_staged_diff(&repo, &highlighter).expect("staged diff failed");
+        let staged = get_staged_diff(&repo, &DiffSettings::default()).expect("staged diff failed");
         assert_eq!(staged.len(), 1);
         assert!(matches!(
-            get_unstaged_diff(&repo, &highlighter),
+            get_unstaged_diff(&repo, &DiffSettings::default()),
             Err(TuicrError::NoChanges)
         ));
     }
+
+    #[test]
+    fn should_summarize_submodule_pointer_changes() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let repo = Repository::init(temp_dir.path()).expect("failed to init repo");
+        let sub = Repository::init(temp_dir.path().join("sub")).expect("failed to init submodule");
+
+        let sig = git2::Signature::now("Test User", "test@example.com")
+            .expect("failed to create signature");
+        let empty_tree = sub
+            .find_tree(sub.treebuilder(None).unwrap().write().unwrap())
+            .unwrap();
+        let first = sub
+            .commit(Some("HEAD"), &sig, &sig, "first", &empty_tree, &[])
+            .unwrap();
+        let parent = sub.find_commit(first).unwrap();
+        let second = sub
+            .commit(Some("HEAD"), &sig, &sig, "second", &empty_tree, &[&parent])
+            .unwrap();
+
+        let gitlink_tree = |oid: Oid| {
+            let mut builder = repo.treebuilder(None).unwrap();
+            builder.insert("sub", oid, 0o160000).unwrap();
+            repo.find_tree(builder.write().unwrap()).unwrap()
+        };
+        let old_tree = gitlink_tree(first);
+        let new_tree = gitlink_tree(second);
+        let diff = repo
+            .diff_tree_to_tree(Some(&old_tree), Some(&new_tree), None)
+            .unwrap();
+
+        let files = parse_diff(&repo, &diff, &DiffSettings::default()).unwrap();
+
+        assert_eq!(files.len(), 1);
+        assert!(files[0].is_submodule);
+        let lines: Vec<_> = files[0].hunks[0]
+            .lines
+            .iter()
+            .map(|l| l.content.as_str())
+            .collect();
+        assert_eq!(
+            lines[0],
+            format!(
+                "Subproject commit {} → {}",
+                &first.to_string()[..7],
+                &second.to_string()[..7]
+            )
+        );
+        assert_eq!(lines[1], format!("{} second", &second.to_string()[..7]));
+        assert_eq!(lines.len(), 2);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn should_keep_mode_only_changes() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let repo = Repository::init(temp_dir.path()).expect("failed to init repo");
+        create_initial_commit(&repo, "script.sh", "echo hi\n");
+
+        let path = temp_dir.path().join("script.sh");
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755))
+            .expect("failed to chmod file");
+
+        let files =
+            get_working_tree_diff(&repo, &DiffSettings::default()).expect("failed to get diff");
+
+        assert_eq!(files.len(), 1);
+        assert!(files[0].hunks.is_empty());
+        assert_eq!(
+            files[0].metadata_descriptor().as_deref(),
+            Some("mode changed 644 → 755")
+        );
+    }
+
+    #[test]
+    fn should_ignore_whitespace_changes_when_requested() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let repo = Repository::init(temp_dir.path()).expect("failed to init repo");
+        create_initial_commit(&repo, "lib.rs", "fn a() {\n    1\n}\n");
+
+        fs::write(temp_dir.path().join("lib.rs"), "fn a()  {\n\t1\n}\n")
+            .expect("failed to update file");
+
+        let files =
+            get_working_tree_diff(&repo, &DiffSettings::default()).expect("failed to get diff");
+        assert_eq!(files.len(), 1);
+        assert!(files[0].hunks[0].lines.iter().any(|l| l.whitespace_only));
+
+        assert!(matches!(
+            get_working_tree_diff(
+                &repo,
+                &DiffSettings {
+                    ignore_whitespace: true,
+                    ..DiffSettings::default()
+                }
+            ),
+            Err(TuicrError::NoChanges)
+        ));
+    }
+
+    #[test]
+    fn should_detect_renames_above_the_configured_threshold() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let repo = Repository::init(temp_dir.path()).expect("failed to init repo");
+        let original: String = (1..=10).map(|n| format!("line {n}\n")).collect();
+        create_initial_commit(&repo, "old.txt", &original);
+
+        // Rename and change 3 of 10 lines, roughly 70% similar
+        let modified = original
+            .replace("line 2\n", "changed 2\n")
+            .replace("line 5\n", "changed 5\n")
+            .replace("line 8\n", "changed 8\n");
+        fs::remove_file(temp_dir.path().join("old.txt")).expect("failed to remove file");
+        fs::write(temp_dir.path().join("new.txt"), modified).expect("failed to write file");
+        let mut index = repo.index().expect("failed to open index");
+        index
+            .remove_path(Path::new("old.txt"))
+            .expect("failed to remove file from index");
+        index
+            .add_path(Path::new("new.txt"))
+            .expect("failed to add file to index");
+        index.write().expect("failed to write index");
+
+        let statuses = |rename_threshold| {
+            let settings = DiffSettings {
+                rename_threshold,
+                ..DiffSettings::default()
+            };
+            get_staged_diff(&repo, &settings)
+                .expect("failed to get diff")
+                .iter()
+                .map(|file| file.status)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(statuses(50), [FileStatus::Renamed]);
+        let mut split = statuses(90);
+        split.sort_by_key(|status| format!("{status:?}"));
+        assert_eq!(split, [FileStatus::Added, FileStatus::Deleted]);
+    }
+
+    #[test]
+    fn should_attach_sizes_and_dimensions_to_binary_files() {
+        let png = |width: u32, height: u32| {
+            let mut bytes = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR".to_vec();
+            bytes.extend(width.to_be_bytes());
+            bytes.extend(height.to_be_bytes());
+            bytes.extend([0; 8]);
+            bytes
+        };
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let repo = Repository::init(temp_dir.path()).expect("failed to init repo");
+        create_initial_commit(&repo, "image.png", png(1024, 768));
+
+        let mut modified = png(800, 600);
+        modified.resize(2048, 0);
+        fs::write(temp_dir.path().join("image.png"), modified).expect("failed to update file");
+
+        let files =
+            get_working_tree_diff(&repo, &DiffSettings::default()).expect("failed to get diff");
+        assert!(files[0].is_binary);
+        assert_eq!(
+            files[0].binary_descriptor(),
+            "(binary, 1024×768 → 800×600, 32 B → 2.0 KiB (+2.0 KiB))"
+        );
+    }
+
+    #[test]
+    fn should_load_only_max_file_lines_until_file_is_fully_loaded() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let repo = Repository::init(temp_dir.path()).expect("failed to init repo");
+        create_initial_commit(&repo, "small.txt", "one\n");
+        let large: String = (1..=50_000).map(|n| format!("line {n}\n")).collect();
+        fs::write(temp_dir.path().join("large.txt"), large).expect("failed to write file");
+        fs::write(temp_dir.path().join("small.txt"), "one\ntwo\n").expect("failed to write file");
+
+        let mut settings = DiffSettings {
+            max_file_lines: Some(1_000),
+            ..DiffSettings::default()
+        };
+        let loaded_lines = |files: &[DiffFile], path: &str| {
+            let file = files
+                .iter()
+                .find(|f| f.display_path() == Path::new(path))
+                .expect("file missing from diff");
+            let lines: usize = file.hunks.iter().map(|h| h.lines.len()).sum();
+            (lines, file.truncated_lines)
+        };
+
+        let files = get_working_tree_diff(&repo, &settings).expect("diff failed");
+        assert_eq!(loaded_lines(&files, "large.txt"), (1_000, 49_000));
+        assert_eq!(
+            files.iter().find(|f| f.truncated_lines > 0).unwrap().hunks[0].new_count,
+            1_000
+        );
+        assert_eq!(loaded_lines(&files, "small.txt"), (2, 0));
+
+        settings.fully_loaded.insert(PathBuf::from("large.txt"));
+        let files = get_working_tree_diff(&repo, &settings).expect("diff failed");
+        assert_eq!(loaded_lines(&files, "large.txt"), (50_000, 0));
+    }
+
+    #[test]
+    fn should_mark_unmerged_files_as_conflicted() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let repo = Repository::init(temp_dir.path()).expect("failed to init repo");
+        create_initial_commit(&repo, "file.txt", "base\n");
+        let sig = git2::Signature::now("Test User", "test@example.com")
+            .expect("failed to create signature");
+        let base = repo.head().unwrap().peel_to_commit().unwrap();
+
+        let commit_content = |content: &str, parent: &git2::Commit| {
+            let blob = repo.blob(content.as_bytes()).unwrap();
+            let mut builder = repo.treebuilder(None).unwrap();
+            builder.insert("file.txt", blob, 0o100644).unwrap();
+            let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+            repo.commit(None, &sig, &sig, content, &tree, &[parent])
+                .unwrap()
+        };
+        let ours = commit_content("ours\n", &base);
+        let theirs = commit_content("theirs\n", &base);
+
+        repo.reset(
+            repo.find_commit(ours).unwrap().as_object(),
+            git2::ResetType::Hard,
+            None,
+        )
+        .unwrap();
+        let theirs = repo.find_annotated_commit(theirs).unwrap();
+        repo.merge(&[&theirs], None, None).unwrap();
+        assert!(repo.index().unwrap().has_conflicts());
+
+        let files =
+            get_working_tree_diff(&repo, &DiffSettings::default()).expect("failed to get diff");
+
+        assert_eq!(files.len(), 1);
+        assert!(files[0].is_conflicted);
+        assert!(files[0].has_conflicts);
+        assert!(
+            files[0].hunks[0]
+                .lines
+                .iter()
+                .any(|l| l.content.starts_with("<<<<<<<"))
+        );
+    }
 }
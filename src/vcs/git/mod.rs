@@ -1,5 +1,6 @@
 pub mod context;
 pub mod diff;
+pub mod remote;
 pub mod repository;
 pub mod staging;
 
@@ -7,15 +8,17 @@ use git2::Repository;
 use std::path::Path;
 
 use crate::error::{Result, TuicrError};
-use crate::model::{DiffFile, DiffLine, FileStatus};
-use crate::syntax::SyntaxHighlighter;
+use crate::model::{DiffFile, DiffHunk, DiffLine, FileStatus, LineRange};
 
-use super::traits::{CommitInfo, PullRequestDiff, VcsBackend, VcsInfo, VcsType};
+use super::traits::{
+    BlameLine, BlameTarget, BranchInfo, CommitInfo, DiffStat, PullRequestDiff, VcsBackend, VcsInfo,
+    VcsType,
+};
 
 // Re-export commonly used functions
 pub use context::{calculate_gap, fetch_context_lines};
 pub use diff::{
-    get_commit_range_diff, get_pull_request_diff, get_staged_diff, get_unstaged_diff,
+    DiffSettings, get_commit_range_diff, get_pull_request_diff, get_staged_diff, get_unstaged_diff,
     get_working_tree_diff, get_working_tree_with_commits_diff,
 };
 
@@ -23,17 +26,27 @@ pub use diff::{
 pub struct GitBackend {
     repo: Repository,
     info: VcsInfo,
+    settings: DiffSettings,
 }
 
 impl GitBackend {
     /// Discover a git repository from the current directory
     pub fn discover() -> Result<Self> {
         let cwd = std::env::current_dir().map_err(|_| TuicrError::NotARepository)?;
-        let repo = Repository::discover(&cwd).map_err(|_| TuicrError::NotARepository)?;
+        Self::from_path(&cwd)
+    }
+
+    /// Discover a git repository containing `path` (used by discover and tests).
+    ///
+    /// Inside a linked worktree (`git worktree add`, or a bare-repo layout
+    /// whose `.git` is a file), `root_path` is that worktree's directory, so
+    /// sessions and ignore files belong to the worktree being reviewed.
+    pub(crate) fn from_path(path: &std::path::Path) -> Result<Self> {
+        let repo = Repository::discover(path).map_err(|_| TuicrError::NotARepository)?;
 
         let root_path = repo
             .workdir()
-            .ok_or(TuicrError::NotARepository)?
+            .ok_or_else(|| TuicrError::BareRepository(repo.path().display().to_string()))?
             .to_path_buf();
 
         let head_commit = repo
@@ -58,7 +71,11 @@ impl GitBackend {
             vcs_type: VcsType::Git,
         };
 
-        Ok(Self { repo, info })
+        Ok(Self {
+            repo,
+            info,
+            settings: DiffSettings::default(),
+        })
     }
 }
 
@@ -67,16 +84,16 @@ impl VcsBackend for GitBackend {
         &self.info
     }
 
-    fn get_working_tree_diff(&self, highlighter: &SyntaxHighlighter) -> Result<Vec<DiffFile>> {
-        get_working_tree_diff(&self.repo, highlighter)
+    fn get_working_tree_diff(&self) -> Result<Vec<DiffFile>> {
+        get_working_tree_diff(&self.repo, &self.settings)
     }
 
-    fn get_staged_diff(&self, highlighter: &SyntaxHighlighter) -> Result<Vec<DiffFile>> {
-        get_staged_diff(&self.repo, highlighter)
+    fn get_staged_diff(&self) -> Result<Vec<DiffFile>> {
+        get_staged_diff(&self.repo, &self.settings)
     }
 
-    fn get_unstaged_diff(&self, highlighter: &SyntaxHighlighter) -> Result<Vec<DiffFile>> {
-        get_unstaged_diff(&self.repo, highlighter)
+    fn get_unstaged_diff(&self) -> Result<Vec<DiffFile>> {
+        get_unstaged_diff(&self.repo, &self.settings)
     }
 
     fn fetch_context_lines(
@@ -109,12 +126,12 @@ impl VcsBackend for GitBackend {
         repository::resolve_revisions(&self.repo, revisions)
     }
 
-    fn get_commit_range_diff(
-        &self,
-        commit_ids: &[String],
-        highlighter: &SyntaxHighlighter,
-    ) -> Result<Vec<DiffFile>> {
-        get_commit_range_diff(&self.repo, commit_ids, highlighter)
+    fn list_branches(&self) -> Result<Vec<BranchInfo>> {
+        repository::list_branches(&self.repo)
+    }
+
+    fn get_commit_range_diff(&self, commit_ids: &[String]) -> Result<Vec<DiffFile>> {
+        get_commit_range_diff(&self.repo, commit_ids, &self.settings)
     }
 
     fn get_commits_info(&self, ids: &[String]) -> Result<Vec<CommitInfo>> {
@@ -133,23 +150,204 @@ impl VcsBackend for GitBackend {
             .collect())
     }
 
-    fn get_working_tree_with_commits_diff(
-        &self,
-        commit_ids: &[String],
-        highlighter: &SyntaxHighlighter,
-    ) -> Result<Vec<DiffFile>> {
-        get_working_tree_with_commits_diff(&self.repo, commit_ids, highlighter)
+    fn commit_diffstat(&self, id: &str) -> Result<DiffStat> {
+        repository::commit_diffstat(&self.repo, id)
     }
 
-    fn get_pull_request_diff(
-        &self,
-        base_ref: Option<&str>,
-        highlighter: &SyntaxHighlighter,
-    ) -> Result<PullRequestDiff> {
-        get_pull_request_diff(&self.repo, base_ref, highlighter)
+    fn get_working_tree_with_commits_diff(&self, commit_ids: &[String]) -> Result<Vec<DiffFile>> {
+        get_working_tree_with_commits_diff(&self.repo, commit_ids, &self.settings)
+    }
+
+    fn get_pull_request_diff(&self, base_ref: Option<&str>) -> Result<PullRequestDiff> {
+        get_pull_request_diff(&self.repo, base_ref, &self.settings)
+    }
+
+    fn blame_line(&self, path: &Path, line: u32, target: BlameTarget) -> Result<Option<String>> {
+        repository::blame_line(&self.repo, path, line, target)
+    }
+
+    fn blame_file(&self, path: &Path) -> Result<Vec<BlameLine>> {
+        repository::blame_file(&self.repo, path)
+    }
+
+    fn commit_message(&self) -> Result<String> {
+        repository::head_message(&self.repo)
+    }
+
+    fn set_commit_message(&self, message: &str) -> Result<()> {
+        repository::amend_head_message(&self.repo, message)
     }
 
     fn stage_file(&self, path: &Path) -> Result<()> {
         staging::stage_file(&self.repo, path)
     }
+
+    fn stage_hunk(&self, path: &Path, hunk: &DiffHunk) -> Result<()> {
+        staging::stage_hunk(&self.repo, path, hunk)
+    }
+
+    fn unstage_hunk(&self, path: &Path, hunk: &DiffHunk) -> Result<()> {
+        staging::unstage_hunk(&self.repo, path, hunk)
+    }
+
+    fn discard_hunk(&self, path: &Path, hunk: &DiffHunk) -> Result<()> {
+        staging::discard_hunk(&self.repo, path, hunk)
+    }
+
+    fn set_ignore_whitespace(&mut self, ignore: bool) -> bool {
+        self.settings.ignore_whitespace = ignore;
+        true
+    }
+
+    fn set_rename_threshold(&mut self, percent: u16) {
+        self.settings.rename_threshold = percent;
+    }
+
+    fn set_max_file_lines(&mut self, max_lines: Option<usize>) {
+        self.settings.max_file_lines = max_lines;
+    }
+
+    fn load_file_fully(&mut self, path: &Path) {
+        self.settings.fully_loaded.insert(path.to_path_buf());
+    }
+
+    fn user_name(&self) -> Option<String> {
+        let name = self.repo.config().ok()?.get_string("user.name").ok()?;
+        (!name.trim().is_empty()).then_some(name)
+    }
+
+    fn permalink(&self, path: &Path, range: LineRange) -> Option<String> {
+        let remote = self.repo.find_remote("origin").ok()?;
+        remote::permalink(remote.url()?, &self.info.head_commit, path, range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ReviewSession;
+    use crate::model::review::SessionDiffSource;
+    use crate::persistence::storage::tests::with_test_reviews_dir;
+    use crate::persistence::{
+        list_sessions_for_repo, load_latest_session_for_context, save_session,
+    };
+    use std::fs;
+    use std::process::Command;
+
+    fn commit_all(repo: &Repository, message: &str) {
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+            .unwrap();
+    }
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .expect("failed to run git");
+        assert!(
+            status.status.success(),
+            "git {args:?} failed: {}",
+            String::from_utf8_lossy(&status.stderr)
+        );
+    }
+
+    #[test]
+    fn should_review_from_inside_a_linked_worktree() {
+        let _guard = with_test_reviews_dir();
+        let temp_dir = tempfile::tempdir().unwrap();
+        let main = temp_dir.path().join("main");
+        let repo = Repository::init(&main).unwrap();
+        fs::write(main.join("file.txt"), "one\ntwo\nthree\n").unwrap();
+        commit_all(&repo, "initial");
+        git(&main, &["worktree", "add", "-b", "feature", "../feature"]);
+        let worktree = temp_dir.path().join("feature");
+        fs::write(worktree.join("file.txt"), "one\n2\nthree\n").unwrap();
+        fs::write(worktree.join("debug.log"), "noise\n").unwrap();
+        fs::write(worktree.join(".tuicrignore"), "*.log\n").unwrap();
+        fs::create_dir(worktree.join("src")).unwrap();
+
+        let backend = GitBackend::from_path(&worktree.join("src")).unwrap();
+        let info = backend.info();
+        assert_eq!(
+            fs::canonicalize(&info.root_path).unwrap(),
+            fs::canonicalize(&worktree).unwrap()
+        );
+        assert_eq!(info.branch_name.as_deref(), Some("feature"));
+
+        // The main checkout is untouched, so only the worktree's edits show,
+        // filtered by the worktree's ignore files
+        let diff = backend.get_working_tree_diff().unwrap();
+        let diff = crate::tuicrignore::filter_diff_files(&info.root_path, diff);
+        let paths: Vec<_> = diff.iter().map(|file| file.display_path()).collect();
+        assert_eq!(paths, [Path::new(".tuicrignore"), Path::new("file.txt")]);
+
+        let lines = backend
+            .fetch_context_lines(Path::new("file.txt"), FileStatus::Modified, 2, 3)
+            .unwrap();
+        let contents: Vec<&str> = lines.iter().map(|line| line.content.as_str()).collect();
+        assert_eq!(contents, ["2", "three"]);
+
+        // Sessions belong to the worktree, not the repository it shares
+        let session = ReviewSession::new(
+            info.root_path.clone(),
+            info.head_commit.clone(),
+            info.branch_name.clone(),
+            SessionDiffSource::WorkingTree,
+        );
+        save_session(&session).unwrap();
+        let (_, loaded) = load_latest_session_for_context(
+            &worktree,
+            Some("feature"),
+            &info.head_commit,
+            SessionDiffSource::WorkingTree,
+            None,
+        )
+        .unwrap()
+        .expect("session should be found from the worktree");
+        assert_eq!(loaded.id, session.id);
+        assert_eq!(list_sessions_for_repo(&worktree).unwrap().len(), 1);
+        assert!(list_sessions_for_repo(&main).unwrap().is_empty());
+    }
+
+    #[test]
+    fn should_reject_a_bare_repository_but_open_its_worktrees() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let seed = temp_dir.path().join("seed");
+        let repo = Repository::init(&seed).unwrap();
+        fs::write(seed.join("file.txt"), "hello\n").unwrap();
+        commit_all(&repo, "initial");
+        git(
+            temp_dir.path(),
+            &["clone", "--bare", "seed", "project/.bare"],
+        );
+        let project = temp_dir.path().join("project");
+        fs::write(project.join(".git"), "gitdir: ./.bare\n").unwrap();
+        git(&project, &["worktree", "add", "review"]);
+
+        assert!(matches!(
+            GitBackend::from_path(&project),
+            Err(TuicrError::BareRepository(_))
+        ));
+
+        let review = project.join("review");
+        fs::write(review.join("file.txt"), "hello again\n").unwrap();
+        let backend = GitBackend::from_path(&review).unwrap();
+        assert_eq!(
+            fs::canonicalize(&backend.info().root_path).unwrap(),
+            fs::canonicalize(&review).unwrap()
+        );
+        let diff = backend.get_working_tree_diff().unwrap();
+        assert_eq!(diff.len(), 1);
+        assert_eq!(diff[0].display_path(), Path::new("file.txt"));
+    }
 }
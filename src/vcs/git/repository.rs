@@ -1,8 +1,10 @@
 use chrono::{DateTime, TimeZone, Utc};
-use git2::{BranchType, Oid, Repository};
+use git2::{BlameOptions, BranchType, Oid, Repository};
 use std::collections::HashMap;
+use std::path::Path;
 
 use crate::error::{Result, TuicrError};
+use crate::vcs::traits::{BlameLine, BlameTarget, BranchInfo, DiffStat};
 
 #[derive(Debug, Clone)]
 pub struct CommitInfo {
@@ -188,3 +190,301 @@ pub fn resolve_revisions(repo: &Repository, revisions: &str) -> Result<Vec<Strin
     commit_ids.reverse();
     Ok(commit_ids)
 }
+
+/// Amending HEAD while changes are staged would be ambiguous about whether
+/// they should be folded in, so it is refused.
+/// Local branches with their tips. A branch's revisions start at its
+/// merge-base with the PR base (`origin/HEAD`, `main` or `master`); without
+/// a base only the tip is reviewed.
+pub fn list_branches(repo: &Repository) -> Result<Vec<BranchInfo>> {
+    let base = super::diff::resolve_base_reference(repo, None)
+        .ok()
+        .map(|(_, oid)| oid);
+    let mut branches = Vec::new();
+    for branch in repo.branches(Some(BranchType::Local))? {
+        let (branch, _) = branch?;
+        let Ok(Some(name)) = branch.name() else {
+            continue;
+        };
+        let tip = branch.get().peel_to_commit()?;
+        let revisions = match base.and_then(|base| repo.merge_base(base, tip.id()).ok()) {
+            Some(merge_base) => format!("{merge_base}..refs/heads/{name}"),
+            None => format!("refs/heads/{name}"),
+        };
+        let (summary, _) = parse_commit_message(tip.message().unwrap_or(""));
+        let id = tip.id().to_string();
+        branches.push(BranchInfo {
+            name: name.to_string(),
+            short_id: id[..7.min(id.len())].to_string(),
+            summary,
+            is_current: branch.is_head(),
+            revisions,
+        });
+    }
+    branches.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(branches)
+}
+
+fn ensure_nothing_staged(repo: &Repository) -> Result<()> {
+    let head_tree = repo.head()?.peel_to_tree()?;
+    let staged = repo.diff_tree_to_index(Some(&head_tree), None, None)?;
+    if staged.deltas().len() > 0 {
+        return Err(TuicrError::VcsCommand(
+            "Can't amend HEAD while there are staged changes".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Full message of HEAD, failing if there are staged changes.
+pub fn head_message(repo: &Repository) -> Result<String> {
+    ensure_nothing_staged(repo)?;
+    let head = repo.head()?.peel_to_commit()?;
+    Ok(head.message().unwrap_or_default().to_string())
+}
+
+/// Rewrite HEAD's message, keeping its tree, author and parents.
+pub fn amend_head_message(repo: &Repository, message: &str) -> Result<()> {
+    ensure_nothing_staged(repo)?;
+    let head = repo.head()?.peel_to_commit()?;
+    head.amend(Some("HEAD"), None, None, None, Some(message), None)?;
+    Ok(())
+}
+
+/// Files and lines changed by commit `id` relative to its first parent.
+pub fn commit_diffstat(repo: &Repository, id: &str) -> Result<DiffStat> {
+    let commit = repo.revparse_single(id)?.peel_to_commit()?;
+    let parent_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree()?),
+        Err(_) => None,
+    };
+    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+    let stats = diff.stats()?;
+    Ok(DiffStat {
+        files_changed: stats.files_changed(),
+        insertions: stats.insertions(),
+        deletions: stats.deletions(),
+    })
+}
+
+/// Id of the commit that last changed `line` (1-based) of `path`, or None
+/// for lines changed in the working tree.
+pub fn blame_line(
+    repo: &Repository,
+    path: &Path,
+    line: u32,
+    target: BlameTarget,
+) -> Result<Option<String>> {
+    let mut options = BlameOptions::new();
+    let newest = match target {
+        BlameTarget::WorkingCopy | BlameTarget::WorkingCopyParent => None,
+        BlameTarget::Commit(rev) => Some(repo.revparse_single(rev)?.peel_to_commit()?.id()),
+        BlameTarget::ParentOf(rev) => {
+            Some(repo.revparse_single(rev)?.peel_to_commit()?.parent_id(0)?)
+        }
+    };
+    if let Some(oid) = newest {
+        options.newest_commit(oid);
+    }
+    // Working tree edits shift line numbers, so the whole file is blamed then
+    if target != BlameTarget::WorkingCopy {
+        options.min_line(line as usize).max_line(line as usize);
+    }
+
+    let blame = repo.blame_file(path, Some(&mut options))?;
+    let working_blame;
+    let blame = if target == BlameTarget::WorkingCopy {
+        let workdir = repo.workdir().ok_or(TuicrError::NotARepository)?;
+        let contents = std::fs::read(workdir.join(path))?;
+        working_blame = blame.blame_buffer(&contents)?;
+        &working_blame
+    } else {
+        &blame
+    };
+
+    let hunk = blame
+        .get_line(line as usize)
+        .ok_or_else(|| TuicrError::VcsCommand(format!("No line {line} in {}", path.display())))?;
+    let id = hunk.final_commit_id();
+    Ok((!id.is_zero()).then(|| id.to_string()))
+}
+
+/// Commit and author of every line of `path` as of HEAD.
+pub fn blame_file(repo: &Repository, path: &Path) -> Result<Vec<BlameLine>> {
+    let blame = repo.blame_file(path, None)?;
+    let mut lines = Vec::new();
+    for hunk in blame.iter() {
+        let line = BlameLine {
+            commit_id: hunk.final_commit_id().to_string(),
+            author: String::from_utf8_lossy(hunk.final_signature().name_bytes()).into_owned(),
+        };
+        lines.extend(std::iter::repeat_n(line, hunk.lines_in_hunk()));
+    }
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn repo_with_commit(message: &str) -> (tempfile::TempDir, Repository) {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let repo = Repository::init(temp_dir.path()).expect("failed to init repo");
+        fs::write(temp_dir.path().join("file.txt"), "hello\n").unwrap();
+        {
+            let mut index = repo.index().unwrap();
+            index.add_path(Path::new("file.txt")).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &[])
+                .unwrap();
+        }
+        (temp_dir, repo)
+    }
+
+    #[test]
+    fn should_list_branches_with_their_range_from_the_merge_base() {
+        let (_dir, repo) = repo_with_commit("initial\n");
+        let initial = repo.head().unwrap().peel_to_commit().unwrap();
+        let head_branch = repo.head().unwrap().shorthand().unwrap().to_string();
+        repo.find_branch(&head_branch, BranchType::Local)
+            .unwrap()
+            .rename("main", true)
+            .unwrap();
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        let tree = initial.tree().unwrap();
+        let feature = repo
+            .commit(
+                Some("refs/heads/feature"),
+                &sig,
+                &sig,
+                "Add feature\n\nDetails",
+                &tree,
+                &[&initial],
+            )
+            .unwrap();
+
+        let branches = list_branches(&repo).unwrap();
+
+        let names: Vec<&str> = branches.iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(names, ["feature", "main"]);
+        assert_eq!(branches[0].summary, "Add feature");
+        assert_eq!(branches[0].short_id, feature.to_string()[..7]);
+        assert!(!branches[0].is_current);
+        assert!(branches[1].is_current);
+        assert_eq!(
+            resolve_revisions(&repo, &branches[0].revisions).unwrap(),
+            [feature.to_string()]
+        );
+        // main has no commits beyond its merge-base with itself
+        assert!(resolve_revisions(&repo, &branches[1].revisions).is_err());
+    }
+
+    #[test]
+    fn should_amend_head_message() {
+        let (_dir, repo) = repo_with_commit("initial\n");
+        let tree_before = repo.head().unwrap().peel_to_tree().unwrap().id();
+
+        assert_eq!(head_message(&repo).unwrap(), "initial\n");
+        amend_head_message(&repo, "Reworded\n\nWith a body\n").unwrap();
+
+        assert_eq!(head_message(&repo).unwrap(), "Reworded\n\nWith a body\n");
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.tree_id(), tree_before);
+        assert_eq!(head.parent_count(), 0);
+    }
+
+    #[test]
+    fn should_refuse_to_amend_with_staged_changes() {
+        let (dir, repo) = repo_with_commit("initial\n");
+        fs::write(dir.path().join("file.txt"), "changed\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("file.txt")).unwrap();
+        index.write().unwrap();
+
+        assert!(head_message(&repo).is_err());
+        assert!(amend_head_message(&repo, "Reworded").is_err());
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.message(), Some("initial\n"));
+    }
+
+    #[test]
+    fn should_blame_committed_and_uncommitted_lines() {
+        let (dir, repo) = repo_with_commit("initial\n");
+        let head = repo
+            .head()
+            .unwrap()
+            .peel_to_commit()
+            .unwrap()
+            .id()
+            .to_string();
+        fs::write(dir.path().join("file.txt"), "new\nhello\n").unwrap();
+        let path = Path::new("file.txt");
+
+        assert_eq!(
+            blame_line(&repo, path, 1, BlameTarget::WorkingCopy).unwrap(),
+            None
+        );
+        assert_eq!(
+            blame_line(&repo, path, 2, BlameTarget::WorkingCopy).unwrap(),
+            Some(head.clone())
+        );
+        assert_eq!(
+            blame_line(&repo, path, 1, BlameTarget::WorkingCopyParent).unwrap(),
+            Some(head.clone())
+        );
+        assert_eq!(
+            blame_line(&repo, path, 1, BlameTarget::Commit(&head)).unwrap(),
+            Some(head.clone())
+        );
+        assert_eq!(
+            blame_file(&repo, path).unwrap(),
+            [BlameLine {
+                commit_id: head,
+                author: "Test".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn should_count_files_and_lines_changed_by_a_commit() {
+        let (dir, repo) = repo_with_commit("initial\n");
+        let root = repo
+            .head()
+            .unwrap()
+            .peel_to_commit()
+            .unwrap()
+            .id()
+            .to_string();
+        assert_eq!(
+            commit_diffstat(&repo, &root).unwrap(),
+            DiffStat {
+                files_changed: 1,
+                insertions: 1,
+                deletions: 0,
+            }
+        );
+
+        fs::write(dir.path().join("file.txt"), "hi\nthere\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("file.txt")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let parent = repo.head().unwrap().peel_to_commit().unwrap();
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        let second = repo
+            .commit(Some("HEAD"), &sig, &sig, "change\n", &tree, &[&parent])
+            .unwrap();
+
+        assert_eq!(
+            commit_diffstat(&repo, &second.to_string()).unwrap(),
+            DiffStat {
+                files_changed: 1,
+                insertions: 2,
+                deletions: 1,
+            }
+        );
+    }
+}
@@ -1,7 +1,9 @@
-use git2::Repository;
+use git2::{ApplyLocation, ApplyOptions, Diff, DiffOptions, Patch, Repository};
+use std::fs;
 use std::path::Path;
 
-use crate::error::Result;
+use crate::error::{Result, TuicrError};
+use crate::model::DiffHunk;
 
 pub fn stage_file(repo: &Repository, path: &Path) -> Result<()> {
     let mut index = repo.index()?;
@@ -10,10 +12,117 @@ pub fn stage_file(repo: &Repository, path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Stage the unstaged changes overlapping `hunk`, whose new side is the working tree.
+pub fn stage_hunk(repo: &Repository, path: &Path, hunk: &DiffHunk) -> Result<()> {
+    let index = repo.index()?;
+    if index.get_path(path, 0).is_none() {
+        // Untracked files have no index entry to patch
+        return stage_file(repo, path);
+    }
+
+    let mut opts = DiffOptions::new();
+    opts.pathspec(path).disable_pathspec_match(true);
+    let diff = repo.diff_index_to_workdir(Some(&index), Some(&mut opts))?;
+
+    apply_hunks(repo, &diff, ApplyLocation::Index, |h| {
+        ranges_overlap(h.new_start(), h.new_lines(), hunk.new_start, hunk.new_count)
+    })
+}
+
+/// Unstage the staged changes overlapping `hunk`, whose old side is HEAD.
+pub fn unstage_hunk(repo: &Repository, path: &Path, hunk: &DiffHunk) -> Result<()> {
+    let head_tree = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+    let in_head = head_tree
+        .as_ref()
+        .is_some_and(|tree| tree.get_path(path).is_ok());
+    if !in_head {
+        // Files added in the index are unstaged by dropping the entry
+        let mut index = repo.index()?;
+        if index.get_path(path, 0).is_none() {
+            return Err(TuicrError::NoChanges);
+        }
+        index.remove_path(path)?;
+        index.write()?;
+        return Ok(());
+    }
+
+    let index = repo.index()?;
+    let mut opts = DiffOptions::new();
+    opts.pathspec(path)
+        .disable_pathspec_match(true)
+        .reverse(true);
+    let diff = repo.diff_tree_to_index(head_tree.as_ref(), Some(&index), Some(&mut opts))?;
+
+    // The diff is reversed, so its new side is HEAD
+    apply_hunks(repo, &diff, ApplyLocation::Index, |h| {
+        ranges_overlap(h.new_start(), h.new_lines(), hunk.old_start, hunk.old_count)
+    })
+}
+
+/// Revert the working tree changes overlapping `hunk`, a HEAD to working tree hunk.
+pub fn discard_hunk(repo: &Repository, path: &Path, hunk: &DiffHunk) -> Result<()> {
+    let head_tree = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+    let in_head = head_tree
+        .as_ref()
+        .is_some_and(|tree| tree.get_path(path).is_ok());
+    if !in_head && hunk.old_count == 0 {
+        // Discarding a new file deletes it
+        let workdir = repo.workdir().ok_or(TuicrError::NotARepository)?;
+        fs::remove_file(workdir.join(path))?;
+        return Ok(());
+    }
+
+    let mut opts = DiffOptions::new();
+    opts.pathspec(path)
+        .disable_pathspec_match(true)
+        .reverse(true);
+    let diff = repo.diff_tree_to_workdir_with_index(head_tree.as_ref(), Some(&mut opts))?;
+
+    // The diff is reversed, so its old side is the working tree
+    apply_hunks(repo, &diff, ApplyLocation::WorkDir, |h| {
+        ranges_overlap(h.old_start(), h.old_lines(), hunk.new_start, hunk.new_count)
+    })
+}
+
+/// Apply the hunks of `diff` accepted by `matches` to `location`.
+/// Returns `NoChanges` if no hunk matches.
+fn apply_hunks(
+    repo: &Repository,
+    diff: &Diff<'_>,
+    location: ApplyLocation,
+    matches: impl Fn(&git2::DiffHunk<'_>) -> bool,
+) -> Result<()> {
+    let mut any_match = false;
+    for delta_idx in 0..diff.deltas().len() {
+        let Some(patch) = Patch::from_diff(diff, delta_idx)? else {
+            continue;
+        };
+        for hunk_idx in 0..patch.num_hunks() {
+            let (hunk, _) = patch.hunk(hunk_idx)?;
+            any_match |= matches(&hunk);
+        }
+    }
+    if !any_match {
+        return Err(TuicrError::NoChanges);
+    }
+
+    let mut opts = ApplyOptions::new();
+    opts.hunk_callback(|hunk| hunk.as_ref().is_some_and(&matches));
+    repo.apply(diff, location, Some(&mut opts))?;
+    Ok(())
+}
+
+/// Whether two line ranges overlap. Pure insertions and deletions have a
+/// count of zero, so they are treated as covering one line.
+fn ranges_overlap(a_start: u32, a_count: u32, b_start: u32, b_count: u32) -> bool {
+    let a_end = a_start + a_count.max(1);
+    let b_end = b_start + b_count.max(1);
+    a_start < b_end && b_start < a_end
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs;
 
     #[test]
     fn stage_file_adds_to_index() {
@@ -28,4 +137,126 @@ mod tests {
         let index = repo.index().unwrap();
         assert!(index.get_path(Path::new("test.txt"), 0).is_some());
     }
+
+    const ORIGINAL: &str =
+        "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12\n13\n14\n15\n16\n17\n18\n19\n20\n";
+    const MODIFIED: &str =
+        "one\n2\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12\n13\n14\n15\n16\n17\n18\n19\ntwenty\n";
+
+    fn repo_with_commit(contents: &str) -> (tempfile::TempDir, Repository) {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let repo = Repository::init(temp_dir.path()).expect("failed to init repo");
+        fs::write(temp_dir.path().join("file.txt"), contents).unwrap();
+        {
+            let mut index = repo.index().unwrap();
+            index.add_path(Path::new("file.txt")).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "initial", &tree, &[])
+                .unwrap();
+        }
+        (temp_dir, repo)
+    }
+
+    fn hunk(old_start: u32, old_count: u32, new_start: u32, new_count: u32) -> DiffHunk {
+        DiffHunk {
+            header: String::new(),
+            lines: Vec::new(),
+            old_start,
+            old_count,
+            new_start,
+            new_count,
+        }
+    }
+
+    fn indexed_contents(repo: &Repository) -> String {
+        let index = repo.index().unwrap();
+        let entry = index.get_path(Path::new("file.txt"), 0).unwrap();
+        let blob = repo.find_blob(entry.id).unwrap();
+        String::from_utf8(blob.content().to_vec()).unwrap()
+    }
+
+    #[test]
+    fn stage_hunk_only_stages_the_selected_hunk() {
+        let (temp_dir, repo) = repo_with_commit(ORIGINAL);
+        fs::write(temp_dir.path().join("file.txt"), MODIFIED).unwrap();
+
+        stage_hunk(&repo, Path::new("file.txt"), &hunk(17, 4, 17, 4)).unwrap();
+
+        let staged = indexed_contents(&repo);
+        assert!(staged.starts_with("1\n"));
+        assert!(staged.ends_with("twenty\n"));
+
+        // The same hunk has nothing left to stage
+        let result = stage_hunk(&repo, Path::new("file.txt"), &hunk(17, 4, 17, 4));
+        assert!(matches!(result, Err(TuicrError::NoChanges)));
+    }
+
+    #[test]
+    fn unstage_hunk_restores_head_for_the_selected_hunk() {
+        let (temp_dir, repo) = repo_with_commit(ORIGINAL);
+        fs::write(temp_dir.path().join("file.txt"), MODIFIED).unwrap();
+        stage_file(&repo, Path::new("file.txt")).unwrap();
+
+        unstage_hunk(&repo, Path::new("file.txt"), &hunk(1, 4, 1, 4)).unwrap();
+
+        let staged = indexed_contents(&repo);
+        assert!(staged.starts_with("1\n"));
+        assert!(staged.ends_with("twenty\n"));
+    }
+
+    #[test]
+    fn stage_and_unstage_hunk_of_new_file() {
+        let (temp_dir, repo) = repo_with_commit(ORIGINAL);
+        fs::write(temp_dir.path().join("new.txt"), "new\n").unwrap();
+        let new_file = hunk(0, 0, 1, 1);
+
+        stage_hunk(&repo, Path::new("new.txt"), &new_file).unwrap();
+        assert!(
+            repo.index()
+                .unwrap()
+                .get_path(Path::new("new.txt"), 0)
+                .is_some()
+        );
+
+        unstage_hunk(&repo, Path::new("new.txt"), &new_file).unwrap();
+        assert!(
+            repo.index()
+                .unwrap()
+                .get_path(Path::new("new.txt"), 0)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn discard_hunk_reverts_only_the_selected_hunk() {
+        let (temp_dir, repo) = repo_with_commit(ORIGINAL);
+        let file_path = temp_dir.path().join("file.txt");
+        fs::write(&file_path, MODIFIED).unwrap();
+
+        discard_hunk(&repo, Path::new("file.txt"), &hunk(1, 4, 1, 4)).unwrap();
+
+        let content = fs::read_to_string(&file_path).unwrap();
+        assert!(content.starts_with("1\n"));
+        assert!(content.ends_with("twenty\n"));
+    }
+
+    #[test]
+    fn discard_hunk_deletes_new_file() {
+        let (temp_dir, repo) = repo_with_commit(ORIGINAL);
+        let new_path = temp_dir.path().join("new.txt");
+        fs::write(&new_path, "new\n").unwrap();
+
+        discard_hunk(&repo, Path::new("new.txt"), &hunk(0, 0, 1, 1)).unwrap();
+
+        assert!(!new_path.exists());
+    }
+
+    #[test]
+    fn ranges_overlap_treats_empty_ranges_as_one_line() {
+        assert!(ranges_overlap(5, 0, 5, 3));
+        assert!(ranges_overlap(1, 4, 4, 2));
+        assert!(!ranges_overlap(1, 4, 5, 2));
+    }
 }
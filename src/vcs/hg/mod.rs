@@ -1,14 +1,17 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use chrono::{TimeZone, Utc};
 
 use crate::error::{Result, TuicrError};
-use crate::model::{DiffFile, DiffLine, FileStatus, LineOrigin};
-use crate::syntax::SyntaxHighlighter;
+use crate::model::{DiffFile, DiffHunk, DiffLine, FileStatus, LineOrigin};
 use crate::vcs::diff_parser::{self, DiffFormat};
-use crate::vcs::traits::{CommitInfo, VcsBackend, VcsInfo, VcsType};
+use crate::vcs::preimage;
+use crate::vcs::traits::{
+    BlameLine, BlameTarget, BranchInfo, CommitInfo, DiffStat, PullRequestDiff, PullRequestInfo,
+    VcsBackend, VcsInfo, VcsType,
+};
 
 /// Parse an hg description into (summary, optional body).
 fn parse_hg_description(desc: &str) -> (String, Option<String>) {
@@ -29,40 +32,70 @@ fn parse_hg_description(desc: &str) -> (String, Option<String>) {
 /// Mercurial backend implementation using hg CLI commands
 pub struct HgBackend {
     info: VcsInfo,
+    /// Pass `--ignore-all-space` to `hg diff`
+    ignore_whitespace: bool,
+    /// Diff lines kept per file; the rest is loaded on request
+    max_file_lines: Option<usize>,
+    /// Files kept in full regardless of `max_file_lines`
+    fully_loaded: HashSet<PathBuf>,
+    /// Command used for every call: `hg`, or Sapling's `sl`
+    program: &'static str,
 }
 
+/// Commands tried in order when discovering a repository. Sapling installs
+/// as `sl`, and its repositories aren't recognized by Mercurial's `hg`.
+const HG_PROGRAMS: [&str; 2] = ["hg", "sl"];
+
 impl HgBackend {
-    /// Discover a Mercurial repository from the current directory
+    /// Discover a Mercurial or Sapling repository from the current directory
     pub fn discover() -> Result<Self> {
         // Use `hg root` to find the repository root
         // This handles being called from subdirectories
-        let root_output = Command::new("hg")
-            .args(["root"])
-            .output()
-            .map_err(|e| TuicrError::VcsCommand(format!("Failed to run hg: {}", e)))?;
-
-        if !root_output.status.success() {
-            return Err(TuicrError::NotARepository);
+        for program in HG_PROGRAMS {
+            // Not installed
+            let Ok(root_output) = Command::new(program).args(["root"]).output() else {
+                continue;
+            };
+            if root_output.status.success() {
+                let root_path = PathBuf::from(String::from_utf8_lossy(&root_output.stdout).trim());
+                return Self::from_path_with(program, root_path);
+            }
         }
 
-        let root_path = PathBuf::from(String::from_utf8_lossy(&root_output.stdout).trim());
-
-        Self::from_path(root_path)
+        Err(TuicrError::NotARepository)
     }
 
-    /// Create backend from a known path (used by discover and tests)
+    /// Create backend from a known path, running `hg` (used by tests)
+    #[cfg(test)]
     fn from_path(root_path: PathBuf) -> Result<Self> {
+        Self::from_path_with("hg", root_path)
+    }
+
+    /// Create backend from a known path, running `program` for every call
+    fn from_path_with(program: &'static str, root_path: PathBuf) -> Result<Self> {
         // Canonicalize to resolve symlinks (e.g., /var -> /private/var on macOS)
         let root_path = root_path.canonicalize().unwrap_or(root_path);
 
         // Get current revision info
-        let head_commit = run_hg_command(&root_path, &["id", "-i"])
+        let head_commit = run_hg_command(program, &root_path, &["id", "-i"])
             .map(|s| s.trim().trim_end_matches('+').to_string())
             .unwrap_or_else(|_| "unknown".to_string());
 
-        let branch_name = run_hg_command(&root_path, &["branch"])
-            .ok()
-            .map(|s| s.trim().to_string());
+        // Prefer the active bookmark, which most hg workflows use instead of
+        // named branches, and fall back to the named branch
+        let branch_name = run_hg_command(
+            program,
+            &root_path,
+            &["log", "-r", ".", "--template", "{activebookmark}"],
+        )
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .or_else(|| {
+            run_hg_command(program, &root_path, &["branch"])
+                .ok()
+                .map(|s| s.trim().to_string())
+        });
 
         let info = VcsInfo {
             root_path,
@@ -71,7 +104,29 @@ impl HgBackend {
             vcs_type: VcsType::Mercurial,
         };
 
-        Ok(Self { info })
+        Ok(Self {
+            info,
+            ignore_whitespace: false,
+            max_file_lines: None,
+            fully_loaded: HashSet::new(),
+            program,
+        })
+    }
+
+    /// Run `hg diff` with `args`, honoring the whitespace setting.
+    fn run_diff(&self, args: &[&str]) -> Result<String> {
+        let mut args = args.to_vec();
+        if self.ignore_whitespace {
+            args.push("--ignore-all-space");
+        }
+        run_hg_command(self.program, &self.info.root_path, &args)
+    }
+
+    /// Parse diff output, truncating files to `max_file_lines`.
+    fn parse_diff(&self, diff_output: &str) -> Result<Vec<DiffFile>> {
+        let mut files = diff_parser::parse_unified_diff(diff_output, DiffFormat::Hg)?;
+        diff_parser::truncate_files(&mut files, self.max_file_lines, &self.fully_loaded);
+        Ok(files)
     }
 }
 
@@ -80,15 +135,96 @@ impl VcsBackend for HgBackend {
         &self.info
     }
 
-    fn get_working_tree_diff(&self, highlighter: &SyntaxHighlighter) -> Result<Vec<DiffFile>> {
+    fn set_ignore_whitespace(&mut self, ignore: bool) -> bool {
+        self.ignore_whitespace = ignore;
+        true
+    }
+
+    fn set_max_file_lines(&mut self, max_lines: Option<usize>) {
+        self.max_file_lines = max_lines;
+    }
+
+    fn load_file_fully(&mut self, path: &Path) {
+        self.fully_loaded.insert(path.to_path_buf());
+    }
+
+    fn blame_line(&self, path: &Path, line: u32, target: BlameTarget) -> Result<Option<String>> {
+        let revision = match target {
+            BlameTarget::WorkingCopy => "wdir()".to_string(),
+            BlameTarget::WorkingCopyParent => ".".to_string(),
+            BlameTarget::Commit(id) => id.to_string(),
+            BlameTarget::ParentOf(id) => format!("p1({id})"),
+        };
+        let output = run_hg_command(
+            self.program,
+            &self.info.root_path,
+            &[
+                "annotate",
+                "-r",
+                &revision,
+                "-T",
+                "{lines % '{node}\\n'}",
+                &path.to_string_lossy(),
+            ],
+        )?;
+        let id = output
+            .lines()
+            .nth(line.saturating_sub(1) as usize)
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .ok_or_else(|| {
+                TuicrError::VcsCommand(format!("No line {line} in {}", path.display()))
+            })?;
+        // The working directory pseudo-revision has an all-f node
+        if id.bytes().all(|b| b == b'f') {
+            return Ok(None);
+        }
+        Ok(Some(id.to_string()))
+    }
+
+    fn blame_file(&self, path: &Path) -> Result<Vec<BlameLine>> {
+        let output = run_hg_command(
+            self.program,
+            &self.info.root_path,
+            &[
+                "annotate",
+                "-r",
+                ".",
+                "-T",
+                "{lines % '{node} {user|person}\\n'}",
+                &path.to_string_lossy(),
+            ],
+        )?;
+        Ok(output.lines().map(BlameLine::from_annotate_line).collect())
+    }
+
+    fn get_working_tree_diff(&self) -> Result<Vec<DiffFile>> {
         // Get unified diff output from hg
-        let diff_output = run_hg_command(&self.info.root_path, &["diff"])?;
+        let diff_output = self.run_diff(&["diff"])?;
 
         if diff_output.trim().is_empty() {
             return Err(TuicrError::NoChanges);
         }
 
-        diff_parser::parse_unified_diff(&diff_output, DiffFormat::Hg, highlighter)
+        let mut files = self.parse_diff(&diff_output)?;
+        // A plain `hg diff` can't tell a copy from an added file, or a
+        // rename from a delete and an add; status can
+        if let Ok(status) = run_hg_command(
+            self.program,
+            &self.info.root_path,
+            &["status", "-mard", "--copies", "-0"],
+        ) {
+            apply_hg_status(&mut files, &parse_hg_status(&status));
+        }
+        diff_parser::attach_binary_meta(&mut files, &self.info.root_path, |path| {
+            run_hg_command_raw(
+                self.program,
+                &self.info.root_path,
+                &["cat", "-r", ".", &path.to_string_lossy()],
+            )
+            .ok()
+        });
+        Ok(files)
     }
 
     fn fetch_context_lines(
@@ -106,6 +242,7 @@ impl VcsBackend for HgBackend {
             FileStatus::Deleted => {
                 // Read from hg cat (last committed version)
                 run_hg_command(
+                    self.program,
                     &self.info.root_path,
                     &["cat", "-r", ".", &file_path.to_string_lossy()],
                 )?
@@ -129,6 +266,8 @@ impl VcsBackend for HgBackend {
                     old_lineno: Some(line_num),
                     new_lineno: Some(line_num),
                     highlighted_spans: None,
+                    whitespace_only: false,
+                    move_id: None,
                 });
             }
         }
@@ -136,10 +275,43 @@ impl VcsBackend for HgBackend {
         Ok(result)
     }
 
+    fn list_branches(&self) -> Result<Vec<BranchInfo>> {
+        let root = &self.info.root_path;
+        // Bookmarks first, so they win over a named branch of the same name
+        let mut output = run_hg_command(
+            self.program,
+            root,
+            &[
+                "log",
+                "-r",
+                "bookmark()",
+                "--template",
+                "{bookmarks % '{bookmark}\t{node|short}\t{desc|firstline}\n'}",
+            ],
+        )?;
+        output.push_str(&run_hg_command(
+            self.program,
+            root,
+            &[
+                "log",
+                "-r",
+                "reverse(head() and not closed())",
+                "--template",
+                "{branch}\t{node|short}\t{desc|firstline}\n",
+            ],
+        )?);
+        Ok(BranchInfo::from_template_output(
+            &output,
+            self.info.branch_name.as_deref(),
+            |name| format!("only({name}, default)"),
+        ))
+    }
+
     fn resolve_revisions(&self, revisions: &str) -> Result<Vec<String>> {
         // Use hg log to resolve the revset to commit hashes.
         // hg log outputs newest first; we reverse so oldest is first.
         let output = run_hg_command(
+            self.program,
             &self.info.root_path,
             &["log", "-r", revisions, "--template", "{node}\\n"],
         )?;
@@ -170,6 +342,7 @@ impl VcsBackend for HgBackend {
         let template =
             "{node}\\x00{node|short}\\x00{desc}\\x00{author|user}\\x00{date|hgdate}\\x01";
         let output = run_hg_command(
+            self.program,
             &self.info.root_path,
             &[
                 "log",
@@ -219,11 +392,7 @@ impl VcsBackend for HgBackend {
         Ok(commits.into_iter().skip(offset).collect())
     }
 
-    fn get_commit_range_diff(
-        &self,
-        commit_ids: &[String],
-        highlighter: &SyntaxHighlighter,
-    ) -> Result<Vec<DiffFile>> {
+    fn get_commit_range_diff(&self, commit_ids: &[String]) -> Result<Vec<DiffFile>> {
         if commit_ids.is_empty() {
             return Err(TuicrError::NoChanges);
         }
@@ -251,6 +420,7 @@ impl VcsBackend for HgBackend {
         // First, get the parent commit of the oldest
         // We use "log -r 'parents({oldest})'" to get the parent hash
         let parent_output = run_hg_command(
+            self.program,
             &self.info.root_path,
             &[
                 "log",
@@ -267,16 +437,93 @@ impl VcsBackend for HgBackend {
             _ => "null".to_string(),
         };
 
-        let diff_output = run_hg_command(
+        let diff_output = self.run_diff(&["diff", "-r", &from_rev, "-r", newest_short])?;
+
+        if diff_output.trim().is_empty() {
+            return Err(TuicrError::NoChanges);
+        }
+
+        self.parse_diff(&diff_output)
+    }
+
+    fn commit_diffstat(&self, id: &str) -> Result<DiffStat> {
+        let output = run_hg_command(
+            self.program,
             &self.info.root_path,
-            &["diff", "-r", &from_rev, "-r", newest_short],
+            &["diff", "--stat", "-c", id],
         )?;
+        Ok(DiffStat::from_stat_output(&output))
+    }
 
-        if diff_output.trim().is_empty() {
+    fn get_pull_request_diff(&self, base_ref: Option<&str>) -> Result<PullRequestDiff> {
+        let root = &self.info.root_path;
+        // Without a base, compare against the last published changesets
+        let base_ref = base_ref.unwrap_or("heads(public())");
+
+        let merge_base_revset = format!("ancestor(., {base_ref})");
+        let merge_base = run_hg_command(
+            self.program,
+            root,
+            &["log", "-r", &merge_base_revset, "--template", "{node}"],
+        )
+        .ok()
+        .map(|node| node.trim().to_string())
+        .filter(|node| !node.is_empty())
+        .ok_or_else(|| {
+            TuicrError::VcsCommand(format!(
+                "Failed to find merge-base between {base_ref} and ."
+            ))
+        })?;
+        let head = run_hg_command(
+            self.program,
+            root,
+            &["log", "-r", ".", "--template", "{node}"],
+        )?
+        .trim()
+        .to_string();
+
+        if merge_base == head {
             return Err(TuicrError::NoChanges);
         }
 
-        diff_parser::parse_unified_diff(&diff_output, DiffFormat::Hg, highlighter)
+        // The range includes the merge-base itself
+        let commit_count = run_hg_command(
+            self.program,
+            root,
+            &[
+                "log",
+                "-r",
+                &format!("{merge_base_revset}::."),
+                "--template",
+                "x\\n",
+            ],
+        )?
+        .lines()
+        .count()
+        .saturating_sub(1);
+
+        // Use short hashes for Sapling compatibility, like get_commit_range_diff
+        let diff_output = self.run_diff(&[
+            "diff",
+            "-r",
+            merge_base.get(..12).unwrap_or(&merge_base),
+            "-r",
+            head.get(..12).unwrap_or(&head),
+        ])?;
+        if diff_output.trim().is_empty() {
+            return Err(TuicrError::NoChanges);
+        }
+        let files = self.parse_diff(&diff_output)?;
+
+        Ok(PullRequestDiff {
+            files,
+            info: PullRequestInfo {
+                base_ref: base_ref.to_string(),
+                merge_base_commit: merge_base,
+                head_commit: head,
+                commit_count,
+            },
+        })
     }
 
     fn get_commits_info(&self, ids: &[String]) -> Result<Vec<CommitInfo>> {
@@ -298,6 +545,7 @@ impl VcsBackend for HgBackend {
         let template =
             "{node}\\x00{node|short}\\x00{desc}\\x00{author|user}\\x00{date|hgdate}\\x01";
         let output = run_hg_command(
+            self.program,
             &self.info.root_path,
             &["log", "-r", &revset, "--template", template],
         )?;
@@ -340,11 +588,7 @@ impl VcsBackend for HgBackend {
         Ok(ids.iter().filter_map(|id| by_id.remove(id)).collect())
     }
 
-    fn get_working_tree_with_commits_diff(
-        &self,
-        commit_ids: &[String],
-        highlighter: &SyntaxHighlighter,
-    ) -> Result<Vec<DiffFile>> {
+    fn get_working_tree_with_commits_diff(&self, commit_ids: &[String]) -> Result<Vec<DiffFile>> {
         if commit_ids.is_empty() {
             return Err(TuicrError::NoChanges);
         }
@@ -359,6 +603,7 @@ impl VcsBackend for HgBackend {
 
         // Get the parent of the oldest commit
         let parent_output = run_hg_command(
+            self.program,
             &self.info.root_path,
             &[
                 "log",
@@ -375,34 +620,130 @@ impl VcsBackend for HgBackend {
         };
 
         // Diff from parent of oldest to working directory (omit --to)
-        let diff_output = run_hg_command(&self.info.root_path, &["diff", "-r", &from_rev])?;
+        let diff_output = self.run_diff(&["diff", "-r", &from_rev])?;
 
         if diff_output.trim().is_empty() {
             return Err(TuicrError::NoChanges);
         }
 
-        diff_parser::parse_unified_diff(&diff_output, DiffFormat::Hg, highlighter)
+        self.parse_diff(&diff_output)
+    }
+
+    fn discard_hunk(&self, path: &Path, hunk: &DiffHunk) -> Result<()> {
+        preimage::restore_hunk_preimage(&self.info.root_path, path, hunk)
+    }
+}
+
+/// One entry of `hg status --copies`: the status letter, the path, and the
+/// path it was copied or renamed from.
+#[derive(Debug, PartialEq)]
+struct HgStatusEntry {
+    status: char,
+    path: PathBuf,
+    source: Option<PathBuf>,
+}
+
+/// Parse `hg status --copies -0` output: `X path` entries, each followed by
+/// `  source` when the file was copied or renamed.
+fn parse_hg_status(output: &str) -> Vec<HgStatusEntry> {
+    let mut entries: Vec<HgStatusEntry> = Vec::new();
+    for field in output.split('\0').filter(|field| !field.is_empty()) {
+        if let Some(source) = field.strip_prefix("  ") {
+            if let Some(entry) = entries.last_mut() {
+                entry.source = Some(PathBuf::from(source));
+            }
+            continue;
+        }
+        let mut chars = field.chars();
+        let (Some(status), Some(' ')) = (chars.next(), chars.next()) else {
+            continue;
+        };
+        entries.push(HgStatusEntry {
+            status,
+            path: PathBuf::from(chars.as_str()),
+            source: None,
+        });
     }
+    entries
 }
 
-/// Run an hg command and return its stdout
-fn run_hg_command(root: &Path, args: &[&str]) -> Result<String> {
-    let output = Command::new("hg")
+/// Correct the statuses guessed from diff text with `hg status`. Copies get
+/// their source, renames replace the deletion of their source, and files
+/// removed with plain `rm` (`!`) count as deleted. Files only one side
+/// knows about are left as they are.
+fn apply_hg_status(files: &mut Vec<DiffFile>, entries: &[HgStatusEntry]) {
+    let removed: HashSet<&Path> = entries
+        .iter()
+        .filter(|entry| entry.status == 'R')
+        .map(|entry| entry.path.as_path())
+        .collect();
+    let mut rename_sources: HashSet<PathBuf> = HashSet::new();
+
+    for entry in entries {
+        let Some(file) = files
+            .iter_mut()
+            .find(|file| *file.display_path() == entry.path)
+        else {
+            continue;
+        };
+        match (entry.status, &entry.source) {
+            ('A', Some(source)) => {
+                file.status = if removed.contains(source.as_path()) {
+                    rename_sources.insert(source.clone());
+                    FileStatus::Renamed
+                } else {
+                    FileStatus::Copied
+                };
+                file.old_path = Some(source.clone());
+            }
+            ('A', None) => {
+                file.status = FileStatus::Added;
+                file.old_path = None;
+            }
+            ('R' | '!', _) => {
+                file.status = FileStatus::Deleted;
+                file.old_path = Some(entry.path.clone());
+                file.new_path = None;
+            }
+            ('M', _) => file.status = FileStatus::Modified,
+            _ => {}
+        }
+    }
+
+    // A plain diff lists a rename's source as a separate deletion
+    files.retain(|file| {
+        file.status != FileStatus::Deleted
+            || !file
+                .old_path
+                .as_ref()
+                .is_some_and(|path| rename_sources.contains(path))
+    });
+}
+
+/// Run an hg (or `sl`) command and return its stdout
+fn run_hg_command(program: &str, root: &Path, args: &[&str]) -> Result<String> {
+    run_hg_command_raw(program, root, args)
+        .map(|stdout| String::from_utf8_lossy(&stdout).to_string())
+}
+
+/// Like [`run_hg_command`], but returns stdout as bytes, e.g. for binary file contents.
+fn run_hg_command_raw(program: &str, root: &Path, args: &[&str]) -> Result<Vec<u8>> {
+    let output = Command::new(program)
         .current_dir(root)
         .args(args)
         .output()
-        .map_err(|e| TuicrError::VcsCommand(format!("Failed to run hg: {}", e)))?;
+        .map_err(|e| TuicrError::VcsCommand(format!("Failed to run {program}: {}", e)))?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         return Err(TuicrError::VcsCommand(format!(
-            "hg {} failed: {}",
+            "{program} {} failed: {}",
             args.join(" "),
             stderr
         )));
     }
 
-    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    Ok(output.stdout)
 }
 
 #[cfg(test)]
@@ -419,6 +760,71 @@ mod tests {
             .unwrap_or(false)
     }
 
+    #[test]
+    fn should_correct_diff_statuses_with_hg_status() {
+        let diff = "diff -r abc123 dest.txt
+--- /dev/null
++++ b/dest.txt
+@@ -0,0 +1,1 @@
++source content
+diff -r abc123 original.txt
+--- a/original.txt
++++ /dev/null
+@@ -1,1 +0,0 @@
+-file content
+diff -r abc123 renamed.txt
+--- /dev/null
++++ b/renamed.txt
+@@ -0,0 +1,1 @@
++file content
+diff -r abc123 notes.txt
+--- a/notes.txt
++++ b/notes.txt
+@@ -1,2 +1,1 @@
+ keep
+-drop
+";
+        let mut files = diff_parser::parse_unified_diff(diff, DiffFormat::Hg).unwrap();
+        let status = "A dest.txt\0  source.txt\0A renamed.txt\0  original.txt\0\
+                      R original.txt\0! notes.txt\0M unlisted.txt\0";
+
+        let entries = parse_hg_status(status);
+        assert_eq!(
+            entries[0],
+            HgStatusEntry {
+                status: 'A',
+                path: PathBuf::from("dest.txt"),
+                source: Some(PathBuf::from("source.txt")),
+            }
+        );
+        assert_eq!(entries.len(), 5);
+
+        apply_hg_status(&mut files, &entries);
+
+        let summary: Vec<_> = files
+            .iter()
+            .map(|file| {
+                (
+                    file.status,
+                    file.old_path.as_deref().and_then(Path::to_str),
+                    file.new_path.as_deref().and_then(Path::to_str),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (FileStatus::Copied, Some("source.txt"), Some("dest.txt")),
+                (
+                    FileStatus::Renamed,
+                    Some("original.txt"),
+                    Some("renamed.txt")
+                ),
+                (FileStatus::Deleted, Some("notes.txt"), None),
+            ]
+        );
+    }
+
     /// Discover a Mercurial repository from a specific directory
     fn discover_in(path: &Path) -> Result<HgBackend> {
         let root_output = Command::new("hg")
@@ -494,6 +900,98 @@ mod tests {
         assert!(!info.head_commit.is_empty());
     }
 
+    /// Check if Sapling's `sl` command is available
+    fn sl_available() -> bool {
+        Command::new("sl")
+            .arg("--version")
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    }
+
+    #[test]
+    fn test_sl_backend_runs_sl() {
+        if !sl_available() {
+            eprintln!("Skipping test: sl command not available");
+            return;
+        }
+
+        let temp = tempfile::tempdir().expect("Failed to create temp dir");
+        let root = temp.path();
+        let sl = |args: &[&str]| {
+            Command::new("sl")
+                .args(args)
+                .args(["--config", "ui.username=Test <test@example.com>"])
+                .current_dir(root)
+                .output()
+                .expect("Failed to run sl")
+        };
+        if !sl(&["init"]).status.success() {
+            eprintln!("Skipping test: sl init failed");
+            return;
+        }
+        fs::write(root.join("hello.txt"), "hello world\n").expect("Failed to write file");
+        sl(&["add", "hello.txt"]);
+        sl(&["commit", "-m", "Initial commit"]);
+        fs::write(root.join("hello.txt"), "hello world\nmodified line\n")
+            .expect("Failed to modify file");
+
+        let backend = HgBackend::from_path_with("sl", root.to_path_buf())
+            .expect("Failed to create sl backend");
+        assert_eq!(backend.program, "sl");
+        assert_eq!(backend.info().vcs_type, VcsType::Mercurial);
+
+        let files = backend.get_working_tree_diff().expect("Failed to get diff");
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].new_path.as_deref(), Some(Path::new("hello.txt")));
+    }
+
+    #[test]
+    fn test_hg_branch_name_prefers_active_bookmark() {
+        let Some(temp) = setup_test_repo() else {
+            eprintln!("Skipping test: hg command not available");
+            return;
+        };
+
+        let backend =
+            HgBackend::from_path(temp.path().to_path_buf()).expect("Failed to create hg backend");
+        assert_eq!(backend.info().branch_name.as_deref(), Some("default"));
+
+        // `hg bookmark` creates the bookmark and activates it
+        Command::new("hg")
+            .args(["bookmark", "feature-x"])
+            .current_dir(temp.path())
+            .output()
+            .expect("Failed to create bookmark");
+
+        let backend =
+            HgBackend::from_path(temp.path().to_path_buf()).expect("Failed to create hg backend");
+        assert_eq!(backend.info().branch_name.as_deref(), Some("feature-x"));
+    }
+
+    #[test]
+    fn test_hg_list_branches() {
+        let Some(temp) = setup_test_repo_with_commits() else {
+            eprintln!("Skipping test: hg command not available");
+            return;
+        };
+        Command::new("hg")
+            .args(["bookmark", "feature-x"])
+            .current_dir(temp.path())
+            .output()
+            .expect("Failed to create bookmark");
+
+        let backend =
+            HgBackend::from_path(temp.path().to_path_buf()).expect("Failed to create hg backend");
+        let branches = backend.list_branches().expect("Failed to list branches");
+
+        let names: Vec<&str> = branches.iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(names, ["default", "feature-x"]);
+        assert!(branches.iter().all(|b| b.summary == "Third commit"));
+        assert!(branches[1].is_current);
+        assert_eq!(branches[1].revisions, "only(\"feature-x\", default)");
+    }
+
     #[test]
     fn test_hg_working_tree_diff() {
         let Some(temp) = setup_test_repo() else {
@@ -510,9 +1008,7 @@ mod tests {
         assert_eq!(backend.info().root_path, expected_path);
         assert_eq!(backend.info().vcs_type, VcsType::Mercurial);
 
-        let files = backend
-            .get_working_tree_diff(&SyntaxHighlighter::default())
-            .expect("Failed to get diff");
+        let files = backend.get_working_tree_diff().expect("Failed to get diff");
 
         assert_eq!(files.len(), 1);
         assert_eq!(
@@ -646,7 +1142,7 @@ mod tests {
 
         // Get diff for the last two commits (Second and Third)
         let commit_ids = vec![commits[1].id.clone(), commits[0].id.clone()];
-        let diff_result = backend.get_commit_range_diff(&commit_ids, &SyntaxHighlighter::default());
+        let diff_result = backend.get_commit_range_diff(&commit_ids);
 
         // Note: Sapling (Meta's hg fork) may fail with "id_dag_snapshot()" error
         // in certain temporary directory configurations. Skip the test in that case.
@@ -681,6 +1177,46 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_hg_pull_request_diff() {
+        let Some(temp) = setup_test_repo_with_commits() else {
+            eprintln!("Skipping test: hg command not available");
+            return;
+        };
+
+        let backend =
+            HgBackend::from_path(temp.path().to_path_buf()).expect("Failed to create hg backend");
+        let commits = backend
+            .get_recent_commits(0, 5)
+            .expect("Failed to get commits");
+        let first = &commits[2].id;
+
+        let pr_diff = match backend.get_pull_request_diff(Some(first)) {
+            Ok(d) => d,
+            Err(TuicrError::VcsCommand(msg)) if msg.contains("id_dag_snapshot") => {
+                eprintln!("Skipping test: Sapling-specific issue with tempdir repos");
+                return;
+            }
+            Err(e) => panic!("Failed to get PR diff: {:?}", e),
+        };
+
+        assert_eq!(pr_diff.info.base_ref, *first);
+        assert_eq!(pr_diff.info.merge_base_commit, *first);
+        assert_eq!(pr_diff.info.head_commit, commits[0].id);
+        assert_eq!(pr_diff.info.commit_count, 2);
+
+        let mut file_paths: Vec<_> = pr_diff
+            .files
+            .iter()
+            .filter_map(|f| f.new_path.as_ref().map(|p| p.to_string_lossy().to_string()))
+            .collect();
+        file_paths.sort();
+        assert_eq!(file_paths, ["file1.txt", "file2.txt"]);
+
+        // Nothing is published in a fresh repo, so there's no default base
+        assert!(backend.get_pull_request_diff(None).is_err());
+    }
+
     /// Create a test repo with a renamed file (no content changes).
     fn setup_test_repo_with_rename() -> Option<tempfile::TempDir> {
         if !hg_available() {
@@ -730,9 +1266,7 @@ mod tests {
         let backend =
             HgBackend::from_path(temp.path().to_path_buf()).expect("Failed to create hg backend");
 
-        let files = backend
-            .get_working_tree_diff(&SyntaxHighlighter::default())
-            .expect("Failed to get diff");
+        let files = backend.get_working_tree_diff().expect("Failed to get diff");
 
         // hg should show the rename
         assert!(!files.is_empty(), "Expected at least one file change");
@@ -748,10 +1282,14 @@ mod tests {
                 .as_ref()
                 .is_some_and(|p| p.to_str() == Some("renamed.txt"))
         });
-        assert!(
-            renamed_file.is_some(),
-            "Expected to find renamed.txt in diff"
+        let renamed_file = renamed_file.expect("Expected to find renamed.txt in diff");
+        assert_eq!(renamed_file.status, FileStatus::Renamed);
+        assert_eq!(
+            renamed_file.old_path.as_deref(),
+            Some(Path::new("original.txt"))
         );
+        // The source isn't listed again as a deletion
+        assert_eq!(files.len(), 1);
     }
 
     /// Create a test repo with a copied file.
@@ -803,9 +1341,7 @@ mod tests {
         let backend =
             HgBackend::from_path(temp.path().to_path_buf()).expect("Failed to create hg backend");
 
-        let files = backend
-            .get_working_tree_diff(&SyntaxHighlighter::default())
-            .expect("Failed to get diff");
+        let files = backend.get_working_tree_diff().expect("Failed to get diff");
 
         assert!(!files.is_empty(), "Expected at least one file change");
 
@@ -820,7 +1356,30 @@ mod tests {
                 .as_ref()
                 .is_some_and(|p| p.to_str() == Some("dest.txt"))
         });
-        assert!(copied_file.is_some(), "Expected to find dest.txt in diff");
+        let copied_file = copied_file.expect("Expected to find dest.txt in diff");
+        assert_eq!(copied_file.status, FileStatus::Copied);
+        assert_eq!(
+            copied_file.old_path.as_deref(),
+            Some(Path::new("source.txt"))
+        );
+    }
+
+    #[test]
+    fn test_hg_file_removed_without_hg_remove() {
+        let Some(temp) = setup_test_repo() else {
+            eprintln!("Skipping test: hg command not available");
+            return;
+        };
+        fs::remove_file(temp.path().join("hello.txt")).expect("Failed to remove file");
+
+        let backend =
+            HgBackend::from_path(temp.path().to_path_buf()).expect("Failed to create hg backend");
+        let files = backend.get_working_tree_diff().expect("Failed to get diff");
+
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].status, FileStatus::Deleted);
+        assert_eq!(files[0].old_path.as_deref(), Some(Path::new("hello.txt")));
+        assert!(files[0].new_path.is_none());
     }
 
     /// Create a test repo with a binary file.
@@ -863,9 +1422,7 @@ mod tests {
         let backend =
             HgBackend::from_path(temp.path().to_path_buf()).expect("Failed to create hg backend");
 
-        let files = backend
-            .get_working_tree_diff(&SyntaxHighlighter::default())
-            .expect("Failed to get diff");
+        let files = backend.get_working_tree_diff().expect("Failed to get diff");
 
         assert_eq!(files.len(), 1, "Expected one file");
 
@@ -901,9 +1458,7 @@ mod tests {
         let backend =
             HgBackend::from_path(temp.path().to_path_buf()).expect("Failed to create hg backend");
 
-        let files = backend
-            .get_working_tree_diff(&SyntaxHighlighter::default())
-            .expect("Failed to get diff");
+        let files = backend.get_working_tree_diff().expect("Failed to get diff");
 
         assert_eq!(files.len(), 1, "Expected one file");
 
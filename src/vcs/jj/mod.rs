@@ -1,16 +1,20 @@
 //! Jujutsu (jj) backend implementation using CLI commands.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Command, Output, Stdio};
 
 use chrono::{DateTime, Utc};
 
 use crate::error::{Result, TuicrError};
-use crate::model::{DiffFile, DiffLine, FileStatus, LineOrigin};
-use crate::syntax::SyntaxHighlighter;
+use crate::model::{DiffFile, DiffHunk, DiffLine, FileStatus, LineOrigin};
 use crate::vcs::diff_parser::{self, DiffFormat};
-use crate::vcs::traits::{CommitInfo, VcsBackend, VcsInfo, VcsType};
+use crate::vcs::preimage;
+use crate::vcs::traits::{
+    BlameLine, BlameTarget, BranchInfo, CommitInfo, DiffStat, PullRequestDiff, PullRequestInfo,
+    VcsBackend, VcsInfo, VcsType,
+};
 
 /// Parse a jj description into (summary, optional body).
 fn parse_description(desc: &str) -> (String, Option<String>) {
@@ -31,6 +35,12 @@ fn parse_description(desc: &str) -> (String, Option<String>) {
 /// Jujutsu backend implementation using jj CLI commands
 pub struct JjBackend {
     info: VcsInfo,
+    /// Pass `--ignore-all-space` to `jj diff`
+    ignore_whitespace: bool,
+    /// Diff lines kept per file; the rest is loaded on request
+    max_file_lines: Option<usize>,
+    /// Files kept in full regardless of `max_file_lines`
+    fully_loaded: HashSet<PathBuf>,
 }
 
 impl JjBackend {
@@ -53,7 +63,7 @@ impl JjBackend {
     }
 
     /// Create backend from a known path (used by discover and tests)
-    fn from_path(root_path: PathBuf) -> Result<Self> {
+    pub(crate) fn from_path(root_path: PathBuf) -> Result<Self> {
         // Canonicalize to resolve symlinks (e.g., /var -> /private/var on macOS)
         let root_path = root_path.canonicalize().unwrap_or(root_path);
 
@@ -108,7 +118,28 @@ impl JjBackend {
             vcs_type: VcsType::Jujutsu,
         };
 
-        Ok(Self { info })
+        Ok(Self {
+            info,
+            ignore_whitespace: false,
+            max_file_lines: None,
+            fully_loaded: HashSet::new(),
+        })
+    }
+
+    /// Run `jj diff` with `args`, honoring the whitespace setting.
+    fn run_diff(&self, args: &[&str]) -> Result<String> {
+        let mut args = args.to_vec();
+        if self.ignore_whitespace {
+            args.push("--ignore-all-space");
+        }
+        run_jj_command(&self.info.root_path, &args)
+    }
+
+    /// Parse diff output, truncating files to `max_file_lines`.
+    fn parse_diff(&self, diff_output: &str) -> Result<Vec<DiffFile>> {
+        let mut files = diff_parser::parse_unified_diff(diff_output, DiffFormat::GitStyle)?;
+        diff_parser::truncate_files(&mut files, self.max_file_lines, &self.fully_loaded);
+        Ok(files)
     }
 }
 
@@ -117,15 +148,113 @@ impl VcsBackend for JjBackend {
         &self.info
     }
 
-    fn get_working_tree_diff(&self, highlighter: &SyntaxHighlighter) -> Result<Vec<DiffFile>> {
+    fn set_ignore_whitespace(&mut self, ignore: bool) -> bool {
+        self.ignore_whitespace = ignore;
+        true
+    }
+
+    fn set_max_file_lines(&mut self, max_lines: Option<usize>) {
+        self.max_file_lines = max_lines;
+    }
+
+    fn load_file_fully(&mut self, path: &Path) {
+        self.fully_loaded.insert(path.to_path_buf());
+    }
+
+    fn blame_line(&self, path: &Path, line: u32, target: BlameTarget) -> Result<Option<String>> {
+        let revision = match target {
+            BlameTarget::WorkingCopy => "@".to_string(),
+            BlameTarget::WorkingCopyParent => "@-".to_string(),
+            BlameTarget::Commit(id) => id.to_string(),
+            BlameTarget::ParentOf(id) => format!("{id}-"),
+        };
+        let output = run_jj_command(
+            &self.info.root_path,
+            &[
+                "file",
+                "annotate",
+                "-r",
+                &revision,
+                "-T",
+                r#"commit.commit_id() ++ "\n""#,
+                &path.to_string_lossy(),
+            ],
+        )?;
+        let id = output
+            .lines()
+            .nth(line.saturating_sub(1) as usize)
+            .map(|id| id.trim().to_string())
+            .filter(|id| !id.is_empty())
+            .ok_or_else(|| {
+                TuicrError::VcsCommand(format!("No line {line} in {}", path.display()))
+            })?;
+
+        // Lines last changed in the working-copy commit aren't committed yet
+        if target == BlameTarget::WorkingCopy {
+            let working_copy = run_jj_command(
+                &self.info.root_path,
+                &["log", "-r", "@", "--no-graph", "-T", "commit_id"],
+            )?;
+            if working_copy.trim() == id {
+                return Ok(None);
+            }
+        }
+        Ok(Some(id))
+    }
+
+    fn blame_file(&self, path: &Path) -> Result<Vec<BlameLine>> {
+        let output = run_jj_command(
+            &self.info.root_path,
+            &[
+                "file",
+                "annotate",
+                "-r",
+                "@-",
+                "-T",
+                r#"commit.commit_id() ++ " " ++ commit.author().name() ++ "\n""#,
+                &path.to_string_lossy(),
+            ],
+        )?;
+        Ok(output.lines().map(BlameLine::from_annotate_line).collect())
+    }
+
+    fn commit_message(&self) -> Result<String> {
+        run_jj_command(
+            &self.info.root_path,
+            &["log", "-r", "@", "--no-graph", "-T", "description"],
+        )
+    }
+
+    fn set_commit_message(&self, message: &str) -> Result<()> {
+        run_jj_command_with_stdin(&self.info.root_path, &["describe", "--stdin"], message)
+            .map(|_| ())
+    }
+
+    fn get_working_tree_diff(&self) -> Result<Vec<DiffFile>> {
         // Get unified diff output from jj using --git format
-        let diff_output = run_jj_command(&self.info.root_path, &["diff", "--git"])?;
+        let diff_output = self.run_diff(&["diff", "--git"])?;
 
         if diff_output.trim().is_empty() {
             return Err(TuicrError::NoChanges);
         }
 
-        diff_parser::parse_unified_diff(&diff_output, DiffFormat::GitStyle, highlighter)
+        let mut files = self.parse_diff(&diff_output)?;
+
+        // `jj resolve --list` exits with an error when there are no conflicts
+        if let Ok(output) = run_jj_command(&self.info.root_path, &["resolve", "--list"]) {
+            let conflicted = parse_resolve_list(&output);
+            for file in &mut files {
+                file.is_conflicted = conflicted.contains(file.display_path());
+            }
+        }
+        diff_parser::attach_binary_meta(&mut files, &self.info.root_path, |path| {
+            run_jj_command_raw(
+                &self.info.root_path,
+                &["file", "show", "-r", "@-", &path.to_string_lossy()],
+            )
+            .ok()
+        });
+        Ok(files)
     }
 
     fn fetch_context_lines(
@@ -166,6 +295,8 @@ impl VcsBackend for JjBackend {
                     old_lineno: Some(line_num),
                     new_lineno: Some(line_num),
                     highlighted_spans: None,
+                    whitespace_only: false,
+                    move_id: None,
                 });
             }
         }
@@ -173,6 +304,24 @@ impl VcsBackend for JjBackend {
         Ok(result)
     }
 
+    fn list_branches(&self) -> Result<Vec<BranchInfo>> {
+        // Local bookmarks only; remote-tracking rows have `remote` set
+        let output = run_jj_command(
+            &self.info.root_path,
+            &[
+                "bookmark",
+                "list",
+                "-T",
+                r#"if(remote, "", name ++ "\t" ++ if(normal_target, normal_target.commit_id().short() ++ "\t" ++ normal_target.description().first_line()) ++ "\n")"#,
+            ],
+        )?;
+        Ok(BranchInfo::from_template_output(
+            &output,
+            self.info.branch_name.as_deref(),
+            |name| format!("trunk()..{name}"),
+        ))
+    }
+
     fn resolve_revisions(&self, revisions: &str) -> Result<Vec<String>> {
         // Use jj log to resolve the revisions to commit IDs, reverse-chronological by default.
         // We reverse the result so the oldest commit is first (matching get_commit_range_diff expectations).
@@ -263,11 +412,7 @@ impl VcsBackend for JjBackend {
         Ok(commits.into_iter().skip(offset).collect())
     }
 
-    fn get_commit_range_diff(
-        &self,
-        commit_ids: &[String],
-        highlighter: &SyntaxHighlighter,
-    ) -> Result<Vec<DiffFile>> {
+    fn get_commit_range_diff(&self, commit_ids: &[String]) -> Result<Vec<DiffFile>> {
         if commit_ids.is_empty() {
             return Err(TuicrError::NoChanges);
         }
@@ -278,23 +423,107 @@ impl VcsBackend for JjBackend {
 
         // Get the parent of the oldest commit to include its changes
         // In jj, we use {commit}- to get the parent(s)
-        let diff_output = run_jj_command(
-            &self.info.root_path,
+        let diff_output = self.run_diff(&[
+            "diff",
+            "--from",
+            &format!("{}-", oldest),
+            "--to",
+            newest,
+            "--git",
+        ])?;
+
+        if diff_output.trim().is_empty() {
+            return Err(TuicrError::NoChanges);
+        }
+
+        self.parse_diff(&diff_output)
+    }
+
+    fn commit_diffstat(&self, id: &str) -> Result<DiffStat> {
+        let output = run_jj_command(&self.info.root_path, &["diff", "--stat", "-r", id])?;
+        Ok(DiffStat::from_stat_output(&output))
+    }
+
+    fn get_pull_request_diff(&self, base_ref: Option<&str>) -> Result<PullRequestDiff> {
+        let root = &self.info.root_path;
+
+        // The closest ancestor of @ that is on the base: the given revset, or
+        // else the nearest bookmark or trunk()
+        let merge_base_revset = match base_ref {
+            Some(base) => format!("heads(::@ & ::({base}))"),
+            None => "heads(::@ & (bookmarks() | trunk()))".to_string(),
+        };
+        let merge_base = run_jj_command(
+            root,
+            &[
+                "log",
+                "-r",
+                &merge_base_revset,
+                "--no-graph",
+                "-T",
+                r#"commit_id ++ "\n""#,
+            ],
+        )?
+        .lines()
+        .next()
+        .map(str::to_string)
+        .filter(|id| !id.is_empty())
+        .ok_or_else(|| {
+            TuicrError::VcsCommand(format!("Failed to find merge-base for {merge_base_revset}"))
+        })?;
+        let head = run_jj_command(root, &["log", "-r", "@", "--no-graph", "-T", "commit_id"])?
+            .trim()
+            .to_string();
+
+        let commit_count = run_jj_command(
+            root,
             &[
-                "diff",
-                "--from",
-                &format!("{}-", oldest),
-                "--to",
-                newest,
-                "--git",
+                "log",
+                "-r",
+                // An empty working-copy commit isn't part of the change
+                &format!("{merge_base}..@ ~ (@ & empty())"),
+                "--no-graph",
+                "-T",
+                r#""x\n""#,
             ],
-        )?;
+        )?
+        .lines()
+        .count();
+        if commit_count == 0 {
+            return Err(TuicrError::NoChanges);
+        }
 
+        let diff_output = self.run_diff(&["diff", "--from", &merge_base, "--to", "@", "--git"])?;
         if diff_output.trim().is_empty() {
             return Err(TuicrError::NoChanges);
         }
+        let files = self.parse_diff(&diff_output)?;
+
+        // Name the base after its local bookmark when none was given
+        let base_ref = match base_ref {
+            Some(base) => base.to_string(),
+            None => run_jj_command(
+                root,
+                &["log", "-r", &merge_base, "--no-graph", "-T", "bookmarks"],
+            )
+            .ok()
+            .and_then(|s| {
+                s.split_whitespace()
+                    .find(|b| !b.contains('@'))
+                    .map(|b| b.trim_end_matches('*').to_string())
+            })
+            .unwrap_or_else(|| "trunk()".to_string()),
+        };
 
-        diff_parser::parse_unified_diff(&diff_output, DiffFormat::GitStyle, highlighter)
+        Ok(PullRequestDiff {
+            files,
+            info: PullRequestInfo {
+                base_ref,
+                merge_base_commit: merge_base,
+                head_commit: head,
+                commit_count,
+            },
+        })
     }
 
     fn get_commits_info(&self, ids: &[String]) -> Result<Vec<CommitInfo>> {
@@ -348,11 +577,7 @@ impl VcsBackend for JjBackend {
         Ok(ids.iter().filter_map(|id| by_id.remove(id)).collect())
     }
 
-    fn get_working_tree_with_commits_diff(
-        &self,
-        commit_ids: &[String],
-        highlighter: &SyntaxHighlighter,
-    ) -> Result<Vec<DiffFile>> {
+    fn get_working_tree_with_commits_diff(&self, commit_ids: &[String]) -> Result<Vec<DiffFile>> {
         if commit_ids.is_empty() {
             return Err(TuicrError::NoChanges);
         }
@@ -361,34 +586,74 @@ impl VcsBackend for JjBackend {
         let oldest = &commit_ids[0];
 
         // Diff from the parent of the oldest commit to the working copy (@)
-        let diff_output = run_jj_command(
-            &self.info.root_path,
-            &[
-                "diff",
-                "--from",
-                &format!("{}-", oldest),
-                "--to",
-                "@",
-                "--git",
-            ],
-        )?;
+        let diff_output = self.run_diff(&[
+            "diff",
+            "--from",
+            &format!("{}-", oldest),
+            "--to",
+            "@",
+            "--git",
+        ])?;
 
         if diff_output.trim().is_empty() {
             return Err(TuicrError::NoChanges);
         }
 
-        diff_parser::parse_unified_diff(&diff_output, DiffFormat::GitStyle, highlighter)
+        self.parse_diff(&diff_output)
     }
+
+    fn discard_hunk(&self, path: &Path, hunk: &DiffHunk) -> Result<()> {
+        preimage::restore_hunk_preimage(&self.info.root_path, path, hunk)
+    }
+}
+
+/// Paths from `jj resolve --list` output, e.g. `src/main.rs    2-sided conflict`.
+fn parse_resolve_list(output: &str) -> HashSet<PathBuf> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let path = line.split_once("  ").map_or(line, |(path, _)| path).trim();
+            (!path.is_empty()).then(|| PathBuf::from(path))
+        })
+        .collect()
 }
 
 /// Run a jj command and return its stdout
 fn run_jj_command(root: &Path, args: &[&str]) -> Result<String> {
+    run_jj_command_raw(root, args).map(|stdout| String::from_utf8_lossy(&stdout).to_string())
+}
+
+/// Like [`run_jj_command`], but returns stdout as bytes, e.g. for binary file contents.
+fn run_jj_command_raw(root: &Path, args: &[&str]) -> Result<Vec<u8>> {
     let output = Command::new("jj")
         .current_dir(root)
         .args(args)
         .output()
         .map_err(|e| TuicrError::VcsCommand(format!("Failed to run jj: {}", e)))?;
+    check_jj_output(args, output)
+}
 
+/// Like [`run_jj_command`], but feeds `stdin` to the command, e.g. a new
+/// description for `jj describe --stdin`.
+fn run_jj_command_with_stdin(root: &Path, args: &[&str], stdin: &str) -> Result<String> {
+    let mut child = Command::new("jj")
+        .current_dir(root)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| TuicrError::VcsCommand(format!("Failed to run jj: {}", e)))?;
+    if let Some(mut input) = child.stdin.take() {
+        input.write_all(stdin.as_bytes())?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| TuicrError::VcsCommand(format!("Failed to run jj: {}", e)))?;
+    check_jj_output(args, output).map(|stdout| String::from_utf8_lossy(&stdout).to_string())
+}
+
+fn check_jj_output(args: &[&str], output: Output) -> Result<Vec<u8>> {
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         return Err(TuicrError::VcsCommand(format!(
@@ -398,7 +663,7 @@ fn run_jj_command(root: &Path, args: &[&str]) -> Result<String> {
         )));
     }
 
-    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    Ok(output.stdout)
 }
 
 #[cfg(test)]
@@ -406,6 +671,15 @@ mod tests {
     use super::*;
     use std::fs;
 
+    #[test]
+    fn should_parse_resolve_list_paths() {
+        let output = "src/main.rs    2-sided conflict\nsrc/my file.rs  2-sided conflict including 1 deletion\n";
+        let paths = parse_resolve_list(output);
+        assert_eq!(paths.len(), 2);
+        assert!(paths.contains(Path::new("src/main.rs")));
+        assert!(paths.contains(Path::new("src/my file.rs")));
+    }
+
     /// Check if jj command is available
     fn jj_available() -> bool {
         Command::new("jj")
@@ -508,9 +782,7 @@ mod tests {
         assert_eq!(backend.info().root_path, expected_path);
         assert_eq!(backend.info().vcs_type, VcsType::Jujutsu);
 
-        let files = backend
-            .get_working_tree_diff(&SyntaxHighlighter::default())
-            .expect("Failed to get diff");
+        let files = backend.get_working_tree_diff().expect("Failed to get diff");
 
         assert_eq!(files.len(), 1);
         assert_eq!(
@@ -671,7 +943,7 @@ mod tests {
 
             let commit_ids = vec![oldest.id.clone(), newest.id.clone()];
             let diff = backend
-                .get_commit_range_diff(&commit_ids, &SyntaxHighlighter::default())
+                .get_commit_range_diff(&commit_ids)
                 .expect("Failed to get commit range diff");
 
             // Should have changes
@@ -679,6 +951,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_jj_pull_request_diff() {
+        let Some(temp) = setup_test_repo_with_commits() else {
+            eprintln!("Skipping test: jj command not available");
+            return;
+        };
+        let root = temp.path();
+
+        // Bookmark the first commit as the base of the change
+        Command::new("jj")
+            .args(["bookmark", "create", "main", "-r", "@---"])
+            .current_dir(root)
+            .output()
+            .expect("Failed to create bookmark");
+        let first = run_jj_command(
+            root,
+            &["log", "-r", "@---", "--no-graph", "-T", "commit_id"],
+        )
+        .expect("Failed to get first commit");
+
+        let backend =
+            JjBackend::from_path(root.to_path_buf()).expect("Failed to create jj backend");
+        let pr_diff = backend
+            .get_pull_request_diff(None)
+            .expect("Failed to get PR diff");
+
+        assert_eq!(pr_diff.info.base_ref, "main");
+        assert_eq!(pr_diff.info.merge_base_commit, first.trim());
+        // The empty working-copy commit on top isn't counted
+        assert_eq!(pr_diff.info.commit_count, 2);
+
+        let mut file_paths: Vec<_> = pr_diff
+            .files
+            .iter()
+            .filter_map(|f| f.new_path.as_ref().map(|p| p.to_string_lossy().to_string()))
+            .collect();
+        file_paths.sort();
+        assert_eq!(file_paths, ["file1.txt", "file2.txt"]);
+    }
+
     /// Create a test repo with a renamed file (no content changes).
     fn setup_test_repo_with_rename() -> Option<tempfile::TempDir> {
         if !jj_available() {
@@ -724,9 +1036,7 @@ mod tests {
         let backend =
             JjBackend::from_path(temp.path().to_path_buf()).expect("Failed to create jj backend");
 
-        let files = backend
-            .get_working_tree_diff(&SyntaxHighlighter::default())
-            .expect("Failed to get diff");
+        let files = backend.get_working_tree_diff().expect("Failed to get diff");
 
         // jj should detect the rename
         // Note: jj may show this as delete + add if it doesn't detect the rename
@@ -775,9 +1085,7 @@ mod tests {
         let backend =
             JjBackend::from_path(temp.path().to_path_buf()).expect("Failed to create jj backend");
 
-        let files = backend
-            .get_working_tree_diff(&SyntaxHighlighter::default())
-            .expect("Failed to get diff");
+        let files = backend.get_working_tree_diff().expect("Failed to get diff");
 
         assert_eq!(files.len(), 1, "Expected one file");
 
@@ -810,9 +1118,7 @@ mod tests {
         let backend =
             JjBackend::from_path(temp.path().to_path_buf()).expect("Failed to create jj backend");
 
-        let files = backend
-            .get_working_tree_diff(&SyntaxHighlighter::default())
-            .expect("Failed to get diff");
+        let files = backend.get_working_tree_diff().expect("Failed to get diff");
 
         assert_eq!(files.len(), 1, "Expected one file");
 
@@ -925,6 +1231,24 @@ mod tests {
         Some(temp_dir)
     }
 
+    #[test]
+    fn test_jj_list_branches() {
+        let Some(temp) = setup_test_repo_with_bookmark_on_ancestor() else {
+            eprintln!("Skipping test: jj command not available");
+            return;
+        };
+
+        let backend =
+            JjBackend::from_path(temp.path().to_path_buf()).expect("Failed to create jj backend");
+        let branches = backend.list_branches().expect("Failed to list bookmarks");
+
+        assert_eq!(branches.len(), 1);
+        assert_eq!(branches[0].name, "main");
+        assert_eq!(branches[0].summary, "Initial commit");
+        assert!(branches[0].is_current);
+        assert_eq!(branches[0].revisions, "trunk()..\"main\"");
+    }
+
     #[test]
     fn test_jj_bookmark_on_ancestor_revision() {
         let Some(temp) = setup_test_repo_with_bookmark_on_ancestor() else {
@@ -4,31 +4,38 @@
 //! - Git
 //! - Mercurial
 //! - Jujutsu
+//! - Pijul
 //!
 //! ## Detection Order
 //!
 //! When auto-detecting the VCS type, Jujutsu is tried first because jj repos
 //! are Git-backed and contain a `.git` directory. If jj detection fails, Git
-//! is tried next, then Mercurial.
+//! is tried next, then Mercurial, then Pijul.
 
 mod diff_parser;
 pub mod file;
 pub mod git;
 mod hg;
 mod jj;
+mod pijul;
+mod preimage;
 pub(crate) mod traits;
 
 pub use file::FileBackend;
 pub use git::GitBackend;
 pub use hg::HgBackend;
 pub use jj::JjBackend;
-pub use traits::{CommitInfo, PullRequestDiff, PullRequestInfo, VcsBackend, VcsInfo};
+pub use pijul::PijulBackend;
+pub use traits::{
+    BlameLine, BlameTarget, BranchInfo, CommitInfo, DiffStat, PullRequestDiff, PullRequestInfo,
+    VcsBackend, VcsInfo,
+};
 
 use crate::error::{Result, TuicrError};
 
 /// Detect the VCS type and return the appropriate backend.
 ///
-/// Detection order: Jujutsu → Git → Mercurial.
+/// Detection order: Jujutsu → Git → Mercurial → Pijul.
 /// Jujutsu is tried first because jj repos are Git-backed.
 pub fn detect_vcs() -> Result<Box<dyn VcsBackend>> {
     // Try jj first since jj repos are Git-backed
@@ -36,9 +43,12 @@ pub fn detect_vcs() -> Result<Box<dyn VcsBackend>> {
         return Ok(Box::new(backend));
     }
 
-    // Try git
-    if let Ok(backend) = GitBackend::discover() {
-        return Ok(Box::new(backend));
+    // Try git. A bare repository is still git, so say so rather than
+    // falling through to the other backends
+    match GitBackend::discover() {
+        Ok(backend) => return Ok(Box::new(backend)),
+        Err(e @ TuicrError::BareRepository(_)) => return Err(e),
+        Err(_) => {}
     }
 
     // Try hg
@@ -46,6 +56,11 @@ pub fn detect_vcs() -> Result<Box<dyn VcsBackend>> {
         return Ok(Box::new(backend));
     }
 
+    // Try pijul
+    if let Ok(backend) = PijulBackend::discover() {
+        return Ok(Box::new(backend));
+    }
+
     Err(TuicrError::NotARepository)
 }
 
@@ -0,0 +1,568 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use git2::{DiffOptions, Patch};
+
+use crate::error::{Result, TuicrError};
+use crate::model::{DiffFile, DiffHunk, DiffLine, FileStatus, LineOrigin};
+use crate::vcs::diff_parser::{self, DiffFormat};
+use crate::vcs::preimage;
+use crate::vcs::traits::{CommitInfo, VcsBackend, VcsInfo, VcsType};
+
+/// A file listed by `pijul diff --short`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ChangedFile {
+    path: String,
+    status: FileStatus,
+}
+
+/// Parse `pijul diff --short` output, one `<code> <path>` line per hunk.
+/// A file edited and moved shows up once per hunk; additions and deletions
+/// take precedence over edits.
+fn parse_short_diff(output: &str) -> Vec<ChangedFile> {
+    let mut files: Vec<ChangedFile> = Vec::new();
+    for line in output.lines() {
+        let Some((code, path)) = line.trim().split_once(char::is_whitespace) else {
+            continue;
+        };
+        let path = path.trim().trim_matches('"');
+        if path.is_empty() {
+            continue;
+        }
+        let status = match code {
+            // Undeleting a file brings it back like an addition
+            "A" | "UD" => FileStatus::Added,
+            "D" => FileStatus::Deleted,
+            _ => FileStatus::Modified,
+        };
+        match files.iter_mut().find(|file| file.path == path) {
+            Some(file) if status != FileStatus::Modified => file.status = status,
+            Some(_) => {}
+            None => files.push(ChangedFile {
+                path: path.to_string(),
+                status,
+            }),
+        }
+    }
+    files
+}
+
+/// Parse `pijul log` output into changes, newest first. Each change starts
+/// with a `Change <hash>` line, followed by `Author:`/`Date:` lines and the
+/// indented message.
+fn parse_log(output: &str) -> Vec<CommitInfo> {
+    struct Entry<'a> {
+        id: String,
+        author: String,
+        time: Option<DateTime<Utc>>,
+        message: Vec<&'a str>,
+    }
+
+    let mut commits = Vec::new();
+    let mut current: Option<Entry> = None;
+    let mut finish = |current: Option<Entry>| {
+        let Some(Entry {
+            id,
+            author,
+            time,
+            message,
+        }) = current
+        else {
+            return;
+        };
+        let message = message.join("\n");
+        let mut lines = message.trim().lines();
+        let summary = lines.next().unwrap_or("(no message)").to_string();
+        let body = lines
+            .skip_while(|l| l.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+        commits.push(CommitInfo {
+            short_id: id.chars().take(12).collect(),
+            id,
+            branch_name: None,
+            summary,
+            body: (!body.trim().is_empty()).then_some(body),
+            author,
+            time: time.unwrap_or_else(Utc::now),
+        });
+    };
+
+    for line in output.lines() {
+        if let Some(hash) = line.strip_prefix("Change ") {
+            finish(current.take());
+            current = Some(Entry {
+                id: hash.trim().to_string(),
+                author: String::new(),
+                time: None,
+                message: Vec::new(),
+            });
+            continue;
+        }
+        let Some(Entry {
+            author,
+            time,
+            message,
+            ..
+        }) = current.as_mut()
+        else {
+            continue;
+        };
+        if message.is_empty()
+            && let Some(value) = line.strip_prefix("Author:")
+        {
+            *author = value.trim().to_string();
+        } else if message.is_empty()
+            && let Some(value) = line.strip_prefix("Date:")
+        {
+            *time = parse_log_date(value.trim());
+        } else if !line.trim().is_empty() || !message.is_empty() {
+            message.push(line.trim());
+        }
+    }
+    finish(current);
+    commits
+}
+
+/// Parse a `pijul log` date such as `2024-03-01 12:30:45.123456 UTC`.
+fn parse_log_date(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+        .or_else(|| {
+            NaiveDateTime::parse_from_str(value.trim_end_matches(" UTC"), "%Y-%m-%d %H:%M:%S%.f")
+                .ok()
+                .map(|time| time.and_utc())
+        })
+}
+
+/// Unified diff text for one file between its recorded and working copy
+/// contents, with a `diff --git` header the git-style parser understands.
+fn file_patch(
+    file: &ChangedFile,
+    old: &[u8],
+    new: &[u8],
+    ignore_whitespace: bool,
+) -> Result<String> {
+    let path = Path::new(&file.path);
+    let mut opts = DiffOptions::new();
+    opts.ignore_whitespace(ignore_whitespace);
+    let mut patch = Patch::from_buffers(old, Some(path), new, Some(path), Some(&mut opts))?;
+    let buf = patch.to_buf()?;
+    let text = String::from_utf8_lossy(&buf);
+
+    // Replace git2's header, which can't tell additions and deletions apart
+    // from edits of an empty file
+    let body: Vec<&str> = text
+        .lines()
+        .skip_while(|line| !line.starts_with("@@") && !line.starts_with("Binary files"))
+        .collect();
+    if body.is_empty() {
+        return Ok(String::new());
+    }
+    let mut out = format!("diff --git a/{0} b/{0}\n", file.path);
+    match file.status {
+        FileStatus::Added => {
+            out.push_str(&format!(
+                "new file mode 100644\n--- /dev/null\n+++ b/{}\n",
+                file.path
+            ));
+        }
+        FileStatus::Deleted => {
+            out.push_str(&format!(
+                "deleted file mode 100644\n--- a/{}\n+++ /dev/null\n",
+                file.path
+            ));
+        }
+        _ => out.push_str(&format!("--- a/{0}\n+++ b/{0}\n", file.path)),
+    }
+    for line in body {
+        out.push_str(line);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Pijul backend implementation using pijul CLI commands.
+///
+/// Pijul has no `git diff`-style output, so the working copy diff is built
+/// from each changed file's recorded contents (`pijul reset --dry-run`) and
+/// the file on disk.
+pub struct PijulBackend {
+    info: VcsInfo,
+    /// Ignore whitespace changes when diffing file contents
+    ignore_whitespace: bool,
+    /// Diff lines kept per file; the rest is loaded on request
+    max_file_lines: Option<usize>,
+    /// Files kept in full regardless of `max_file_lines`
+    fully_loaded: HashSet<PathBuf>,
+}
+
+impl PijulBackend {
+    /// Discover a Pijul repository from the current directory
+    pub fn discover() -> Result<Self> {
+        let cwd = std::env::current_dir()?;
+        let root_path = find_root(&cwd).ok_or(TuicrError::NotARepository)?;
+        // Fails when pijul isn't installed
+        run_pijul_command(&root_path, &["log", "--hash-only", "--limit", "1"])
+            .map_err(|_| TuicrError::NotARepository)?;
+        Self::from_path(root_path)
+    }
+
+    /// Create backend from a known path (used by discover and tests)
+    fn from_path(root_path: PathBuf) -> Result<Self> {
+        // Canonicalize to resolve symlinks (e.g., /var -> /private/var on macOS)
+        let root_path = root_path.canonicalize().unwrap_or(root_path);
+
+        // The latest change applied to the channel stands in for a commit
+        let head_commit = run_pijul_command(&root_path, &["log", "--hash-only", "--limit", "1"])
+            .ok()
+            .and_then(|s| s.lines().next().map(|hash| hash.trim().to_string()))
+            .filter(|hash| !hash.is_empty())
+            .unwrap_or_else(|| "unknown".to_string());
+
+        // Channels play the part of branches; the current one is starred
+        let branch_name = run_pijul_command(&root_path, &["channel"])
+            .ok()
+            .and_then(|s| {
+                s.lines()
+                    .find_map(|line| line.strip_prefix("* ").map(|name| name.trim().to_string()))
+            });
+
+        let info = VcsInfo {
+            root_path,
+            head_commit,
+            branch_name,
+            vcs_type: VcsType::Pijul,
+        };
+
+        Ok(Self {
+            info,
+            ignore_whitespace: false,
+            max_file_lines: None,
+            fully_loaded: HashSet::new(),
+        })
+    }
+
+    /// Recorded contents of `path` on the current channel
+    fn recorded_contents(&self, path: &str) -> Result<Vec<u8>> {
+        run_pijul_command_raw(&self.info.root_path, &["reset", "--dry-run", path])
+    }
+}
+
+impl VcsBackend for PijulBackend {
+    fn info(&self) -> &VcsInfo {
+        &self.info
+    }
+
+    fn set_ignore_whitespace(&mut self, ignore: bool) -> bool {
+        self.ignore_whitespace = ignore;
+        true
+    }
+
+    fn set_max_file_lines(&mut self, max_lines: Option<usize>) {
+        self.max_file_lines = max_lines;
+    }
+
+    fn load_file_fully(&mut self, path: &Path) {
+        self.fully_loaded.insert(path.to_path_buf());
+    }
+
+    fn get_working_tree_diff(&self) -> Result<Vec<DiffFile>> {
+        let output = run_pijul_command(&self.info.root_path, &["diff", "--short"])?;
+
+        let mut diff_text = String::new();
+        for file in parse_short_diff(&output) {
+            let full_path = self.info.root_path.join(&file.path);
+            // Added directories are listed like files
+            if full_path.is_dir() {
+                continue;
+            }
+            let old = match file.status {
+                FileStatus::Added => Vec::new(),
+                // Moved files may not be recorded under their new name yet
+                _ => self.recorded_contents(&file.path).unwrap_or_default(),
+            };
+            let new = match file.status {
+                FileStatus::Deleted => Vec::new(),
+                _ => std::fs::read(&full_path).unwrap_or_default(),
+            };
+            diff_text.push_str(&file_patch(&file, &old, &new, self.ignore_whitespace)?);
+        }
+
+        if diff_text.trim().is_empty() {
+            return Err(TuicrError::NoChanges);
+        }
+
+        let mut files = diff_parser::parse_unified_diff(&diff_text, DiffFormat::GitStyle)?;
+        diff_parser::truncate_files(&mut files, self.max_file_lines, &self.fully_loaded);
+        diff_parser::attach_binary_meta(&mut files, &self.info.root_path, |path| {
+            self.recorded_contents(&path.to_string_lossy()).ok()
+        });
+        Ok(files)
+    }
+
+    fn fetch_context_lines(
+        &self,
+        file_path: &Path,
+        file_status: FileStatus,
+        start_line: u32,
+        end_line: u32,
+    ) -> Result<Vec<DiffLine>> {
+        if start_line > end_line || start_line == 0 {
+            return Ok(Vec::new());
+        }
+
+        let content = match file_status {
+            FileStatus::Deleted => {
+                String::from_utf8_lossy(&self.recorded_contents(&file_path.to_string_lossy())?)
+                    .into_owned()
+            }
+            _ => std::fs::read_to_string(self.info.root_path.join(file_path))?,
+        };
+
+        Ok(content
+            .lines()
+            .enumerate()
+            .skip(start_line as usize - 1)
+            .take((end_line - start_line + 1) as usize)
+            .map(|(idx, line)| {
+                let line_num = idx as u32 + 1;
+                DiffLine {
+                    origin: LineOrigin::Context,
+                    content: line.to_string(),
+                    old_lineno: Some(line_num),
+                    new_lineno: Some(line_num),
+                    highlighted_spans: None,
+                    whitespace_only: false,
+                    move_id: None,
+                }
+            })
+            .collect())
+    }
+
+    fn get_recent_commits(&self, offset: usize, limit: usize) -> Result<Vec<CommitInfo>> {
+        let output = run_pijul_command(
+            &self.info.root_path,
+            &[
+                "log",
+                "--offset",
+                &offset.to_string(),
+                "--limit",
+                &limit.to_string(),
+            ],
+        )?;
+        Ok(parse_log(&output))
+    }
+
+    fn get_commits_info(&self, ids: &[String]) -> Result<Vec<CommitInfo>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let output = run_pijul_command(&self.info.root_path, &["log"])?;
+        let mut by_id: HashMap<String, CommitInfo> = parse_log(&output)
+            .into_iter()
+            .map(|commit| (commit.id.clone(), commit))
+            .collect();
+
+        // Return in input order
+        Ok(ids.iter().filter_map(|id| by_id.remove(id)).collect())
+    }
+
+    fn get_commit_range_diff(&self, _commit_ids: &[String]) -> Result<Vec<DiffFile>> {
+        // Changes are patches that commute rather than snapshots, so there is
+        // no recorded state "before" an arbitrary set of them to diff against
+        Err(TuicrError::UnsupportedOperation(
+            "Reviewing recorded changes is not supported for Pijul; review the working copy instead"
+                .into(),
+        ))
+    }
+
+    fn discard_hunk(&self, path: &Path, hunk: &DiffHunk) -> Result<()> {
+        preimage::restore_hunk_preimage(&self.info.root_path, path, hunk)
+    }
+}
+
+/// The closest directory at or above `start` containing a `.pijul` directory
+fn find_root(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| dir.join(".pijul").is_dir())
+        .map(Path::to_path_buf)
+}
+
+/// Run a pijul command and return its stdout
+fn run_pijul_command(root: &Path, args: &[&str]) -> Result<String> {
+    run_pijul_command_raw(root, args).map(|stdout| String::from_utf8_lossy(&stdout).to_string())
+}
+
+/// Like [`run_pijul_command`], but returns stdout as bytes, e.g. for binary file contents.
+fn run_pijul_command_raw(root: &Path, args: &[&str]) -> Result<Vec<u8>> {
+    let output = Command::new("pijul")
+        .current_dir(root)
+        .args(args)
+        .output()
+        .map_err(|e| TuicrError::VcsCommand(format!("Failed to run pijul: {}", e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(TuicrError::VcsCommand(format!(
+            "pijul {} failed: {}",
+            args.join(" "),
+            stderr
+        )));
+    }
+
+    Ok(output.stdout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// Check if pijul command is available
+    fn pijul_available() -> bool {
+        Command::new("pijul")
+            .arg("--version")
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    }
+
+    #[test]
+    fn should_parse_short_diff_output() {
+        let output = "\
+A  src/new.rs
+M  src/lib.rs
+MV src/lib.rs
+R  \"docs/read me.md\"
+D  old.txt
+UD restored.txt
+";
+        let files = parse_short_diff(output);
+        let summary: Vec<(&str, FileStatus)> = files
+            .iter()
+            .map(|file| (file.path.as_str(), file.status))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("src/new.rs", FileStatus::Added),
+                ("src/lib.rs", FileStatus::Modified),
+                ("docs/read me.md", FileStatus::Modified),
+                ("old.txt", FileStatus::Deleted),
+                ("restored.txt", FileStatus::Added),
+            ]
+        );
+        assert!(parse_short_diff("").is_empty());
+    }
+
+    #[test]
+    fn should_parse_log_output() {
+        let output = "\
+Change MNYNGT2VGEQZX4QA43FWBDVYQY7CGXN4J2CGE5FDFIHOWQFKFIJQC
+Author: Ada Lovelace <ada@example.com>
+Date: 2024-03-01 12:30:45.123456 UTC
+
+    Fix the parser
+
+    It dropped the last line.
+
+Change AAAABBBBCCCCDDDDEEEEFFFFGGGGHHHHIIIIJJJJKKKKLLLLMMMMC
+Author: Grace
+Date: not a date
+
+    Initial change
+";
+        let commits = parse_log(output);
+        assert_eq!(commits.len(), 2);
+        assert_eq!(
+            commits[0].id,
+            "MNYNGT2VGEQZX4QA43FWBDVYQY7CGXN4J2CGE5FDFIHOWQFKFIJQC"
+        );
+        assert_eq!(commits[0].short_id, "MNYNGT2VGEQZ");
+        assert_eq!(commits[0].author, "Ada Lovelace <ada@example.com>");
+        assert_eq!(commits[0].summary, "Fix the parser");
+        assert_eq!(
+            commits[0].body.as_deref(),
+            Some("It dropped the last line.")
+        );
+        assert_eq!(
+            commits[0].time.to_rfc3339(),
+            "2024-03-01T12:30:45.123456+00:00"
+        );
+        assert_eq!(commits[1].summary, "Initial change");
+        assert_eq!(commits[1].body, None);
+    }
+
+    #[test]
+    fn should_build_parsable_patches_from_file_contents() {
+        let modified = ChangedFile {
+            path: "src/lib.rs".to_string(),
+            status: FileStatus::Modified,
+        };
+        let added = ChangedFile {
+            path: "new.txt".to_string(),
+            status: FileStatus::Added,
+        };
+        let mut text = file_patch(&modified, b"one\ntwo\n", b"one\n2\n", false).unwrap();
+        text.push_str(&file_patch(&added, b"", b"hello\n", false).unwrap());
+        assert_eq!(
+            file_patch(&modified, b"same\n", b"same\n", false).unwrap(),
+            ""
+        );
+
+        let files = diff_parser::parse_unified_diff(&text, DiffFormat::GitStyle).unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].status, FileStatus::Modified);
+        let origins: Vec<LineOrigin> = files[0].hunks[0]
+            .lines
+            .iter()
+            .map(|line| line.origin)
+            .collect();
+        assert_eq!(
+            origins,
+            [
+                LineOrigin::Context,
+                LineOrigin::Deletion,
+                LineOrigin::Addition
+            ]
+        );
+        assert_eq!(files[1].status, FileStatus::Added);
+        assert_eq!(files[1].hunks[0].lines[0].content, "hello");
+    }
+
+    #[test]
+    fn test_pijul_working_tree_diff() {
+        if !pijul_available() {
+            eprintln!("Skipping test: pijul command not available");
+            return;
+        }
+
+        let temp = tempfile::tempdir().expect("Failed to create temp dir");
+        let root = temp.path();
+        Command::new("pijul")
+            .args(["init"])
+            .current_dir(root)
+            .output()
+            .expect("Failed to init pijul repo");
+        fs::write(root.join("hello.txt"), "hello world\n").expect("Failed to write file");
+        Command::new("pijul")
+            .args(["add", "hello.txt"])
+            .current_dir(root)
+            .output()
+            .expect("Failed to add file");
+
+        assert_eq!(find_root(&root.join("sub/dir")), Some(root.to_path_buf()));
+        let backend = PijulBackend::from_path(root.to_path_buf()).unwrap();
+        assert_eq!(backend.info().vcs_type, VcsType::Pijul);
+
+        let files = backend.get_working_tree_diff().unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].status, FileStatus::Added);
+        assert_eq!(files[0].hunks[0].lines[0].content, "hello world");
+    }
+}
@@ -0,0 +1,175 @@
+//! Reverting a hunk by rewriting the working copy file, for backends without
+//! a patch-application API.
+
+use std::fs;
+use std::path::Path;
+
+use crate::error::{Result, TuicrError};
+use crate::model::{DiffHunk, LineOrigin};
+
+/// Replace the lines `hunk` added in the working copy file with the lines it
+/// removed. Fails if the file no longer contains the hunk's new side.
+pub(crate) fn restore_hunk_preimage(root: &Path, path: &Path, hunk: &DiffHunk) -> Result<()> {
+    let full_path = root.join(path);
+    let content = fs::read_to_string(&full_path)?;
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+
+    // Pure deletions have a count of zero and start at the line before the gap
+    let start = if hunk.new_count == 0 {
+        hunk.new_start
+    } else {
+        hunk.new_start.saturating_sub(1)
+    } as usize;
+    let end = start + hunk.new_count as usize;
+
+    let expected = hunk
+        .lines
+        .iter()
+        .filter(|line| line.origin != LineOrigin::Deletion)
+        .map(|line| strip_eol(&line.content));
+    let matches = lines
+        .get(start..end)
+        .is_some_and(|current| current.iter().map(|line| strip_eol(line)).eq(expected));
+    if !matches {
+        return Err(TuicrError::FileChanged(path.display().to_string()));
+    }
+
+    let eol = if content.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let mut restored: Vec<String> = hunk
+        .lines
+        .iter()
+        .filter(|line| line.origin != LineOrigin::Addition)
+        .map(|line| format!("{}{eol}", strip_eol(&line.content)))
+        .collect();
+    if end == lines.len()
+        && !content.ends_with('\n')
+        && let Some(last) = restored.last_mut()
+    {
+        last.truncate(last.len() - eol.len());
+    }
+
+    let output = [
+        lines[..start].concat(),
+        restored.concat(),
+        lines[end..].concat(),
+    ]
+    .concat();
+    if output.is_empty() && hunk.old_count == 0 {
+        // The hunk created the file
+        fs::remove_file(&full_path)?;
+    } else {
+        fs::write(&full_path, output)?;
+    }
+    Ok(())
+}
+
+fn strip_eol(line: &str) -> &str {
+    line.trim_end_matches(['\n', '\r'])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::DiffLine;
+
+    fn line(origin: LineOrigin, content: &str) -> DiffLine {
+        DiffLine {
+            origin,
+            content: content.to_string(),
+            old_lineno: None,
+            new_lineno: None,
+            highlighted_spans: None,
+            whitespace_only: false,
+            move_id: None,
+        }
+    }
+
+    fn hunk(
+        old_start: u32,
+        old_count: u32,
+        new_start: u32,
+        new_count: u32,
+        lines: Vec<DiffLine>,
+    ) -> DiffHunk {
+        DiffHunk {
+            header: String::new(),
+            lines,
+            old_start,
+            old_count,
+            new_start,
+            new_count,
+        }
+    }
+
+    #[test]
+    fn restores_removed_lines_and_drops_added_ones() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), "one\nTWO\nthree\n").unwrap();
+        let change = hunk(
+            1,
+            3,
+            1,
+            3,
+            vec![
+                line(LineOrigin::Context, "one"),
+                line(LineOrigin::Deletion, "two"),
+                line(LineOrigin::Addition, "TWO"),
+                line(LineOrigin::Context, "three"),
+            ],
+        );
+
+        restore_hunk_preimage(dir.path(), Path::new("a.txt"), &change).unwrap();
+
+        let content = fs::read_to_string(dir.path().join("a.txt")).unwrap();
+        assert_eq!(content, "one\ntwo\nthree\n");
+    }
+
+    #[test]
+    fn restores_pure_deletion() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), "one\nthree\n").unwrap();
+        let change = hunk(2, 1, 1, 0, vec![line(LineOrigin::Deletion, "two")]);
+
+        restore_hunk_preimage(dir.path(), Path::new("a.txt"), &change).unwrap();
+
+        let content = fs::read_to_string(dir.path().join("a.txt")).unwrap();
+        assert_eq!(content, "one\ntwo\nthree\n");
+    }
+
+    #[test]
+    fn removes_file_created_by_hunk() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("new.txt"), "hello\n").unwrap();
+        let change = hunk(0, 0, 1, 1, vec![line(LineOrigin::Addition, "hello")]);
+
+        restore_hunk_preimage(dir.path(), Path::new("new.txt"), &change).unwrap();
+
+        assert!(!dir.path().join("new.txt").exists());
+    }
+
+    #[test]
+    fn refuses_when_file_no_longer_matches() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), "one\nedited\n").unwrap();
+        let change = hunk(
+            1,
+            1,
+            1,
+            2,
+            vec![
+                line(LineOrigin::Context, "one"),
+                line(LineOrigin::Addition, "two"),
+            ],
+        );
+
+        let result = restore_hunk_preimage(dir.path(), Path::new("a.txt"), &change);
+
+        assert!(matches!(result, Err(TuicrError::FileChanged(_))));
+        let content = fs::read_to_string(dir.path().join("a.txt")).unwrap();
+        assert_eq!(content, "one\nedited\n");
+    }
+}
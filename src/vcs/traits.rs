@@ -2,8 +2,7 @@ use chrono::{DateTime, Utc};
 use std::path::{Path, PathBuf};
 
 use crate::error::Result;
-use crate::model::{DiffFile, DiffLine, FileStatus};
-use crate::syntax::SyntaxHighlighter;
+use crate::model::{DiffFile, DiffHunk, DiffLine, FileStatus, LineRange};
 
 /// Information about the VCS type
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -11,6 +10,7 @@ pub enum VcsType {
     Git,
     Mercurial,
     Jujutsu,
+    Pijul,
     File,
 }
 
@@ -20,6 +20,7 @@ impl std::fmt::Display for VcsType {
             VcsType::Git => write!(f, "git"),
             VcsType::Mercurial => write!(f, "hg"),
             VcsType::Jujutsu => write!(f, "jj"),
+            VcsType::Pijul => write!(f, "pijul"),
             VcsType::File => write!(f, "file"),
         }
     }
@@ -49,6 +50,100 @@ pub struct CommitInfo {
     pub time: DateTime<Utc>,
 }
 
+/// A local branch (a bookmark for jj and hg) listed by `:branches`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchInfo {
+    pub name: String,
+    /// Short id of the branch tip
+    pub short_id: String,
+    /// First line of the tip's message
+    pub summary: String,
+    /// Whether the working copy is on this branch
+    pub is_current: bool,
+    /// Revisions expression for the commits on the branch since it forked
+    /// from the main line, passed to [`VcsBackend::resolve_revisions`]
+    pub revisions: String,
+}
+
+impl BranchInfo {
+    /// Parse `name<TAB>short id<TAB>summary` lines printed by a jj or hg
+    /// template. Lines without a tip (conflicted bookmarks) are skipped and
+    /// the first line per name wins. `revisions` is given the name quoted as
+    /// a revset string.
+    pub fn from_template_output(
+        output: &str,
+        current: Option<&str>,
+        revisions: impl Fn(&str) -> String,
+    ) -> Vec<Self> {
+        let mut branches: Vec<Self> = Vec::new();
+        for line in output.lines() {
+            let mut fields = line.splitn(3, '\t');
+            let (Some(name), Some(short_id)) = (fields.next(), fields.next()) else {
+                continue;
+            };
+            let (name, short_id) = (name.trim(), short_id.trim());
+            if name.is_empty() || short_id.is_empty() || branches.iter().any(|b| b.name == name) {
+                continue;
+            }
+            let quoted = format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""));
+            branches.push(Self {
+                name: name.to_string(),
+                short_id: short_id.to_string(),
+                summary: fields.next().unwrap_or_default().trim().to_string(),
+                is_current: current == Some(name),
+                revisions: revisions(&quoted),
+            });
+        }
+        branches.sort_by(|a, b| a.name.cmp(&b.name));
+        branches
+    }
+}
+
+/// Lines and files changed by a commit, like `git diff --shortstat`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiffStat {
+    pub files_changed: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+impl DiffStat {
+    /// Parse the summary line that ends `jj diff --stat` and `hg diff --stat`
+    /// output, e.g. `3 files changed, 120 insertions(+), 45 deletions(-)`.
+    /// Output without a summary (an empty diff) counts as no changes.
+    pub fn from_stat_output(output: &str) -> Self {
+        let mut stat = Self::default();
+        let Some(summary) = output.lines().rev().find(|line| !line.trim().is_empty()) else {
+            return stat;
+        };
+        for part in summary.split(',') {
+            let mut words = part.split_whitespace();
+            let (Some(count), Some(kind)) = (words.next(), words.next()) else {
+                continue;
+            };
+            let Ok(count) = count.parse() else {
+                continue;
+            };
+            if kind.starts_with("file") {
+                stat.files_changed = count;
+            } else if kind.starts_with("insertion") {
+                stat.insertions = count;
+            } else if kind.starts_with("deletion") {
+                stat.deletions = count;
+            }
+        }
+        stat
+    }
+}
+
+impl std::ops::AddAssign for DiffStat {
+    fn add_assign(&mut self, other: Self) {
+        self.files_changed += other.files_changed;
+        self.insertions += other.insertions;
+        self.deletions += other.deletions;
+    }
+}
+
 /// Metadata about a pull-request style diff.
 #[derive(Debug, Clone)]
 pub struct PullRequestInfo {
@@ -69,23 +164,54 @@ pub struct PullRequestDiff {
     pub info: PullRequestInfo,
 }
 
+/// Revision whose version of a file is blamed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlameTarget<'a> {
+    /// The working copy; lines changed there have no commit yet.
+    WorkingCopy,
+    /// The commit the working copy is based on (HEAD, `@-` or `.`).
+    WorkingCopyParent,
+    Commit(&'a str),
+    /// The first parent of the commit, e.g. for lines it deleted.
+    ParentOf(&'a str),
+}
+
+/// Commit and author that last changed one line, from
+/// [`VcsBackend::blame_file`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlameLine {
+    pub commit_id: String,
+    pub author: String,
+}
+
+impl BlameLine {
+    /// Parse a `<commit id> <author>` line of annotate output.
+    pub fn from_annotate_line(line: &str) -> Self {
+        let (commit_id, author) = line.trim_end().split_once(' ').unwrap_or((line, ""));
+        Self {
+            commit_id: commit_id.trim().to_string(),
+            author: author.trim().to_string(),
+        }
+    }
+}
+
 /// Trait for VCS backend implementations
 pub trait VcsBackend: Send {
     /// Get repository information
     fn info(&self) -> &VcsInfo;
 
     /// Get the working tree diff (staged + unstaged changes)
-    fn get_working_tree_diff(&self, highlighter: &SyntaxHighlighter) -> Result<Vec<DiffFile>>;
+    fn get_working_tree_diff(&self) -> Result<Vec<DiffFile>>;
 
     /// Get the staged diff (index vs HEAD)
-    fn get_staged_diff(&self, _highlighter: &SyntaxHighlighter) -> Result<Vec<DiffFile>> {
+    fn get_staged_diff(&self) -> Result<Vec<DiffFile>> {
         Err(crate::error::TuicrError::UnsupportedOperation(
             "Staged diff not supported for this VCS".into(),
         ))
     }
 
     /// Get the unstaged diff (working tree vs index)
-    fn get_unstaged_diff(&self, _highlighter: &SyntaxHighlighter) -> Result<Vec<DiffFile>> {
+    fn get_unstaged_diff(&self) -> Result<Vec<DiffFile>> {
         Err(crate::error::TuicrError::UnsupportedOperation(
             "Unstaged diff not supported for this VCS".into(),
         ))
@@ -115,13 +241,17 @@ pub trait VcsBackend: Send {
         ))
     }
 
+    /// Local branches, or bookmarks where the VCS uses those, sorted by name.
+    /// Returns error if not supported (default).
+    fn list_branches(&self) -> Result<Vec<BranchInfo>> {
+        Err(crate::error::TuicrError::UnsupportedOperation(
+            "Branch listing not supported for this VCS".into(),
+        ))
+    }
+
     /// Get diff for a commit range.
     /// Returns error if not supported (default).
-    fn get_commit_range_diff(
-        &self,
-        _commit_ids: &[String],
-        _highlighter: &SyntaxHighlighter,
-    ) -> Result<Vec<DiffFile>> {
+    fn get_commit_range_diff(&self, _commit_ids: &[String]) -> Result<Vec<DiffFile>> {
         Err(crate::error::TuicrError::UnsupportedOperation(
             "Commit range diff not supported for this VCS".into(),
         ))
@@ -133,36 +263,116 @@ pub trait VcsBackend: Send {
         Ok(Vec::new())
     }
 
+    /// Files and lines changed by a single commit relative to its first parent.
+    fn commit_diffstat(&self, _id: &str) -> Result<DiffStat> {
+        Err(crate::error::TuicrError::UnsupportedOperation(
+            "Diffstat not supported for this VCS".into(),
+        ))
+    }
+
     /// Get a combined diff from the parent of the oldest commit through to the working tree.
     /// This shows both committed and working tree changes in a single diff.
     /// Returns error if not supported (default).
-    fn get_working_tree_with_commits_diff(
-        &self,
-        _commit_ids: &[String],
-        _highlighter: &SyntaxHighlighter,
-    ) -> Result<Vec<DiffFile>> {
+    fn get_working_tree_with_commits_diff(&self, _commit_ids: &[String]) -> Result<Vec<DiffFile>> {
         Err(crate::error::TuicrError::UnsupportedOperation(
             "Working tree + commits diff not supported for this VCS".into(),
         ))
     }
 
     /// Get a PR-style diff from merge-base(base_ref, HEAD) to HEAD.
-    fn get_pull_request_diff(
-        &self,
-        _base_ref: Option<&str>,
-        _highlighter: &SyntaxHighlighter,
-    ) -> Result<PullRequestDiff> {
+    fn get_pull_request_diff(&self, _base_ref: Option<&str>) -> Result<PullRequestDiff> {
         Err(crate::error::TuicrError::UnsupportedOperation(
             "PR diff not supported for this VCS".into(),
         ))
     }
 
+    /// Ignore whitespace changes in subsequent diffs (like `git diff -w`).
+    /// Returns false if not supported (default).
+    fn set_ignore_whitespace(&mut self, _ignore: bool) -> bool {
+        false
+    }
+
+    /// Minimum similarity percentage for rename and copy detection.
+    /// Ignored if the backend doesn't detect renames itself (default).
+    fn set_rename_threshold(&mut self, _percent: u16) {}
+
+    /// Load at most `max_lines` diff lines per file in subsequent diffs,
+    /// counting the rest in `DiffFile::truncated_lines`. `None` loads everything.
+    fn set_max_file_lines(&mut self, _max_lines: Option<usize>) {}
+
+    /// Exempt `path` from `set_max_file_lines` in subsequent diffs.
+    fn load_file_fully(&mut self, _path: &Path) {}
+
+    /// Web permalink to `range` of `path` at the current commit, if the
+    /// repository has a hosted remote. Returns None if not supported (default).
+    fn permalink(&self, _path: &Path, _range: LineRange) -> Option<String> {
+        None
+    }
+
+    /// Name the user commits as, used to attribute new comments. Returns
+    /// None if not supported (default) or not configured.
+    fn user_name(&self) -> Option<String> {
+        None
+    }
+
+    /// Message of the commit `set_commit_message` rewrites: the working-copy
+    /// change's description for jj, HEAD's message for git.
+    fn commit_message(&self) -> Result<String> {
+        Err(crate::error::TuicrError::UnsupportedOperation(
+            "Editing the commit message is not supported for this VCS".into(),
+        ))
+    }
+
+    /// Replace the message of the commit returned by `commit_message`.
+    fn set_commit_message(&self, _message: &str) -> Result<()> {
+        Err(crate::error::TuicrError::UnsupportedOperation(
+            "Editing the commit message is not supported for this VCS".into(),
+        ))
+    }
+
+    /// Id of the commit that last changed `line` (1-based) of `path` in
+    /// `target`, or None if the line isn't committed yet.
+    fn blame_line(&self, _path: &Path, _line: u32, _target: BlameTarget) -> Result<Option<String>> {
+        Err(crate::error::TuicrError::UnsupportedOperation(
+            "Blame is not supported for this VCS".into(),
+        ))
+    }
+
+    /// Commit and author of every line of `path` in the revision the working
+    /// copy is based on (HEAD, `@-` or `.`), in line order.
+    fn blame_file(&self, _path: &Path) -> Result<Vec<BlameLine>> {
+        Err(crate::error::TuicrError::UnsupportedOperation(
+            "Blame is not supported for this VCS".into(),
+        ))
+    }
+
     /// Stage a file (add to index).
     fn stage_file(&self, _path: &Path) -> Result<()> {
         Err(crate::error::TuicrError::UnsupportedOperation(
             "Staging not supported for this VCS".into(),
         ))
     }
+
+    /// Stage the unstaged changes of a single hunk.
+    fn stage_hunk(&self, _path: &Path, _hunk: &DiffHunk) -> Result<()> {
+        Err(crate::error::TuicrError::UnsupportedOperation(
+            "Staging not supported for this VCS".into(),
+        ))
+    }
+
+    /// Unstage the staged changes of a single hunk.
+    fn unstage_hunk(&self, _path: &Path, _hunk: &DiffHunk) -> Result<()> {
+        Err(crate::error::TuicrError::UnsupportedOperation(
+            "Staging not supported for this VCS".into(),
+        ))
+    }
+
+    /// Revert a single working tree hunk, discarding its changes.
+    fn discard_hunk(&self, _path: &Path, _hunk: &DiffHunk) -> Result<()> {
+        Err(crate::error::TuicrError::UnsupportedOperation(
+            "Discarding changes not supported for this VCS".into(),
+        ))
+    }
 }
 
 #[cfg(test)]
@@ -184,6 +394,11 @@ mod tests {
         assert_eq!(format!("{}", VcsType::Jujutsu), "jj");
     }
 
+    #[test]
+    fn vcs_type_display_pijul() {
+        assert_eq!(format!("{}", VcsType::Pijul), "pijul");
+    }
+
     #[test]
     fn vcs_type_equality() {
         assert_eq!(VcsType::Git, VcsType::Git);
@@ -240,4 +455,71 @@ mod tests {
         assert_eq!(cloned.summary, "Fix bug");
         assert_eq!(cloned.author, "Test User");
     }
+
+    #[test]
+    fn blame_line_from_annotate_output() {
+        assert_eq!(
+            BlameLine::from_annotate_line("c0ffee Ada Lovelace\n"),
+            BlameLine {
+                commit_id: "c0ffee".to_string(),
+                author: "Ada Lovelace".to_string(),
+            }
+        );
+        assert_eq!(
+            BlameLine::from_annotate_line("c0ffee"),
+            BlameLine {
+                commit_id: "c0ffee".to_string(),
+                author: String::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn branch_info_from_template_output() {
+        let output = "main\tc0ffee1\tRelease 1.0\nconflicted\t\t\nfeat\t1234abc\tAdd \"x\"\tfast\nmain\tdeadbee\tOther head\n";
+        let branches =
+            BranchInfo::from_template_output(output, Some("feat"), |name| format!("only({name})"));
+
+        assert_eq!(
+            branches,
+            [
+                BranchInfo {
+                    name: "feat".to_string(),
+                    short_id: "1234abc".to_string(),
+                    summary: "Add \"x\"\tfast".to_string(),
+                    is_current: true,
+                    revisions: "only(\"feat\")".to_string(),
+                },
+                BranchInfo {
+                    name: "main".to_string(),
+                    short_id: "c0ffee1".to_string(),
+                    summary: "Release 1.0".to_string(),
+                    is_current: false,
+                    revisions: "only(\"main\")".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_stat_from_stat_output() {
+        let output = " src/main.rs | 10 +++++++---\n README.md | 2 +-\n 2 files changed, 8 insertions(+), 4 deletions(-)\n";
+        assert_eq!(
+            DiffStat::from_stat_output(output),
+            DiffStat {
+                files_changed: 2,
+                insertions: 8,
+                deletions: 4,
+            }
+        );
+        assert_eq!(
+            DiffStat::from_stat_output(" a.txt | 1 +\n 1 file changed, 1 insertion(+)\n"),
+            DiffStat {
+                files_changed: 1,
+                insertions: 1,
+                deletions: 0,
+            }
+        );
+        assert_eq!(DiffStat::from_stat_output(""), DiffStat::default());
+    }
 }
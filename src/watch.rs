@@ -0,0 +1,147 @@
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::Duration;
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::error::{Result, TuicrError};
+
+/// How long the file system must stay quiet before a reload is signalled.
+pub const WATCH_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// VCS metadata directories whose changes never affect the reviewed diff.
+const IGNORED_DIRS: &[&str] = &[".git", ".jj", ".hg", ".sl"];
+
+/// Watches the repository for changes and signals once they settle.
+pub struct DiffWatcher {
+    // Dropping the watcher stops events and ends the debounce thread
+    _watcher: RecommendedWatcher,
+    changes: Receiver<()>,
+}
+
+impl DiffWatcher {
+    pub fn new(root: &Path) -> Result<Self> {
+        let (raw_tx, raw_rx) = mpsc::channel();
+        let (changes_tx, changes) = mpsc::channel();
+
+        let watch_root = root.to_path_buf();
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
+            if let Ok(event) = res
+                && is_relevant_event(&watch_root, &event)
+            {
+                let _ = raw_tx.send(());
+            }
+        })
+        .map_err(watch_error)?;
+        watcher
+            .watch(root, RecursiveMode::Recursive)
+            .map_err(watch_error)?;
+
+        thread::spawn(move || debounce(raw_rx, changes_tx, WATCH_DEBOUNCE));
+
+        Ok(Self {
+            _watcher: watcher,
+            changes,
+        })
+    }
+
+    /// Returns true if files changed since the last call.
+    pub fn has_changes(&self) -> bool {
+        self.changes.try_iter().count() > 0
+    }
+}
+
+fn watch_error(e: notify::Error) -> TuicrError {
+    TuicrError::Io(std::io::Error::other(format!("File watcher failed: {e}")))
+}
+
+/// Forward one signal per burst of events, once `delay` passes without a new one.
+fn debounce(raw: Receiver<()>, out: Sender<()>, delay: Duration) {
+    while raw.recv().is_ok() {
+        loop {
+            match raw.recv_timeout(delay) {
+                Ok(()) => continue,
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+        if out.send(()).is_err() {
+            return;
+        }
+    }
+}
+
+fn is_relevant_event(root: &Path, event: &Event) -> bool {
+    // Reading files while reloading produces access events; ignore them to
+    // avoid reload loops.
+    if matches!(event.kind, EventKind::Access(_) | EventKind::Other) {
+        return false;
+    }
+    event.paths.iter().any(|path| !is_vcs_internal(root, path))
+}
+
+fn is_vcs_internal(root: &Path, path: &Path) -> bool {
+    let relative: PathBuf = path.strip_prefix(root).unwrap_or(path).to_path_buf();
+    relative.components().any(|component| {
+        matches!(component, Component::Normal(name)
+            if IGNORED_DIRS.iter().any(|dir| name == *dir))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{AccessKind, CreateKind, ModifyKind};
+
+    fn event(kind: EventKind, path: &str) -> Event {
+        Event::new(kind).add_path(PathBuf::from(path))
+    }
+
+    #[test]
+    fn should_ignore_changes_inside_vcs_directories() {
+        let root = Path::new("/repo");
+        assert!(is_vcs_internal(root, Path::new("/repo/.git/index")));
+        assert!(is_vcs_internal(root, Path::new("/repo/.jj/repo/op_heads")));
+        assert!(is_vcs_internal(root, Path::new("/repo/.hg/dirstate")));
+        assert!(!is_vcs_internal(root, Path::new("/repo/src/main.rs")));
+        assert!(!is_vcs_internal(root, Path::new("/repo/.github/ci.yml")));
+    }
+
+    #[test]
+    fn should_only_react_to_content_changes() {
+        let root = Path::new("/repo");
+        assert!(is_relevant_event(
+            root,
+            &event(EventKind::Modify(ModifyKind::Any), "/repo/src/lib.rs")
+        ));
+        assert!(is_relevant_event(
+            root,
+            &event(EventKind::Create(CreateKind::File), "/repo/new.rs")
+        ));
+        assert!(!is_relevant_event(
+            root,
+            &event(EventKind::Access(AccessKind::Any), "/repo/src/lib.rs")
+        ));
+        assert!(!is_relevant_event(
+            root,
+            &event(EventKind::Modify(ModifyKind::Any), "/repo/.git/index")
+        ));
+    }
+
+    #[test]
+    fn should_collapse_burst_of_events_into_one_signal() {
+        let (raw_tx, raw_rx) = mpsc::channel();
+        let (out_tx, out_rx) = mpsc::channel();
+        let handle = thread::spawn(move || debounce(raw_rx, out_tx, Duration::from_millis(50)));
+
+        for _ in 0..5 {
+            raw_tx.send(()).unwrap();
+        }
+        assert!(out_rx.recv_timeout(Duration::from_secs(2)).is_ok());
+        assert!(out_rx.recv_timeout(Duration::from_millis(200)).is_err());
+
+        drop(raw_tx);
+        handle.join().unwrap();
+    }
+}